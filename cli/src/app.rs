@@ -55,12 +55,26 @@ pub enum Command {
     /// Manage active invocations
     #[clap(subcommand)]
     Invocations(invocations::Invocations),
+    /// Invoke a handler and wait for its output
+    Invoke(invoke::Invoke),
+    /// Send an invocation to a handler without waiting for its output
+    Send(invoke::SendInvocation),
     /// Runs SQL queries against the data fusion service
     Sql(sql::Sql),
     /// Download one of Restate's examples in this directory.
     #[clap(name = "example", alias = "examples")]
     Examples(examples::Examples),
 
+    /// Declaratively apply deployments, service configuration and subscriptions from a manifest
+    Apply(apply::Apply),
+
+    /// Resolve or reject awakeables
+    #[clap(subcommand)]
+    Awakeables(awakeables::Awakeables),
+    /// Manage workflow runs and their promises
+    #[clap(subcommand)]
+    Workflows(workflows::Workflows),
+
     /// Manage service state
     #[clap(name = "state", alias = "kv")]
     #[clap(subcommand)]
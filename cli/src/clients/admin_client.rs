@@ -31,6 +31,8 @@ use super::errors::ApiError;
 pub const MIN_ADMIN_API_VERSION: AdminApiVersion = AdminApiVersion::V1;
 pub const MAX_ADMIN_API_VERSION: AdminApiVersion = AdminApiVersion::V2;
 
+const X_RESTATE_AUTHOR: &str = "x-restate-author";
+
 #[derive(Error, Debug)]
 #[error(transparent)]
 pub enum Error {
@@ -118,7 +120,18 @@ pub struct AdminClient {
 
 impl AdminClient {
     pub async fn new(env: &CliEnv) -> anyhow::Result<Self> {
+        // Recorded by the server in the schema change history
+        let mut default_headers = reqwest::header::HeaderMap::new();
+        if let Some(author) = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok()
+            .and_then(|user| reqwest::header::HeaderValue::from_str(&user).ok())
+        {
+            default_headers.insert(X_RESTATE_AUTHOR, author);
+        }
+
         let raw_client = reqwest::Client::builder()
+            .default_headers(default_headers)
             .user_agent(format!(
                 "{}/{} {}-{}",
                 env!("CARGO_PKG_NAME"),
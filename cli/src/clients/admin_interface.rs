@@ -12,8 +12,14 @@ use super::admin_client::Envelope;
 use super::AdminClient;
 
 use restate_admin_rest_model::deployments::*;
+use restate_admin_rest_model::invocations::{RestartInvocationRequest, RestartInvocationResponse};
+use restate_admin_rest_model::manifest::*;
+use restate_admin_rest_model::schema_history::*;
 use restate_admin_rest_model::services::*;
 use restate_admin_rest_model::version::VersionInformation;
+use restate_admin_rest_model::workflows::{
+    CompletePromiseRequest, DescribeWorkflowRunResponse, ListWorkflowRunsResponse,
+};
 use restate_types::schema::service::ServiceMetadata;
 
 pub trait AdminClientInterface {
@@ -21,11 +27,34 @@ pub trait AdminClientInterface {
     async fn health(&self) -> reqwest::Result<Envelope<()>>;
     async fn get_services(&self) -> reqwest::Result<Envelope<ListServicesResponse>>;
     async fn get_service(&self, name: &str) -> reqwest::Result<Envelope<ServiceMetadata>>;
+    async fn get_service_openapi(&self, name: &str)
+        -> reqwest::Result<Envelope<serde_json::Value>>;
     async fn patch_service(
         &self,
         name: &str,
         modify_service_request: ModifyServiceRequest,
     ) -> reqwest::Result<Envelope<ServiceMetadata>>;
+    async fn rollback_service(
+        &self,
+        name: &str,
+        req: RollbackServiceRequest,
+    ) -> reqwest::Result<Envelope<ServiceMetadata>>;
+    async fn delete_service(
+        &self,
+        name: &str,
+        force: bool,
+        purge: bool,
+    ) -> reqwest::Result<Envelope<DeleteServiceResponse>>;
+    async fn get_schema_changes(&self) -> reqwest::Result<Envelope<ListSchemaChangesResponse>>;
+    async fn apply_manifest(
+        &self,
+        req: &ApplyManifestRequest,
+    ) -> reqwest::Result<Envelope<ApplyManifestResponse>>;
+    async fn diff_schema_versions(
+        &self,
+        from: u32,
+        to: Option<u32>,
+    ) -> reqwest::Result<Envelope<SchemaDiffResponse>>;
     async fn get_deployments(&self) -> reqwest::Result<Envelope<ListDeploymentsResponse>>;
     async fn get_deployment<D: AsRef<str>>(
         &self,
@@ -33,6 +62,12 @@ pub trait AdminClientInterface {
     ) -> reqwest::Result<Envelope<DetailedDeploymentResponse>>;
     async fn remove_deployment(&self, id: &str, force: bool) -> reqwest::Result<Envelope<()>>;
 
+    async fn drain_deployment(
+        &self,
+        id: &str,
+        req: DrainDeploymentRequest,
+    ) -> reqwest::Result<Envelope<DrainDeploymentResponse>>;
+
     async fn discover_deployment(
         &self,
         body: RegisterDeploymentRequest,
@@ -42,12 +77,57 @@ pub trait AdminClientInterface {
 
     async fn cancel_invocation(&self, id: &str, kill: bool) -> reqwest::Result<Envelope<()>>;
 
+    async fn restart_invocation(
+        &self,
+        id: &str,
+        req: RestartInvocationRequest,
+    ) -> reqwest::Result<Envelope<RestartInvocationResponse>>;
+
     async fn patch_state(
         &self,
         service: &str,
         req: ModifyServiceStateRequest,
     ) -> reqwest::Result<Envelope<()>>;
 
+    async fn patch_state_batch(
+        &self,
+        service: &str,
+        req: ModifyServiceStateBatchRequest,
+    ) -> reqwest::Result<Envelope<ModifyServiceStateBatchResponse>>;
+
+    async fn list_workflow_runs(
+        &self,
+        workflow: &str,
+        limit: usize,
+    ) -> reqwest::Result<Envelope<ListWorkflowRunsResponse>>;
+
+    async fn describe_workflow_run(
+        &self,
+        workflow: &str,
+        key: &str,
+    ) -> reqwest::Result<Envelope<DescribeWorkflowRunResponse>>;
+
+    async fn complete_workflow_promise(
+        &self,
+        workflow: &str,
+        key: &str,
+        promise: &str,
+        req: CompletePromiseRequest,
+    ) -> reqwest::Result<Envelope<()>>;
+
+    async fn cancel_workflow(
+        &self,
+        workflow: &str,
+        key: &str,
+        kill: bool,
+    ) -> reqwest::Result<Envelope<()>>;
+
+    async fn complete_awakeable(
+        &self,
+        awakeable_id: &str,
+        req: CompletePromiseRequest,
+    ) -> reqwest::Result<Envelope<()>>;
+
     async fn version(&self) -> reqwest::Result<Envelope<VersionInformation>>;
 }
 
@@ -67,6 +147,14 @@ impl AdminClientInterface for AdminClient {
         self.run(reqwest::Method::GET, url).await
     }
 
+    async fn get_service_openapi(
+        &self,
+        name: &str,
+    ) -> reqwest::Result<Envelope<serde_json::Value>> {
+        let url = self.versioned_url(["services", name, "openapi"]);
+        self.run(reqwest::Method::GET, url).await
+    }
+
     async fn patch_service(
         &self,
         name: &str,
@@ -77,6 +165,53 @@ impl AdminClientInterface for AdminClient {
             .await
     }
 
+    async fn rollback_service(
+        &self,
+        name: &str,
+        req: RollbackServiceRequest,
+    ) -> reqwest::Result<Envelope<ServiceMetadata>> {
+        let url = self.versioned_url(["services", name, "rollback"]);
+        self.run_with_body(reqwest::Method::POST, url, req).await
+    }
+
+    async fn delete_service(
+        &self,
+        name: &str,
+        force: bool,
+        purge: bool,
+    ) -> reqwest::Result<Envelope<DeleteServiceResponse>> {
+        let mut url = self.versioned_url(["services", name]);
+        url.set_query(Some(&format!("force={force}&purge={purge}")));
+
+        self.run(reqwest::Method::DELETE, url).await
+    }
+
+    async fn apply_manifest(
+        &self,
+        req: &ApplyManifestRequest,
+    ) -> reqwest::Result<Envelope<ApplyManifestResponse>> {
+        let url = self.versioned_url(["schema", "apply"]);
+        self.run_with_body(reqwest::Method::POST, url, req).await
+    }
+
+    async fn get_schema_changes(&self) -> reqwest::Result<Envelope<ListSchemaChangesResponse>> {
+        let url = self.versioned_url(["schema", "history"]);
+        self.run(reqwest::Method::GET, url).await
+    }
+
+    async fn diff_schema_versions(
+        &self,
+        from: u32,
+        to: Option<u32>,
+    ) -> reqwest::Result<Envelope<SchemaDiffResponse>> {
+        let mut url = self.versioned_url(["schema", "history", "diff"]);
+        url.set_query(Some(&match to {
+            Some(to) => format!("from={from}&to={to}"),
+            None => format!("from={from}"),
+        }));
+        self.run(reqwest::Method::GET, url).await
+    }
+
     async fn get_deployments(&self) -> reqwest::Result<Envelope<ListDeploymentsResponse>> {
         let url = self.versioned_url(["deployments"]);
         self.run(reqwest::Method::GET, url).await
@@ -97,6 +232,15 @@ impl AdminClientInterface for AdminClient {
         self.run(reqwest::Method::DELETE, url).await
     }
 
+    async fn drain_deployment(
+        &self,
+        id: &str,
+        req: DrainDeploymentRequest,
+    ) -> reqwest::Result<Envelope<DrainDeploymentResponse>> {
+        let url = self.versioned_url(["deployments", id, "drain"]);
+        self.run_with_body(reqwest::Method::POST, url, req).await
+    }
+
     async fn discover_deployment(
         &self,
         body: RegisterDeploymentRequest,
@@ -122,6 +266,15 @@ impl AdminClientInterface for AdminClient {
         self.run(reqwest::Method::DELETE, url).await
     }
 
+    async fn restart_invocation(
+        &self,
+        id: &str,
+        req: RestartInvocationRequest,
+    ) -> reqwest::Result<Envelope<RestartInvocationResponse>> {
+        let url = self.versioned_url(["invocations", id, "restart"]);
+        self.run_with_body(reqwest::Method::POST, url, req).await
+    }
+
     async fn patch_state(
         &self,
         service: &str,
@@ -131,6 +284,69 @@ impl AdminClientInterface for AdminClient {
         self.run_with_body(reqwest::Method::POST, url, req).await
     }
 
+    async fn patch_state_batch(
+        &self,
+        service: &str,
+        req: ModifyServiceStateBatchRequest,
+    ) -> reqwest::Result<Envelope<ModifyServiceStateBatchResponse>> {
+        let url = self.versioned_url(["services", service, "state", "batch"]);
+        self.run_with_body(reqwest::Method::POST, url, req).await
+    }
+
+    async fn list_workflow_runs(
+        &self,
+        workflow: &str,
+        limit: usize,
+    ) -> reqwest::Result<Envelope<ListWorkflowRunsResponse>> {
+        let mut url = self.versioned_url(["workflows", workflow]);
+        url.set_query(Some(&format!("limit={limit}")));
+        self.run(reqwest::Method::GET, url).await
+    }
+
+    async fn describe_workflow_run(
+        &self,
+        workflow: &str,
+        key: &str,
+    ) -> reqwest::Result<Envelope<DescribeWorkflowRunResponse>> {
+        let url = self.versioned_url(["workflows", workflow, key]);
+        self.run(reqwest::Method::GET, url).await
+    }
+
+    async fn complete_workflow_promise(
+        &self,
+        workflow: &str,
+        key: &str,
+        promise: &str,
+        req: CompletePromiseRequest,
+    ) -> reqwest::Result<Envelope<()>> {
+        let url = self.versioned_url(["workflows", workflow, key, "promises", promise]);
+        self.run_with_body(reqwest::Method::POST, url, req).await
+    }
+
+    async fn cancel_workflow(
+        &self,
+        workflow: &str,
+        key: &str,
+        kill: bool,
+    ) -> reqwest::Result<Envelope<()>> {
+        let mut url = self.versioned_url(["workflows", workflow, key]);
+        url.set_query(Some(&format!(
+            "mode={}",
+            if kill { "kill" } else { "cancel" }
+        )));
+
+        self.run(reqwest::Method::DELETE, url).await
+    }
+
+    async fn complete_awakeable(
+        &self,
+        awakeable_id: &str,
+        req: CompletePromiseRequest,
+    ) -> reqwest::Result<Envelope<()>> {
+        let url = self.versioned_url(["awakeables", awakeable_id]);
+        self.run_with_body(reqwest::Method::POST, url, req).await
+    }
+
     async fn version(&self) -> reqwest::Result<Envelope<VersionInformation>> {
         let url = self.versioned_url(["version"]);
         self.run(reqwest::Method::GET, url).await
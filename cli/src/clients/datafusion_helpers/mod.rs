@@ -9,7 +9,7 @@ use restate_types::{
     identifiers::{AwakeableIdentifier, DeploymentId, ServiceId},
     invocation::ServiceType,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DeserializeAs};
 
 use super::DataFusionHttpClient;
@@ -204,6 +204,43 @@ impl JournalEntry {
     }
 }
 
+/// A row of the invocation lifecycle event log.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvocationEvent {
+    pub recorded_at: DateTime<Local>,
+    pub event_type: String,
+    pub execution_time: Option<DateTime<Local>>,
+    pub attempt: Option<u32>,
+    pub deployment_id: Option<String>,
+    pub error_code: Option<u32>,
+    pub error_message: Option<String>,
+    pub related_entry_index: Option<u32>,
+    pub related_entry_name: Option<String>,
+}
+
+/// The journal of an invocation, with its entries framed as service protocol messages.
+/// This is everything needed to replay the invocation against a deployment, and it can be
+/// stored to a file to replay the invocation offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayJournal {
+    pub invocation_id: String,
+    pub service: String,
+    pub handler: String,
+    pub key: Option<String>,
+    pub journal_version: u32,
+    /// The service protocol version the invocation is pinned to, if any.
+    pub service_protocol_version: Option<i32>,
+    pub entries: Vec<ReplayJournalEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayJournalEntry {
+    pub index: u32,
+    pub entry_type: String,
+    /// Base64 encoded protocol message, absent for entries that are not sent to the deployment.
+    pub protocol_message: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub enum JournalEntryType {
     Sleep {
@@ -425,3 +462,82 @@ v1_converter_fn!(get_service_invocations, (service: &str, limit_active: usize) -
 v1_converter_fn!(get_state_keys, (service: &str, key: Option<&str>) -> Result<HashMap<ServiceId, HashMap<String, Bytes>>> );
 v1_converter_fn!(get_locked_keys_status, (services_filter: impl IntoIterator<Item = impl AsRef<str>>) -> Result<ServiceHandlerLockedKeysMap> );
 v1_converter_fn!(get_service_status, (services_filter: impl IntoIterator<Item = impl AsRef<str>>) -> Result<ServiceStatusMap> );
+
+pub async fn get_invocation_replay_journal(
+    client: &DataFusionHttpClient,
+    invocation_id: &str,
+) -> Result<Option<ReplayJournal>> {
+    match client.admin_api_version() {
+        AdminApiVersion::V1 => {
+            anyhow::bail!("Replaying invocations is not supported by this Restate server version")
+        }
+        _ => v2::get_invocation_replay_journal(client, invocation_id).await,
+    }
+}
+
+/// The event log is not available on servers exposing the V1 admin API.
+pub async fn get_invocation_events(
+    client: &DataFusionHttpClient,
+    invocation_id: &str,
+) -> Result<Vec<InvocationEvent>> {
+    match client.admin_api_version() {
+        AdminApiVersion::V1 => Ok(vec![]),
+        _ => v2::get_invocation_events(client, invocation_id).await,
+    }
+}
+
+/// Quotes the value as a SQL string literal, escaping the quotes it contains.
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+pub async fn count_service_active_inv(client: &DataFusionHttpClient, service: &str) -> Result<i64> {
+    Ok(client
+        .run_count_agg_query(format!(
+            "SELECT COUNT(id) AS inv_count \
+            FROM sys_invocation_status \
+            WHERE target_service_name = {} AND status NOT IN ('completed', 'free')",
+            sql_string(service)
+        ))
+        .await?)
+}
+
+/// The data stored for a service across all the partitions that the purge removes. The data
+/// in use by in-flight invocations is not counted, as the purge retains it: the inboxed
+/// invocations, and the state and promises of the locked keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceStoredData {
+    pub state_entries: i64,
+    pub promises: i64,
+    pub completed_invocations: i64,
+}
+
+impl ServiceStoredData {
+    pub fn total(&self) -> i64 {
+        self.state_entries + self.promises + self.completed_invocations
+    }
+}
+
+pub async fn count_service_stored_data(
+    client: &DataFusionHttpClient,
+    service: &str,
+) -> Result<ServiceStoredData> {
+    let service = sql_string(service);
+    let unlocked_keys = format!(
+        "service_name = {service} AND service_key NOT IN \
+        (SELECT service_key FROM sys_keyed_service_status WHERE service_name = {service})"
+    );
+    let count = |table: &str, filter: &str| {
+        client.run_count_agg_query(format!("SELECT COUNT(*) FROM {table} WHERE {filter}"))
+    };
+
+    Ok(ServiceStoredData {
+        state_entries: count("state", &unlocked_keys).await?,
+        promises: count("sys_promise", &unlocked_keys).await?,
+        completed_invocations: count(
+            "sys_invocation_status",
+            &format!("target_service_name = {service} AND status = 'completed'"),
+        )
+        .await?,
+    })
+}
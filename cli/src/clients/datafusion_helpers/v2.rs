@@ -12,7 +12,7 @@
 
 use std::collections::HashMap;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use bytes::Bytes;
 use chrono::{DateTime, Local};
 
@@ -24,8 +24,8 @@ use serde_with::serde_as;
 use crate::clients::DataFusionHttpClient;
 
 use super::{
-    HandlerStateStats, Invocation, InvocationCompletion, InvocationState, JournalEntry,
-    JournalEntryType, LockedKeyInfo, OutgoingInvoke, ServiceHandlerLockedKeysMap,
+    HandlerStateStats, Invocation, InvocationCompletion, InvocationEvent, InvocationState,
+    JournalEntry, JournalEntryType, LockedKeyInfo, OutgoingInvoke, ServiceHandlerLockedKeysMap,
     ServiceHandlerUsage, ServiceStatusMap, SimpleInvocation,
 };
 
@@ -492,6 +492,114 @@ pub async fn get_invocation_journal(
     Ok(journal)
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct ReplayInvocationQueryResult {
+    target_service_name: String,
+    target_handler_name: String,
+    target_service_key: Option<String>,
+    pinned_service_protocol_version: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct ReplayJournalQueryResult {
+    index: u32,
+    version: Option<u32>,
+    entry_type: String,
+    protocol_message: Option<String>,
+}
+
+pub async fn get_invocation_replay_journal(
+    client: &DataFusionHttpClient,
+    invocation_id: &str,
+) -> Result<Option<ReplayJournal>> {
+    if !client
+        .check_columns_exists("sys_journal", &["protocol_message"])
+        .await?
+    {
+        bail!("Replaying invocations is not supported by this Restate server version");
+    }
+
+    let Some(invocation) = client
+        .run_json_query::<ReplayInvocationQueryResult>(format!(
+            "SELECT
+                inv.target_service_name,
+                inv.target_handler_name,
+                inv.target_service_key,
+                inv.pinned_service_protocol_version
+            FROM sys_invocation inv
+            WHERE inv.id = '{invocation_id}'"
+        ))
+        .await?
+        .pop()
+    else {
+        return Ok(None);
+    };
+
+    // The whole journal is needed to replay the invocation, hence no limit here.
+    let entries = client
+        .run_json_query::<ReplayJournalQueryResult>(format!(
+            "SELECT
+                sj.index,
+                sj.version,
+                sj.entry_type,
+                encode(sj.protocol_message, 'base64') AS protocol_message
+            FROM sys_journal sj
+            WHERE
+                sj.id = '{invocation_id}'
+            ORDER BY index ASC"
+        ))
+        .await?;
+
+    Ok(Some(ReplayJournal {
+        invocation_id: invocation_id.to_owned(),
+        service: invocation.target_service_name,
+        handler: invocation.target_handler_name,
+        key: invocation.target_service_key,
+        journal_version: entries.first().and_then(|e| e.version).unwrap_or(1),
+        service_protocol_version: invocation.pinned_service_protocol_version,
+        entries: entries
+            .into_iter()
+            .map(|e| ReplayJournalEntry {
+                index: e.index,
+                entry_type: e.entry_type,
+                protocol_message: e.protocol_message,
+            })
+            .collect(),
+    }))
+}
+
+pub async fn get_invocation_events(
+    client: &DataFusionHttpClient,
+    invocation_id: &str,
+) -> Result<Vec<InvocationEvent>> {
+    // Older servers don't record the invocation event log
+    let has_event_log = client
+        .check_columns_exists("sys_invocation_event", &["event_type"])
+        .await?;
+    if !has_event_log {
+        return Ok(vec![]);
+    }
+
+    let query = format!(
+        "SELECT
+            ie.recorded_at,
+            ie.event_type,
+            ie.execution_time,
+            ie.attempt,
+            ie.deployment_id,
+            ie.error_code,
+            ie.error_message,
+            ie.related_entry_index,
+            ie.related_entry_name
+        FROM sys_invocation_event ie
+        WHERE
+            ie.id = '{invocation_id}'
+        ORDER BY ie.index ASC",
+    );
+
+    Ok(client.run_json_query::<InvocationEvent>(query).await?)
+}
+
 #[serde_as]
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct StateKeysQueryResult {
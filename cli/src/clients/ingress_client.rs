@@ -0,0 +1,266 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A wrapper client for the ingress HTTP service.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::HeaderValue;
+use restate_cli_util::CliContext;
+use tracing::{debug, info};
+use url::Url;
+
+use crate::build_info;
+use crate::cli_env::CliEnv;
+
+use super::errors::{ApiError, ApiErrorBody};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const INVOCATION_ID_HEADER: &str = "x-restate-id";
+
+#[derive(thiserror::Error, Debug)]
+#[error(transparent)]
+pub enum Error {
+    // Error is boxed because ApiError can get quite large if the message body is large.
+    Api(#[from] Box<ApiError>),
+    Network(#[from] reqwest::Error),
+}
+
+/// Handler targeted by an ingress request.
+#[derive(Debug, Clone)]
+pub struct IngressTarget<'a> {
+    pub service: &'a str,
+    pub key: Option<&'a str>,
+    pub handler: &'a str,
+}
+
+/// Successful response of the ingress.
+pub struct IngressResponse {
+    pub invocation_id: Option<String>,
+    pub content_type: Option<HeaderValue>,
+    pub body: Bytes,
+}
+
+/// A handy client for the ingress HTTP service.
+#[derive(Clone)]
+pub struct IngressClient {
+    inner: reqwest::Client,
+    base_url: Url,
+    bearer_token: Option<String>,
+    request_timeout: Duration,
+}
+
+impl IngressClient {
+    pub fn new(env: &CliEnv) -> anyhow::Result<Self> {
+        let inner = reqwest::Client::builder()
+            .user_agent(format!(
+                "{}/{} {}-{}",
+                env!("CARGO_PKG_NAME"),
+                build_info::RESTATE_CLI_VERSION,
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+            ))
+            .connect_timeout(CliContext::get().connect_timeout())
+            .build()?;
+
+        Ok(Self {
+            inner,
+            base_url: env.ingress_base_url()?.clone(),
+            bearer_token: env.bearer_token()?.map(str::to_string),
+            request_timeout: CliContext::get().request_timeout(),
+        })
+    }
+
+    fn url(&self, path: impl IntoIterator<Item = impl AsRef<str>>) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Bad url!")
+            .pop_if_empty()
+            .extend(path);
+        url
+    }
+
+    fn target_url(&self, target: &IngressTarget<'_>, send: bool) -> Url {
+        let mut segments = vec![target.service];
+        segments.extend(target.key);
+        segments.push(target.handler);
+        if send {
+            segments.push("send");
+        }
+        self.url(segments)
+    }
+
+    /// Prepare a request builder for the given method and path. Requests without a timeout
+    /// wait until the invocation completes.
+    fn prepare(
+        &self,
+        method: reqwest::Method,
+        url: Url,
+        with_timeout: bool,
+    ) -> reqwest::RequestBuilder {
+        let mut request_builder = self.inner.request(method, url);
+        if with_timeout {
+            request_builder = request_builder.timeout(self.request_timeout);
+        }
+
+        match self.bearer_token.as_deref() {
+            Some(token) => request_builder.bearer_auth(token),
+            None => request_builder,
+        }
+    }
+
+    /// Invoke the target and wait for its output.
+    pub async fn call(
+        &self,
+        target: &IngressTarget<'_>,
+        payload: Option<Bytes>,
+        idempotency_key: Option<&str>,
+    ) -> Result<IngressResponse, Error> {
+        let url = self.target_url(target, false);
+        let request = self.prepare(reqwest::Method::POST, url, false);
+        self.execute(with_payload(request, payload, idempotency_key))
+            .await
+    }
+
+    /// Invoke the target without waiting for its output. The response body contains the id of
+    /// the invocation.
+    pub async fn send(
+        &self,
+        target: &IngressTarget<'_>,
+        payload: Option<Bytes>,
+        idempotency_key: Option<&str>,
+        delay: Option<Duration>,
+    ) -> Result<IngressResponse, Error> {
+        let mut url = self.target_url(target, true);
+        if let Some(delay) = delay {
+            url.query_pairs_mut()
+                .append_pair("delay", &format!("{}ms", delay.as_millis()));
+        }
+        let request = self.prepare(reqwest::Method::POST, url, true);
+        self.execute(with_payload(request, payload, idempotency_key))
+            .await
+    }
+
+    /// Attach to the given invocation and wait for its output.
+    pub async fn attach(&self, invocation_id: &str) -> Result<IngressResponse, Error> {
+        let url = self.url(["restate", "invocation", invocation_id, "attach"]);
+        self.execute(self.prepare(reqwest::Method::GET, url, false))
+            .await
+    }
+
+    async fn execute(&self, request: reqwest::RequestBuilder) -> Result<IngressResponse, Error> {
+        let response = request.send().await?;
+        let http_status_code = response.status();
+        let url = response.url().clone();
+        debug!("Response from {} ({})", url, http_status_code);
+
+        let invocation_id = response
+            .headers()
+            .get(INVOCATION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let content_type = response.headers().get(http::header::CONTENT_TYPE).cloned();
+        let body = response.bytes().await?;
+
+        if !http_status_code.is_success() {
+            info!("Response from {} ({})", url, http_status_code);
+            info!("  {}", String::from_utf8_lossy(&body));
+            let body = serde_json::from_slice::<ApiErrorBody>(&body).unwrap_or_else(|_| {
+                ApiErrorBody::from(String::from_utf8_lossy(&body).into_owned())
+            });
+            return Err(Error::Api(Box::new(ApiError {
+                http_status_code,
+                url,
+                body,
+            })));
+        }
+
+        Ok(IngressResponse {
+            invocation_id,
+            content_type,
+            body,
+        })
+    }
+}
+
+fn with_payload(
+    mut request: reqwest::RequestBuilder,
+    payload: Option<Bytes>,
+    idempotency_key: Option<&str>,
+) -> reqwest::RequestBuilder {
+    if let Some(payload) = payload {
+        request = request
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(payload);
+    }
+    if let Some(idempotency_key) = idempotency_key {
+        request = request.header(IDEMPOTENCY_KEY_HEADER, idempotency_key);
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(base_url: &str) -> IngressClient {
+        IngressClient {
+            inner: reqwest::Client::new(),
+            base_url: Url::parse(base_url).unwrap(),
+            bearer_token: None,
+            request_timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn builds_target_urls() {
+        let client = client("http://localhost:8080/");
+        let service = IngressTarget {
+            service: "Greeter",
+            key: None,
+            handler: "greet",
+        };
+        let object = IngressTarget {
+            service: "Counter",
+            key: Some("my key/1"),
+            handler: "add",
+        };
+
+        assert_eq!(
+            client.target_url(&service, false).as_str(),
+            "http://localhost:8080/Greeter/greet"
+        );
+        assert_eq!(
+            client.target_url(&service, true).as_str(),
+            "http://localhost:8080/Greeter/greet/send"
+        );
+        // keys are percent-encoded into a single path segment
+        assert_eq!(
+            client.target_url(&object, false).as_str(),
+            "http://localhost:8080/Counter/my%20key%2F1/add"
+        );
+    }
+
+    #[test]
+    fn builds_target_urls_below_base_path() {
+        let client = client("http://localhost:8080/restate/");
+        let target = IngressTarget {
+            service: "Greeter",
+            key: None,
+            handler: "greet",
+        };
+
+        assert_eq!(
+            client.target_url(&target, false).as_str(),
+            "http://localhost:8080/restate/Greeter/greet"
+        );
+    }
+}
@@ -15,9 +15,11 @@ pub mod cloud;
 pub mod datafusion_helpers;
 mod datafusion_http_client;
 mod errors;
+mod ingress_client;
 
 pub use self::admin_client::AdminClient;
 pub use self::admin_client::Error as MetasClientError;
 pub use self::admin_client::{MAX_ADMIN_API_VERSION, MIN_ADMIN_API_VERSION};
 pub use self::admin_interface::AdminClientInterface;
 pub use self::datafusion_http_client::DataFusionHttpClient;
+pub use self::ingress_client::{IngressClient, IngressResponse, IngressTarget};
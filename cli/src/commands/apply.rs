@@ -0,0 +1,162 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use cling::prelude::*;
+use comfy_table::Table;
+
+use restate_admin_rest_model::manifest::{
+    ApplyManifestRequest, ApplyManifestResponse, ManifestChange,
+};
+use restate_cli_util::ui::console::{confirm_or_exit, Styled, StyledTable};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_indent_table, c_println, c_success, c_title};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::ui::schema_diff::render_service_diffs;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_apply")]
+pub struct Apply {
+    /// Manifest file describing the deployments, service configuration overrides and
+    /// subscriptions, in YAML or JSON. Use `-` to read it from stdin.
+    ///
+    /// Example:
+    ///
+    /// deployments:
+    ///   - uri: http://greeter:9080
+    /// services:
+    ///   Greeter:
+    ///     public: false
+    ///     idempotency_retention: 1d
+    /// subscriptions:
+    ///   - source: kafka://my-cluster/orders
+    ///     sink: service://Greeter/greet
+    #[clap(short = 'f', long = "file", verbatim_doc_comment)]
+    file: PathBuf,
+
+    /// Override the deployments already registered with the same endpoint but different
+    /// services or headers. Beware that this can lead in-flight invocations to an
+    /// unrecoverable error state. Without this flag, applying such deployments fails.
+    #[clap(long)]
+    force: bool,
+
+    /// Only show the changes, without applying them.
+    #[clap(long)]
+    dry_run: bool,
+}
+
+pub async fn run_apply(State(env): State<CliEnv>, opts: &Apply) -> Result<()> {
+    let mut manifest = read_manifest(&opts.file)?;
+    manifest.force |= opts.force;
+    let client = AdminClient::new(&env).await?;
+
+    // Always start with a dry run, to show the plan before applying it.
+    manifest.dry_run = true;
+    let plan = client.apply_manifest(&manifest).await?.into_body().await?;
+
+    let has_changes = render_plan(&plan);
+    if !has_changes {
+        c_success!("Nothing to apply, the manifest matches the registered schema.");
+        return Ok(());
+    }
+    if opts.dry_run {
+        return Ok(());
+    }
+
+    c_println!();
+    confirm_or_exit("Are you sure you want to apply these changes?")?;
+
+    manifest.dry_run = false;
+    let result = client.apply_manifest(&manifest).await?.into_body().await?;
+
+    c_println!();
+    if result.applied {
+        c_success!(
+            "Manifest applied, the schema is now at v{}",
+            result.schema_version
+        );
+    } else {
+        c_success!("Nothing to apply, the manifest matches the registered schema.");
+    }
+    Ok(())
+}
+
+fn read_manifest(file: &Path) -> Result<ApplyManifestRequest> {
+    let content = if file.as_os_str() == "-" {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .context("Cannot read the manifest from stdin")?;
+        content
+    } else {
+        std::fs::read_to_string(file)
+            .with_context(|| format!("Cannot read the manifest file {}", file.display()))?
+    };
+
+    // JSON is valid YAML, so this covers both formats
+    serde_yaml::from_str(&content)
+        .with_context(|| format!("Invalid manifest file {}", file.display()))
+}
+
+/// Prints the planned changes, returns whether there's anything to apply.
+fn render_plan(plan: &ApplyManifestResponse) -> bool {
+    let mut has_changes = !plan.services.is_empty();
+
+    if !plan.deployments.is_empty() {
+        c_title!("📦", "Deployments");
+        let mut table = Table::new_styled();
+        table.set_styled_header(vec!["", "ID", "ADDRESS"]);
+        for deployment in &plan.deployments {
+            has_changes |= deployment.change != ManifestChange::Unchanged;
+            table.add_row(vec![
+                render_change(deployment.change).to_string(),
+                deployment.id.to_string(),
+                deployment.address.clone(),
+            ]);
+        }
+        c_indent_table!(0, table);
+    }
+
+    if !plan.services.is_empty() {
+        c_title!("📜", "Services");
+        render_service_diffs(&plan.services);
+    }
+
+    if !plan.subscriptions.is_empty() {
+        c_title!("📨", "Subscriptions");
+        let mut table = Table::new_styled();
+        table.set_styled_header(vec!["", "ID", "SOURCE", "SINK"]);
+        for subscription in &plan.subscriptions {
+            has_changes |= subscription.change != ManifestChange::Unchanged;
+            table.add_row(vec![
+                render_change(subscription.change).to_string(),
+                subscription.id.to_string(),
+                subscription.source.clone(),
+                subscription.sink.clone(),
+            ]);
+        }
+        c_indent_table!(0, table);
+    }
+
+    has_changes
+}
+
+fn render_change(change: ManifestChange) -> Styled<&'static str> {
+    match change {
+        ManifestChange::Added => Styled(Style::Success, "+ added"),
+        ManifestChange::Updated => Styled(Style::Warn, "~ updated"),
+        ManifestChange::Unchanged => Styled(Style::Normal, "unchanged"),
+    }
+}
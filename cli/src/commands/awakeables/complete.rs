@@ -0,0 +1,90 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use serde_json::Value;
+
+use restate_admin_rest_model::workflows::{CompletePromiseRequest, PromiseFailure};
+use restate_cli_util::ui::console::{confirm_or_exit, Styled};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_println, c_success};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::commands::invoke::read_payload;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_resolve")]
+pub struct Resolve {
+    /// The ID of the awakeable
+    awakeable_id: String,
+    /// JSON value to resolve the awakeable with. Use `@path` to read it from a file, or `-` to read it from stdin.
+    /// Defaults to `null`.
+    value: Option<String>,
+}
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_reject")]
+pub struct Reject {
+    /// The ID of the awakeable
+    awakeable_id: String,
+    /// Failure message
+    message: String,
+    /// Error code of the failure
+    #[clap(long)]
+    code: Option<u16>,
+}
+
+pub async fn run_resolve(State(env): State<CliEnv>, opts: &Resolve) -> Result<()> {
+    let value = read_payload(opts.value.as_deref())?.unwrap_or(Value::Null);
+    complete(
+        &env,
+        &opts.awakeable_id,
+        CompletePromiseRequest::Value(value),
+        Styled(Style::Success, "resolve"),
+    )
+    .await
+}
+
+pub async fn run_reject(State(env): State<CliEnv>, opts: &Reject) -> Result<()> {
+    complete(
+        &env,
+        &opts.awakeable_id,
+        CompletePromiseRequest::Failure(PromiseFailure {
+            code: opts.code,
+            message: opts.message.clone(),
+        }),
+        Styled(Style::Danger, "reject"),
+    )
+    .await
+}
+
+async fn complete(
+    env: &CliEnv,
+    awakeable_id: &str,
+    request: CompletePromiseRequest,
+    action: Styled<&str>,
+) -> Result<()> {
+    let client = AdminClient::new(env).await?;
+
+    let prompt = format!("Are you sure you want to {action} the awakeable {awakeable_id}?");
+    confirm_or_exit(&prompt)?;
+
+    let _ = client
+        .complete_awakeable(awakeable_id, request)
+        .await?
+        .success_or_error()?;
+
+    c_println!();
+    c_success!("Request was sent successfully");
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+use comfy_table::{Attribute, Cell, Table};
+use serde::Deserialize;
+
+use restate_admin_rest_model::version::AdminApiVersion;
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::{c_indent_table, c_println};
+use restate_types::identifiers::InvocationId;
+
+use crate::cli_env::CliEnv;
+use crate::clients::DataFusionHttpClient;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[clap(visible_alias = "ls")]
+#[cling(run = "run_list")]
+pub struct List {
+    /// Only list the awakeables the given invocation is waiting on
+    #[clap(long)]
+    invocation: Option<String>,
+    /// Limit the number of results
+    #[clap(long, default_value = "100")]
+    limit: usize,
+}
+
+#[derive(Deserialize)]
+struct PendingAwakeable {
+    id: String,
+    invocation_id: String,
+    journal_index: Option<u32>,
+    name: Option<String>,
+    signal_index: Option<u32>,
+    target: Option<String>,
+}
+
+pub async fn run_list(State(env): State<CliEnv>, opts: &List) -> Result<()> {
+    let sql_client = DataFusionHttpClient::new(&env).await?;
+    if sql_client.admin_api_version() == AdminApiVersion::V1 {
+        bail!("Listing awakeables requires a newer version of the Restate server");
+    }
+
+    let filter = match &opts.invocation {
+        Some(invocation_id) => {
+            let invocation_id = invocation_id.parse::<InvocationId>()?;
+            format!("WHERE aw.invocation_id = '{invocation_id}'")
+        }
+        None => String::new(),
+    };
+    let query = format!(
+        "SELECT aw.id, aw.invocation_id, aw.journal_index, aw.name, aw.signal_index, inv.target
+        FROM sys_awakeable aw
+        LEFT JOIN sys_invocation_status inv ON aw.invocation_id = inv.id
+        {filter}
+        ORDER BY aw.invocation_id, aw.journal_index, aw.signal_index
+        LIMIT {}",
+        opts.limit
+    );
+    let awakeables = sql_client.run_json_query::<PendingAwakeable>(query).await?;
+
+    if awakeables.is_empty() {
+        c_println!("No pending awakeables");
+        return Ok(());
+    }
+
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec!["ID", "INVOCATION", "TARGET", "ENTRY", "NAME"]);
+    for awakeable in &awakeables {
+        table.add_row(vec![
+            Cell::new(&awakeable.id).add_attribute(Attribute::Bold),
+            Cell::new(&awakeable.invocation_id),
+            Cell::new(awakeable.target.as_deref().unwrap_or_default()),
+            Cell::new(awakeable_entry(awakeable)),
+            Cell::new(awakeable.name.as_deref().unwrap_or_default()),
+        ]);
+    }
+    c_indent_table!(0, table);
+    c_println!();
+    c_println!("Showing {} pending awakeables", awakeables.len());
+
+    Ok(())
+}
+
+/// Journal v1 awakeables are journal entries, while journal v2 awakeables are signals.
+fn awakeable_entry(awakeable: &PendingAwakeable) -> String {
+    match (awakeable.journal_index, awakeable.signal_index) {
+        (Some(journal_index), _) => journal_index.to_string(),
+        (None, Some(signal_index)) => format!("signal {signal_index}"),
+        (None, None) => String::new(),
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod complete;
+mod list;
+
+use cling::prelude::*;
+
+#[derive(Run, Subcommand, Clone)]
+pub enum Awakeables {
+    /// List the pending awakeables
+    List(list::List),
+    /// Resolve an awakeable with a value
+    Resolve(complete::Resolve),
+    /// Reject an awakeable with a failure
+    Reject(complete::Reject),
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::{Context, Result};
+use cling::prelude::*;
+
+use restate_admin_rest_model::deployments::DrainDeploymentRequest;
+use restate_cli_util::ui::console::{confirm_or_exit, Styled};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_indentln, c_println, c_success};
+use restate_types::identifiers::{DeploymentId, InvocationId};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_drain")]
+pub struct Drain {
+    /// Deployment ID to re-pin the in-flight invocations to. If not provided, the deployment
+    /// serving the latest revision of the services of the drained deployment is used.
+    #[clap(long)]
+    to: Option<String>,
+    /// Migrate only the given invocation. Can be repeated.
+    #[clap(long = "invocation", value_name = "INVOCATION_ID")]
+    invocations: Vec<String>,
+    /// Only list the invocations that would be migrated.
+    #[clap(long)]
+    dry_run: bool,
+    /// Deployment ID
+    deployment_id: String,
+}
+
+pub async fn run_drain(State(env): State<CliEnv>, opts: &Drain) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+
+    let to = opts
+        .to
+        .as_deref()
+        .map(str::parse::<DeploymentId>)
+        .transpose()
+        .context("Invalid target deployment ID")?;
+    let invocations = if opts.invocations.is_empty() {
+        None
+    } else {
+        Some(
+            opts.invocations
+                .iter()
+                .map(|id| id.parse::<InvocationId>())
+                .collect::<Result<Vec<_>, _>>()
+                .context("Invalid invocation ID")?,
+        )
+    };
+
+    // Always start with a dry run, to show the affected invocations before migrating them.
+    let plan = client
+        .drain_deployment(
+            &opts.deployment_id,
+            DrainDeploymentRequest {
+                to,
+                invocations: invocations.clone(),
+                dry_run: true,
+            },
+        )
+        .await?
+        .into_body()
+        .await?;
+
+    if plan.invocations.is_empty() {
+        c_success!(
+            "No in-flight invocation is pinned to the deployment {}",
+            opts.deployment_id
+        );
+        return Ok(());
+    }
+
+    c_println!(
+        "The following {} in-flight invocation(s) will be re-pinned to the deployment {} and restarted from their journal:",
+        plan.invocations.len(),
+        Styled(Style::Info, plan.to),
+    );
+    for invocation_id in &plan.invocations {
+        c_indentln!(1, "- {}", invocation_id);
+    }
+    c_println!();
+
+    if opts.dry_run {
+        return Ok(());
+    }
+
+    confirm_or_exit("Are you sure you want to migrate these invocations?")?;
+
+    let result = client
+        .drain_deployment(
+            &opts.deployment_id,
+            DrainDeploymentRequest {
+                to: Some(plan.to),
+                invocations: Some(plan.invocations),
+                dry_run: false,
+            },
+        )
+        .await?
+        .into_body()
+        .await?;
+
+    c_println!();
+    c_success!(
+        "{} invocation(s) migrated to the deployment {}",
+        result.invocations.len(),
+        result.to
+    );
+    Ok(())
+}
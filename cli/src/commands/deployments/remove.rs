@@ -151,12 +151,13 @@ pub async fn run_remove(State(env): State<CliEnv>, opts: &Remove) -> Result<()>
             c_error!(
                 indoc! {
                 "Deployment is still {}. There are {} invocations that will break if you proceed
-                    with this operation. Please make sure in-flight invocations are completed (deployment is Drained)
-                    or killed/cancelled before continuing.
+                    with this operation. Please make sure in-flight invocations are completed (deployment is Drained),
+                    migrated to another deployment using {}, or killed/cancelled before continuing.
                 "
                 },
                 Styled(Style::Warn, "Draining"),
-                Styled(Style::Warn, total_active_inv)
+                Styled(Style::Warn, total_active_inv),
+                Styled(Style::Notice, "restate deployments drain"),
             );
             false
         }
@@ -178,11 +179,7 @@ pub async fn run_remove(State(env): State<CliEnv>, opts: &Remove) -> Result<()>
     confirm_or_exit("Are you sure you want to remove this deployment?")?;
 
     let result = client
-        .remove_deployment(
-            &opts.deployment_id,
-            //TODO: Use opts.force when the server implements the false + validation case!
-            true,
-        )
+        .remove_deployment(&opts.deployment_id, opts.force)
         .await?;
     let _ = result.success_or_error()?;
 
@@ -20,9 +20,13 @@ use restate_cli_util::ui::watcher::Watch;
 use restate_cli_util::{c_println, c_tip, c_title};
 
 use crate::cli_env::CliEnv;
-use crate::clients::datafusion_helpers::{get_invocation, get_invocation_journal, InvocationState};
+use crate::clients::datafusion_helpers::{
+    get_invocation, get_invocation_events, get_invocation_journal, InvocationState,
+};
 use crate::clients::{self};
-use crate::ui::invocations::{add_invocation_to_kv_table, format_journal_entry, invocation_status};
+use crate::ui::invocations::{
+    add_invocation_to_kv_table, format_invocation_event, format_journal_entry, invocation_status,
+};
 
 #[derive(Run, Parser, Collect, Clone)]
 #[cling(run = "run_describe")]
@@ -124,5 +128,14 @@ async fn describe(env: &CliEnv, opts: &Describe) -> Result<()> {
             invocation_status(inv.status)
         );
     }
+
+    let events = get_invocation_events(&sql_client, &opts.invocation_id).await?;
+    if !events.is_empty() {
+        c_println!();
+        c_title!("🗒️", "Event Log");
+        for event in &events {
+            c_println!("  {}", format_invocation_event(event));
+        }
+    }
     Ok(())
 }
@@ -12,6 +12,8 @@ mod cancel;
 mod describe;
 mod list;
 mod purge;
+mod replay;
+mod restart;
 
 use cling::prelude::*;
 
@@ -25,4 +27,9 @@ pub enum Invocations {
     Cancel(cancel::Cancel),
     /// Purge a completed invocation, or a set of invocations. This command affects only completed invocations.
     Purge(purge::Purge),
+    /// Restart an in-flight invocation, either as a new invocation or in place from a journal index
+    Restart(restart::Restart),
+    /// Replay the journal of an invocation against a deployment, reporting the first point where
+    /// the handler diverges from the stored journal
+    Replay(replay::Replay),
 }
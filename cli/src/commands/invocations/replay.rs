@@ -0,0 +1,403 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use base64::alphabet::STANDARD;
+use base64::engine::{DecodePaddingMode, Engine, GeneralPurpose, GeneralPurposeConfig};
+use bytes::{Bytes, BytesMut};
+use cling::prelude::*;
+use dialoguer::console::style;
+use url::Url;
+
+use restate_cli_util::{c_error, c_indentln, c_println, c_success, c_warn, CliContext};
+use restate_service_protocol::message::{
+    Decoder as V1Decoder, Encoder as V1Encoder, ProtocolMessage,
+};
+use restate_service_protocol_v4::message_codec::{
+    Decoder as V4Decoder, Encoder as V4Encoder, Message, MessageHeader, MessageType,
+};
+use restate_types::errors::{codes, InvocationErrorCode};
+use restate_types::identifiers::InvocationId;
+use restate_types::payload_reference::PAYLOAD_REFERENCE_MAGIC;
+use restate_types::service_protocol::ServiceProtocolVersion;
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::{get_invocation_replay_journal, ReplayJournal};
+use crate::clients::{self};
+
+/// Base64 engine accepting both padded and unpadded input, since the padding of the
+/// SQL `encode` function depends on the server version.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_replay")]
+pub struct Replay {
+    /// The id of the invocation to replay
+    invocation_id: InvocationId,
+
+    /// Base URL of the deployment to replay the journal against, e.g. http://localhost:9080
+    #[clap(long, value_hint = clap::ValueHint::Url)]
+    deployment: Url,
+
+    /// Use HTTP/2 with prior knowledge to connect to the deployment
+    #[clap(long)]
+    http2: bool,
+
+    /// Read the journal from a file previously written with --save-journal,
+    /// rather than from the Restate server
+    #[clap(long, conflicts_with = "save_journal")]
+    journal_file: Option<PathBuf>,
+
+    /// Write the journal read from the Restate server to the given file,
+    /// to replay it later offline with --journal-file
+    #[clap(long)]
+    save_journal: Option<PathBuf>,
+}
+
+pub async fn run_replay(State(env): State<CliEnv>, opts: &Replay) -> Result<()> {
+    let journal = match &opts.journal_file {
+        Some(path) => {
+            let journal: ReplayJournal = serde_json::from_slice(
+                &tokio::fs::read(path)
+                    .await
+                    .with_context(|| format!("Cannot read journal file {}", path.display()))?,
+            )
+            .with_context(|| format!("Cannot parse journal file {}", path.display()))?;
+            if journal.invocation_id != opts.invocation_id.to_string() {
+                bail!(
+                    "The journal file {} belongs to invocation {}, not to {}",
+                    path.display(),
+                    journal.invocation_id,
+                    opts.invocation_id
+                );
+            }
+            journal
+        }
+        None => {
+            let sql_client = clients::DataFusionHttpClient::new(&env).await?;
+            let Some(journal) =
+                get_invocation_replay_journal(&sql_client, &opts.invocation_id.to_string()).await?
+            else {
+                bail!("Invocation {} not found!", opts.invocation_id);
+            };
+            journal
+        }
+    };
+
+    if let Some(path) = &opts.save_journal {
+        tokio::fs::write(path, serde_json::to_vec_pretty(&journal)?)
+            .await
+            .with_context(|| format!("Cannot write journal file {}", path.display()))?;
+        c_println!("Journal saved to {}", path.display());
+    }
+
+    let replay = ReplayRequest::new(opts.invocation_id, &journal)?;
+    c_println!(
+        "Replaying {} journal entries of {} against {} using service protocol {:?}",
+        replay.known_entries,
+        style(format!("{}/{}", journal.service, journal.handler)).bold(),
+        opts.deployment,
+        replay.service_protocol_version
+    );
+    c_println!();
+
+    let response_body = replay.send(&opts.deployment, opts.http2).await?;
+    let outcome = replay.decode_response(response_body)?;
+
+    if let Some(error) = &outcome.error {
+        let related_entry = error
+            .related_entry_index
+            .and_then(|idx| journal.entries.iter().find(|e| e.index == idx));
+
+        if error.code == codes::JOURNAL_MISMATCH || error.code == codes::PROTOCOL_VIOLATION {
+            c_error!("The handler diverged from the stored journal!");
+        } else {
+            c_warn!("The handler failed while replaying the journal.");
+        }
+        c_println!();
+        if let Some(entry) = related_entry {
+            c_indentln!(
+                1,
+                "Stored entry:  #{} {}",
+                entry.index,
+                style(&entry.entry_type).bold()
+            );
+        }
+        if let Some(related_entry_name) = &error.related_entry_name {
+            c_indentln!(1, "Entry name:    {}", related_entry_name);
+        }
+        if let Some(related_entry_type) = &error.related_entry_type {
+            c_indentln!(1, "Emitted entry: {}", related_entry_type);
+        }
+        c_indentln!(1, "Error:         [{}] {}", error.code, error.message);
+        if !error.description.is_empty() {
+            c_indentln!(1, "{}", style(&error.description).dim());
+        }
+        c_println!();
+        bail!("Replay of invocation {} failed", opts.invocation_id);
+    }
+
+    c_success!(
+        "Replayed {} journal entries, no divergence found.",
+        replay.known_entries
+    );
+    if !outcome.new_messages.is_empty() {
+        c_println!("After the replay, the handler sent:");
+        for message in &outcome.new_messages {
+            c_indentln!(1, "{}", message);
+        }
+    }
+    match outcome.termination {
+        Termination::End => c_println!("The handler completed."),
+        Termination::Suspension => {
+            c_println!("The handler suspended, waiting for results not available in the journal.")
+        }
+        Termination::Closed => c_println!("The deployment closed the stream."),
+    }
+
+    Ok(())
+}
+
+struct ReplayRequest {
+    service_protocol_version: ServiceProtocolVersion,
+    url_path: [String; 3],
+    body: Bytes,
+    known_entries: u32,
+    /// For each command sent to the deployment, the related journal index.
+    /// Service protocol V4 refers to commands by their index, rather than by the journal index.
+    command_journal_indexes: Vec<u32>,
+}
+
+impl ReplayRequest {
+    fn new(invocation_id: InvocationId, journal: &ReplayJournal) -> Result<Self> {
+        let service_protocol_version = match journal.service_protocol_version {
+            Some(version) => ServiceProtocolVersion::try_from(version)
+                .ok()
+                .filter(ServiceProtocolVersion::is_supported)
+                .with_context(|| format!("Unsupported service protocol version {version}"))?,
+            // The journal version 2 is only supported by the service protocol V4
+            None if journal.journal_version >= 2 => ServiceProtocolVersion::V4,
+            None => ServiceProtocolVersion::V3,
+        };
+        if journal.journal_version >= 2 && service_protocol_version < ServiceProtocolVersion::V4 {
+            bail!("Journal version 2 cannot be replayed with service protocol {service_protocol_version:?}");
+        }
+
+        let mut messages = vec![];
+        let mut command_journal_indexes = vec![];
+        for entry in &journal.entries {
+            let Some(protocol_message) = &entry.protocol_message else {
+                continue;
+            };
+            let message = Bytes::from(
+                BASE64
+                    .decode(protocol_message)
+                    .with_context(|| format!("Cannot decode journal entry {}", entry.index))?,
+            );
+            if message
+                .windows(PAYLOAD_REFERENCE_MAGIC.len())
+                .any(|window| window == PAYLOAD_REFERENCE_MAGIC)
+            {
+                bail!(
+                    "Journal entry {} references a payload offloaded to the payload store, which cannot be replayed",
+                    entry.index
+                );
+            }
+            if service_protocol_version >= ServiceProtocolVersion::V4 && is_v4_command(&message)? {
+                command_journal_indexes.push(entry.index);
+            }
+            messages.push(message);
+        }
+        let known_entries = u32::try_from(messages.len())?;
+
+        let id = Bytes::copy_from_slice(&invocation_id.to_bytes());
+        let key = journal.key.clone().map(Bytes::from);
+        let start_message = if service_protocol_version >= ServiceProtocolVersion::V4 {
+            V4Encoder::new(service_protocol_version).encode(Message::new_start_message(
+                id,
+                invocation_id.to_string(),
+                key,
+                known_entries,
+                true,
+                [],
+                0,
+                Duration::ZERO,
+            ))
+        } else {
+            V1Encoder::new(service_protocol_version).encode(ProtocolMessage::new_start_message(
+                id,
+                invocation_id.to_string(),
+                key,
+                known_entries,
+                true,
+                [],
+                0,
+                Duration::ZERO,
+            ))
+        };
+
+        let mut body = BytesMut::from(start_message.as_ref());
+        for message in messages {
+            body.extend_from_slice(&message);
+        }
+
+        Ok(Self {
+            service_protocol_version,
+            url_path: [
+                "invoke".to_owned(),
+                journal.service.clone(),
+                journal.handler.clone(),
+            ],
+            body: body.freeze(),
+            known_entries,
+            command_journal_indexes,
+        })
+    }
+
+    async fn send(&self, deployment: &Url, http2: bool) -> Result<Bytes> {
+        let mut url = deployment.clone();
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid deployment url {deployment}"))?
+            .pop_if_empty()
+            .extend(&self.url_path);
+
+        let mut client_builder = reqwest::Client::builder()
+            .connect_timeout(CliContext::get().connect_timeout())
+            .timeout(CliContext::get().request_timeout());
+        if http2 {
+            client_builder = client_builder.http2_prior_knowledge();
+        }
+
+        let content_type = format!(
+            "application/vnd.restate.invocation.v{}",
+            self.service_protocol_version.as_repr()
+        );
+        let response = client_builder
+            .build()?
+            .post(url.clone())
+            .header(http::header::CONTENT_TYPE, &content_type)
+            .header(http::header::ACCEPT, &content_type)
+            .body(self.body.clone())
+            .send()
+            .await
+            .with_context(|| format!("Cannot reach the deployment at {url}"))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("The deployment replied with status {status}: {body}");
+        }
+
+        Ok(response.bytes().await?)
+    }
+
+    fn decode_response(&self, body: Bytes) -> Result<ReplayOutcome> {
+        let mut outcome = ReplayOutcome {
+            new_messages: vec![],
+            error: None,
+            termination: Termination::Closed,
+        };
+
+        if self.service_protocol_version >= ServiceProtocolVersion::V4 {
+            let mut decoder = V4Decoder::new(self.service_protocol_version, usize::MAX, None);
+            decoder.push(body);
+            while let Some((_, message)) = decoder.consume_next()? {
+                match message {
+                    Message::End(_) => outcome.termination = Termination::End,
+                    Message::Suspension(_) => outcome.termination = Termination::Suspension,
+                    Message::Error(error) => {
+                        outcome.error = Some(ReplayError {
+                            code: InvocationErrorCode::from(error.code),
+                            message: error.message,
+                            description: error.description,
+                            related_entry_index: error.related_command_index.and_then(|idx| {
+                                self.command_journal_indexes.get(idx as usize).copied()
+                            }),
+                            related_entry_name: error.related_command_name,
+                            related_entry_type: error.related_command_type.map(|ty| {
+                                u16::try_from(ty)
+                                    .ok()
+                                    .and_then(|ty| MessageType::try_from(ty).ok())
+                                    .map(|ty| format!("{ty:?}"))
+                                    .unwrap_or_else(|| format!("{ty:#x}"))
+                            }),
+                        })
+                    }
+                    message => outcome.new_messages.push(format!("{:?}", message.ty())),
+                }
+            }
+        } else {
+            let mut decoder = V1Decoder::new(self.service_protocol_version, usize::MAX, None);
+            decoder.push(body);
+            while let Some((_, message)) = decoder.consume_next()? {
+                match message {
+                    ProtocolMessage::End(_) => outcome.termination = Termination::End,
+                    ProtocolMessage::Suspension(_) => outcome.termination = Termination::Suspension,
+                    ProtocolMessage::Error(error) => {
+                        outcome.error = Some(ReplayError {
+                            code: InvocationErrorCode::from(error.code),
+                            message: error.message,
+                            description: error.description,
+                            related_entry_index: error.related_entry_index,
+                            related_entry_name: error.related_entry_name,
+                            related_entry_type: error
+                                .related_entry_type
+                                .map(|ty| format!("{ty:#x}")),
+                        })
+                    }
+                    ProtocolMessage::UnparsedEntry(entry) => outcome
+                        .new_messages
+                        .push(entry.header().as_entry_type().to_string()),
+                    message => outcome.new_messages.push(format!("{message:?}")),
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Commands are identified by the message type range 0x0400 - 0x7FFF, see the service protocol specification.
+fn is_v4_command(message: &Bytes) -> Result<bool> {
+    let Some(header) = message.get(..8) else {
+        bail!("Invalid protocol message, the header is missing");
+    };
+    let header = MessageHeader::try_from(u64::from_be_bytes(header.try_into()?))?;
+    let ty = u16::from(header.message_type());
+    Ok((0x0400..0x8000).contains(&ty))
+}
+
+struct ReplayOutcome {
+    /// Messages sent by the deployment after the end of the replay
+    new_messages: Vec<String>,
+    error: Option<ReplayError>,
+    termination: Termination,
+}
+
+struct ReplayError {
+    code: InvocationErrorCode,
+    message: String,
+    description: String,
+    related_entry_index: Option<u32>,
+    related_entry_name: Option<String>,
+    related_entry_type: Option<String>,
+}
+
+enum Termination {
+    End,
+    Suspension,
+    Closed,
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::find_active_invocations_simple;
+use crate::clients::{self, AdminClientInterface};
+use crate::ui::invocations::render_simple_invocation_list;
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+use restate_admin_rest_model::invocations::RestartInvocationRequest;
+use restate_cli_util::ui::console::confirm_or_exit;
+use restate_cli_util::{c_println, c_success, c_warn};
+use restate_types::identifiers::InvocationId;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_restart")]
+pub struct Restart {
+    /// The id of the in-flight invocation to restart
+    invocation_id: InvocationId,
+
+    /// Restart the invocation in place, removing the journal entries starting from the given
+    /// index. The restart is refused while any of the removed entries, other than sleeps, is still
+    /// awaiting its completion. If omitted, a new invocation with the same input is started instead.
+    #[clap(long)]
+    from_index: Option<u32>,
+}
+
+pub async fn run_restart(State(env): State<CliEnv>, opts: &Restart) -> Result<()> {
+    let client = clients::AdminClient::new(&env).await?;
+    let sql_client = clients::DataFusionHttpClient::from(client.clone());
+
+    let filter = format!(
+        "id = '{}' AND status IN ('invoked', 'suspended')",
+        opts.invocation_id
+    );
+    let invocations = find_active_invocations_simple(&sql_client, &filter).await?;
+    if invocations.is_empty() {
+        bail!(
+            "No in-flight invocation found with id {}! Note that only invoked or suspended invocations can be restarted.",
+            opts.invocation_id
+        );
+    };
+
+    render_simple_invocation_list(&invocations);
+
+    let request = match opts.from_index {
+        Some(from_index) => {
+            c_warn!(
+                "Journal entries starting from index {from_index} will be removed. \
+                In-flight calls or awakeables recorded in those entries are not cancelled."
+            );
+            RestartInvocationRequest::InPlace { from_index }
+        }
+        None => RestartInvocationRequest::AsNew,
+    };
+
+    confirm_or_exit("Are you sure you want to restart this invocation?")?;
+
+    let response = client
+        .restart_invocation(&opts.invocation_id.to_string(), request)
+        .await?
+        .into_body()
+        .await?;
+
+    c_println!();
+    if response.invocation_id == opts.invocation_id {
+        c_success!("Invocation {} is being restarted", response.invocation_id);
+    } else {
+        c_success!(
+            "Invocation restarted as new invocation {}",
+            response.invocation_id
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,336 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::io::Read;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use bytes::Bytes;
+use cling::prelude::*;
+use serde::Deserialize;
+use serde_json::Value;
+
+use restate_cli_util::{c_eprintln, c_println, c_success};
+use restate_types::invocation::ServiceType;
+use restate_types::schema::service::HandlerMetadata;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{
+    AdminClient, AdminClientInterface, IngressClient, IngressResponse, IngressTarget,
+};
+
+#[derive(Args, Collect, Clone)]
+pub struct InvocationOpts {
+    /// The handler to invoke, in the format `service/handler`
+    target: String,
+
+    /// JSON input of the handler. Use `@path` to read it from a file, or `-` to read it from stdin.
+    payload: Option<String>,
+
+    /// Key of the virtual object or workflow to invoke
+    #[clap(long, short)]
+    key: Option<String>,
+
+    /// Idempotency key of the invocation. Invoking again with the same idempotency key
+    /// returns the result of the first invocation.
+    #[clap(long)]
+    idempotency_key: Option<String>,
+
+    /// Don't validate the payload against the JSON schema of the handler input
+    #[clap(long)]
+    skip_validation: bool,
+}
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_invoke")]
+#[clap(visible_alias = "call")]
+pub struct Invoke {
+    #[clap(flatten)]
+    opts: InvocationOpts,
+}
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_send")]
+pub struct SendInvocation {
+    #[clap(flatten)]
+    opts: InvocationOpts,
+
+    /// Delay the execution of the invocation, e.g. `10s` or `1h 30m`
+    #[clap(long, value_parser = humantime::parse_duration)]
+    delay: Option<Duration>,
+
+    /// Wait for the invocation to complete and print its output
+    #[clap(long)]
+    attach: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SendResponse {
+    invocation_id: String,
+    status: SendStatus,
+}
+
+#[derive(Deserialize)]
+enum SendStatus {
+    Accepted,
+    PreviouslyAccepted,
+}
+
+pub async fn run_invoke(State(env): State<CliEnv>, opts: &Invoke) -> Result<()> {
+    let opts = &opts.opts;
+    let (service, handler) = parse_target(&opts.target)?;
+    let payload = read_payload(opts.payload.as_deref())?;
+    resolve_and_validate(&env, service, handler, opts, payload.as_ref()).await?;
+
+    let target = IngressTarget {
+        service,
+        key: opts.key.as_deref(),
+        handler,
+    };
+    let response = IngressClient::new(&env)?
+        .call(
+            &target,
+            payload.map(to_bytes).transpose()?,
+            opts.idempotency_key.as_deref(),
+        )
+        .await?;
+
+    print_output(response)
+}
+
+pub async fn run_send(State(env): State<CliEnv>, opts: &SendInvocation) -> Result<()> {
+    let (service, handler) = parse_target(&opts.opts.target)?;
+    let payload = read_payload(opts.opts.payload.as_deref())?;
+    resolve_and_validate(&env, service, handler, &opts.opts, payload.as_ref()).await?;
+
+    let target = IngressTarget {
+        service,
+        key: opts.opts.key.as_deref(),
+        handler,
+    };
+    let ingress_client = IngressClient::new(&env)?;
+    let response = ingress_client
+        .send(
+            &target,
+            payload.map(to_bytes).transpose()?,
+            opts.opts.idempotency_key.as_deref(),
+            opts.delay,
+        )
+        .await?;
+    let send_response: SendResponse = serde_json::from_slice(&response.body)
+        .context("Failed parsing the response of the ingress")?;
+
+    match send_response.status {
+        SendStatus::Accepted => {
+            c_success!("Invocation {} accepted", send_response.invocation_id)
+        }
+        SendStatus::PreviouslyAccepted => c_success!(
+            "Invocation {} was already accepted with the same idempotency key",
+            send_response.invocation_id
+        ),
+    }
+
+    if opts.attach {
+        let response = ingress_client.attach(&send_response.invocation_id).await?;
+        print_output(response)?;
+    }
+
+    Ok(())
+}
+
+fn parse_target(target: &str) -> Result<(&str, &str)> {
+    match target.split_once('/') {
+        Some((service, handler))
+            if !service.is_empty() && !handler.is_empty() && !handler.contains('/') =>
+        {
+            Ok((service, handler))
+        }
+        _ => bail!(
+            "Invalid target '{target}', expected 'service/handler'. Use --key to specify the key of virtual objects and workflows."
+        ),
+    }
+}
+
+/// Reads a JSON payload given inline, as `@path` or as `-` for stdin.
+pub(crate) fn read_payload(payload: Option<&str>) -> Result<Option<Value>> {
+    let Some(payload) = payload else {
+        return Ok(None);
+    };
+
+    let raw = if payload == "-" {
+        let mut raw = String::new();
+        std::io::stdin()
+            .read_to_string(&mut raw)
+            .context("Failed reading the payload from stdin")?;
+        raw
+    } else if let Some(path) = payload.strip_prefix('@') {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed reading the payload from '{path}'"))?
+    } else {
+        payload.to_owned()
+    };
+
+    serde_json::from_str(&raw)
+        .map(Some)
+        .context("The payload is not valid JSON")
+}
+
+fn to_bytes(payload: Value) -> Result<Bytes> {
+    Ok(Bytes::from(serde_json::to_vec(&payload)?))
+}
+
+/// Resolves the handler from the schema registry and checks that the request matches it.
+async fn resolve_and_validate(
+    env: &CliEnv,
+    service: &str,
+    handler: &str,
+    opts: &InvocationOpts,
+    payload: Option<&Value>,
+) -> Result<()> {
+    let client = AdminClient::new(env).await?;
+    let service_metadata = client.get_service(service).await?.into_body().await?;
+
+    if !service_metadata.public {
+        bail!("Service '{service}' is private and cannot be invoked through the ingress");
+    }
+    match (service_metadata.ty, &opts.key) {
+        (ServiceType::Service, Some(_)) => {
+            bail!("Service '{service}' is not keyed, --key cannot be used")
+        }
+        (ServiceType::VirtualObject | ServiceType::Workflow, None) => {
+            bail!(
+                "'{service}' is a {}, the key must be provided with --key",
+                service_metadata.ty
+            )
+        }
+        _ => {}
+    }
+
+    let handler_metadata = service_metadata
+        .handlers
+        .into_iter()
+        .find(|h| h.name == handler)
+        .ok_or_else(|| anyhow!("Service '{service}' has no handler named '{handler}'"))?;
+
+    if let (Some(payload), false) = (payload, opts.skip_validation) {
+        validate_payload(&handler_metadata, payload)?;
+    }
+
+    Ok(())
+}
+
+fn validate_payload(handler: &HandlerMetadata, payload: &Value) -> Result<()> {
+    let Some(schema) = &handler.input_json_schema else {
+        return Ok(());
+    };
+
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| anyhow!("The input JSON schema of the handler is invalid: {e}"))?;
+    let errors: Vec<_> = validator
+        .iter_errors(payload)
+        .map(|e| format!("  - {}: {e}", e.instance_path))
+        .collect();
+    if !errors.is_empty() {
+        bail!(
+            "The payload doesn't match the input JSON schema of the handler '{}':\n{}\nUse --skip-validation to send it anyway.",
+            handler.name,
+            errors.join("\n")
+        );
+    }
+
+    Ok(())
+}
+
+fn print_output(response: IngressResponse) -> Result<()> {
+    if let Some(invocation_id) = &response.invocation_id {
+        c_eprintln!("Invocation id: {invocation_id}");
+    }
+
+    let is_json = response
+        .content_type
+        .as_ref()
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+
+    if response.body.is_empty() {
+        return Ok(());
+    }
+    if is_json {
+        if let Ok(value) = serde_json::from_slice::<Value>(&response.body) {
+            c_println!("{}", serde_json::to_string_pretty(&value)?);
+            return Ok(());
+        }
+    }
+    c_println!("{}", String::from_utf8_lossy(&response.body));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn handler(input_json_schema: Option<Value>) -> HandlerMetadata {
+        serde_json::from_value(json!({
+            "name": "greet",
+            "input_description": "",
+            "output_description": "",
+            "input_json_schema": input_json_schema,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_target() {
+        assert_eq!(parse_target("Greeter/greet").unwrap(), ("Greeter", "greet"));
+
+        for invalid in ["Greeter", "Greeter/", "/greet", "Greeter/key/greet"] {
+            assert!(parse_target(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn reads_inline_and_file_payloads() {
+        assert_eq!(read_payload(None).unwrap(), None);
+        assert_eq!(
+            read_payload(Some(r#"{"name":"Till"}"#)).unwrap(),
+            Some(json!({"name": "Till"}))
+        );
+        assert!(read_payload(Some("{not json")).is_err());
+
+        let path = std::env::temp_dir().join(format!("payload-{}.json", std::process::id()));
+        std::fs::write(&path, "[1, 2]").unwrap();
+        let payload = read_payload(Some(&format!("@{}", path.display())));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(payload.unwrap(), Some(json!([1, 2])));
+    }
+
+    #[test]
+    fn validates_payload_against_input_schema() {
+        let handler = handler(Some(json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"],
+        })));
+
+        assert!(validate_payload(&handler, &json!({"name": "Till"})).is_ok());
+        let err = validate_payload(&handler, &json!({"name": 1})).unwrap_err();
+        assert!(err.to_string().contains("/name"), "{err}");
+        assert!(validate_payload(&handler, &json!({})).is_err());
+    }
+
+    #[test]
+    fn accepts_any_payload_without_input_schema() {
+        assert!(validate_payload(&handler(None), &json!("anything")).is_ok());
+    }
+}
@@ -8,13 +8,17 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub mod apply;
+pub mod awakeables;
 #[cfg(feature = "cloud")]
 pub mod cloud;
 pub mod config;
 pub mod deployments;
 pub mod examples;
 pub mod invocations;
+pub mod invoke;
 pub mod services;
 pub mod sql;
 pub mod state;
 pub mod whoami;
+pub mod workflows;
@@ -0,0 +1,579 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Generation of typed ingress clients from the OpenAPI of the registered services.
+//!
+//! The OpenAPI of each service is parsed into a [`ServiceClient`], listing the handlers with
+//! their payloads, and the JSON schemas referenced by them. The language specific generators
+//! then render the types and one client per service, covering call, send (with delay and
+//! idempotency key), and attach/get output routes.
+
+mod rust;
+mod typescript;
+
+/// Compile check of the generated Rust clients.
+#[cfg(test)]
+#[rustfmt::skip]
+#[path = "snapshots/clients.rs"]
+mod rust_snapshot;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use cling::prelude::*;
+use serde_json::{Map, Value};
+
+use restate_cli_util::{c_println, c_success};
+use restate_types::invocation::ServiceType;
+use restate_types::schema::service::ServiceMetadata;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+#[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Language {
+    #[clap(alias = "ts")]
+    Typescript,
+    #[clap(alias = "rs")]
+    Rust,
+}
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_codegen")]
+pub struct Codegen {
+    /// Language of the generated clients
+    #[clap(long, short, value_enum)]
+    lang: Language,
+
+    /// File to write the generated clients to. If not provided, they're printed to stdout.
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+
+    /// Services to generate clients for. Defaults to all the public services.
+    services: Vec<String>,
+}
+
+pub async fn run_codegen(State(env): State<CliEnv>, opts: &Codegen) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    let mut services = client.get_services().await?.into_body().await?.services;
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let services: Vec<_> = if opts.services.is_empty() {
+        services.into_iter().filter(|svc| svc.public).collect()
+    } else {
+        let mut selected = Vec::with_capacity(opts.services.len());
+        for name in &opts.services {
+            let Some(service) = services.iter().find(|svc| &svc.name == name) else {
+                bail!("Service '{}' was not found", name);
+            };
+            selected.push(service.clone());
+        }
+        selected
+    };
+    if services.is_empty() {
+        bail!("No public services were found! Services are added by registering deployments with 'restate dep register'");
+    }
+
+    let mut clients = Vec::with_capacity(services.len());
+    for service in &services {
+        let openapi = client
+            .get_service_openapi(&service.name)
+            .await?
+            .into_body()
+            .await
+            .with_context(|| format!("Cannot get the OpenAPI of the service '{}'", service.name))?;
+        clients.push(ServiceClient::parse(service, &openapi));
+    }
+
+    let generated = match opts.lang {
+        Language::Typescript => typescript::generate(&clients),
+        Language::Rust => rust::generate(&clients),
+    };
+
+    match &opts.output {
+        Some(path) => {
+            std::fs::write(path, generated)
+                .with_context(|| format!("Cannot write the clients to {}", path.display()))?;
+            c_success!(
+                "Generated clients for {} service(s) in {}",
+                clients.len(),
+                path.display()
+            );
+        }
+        None => c_println!("{}", generated),
+    }
+    Ok(())
+}
+
+/// Client of a service, inferred from its OpenAPI.
+struct ServiceClient {
+    name: String,
+    ty: ServiceType,
+    documentation: Option<String>,
+    handlers: Vec<HandlerClient>,
+    /// JSON schemas of the handler payloads, by component name.
+    schemas: Map<String, Value>,
+}
+
+struct HandlerClient {
+    name: String,
+    documentation: Option<String>,
+    input: Option<Payload>,
+    input_required: bool,
+    output: Option<Payload>,
+    /// Whether the ingress exposes the attach and get output routes for this handler.
+    attachable: bool,
+}
+
+struct Payload {
+    content_type: String,
+    kind: PayloadKind,
+}
+
+enum PayloadKind {
+    /// JSON payload, with the name of the schema component describing it, if any.
+    Json(Option<String>),
+    Raw,
+}
+
+const SCHEMAS_REF_PREFIX: &str = "#/components/schemas/";
+
+impl ServiceClient {
+    fn parse(service: &ServiceMetadata, openapi: &Value) -> Self {
+        let mut handlers = BTreeMap::new();
+        let mut attachable = BTreeSet::new();
+
+        for item in openapi
+            .get("paths")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(Map::values)
+        {
+            if let Some(operation) = item.get("post") {
+                // Send routes are tagged, call routes are not
+                if operation.get("tags").is_some() {
+                    continue;
+                }
+                let Some(name) = operation.get("operationId").and_then(Value::as_str) else {
+                    continue;
+                };
+                let request_body = operation.get("requestBody");
+                handlers.insert(
+                    name.to_owned(),
+                    HandlerClient {
+                        name: name.to_owned(),
+                        documentation: service
+                            .handlers
+                            .iter()
+                            .find(|handler| handler.name == name)
+                            .and_then(|handler| handler.documentation.clone()),
+                        input: request_body.and_then(Payload::parse),
+                        input_required: request_body
+                            .and_then(|body| body.get("required"))
+                            .and_then(Value::as_bool)
+                            .unwrap_or(false),
+                        output: operation.pointer("/responses/200").and_then(Payload::parse),
+                        attachable: false,
+                    },
+                );
+            } else if let Some(name) = item
+                .pointer("/get/operationId")
+                .and_then(Value::as_str)
+                .and_then(|id| id.strip_suffix("Attach"))
+            {
+                attachable.insert(name.to_owned());
+            }
+        }
+
+        for (name, handler) in handlers.iter_mut() {
+            handler.attachable = attachable.contains(name);
+        }
+
+        let restate_schemas = ["RestateError", "GenericError"];
+        let schemas = openapi
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+            .map(|schemas| {
+                schemas
+                    .iter()
+                    .filter(|(name, _)| !restate_schemas.contains(&name.as_str()))
+                    .map(|(name, schema)| (name.clone(), schema.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            name: service.name.clone(),
+            ty: service.ty,
+            documentation: service.documentation.clone(),
+            handlers: handlers.into_values().collect(),
+            schemas,
+        }
+    }
+
+    fn is_keyed(&self) -> bool {
+        self.ty.is_keyed()
+    }
+
+    fn is_workflow(&self) -> bool {
+        self.ty == ServiceType::Workflow
+    }
+
+    /// Name of the generated type for the given schema component.
+    fn type_name(&self, component: &str) -> String {
+        format!("{}{}", pascal_case(&self.name), pascal_case(component))
+    }
+
+    /// Resolves a `$ref` to the schemas of this service, returning the name of the generated
+    /// type together with the referenced schema. References to definitions nested in a
+    /// component, e.g. `$defs`, get their own type too.
+    fn resolve_ref(&self, reference: &str) -> Option<(String, &Value)> {
+        let path = reference.strip_prefix(SCHEMAS_REF_PREFIX)?;
+        let (component, pointer) = match path.split_once('/') {
+            Some((component, pointer)) => (component, Some(pointer)),
+            None => (path, None),
+        };
+        let schema = self.schemas.get(component)?;
+        match pointer {
+            None => Some((self.type_name(component), schema)),
+            Some(pointer) => {
+                let nested = schema.pointer(&format!("/{pointer}"))?;
+                let last = pointer.rsplit('/').next().unwrap_or(pointer);
+                Some((
+                    format!("{}{}", self.type_name(component), pascal_case(last)),
+                    nested,
+                ))
+            }
+        }
+    }
+}
+
+impl Payload {
+    fn parse(value: &Value) -> Option<Self> {
+        let (content_type, content) = value.get("content")?.as_object()?.iter().next()?;
+        let kind = if content_type.contains("json") {
+            PayloadKind::Json(
+                content
+                    .pointer("/schema/$ref")
+                    .and_then(Value::as_str)
+                    .and_then(|reference| reference.strip_prefix(SCHEMAS_REF_PREFIX))
+                    .map(str::to_owned),
+            )
+        } else {
+            PayloadKind::Raw
+        };
+        Some(Self {
+            content_type: content_type.clone(),
+            kind,
+        })
+    }
+}
+
+/// Splits the name in words, on non alphanumeric characters and on lower to upper case
+/// transitions.
+fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lowercase = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lowercase && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lowercase = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn pascal_case(name: &str) -> String {
+    words(name)
+        .into_iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+fn snake_case(name: &str) -> String {
+    words(name)
+        .into_iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::identifiers::DeploymentId;
+    use serde_json::json;
+
+    fn greeter_openapi() -> Value {
+        json!({
+            "paths": {
+                "/Greeter/{key}/greet": {
+                    "post": {
+                        "operationId": "greet",
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/greetRequest" }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "200": { "content": { "text/plain": {} } }
+                        }
+                    }
+                },
+                "/Greeter/{key}/greet/send": {
+                    "post": { "operationId": "greetSend", "tags": ["Send"] }
+                },
+                "/restate/invocation/Greeter/{key}/greet/{idempotencyKey}/attach": {
+                    "get": { "operationId": "greetAttach", "tags": ["Attach"] }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "greetRequest": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "tags": { "type": "array", "items": { "$ref": "#/components/schemas/greetRequest/$defs/Tag" } }
+                        },
+                        "required": ["name"],
+                        "$defs": {
+                            "Tag": { "type": "string", "enum": ["friend", "family"] }
+                        }
+                    },
+                    "RestateError": { "type": "object" }
+                }
+            }
+        })
+    }
+
+    fn signup_openapi() -> Value {
+        json!({
+            "paths": {
+                "/SignupWorkflow/{key}/run": {
+                    "post": {
+                        "operationId": "run",
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/runRequest" }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/runResponse" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/SignupWorkflow/{key}/run/send": {
+                    "post": { "operationId": "runSend", "tags": ["Send"] }
+                },
+                "/restate/workflow/SignupWorkflow/{key}/attach": {
+                    "get": { "operationId": "runAttach", "tags": ["Attach"] }
+                },
+                "/restate/workflow/SignupWorkflow/{key}/output": {
+                    "get": { "operationId": "runOutput", "tags": ["Get Output"] }
+                },
+                "/SignupWorkflow/{key}/click": {
+                    "post": {
+                        "operationId": "click",
+                        "requestBody": {
+                            "required": false,
+                            "content": { "application/octet-stream": {} }
+                        },
+                        "responses": { "200": {} }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "runRequest": {
+                        "type": "object",
+                        "properties": {
+                            "email": { "type": "string", "description": "Email to send the link to" },
+                            "type": { "type": "string" },
+                            "attributes": { "type": "object", "additionalProperties": { "type": "string" } }
+                        },
+                        "required": ["email"]
+                    },
+                    "runResponse": { "type": ["boolean", "null"] }
+                }
+            }
+        })
+    }
+
+    fn service(name: &str, ty: ServiceType, documentation: Option<&str>) -> ServiceMetadata {
+        ServiceMetadata {
+            name: name.to_owned(),
+            handlers: vec![],
+            ty,
+            documentation: documentation.map(str::to_owned),
+            metadata: Default::default(),
+            deployment_id: DeploymentId::new(),
+            revision: 1,
+            public: true,
+            idempotency_retention: std::time::Duration::from_secs(60).into(),
+            workflow_completion_retention: None,
+            inactivity_timeout: None,
+            abort_timeout: None,
+            enforce_input_schema: false,
+        }
+    }
+
+    fn greeter_client() -> ServiceClient {
+        ServiceClient::parse(
+            &service("Greeter", ServiceType::VirtualObject, None),
+            &greeter_openapi(),
+        )
+    }
+
+    fn snapshot_clients() -> [ServiceClient; 2] {
+        [
+            greeter_client(),
+            ServiceClient::parse(
+                &service(
+                    "SignupWorkflow",
+                    ServiceType::Workflow,
+                    Some("Signs up new users."),
+                ),
+                &signup_openapi(),
+            ),
+        ]
+    }
+
+    /// Compares the generated code with the checked in snapshot. Run the tests with
+    /// `UPDATE_SNAPSHOTS=1` to overwrite the snapshot instead.
+    fn assert_snapshot(file: &str, snapshot: &str, generated: &str) {
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("src/commands/services/codegen/snapshots")
+                .join(file);
+            std::fs::write(path, generated).unwrap();
+            return;
+        }
+        assert!(
+            snapshot == generated,
+            "the generated code differs from snapshots/{file}, \
+            run the tests with UPDATE_SNAPSHOTS=1 to update it:\n{generated}"
+        );
+    }
+
+    #[test]
+    fn parse_service_openapi() {
+        let client = greeter_client();
+
+        assert_eq!(client.handlers.len(), 1);
+        let greet = &client.handlers[0];
+        assert_eq!(greet.name, "greet");
+        assert!(greet.input_required);
+        assert!(greet.attachable);
+        assert!(matches!(
+            greet.input.as_ref().map(|payload| &payload.kind),
+            Some(PayloadKind::Json(Some(name))) if name == "greetRequest"
+        ));
+        assert!(matches!(
+            greet.output.as_ref().map(|payload| &payload.kind),
+            Some(PayloadKind::Raw)
+        ));
+        assert!(!client.schemas.contains_key("RestateError"));
+
+        let (name, _) = client
+            .resolve_ref("#/components/schemas/greetRequest/$defs/Tag")
+            .unwrap();
+        assert_eq!(name, "GreeterGreetRequestTag");
+    }
+
+    #[test]
+    fn generate_typescript_and_rust_clients() {
+        let clients = [greeter_client()];
+
+        let typescript = typescript::generate(&clients);
+        assert!(
+            typescript.contains("export type GreeterGreetRequestTag = \"friend\" | \"family\";")
+        );
+        assert!(typescript.contains(
+            "export type GreeterGreetRequest = { name: string; tags?: Array<GreeterGreetRequestTag> };"
+        ));
+        assert!(typescript.contains("export class GreeterClient"));
+        assert!(typescript.contains("async attachGreet(key: string, idempotencyKey: string)"));
+
+        let rust = rust::generate(&clients);
+        assert!(rust.contains("pub struct GreeterGreetRequest {"));
+        assert!(rust.contains("pub tags: Option<Vec<GreeterGreetRequestTag>>,"));
+        assert!(rust.contains("pub enum GreeterGreetRequestTag {"));
+        assert!(rust.contains("pub async fn send_greet("));
+    }
+
+    #[test]
+    fn typescript_snapshot() {
+        assert_snapshot(
+            "clients.ts",
+            include_str!("snapshots/clients.ts"),
+            &typescript::generate(&snapshot_clients()),
+        );
+    }
+
+    /// The Rust snapshot is also compiled as the `rust_snapshot` module.
+    #[test]
+    fn rust_snapshot() {
+        assert_snapshot(
+            "clients.rs",
+            include_str!("snapshots/clients.rs"),
+            &rust::generate(&snapshot_clients()),
+        );
+    }
+
+    #[test]
+    fn convert_names() {
+        assert_eq!(pascal_case("greetRequest"), "GreetRequest");
+        assert_eq!(camel_case("Greet_all"), "greetAll");
+        assert_eq!(snake_case("getHTTPStatus2"), "get_httpstatus2");
+        assert_eq!(snake_case("my-handler"), "my_handler");
+    }
+}
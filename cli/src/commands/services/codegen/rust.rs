@@ -0,0 +1,695 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Rust clients, based on `reqwest` and `serde`.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+
+use super::{
+    pascal_case, snake_case, HandlerClient, Payload, PayloadKind, ServiceClient, SCHEMAS_REF_PREFIX,
+};
+
+const PRELUDE: &str = r#"// Code generated by `restate services codegen`. DO NOT EDIT.
+//
+// Requires the `reqwest` (with the `json` feature), `serde` (with the `derive` feature)
+// and `serde_json` crates.
+#![allow(dead_code, unused_imports, clippy::all)]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Base URL of the Restate ingress, e.g. http://localhost:8080
+    pub url: reqwest::Url,
+    /// Headers added to every request
+    pub headers: HeaderMap,
+}
+
+impl ClientOptions {
+    pub fn new(url: reqwest::Url) -> Self {
+        Self {
+            url,
+            headers: HeaderMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    pub idempotency_key: Option<String>,
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SendOptions {
+    pub idempotency_key: Option<String>,
+    /// Delay before the invocation is executed
+    pub delay: Option<Duration>,
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowSendOptions {
+    /// Delay before the invocation is executed
+    pub delay: Option<Duration>,
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResponse {
+    pub invocation_id: String,
+    pub status: SendStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SendStatus {
+    Accepted,
+    PreviouslyAccepted,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidUrl,
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+    Restate {
+        status: u16,
+        message: String,
+        description: Option<String>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidUrl => write!(f, "the ingress url cannot be a base"),
+            Error::Http(err) => write!(f, "http error: {err}"),
+            Error::Json(err) => write!(f, "json error: {err}"),
+            Error::Restate {
+                status, message, ..
+            } => write!(f, "restate error {status}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+struct Body {
+    content_type: &'static str,
+    data: Vec<u8>,
+}
+
+impl Body {
+    fn json<T: Serialize + ?Sized>(content_type: &'static str, value: &T) -> Result<Self, Error> {
+        Ok(Self {
+            content_type,
+            data: serde_json::to_vec(value)?,
+        })
+    }
+
+    fn bytes(content_type: &'static str, value: &[u8]) -> Self {
+        Self {
+            content_type,
+            data: value.to_vec(),
+        }
+    }
+}
+
+struct Request<'a> {
+    method: reqwest::Method,
+    segments: &'a [&'a str],
+    delay: Option<Duration>,
+    body: Option<Body>,
+    idempotency_key: Option<String>,
+    headers: HeaderMap,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+async fn invoke(
+    client: &reqwest::Client,
+    options: &ClientOptions,
+    request: Request<'_>,
+) -> Result<reqwest::Response, Error> {
+    let mut url = options.url.clone();
+    url.path_segments_mut()
+        .map_err(|_| Error::InvalidUrl)?
+        .pop_if_empty()
+        .extend(request.segments);
+    if let Some(delay) = request.delay {
+        url.query_pairs_mut()
+            .append_pair("delay", &format!("{}ms", delay.as_millis()));
+    }
+
+    let mut builder = client
+        .request(request.method, url)
+        .headers(options.headers.clone())
+        .headers(request.headers);
+    if let Some(idempotency_key) = request.idempotency_key {
+        builder = builder.header("idempotency-key", idempotency_key);
+    }
+    if let Some(body) = request.body {
+        builder = builder
+            .header(reqwest::header::CONTENT_TYPE, body.content_type)
+            .body(body.data);
+    }
+
+    let response = builder.send().await?;
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status().as_u16();
+    let text = response.text().await?;
+    let (message, description) = match serde_json::from_str::<ErrorBody>(&text) {
+        Ok(body) => (body.message, body.description),
+        Err(_) => (text, None),
+    };
+    Err(Error::Restate {
+        status,
+        message,
+        description,
+    })
+}
+"#;
+
+pub(super) fn generate(services: &[ServiceClient]) -> String {
+    let mut out = String::from(PRELUDE);
+    for service in services {
+        let mut types = Types::new(service);
+        for component in service.schemas.keys() {
+            types.named(&format!("{SCHEMAS_REF_PREFIX}{component}"));
+        }
+        out.push('\n');
+        out.push_str(&types.definitions);
+        write_client(&mut out, service);
+    }
+    out
+}
+
+/// Renders the JSON schemas of a service as Rust types. Nested objects and enums get their
+/// own type, named after the containing type and field.
+struct Types<'a> {
+    service: &'a ServiceClient,
+    emitted: HashSet<String>,
+    /// Types being defined, used to box recursive references.
+    defining: Vec<String>,
+    definitions: String,
+}
+
+impl<'a> Types<'a> {
+    fn new(service: &'a ServiceClient) -> Self {
+        Self {
+            service,
+            emitted: HashSet::new(),
+            defining: vec![],
+            definitions: String::new(),
+        }
+    }
+
+    /// Defines the type for the referenced schema, if not done already, returning its name.
+    fn named(&mut self, reference: &str) -> String {
+        let Some((name, schema)) = self.service.resolve_ref(reference) else {
+            return "serde_json::Value".to_owned();
+        };
+        if self.defining.contains(&name) {
+            return format!("Box<{name}>");
+        }
+        if self.emitted.insert(name.clone()) {
+            self.define(&name, schema);
+        }
+        name
+    }
+
+    /// Returns a name based on the hint that's not used yet by another type.
+    fn unique_name(&mut self, hint: &str) -> String {
+        let mut name = hint.to_owned();
+        let mut i = 2;
+        while !self.emitted.insert(name.clone()) {
+            name = format!("{hint}{i}");
+            i += 1;
+        }
+        name
+    }
+
+    fn define(&mut self, name: &str, schema: &Value) {
+        self.defining.push(name.to_owned());
+        let mut definition = String::new();
+        write_doc(
+            &mut definition,
+            "",
+            schema.get("description").and_then(Value::as_str),
+        );
+        if let Some(object) = schema.as_object().filter(|object| is_struct(object)) {
+            self.write_struct(&mut definition, name, object);
+        } else if let Some(values) = string_enum(schema) {
+            write_enum(&mut definition, name, &values);
+        } else {
+            let ty = self.ty(schema, name);
+            let _ = writeln!(definition, "pub type {name} = {ty};");
+        }
+        self.defining.pop();
+        self.definitions.push_str(&definition);
+        self.definitions.push('\n');
+    }
+
+    fn ty(&mut self, schema: &Value, hint: &str) -> String {
+        let Some(object) = schema.as_object() else {
+            return "serde_json::Value".to_owned();
+        };
+
+        if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+            return self.named(reference);
+        }
+        if is_struct(object) || string_enum(schema).is_some() {
+            let name = self.unique_name(hint);
+            self.define(&name, schema);
+            return name;
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(variants)) = object.get(key) {
+                let non_null: Vec<_> = variants.iter().filter(|v| !is_null(v)).collect();
+                return match non_null.as_slice() {
+                    [variant] if non_null.len() < variants.len() => {
+                        format!("Option<{}>", self.ty(variant, hint))
+                    }
+                    [variant] => self.ty(variant, hint),
+                    _ => "serde_json::Value".to_owned(),
+                };
+            }
+        }
+
+        match object.get("type") {
+            Some(Value::String(ty)) => self.typed(ty, object, hint),
+            Some(Value::Array(types)) => {
+                let non_null: Vec<_> = types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter(|ty| *ty != "null")
+                    .collect();
+                match non_null.as_slice() {
+                    [ty] if non_null.len() < types.len() => {
+                        format!("Option<{}>", self.typed(ty, object, hint))
+                    }
+                    [ty] => self.typed(ty, object, hint),
+                    _ => "serde_json::Value".to_owned(),
+                }
+            }
+            _ => "serde_json::Value".to_owned(),
+        }
+    }
+
+    fn typed(&mut self, ty: &str, schema: &Map<String, Value>, hint: &str) -> String {
+        match ty {
+            "string" => "String".to_owned(),
+            "integer" => "i64".to_owned(),
+            "number" => "f64".to_owned(),
+            "boolean" => "bool".to_owned(),
+            "null" => "()".to_owned(),
+            "array" => {
+                let items = schema
+                    .get("items")
+                    .map(|items| self.ty(items, &format!("{hint}Item")))
+                    .unwrap_or_else(|| "serde_json::Value".to_owned());
+                format!("Vec<{items}>")
+            }
+            "object" => match schema.get("additionalProperties") {
+                Some(values @ Value::Object(_)) => format!(
+                    "HashMap<String, {}>",
+                    self.ty(values, &format!("{hint}Value"))
+                ),
+                _ => "serde_json::Map<String, serde_json::Value>".to_owned(),
+            },
+            _ => "serde_json::Value".to_owned(),
+        }
+    }
+
+    fn write_struct(&mut self, out: &mut String, name: &str, schema: &Map<String, Value>) {
+        let required: HashSet<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+
+        let _ = writeln!(
+            out,
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {name} {{"
+        );
+        for (property, property_schema) in schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let field = field_name(property);
+            let mut ty = self.ty(property_schema, &format!("{name}{}", pascal_case(property)));
+            let optional = !required.contains(property.as_str());
+            if optional && !ty.starts_with("Option<") {
+                ty = format!("Option<{ty}>");
+            }
+
+            write_doc(
+                out,
+                "    ",
+                property_schema.get("description").and_then(Value::as_str),
+            );
+            if field.trim_start_matches("r#") != property {
+                let _ = writeln!(
+                    out,
+                    "    #[serde(rename = {})]",
+                    Value::from(property.as_str())
+                );
+            }
+            if optional {
+                let _ = writeln!(
+                    out,
+                    "    #[serde(default, skip_serializing_if = \"Option::is_none\")]"
+                );
+            }
+            let _ = writeln!(out, "    pub {field}: {ty},");
+        }
+        let _ = writeln!(out, "}}");
+    }
+}
+
+fn is_null(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+fn is_struct(schema: &Map<String, Value>) -> bool {
+    let is_object = match schema.get("type") {
+        Some(Value::String(ty)) => ty == "object",
+        None => true,
+        _ => false,
+    };
+    is_object
+        && schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .is_some_and(|properties| !properties.is_empty())
+}
+
+fn string_enum(schema: &Value) -> Option<Vec<&str>> {
+    let values = schema.get("enum")?.as_array()?;
+    values.iter().map(Value::as_str).collect()
+}
+
+fn write_enum(out: &mut String, name: &str, values: &[&str]) {
+    let _ = writeln!(
+        out,
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\npub enum {name} {{"
+    );
+    let mut variants = HashSet::new();
+    for value in values {
+        let mut variant = pascal_case(value);
+        if variant.is_empty() || variant.starts_with(|c: char| c.is_ascii_digit()) {
+            variant = format!("V{variant}");
+        }
+        if !variants.insert(variant.clone()) {
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "    #[serde(rename = {})]\n    {variant},",
+            Value::from(*value)
+        );
+    }
+    let _ = writeln!(out, "}}");
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Converts the name to a snake case identifier, escaping keywords.
+fn field_name(name: &str) -> String {
+    let name = snake_case(name);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{name}")
+    } else if matches!(name.as_str(), "self" | "super" | "crate") {
+        // These can't be raw identifiers
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else {
+        name
+    }
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    let Some(doc) = doc.map(str::trim).filter(|doc| !doc.is_empty()) else {
+        return;
+    };
+    for line in doc.lines() {
+        let _ = writeln!(out, "{indent}/// {}", line.trim_end());
+    }
+}
+
+fn payload_type(service: &ServiceClient, payload: &Payload) -> String {
+    match &payload.kind {
+        PayloadKind::Json(Some(component)) => service.type_name(component),
+        PayloadKind::Json(None) => "serde_json::Value".to_owned(),
+        PayloadKind::Raw => "Vec<u8>".to_owned(),
+    }
+}
+
+/// Returns the output type of the handler, and the expression decoding it from `response`.
+fn output(service: &ServiceClient, handler: &HandlerClient) -> (String, &'static str) {
+    match handler.output.as_ref() {
+        Some(
+            payload @ Payload {
+                kind: PayloadKind::Json(_),
+                ..
+            },
+        ) => (payload_type(service, payload), "Ok(response.json().await?)"),
+        Some(payload) => (
+            payload_type(service, payload),
+            "Ok(response.bytes().await?.to_vec())",
+        ),
+        None => ("()".to_owned(), "let _ = response;\n        Ok(())"),
+    }
+}
+
+fn write_client(out: &mut String, service: &ServiceClient) {
+    let client = format!("{}Client", pascal_case(&service.name));
+    let svc = Value::from(service.name.as_str()).to_string();
+    let (call_options, send_options) = if service.is_workflow() {
+        ("RequestOptions", "WorkflowSendOptions")
+    } else {
+        ("CallOptions", "SendOptions")
+    };
+    let idempotency_key = if service.is_workflow() {
+        "None"
+    } else {
+        "opts.idempotency_key"
+    };
+
+    write_doc(out, "", service.documentation.as_deref());
+    let _ = writeln!(
+        out,
+        "#[derive(Debug, Clone)]
+pub struct {client} {{
+    client: reqwest::Client,
+    options: ClientOptions,
+}}
+
+impl {client} {{
+    pub fn new(options: ClientOptions) -> Self {{
+        Self::with_client(reqwest::Client::new(), options)
+    }}
+
+    pub fn with_client(client: reqwest::Client, options: ClientOptions) -> Self {{
+        Self {{ client, options }}
+    }}"
+    );
+
+    for handler in &service.handlers {
+        let method = field_name(&handler.name);
+        let snake = snake_case(&handler.name);
+        let handler_name = &handler.name;
+        let name = Value::from(handler.name.as_str()).to_string();
+        let (output_type, decode) = output(service, handler);
+
+        let mut params = String::new();
+        let mut segments = vec![svc.clone()];
+        if service.is_keyed() {
+            params.push_str(", key: &str");
+            segments.push("key".to_owned());
+        }
+        segments.push(name.clone());
+        let body = match &handler.input {
+            Some(payload) => {
+                let ty = match payload.kind {
+                    PayloadKind::Raw => "[u8]".to_owned(),
+                    _ => payload_type(service, payload),
+                };
+                let content_type = Value::from(payload.content_type.as_str());
+                let constructor = match payload.kind {
+                    PayloadKind::Json(_) => format!("Body::json({content_type}, input)?"),
+                    PayloadKind::Raw => format!("Body::bytes({content_type}, input)"),
+                };
+                if handler.input_required {
+                    let _ = write!(params, ", input: &{ty}");
+                    format!("Some({constructor})")
+                } else {
+                    let _ = write!(params, ", input: Option<&{ty}>");
+                    match payload.kind {
+                        PayloadKind::Json(_) => format!(
+                            "input.map(|input| Body::json({content_type}, input)).transpose()?"
+                        ),
+                        PayloadKind::Raw => {
+                            format!("input.map(|input| Body::bytes({content_type}, input))")
+                        }
+                    }
+                }
+            }
+            None => "None".to_owned(),
+        };
+        let segments = segments.join(", ");
+
+        let _ = writeln!(out);
+        write_doc(out, "    ", handler.documentation.as_deref());
+        let _ = writeln!(
+            out,
+            "    pub async fn {method}(&self{params}, opts: {call_options}) -> Result<{output_type}, Error> {{
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {{
+                method: reqwest::Method::POST,
+                segments: &[{segments}],
+                delay: None,
+                body: {body},
+                idempotency_key: {idempotency_key},
+                headers: opts.headers,
+            }},
+        )
+        .await?;
+        {decode}
+    }}"
+        );
+
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "    /// Sends a request to `{handler_name}`, without waiting for the response.
+    pub async fn send_{snake}(&self{params}, opts: {send_options}) -> Result<SendResponse, Error> {{
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {{
+                method: reqwest::Method::POST,
+                segments: &[{segments}, \"send\"],
+                delay: opts.delay,
+                body: {body},
+                idempotency_key: {idempotency_key},
+                headers: opts.headers,
+            }},
+        )
+        .await?;
+        Ok(response.json().await?)
+    }}"
+        );
+
+        if handler.attachable {
+            let (params, segments) = if service.is_workflow() {
+                (
+                    ", key: &str".to_owned(),
+                    format!("\"restate\", \"workflow\", {svc}, key"),
+                )
+            } else if service.is_keyed() {
+                (
+                    ", key: &str, idempotency_key: &str".to_owned(),
+                    format!("\"restate\", \"invocation\", {svc}, key, {name}, idempotency_key"),
+                )
+            } else {
+                (
+                    ", idempotency_key: &str".to_owned(),
+                    format!("\"restate\", \"invocation\", {svc}, {name}, idempotency_key"),
+                )
+            };
+
+            for (function, route, doc) in [
+                (
+                    format!("attach_{snake}"),
+                    "attach",
+                    "Waits for the invocation of `{handler_name}` to complete and returns its output.",
+                ),
+                (
+                    format!("get_{snake}_output"),
+                    "output",
+                    "Returns the output of the invocation of `{handler_name}`, failing if it didn't complete yet.",
+                ),
+            ] {
+                let doc = doc.replace("{handler_name}", &handler.name);
+                let _ = writeln!(out);
+                let _ = writeln!(
+                    out,
+                    "    /// {doc}
+    pub async fn {function}(&self{params}) -> Result<{output_type}, Error> {{
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {{
+                method: reqwest::Method::GET,
+                segments: &[{segments}, \"{route}\"],
+                delay: None,
+                body: None,
+                idempotency_key: None,
+                headers: HeaderMap::new(),
+            }},
+        )
+        .await?;
+        {decode}
+    }}"
+                );
+            }
+        }
+    }
+    let _ = writeln!(out, "}}");
+}
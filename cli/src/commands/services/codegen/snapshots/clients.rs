@@ -0,0 +1,429 @@
+// Code generated by `restate services codegen`. DO NOT EDIT.
+//
+// Requires the `reqwest` (with the `json` feature), `serde` (with the `derive` feature)
+// and `serde_json` crates.
+#![allow(dead_code, unused_imports, clippy::all)]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Base URL of the Restate ingress, e.g. http://localhost:8080
+    pub url: reqwest::Url,
+    /// Headers added to every request
+    pub headers: HeaderMap,
+}
+
+impl ClientOptions {
+    pub fn new(url: reqwest::Url) -> Self {
+        Self {
+            url,
+            headers: HeaderMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    pub idempotency_key: Option<String>,
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SendOptions {
+    pub idempotency_key: Option<String>,
+    /// Delay before the invocation is executed
+    pub delay: Option<Duration>,
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowSendOptions {
+    /// Delay before the invocation is executed
+    pub delay: Option<Duration>,
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResponse {
+    pub invocation_id: String,
+    pub status: SendStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SendStatus {
+    Accepted,
+    PreviouslyAccepted,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidUrl,
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+    Restate {
+        status: u16,
+        message: String,
+        description: Option<String>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidUrl => write!(f, "the ingress url cannot be a base"),
+            Error::Http(err) => write!(f, "http error: {err}"),
+            Error::Json(err) => write!(f, "json error: {err}"),
+            Error::Restate {
+                status, message, ..
+            } => write!(f, "restate error {status}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+struct Body {
+    content_type: &'static str,
+    data: Vec<u8>,
+}
+
+impl Body {
+    fn json<T: Serialize + ?Sized>(content_type: &'static str, value: &T) -> Result<Self, Error> {
+        Ok(Self {
+            content_type,
+            data: serde_json::to_vec(value)?,
+        })
+    }
+
+    fn bytes(content_type: &'static str, value: &[u8]) -> Self {
+        Self {
+            content_type,
+            data: value.to_vec(),
+        }
+    }
+}
+
+struct Request<'a> {
+    method: reqwest::Method,
+    segments: &'a [&'a str],
+    delay: Option<Duration>,
+    body: Option<Body>,
+    idempotency_key: Option<String>,
+    headers: HeaderMap,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+async fn invoke(
+    client: &reqwest::Client,
+    options: &ClientOptions,
+    request: Request<'_>,
+) -> Result<reqwest::Response, Error> {
+    let mut url = options.url.clone();
+    url.path_segments_mut()
+        .map_err(|_| Error::InvalidUrl)?
+        .pop_if_empty()
+        .extend(request.segments);
+    if let Some(delay) = request.delay {
+        url.query_pairs_mut()
+            .append_pair("delay", &format!("{}ms", delay.as_millis()));
+    }
+
+    let mut builder = client
+        .request(request.method, url)
+        .headers(options.headers.clone())
+        .headers(request.headers);
+    if let Some(idempotency_key) = request.idempotency_key {
+        builder = builder.header("idempotency-key", idempotency_key);
+    }
+    if let Some(body) = request.body {
+        builder = builder
+            .header(reqwest::header::CONTENT_TYPE, body.content_type)
+            .body(body.data);
+    }
+
+    let response = builder.send().await?;
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status().as_u16();
+    let text = response.text().await?;
+    let (message, description) = match serde_json::from_str::<ErrorBody>(&text) {
+        Ok(body) => (body.message, body.description),
+        Err(_) => (text, None),
+    };
+    Err(Error::Restate {
+        status,
+        message,
+        description,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GreeterGreetRequestTag {
+    #[serde(rename = "friend")]
+    Friend,
+    #[serde(rename = "family")]
+    Family,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GreeterGreetRequest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<GreeterGreetRequestTag>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GreeterClient {
+    client: reqwest::Client,
+    options: ClientOptions,
+}
+
+impl GreeterClient {
+    pub fn new(options: ClientOptions) -> Self {
+        Self::with_client(reqwest::Client::new(), options)
+    }
+
+    pub fn with_client(client: reqwest::Client, options: ClientOptions) -> Self {
+        Self { client, options }
+    }
+
+    pub async fn greet(&self, key: &str, input: &GreeterGreetRequest, opts: CallOptions) -> Result<Vec<u8>, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::POST,
+                segments: &["Greeter", key, "greet"],
+                delay: None,
+                body: Some(Body::json("application/json", input)?),
+                idempotency_key: opts.idempotency_key,
+                headers: opts.headers,
+            },
+        )
+        .await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Sends a request to `greet`, without waiting for the response.
+    pub async fn send_greet(&self, key: &str, input: &GreeterGreetRequest, opts: SendOptions) -> Result<SendResponse, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::POST,
+                segments: &["Greeter", key, "greet", "send"],
+                delay: opts.delay,
+                body: Some(Body::json("application/json", input)?),
+                idempotency_key: opts.idempotency_key,
+                headers: opts.headers,
+            },
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Waits for the invocation of `greet` to complete and returns its output.
+    pub async fn attach_greet(&self, key: &str, idempotency_key: &str) -> Result<Vec<u8>, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::GET,
+                segments: &["restate", "invocation", "Greeter", key, "greet", idempotency_key, "attach"],
+                delay: None,
+                body: None,
+                idempotency_key: None,
+                headers: HeaderMap::new(),
+            },
+        )
+        .await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Returns the output of the invocation of `greet`, failing if it didn't complete yet.
+    pub async fn get_greet_output(&self, key: &str, idempotency_key: &str) -> Result<Vec<u8>, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::GET,
+                segments: &["restate", "invocation", "Greeter", key, "greet", idempotency_key, "output"],
+                delay: None,
+                body: None,
+                idempotency_key: None,
+                headers: HeaderMap::new(),
+            },
+        )
+        .await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignupWorkflowRunRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<HashMap<String, String>>,
+    /// Email to send the link to
+    pub email: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+}
+
+pub type SignupWorkflowRunResponse = Option<bool>;
+
+/// Signs up new users.
+#[derive(Debug, Clone)]
+pub struct SignupWorkflowClient {
+    client: reqwest::Client,
+    options: ClientOptions,
+}
+
+impl SignupWorkflowClient {
+    pub fn new(options: ClientOptions) -> Self {
+        Self::with_client(reqwest::Client::new(), options)
+    }
+
+    pub fn with_client(client: reqwest::Client, options: ClientOptions) -> Self {
+        Self { client, options }
+    }
+
+    pub async fn click(&self, key: &str, input: Option<&[u8]>, opts: RequestOptions) -> Result<(), Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::POST,
+                segments: &["SignupWorkflow", key, "click"],
+                delay: None,
+                body: input.map(|input| Body::bytes("application/octet-stream", input)),
+                idempotency_key: None,
+                headers: opts.headers,
+            },
+        )
+        .await?;
+        let _ = response;
+        Ok(())
+    }
+
+    /// Sends a request to `click`, without waiting for the response.
+    pub async fn send_click(&self, key: &str, input: Option<&[u8]>, opts: WorkflowSendOptions) -> Result<SendResponse, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::POST,
+                segments: &["SignupWorkflow", key, "click", "send"],
+                delay: opts.delay,
+                body: input.map(|input| Body::bytes("application/octet-stream", input)),
+                idempotency_key: None,
+                headers: opts.headers,
+            },
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn run(&self, key: &str, input: &SignupWorkflowRunRequest, opts: RequestOptions) -> Result<SignupWorkflowRunResponse, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::POST,
+                segments: &["SignupWorkflow", key, "run"],
+                delay: None,
+                body: Some(Body::json("application/json", input)?),
+                idempotency_key: None,
+                headers: opts.headers,
+            },
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Sends a request to `run`, without waiting for the response.
+    pub async fn send_run(&self, key: &str, input: &SignupWorkflowRunRequest, opts: WorkflowSendOptions) -> Result<SendResponse, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::POST,
+                segments: &["SignupWorkflow", key, "run", "send"],
+                delay: opts.delay,
+                body: Some(Body::json("application/json", input)?),
+                idempotency_key: None,
+                headers: opts.headers,
+            },
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Waits for the invocation of `run` to complete and returns its output.
+    pub async fn attach_run(&self, key: &str) -> Result<SignupWorkflowRunResponse, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::GET,
+                segments: &["restate", "workflow", "SignupWorkflow", key, "attach"],
+                delay: None,
+                body: None,
+                idempotency_key: None,
+                headers: HeaderMap::new(),
+            },
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Returns the output of the invocation of `run`, failing if it didn't complete yet.
+    pub async fn get_run_output(&self, key: &str) -> Result<SignupWorkflowRunResponse, Error> {
+        let response = invoke(
+            &self.client,
+            &self.options,
+            Request {
+                method: reqwest::Method::GET,
+                segments: &["restate", "workflow", "SignupWorkflow", key, "output"],
+                delay: None,
+                body: None,
+                idempotency_key: None,
+                headers: HeaderMap::new(),
+            },
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
+}
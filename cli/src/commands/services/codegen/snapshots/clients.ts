@@ -0,0 +1,173 @@
+// Code generated by `restate services codegen`. DO NOT EDIT.
+/* eslint-disable */
+
+export interface ClientOptions {
+  /** Base URL of the Restate ingress, e.g. http://localhost:8080 */
+  url: string;
+  /** Headers added to every request */
+  headers?: Record<string, string>;
+}
+
+export interface RequestOptions {
+  headers?: Record<string, string>;
+}
+
+export interface CallOptions extends RequestOptions {
+  idempotencyKey?: string;
+}
+
+export interface SendOptions extends CallOptions {
+  /** Delay before the invocation is executed, in the humantime format, e.g. 10s */
+  delay?: string;
+}
+
+export interface SendResponse {
+  invocationId: string;
+  status: "Accepted" | "PreviouslyAccepted";
+  executionTime?: string;
+}
+
+export class RestateError extends Error {
+  constructor(
+    readonly status: number,
+    message: string,
+    readonly description?: string,
+  ) {
+    super(message);
+    this.name = "RestateError";
+  }
+}
+
+interface Body {
+  contentType: string;
+  data: BodyInit;
+}
+
+function json(contentType: string, value: unknown): Body | undefined {
+  return value === undefined ? undefined : { contentType, data: JSON.stringify(value) };
+}
+
+function bytes(contentType: string, value: Uint8Array | undefined): Body | undefined {
+  return value === undefined ? undefined : { contentType, data: value };
+}
+
+function route(segments: string[], query: Record<string, string | undefined> = {}): string {
+  const path = segments.map(encodeURIComponent).join("/");
+  const params = new URLSearchParams();
+  for (const [name, value] of Object.entries(query)) {
+    if (value !== undefined) {
+      params.set(name, value);
+    }
+  }
+  const search = params.toString();
+  return search ? `/${path}?${search}` : `/${path}`;
+}
+
+async function invoke<T>(
+  client: ClientOptions,
+  method: "GET" | "POST",
+  path: string,
+  body: Body | undefined,
+  options: CallOptions | undefined,
+  output: "json" | "bytes" | "none",
+): Promise<T> {
+  const headers: Record<string, string> = { ...client.headers, ...options?.headers };
+  if (body !== undefined) {
+    headers["content-type"] = body.contentType;
+  }
+  if (options?.idempotencyKey !== undefined) {
+    headers["idempotency-key"] = options.idempotencyKey;
+  }
+  const response = await fetch(client.url.replace(/\/+$/, "") + path, {
+    method,
+    headers,
+    body: body?.data,
+  });
+  if (!response.ok) {
+    const text = await response.text();
+    let message = text;
+    let description: string | undefined;
+    try {
+      const error = JSON.parse(text);
+      message = error.message ?? text;
+      description = error.description;
+    } catch {
+      // Not a Restate error body
+    }
+    throw new RestateError(response.status, message, description);
+  }
+  switch (output) {
+    case "json":
+      return (await response.json()) as T;
+    case "bytes":
+      return new Uint8Array(await response.arrayBuffer()) as T;
+    case "none":
+      return undefined as T;
+  }
+}
+
+export type GreeterGreetRequestTag = "friend" | "family";
+
+export type GreeterGreetRequest = { name: string; tags?: Array<GreeterGreetRequestTag> };
+
+export class GreeterClient {
+  constructor(private readonly options: ClientOptions) {}
+
+  async greet(key: string, input: GreeterGreetRequest, opts?: CallOptions): Promise<Uint8Array> {
+    return invoke<Uint8Array>(this.options, "POST", route(["Greeter", key, "greet"]), json("application/json", input), opts, "bytes");
+  }
+
+  /** Sends a request to `greet`, without waiting for the response. */
+  async sendGreet(key: string, input: GreeterGreetRequest, opts?: SendOptions): Promise<SendResponse> {
+    return invoke<SendResponse>(this.options, "POST", route(["Greeter", key, "greet", "send"], { delay: opts?.delay }), json("application/json", input), opts, "json");
+  }
+
+  /** Waits for the invocation of `greet` to complete and returns its output. */
+  async attachGreet(key: string, idempotencyKey: string): Promise<Uint8Array> {
+    return invoke<Uint8Array>(this.options, "GET", route(["restate", "invocation", "Greeter", key, "greet", idempotencyKey, "attach"]), undefined, undefined, "bytes");
+  }
+
+  /** Returns the output of the invocation of `greet`, failing if it didn't complete yet. */
+  async getGreetOutput(key: string, idempotencyKey: string): Promise<Uint8Array> {
+    return invoke<Uint8Array>(this.options, "GET", route(["restate", "invocation", "Greeter", key, "greet", idempotencyKey, "output"]), undefined, undefined, "bytes");
+  }
+}
+
+export type SignupWorkflowRunRequest = { attributes?: Record<string, string>; email: string; type?: string };
+
+export type SignupWorkflowRunResponse = boolean | null;
+
+/**
+ * Signs up new users.
+ */
+export class SignupWorkflowClient {
+  constructor(private readonly options: ClientOptions) {}
+
+  async click(key: string, input?: Uint8Array, opts?: RequestOptions): Promise<void> {
+    return invoke<void>(this.options, "POST", route(["SignupWorkflow", key, "click"]), bytes("application/octet-stream", input), opts, "none");
+  }
+
+  /** Sends a request to `click`, without waiting for the response. */
+  async sendClick(key: string, input?: Uint8Array, opts?: Omit<SendOptions, "idempotencyKey">): Promise<SendResponse> {
+    return invoke<SendResponse>(this.options, "POST", route(["SignupWorkflow", key, "click", "send"], { delay: opts?.delay }), bytes("application/octet-stream", input), opts, "json");
+  }
+
+  async run(key: string, input: SignupWorkflowRunRequest, opts?: RequestOptions): Promise<SignupWorkflowRunResponse> {
+    return invoke<SignupWorkflowRunResponse>(this.options, "POST", route(["SignupWorkflow", key, "run"]), json("application/json", input), opts, "json");
+  }
+
+  /** Sends a request to `run`, without waiting for the response. */
+  async sendRun(key: string, input: SignupWorkflowRunRequest, opts?: Omit<SendOptions, "idempotencyKey">): Promise<SendResponse> {
+    return invoke<SendResponse>(this.options, "POST", route(["SignupWorkflow", key, "run", "send"], { delay: opts?.delay }), json("application/json", input), opts, "json");
+  }
+
+  /** Waits for the invocation of `run` to complete and returns its output. */
+  async attachRun(key: string): Promise<SignupWorkflowRunResponse> {
+    return invoke<SignupWorkflowRunResponse>(this.options, "GET", route(["restate", "workflow", "SignupWorkflow", key, "attach"]), undefined, undefined, "json");
+  }
+
+  /** Returns the output of the invocation of `run`, failing if it didn't complete yet. */
+  async getRunOutput(key: string): Promise<SignupWorkflowRunResponse> {
+    return invoke<SignupWorkflowRunResponse>(this.options, "GET", route(["restate", "workflow", "SignupWorkflow", key, "output"]), undefined, undefined, "json");
+  }
+}
@@ -0,0 +1,432 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! TypeScript clients, based on the `fetch` API.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+
+use super::{camel_case, pascal_case, HandlerClient, Payload, PayloadKind, ServiceClient};
+
+const PRELUDE: &str = r#"// Code generated by `restate services codegen`. DO NOT EDIT.
+/* eslint-disable */
+
+export interface ClientOptions {
+  /** Base URL of the Restate ingress, e.g. http://localhost:8080 */
+  url: string;
+  /** Headers added to every request */
+  headers?: Record<string, string>;
+}
+
+export interface RequestOptions {
+  headers?: Record<string, string>;
+}
+
+export interface CallOptions extends RequestOptions {
+  idempotencyKey?: string;
+}
+
+export interface SendOptions extends CallOptions {
+  /** Delay before the invocation is executed, in the humantime format, e.g. 10s */
+  delay?: string;
+}
+
+export interface SendResponse {
+  invocationId: string;
+  status: "Accepted" | "PreviouslyAccepted";
+  executionTime?: string;
+}
+
+export class RestateError extends Error {
+  constructor(
+    readonly status: number,
+    message: string,
+    readonly description?: string,
+  ) {
+    super(message);
+    this.name = "RestateError";
+  }
+}
+
+interface Body {
+  contentType: string;
+  data: BodyInit;
+}
+
+function json(contentType: string, value: unknown): Body | undefined {
+  return value === undefined ? undefined : { contentType, data: JSON.stringify(value) };
+}
+
+function bytes(contentType: string, value: Uint8Array | undefined): Body | undefined {
+  return value === undefined ? undefined : { contentType, data: value };
+}
+
+function route(segments: string[], query: Record<string, string | undefined> = {}): string {
+  const path = segments.map(encodeURIComponent).join("/");
+  const params = new URLSearchParams();
+  for (const [name, value] of Object.entries(query)) {
+    if (value !== undefined) {
+      params.set(name, value);
+    }
+  }
+  const search = params.toString();
+  return search ? `/${path}?${search}` : `/${path}`;
+}
+
+async function invoke<T>(
+  client: ClientOptions,
+  method: "GET" | "POST",
+  path: string,
+  body: Body | undefined,
+  options: CallOptions | undefined,
+  output: "json" | "bytes" | "none",
+): Promise<T> {
+  const headers: Record<string, string> = { ...client.headers, ...options?.headers };
+  if (body !== undefined) {
+    headers["content-type"] = body.contentType;
+  }
+  if (options?.idempotencyKey !== undefined) {
+    headers["idempotency-key"] = options.idempotencyKey;
+  }
+  const response = await fetch(client.url.replace(/\/+$/, "") + path, {
+    method,
+    headers,
+    body: body?.data,
+  });
+  if (!response.ok) {
+    const text = await response.text();
+    let message = text;
+    let description: string | undefined;
+    try {
+      const error = JSON.parse(text);
+      message = error.message ?? text;
+      description = error.description;
+    } catch {
+      // Not a Restate error body
+    }
+    throw new RestateError(response.status, message, description);
+  }
+  switch (output) {
+    case "json":
+      return (await response.json()) as T;
+    case "bytes":
+      return new Uint8Array(await response.arrayBuffer()) as T;
+    case "none":
+      return undefined as T;
+  }
+}
+"#;
+
+pub(super) fn generate(services: &[ServiceClient]) -> String {
+    let mut out = String::from(PRELUDE);
+    for service in services {
+        let mut types = Types::new(service);
+        for component in service.schemas.keys() {
+            types.named(&format!("{}{component}", super::SCHEMAS_REF_PREFIX));
+        }
+        out.push('\n');
+        out.push_str(&types.definitions);
+        write_client(&mut out, service);
+    }
+    out
+}
+
+/// Renders the JSON schemas of a service as TypeScript type aliases.
+struct Types<'a> {
+    service: &'a ServiceClient,
+    emitted: HashSet<String>,
+    definitions: String,
+}
+
+impl<'a> Types<'a> {
+    fn new(service: &'a ServiceClient) -> Self {
+        Self {
+            service,
+            emitted: HashSet::new(),
+            definitions: String::new(),
+        }
+    }
+
+    /// Emits the type alias for the referenced schema, if not done already, returning its name.
+    fn named(&mut self, reference: &str) -> String {
+        let Some((name, schema)) = self.service.resolve_ref(reference) else {
+            return "unknown".to_owned();
+        };
+        if self.emitted.insert(name.clone()) {
+            let ty = self.ty(schema);
+            write_doc(
+                &mut self.definitions,
+                "",
+                schema.get("description").and_then(Value::as_str),
+            );
+            let _ = writeln!(self.definitions, "export type {name} = {ty};\n");
+        }
+        name
+    }
+
+    fn ty(&mut self, schema: &Value) -> String {
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            Value::Bool(false) => return "never".to_owned(),
+            _ => return "unknown".to_owned(),
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return self.named(reference);
+        }
+        if let Some(value) = schema.get("const") {
+            return value.to_string();
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            return union(values.iter().map(Value::to_string));
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(schemas)) = schema.get(key) {
+                let variants: Vec<_> = schemas.iter().map(|s| self.ty(s)).collect();
+                return union(variants.into_iter());
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            return schemas
+                .iter()
+                .map(|s| format!("({})", self.ty(s)))
+                .collect::<Vec<_>>()
+                .join(" & ");
+        }
+
+        match schema.get("type") {
+            Some(Value::String(ty)) => self.typed(ty, schema),
+            Some(Value::Array(types)) => {
+                let variants: Vec<_> = types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|ty| self.typed(ty, schema))
+                    .collect();
+                union(variants.into_iter())
+            }
+            _ if schema.contains_key("properties") => self.object(schema),
+            _ => "unknown".to_owned(),
+        }
+    }
+
+    fn typed(&mut self, ty: &str, schema: &Map<String, Value>) -> String {
+        match ty {
+            "string" => "string".to_owned(),
+            "number" | "integer" => "number".to_owned(),
+            "boolean" => "boolean".to_owned(),
+            "null" => "null".to_owned(),
+            "array" => {
+                let items = schema
+                    .get("items")
+                    .map(|items| self.ty(items))
+                    .unwrap_or_else(|| "unknown".to_owned());
+                format!("Array<{items}>")
+            }
+            "object" => self.object(schema),
+            _ => "unknown".to_owned(),
+        }
+    }
+
+    fn object(&mut self, schema: &Map<String, Value>) -> String {
+        let required: HashSet<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+
+        match schema.get("properties").and_then(Value::as_object) {
+            Some(properties) if !properties.is_empty() => {
+                let fields: Vec<_> = properties
+                    .iter()
+                    .map(|(name, property)| {
+                        let optional = if required.contains(name.as_str()) {
+                            ""
+                        } else {
+                            "?"
+                        };
+                        format!("{}{optional}: {}", property_name(name), self.ty(property))
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            _ => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => "Record<string, never>".to_owned(),
+                Some(values @ Value::Object(_)) => format!("Record<string, {}>", self.ty(values)),
+                _ => "Record<string, unknown>".to_owned(),
+            },
+        }
+    }
+}
+
+fn union(variants: impl Iterator<Item = String>) -> String {
+    let mut seen = HashSet::new();
+    let variants: Vec<_> = variants.filter(|v| seen.insert(v.clone())).collect();
+    if variants.is_empty() {
+        "never".to_owned()
+    } else {
+        variants.join(" | ")
+    }
+}
+
+fn property_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_owned()
+    } else {
+        Value::from(name).to_string()
+    }
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    let Some(doc) = doc.map(str::trim).filter(|doc| !doc.is_empty()) else {
+        return;
+    };
+    let _ = writeln!(out, "{indent}/**");
+    for line in doc.lines() {
+        let _ = writeln!(out, "{indent} * {}", line.replace("*/", "*\\/").trim_end());
+    }
+    let _ = writeln!(out, "{indent} */");
+}
+
+fn input_type(service: &ServiceClient, payload: &Payload) -> String {
+    match &payload.kind {
+        PayloadKind::Json(Some(component)) => service.type_name(component),
+        PayloadKind::Json(None) => "unknown".to_owned(),
+        PayloadKind::Raw => "Uint8Array".to_owned(),
+    }
+}
+
+fn output(service: &ServiceClient, handler: &HandlerClient) -> (String, &'static str) {
+    match handler.output.as_ref() {
+        Some(
+            payload @ Payload {
+                kind: PayloadKind::Json(_),
+                ..
+            },
+        ) => (input_type(service, payload), "json"),
+        Some(payload) => (input_type(service, payload), "bytes"),
+        None => ("void".to_owned(), "none"),
+    }
+}
+
+fn write_client(out: &mut String, service: &ServiceClient) {
+    let svc = Value::from(service.name.as_str()).to_string();
+    let (call_options, send_options) = if service.is_workflow() {
+        ("RequestOptions", "Omit<SendOptions, \"idempotencyKey\">")
+    } else {
+        ("CallOptions", "SendOptions")
+    };
+
+    write_doc(out, "", service.documentation.as_deref());
+    let _ = writeln!(
+        out,
+        "export class {}Client {{\n  constructor(private readonly options: ClientOptions) {{}}",
+        pascal_case(&service.name)
+    );
+
+    for handler in &service.handlers {
+        let method = camel_case(&handler.name);
+        let pascal = pascal_case(&handler.name);
+        let handler_name = &handler.name;
+        let name = Value::from(handler.name.as_str()).to_string();
+        let (output_type, output_kind) = output(service, handler);
+
+        let mut params = vec![];
+        let mut segments = vec![svc.clone()];
+        if service.is_keyed() {
+            params.push("key: string".to_owned());
+            segments.push("key".to_owned());
+        }
+        segments.push(name.clone());
+        let body = match &handler.input {
+            Some(payload) => {
+                let optional = if handler.input_required { "" } else { "?" };
+                params.push(format!("input{optional}: {}", input_type(service, payload)));
+                let content_type = Value::from(payload.content_type.as_str());
+                match payload.kind {
+                    PayloadKind::Json(_) => format!("json({content_type}, input)"),
+                    PayloadKind::Raw => format!("bytes({content_type}, input)"),
+                }
+            }
+            None => "undefined".to_owned(),
+        };
+        let segments = segments.join(", ");
+        let params = params.join(", ");
+        let separator = if params.is_empty() { "" } else { ", " };
+
+        let _ = writeln!(out);
+        write_doc(out, "  ", handler.documentation.as_deref());
+        let _ = writeln!(
+            out,
+            "  async {method}({params}{separator}opts?: {call_options}): Promise<{output_type}> {{\n    \
+             return invoke<{output_type}>(this.options, \"POST\", route([{segments}]), {body}, opts, \"{output_kind}\");\n  }}"
+        );
+
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "  /** Sends a request to `{handler_name}`, without waiting for the response. */"
+        );
+        let _ = writeln!(
+            out,
+            "  async send{pascal}({params}{separator}opts?: {send_options}): Promise<SendResponse> {{\n    \
+             return invoke<SendResponse>(this.options, \"POST\", route([{segments}, \"send\"], {{ delay: opts?.delay }}), {body}, opts, \"json\");\n  }}"
+        );
+
+        if handler.attachable {
+            let (params, segments) = if service.is_workflow() {
+                (
+                    "key: string".to_owned(),
+                    format!("\"restate\", \"workflow\", {svc}, key"),
+                )
+            } else if service.is_keyed() {
+                (
+                    "key: string, idempotencyKey: string".to_owned(),
+                    format!("\"restate\", \"invocation\", {svc}, key, {name}, idempotencyKey"),
+                )
+            } else {
+                (
+                    "idempotencyKey: string".to_owned(),
+                    format!("\"restate\", \"invocation\", {svc}, {name}, idempotencyKey"),
+                )
+            };
+
+            let _ = writeln!(out);
+            let _ = writeln!(
+                out,
+                "  /** Waits for the invocation of `{handler_name}` to complete and returns its output. */"
+            );
+            let _ = writeln!(
+                out,
+                "  async attach{pascal}({params}): Promise<{output_type}> {{\n    \
+                 return invoke<{output_type}>(this.options, \"GET\", route([{segments}, \"attach\"]), undefined, undefined, \"{output_kind}\");\n  }}"
+            );
+            let _ = writeln!(out);
+            let _ = writeln!(
+                out,
+                "  /** Returns the output of the invocation of `{handler_name}`, failing if it didn't complete yet. */"
+            );
+            let _ = writeln!(
+                out,
+                "  async get{pascal}Output({params}): Promise<{output_type}> {{\n    \
+                 return invoke<{output_type}>(this.options, \"GET\", route([{segments}, \"output\"]), undefined, undefined, \"{output_kind}\");\n  }}"
+            );
+        }
+    }
+    let _ = writeln!(out, "}}");
+}
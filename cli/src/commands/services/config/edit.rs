@@ -95,6 +95,11 @@ fn write_out_edit_toml(w: &mut impl io::Write, service_type: ServiceType) -> Res
     writeln!(w, "# abort_timeout = \"10min\"")?;
     writeln!(w)?;
 
+    write_prefixed_lines(w, "# ", super::view::ENFORCE_INPUT_SCHEMA_DESCRIPTION)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# enforce_input_schema = true")?;
+    writeln!(w)?;
+
     Ok(())
 }
 
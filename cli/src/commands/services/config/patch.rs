@@ -56,6 +56,9 @@ pub struct Patch {
     #[clap(long, alias = "abort_retention", help = ABORT_TIMEOUT_EDIT_DESCRIPTION)]
     abort_timeout: Option<String>,
 
+    #[clap(long, alias = "enforce_input_schema", help = super::view::ENFORCE_INPUT_SCHEMA_DESCRIPTION)]
+    enforce_input_schema: Option<bool>,
+
     /// Service name
     service: String,
 }
@@ -93,6 +96,7 @@ async fn patch(env: &CliEnv, opts: &Patch) -> Result<()> {
             .as_ref()
             .map(|s| DurationString::parse_duration(s).context("Cannot parse abort_timeout"))
             .transpose()?,
+        enforce_input_schema: opts.enforce_input_schema,
     };
 
     apply_service_configuration_patch(opts.service.clone(), admin_client, modify_request).await
@@ -109,6 +113,7 @@ pub(super) async fn apply_service_configuration_patch(
         && modify_request.idempotency_retention.is_none()
         && modify_request.inactivity_timeout.is_none()
         && modify_request.abort_timeout.is_none()
+        && modify_request.enforce_input_schema.is_none()
     {
         c_println!("No changes requested");
         return Ok(());
@@ -140,6 +145,9 @@ pub(super) async fn apply_service_configuration_patch(
     if let Some(abort_timeout) = &modify_request.abort_timeout {
         table.add_kv_row("Abort timeout:", humantime::Duration::from(*abort_timeout));
     }
+    if let Some(enforce_input_schema) = &modify_request.enforce_input_schema {
+        table.add_kv_row("Enforce input schema:", enforce_input_schema);
+    }
     c_println!("{table}");
     confirm_or_exit("Are you sure you want to apply these changes?")?;
 
@@ -55,6 +55,11 @@ pub(super) const ABORT_TIMEOUT: &str = indoc! {
 
     This overrides the default abort timeout set in invoker options."
 };
+pub(super) const ENFORCE_INPUT_SCHEMA_DESCRIPTION: &str = indoc! {
+    "Whether the ingress validates the request body against the input JSON schema of the handler.
+    Requests not matching the schema are rejected with 400 Bad Request.
+    Handlers can override this with the 'restate.enforce-input-schema' metadata."
+};
 
 #[derive(Run, Parser, Collect, Clone)]
 #[cling(run = "run_view")]
@@ -129,5 +134,11 @@ async fn view(env: &CliEnv, opts: &View) -> Result<()> {
     c_tip!("{}", ABORT_TIMEOUT);
     c_println!();
 
+    let mut table = Table::new_styled();
+    table.add_kv_row("Enforce input schema:", service.enforce_input_schema);
+    c_println!("{table}");
+    c_tip!("{}", ENFORCE_INPUT_SCHEMA_DESCRIPTION);
+    c_println!();
+
     Ok(())
 }
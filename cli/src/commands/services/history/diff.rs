@@ -0,0 +1,49 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+
+use restate_cli_util::c_println;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::ui::schema_diff::render_service_diffs;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_diff")]
+pub struct Diff {
+    /// Schema version to compare to. Defaults to the latest change.
+    #[clap(long)]
+    to: Option<u32>,
+    /// Schema version to compare from
+    from: u32,
+}
+
+pub async fn run_diff(State(env): State<CliEnv>, opts: &Diff) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    let diff = client
+        .diff_schema_versions(opts.from, opts.to)
+        .await?
+        .into_body()
+        .await?;
+
+    if diff.services.is_empty() {
+        c_println!(
+            "No service changed between v{} and v{}.",
+            diff.from,
+            diff.to
+        );
+        return Ok(());
+    }
+
+    render_service_diffs(&diff.services);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use comfy_table::Table;
+
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::{c_println, c_tip};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_list")]
+pub struct List {
+    /// Maximum number of changes to show
+    #[clap(long, short, default_value_t = 20)]
+    limit: usize,
+}
+
+pub async fn run_list(State(env): State<CliEnv>, opts: &List) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    let history = client.get_schema_changes().await?.into_body().await?;
+
+    if history.changes.is_empty() {
+        c_println!("No schema change was recorded yet.");
+        return Ok(());
+    }
+
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec!["VERSION", "TIME", "AUTHOR", "DESCRIPTION", "SERVICES"]);
+    for change in history.changes.iter().take(opts.limit) {
+        table.add_row(vec![
+            change.version.to_string(),
+            change.timestamp.to_string(),
+            change.author.clone().unwrap_or_default(),
+            change.description.clone(),
+            change.services.join("\n"),
+        ]);
+    }
+    c_println!("{}", table);
+
+    c_tip!(
+        "Changes can be compared with 'restate services history diff <FROM>', the oldest comparable version is {}.",
+        history.oldest_version
+    );
+    Ok(())
+}
@@ -0,0 +1,26 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod diff;
+mod list;
+mod rollback;
+
+use cling::prelude::*;
+
+#[derive(Run, Subcommand, Clone)]
+pub enum History {
+    /// List the recent changes of the registered services
+    #[clap(visible_alias = "ls")]
+    List(list::List),
+    /// Show how the services changed between two schema versions
+    Diff(diff::Diff),
+    /// Restore the configuration of a service as it was at a previous schema version
+    Rollback(rollback::Rollback),
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+
+use restate_admin_rest_model::schema_history::RollbackServiceRequest;
+use restate_cli_util::c_success;
+use restate_cli_util::ui::console::confirm_or_exit;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_rollback")]
+pub struct Rollback {
+    /// Service name
+    service: String,
+    /// Schema version to restore the service configuration from, as shown by
+    /// 'restate services history list'
+    version: u32,
+}
+
+pub async fn run_rollback(State(env): State<CliEnv>, opts: &Rollback) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+
+    confirm_or_exit(&format!(
+        "Are you sure you want to restore the configuration of '{}' as it was at v{}? Handlers and deployments are not changed.",
+        opts.service, opts.version
+    ))?;
+
+    client
+        .rollback_service(
+            &opts.service,
+            RollbackServiceRequest {
+                version: opts.version,
+            },
+        )
+        .await?
+        .into_body()
+        .await?;
+
+    c_success!(
+        "Configuration of '{}' restored to v{}",
+        opts.service,
+        opts.version
+    );
+    Ok(())
+}
@@ -8,9 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod codegen;
 mod config;
 mod describe;
+mod history;
 mod list;
+mod remove;
 mod status;
 
 use cling::prelude::*;
@@ -22,10 +25,17 @@ pub enum Services {
     List(list::List),
     /// Prints detailed information about a given service
     Describe(describe::Describe),
+    /// Remove a service, optionally purging its stored data
+    Remove(remove::Remove),
     /// Prints activity information about a given service (and method)
     Status(status::Status),
     /// Configure a service
     #[clap(name = "config", alias = "conf")]
     #[clap(subcommand)]
     Config(config::Config),
+    /// Inspect the history of service changes, and roll back service configurations
+    #[clap(subcommand)]
+    History(history::History),
+    /// Generate typed ingress clients for the registered services
+    Codegen(codegen::Codegen),
 }
@@ -0,0 +1,199 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+use comfy_table::Table;
+use indicatif::ProgressBar;
+use reqwest::StatusCode;
+
+use restate_cli_util::ui::console::{confirm_or_exit, Styled, StyledTable};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_println, c_success, c_tip, c_warn};
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::{
+    count_service_active_inv, count_service_stored_data, ServiceStoredData,
+};
+use crate::clients::{AdminClient, AdminClientInterface, DataFusionHttpClient};
+use crate::ui::deployments::render_active_invocations;
+use crate::ui::service_handlers::icon_for_service_type;
+
+/// How long to wait for the partitions to apply the purge before giving up on reporting progress.
+const PURGE_PROGRESS_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Run, Parser, Collect, Clone)]
+#[clap(visible_alias = "rm", alias = "delete")]
+#[cling(run = "run_remove")]
+pub struct Remove {
+    /// Kill the in-flight invocations of the service. Without this flag, a service
+    /// with in-flight invocations cannot be removed.
+    #[clap(long)]
+    force: bool,
+
+    /// Purge the stored data of the service from all the partitions: its state,
+    /// inbox, promises and completed invocations. Can be used on a service that
+    /// was already removed.
+    #[clap(long)]
+    purge: bool,
+
+    /// Service name
+    service: String,
+}
+
+pub async fn run_remove(State(env): State<CliEnv>, opts: &Remove) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    let sql_client = DataFusionHttpClient::from(client.clone());
+
+    let mut table = Table::new_styled();
+    let service = client.get_service(&opts.service).await?;
+    if service.status_code() == StatusCode::NOT_FOUND && opts.purge {
+        c_println!(
+            "Service {} is not registered, only its stored data will be purged.",
+            Styled(Style::Info, &opts.service)
+        );
+    } else {
+        let service = service.into_body().await?;
+        table.add_kv_row("Name:", &service.name);
+        table.add_kv_row(
+            "Service type:",
+            format!("{:?} {}", service.ty, icon_for_service_type(&service.ty)),
+        );
+        table.add_kv_row("Revision:", service.revision);
+        table.add_kv_row("Deployment ID:", service.deployment_id);
+    }
+
+    let active_inv = count_service_active_inv(&sql_client, &opts.service).await?;
+    table.add_kv_row("Invocations:", render_active_invocations(active_inv));
+    c_println!("{}", table);
+    let stored_data = if opts.purge {
+        let stored_data = count_service_stored_data(&sql_client, &opts.service).await?;
+        c_println!();
+        c_println!("{}", Styled(Style::Info, "Stored data to purge:"));
+        c_println!("{}", stored_data_table(&stored_data));
+        stored_data
+    } else {
+        ServiceStoredData::default()
+    };
+    c_println!();
+
+    if active_inv > 0 {
+        if !opts.force {
+            bail!(
+                "The service has {} in-flight invocations. Wait for them to complete, or use {} \
+                to kill them and remove the service.",
+                active_inv,
+                Styled(Style::Notice, "--force"),
+            );
+        }
+        c_warn!(
+            "The {} in-flight invocations of the service will be killed.",
+            Styled(Style::Warn, active_inv)
+        );
+    }
+
+    confirm_or_exit("Are you sure you want to remove this service?")?;
+
+    let response = client
+        .delete_service(&opts.service, opts.force, opts.purge)
+        .await?
+        .into_body()
+        .await?;
+
+    c_println!();
+    if !response.killed_invocations.is_empty() {
+        c_println!(
+            "Killed {} in-flight invocations",
+            response.killed_invocations.len()
+        );
+    }
+    if response.purged_partitions == 0 {
+        c_success!("Service {} removed successfully", &opts.service);
+        return Ok(());
+    }
+
+    let remaining = wait_for_purge(
+        &sql_client,
+        &opts.service,
+        stored_data.total(),
+        response.purge_commands,
+    )
+    .await?;
+    if remaining.total() == 0 {
+        c_success!(
+            "Service {} removed and its stored data purged successfully",
+            &opts.service
+        );
+    } else {
+        c_warn!(
+            "Service {} removed, but the purge of its stored data didn't complete within {:?}. \
+            The partitions keep applying it in the background.",
+            &opts.service,
+            PURGE_PROGRESS_TIMEOUT
+        );
+        c_println!("{}", stored_data_table(&remaining));
+        c_tip!(
+            "Run {} later to purge the data of the invocations that completed in the meantime.",
+            Styled(
+                Style::Notice,
+                format!("restate services remove --purge {}", opts.service)
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// Polls the stored data of the service until the purge commands were applied by all the
+/// partitions, i.e. until no purgeable data is left.
+async fn wait_for_purge(
+    sql_client: &DataFusionHttpClient,
+    service: &str,
+    stored_data: i64,
+    purge_commands: u32,
+) -> Result<ServiceStoredData> {
+    let progress = ProgressBar::new(u64::try_from(stored_data).unwrap_or_default());
+    progress.set_style(
+        indicatif::ProgressStyle::with_template(
+            "{spinner} [{elapsed}] {wide_bar} {pos}/{len} entries purged, {msg}",
+        )
+        .unwrap(),
+    );
+    progress.enable_steady_tick(Duration::from_millis(120));
+    progress.set_message(format!("{purge_commands} purge commands scheduled"));
+
+    let started = Instant::now();
+    let remaining = loop {
+        let remaining = count_service_stored_data(sql_client, service).await?;
+        let remaining_entries = u64::try_from(remaining.total()).unwrap_or_default();
+        // Data of invocations completing or killed in the meantime gets purged too
+        if remaining_entries > progress.length().unwrap_or_default() {
+            progress.set_length(remaining_entries);
+        }
+        progress.set_position(progress.length().unwrap_or_default() - remaining_entries);
+        if remaining_entries == 0 || started.elapsed() >= PURGE_PROGRESS_TIMEOUT {
+            break remaining;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    };
+    progress.finish_and_clear();
+
+    Ok(remaining)
+}
+
+fn stored_data_table(stored_data: &ServiceStoredData) -> Table {
+    let mut table = Table::new_styled();
+    table.add_kv_row("State entries:", stored_data.state_entries);
+    table.add_kv_row("Promises:", stored_data.promises);
+    table.add_kv_row("Completed invocations:", stored_data.completed_invocations);
+    table
+}
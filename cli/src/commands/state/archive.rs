@@ -0,0 +1,281 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Portable file formats of the exported state of a service.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use arrow::array::{
+    AsArray, LargeBinaryArray, LargeBinaryBuilder, LargeStringArray, LargeStringBuilder,
+};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::util::{as_json, compute_version, from_json};
+
+#[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum Format {
+    /// One JSON object per line, holding all the state entries of a key.
+    /// Values are base64 encoded.
+    #[default]
+    Jsonl,
+    /// Arrow IPC file, with one row per state entry like the `state` table.
+    Arrow,
+}
+
+impl Format {
+    /// Infers the format from the file extension, defaulting to JSONL.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("arrow" | "ipc") => Format::Arrow,
+            _ => Format::Jsonl,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Jsonl => "jsonl",
+            Format::Arrow => "arrow",
+        }
+    }
+}
+
+/// The state of a virtual object/workflow key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedObject {
+    pub service: String,
+    pub key: String,
+    /// Version of the state at the time of the export
+    pub version: String,
+    /// The state entries, with base64 encoded values
+    pub state: Value,
+}
+
+impl ExportedObject {
+    pub fn new(service: String, key: String, state: HashMap<String, Bytes>) -> Result<Self> {
+        Ok(Self {
+            service,
+            key,
+            version: compute_version(&state),
+            state: as_json(state, true)?,
+        })
+    }
+
+    pub fn user_state(&self) -> Result<HashMap<String, Bytes>> {
+        if !self.state.is_object() {
+            bail!(
+                "The state of key '{}' of service '{}' must be a JSON object",
+                self.key,
+                self.service
+            );
+        }
+        from_json(self.state.clone(), true)
+    }
+}
+
+/// Groups the rows of the `state` table into objects. The rows of a key must be contiguous.
+pub fn objects_from_batches(batches: &[RecordBatch]) -> Result<Vec<ExportedObject>> {
+    let mut objects = Vec::new();
+    let mut current: Option<(String, String, HashMap<String, Bytes>)> = None;
+
+    for batch in batches {
+        let service_names = string_column(batch, "service_name")?;
+        let service_keys = string_column(batch, "service_key")?;
+        let keys = string_column(batch, "key")?;
+        let values = binary_column(batch, "value")?;
+
+        for row in 0..batch.num_rows() {
+            let (service_name, service_key) = (service_names.value(row), service_keys.value(row));
+            if !matches!(&current, Some((name, key, _)) if name == service_name && key == service_key)
+            {
+                if let Some((name, key, state)) = current.take() {
+                    objects.push(ExportedObject::new(name, key, state)?);
+                }
+                current = Some((
+                    service_name.to_owned(),
+                    service_key.to_owned(),
+                    HashMap::new(),
+                ));
+            }
+            let (_, _, state) = current.as_mut().expect("current object is set");
+            state.insert(
+                keys.value(row).to_owned(),
+                Bytes::copy_from_slice(values.value(row)),
+            );
+        }
+    }
+    if let Some((name, key, state)) = current {
+        objects.push(ExportedObject::new(name, key, state)?);
+    }
+
+    Ok(objects)
+}
+
+pub fn write_objects(path: &Path, format: Format, objects: &[ExportedObject]) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+
+    match format {
+        Format::Jsonl => {
+            let mut writer = BufWriter::new(file);
+            for object in objects {
+                serde_json::to_writer(&mut writer, object)?;
+                writer.write_all(b"\n")?;
+            }
+            writer
+                .into_inner()
+                .context("Failed to write to file")?
+                .sync_all()
+                .context("unable to flush the file to disk")?;
+        }
+        Format::Arrow => {
+            let schema = Arc::new(Schema::new(vec![
+                Field::new("service_name", DataType::LargeUtf8, false),
+                Field::new("service_key", DataType::LargeUtf8, false),
+                Field::new("key", DataType::LargeUtf8, false),
+                Field::new("value", DataType::LargeBinary, false),
+            ]));
+            let mut service_names = LargeStringBuilder::new();
+            let mut service_keys = LargeStringBuilder::new();
+            let mut keys = LargeStringBuilder::new();
+            let mut values = LargeBinaryBuilder::new();
+            for object in objects {
+                // Sorted to keep the exported file stable
+                for (key, value) in object.user_state()?.into_iter().collect::<BTreeMap<_, _>>() {
+                    service_names.append_value(&object.service);
+                    service_keys.append_value(&object.key);
+                    keys.append_value(key);
+                    values.append_value(value);
+                }
+            }
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(service_names.finish()),
+                    Arc::new(service_keys.finish()),
+                    Arc::new(keys.finish()),
+                    Arc::new(values.finish()),
+                ],
+            )?;
+
+            let mut writer = FileWriter::try_new(file, &schema)?;
+            writer.write(&batch)?;
+            writer.finish()?;
+            writer
+                .into_inner()?
+                .sync_all()
+                .context("unable to flush the file to disk")?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn read_objects(path: &Path, format: Format) -> Result<Vec<ExportedObject>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+    match format {
+        Format::Jsonl => {
+            let mut objects = Vec::new();
+            for (line_number, line) in BufReader::new(file).lines().enumerate() {
+                let line = line.context("Failed to read from file")?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                objects.push(
+                    serde_json::from_str(&line)
+                        .with_context(|| format!("Invalid object at line {}", line_number + 1))?,
+                );
+            }
+            Ok(objects)
+        }
+        Format::Arrow => {
+            let batches = FileReader::try_new(file, None)
+                .context("Invalid Arrow IPC file")?
+                .collect::<Result<Vec<_>, _>>()?;
+            objects_from_batches(&batches)
+        }
+    }
+}
+
+/// Path of the file recording how many objects of `path` were imported.
+pub fn progress_path(path: &Path) -> PathBuf {
+    let mut progress_path = path.as_os_str().to_owned();
+    progress_path.push(".progress");
+    PathBuf::from(progress_path)
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a LargeStringArray> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_string_opt::<i64>())
+        .with_context(|| format!("Expected a string column '{name}'"))
+}
+
+fn binary_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a LargeBinaryArray> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_binary_opt::<i64>())
+        .with_context(|| format!("Expected a binary column '{name}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn objects() -> Vec<ExportedObject> {
+        vec![
+            ExportedObject::new(
+                "Counter".to_owned(),
+                "a".to_owned(),
+                HashMap::from([
+                    ("count".to_owned(), Bytes::from_static(b"1")),
+                    ("raw".to_owned(), Bytes::from_static(&[0, 159, 146, 150])),
+                ]),
+            )
+            .unwrap(),
+            ExportedObject::new(
+                "Counter".to_owned(),
+                "b".to_owned(),
+                HashMap::from([("count".to_owned(), Bytes::from_static(b"2"))]),
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        for format in [Format::Jsonl, Format::Arrow] {
+            let path = dir.path().join(format!("state.{}", format.extension()));
+            assert_eq!(Format::from_path(&path), format);
+
+            write_objects(&path, format, &objects()).unwrap();
+            let read = read_objects(&path, format).unwrap();
+
+            assert_eq!(read, objects());
+            assert_eq!(
+                read[0].user_state().unwrap(),
+                objects()[0].user_state().unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+use itertools::Itertools;
+
+use restate_cli_util::{c_success, c_warn};
+use restate_types::invocation::ServiceType;
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::get_state_keys;
+use crate::clients::{AdminClient, AdminClientInterface, DataFusionHttpClient, MetasClientError};
+use crate::commands::state::archive::{write_objects, ExportedObject, Format};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_export")]
+pub struct Export {
+    /// Service name
+    #[clap(long)]
+    service: String,
+
+    /// The file to write, defaults to `<service>.jsonl` or `<service>.arrow`
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+
+    /// File format, inferred from the output file extension if not set
+    #[clap(long, value_enum)]
+    format: Option<Format>,
+}
+
+pub async fn run_export(State(env): State<CliEnv>, opts: &Export) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    match client.get_service(&opts.service).await?.into_body().await {
+        Ok(service_meta) => match service_meta.ty {
+            ServiceType::VirtualObject | ServiceType::Workflow => {}
+            ServiceType::Service => bail!("Only virtual objects and workflows support state"),
+        },
+        Err(MetasClientError::Api(err)) if err.http_status_code == 404 => {
+            c_warn!("This service does not exist in the registry; it may have been deleted, or never existed")
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    let format = opts
+        .format
+        .or_else(|| opts.output.as_deref().map(Format::from_path))
+        .unwrap_or_default();
+    let output = opts
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.{}", opts.service, format.extension())));
+
+    let sql_client = DataFusionHttpClient::from(client);
+    #[allow(clippy::mutable_key_type)]
+    let services_state = get_state_keys(&sql_client, &opts.service, None).await?;
+    let objects: Vec<_> = services_state
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.key.cmp(&b.key))
+        .map(|(service_id, state)| {
+            ExportedObject::new(
+                service_id.service_name.to_string(),
+                service_id.key.to_string(),
+                state,
+            )
+        })
+        .try_collect()?;
+
+    write_objects(&output, format, &objects)?;
+
+    c_success!(
+        "Exported the state of {} keys of service {} to {}",
+        objects.len(),
+        opts.service,
+        output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,360 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use cling::prelude::*;
+use comfy_table::Table;
+use indicatif::ProgressBar;
+use itertools::Itertools;
+
+use restate_admin_rest_model::services::{
+    ModifyServiceStateBatchRequest, ModifyServiceStateBatchResponse, ModifyServiceStateRequest,
+};
+use restate_cli_util::ui::console::{confirm_or_exit, Styled, StyledTable};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_error, c_println, c_success, c_tip};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::commands::state::archive::{progress_path, read_objects, ExportedObject, Format};
+use crate::commands::state::util::compute_version;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_import")]
+pub struct Import {
+    /// The file to import, as written by `restate state export`
+    file: PathBuf,
+
+    /// File format, inferred from the file extension if not set
+    #[clap(long, value_enum)]
+    format: Option<Format>,
+
+    /// Import the state into this service, instead of the exported one
+    #[clap(long)]
+    service: Option<String>,
+
+    /// Replace the state of keys which already have state. By default, only the keys
+    /// without state are imported, the others are left untouched.
+    #[clap(long)]
+    overwrite: bool,
+
+    /// Number of keys to send per request
+    #[clap(long, default_value_t = 100, value_parser = clap::value_parser!(u16).range(1..=1000))]
+    batch_size: u16,
+
+    /// Maximum number of keys to import per second
+    #[clap(long)]
+    rate_limit: Option<u32>,
+
+    /// Continue a previously interrupted import, skipping the keys it already imported
+    #[clap(long)]
+    resume: bool,
+}
+
+pub async fn run_import(State(env): State<CliEnv>, opts: &Import) -> Result<()> {
+    let format = opts.format.unwrap_or_else(|| Format::from_path(&opts.file));
+    let mut objects = read_objects(&opts.file, format)?;
+    if let Some(service) = &opts.service {
+        for object in &mut objects {
+            object.service.clone_from(service);
+        }
+    }
+
+    let progress_path = progress_path(&opts.file);
+    let already_imported = if opts.resume {
+        read_progress(&progress_path)?
+    } else {
+        0
+    };
+    let Some(remaining) = objects.get(already_imported..).filter(|r| !r.is_empty()) else {
+        c_success!("All the {} keys were already imported", objects.len());
+        return Ok(());
+    };
+
+    let mut table = Table::new_styled();
+    table.add_kv_row("File:", opts.file.display());
+    table.add_kv_row(
+        "Services:",
+        remaining
+            .iter()
+            .map(|object| &object.service)
+            .unique()
+            .join(", "),
+    );
+    table.add_kv_row("Keys:", remaining.len());
+    if already_imported > 0 {
+        table.add_kv_row("Already imported keys:", already_imported);
+    }
+    table.add_kv_row(
+        "Existing state:",
+        if opts.overwrite {
+            Styled(Style::Warn, "Overwritten")
+        } else {
+            Styled(Style::Info, "Left untouched")
+        },
+    );
+    c_println!("{}", table);
+    c_println!();
+
+    confirm_or_exit("Are you sure you want to import the state?")?;
+
+    let client = AdminClient::new(&env).await?;
+
+    // Unless overwriting, the state is only applied to the keys whose current state is empty
+    let expected_version = (!opts.overwrite).then(|| compute_version(&HashMap::new()));
+
+    let progress = ProgressBar::new(remaining.len() as u64);
+    progress.set_style(
+        indicatif::ProgressStyle::with_template("{spinner} [{elapsed}] {wide_bar} {pos}/{len}")
+            .unwrap(),
+    );
+    progress.enable_steady_tick(Duration::from_millis(120));
+
+    let started = Instant::now();
+    let (progress_ref, client_ref) = (&progress, &client);
+    let expected_version = expected_version.as_ref();
+    let (imported, result) = import_batches(
+        remaining,
+        usize::from(opts.batch_size),
+        &progress_path,
+        already_imported,
+        |batch| async move {
+            let (imported, result) = import_batch(client_ref, batch, expected_version).await;
+            progress_ref.inc(imported as u64);
+
+            if let Some(rate_limit) = opts.rate_limit {
+                let target = Duration::from_secs_f64(
+                    progress_ref.position() as f64 / f64::from(rate_limit.max(1)),
+                );
+                if let Some(wait) = target.checked_sub(started.elapsed()) {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+            (imported, result)
+        },
+    )
+    .await;
+    if let Err(err) = result {
+        progress.finish_and_clear();
+        c_error!(
+            "Failed importing the state after {} keys: {}",
+            already_imported + imported,
+            err
+        );
+        c_tip!(
+            "Use {} to continue the import from where it stopped.",
+            Styled(Style::Notice, "--resume")
+        );
+        return Err(err);
+    }
+    progress.finish_and_clear();
+
+    // The import completed, nothing to resume anymore
+    let _ = std::fs::remove_file(&progress_path);
+
+    c_success!("Imported the state of {} keys", imported);
+    if !opts.overwrite {
+        c_tip!(
+            "Keys which already had state were left untouched, use {} to replace their state.",
+            Styled(Style::Notice, "--overwrite")
+        );
+    }
+    Ok(())
+}
+
+/// Imports the objects in batches of keys of the same service. After every batch, the number of
+/// keys imported so far, `already_imported` included, is recorded in the progress file, so an
+/// interrupted import can be resumed from the first key that was not imported. Returns the number
+/// of keys imported by this run, and the error which interrupted it.
+async fn import_batches<'a, F, Fut>(
+    objects: &'a [ExportedObject],
+    batch_size: usize,
+    progress_path: &Path,
+    already_imported: usize,
+    mut import_batch: F,
+) -> (usize, Result<()>)
+where
+    F: FnMut(&'a [ExportedObject]) -> Fut,
+    Fut: Future<Output = (usize, Result<()>)>,
+{
+    let mut imported = 0;
+    for batch in objects
+        .chunk_by(|a, b| a.service == b.service)
+        .flat_map(|objects| objects.chunks(batch_size))
+    {
+        let (batch_imported, result) = import_batch(batch).await;
+        imported += batch_imported;
+        if let Err(err) = write_progress(progress_path, already_imported + imported) {
+            return (imported, Err(err));
+        }
+        if result.is_err() {
+            return (imported, result);
+        }
+    }
+    (imported, Ok(()))
+}
+
+/// Imports a batch of keys of the same service, returning how many of them were imported before
+/// failing, if it failed.
+async fn import_batch(
+    client: &AdminClient,
+    batch: &[ExportedObject],
+    expected_version: Option<&String>,
+) -> (usize, Result<()>) {
+    match send_batch(client, batch, expected_version).await {
+        Ok(ModifyServiceStateBatchResponse {
+            applied,
+            error: None,
+        }) => (applied, Ok(())),
+        Ok(ModifyServiceStateBatchResponse {
+            applied,
+            error: Some(error),
+        }) => (applied, Err(anyhow!(error))),
+        Err(err) => (0, Err(err)),
+    }
+}
+
+async fn send_batch(
+    client: &AdminClient,
+    batch: &[ExportedObject],
+    expected_version: Option<&String>,
+) -> Result<ModifyServiceStateBatchResponse> {
+    let request = ModifyServiceStateBatchRequest {
+        objects: batch
+            .iter()
+            .map(|object| {
+                Ok::<_, anyhow::Error>(ModifyServiceStateRequest {
+                    version: expected_version.cloned(),
+                    object_key: object.key.clone(),
+                    new_state: object.user_state()?,
+                })
+            })
+            .try_collect()?,
+    };
+
+    Ok(client
+        .patch_state_batch(&batch[0].service, request)
+        .await?
+        .into_body()
+        .await?)
+}
+
+fn read_progress(path: &Path) -> Result<usize> {
+    match std::fs::read_to_string(path) {
+        Ok(progress) => progress
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid progress file {}", path.display())),
+        // Nothing was imported yet
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(err) => {
+            Err(err).with_context(|| format!("Failed to read progress file {}", path.display()))
+        }
+    }
+}
+
+fn write_progress(path: &Path, imported: usize) -> Result<()> {
+    std::fs::write(path, imported.to_string())
+        .with_context(|| format!("Failed to write progress file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    fn objects() -> Vec<ExportedObject> {
+        [
+            ("Counter", "a"),
+            ("Counter", "b"),
+            ("Counter", "c"),
+            ("Other", "d"),
+        ]
+        .into_iter()
+        .map(|(service, key)| {
+            ExportedObject::new(service.to_owned(), key.to_owned(), HashMap::new()).unwrap()
+        })
+        .collect()
+    }
+
+    fn keys(objects: &[ExportedObject]) -> Vec<&str> {
+        objects.iter().map(|object| object.key.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn resume_interrupted_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let progress_path = progress_path(&dir.path().join("state.jsonl"));
+        let objects = objects();
+        let sent = Mutex::new(vec![]);
+
+        // The second batch fails without importing any key
+        let (imported, result) = import_batches(&objects, 2, &progress_path, 0, |batch| {
+            let sent = &sent;
+            async move {
+                sent.lock().unwrap().push(keys(batch));
+                if batch[0].key == "c" {
+                    (0, Err(anyhow!("unavailable")))
+                } else {
+                    (batch.len(), Ok(()))
+                }
+            }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(imported, 2);
+        assert_eq!(*sent.lock().unwrap(), vec![vec!["a", "b"], vec!["c"]]);
+        assert_eq!(read_progress(&progress_path).unwrap(), 2);
+
+        // Batches are split by service, the resumed import continues from the first key that
+        // was not imported
+        sent.lock().unwrap().clear();
+        let already_imported = read_progress(&progress_path).unwrap();
+        let (imported, result) = import_batches(
+            &objects[already_imported..],
+            2,
+            &progress_path,
+            already_imported,
+            |batch| {
+                let sent = &sent;
+                async move {
+                    sent.lock().unwrap().push(keys(batch));
+                    (batch.len(), Ok(()))
+                }
+            },
+        )
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(imported, 2);
+        assert_eq!(*sent.lock().unwrap(), vec![vec!["c"], vec!["d"]]);
+        assert_eq!(read_progress(&progress_path).unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn record_partially_imported_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let progress_path = progress_path(&dir.path().join("state.jsonl"));
+        let objects = objects();
+
+        let (imported, result) = import_batches(&objects, 3, &progress_path, 5, |_| async {
+            (1, Err(anyhow!("partially applied")))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(imported, 1);
+        assert_eq!(read_progress(&progress_path).unwrap(), 6);
+    }
+}
@@ -8,9 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod archive;
 mod clear;
 mod edit;
+mod export;
 mod get;
+mod import;
 mod util;
 
 use cling::prelude::*;
@@ -23,4 +26,8 @@ pub enum ServiceState {
     Edit(edit::Edit),
     /// Clear of the state of a given service
     Clear(clear::Clear),
+    /// Export the persisted state of all the keys of a service to a file
+    Export(export::Export),
+    /// Import the persisted state of service keys from an exported file
+    Import(import::Import),
 }
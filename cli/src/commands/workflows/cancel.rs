@@ -0,0 +1,83 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+
+use restate_cli_util::ui::console::{confirm_or_exit, Styled};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_println, c_success};
+
+use crate::cli_env::CliEnv;
+use crate::clients::datafusion_helpers::InvocationState;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::ui::invocations::invocation_status;
+
+use super::util::run_status;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_cancel")]
+pub struct Cancel {
+    /// The name of the workflow
+    workflow: String,
+    /// The key of the workflow run
+    key: String,
+    /// Ungracefully kill the workflow run and its children
+    #[clap(long)]
+    kill: bool,
+}
+
+pub async fn run_cancel(State(env): State<CliEnv>, opts: &Cancel) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    let Some(run) = client
+        .describe_workflow_run(&opts.workflow, &opts.key)
+        .await?
+        .into_body()
+        .await?
+        .run
+    else {
+        bail!("Workflow run {}/{} not found!", opts.workflow, opts.key);
+    };
+    let status = run_status(&run);
+    if status == InvocationState::Completed {
+        bail!(
+            "Workflow run {}/{} is already completed",
+            opts.workflow,
+            opts.key
+        );
+    }
+
+    c_println!(
+        "Workflow run {}/{} ({}) is {}",
+        opts.workflow,
+        opts.key,
+        run.invocation_id,
+        invocation_status(status)
+    );
+    let prompt = format!(
+        "Are you sure you want to {} this workflow run?",
+        if opts.kill {
+            Styled(Style::Danger, "kill")
+        } else {
+            Styled(Style::Warn, "cancel")
+        },
+    );
+    confirm_or_exit(&prompt)?;
+
+    let _ = client
+        .cancel_workflow(&opts.workflow, &opts.key, opts.kill)
+        .await?
+        .success_or_error()?;
+
+    c_println!();
+    c_success!("Request was sent successfully");
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use comfy_table::{Attribute, Cell, Table};
+
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::ui::watcher::Watch;
+use restate_cli_util::{c_indent_table, c_println, c_tip, c_title};
+
+use restate_admin_rest_model::workflows::DescribeWorkflowRunResponse;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::ui::invocations::invocation_status;
+
+use super::util::{run_created_at, run_result, run_status, workflow_run_handler};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_describe")]
+#[clap(visible_alias = "get")]
+pub struct Describe {
+    /// The name of the workflow
+    workflow: String,
+    /// The key of the workflow run
+    key: String,
+
+    #[clap(flatten)]
+    watch: Watch,
+}
+
+pub async fn run_describe(State(env): State<CliEnv>, opts: &Describe) -> Result<()> {
+    opts.watch.run(|| describe(&env, opts)).await
+}
+
+async fn describe(env: &CliEnv, opts: &Describe) -> Result<()> {
+    let client = AdminClient::new(env).await?;
+    let handler = workflow_run_handler(&client, &opts.workflow).await?;
+    let DescribeWorkflowRunResponse { run, promises } = client
+        .describe_workflow_run(&opts.workflow, &opts.key)
+        .await?
+        .into_body()
+        .await?;
+
+    c_title!("📜", "Workflow Run");
+    if let Some(run) = &run {
+        let mut table = Table::new_styled();
+        table.add_kv_row("Invocation ID:", &run.invocation_id);
+        table.add_kv_row(
+            "Target:",
+            format!("{}/{}/{}", opts.workflow, opts.key, handler),
+        );
+        table.add_kv_row("Status:", invocation_status(run_status(run)));
+        table.add_kv_row("Created at:", run_created_at(run));
+        table.add_kv_row_if(
+            || run.completion_result.is_some(),
+            "Result:",
+            || run_result(run),
+        );
+        c_println!("{}", table);
+    } else {
+        c_println!("The workflow handler was not invoked for this key");
+    }
+    c_println!();
+
+    c_title!("🤝", "Promises");
+    if promises.is_empty() {
+        c_println!("No promises");
+    } else {
+        let mut promises_table = Table::new_styled();
+        promises_table.set_styled_header(vec!["KEY", "COMPLETED", "VALUE"]);
+        for promise in promises {
+            let value = match (promise.value, promise.failure) {
+                (Some(value), _) => value,
+                (_, Some(failure)) => format!("failure: {failure}"),
+                _ => String::new(),
+            };
+            promises_table.add_row(vec![
+                Cell::new(promise.name).add_attribute(Attribute::Bold),
+                Cell::new(promise.completed),
+                Cell::new(value),
+            ]);
+        }
+        c_indent_table!(0, promises_table);
+    }
+    c_println!();
+
+    if let Some(run) = run {
+        c_tip!(
+            "Use 'restate invocations describe {}' to inspect the journal of this run.",
+            run.invocation_id
+        );
+    }
+
+    Ok(())
+}
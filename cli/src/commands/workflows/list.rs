@@ -0,0 +1,72 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use comfy_table::{Attribute, Cell, Table};
+
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::ui::watcher::Watch;
+use restate_cli_util::{c_indent_table, c_println};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::ui::invocations::invocation_status;
+
+use super::util::{run_created_at, run_result, run_status};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[clap(visible_alias = "ls")]
+#[cling(run = "run_list")]
+pub struct List {
+    /// The name of the workflow
+    workflow: String,
+    /// Limit the number of results
+    #[clap(long, default_value = "100")]
+    limit: usize,
+
+    #[clap(flatten)]
+    watch: Watch,
+}
+
+pub async fn run_list(State(env): State<CliEnv>, opts: &List) -> Result<()> {
+    opts.watch.run(|| list(&env, opts)).await
+}
+
+async fn list(env: &CliEnv, opts: &List) -> Result<()> {
+    let client = AdminClient::new(env).await?;
+    let runs = client
+        .list_workflow_runs(&opts.workflow, opts.limit)
+        .await?
+        .into_body()
+        .await?
+        .runs;
+    if runs.is_empty() {
+        c_println!("No runs found for workflow {}", opts.workflow);
+        return Ok(());
+    }
+
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec!["KEY", "ID", "STATUS", "CREATED AT", "RESULT"]);
+    for run in &runs {
+        table.add_row(vec![
+            Cell::new(&run.key).add_attribute(Attribute::Bold),
+            Cell::new(&run.invocation_id),
+            Cell::new(invocation_status(run_status(run))),
+            Cell::new(run_created_at(run)),
+            Cell::new(run_result(run)),
+        ]);
+    }
+    c_indent_table!(0, table);
+    c_println!();
+    c_println!("Showing {} workflow runs", runs.len());
+
+    Ok(())
+}
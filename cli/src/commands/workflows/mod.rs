@@ -0,0 +1,29 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod cancel;
+mod describe;
+mod list;
+mod resolve_promise;
+mod util;
+
+use cling::prelude::*;
+
+#[derive(Run, Subcommand, Clone)]
+pub enum Workflows {
+    /// List the runs of a workflow
+    List(list::List),
+    /// Prints detailed information about a workflow run and its promises
+    Describe(describe::Describe),
+    /// Resolve or reject a durable promise of a workflow run
+    ResolvePromise(resolve_promise::ResolvePromise),
+    /// Cancel a workflow run
+    Cancel(cancel::Cancel),
+}
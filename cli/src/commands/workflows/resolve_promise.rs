@@ -0,0 +1,89 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::{bail, Result};
+use cling::prelude::*;
+use serde_json::Value;
+
+use restate_admin_rest_model::workflows::{CompletePromiseRequest, PromiseFailure};
+use restate_cli_util::ui::console::{confirm_or_exit, Styled};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_println, c_success};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::commands::invoke::read_payload;
+
+use super::util::workflow_run_handler;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_resolve_promise")]
+pub struct ResolvePromise {
+    /// The name of the workflow
+    workflow: String,
+    /// The key of the workflow run
+    key: String,
+    /// The name of the promise
+    promise: String,
+    /// JSON value to resolve the promise with. Use `@path` to read it from a file, or `-` to read it from stdin.
+    /// Defaults to `null`.
+    #[clap(conflicts_with = "reject")]
+    value: Option<String>,
+    /// Reject the promise with the given failure message instead of resolving it
+    #[clap(long)]
+    reject: Option<String>,
+    /// Error code of the failure, used together with --reject
+    #[clap(long, requires = "reject")]
+    code: Option<u16>,
+}
+
+pub async fn run_resolve_promise(State(env): State<CliEnv>, opts: &ResolvePromise) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    workflow_run_handler(&client, &opts.workflow).await?;
+
+    let (request, action) = match &opts.reject {
+        Some(message) => {
+            if message.is_empty() {
+                bail!("The failure message cannot be empty");
+            }
+            (
+                CompletePromiseRequest::Failure(PromiseFailure {
+                    code: opts.code,
+                    message: message.clone(),
+                }),
+                Styled(Style::Danger, "reject"),
+            )
+        }
+        None => (
+            CompletePromiseRequest::Value(
+                read_payload(opts.value.as_deref())?.unwrap_or(Value::Null),
+            ),
+            Styled(Style::Success, "resolve"),
+        ),
+    };
+
+    let prompt = format!(
+        "Are you sure you want to {} the promise '{}' of the workflow run {}/{}?",
+        action, opts.promise, opts.workflow, opts.key
+    );
+    confirm_or_exit(&prompt)?;
+
+    let _ = client
+        .complete_workflow_promise(&opts.workflow, &opts.key, &opts.promise, request)
+        .await?
+        .success_or_error()?;
+
+    c_println!();
+    c_success!(
+        "Request was sent successfully. Promises that are already completed are left untouched."
+    );
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Local};
+
+use restate_admin_rest_model::workflows::WorkflowRunResponse;
+use restate_types::invocation::ServiceType;
+use restate_types::schema::service::HandlerMetadataType;
+
+use crate::clients::datafusion_helpers::InvocationState;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+/// Checks the given service is a workflow, and returns the name of its run handler.
+pub(super) async fn workflow_run_handler(client: &AdminClient, workflow: &str) -> Result<String> {
+    let service = client.get_service(workflow).await?.into_body().await?;
+    if service.ty != ServiceType::Workflow {
+        bail!("'{workflow}' is a {}, not a workflow", service.ty);
+    }
+
+    service
+        .handlers
+        .into_iter()
+        .find(|h| h.ty == Some(HandlerMetadataType::Workflow))
+        .map(|h| h.name)
+        .ok_or_else(|| anyhow!("Workflow '{workflow}' has no workflow handler"))
+}
+
+pub(super) fn run_status(run: &WorkflowRunResponse) -> InvocationState {
+    InvocationState::from_str(&run.status).unwrap_or_default()
+}
+
+pub(super) fn run_created_at(run: &WorkflowRunResponse) -> DateTime<Local> {
+    DateTime::from(*run.created_at)
+}
+
+pub(super) fn run_result(run: &WorkflowRunResponse) -> String {
+    match (run.completion_result.as_deref(), &run.completion_failure) {
+        (Some("success"), _) => "success".to_owned(),
+        (Some("failure"), Some(failure)) => format!("failure: {failure}"),
+        (Some("failure"), None) => "failure".to_owned(),
+        _ => String::new(),
+    }
+}
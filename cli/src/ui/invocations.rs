@@ -22,7 +22,7 @@ use restate_cli_util::ui::duration_to_human_precise;
 
 use crate::clients::datafusion_helpers::{Invocation, InvocationState};
 use crate::clients::datafusion_helpers::{InvocationCompletion, JournalEntryType};
-use crate::clients::datafusion_helpers::{JournalEntry, SimpleInvocation};
+use crate::clients::datafusion_helpers::{InvocationEvent, JournalEntry, SimpleInvocation};
 
 pub fn invocation_status_note(invocation: &Invocation) -> String {
     let mut msg = String::new();
@@ -302,6 +302,51 @@ pub fn format_journal_entry(entry: &JournalEntry) -> String {
     )
 }
 
+pub fn format_invocation_event(event: &InvocationEvent) -> String {
+    let event_type_style = match event.event_type.as_str() {
+        "transient_error" => DStyle::new().yellow(),
+        "failed" => DStyle::new().red().bold(),
+        "completed" => DStyle::new().green(),
+        _ => DStyle::new().bold(),
+    };
+
+    let mut details = vec![];
+    if let Some(execution_time) = &event.execution_time {
+        details.push(format!("until {execution_time}"));
+    }
+    if let Some(attempt) = event.attempt {
+        details.push(format!("attempt {attempt}"));
+    }
+    if let Some(deployment_id) = &event.deployment_id {
+        details.push(format!("deployment {}", style(deployment_id).cyan()));
+    }
+    if let Some(error_code) = event.error_code {
+        details.push(format!(
+            "[{}] {}",
+            error_code,
+            event.error_message.as_deref().unwrap_or_default()
+        ));
+    }
+    if let Some(related_entry_index) = event.related_entry_index {
+        details.push(format!(
+            "at entry #{}{}",
+            related_entry_index,
+            event
+                .related_entry_name
+                .as_ref()
+                .map(|name| format!(" [{name}]"))
+                .unwrap_or_default()
+        ));
+    }
+
+    format!(
+        "{} {} {}",
+        style(event.recorded_at.to_rfc3339()).dim(),
+        event_type_style.apply_to(&event.event_type),
+        details.join(", ")
+    )
+}
+
 pub fn format_entry_type_details(entry_type: &JournalEntryType) -> String {
     match entry_type {
         JournalEntryType::Sleep {
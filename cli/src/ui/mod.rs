@@ -10,4 +10,5 @@
 
 pub mod deployments;
 pub mod invocations;
+pub mod schema_diff;
 pub mod service_handlers;
@@ -0,0 +1,52 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use comfy_table::Table;
+
+use restate_admin_rest_model::schema_history::{FieldDiff, ServiceChangeKind, ServiceDiff};
+use restate_cli_util::ui::console::{Styled, StyledTable};
+use restate_cli_util::ui::stylesheet::Style;
+use restate_cli_util::{c_indent_table, c_println, c_title};
+
+/// Prints the added and removed services, and a table of the changed fields of the modified
+/// ones.
+pub fn render_service_diffs(services: &[ServiceDiff]) {
+    for service in services {
+        match service.change {
+            ServiceChangeKind::Added => {
+                c_println!("{} {}", Styled(Style::Success, "+"), service.name)
+            }
+            ServiceChangeKind::Removed => {
+                c_println!("{} {}", Styled(Style::Danger, "-"), service.name)
+            }
+            ServiceChangeKind::Modified => {
+                c_title!("~", "{}", service.name);
+                let mut table = Table::new_styled();
+                table.set_styled_header(vec!["FIELD", "FROM", "TO"]);
+                for FieldDiff { field, from, to } in &service.fields {
+                    table.add_row(vec![
+                        field.clone(),
+                        render_value(from.as_ref()),
+                        render_value(to.as_ref()),
+                    ]);
+                }
+                c_indent_table!(0, table);
+            }
+        }
+    }
+}
+
+fn render_value(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None => "<none>".to_owned(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(value) => serde_json::to_string_pretty(value).unwrap_or_default(),
+    }
+}
@@ -12,7 +12,7 @@ use http::Uri;
 use http::Version;
 use restate_serde_util::SerdeableHeaderHashMap;
 use restate_types::identifiers::ServiceRevision;
-use restate_types::identifiers::{DeploymentId, LambdaARN};
+use restate_types::identifiers::{DeploymentId, InvocationId, LambdaARN};
 use restate_types::schema::deployment::DeploymentType;
 use restate_types::schema::deployment::{DeploymentMetadata, ProtocolType};
 use restate_types::schema::service::ServiceMetadata;
@@ -383,3 +383,44 @@ pub enum UpdateDeploymentRequest {
         dry_run: bool,
     },
 }
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DrainDeploymentRequest {
+    /// # Target deployment
+    ///
+    /// Deployment the in-flight invocations are re-pinned to.
+    /// If not provided, the deployment serving the latest revision of the services
+    /// of the drained deployment is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<DeploymentId>,
+
+    /// # Invocations
+    ///
+    /// Invocations to migrate. If not provided, all the in-flight invocations
+    /// pinned to the drained deployment are migrated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<Vec<String>>"))]
+    pub invocations: Option<Vec<InvocationId>>,
+
+    /// # Dry-run mode
+    ///
+    /// If `true`, the affected invocations are checked and returned, but not migrated.
+    #[serde(default = "restate_serde_util::default::bool::<false>")]
+    pub dry_run: bool,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DrainDeploymentResponse {
+    /// # Target deployment
+    ///
+    /// Deployment the invocations are re-pinned to.
+    pub to: DeploymentId,
+
+    /// # Invocations
+    ///
+    /// Invocations re-pinned to the target deployment, or that would be re-pinned in dry-run mode.
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<String>"))]
+    pub invocations: Vec<InvocationId>,
+}
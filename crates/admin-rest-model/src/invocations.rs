@@ -0,0 +1,47 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_types::identifiers::InvocationId;
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartInvocationRequest {
+    /// # As new
+    ///
+    /// Start a new invocation with the same target, input and headers of the restarted invocation.
+    /// The restarted invocation is left untouched, and can be cancelled or killed separately.
+    /// Workflow runs cannot be restarted as new invocations.
+    AsNew,
+    /// # In place
+    ///
+    /// Remove the journal entries of the invocation starting from the given index, and execute it
+    /// again. The input entry at index 0 cannot be removed. The restart is refused while any of
+    /// the removed entries, other than sleeps, is still awaiting its completion. Completions and
+    /// signals received for the kept entries are preserved.
+    InPlace {
+        /// # From index
+        ///
+        /// Index of the first journal entry to remove.
+        from_index: u32,
+    },
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartInvocationResponse {
+    /// # Invocation ID
+    ///
+    /// Identifier of the restarted invocation. For restarts as new invocation, this is the
+    /// identifier of the new invocation.
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub invocation_id: InvocationId,
+}
@@ -11,6 +11,10 @@
 pub mod converters;
 pub mod deployments;
 pub mod handlers;
+pub mod invocations;
+pub mod manifest;
+pub mod schema_history;
 pub mod services;
 pub mod subscriptions;
 pub mod version;
+pub mod workflows;
@@ -0,0 +1,156 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+
+use http::Uri;
+use restate_serde_util::SerdeableHeaderHashMap;
+use restate_types::identifiers::{DeploymentId, SubscriptionId};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::schema_history::ServiceDiff;
+use crate::services::ModifyServiceRequest;
+use crate::subscriptions::CreateSubscriptionRequest;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApplyManifestRequest {
+    /// # Deployments
+    ///
+    /// Deployments to register. Deployments already registered with the same endpoint and the
+    /// same services are left untouched, otherwise they're overridden if `force` is set.
+    #[serde(default)]
+    pub deployments: Vec<ManifestDeployment>,
+
+    /// # Services
+    ///
+    /// Configuration overrides by service name. Only the provided options are changed.
+    #[serde(default)]
+    pub services: BTreeMap<String, ModifyServiceRequest>,
+
+    /// # Subscriptions
+    ///
+    /// Subscriptions to create. Subscriptions already existing with the same source and sink
+    /// are left untouched, unless their options differ. Subscriptions not listed here are not
+    /// removed.
+    #[serde(default)]
+    pub subscriptions: Vec<CreateSubscriptionRequest>,
+
+    /// # Force
+    ///
+    /// If `true`, deployments already registered with the same endpoint but with different
+    /// services or headers are overridden. Beware that this can lead in-flight invocations to an
+    /// unrecoverable error state. If `false`, applying the manifest fails instead.
+    #[serde(default)]
+    pub force: bool,
+
+    /// # Dry-run mode
+    ///
+    /// If `true`, the plan is computed but not applied.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[serde_as]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ManifestDeployment {
+    Http {
+        /// # Uri
+        ///
+        /// Uri to use to discover/invoke the http deployment.
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        #[cfg_attr(feature = "schema", schemars(with = "String"))]
+        uri: Uri,
+
+        /// # Additional headers
+        ///
+        /// Additional headers added to the discover/invoke requests to the deployment.
+        additional_headers: Option<SerdeableHeaderHashMap>,
+
+        /// # Use http1.1
+        ///
+        /// If `true`, discovery will be attempted using a client that defaults to HTTP1.1
+        /// instead of a prior-knowledge HTTP2 client.
+        #[serde(default = "restate_serde_util::default::bool::<false>")]
+        use_http_11: bool,
+    },
+    Lambda {
+        /// # ARN
+        ///
+        /// ARN to use to discover/invoke the lambda deployment.
+        arn: String,
+
+        /// # Assume role ARN
+        ///
+        /// Optional ARN of a role to assume when invoking the addressed Lambda, to support role chaining
+        assume_role_arn: Option<String>,
+
+        /// # Additional headers
+        ///
+        /// Additional headers added to the discover/invoke requests to the deployment.
+        additional_headers: Option<SerdeableHeaderHashMap>,
+    },
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ManifestChange {
+    Added,
+    Updated,
+    Unchanged,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestDeploymentPlan {
+    pub id: DeploymentId,
+    /// # Address
+    ///
+    /// Uri or ARN of the deployment.
+    pub address: String,
+    pub change: ManifestChange,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestSubscriptionPlan {
+    pub id: SubscriptionId,
+    pub source: String,
+    pub sink: String,
+    pub change: ManifestChange,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplyManifestResponse {
+    /// # Applied
+    ///
+    /// `false` if the request was a dry run, or if there was nothing to change.
+    pub applied: bool,
+
+    /// # Schema version
+    ///
+    /// Version of the schema after applying the manifest.
+    pub schema_version: u32,
+
+    pub deployments: Vec<ManifestDeploymentPlan>,
+
+    /// # Services
+    ///
+    /// Changes of the registered services, as a result of the new deployments and the
+    /// configuration overrides.
+    pub services: Vec<ServiceDiff>,
+
+    pub subscriptions: Vec<ManifestSubscriptionPlan>,
+}
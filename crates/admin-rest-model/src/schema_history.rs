@@ -0,0 +1,132 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+use restate_types::schema::history::SchemaChange;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaChangeResponse {
+    /// # Version
+    ///
+    /// Version of the schema after the change.
+    pub version: u32,
+
+    /// # Timestamp
+    ///
+    /// When the change was applied.
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub timestamp: humantime::Timestamp,
+
+    /// # Author
+    ///
+    /// Who applied the change, as reported by the client through the `x-restate-author` header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// # Description
+    pub description: String,
+
+    /// # Services
+    ///
+    /// Services touched by the change.
+    pub services: Vec<String>,
+
+    /// # Untracked
+    ///
+    /// True if the change was not recorded when applied, and was reconstructed from the schema
+    /// observed by the following change. Author and description are unknown for these changes.
+    #[serde(default)]
+    pub untracked: bool,
+}
+
+impl From<&SchemaChange> for SchemaChangeResponse {
+    fn from(value: &SchemaChange) -> Self {
+        let mut services: Vec<_> = value.services.keys().cloned().collect();
+        services.sort();
+        Self {
+            version: value.schema_version.into(),
+            timestamp: SystemTime::from(value.timestamp).into(),
+            author: value.author.clone(),
+            description: value.description.clone(),
+            services,
+            untracked: value.untracked,
+        }
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSchemaChangesResponse {
+    /// # Oldest version
+    ///
+    /// Oldest schema version that can be used for diffs and rollbacks. Older changes are folded
+    /// once the history exceeds the `admin.schema-history-max-changes` configuration.
+    pub oldest_version: u32,
+
+    /// # Changes
+    ///
+    /// Retained schema changes, from the most recent to the oldest.
+    pub changes: Vec<SchemaChangeResponse>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldDiff {
+    /// # Field
+    ///
+    /// Name of the service field, or `handlers.<name>` for handlers.
+    pub field: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<serde_json::Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<serde_json::Value>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServiceDiff {
+    pub name: String,
+    pub change: ServiceChangeKind,
+    /// # Fields
+    ///
+    /// Changed fields. Empty for added and removed services.
+    pub fields: Vec<FieldDiff>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaDiffResponse {
+    pub from: u32,
+    pub to: u32,
+    pub services: Vec<ServiceDiff>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackServiceRequest {
+    /// # Version
+    ///
+    /// Schema version to restore the service configuration from.
+    pub version: u32,
+}
@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 
+use restate_types::identifiers::InvocationId;
 use restate_types::schema::service::ServiceMetadata;
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -93,6 +94,13 @@ pub struct ModifyServiceRequest {
     )]
     #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub abort_timeout: Option<Duration>,
+
+    /// # Enforce input schema
+    ///
+    /// If true, the ingress rejects requests whose body doesn't match the input JSON schema of
+    /// the handler. Handlers can override this with the `restate.enforce-input-schema` metadata.
+    #[serde(default)]
+    pub enforce_input_schema: Option<bool>,
 }
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -114,3 +122,52 @@ pub struct ModifyServiceStateRequest {
     /// The new state to replace the previous state with
     pub new_state: HashMap<String, Bytes>,
 }
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModifyServiceStateBatchRequest {
+    /// # Objects
+    ///
+    /// The state changes to apply, one per virtual object key.
+    pub objects: Vec<ModifyServiceStateRequest>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModifyServiceStateBatchResponse {
+    /// # Applied
+    ///
+    /// Number of state changes, from the start of the request, that were sent to the
+    /// partitions. The following ones were not applied, and `error` describes why.
+    pub applied: usize,
+
+    /// # Error
+    ///
+    /// Why the state changes after the applied ones could not be sent. Not set if all the
+    /// state changes were sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteServiceResponse {
+    /// # Killed invocations
+    ///
+    /// In-flight invocations of the service that were killed, when deleting using the force flag.
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<String>"))]
+    pub killed_invocations: Vec<InvocationId>,
+
+    /// # Purged partitions
+    ///
+    /// Number of partitions the purge of the service data was scheduled on.
+    /// Zero if the purge was not requested.
+    pub purged_partitions: u16,
+
+    /// # Purge commands
+    ///
+    /// Number of purge commands appended to the partitions, each one purging a chunk of the
+    /// keys of a partition.
+    #[serde(default)]
+    pub purge_commands: u32,
+}
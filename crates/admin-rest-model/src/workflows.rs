@@ -0,0 +1,126 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+/// Completion of a workflow promise or of an awakeable.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletePromiseRequest {
+    /// # Value
+    ///
+    /// Resolve the promise with the given JSON value.
+    Value(serde_json::Value),
+    /// # Failure
+    ///
+    /// Reject the promise with the given failure.
+    Failure(PromiseFailure),
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromiseFailure {
+    /// # Code
+    ///
+    /// Error code of the failure. Defaults to 500.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<u16>,
+
+    /// # Message
+    ///
+    /// Error message of the failure.
+    pub message: String,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListWorkflowRunsResponse {
+    /// # Runs
+    ///
+    /// Runs of the workflow, the most recently created first.
+    pub runs: Vec<WorkflowRunResponse>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunResponse {
+    /// # Key
+    ///
+    /// Key of the workflow run.
+    pub key: String,
+
+    /// # Invocation ID
+    ///
+    /// Id of the invocation of the workflow handler.
+    pub invocation_id: String,
+
+    /// # Status
+    ///
+    /// Status of the invocation, as in the `status` column of `sys_invocation`.
+    pub status: String,
+
+    /// # Created at
+    ///
+    /// When the workflow run was created.
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub created_at: humantime::Timestamp,
+
+    /// # Completion result
+    ///
+    /// `success` or `failure`, once the workflow run completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_result: Option<String>,
+
+    /// # Completion failure
+    ///
+    /// Failure of the workflow run, if it completed with a failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_failure: Option<String>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeWorkflowRunResponse {
+    /// # Run
+    ///
+    /// The workflow run. Missing if the workflow handler was not invoked for this key, while
+    /// shared handlers were.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<WorkflowRunResponse>,
+
+    /// # Promises
+    ///
+    /// Durable promises of the workflow run, ordered by name.
+    pub promises: Vec<WorkflowPromiseResponse>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowPromiseResponse {
+    /// # Name
+    pub name: String,
+
+    /// # Completed
+    pub completed: bool,
+
+    /// # Value
+    ///
+    /// Value the promise was resolved with, if it's valid UTF-8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// # Failure
+    ///
+    /// Failure the promise was rejected with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
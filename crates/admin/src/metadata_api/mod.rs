@@ -43,6 +43,7 @@ static PROTECTED_KEYS: LazyLock<HashSet<ByteString>> = LazyLock::new(|| {
         keys::BIFROST_CONFIG_KEY.clone(),
         keys::NODES_CONFIG_KEY.clone(),
         keys::SCHEMA_INFORMATION_KEY.clone(),
+        keys::SCHEMA_HISTORY_KEY.clone(),
     ]
     .into()
 });
@@ -0,0 +1,99 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::create_envelope_header;
+use super::error::*;
+use super::workflows::completion_result;
+use crate::state::AdminServiceState;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use okapi_operation::*;
+use restate_admin_rest_model::workflows::CompletePromiseRequest;
+use restate_types::identifiers::{AwakeableIdentifier, ExternalSignalIdentifier, WithPartitionKey};
+use restate_types::invocation::{InvocationResponse, NotifySignalRequest, ResponseResult};
+use restate_types::journal_v2::{Signal, SignalResult};
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+use tracing::warn;
+
+/// Complete an awakeable
+#[openapi(
+    summary = "Complete an awakeable",
+    description = "Resolve or reject the given awakeable. This has the same effect of completing \
+    the awakeable through the ingress.",
+    operation_id = "complete_awakeable",
+    tags = "invocation",
+    parameters(path(
+        name = "awakeable_id",
+        description = "Awakeable identifier.",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn complete_awakeable<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(awakeable_id): Path<String>,
+    #[request_body(required = true)] Json(request): Json<CompletePromiseRequest>,
+) -> Result<StatusCode, MetaApiError> {
+    let result = completion_result(request)?;
+
+    // Same commands appended by the ingress when completing awakeables
+    let (partition_key, cmd) =
+        if let Ok(signal_id) = ExternalSignalIdentifier::from_str(&awakeable_id) {
+            let (invocation_id, signal_id) = signal_id.into_inner();
+            let cmd = Command::NotifySignal(NotifySignalRequest {
+                invocation_id,
+                signal: Signal::new(
+                    signal_id,
+                    match result {
+                        ResponseResult::Success(s) => SignalResult::Success(s),
+                        ResponseResult::Failure(f) => SignalResult::Failure(f.into()),
+                    },
+                ),
+            });
+            (invocation_id.partition_key(), cmd)
+        } else {
+            let (invocation_id, entry_index) = AwakeableIdentifier::from_str(&awakeable_id)
+                .map_err(|e| MetaApiError::InvalidField("awakeable_id", e.to_string()))?
+                .into_inner();
+            let cmd = Command::InvocationResponse(InvocationResponse {
+                id: invocation_id,
+                entry_index,
+                result,
+            });
+            (invocation_id.partition_key(), cmd)
+        };
+
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(create_envelope_header(partition_key), cmd)),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append awakeable completion command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending awakeable completion to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
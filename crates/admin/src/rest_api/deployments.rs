@@ -9,13 +9,20 @@
 // by the Apache License, Version 2.0.
 
 use super::error::*;
+use super::query::{execute_query, query_context, string_column};
+use crate::rest_api::{change_author, create_envelope_header};
 use crate::state::AdminServiceState;
+use std::collections::HashSet;
+use std::sync::Arc;
 
-use crate::schema_registry::{ApplyMode, Force};
+use crate::schema_registry::{ApplyMode, Force, SchemaRegistry};
 use axum::extract::{Path, Query, State};
-use axum::http::{header, StatusCode};
+use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::IntoResponse;
 use axum::{Extension, Json};
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::UInt32Type;
+use datafusion::common::ScalarValue;
 use http::uri::Scheme;
 use okapi_operation::*;
 use restate_admin_rest_model::deployments::*;
@@ -23,8 +30,14 @@ use restate_admin_rest_model::version::AdminApiVersion;
 use restate_errors::warn_it;
 use restate_service_client::Endpoint;
 use restate_service_protocol::discovery::DiscoverEndpoint;
-use restate_types::identifiers::{DeploymentId, InvalidLambdaARN};
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::identifiers::{DeploymentId, InvalidLambdaARN, InvocationId, WithPartitionKey};
+use restate_types::invocation::MigrateInvocationRequest;
+use restate_types::schema::deployment::Deployment as DeploymentSchema;
+use restate_types::schema::service::ServiceMetadata;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
 use serde::Deserialize;
+use tracing::warn;
 
 /// Create deployment and return discovered services.
 #[openapi(
@@ -45,6 +58,7 @@ use serde::Deserialize;
 pub async fn create_deployment<V>(
     State(state): State<AdminServiceState<V>>,
     Extension(version): Extension<AdminApiVersion>,
+    headers: HeaderMap,
     #[request_body(required = true)] Json(payload): Json<RegisterDeploymentRequest>,
 ) -> Result<impl IntoResponse, MetaApiError> {
     let (discover_endpoint, force, dry_run) = match payload {
@@ -118,7 +132,12 @@ pub async fn create_deployment<V>(
 
     let (id, services) = state
         .schema_registry
-        .register_deployment(discover_endpoint, force, apply_mode)
+        .register_deployment(
+            discover_endpoint,
+            force,
+            apply_mode,
+            change_author(&headers),
+        )
         .await
         .inspect_err(|e| warn_it!(e))?;
 
@@ -209,7 +228,7 @@ pub struct DeleteDeploymentParams {
 /// Discover endpoint and return discovered endpoints.
 #[openapi(
     summary = "Delete deployment",
-    description = "Delete deployment. Currently it's supported to remove a deployment only using the force flag",
+    description = "Delete deployment. The deployment can be deleted without the force flag only if no in-flight invocation is pinned to it anymore, see the drain deployment operation to migrate them to another deployment.",
     operation_id = "delete_deployment",
     tags = "deployment",
     parameters(
@@ -236,7 +255,7 @@ pub struct DeleteDeploymentParams {
         ),
         response(
             status = "501",
-            description = "Not implemented. Only using the force flag is supported at the moment.",
+            description = "Not implemented. Without the storage query engine, only using the force flag is supported.",
             content = "okapi_operation::Empty",
         ),
         from_type = "MetaApiError",
@@ -246,17 +265,249 @@ pub async fn delete_deployment<V>(
     State(state): State<AdminServiceState<V>>,
     Path(deployment_id): Path<DeploymentId>,
     Query(DeleteDeploymentParams { force }): Query<DeleteDeploymentParams>,
+    headers: HeaderMap,
 ) -> Result<StatusCode, MetaApiError> {
-    if let Some(true) = force {
-        state
-            .schema_registry
-            .delete_deployment(deployment_id)
-            .await
-            .inspect_err(|e| warn_it!(e))?;
-        Ok(StatusCode::ACCEPTED)
-    } else {
-        Ok(StatusCode::NOT_IMPLEMENTED)
+    if force != Some(true) {
+        let Some(query_context) = &state.query_context else {
+            return Ok(StatusCode::NOT_IMPLEMENTED);
+        };
+        let pinned_invocations = pinned_invocations(query_context, deployment_id).await?;
+        if !pinned_invocations.is_empty() {
+            return Err(MetaApiError::DeploymentHasPinnedInvocations(
+                deployment_id,
+                pinned_invocations.len(),
+            ));
+        }
+    }
+
+    state
+        .schema_registry
+        .delete_deployment(deployment_id, change_author(&headers))
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Drain a deployment
+#[openapi(
+    summary = "Drain deployment",
+    description = "Re-pin the in-flight invocations of a deployment to another deployment, restarting them from their journal. The target deployment must expose the services and handlers of the migrated invocations, and support the service protocol version they were pinned with. Use the dry-run mode to list the affected invocations without migrating them.",
+    operation_id = "drain_deployment",
+    tags = "deployment",
+    parameters(path(
+        name = "deployment",
+        description = "Deployment identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn drain_deployment<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(deployment_id): Path<DeploymentId>,
+    #[request_body(required = true)] Json(DrainDeploymentRequest {
+        to,
+        invocations,
+        dry_run,
+    }): Json<DrainDeploymentRequest>,
+) -> Result<Json<DrainDeploymentResponse>, MetaApiError> {
+    let query_context = query_context(&state)?;
+
+    let to = match to {
+        Some(to) => to,
+        None => infer_drain_target(&state.schema_registry, deployment_id)?,
+    };
+    if to == deployment_id {
+        return Err(MetaApiError::InvalidField(
+            "to",
+            "The target deployment must be different from the drained deployment".to_owned(),
+        ));
+    }
+    let (target_deployment, target_services) = state
+        .schema_registry
+        .get_deployment(to)
+        .ok_or(MetaApiError::DeploymentNotFound(to))?;
+
+    let mut pinned_invocations = pinned_invocations(query_context, deployment_id).await?;
+    if let Some(selected_invocations) = invocations {
+        if let Some(not_pinned) = selected_invocations.iter().find(|invocation_id| {
+            !pinned_invocations
+                .iter()
+                .any(|pinned| pinned.invocation_id == **invocation_id)
+        }) {
+            return Err(MetaApiError::InvalidField(
+                "invocations",
+                format!(
+                    "The invocation {not_pinned} is not in-flight on deployment {deployment_id}"
+                ),
+            ));
+        }
+        pinned_invocations.retain(|pinned| selected_invocations.contains(&pinned.invocation_id));
+    }
+
+    for pinned_invocation in &pinned_invocations {
+        pinned_invocation
+            .check_compatibility(&target_deployment, &target_services)
+            .map_err(|reason| MetaApiError::IncompatibleDeployment {
+                from: deployment_id,
+                to,
+                reason,
+            })?;
+    }
+
+    let invocations: Vec<_> = pinned_invocations
+        .into_iter()
+        .map(|pinned| pinned.invocation_id)
+        .collect();
+
+    if !dry_run {
+        for invocation_id in &invocations {
+            let result = append_envelope_to_bifrost(
+                &state.bifrost,
+                Arc::new(Envelope::new(
+                    create_envelope_header(invocation_id.partition_key()),
+                    Command::MigrateInvocation(MigrateInvocationRequest {
+                        invocation_id: *invocation_id,
+                        from_deployment: deployment_id,
+                        to_deployment: to,
+                    }),
+                )),
+            )
+            .await;
+
+            if let Err(err) = result {
+                warn!("Could not append invocation migration command to Bifrost: {err}");
+                return Err(MetaApiError::Internal(
+                    "Failed sending invocation migration to the cluster.".to_owned(),
+                ));
+            }
+        }
     }
+
+    Ok(DrainDeploymentResponse { to, invocations }.into())
+}
+
+/// The drain target is the deployment serving the latest revision of the services exposed by
+/// the drained deployment. Fails if there's no such deployment, or if there's more than one.
+fn infer_drain_target<V>(
+    schema_registry: &SchemaRegistry<V>,
+    deployment_id: DeploymentId,
+) -> Result<DeploymentId, MetaApiError> {
+    let (_, services) = schema_registry
+        .get_deployment(deployment_id)
+        .ok_or(MetaApiError::DeploymentNotFound(deployment_id))?;
+
+    let latest_deployments: HashSet<_> = services
+        .iter()
+        .filter_map(|service| schema_registry.get_service(&service.name))
+        .map(|service| service.deployment_id)
+        .filter(|latest_deployment_id| *latest_deployment_id != deployment_id)
+        .collect();
+
+    let mut latest_deployments = latest_deployments.into_iter();
+    match (latest_deployments.next(), latest_deployments.next()) {
+        (Some(latest_deployment_id), None) => Ok(latest_deployment_id),
+        (None, _) => Err(MetaApiError::InvalidField(
+            "to",
+            format!("No newer deployment serves the services of deployment {deployment_id}, please provide the target deployment"),
+        )),
+        (Some(_), Some(_)) => Err(MetaApiError::InvalidField(
+            "to",
+            format!("The services of deployment {deployment_id} are served by different deployments, please provide the target deployment"),
+        )),
+    }
+}
+
+struct PinnedInvocation {
+    invocation_id: InvocationId,
+    service_name: String,
+    handler_name: String,
+    service_protocol_version: Option<u32>,
+}
+
+impl PinnedInvocation {
+    fn check_compatibility(
+        &self,
+        deployment: &DeploymentSchema,
+        services: &[ServiceMetadata],
+    ) -> Result<(), String> {
+        let Some(service) = services
+            .iter()
+            .find(|service| service.name == self.service_name)
+        else {
+            return Err(format!(
+                "the service '{}' of invocation {} is not exposed by the target deployment",
+                self.service_name, self.invocation_id
+            ));
+        };
+        if !service
+            .handlers
+            .iter()
+            .any(|handler| handler.name == self.handler_name)
+        {
+            return Err(format!(
+                "the handler '{}/{}' of invocation {} is not exposed by the target deployment",
+                self.service_name, self.handler_name, self.invocation_id
+            ));
+        }
+        if let Some(service_protocol_version) = self.service_protocol_version {
+            if !deployment
+                .metadata
+                .supported_protocol_versions
+                .contains(&(service_protocol_version as i32))
+            {
+                return Err(format!(
+                    "the service protocol version {} of invocation {} is not supported by the target deployment",
+                    service_protocol_version, self.invocation_id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lists the in-flight invocations pinned to the given deployment.
+async fn pinned_invocations(
+    query_context: &QueryContext,
+    deployment_id: DeploymentId,
+) -> Result<Vec<PinnedInvocation>, MetaApiError> {
+    let batches = execute_query(
+        query_context,
+        "SELECT id, target_service_name, target_handler_name, pinned_service_protocol_version \
+         FROM sys_invocation_status \
+         WHERE pinned_deployment_id = $1 AND status IN ('invoked', 'suspended')",
+        [ScalarValue::from(deployment_id.to_string())],
+    )
+    .await?;
+
+    let mut pinned_invocations = Vec::new();
+    for batch in batches {
+        let ids = string_column(&batch, "id")?;
+        let service_names = string_column(&batch, "target_service_name")?;
+        let handler_names = string_column(&batch, "target_handler_name")?;
+        let service_protocol_versions = batch
+            .column_by_name("pinned_service_protocol_version")
+            .and_then(|column| column.as_primitive_opt::<UInt32Type>())
+            .ok_or_else(|| {
+                MetaApiError::Internal(
+                    "Unexpected type or missing column 'pinned_service_protocol_version'"
+                        .to_owned(),
+                )
+            })?;
+
+        for row in 0..batch.num_rows() {
+            pinned_invocations.push(PinnedInvocation {
+                invocation_id: ids.value(row).parse().map_err(|err| {
+                    MetaApiError::Internal(format!("Unexpected invocation id: {err}"))
+                })?,
+                service_name: service_names.value(row).to_owned(),
+                handler_name: handler_names.value(row).to_owned(),
+                service_protocol_version: service_protocol_versions
+                    .is_valid(row)
+                    .then(|| service_protocol_versions.value(row)),
+            });
+        }
+    }
+
+    Ok(pinned_invocations)
 }
 
 /// Update a deployment
@@ -275,6 +526,7 @@ pub async fn update_deployment<V>(
     State(state): State<AdminServiceState<V>>,
     Extension(version): Extension<AdminApiVersion>,
     Path(deployment_id): Path<DeploymentId>,
+    headers: HeaderMap,
     #[request_body(required = true)] Json(payload): Json<UpdateDeploymentRequest>,
 ) -> Result<Json<DetailedDeploymentResponse>, MetaApiError> {
     let (discover_endpoint, dry_run) = match payload {
@@ -342,7 +594,12 @@ pub async fn update_deployment<V>(
 
     let (deployment, services) = state
         .schema_registry
-        .update_deployment(deployment_id, discover_endpoint, apply_mode)
+        .update_deployment(
+            deployment_id,
+            discover_endpoint,
+            apply_mode,
+            change_author(&headers),
+        )
         .await
         .inspect_err(|e| warn_it!(e))?;
 
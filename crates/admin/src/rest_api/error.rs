@@ -18,7 +18,7 @@ use okapi_operation::okapi::map;
 use okapi_operation::okapi::openapi3::Responses;
 use okapi_operation::{okapi, Components, ToMediaTypes, ToResponses};
 use restate_core::ShutdownError;
-use restate_types::identifiers::{DeploymentId, SubscriptionId};
+use restate_types::identifiers::{DeploymentId, InvocationId, SubscriptionId};
 use restate_types::invocation::ServiceType;
 use schemars::JsonSchema;
 use serde::Serialize;
@@ -40,12 +40,32 @@ pub enum MetaApiError {
     },
     #[error("The requested subscription '{0}' does not exist")]
     SubscriptionNotFound(SubscriptionId),
+    #[error("The requested invocation '{0}' does not exist")]
+    InvocationNotFound(InvocationId),
+    #[error("The requested workflow run '{0}/{1}' does not exist")]
+    WorkflowRunNotFound(String, String),
     #[error("Cannot {0} for service type {1}")]
     UnsupportedOperation(&'static str, ServiceType),
+    #[error("The deployment '{0}' still has {1} in-flight invocation(s) pinned to it. Drain the deployment first, or delete it using the force flag")]
+    DeploymentHasPinnedInvocations(DeploymentId, usize),
+    #[error("The service '{0}' still has {1} in-flight invocation(s). Wait for them to complete, or delete it using the force flag to kill them")]
+    ServiceHasInFlightInvocations(String, usize),
+    #[error("The invocation '{0}' has {1} journal entries awaiting a completion from the given index. Wait for them to complete, or restart from a later index")]
+    InvocationHasPendingCompletions(InvocationId, usize),
+    #[error(
+        "Cannot migrate the invocations of deployment '{from}' to deployment '{to}': {reason}"
+    )]
+    IncompatibleDeployment {
+        from: DeploymentId,
+        to: DeploymentId,
+        reason: String,
+    },
     #[error(transparent)]
     Schema(#[from] SchemaError),
     #[error(transparent)]
     Discovery(#[from] restate_service_protocol::discovery::DiscoveryError),
+    #[error("The query engine is not available on this node")]
+    QueryEngineUnavailable,
     #[error("Internal server error: {0}")]
     Internal(String),
 }
@@ -68,15 +88,23 @@ impl IntoResponse for MetaApiError {
             MetaApiError::ServiceNotFound(_)
             | MetaApiError::HandlerNotFound { .. }
             | MetaApiError::DeploymentNotFound(_)
-            | MetaApiError::SubscriptionNotFound(_) => StatusCode::NOT_FOUND,
+            | MetaApiError::SubscriptionNotFound(_)
+            | MetaApiError::InvocationNotFound(_)
+            | MetaApiError::WorkflowRunNotFound(_, _) => StatusCode::NOT_FOUND,
             MetaApiError::InvalidField(_, _) | MetaApiError::UnsupportedOperation(_, _) => {
                 StatusCode::BAD_REQUEST
             }
+            MetaApiError::DeploymentHasPinnedInvocations(_, _)
+            | MetaApiError::ServiceHasInFlightInvocations(_, _)
+            | MetaApiError::InvocationHasPendingCompletions(_, _)
+            | MetaApiError::IncompatibleDeployment { .. } => StatusCode::CONFLICT,
+            MetaApiError::QueryEngineUnavailable => StatusCode::SERVICE_UNAVAILABLE,
             MetaApiError::Schema(schema_error) => match schema_error {
                 SchemaError::NotFound(_) => StatusCode::NOT_FOUND,
                 SchemaError::Override(_)
                 | SchemaError::Service(ServiceError::DifferentType { .. })
-                | SchemaError::Service(ServiceError::RemovedHandlers { .. }) => {
+                | SchemaError::Service(ServiceError::RemovedHandlers { .. })
+                | SchemaError::Service(ServiceError::TargetedBySubscriptions(_, _)) => {
                     StatusCode::CONFLICT
                 }
                 SchemaError::Service(_) => StatusCode::BAD_REQUEST,
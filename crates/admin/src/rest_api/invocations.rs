@@ -9,15 +9,26 @@
 // by the Apache License, Version 2.0.
 
 use super::error::*;
+use super::query::{bool_column, execute_query, optional_string, query_context, string_column};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::rest_api::create_envelope_header;
 use crate::state::AdminServiceState;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::Json;
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::UInt32Type;
+use datafusion::common::ScalarValue;
 use okapi_operation::*;
+use restate_admin_rest_model::invocations::{RestartInvocationRequest, RestartInvocationResponse};
+use restate_storage_query_datafusion::context::QueryContext;
 use restate_types::identifiers::{InvocationId, WithPartitionKey};
-use restate_types::invocation::{InvocationTermination, PurgeInvocationRequest};
+use restate_types::invocation::{
+    InvocationTermination, PurgeInvocationRequest, RestartMode, ServiceType,
+};
+use restate_types::schema::service::HandlerMetadataType;
 use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
 use serde::Deserialize;
 use tracing::warn;
@@ -82,7 +93,28 @@ pub async fn delete_invocation<V>(
         .parse::<InvocationId>()
         .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
 
-    let cmd = match mode.unwrap_or_default() {
+    let cmd = deletion_command(invocation_id, mode.unwrap_or_default());
+
+    let partition_key = invocation_id.partition_key();
+
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(create_envelope_header(partition_key), cmd)),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append invocation termination command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending invocation termination to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
+
+pub(super) fn deletion_command(invocation_id: InvocationId, mode: DeletionMode) -> Command {
+    match mode {
         DeletionMode::Cancel => {
             Command::TerminateInvocation(InvocationTermination::cancel(invocation_id))
         }
@@ -90,22 +122,354 @@ pub async fn delete_invocation<V>(
             Command::TerminateInvocation(InvocationTermination::kill(invocation_id))
         }
         DeletionMode::Purge => Command::PurgeInvocation(PurgeInvocationRequest { invocation_id }),
+    }
+}
+
+/// Restart an invocation
+#[openapi(
+    summary = "Restart an invocation",
+    description = "Restart the given in-flight invocation. The invocation can either be restarted as a new \
+    invocation with the same input and headers, leaving the original invocation untouched, or in place, \
+    removing the journal entries starting from the given index and executing it again. \
+    Restarting in place is refused while any of the removed entries, other than sleeps, is still awaiting \
+    its completion, as late completions couldn't be told apart from the ones of the re-executed entries. \
+    The restart is validated through the query engine, hence it's not available on the nodes not running it.",
+    operation_id = "restart_invocation",
+    tags = "invocation",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn restart_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+    #[request_body(required = true)] Json(payload): Json<RestartInvocationRequest>,
+) -> Result<Json<RestartInvocationResponse>, MetaApiError> {
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
+
+    let (mode, restarted_invocation_id) = match payload {
+        RestartInvocationRequest::AsNew => {
+            let new_invocation_id = invocation_id.generate_restart();
+            (RestartMode::AsNew { new_invocation_id }, new_invocation_id)
+        }
+        RestartInvocationRequest::InPlace { from_index } => {
+            (RestartMode::InPlace { from_index }, invocation_id)
+        }
     };
 
-    let partition_key = invocation_id.partition_key();
+    // The partition processor ignores the restarts it can't apply, validate them upfront to
+    // report the reason to the caller. The state might still change until the command is applied.
+    let query_context = query_context(&state)?;
+    let Some(invocation) = restarted_invocation(query_context, invocation_id).await? else {
+        return Err(MetaApiError::InvocationNotFound(invocation_id));
+    };
+    if invocation.status != "invoked" && invocation.status != "suspended" {
+        return Err(MetaApiError::InvalidField(
+            "invocation_id",
+            format!(
+                "Only in-flight invocations can be restarted, but the invocation is {}",
+                invocation.status
+            ),
+        ));
+    }
+    match mode {
+        RestartMode::AsNew { .. } => {
+            if invocation.is_workflow_run(&state) {
+                // The id of the workflow run is deterministic, there can't be a new one
+                return Err(MetaApiError::UnsupportedOperation(
+                    "restart workflow runs as new",
+                    ServiceType::Workflow,
+                ));
+            }
+        }
+        RestartMode::InPlace { from_index } => {
+            if from_index == 0 || from_index > invocation.journal_size {
+                return Err(MetaApiError::InvalidField(
+                    "from_index",
+                    format!(
+                        "The index must be in the range 1..={}",
+                        invocation.journal_size
+                    ),
+                ));
+            }
+            let pending_completions =
+                pending_completions(query_context, invocation_id, from_index).await?;
+            if pending_completions > 0 {
+                return Err(MetaApiError::InvocationHasPendingCompletions(
+                    invocation_id,
+                    pending_completions,
+                ));
+            }
+        }
+    }
 
     let result = append_envelope_to_bifrost(
         &state.bifrost,
-        Arc::new(Envelope::new(create_envelope_header(partition_key), cmd)),
+        Arc::new(Envelope::new(
+            create_envelope_header(invocation_id.partition_key()),
+            Command::RestartInvocation(restate_types::invocation::RestartInvocationRequest {
+                invocation_id,
+                mode,
+            }),
+        )),
     )
     .await;
 
     if let Err(err) = result {
-        warn!("Could not append invocation termination command to Bifrost: {err}");
-        Err(MetaApiError::Internal(
-            "Failed sending invocation termination to the cluster.".to_owned(),
+        warn!("Could not append invocation restart command to Bifrost: {err}");
+        return Err(MetaApiError::Internal(
+            "Failed sending invocation restart to the cluster.".to_owned(),
+        ));
+    }
+
+    Ok(RestartInvocationResponse {
+        invocation_id: restarted_invocation_id,
+    }
+    .into())
+}
+
+/// The columns of `sys_invocation_status` needed to validate a restart.
+struct RestartedInvocation {
+    status: String,
+    journal_size: u32,
+    service_ty: String,
+    service_name: String,
+    handler_name: String,
+}
+
+impl RestartedInvocation {
+    fn is_workflow_run<V>(&self, state: &AdminServiceState<V>) -> bool {
+        self.service_ty == "workflow"
+            && state
+                .schema_registry
+                .get_service(&self.service_name)
+                .is_some_and(|service| {
+                    service.handlers.iter().any(|handler| {
+                        handler.name == self.handler_name
+                            && handler.ty == Some(HandlerMetadataType::Workflow)
+                    })
+                })
+    }
+}
+
+async fn restarted_invocation(
+    query_context: &QueryContext,
+    invocation_id: InvocationId,
+) -> Result<Option<RestartedInvocation>, MetaApiError> {
+    let batches = execute_query(
+        query_context,
+        "SELECT status, journal_size, target_service_ty, target_service_name, target_handler_name
+        FROM sys_invocation_status
+        WHERE id = $1",
+        [ScalarValue::from(invocation_id.to_string())],
+    )
+    .await?;
+
+    let Some(batch) = batches.iter().find(|batch| batch.num_rows() > 0) else {
+        return Ok(None);
+    };
+    let journal_size = batch
+        .column_by_name("journal_size")
+        .and_then(|column| column.as_primitive_opt::<UInt32Type>())
+        .ok_or_else(|| {
+            MetaApiError::Internal("Unexpected type or missing column 'journal_size'".to_owned())
+        })?;
+    Ok(Some(RestartedInvocation {
+        status: string_column(batch, "status")?.value(0).to_owned(),
+        journal_size: journal_size.value(0),
+        service_ty: string_column(batch, "target_service_ty")?
+            .value(0)
+            .to_owned(),
+        service_name: string_column(batch, "target_service_name")?
+            .value(0)
+            .to_owned(),
+        handler_name: string_column(batch, "target_handler_name")?
+            .value(0)
+            .to_owned(),
+    }))
+}
+
+/// Counts the journal entries starting from `from_index` which are still awaiting a completion,
+/// sleeps excluded, mirroring the checks of the partition processor.
+async fn pending_completions(
+    query_context: &QueryContext,
+    invocation_id: InvocationId,
+    from_index: u32,
+) -> Result<usize, MetaApiError> {
+    let batches = execute_query(
+        query_context,
+        "SELECT index, version, entry_type, completed, entry_json
+        FROM sys_journal
+        WHERE id = $1",
+        [ScalarValue::from(invocation_id.to_string())],
+    )
+    .await?;
+
+    let mut pending_completions = 0;
+    // Journal v2 stores completions as separate notification entries
+    let mut awaited_completion_ids = vec![];
+    let mut received_completion_ids = HashSet::new();
+    for batch in &batches {
+        let indexes = batch
+            .column_by_name("index")
+            .and_then(|column| column.as_primitive_opt::<UInt32Type>())
+            .ok_or_else(|| {
+                MetaApiError::Internal("Unexpected type or missing column 'index'".to_owned())
+            })?;
+        let versions = batch
+            .column_by_name("version")
+            .and_then(|column| column.as_primitive_opt::<UInt32Type>())
+            .ok_or_else(|| {
+                MetaApiError::Internal("Unexpected type or missing column 'version'".to_owned())
+            })?;
+        let entry_types = string_column(batch, "entry_type")?;
+        let completed = bool_column(batch, "completed")?;
+        let entries_json = string_column(batch, "entry_json")?;
+
+        for row in 0..batch.num_rows() {
+            let removed = indexes.value(row) >= from_index;
+            if versions.value(row) < 2 {
+                if removed
+                    && !completed.is_null(row)
+                    && !completed.value(row)
+                    && entry_types.value(row) != "Sleep"
+                {
+                    pending_completions += 1;
+                }
+                continue;
+            }
+            let Some(entry) = optional_string(entries_json, row)
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            else {
+                continue;
+            };
+            match journal_v2_completion_ids(&entry) {
+                JournalV2CompletionIds::Command(completion_ids) if removed => {
+                    awaited_completion_ids.push(completion_ids)
+                }
+                JournalV2CompletionIds::Completion(completion_id) => {
+                    received_completion_ids.insert(completion_id);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(pending_completions
+        + awaited_completion_ids
+            .into_iter()
+            .filter(|completion_ids| {
+                completion_ids
+                    .iter()
+                    .any(|completion_id| !received_completion_ids.contains(completion_id))
+            })
+            .count())
+}
+
+#[derive(Debug, PartialEq)]
+enum JournalV2CompletionIds {
+    /// Completion ids awaited by a command, sleeps and runs excluded
+    Command(Vec<u64>),
+    /// Completion id of a completion notification
+    Completion(u64),
+    Other,
+}
+
+/// Extracts the completion ids from the JSON serialization of a journal v2 entry, in which
+/// enums are externally tagged, e.g. `{"Command":{"Call":{"result_completion_id":2,..}}}`.
+fn journal_v2_completion_ids(entry: &serde_json::Value) -> JournalV2CompletionIds {
+    fn single_variant(value: &serde_json::Value) -> Option<(&str, &serde_json::Value)> {
+        let object = value.as_object()?;
+        if object.len() != 1 {
+            return None;
+        }
+        object
+            .iter()
+            .next()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    match single_variant(entry) {
+        Some(("Command", command)) => match single_variant(command) {
+            // Sleeps are completed by their timers, which are removed with them, while runs
+            // are completed by the aborted attempt
+            Some(("Sleep" | "Run", _)) | None => JournalV2CompletionIds::Other,
+            Some((_, fields)) => JournalV2CompletionIds::Command(
+                fields
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(field, _)| field.ends_with("completion_id"))
+                    .filter_map(|(_, value)| value.as_u64())
+                    .collect(),
+            ),
+        },
+        Some(("Notification", notification)) => single_variant(notification)
+            .filter(|(ty, _)| *ty == "Completion")
+            .and_then(|(_, completion)| single_variant(completion))
+            .and_then(|(_, fields)| fields.get("completion_id"))
+            .and_then(serde_json::Value::as_u64)
+            .map_or(
+                JournalV2CompletionIds::Other,
+                JournalV2CompletionIds::Completion,
+            ),
+        _ => JournalV2CompletionIds::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::journal_v2::{
+        Entry, GetPromiseCommand, GetPromiseCompletion, GetPromiseResult, Notification,
+        SleepCommand,
+    };
+
+    #[test]
+    fn completion_ids_of_journal_v2_entries() {
+        let command = serde_json::to_value(Entry::Command(
+            GetPromiseCommand {
+                key: "my-promise".into(),
+                completion_id: 3,
+                name: Default::default(),
+            }
+            .into(),
         ))
-    } else {
-        Ok(StatusCode::ACCEPTED)
+        .unwrap();
+        assert_eq!(
+            journal_v2_completion_ids(&command),
+            JournalV2CompletionIds::Command(vec![3])
+        );
+
+        let completion = serde_json::to_value(Entry::Notification(Notification::new_completion(
+            GetPromiseCompletion {
+                completion_id: 3,
+                result: GetPromiseResult::Success(Default::default()),
+            },
+        )))
+        .unwrap();
+        assert_eq!(
+            journal_v2_completion_ids(&completion),
+            JournalV2CompletionIds::Completion(3)
+        );
+
+        let sleep = serde_json::to_value(Entry::Command(
+            SleepCommand {
+                wake_up_time: 1337.into(),
+                completion_id: 4,
+                name: Default::default(),
+            }
+            .into(),
+        ))
+        .unwrap();
+        assert_eq!(
+            journal_v2_completion_ids(&sleep),
+            JournalV2CompletionIds::Other
+        );
     }
 }
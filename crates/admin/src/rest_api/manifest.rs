@@ -0,0 +1,193 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::error::*;
+use super::schema_history::diff_services;
+use super::services::modify_service_changes;
+use crate::rest_api::change_author;
+use crate::schema_registry::{
+    ApplyMode, Force, ManifestChange as RegistryManifestChange, ManifestSubscription,
+    SchemaManifest,
+};
+use crate::state::AdminServiceState;
+
+use std::collections::HashMap;
+
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::Json;
+use http::uri::Scheme;
+use okapi_operation::*;
+use restate_admin_rest_model::manifest::*;
+use restate_errors::warn_it;
+use restate_service_client::Endpoint;
+use restate_service_protocol::discovery::DiscoverEndpoint;
+use restate_types::identifiers::InvalidLambdaARN;
+use restate_types::schema::deployment::DeploymentResolver;
+use restate_types::schema::service::{ServiceMetadata, ServiceMetadataResolver};
+use restate_types::schema::subscriptions::{SubscriptionResolver, SubscriptionValidator};
+use restate_types::schema::Schema;
+use restate_types::Versioned;
+
+/// Apply a manifest
+#[openapi(
+    summary = "Apply manifest",
+    description = "Declaratively apply deployments, service configuration overrides and subscriptions. Restate discovers the deployments, computes the changes against the registered schema and applies them atomically. Entries already matching the registered schema are left untouched, and nothing is removed. Deployments conflicting with a registered one are overridden only with force.",
+    operation_id = "apply_manifest",
+    tags = "schema"
+)]
+pub async fn apply_manifest<V: SubscriptionValidator>(
+    State(state): State<AdminServiceState<V>>,
+    headers: HeaderMap,
+    #[request_body(required = true)] Json(ApplyManifestRequest {
+        deployments,
+        services,
+        subscriptions,
+        force,
+        dry_run,
+    }): Json<ApplyManifestRequest>,
+) -> Result<Json<ApplyManifestResponse>, MetaApiError> {
+    let manifest = SchemaManifest {
+        deployments: deployments
+            .into_iter()
+            .map(discover_endpoint)
+            .collect::<Result<_, _>>()?,
+        services: services
+            .into_iter()
+            .map(|(name, modify_request)| (name, modify_service_changes(modify_request)))
+            .collect(),
+        subscriptions: subscriptions
+            .into_iter()
+            .map(|subscription| ManifestSubscription {
+                source: subscription.source,
+                sink: subscription.sink,
+                options: subscription.options,
+            })
+            .collect(),
+    };
+
+    let force = if force { Force::Yes } else { Force::No };
+    let apply_mode = if dry_run {
+        ApplyMode::DryRun
+    } else {
+        ApplyMode::Apply
+    };
+
+    let plan = state
+        .schema_registry
+        .apply_manifest(manifest, force, apply_mode.clone(), change_author(&headers))
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    let schema = &plan.current;
+    Ok(ApplyManifestResponse {
+        applied: apply_mode.should_apply() && plan.has_changes(),
+        schema_version: schema.version().into(),
+        deployments: plan
+            .deployments
+            .iter()
+            .map(|(id, change)| ManifestDeploymentPlan {
+                id: *id,
+                address: schema
+                    .get_deployment(id)
+                    .map(|deployment| deployment.metadata.address_display().to_string())
+                    .unwrap_or_default(),
+                change: (*change).into(),
+            })
+            .collect(),
+        services: diff_services(
+            &services_by_name(&plan.previous),
+            &services_by_name(&plan.current),
+        ),
+        subscriptions: plan
+            .subscriptions
+            .iter()
+            .filter_map(|(id, change)| {
+                let subscription = schema.get_subscription(*id)?;
+                Some(ManifestSubscriptionPlan {
+                    id: *id,
+                    source: subscription.source().to_string(),
+                    sink: subscription.sink().to_string(),
+                    change: (*change).into(),
+                })
+            })
+            .collect(),
+    }
+    .into())
+}
+
+fn discover_endpoint(deployment: ManifestDeployment) -> Result<DiscoverEndpoint, MetaApiError> {
+    Ok(match deployment {
+        ManifestDeployment::Http {
+            uri,
+            additional_headers,
+            use_http_11,
+        } => {
+            // Verify URI is absolute!
+            if uri.scheme().is_none() || uri.authority().is_none() {
+                return Err(MetaApiError::InvalidField(
+                    "uri",
+                    format!(
+                        "The provided uri {uri} is not absolute, only absolute URIs can be used."
+                    ),
+                ));
+            }
+
+            let is_using_https = uri.scheme().unwrap() == &Scheme::HTTPS;
+
+            DiscoverEndpoint::new(
+                Endpoint::Http(
+                    uri,
+                    if use_http_11 {
+                        Some(http::Version::HTTP_11)
+                    } else if is_using_https {
+                        // ALPN will sort this out
+                        None
+                    } else {
+                        // By default, we use h2c on HTTP
+                        Some(http::Version::HTTP_2)
+                    },
+                ),
+                additional_headers.unwrap_or_default().into(),
+            )
+        }
+        ManifestDeployment::Lambda {
+            arn,
+            assume_role_arn,
+            additional_headers,
+        } => DiscoverEndpoint::new(
+            Endpoint::Lambda(
+                arn.parse().map_err(|e: InvalidLambdaARN| {
+                    MetaApiError::InvalidField("arn", e.to_string())
+                })?,
+                assume_role_arn.map(Into::into),
+            ),
+            additional_headers.unwrap_or_default().into(),
+        ),
+    })
+}
+
+fn services_by_name(schema: &Schema) -> HashMap<String, ServiceMetadata> {
+    schema
+        .list_services()
+        .into_iter()
+        .map(|service| (service.name.clone(), service))
+        .collect()
+}
+
+impl From<RegistryManifestChange> for ManifestChange {
+    fn from(value: RegistryManifestChange) -> Self {
+        match value {
+            RegistryManifestChange::Added => ManifestChange::Added,
+            RegistryManifestChange::Updated => ManifestChange::Updated,
+            RegistryManifestChange::Unchanged => ManifestChange::Unchanged,
+        }
+    }
+}
@@ -10,16 +10,22 @@
 
 //! This module implements the Meta API endpoint.
 
+mod awakeables;
 mod deployments;
 mod error;
 mod handlers;
 mod health;
 mod invocations;
+mod manifest;
+mod query;
+mod schema_history;
 mod services;
 mod subscriptions;
 mod version;
+mod workflows;
 
 use axum_integration::put;
+use http::{header, HeaderMap, HeaderName};
 use okapi_operation::axum_integration::{delete, get, patch, post};
 use okapi_operation::*;
 use restate_types::identifiers::PartitionKey;
@@ -54,6 +60,10 @@ where
             "/deployments/:deployment",
             put(openapi_handler!(deployments::update_deployment)),
         )
+        .route(
+            "/deployments/:deployment/drain",
+            post(openapi_handler!(deployments::drain_deployment)),
+        )
         .route("/services", get(openapi_handler!(services::list_services)))
         .route(
             "/services/:service",
@@ -67,10 +77,22 @@ where
             "/services/:service",
             patch(openapi_handler!(services::modify_service)),
         )
+        .route(
+            "/services/:service",
+            delete(openapi_handler!(services::delete_service)),
+        )
+        .route(
+            "/services/:service/rollback",
+            post(openapi_handler!(schema_history::rollback_service)),
+        )
         .route(
             "/services/:service/state",
             post(openapi_handler!(services::modify_service_state)),
         )
+        .route(
+            "/services/:service/state/batch",
+            post(openapi_handler!(services::modify_service_state_batch)),
+        )
         .route(
             "/services/:service/handlers",
             get(openapi_handler!(handlers::list_service_handlers)),
@@ -83,6 +105,30 @@ where
             "/invocations/:invocation_id",
             delete(openapi_handler!(invocations::delete_invocation)),
         )
+        .route(
+            "/invocations/:invocation_id/restart",
+            post(openapi_handler!(invocations::restart_invocation)),
+        )
+        .route(
+            "/workflows/:workflow",
+            get(openapi_handler!(workflows::list_workflow_runs)),
+        )
+        .route(
+            "/workflows/:workflow/:key",
+            get(openapi_handler!(workflows::describe_workflow_run)),
+        )
+        .route(
+            "/workflows/:workflow/:key",
+            delete(openapi_handler!(workflows::delete_workflow)),
+        )
+        .route(
+            "/workflows/:workflow/:key/promises/:promise",
+            post(openapi_handler!(workflows::complete_workflow_promise)),
+        )
+        .route(
+            "/awakeables/:awakeable_id",
+            post(openapi_handler!(awakeables::complete_awakeable)),
+        )
         .route(
             "/subscriptions",
             post(openapi_handler!(subscriptions::create_subscription)),
@@ -99,6 +145,18 @@ where
             "/subscriptions/:subscription",
             delete(openapi_handler!(subscriptions::delete_subscription)),
         )
+        .route(
+            "/schema/apply",
+            post(openapi_handler!(manifest::apply_manifest)),
+        )
+        .route(
+            "/schema/history",
+            get(openapi_handler!(schema_history::list_schema_changes)),
+        )
+        .route(
+            "/schema/history/diff",
+            get(openapi_handler!(schema_history::diff_schema_versions)),
+        )
         .route("/health", get(openapi_handler!(health::health)))
         .route("/version", get(openapi_handler!(version::version)))
         .finish_openapi("/openapi", "Admin API", env!("CARGO_PKG_VERSION"))
@@ -106,6 +164,18 @@ where
         .with_state(state)
 }
 
+/// Header clients can set to record who applied a schema change.
+const X_RESTATE_AUTHOR: HeaderName = HeaderName::from_static("x-restate-author");
+
+/// Author of a schema change, falling back to the user agent of the client.
+fn change_author(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(X_RESTATE_AUTHOR)
+        .or_else(|| headers.get(header::USER_AGENT))
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
 fn create_envelope_header(partition_key: PartitionKey) -> Header {
     Header {
         source: Source::ControlPlane {},
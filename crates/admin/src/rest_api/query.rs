@@ -0,0 +1,114 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Helpers for the endpoints backed by the query engine.
+
+use datafusion::arrow::array::{Array, AsArray, BooleanArray, LargeStringArray};
+use datafusion::arrow::datatypes::TimestampMillisecondType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{ParamValues, ScalarValue};
+use datafusion::error::DataFusionError;
+use futures::TryStreamExt;
+
+use restate_storage_query_datafusion::context::QueryContext;
+
+use super::error::MetaApiError;
+use crate::state::AdminServiceState;
+
+pub(super) fn query_context<V>(
+    state: &AdminServiceState<V>,
+) -> Result<&QueryContext, MetaApiError> {
+    state
+        .query_context
+        .as_ref()
+        .ok_or(MetaApiError::QueryEngineUnavailable)
+}
+
+/// Runs the given query, binding `params` to the placeholders `$1`, `$2`, ... of the query.
+/// The parameters are never spliced into the SQL text, so user provided values can't change
+/// the query.
+pub(super) async fn execute_query(
+    query_context: &QueryContext,
+    sql: &str,
+    params: impl IntoIterator<Item = ScalarValue>,
+) -> Result<Vec<RecordBatch>, MetaApiError> {
+    execute_query_inner(query_context, sql, params.into_iter().collect())
+        .await
+        .map_err(|err| MetaApiError::Internal(format!("Cannot query the cluster state: {err}")))
+}
+
+async fn execute_query_inner(
+    query_context: &QueryContext,
+    sql: &str,
+    params: Vec<ScalarValue>,
+) -> Result<Vec<RecordBatch>, DataFusionError> {
+    let plan = query_context.plan(sql).await?;
+
+    // Placeholders are bound by position, and their values must have the exact types
+    // DataFusion inferred for them.
+    let parameter_types = plan.get_parameter_types()?;
+    let params = params
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            match parameter_types
+                .get(&format!("${}", idx + 1))
+                .and_then(Option::as_ref)
+            {
+                Some(data_type) if *data_type != value.data_type() => value.cast_to(data_type),
+                _ => Ok(value),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let plan = plan.with_param_values(ParamValues::List(params))?;
+
+    query_context.execute_plan(plan).await?.try_collect().await
+}
+
+pub(super) fn string_column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<&'a LargeStringArray, MetaApiError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_string_opt::<i64>())
+        .ok_or_else(|| unexpected_column(name))
+}
+
+pub(super) fn bool_column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<&'a BooleanArray, MetaApiError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_boolean_opt())
+        .ok_or_else(|| unexpected_column(name))
+}
+
+/// Returns the milliseconds since the Unix epoch of the given timestamp column.
+pub(super) fn timestamp_millis(
+    batch: &RecordBatch,
+    name: &str,
+    row: usize,
+) -> Result<Option<u64>, MetaApiError> {
+    let column = batch
+        .column_by_name(name)
+        .and_then(|column| column.as_primitive_opt::<TimestampMillisecondType>())
+        .ok_or_else(|| unexpected_column(name))?;
+    Ok((!column.is_null(row)).then(|| u64::try_from(column.value(row)).unwrap_or_default()))
+}
+
+pub(super) fn optional_string(column: &LargeStringArray, row: usize) -> Option<String> {
+    (!column.is_null(row)).then(|| column.value(row).to_owned())
+}
+
+fn unexpected_column(name: &str) -> MetaApiError {
+    MetaApiError::Internal(format!("Unexpected type or missing column '{name}'"))
+}
@@ -0,0 +1,225 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::change_author;
+use super::error::*;
+use crate::state::AdminServiceState;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use axum::extract::{Path, Query, State};
+use axum::{Extension, Json};
+use http::HeaderMap;
+use okapi_operation::*;
+use restate_admin_rest_model::schema_history::*;
+use restate_admin_rest_model::version::AdminApiVersion;
+use restate_errors::warn_it;
+use restate_types::schema::history::SchemaHistory;
+use restate_types::schema::service::ServiceMetadata;
+use restate_types::Version;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// List schema changes
+#[openapi(
+    summary = "List schema changes",
+    description = "List the retained changes of the registered services, from the most recent to the oldest.",
+    operation_id = "list_schema_changes",
+    tags = "schema"
+)]
+pub async fn list_schema_changes<V>(
+    State(state): State<AdminServiceState<V>>,
+) -> Result<Json<ListSchemaChangesResponse>, MetaApiError> {
+    let history = state.schema_registry.get_history().await?;
+
+    Ok(ListSchemaChangesResponse {
+        oldest_version: history.oldest_version().into(),
+        changes: history.changes().rev().map(Into::into).collect(),
+    }
+    .into())
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiffSchemaVersionsParams {
+    pub from: u32,
+    pub to: Option<u32>,
+}
+
+/// Diff schema versions
+#[openapi(
+    summary = "Diff schema versions",
+    description = "Compare the registered services between two schema versions of the retained history.",
+    operation_id = "diff_schema_versions",
+    tags = "schema",
+    parameters(
+        query(
+            name = "from",
+            description = "Schema version to compare from.",
+            required = true,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u32",
+        ),
+        query(
+            name = "to",
+            description = "Schema version to compare to. Defaults to the latest change.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u32",
+        )
+    )
+)]
+pub async fn diff_schema_versions<V>(
+    State(state): State<AdminServiceState<V>>,
+    Query(DiffSchemaVersionsParams { from, to }): Query<DiffSchemaVersionsParams>,
+) -> Result<Json<SchemaDiffResponse>, MetaApiError> {
+    let history = state.schema_registry.get_history().await?;
+    let to = to.unwrap_or_else(|| {
+        history
+            .changes()
+            .next_back()
+            .map(|change| change.schema_version.into())
+            .unwrap_or(from)
+    });
+
+    let from_services = services_at(&history, "from", from)?;
+    let to_services = services_at(&history, "to", to)?;
+
+    Ok(SchemaDiffResponse {
+        from,
+        to,
+        services: diff_services(&from_services, &to_services),
+    }
+    .into())
+}
+
+/// Rollback a service configuration
+#[openapi(
+    summary = "Rollback service configuration",
+    description = "Restore the service-level configuration (public, retentions, timeouts and input schema enforcement) as it was at a previous schema version. Handlers and deployments are not changed.",
+    operation_id = "rollback_service",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn rollback_service<V>(
+    State(state): State<AdminServiceState<V>>,
+    Extension(version): Extension<AdminApiVersion>,
+    Path(service_name): Path<String>,
+    headers: HeaderMap,
+    #[request_body(required = true)] Json(RollbackServiceRequest {
+        version: schema_version,
+    }): Json<RollbackServiceRequest>,
+) -> Result<Json<ServiceMetadata>, MetaApiError> {
+    let response = state
+        .schema_registry
+        .rollback_service(
+            service_name,
+            Version::from(schema_version),
+            change_author(&headers),
+        )
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(restate_admin_rest_model::converters::convert_service_metadata(version, response).into())
+}
+
+fn services_at(
+    history: &SchemaHistory,
+    field: &'static str,
+    schema_version: u32,
+) -> Result<HashMap<String, ServiceMetadata>, MetaApiError> {
+    history
+        .services_at(Version::from(schema_version))
+        .ok_or_else(|| {
+            MetaApiError::InvalidField(
+                field,
+                format!(
+                    "schema version {schema_version} is older than the retained history, the oldest one is {}",
+                    u32::from(history.oldest_version())
+                ),
+            )
+        })
+}
+
+pub(super) fn diff_services(
+    from: &HashMap<String, ServiceMetadata>,
+    to: &HashMap<String, ServiceMetadata>,
+) -> Vec<ServiceDiff> {
+    let names: BTreeSet<_> = from.keys().chain(to.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| match (from.get(name), to.get(name)) {
+            (None, Some(_)) => Some(ServiceDiff {
+                name: name.clone(),
+                change: ServiceChangeKind::Added,
+                fields: vec![],
+            }),
+            (Some(_), None) => Some(ServiceDiff {
+                name: name.clone(),
+                change: ServiceChangeKind::Removed,
+                fields: vec![],
+            }),
+            (Some(from), Some(to)) => {
+                let fields = diff_fields(from, to);
+                (!fields.is_empty()).then(|| ServiceDiff {
+                    name: name.clone(),
+                    change: ServiceChangeKind::Modified,
+                    fields,
+                })
+            }
+            (None, None) => None,
+        })
+        .collect()
+}
+
+fn diff_fields(from: &ServiceMetadata, to: &ServiceMetadata) -> Vec<FieldDiff> {
+    let from = flatten_service(from);
+    let to = flatten_service(to);
+    let fields: BTreeSet<_> = from.keys().chain(to.keys()).collect();
+    fields
+        .into_iter()
+        .filter(|field| from.get(*field) != to.get(*field))
+        .map(|field| FieldDiff {
+            field: field.clone(),
+            from: from.get(field).cloned(),
+            to: to.get(field).cloned(),
+        })
+        .collect()
+}
+
+/// Flattens the service metadata in its top level fields, plus one `handlers.<name>` field per
+/// handler, since handlers are listed in no particular order.
+fn flatten_service(service: &ServiceMetadata) -> BTreeMap<String, Value> {
+    let Ok(Value::Object(fields)) = serde_json::to_value(service) else {
+        return BTreeMap::new();
+    };
+
+    let mut flattened = BTreeMap::new();
+    for (field, value) in fields {
+        match value {
+            Value::Array(handlers) if field == "handlers" => {
+                for handler in handlers {
+                    if let Some(name) = handler.get("name").and_then(Value::as_str) {
+                        flattened.insert(format!("handlers.{name}"), handler);
+                    }
+                }
+            }
+            value => {
+                flattened.insert(field, value);
+            }
+        }
+    }
+    flattened
+}
@@ -8,26 +8,33 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use super::create_envelope_header;
 use super::error::*;
+use super::query::{execute_query, query_context, string_column};
+use super::{change_author, create_envelope_header};
 use crate::schema_registry::ModifyServiceChange;
 use crate::state::AdminServiceState;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::Extension;
 use axum::Json;
 use bytes::Bytes;
-use http::StatusCode;
+use datafusion::common::ScalarValue;
+use http::{HeaderMap, StatusCode};
 use okapi_operation::*;
 use restate_admin_rest_model::services::ListServicesResponse;
 use restate_admin_rest_model::services::*;
 use restate_admin_rest_model::version::AdminApiVersion;
+use restate_core::Metadata;
 use restate_errors::warn_it;
-use restate_types::identifiers::{ServiceId, WithPartitionKey};
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::identifiers::{InvocationId, PartitionKey, ServiceId, WithPartitionKey};
+use restate_types::invocation::{InvocationTermination, PurgeServiceRequest};
 use restate_types::schema::service::ServiceMetadata;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+use serde::Deserialize;
 use tracing::{debug, warn};
 
 /// List services
@@ -122,14 +129,35 @@ pub async fn modify_service<V>(
     State(state): State<AdminServiceState<V>>,
     Extension(version): Extension<AdminApiVersion>,
     Path(service_name): Path<String>,
-    #[request_body(required = true)] Json(ModifyServiceRequest {
+    headers: HeaderMap,
+    #[request_body(required = true)] Json(modify_request): Json<ModifyServiceRequest>,
+) -> Result<Json<ServiceMetadata>, MetaApiError> {
+    let modify_request = modify_service_changes(modify_request);
+
+    if modify_request.is_empty() {
+        // No need to do anything
+        return get_service(State(state), Extension(version), Path(service_name)).await;
+    }
+
+    let response = state
+        .schema_registry
+        .modify_service(service_name, modify_request, change_author(&headers))
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(restate_admin_rest_model::converters::convert_service_metadata(version, response).into())
+}
+
+pub(super) fn modify_service_changes(
+    ModifyServiceRequest {
         public,
         idempotency_retention,
         workflow_completion_retention,
         inactivity_timeout,
         abort_timeout,
-    }): Json<ModifyServiceRequest>,
-) -> Result<Json<ServiceMetadata>, MetaApiError> {
+        enforce_input_schema,
+    }: ModifyServiceRequest,
+) -> Vec<ModifyServiceChange> {
     let mut modify_request = vec![];
     if let Some(new_public_value) = public {
         modify_request.push(ModifyServiceChange::Public(new_public_value));
@@ -150,19 +178,12 @@ pub async fn modify_service<V>(
     if let Some(abort_timeout) = abort_timeout {
         modify_request.push(ModifyServiceChange::AbortTimeout(abort_timeout));
     }
-
-    if modify_request.is_empty() {
-        // No need to do anything
-        return get_service(State(state), Extension(version), Path(service_name)).await;
+    if let Some(enforce_input_schema) = enforce_input_schema {
+        modify_request.push(ModifyServiceChange::EnforceInputSchema(
+            enforce_input_schema,
+        ));
     }
-
-    let response = state
-        .schema_registry
-        .modify_service(service_name, modify_request)
-        .await
-        .inspect_err(|e| warn_it!(e))?;
-
-    Ok(restate_admin_rest_model::converters::convert_service_metadata(version, response).into())
+    modify_request
 }
 
 /// Modify a service state
@@ -189,26 +210,112 @@ pub async fn modify_service<V>(
 pub async fn modify_service_state<V>(
     State(state): State<AdminServiceState<V>>,
     Path(service_name): Path<String>,
-    #[request_body(required = true)] Json(ModifyServiceStateRequest {
-        version,
-        object_key,
-        new_state,
-    }): Json<ModifyServiceStateRequest>,
+    #[request_body(required = true)] Json(modify_request): Json<ModifyServiceStateRequest>,
 ) -> Result<StatusCode, MetaApiError> {
-    if let Some(svc) = state.schema_registry.get_service(&service_name) {
+    check_service_has_state(&state, &service_name, modify_request.new_state.is_empty())?;
+    append_patch_state(&state, service_name, modify_request).await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Modify the state of multiple service keys
+#[openapi(
+    summary = "Modify the state of multiple service keys",
+    description = "Modify the state of multiple virtual objects/workflows of a service. Every change is applied independently, with its own version check. The changes are sent to the partitions in order: if sending one fails, the response reports how many were sent, and the request can be resumed from the first change that was not.",
+    operation_id = "modify_service_state_batch",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "Json<ModifyServiceStateBatchResponse>",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn modify_service_state_batch<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(service_name): Path<String>,
+    #[request_body(required = true)] Json(ModifyServiceStateBatchRequest { objects }): Json<
+        ModifyServiceStateBatchRequest,
+    >,
+) -> Result<(StatusCode, Json<ModifyServiceStateBatchResponse>), MetaApiError> {
+    if objects.is_empty() {
+        return Err(MetaApiError::InvalidField(
+            "objects",
+            "At least one state change must be provided".to_owned(),
+        ));
+    }
+    if objects.len() > MAX_STATE_BATCH_SIZE {
+        return Err(MetaApiError::InvalidField(
+            "objects",
+            format!("At most {MAX_STATE_BATCH_SIZE} state changes can be applied at once"),
+        ));
+    }
+    check_service_has_state(
+        &state,
+        &service_name,
+        objects.iter().all(|object| object.new_state.is_empty()),
+    )?;
+
+    let mut applied = 0;
+    let mut error = None;
+    for modify_request in objects {
+        match append_patch_state(&state, service_name.clone(), modify_request).await {
+            Ok(()) => applied += 1,
+            // Nothing was sent, the whole request can be retried
+            Err(err) if applied == 0 => return Err(err),
+            Err(err) => {
+                error = Some(err.to_string());
+                break;
+            }
+        }
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ModifyServiceStateBatchResponse { applied, error }),
+    ))
+}
+
+const MAX_STATE_BATCH_SIZE: usize = 1000;
+
+fn check_service_has_state<V>(
+    state: &AdminServiceState<V>,
+    service_name: &str,
+    clears_state: bool,
+) -> Result<(), MetaApiError> {
+    if let Some(svc) = state.schema_registry.get_service(service_name) {
         if !svc.ty.has_state() {
             return Err(MetaApiError::UnsupportedOperation("modify state", svc.ty));
         }
-    } else if new_state.is_empty() {
+    } else if clears_state {
         // could be a deleted service; we still want to allow state to be cleared, so lets continue given that the new state is empty
         debug!(
             rpc.service = service_name,
             "Attempting to delete state for service that does not exist in the registry (perhaps deleted)"
         );
     } else {
-        return Err(MetaApiError::ServiceNotFound(service_name));
+        return Err(MetaApiError::ServiceNotFound(service_name.to_owned()));
     }
+    Ok(())
+}
 
+async fn append_patch_state<V>(
+    state: &AdminServiceState<V>,
+    service_name: String,
+    ModifyServiceStateRequest {
+        version,
+        object_key,
+        new_state,
+    }: ModifyServiceStateRequest,
+) -> Result<(), MetaApiError> {
     let service_id = ServiceId::new(service_name, object_key);
 
     let new_state = new_state
@@ -238,6 +345,393 @@ pub async fn modify_service_state<V>(
             "Failed sending state patching command to the cluster.".to_owned(),
         ))
     } else {
-        Ok(StatusCode::ACCEPTED)
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeleteServiceParams {
+    pub force: Option<bool>,
+    pub purge: Option<bool>,
+}
+
+/// Delete a service
+#[openapi(
+    summary = "Delete service",
+    description = "Delete a service from the registry. Optionally, purge its stored data from every partition: the state, the inbox, the promises and the completed invocations of the service. The purge retains the data still in use by in-flight invocations. Delete the service using the force flag to kill them. The service is removed from the registry before killing the invocations and purging the data, so no new invocations are accepted in the meantime.",
+    operation_id = "delete_service",
+    tags = "service",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "force",
+            description = "If true, the in-flight invocations of the service will be killed. Otherwise, the service cannot be deleted while it has in-flight invocations.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "bool",
+        ),
+        query(
+            name = "purge",
+            description = "If true, the stored data of the service will be purged from all the partitions. The purge can be requested again for a service that was already deleted.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "bool",
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "Json<DeleteServiceResponse>",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn delete_service<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(service_name): Path<String>,
+    Query(DeleteServiceParams { force, purge }): Query<DeleteServiceParams>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<DeleteServiceResponse>), MetaApiError> {
+    let force = force == Some(true);
+    let purge = purge == Some(true);
+
+    let registered = state.schema_registry.get_service(&service_name).is_some();
+    if !registered && !purge {
+        return Err(MetaApiError::ServiceNotFound(service_name));
+    }
+
+    // Without the force flag, fail before touching the registry. Invocations started between this
+    // check and the removal of the service are not killed, and the purge retains their data.
+    if !force {
+        let in_flight_invocations =
+            in_flight_invocations(query_context(&state)?, &service_name).await?;
+        if !in_flight_invocations.is_empty() {
+            return Err(MetaApiError::ServiceHasInFlightInvocations(
+                service_name,
+                in_flight_invocations.len(),
+            ));
+        }
+    }
+
+    // The service is removed first, so no new invocations are accepted while the in-flight ones
+    // are killed and the data is purged
+    if registered {
+        state
+            .schema_registry
+            .delete_service(service_name.clone(), change_author(&headers))
+            .await
+            .inspect_err(|e| warn_it!(e))?;
+    } else {
+        debug!(
+            rpc.service = service_name,
+            "Purging the data of a service that does not exist in the registry (perhaps deleted)"
+        );
+    }
+
+    let killed_invocations = match &state.query_context {
+        Some(query_context) if force => in_flight_invocations(query_context, &service_name).await?,
+        None if force => {
+            warn!(
+                rpc.service = service_name,
+                "Cannot kill the in-flight invocations of the deleted service, the storage query engine is not available on this node"
+            );
+            vec![]
+        }
+        _ => vec![],
+    };
+    // The kill commands are appended before the purge commands, hence they're applied first
+    for invocation_id in &killed_invocations {
+        append_command(
+            &state,
+            invocation_id.partition_key(),
+            Command::TerminateInvocation(InvocationTermination::kill(*invocation_id)),
+        )
+        .await?;
+    }
+
+    let mut purged_partitions = 0;
+    let mut purge_commands = 0;
+    if purge {
+        let partition_table = Metadata::with_current(|m| m.partition_table_ref());
+        for (_, partition) in partition_table.partitions() {
+            for chunk in purge_chunks(&partition.key_range) {
+                append_command(
+                    &state,
+                    *chunk.start(),
+                    Command::PurgeService(PurgeServiceRequest {
+                        service_name: service_name.clone().into(),
+                        partition_key_range: chunk,
+                    }),
+                )
+                .await?;
+                purge_commands += 1;
+            }
+            purged_partitions += 1;
+        }
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(DeleteServiceResponse {
+            killed_invocations,
+            purged_partitions,
+            purge_commands,
+        }),
+    ))
+}
+
+/// Number of purge commands appended to each partition, each one sweeping a chunk of its keys.
+/// This bounds the work done by the partition processor when applying a single command.
+const PURGE_CHUNKS_PER_PARTITION: u64 = 16;
+
+/// Splits the key range of a partition in [`PURGE_CHUNKS_PER_PARTITION`] contiguous chunks.
+fn purge_chunks(
+    key_range: &RangeInclusive<PartitionKey>,
+) -> impl Iterator<Item = RangeInclusive<PartitionKey>> {
+    let end = *key_range.end();
+    // Rounded up, the length of the whole key space doesn't fit in a partition key
+    let chunk_len = (end - key_range.start()) / PURGE_CHUNKS_PER_PARTITION + 1;
+    let mut next = Some(*key_range.start());
+    std::iter::from_fn(move || {
+        let start = next?;
+        let chunk_end = start.saturating_add(chunk_len - 1).min(end);
+        next = chunk_end.checked_add(1).filter(|next| *next <= end);
+        Some(start..=chunk_end)
+    })
+}
+
+async fn append_command<V>(
+    state: &AdminServiceState<V>,
+    partition_key: PartitionKey,
+    command: Command,
+) -> Result<(), MetaApiError> {
+    append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(partition_key),
+            command,
+        )),
+    )
+    .await
+    .map(|_| ())
+    .map_err(|err| {
+        warn!("Could not append service deletion command to Bifrost: {err}");
+        MetaApiError::Internal("Failed sending service deletion command to the cluster.".to_owned())
+    })
+}
+
+async fn in_flight_invocations(
+    query_context: &QueryContext,
+    service_name: &str,
+) -> Result<Vec<InvocationId>, MetaApiError> {
+    let batches = execute_query(
+        query_context,
+        "SELECT id FROM sys_invocation_status \
+         WHERE target_service_name = $1 AND status NOT IN ('completed', 'free')",
+        [ScalarValue::from(service_name)],
+    )
+    .await?;
+
+    let mut invocation_ids = Vec::new();
+    for batch in batches {
+        let ids = string_column(&batch, "id")?;
+        for row in 0..batch.num_rows() {
+            invocation_ids.push(ids.value(row).parse().map_err(|err| {
+                MetaApiError::Internal(format!("Unexpected invocation id: {err}"))
+            })?);
+        }
+    }
+
+    Ok(invocation_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use googletest::prelude::*;
+    use test_log::test;
+
+    use restate_bifrost::providers::memory_loglet;
+    use restate_bifrost::BifrostService;
+    use restate_core::TestCoreEnvBuilder;
+    use restate_service_client::{AssumeRoleCacheMode, ServiceClient};
+    use restate_service_protocol::discovery::ServiceDiscovery;
+    use restate_types::config::IngressOptions;
+    use restate_types::logs::LogId;
+    use restate_types::partition_table::PartitionTable;
+    use restate_types::retries::RetryPolicy;
+    use restate_types::Version;
+
+    use crate::schema_registry::SchemaRegistry;
+
+    /// Admin state of a cluster with a single partition, writing to the in-memory log 0.
+    async fn admin_state() -> AdminServiceState<IngressOptions> {
+        let builder = TestCoreEnvBuilder::with_incoming_only_connector().set_partition_table(
+            PartitionTable::with_equally_sized_partitions(Version::MIN, 1),
+        );
+        let bifrost_svc = BifrostService::new(builder.metadata_writer.clone())
+            .with_factory(memory_loglet::Factory::default());
+        let bifrost = bifrost_svc.handle();
+        let env = builder.build().await;
+        bifrost_svc.start().await.unwrap();
+
+        let service_discovery = ServiceDiscovery::new(
+            RetryPolicy::None,
+            ServiceClient::from_options(&Default::default(), AssumeRoleCacheMode::None).unwrap(),
+        );
+        AdminServiceState::new(
+            SchemaRegistry::new(
+                env.metadata_writer,
+                service_discovery,
+                IngressOptions::default(),
+                false,
+            ),
+            bifrost,
+            None,
+        )
+    }
+
+    fn clear_state(object_key: &str) -> ModifyServiceStateRequest {
+        ModifyServiceStateRequest {
+            version: Some("my-version".to_owned()),
+            object_key: object_key.to_owned(),
+            new_state: Default::default(),
+        }
+    }
+
+    #[test(restate_core::test)]
+    async fn modify_service_state_batch_appends_a_patch_per_key() {
+        let state = admin_state().await;
+        let bifrost = state.bifrost.clone();
+
+        // Clearing the state is allowed for services that are not registered anymore
+        let (status, Json(response)) = modify_service_state_batch(
+            State(state),
+            Path("MyObject".to_owned()),
+            Json(ModifyServiceStateBatchRequest {
+                objects: vec![clear_state("a"), clear_state("b")],
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_that!(
+            response,
+            pat!(ModifyServiceStateBatchResponse {
+                applied: eq(2),
+                error: none()
+            })
+        );
+
+        let commands: Vec<_> = bifrost
+            .read_all(LogId::new(0))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.try_decode::<Envelope>().unwrap().unwrap().command)
+            .collect();
+        assert_that!(
+            commands,
+            elements_are![
+                pat!(Command::PatchState(pat!(ExternalStateMutation {
+                    service_id: eq(ServiceId::new("MyObject", "a")),
+                    version: some(eq("my-version")),
+                    state: empty(),
+                }))),
+                pat!(Command::PatchState(pat!(ExternalStateMutation {
+                    service_id: eq(ServiceId::new("MyObject", "b")),
+                    version: some(eq("my-version")),
+                    state: empty(),
+                }))),
+            ]
+        );
+    }
+
+    #[test(restate_core::test)]
+    async fn modify_service_state_batch_validates_the_request() {
+        let state = admin_state().await;
+
+        let result = modify_service_state_batch(
+            State(state.clone()),
+            Path("MyObject".to_owned()),
+            Json(ModifyServiceStateBatchRequest { objects: vec![] }),
+        )
+        .await;
+        assert_that!(
+            result,
+            err(pat!(MetaApiError::InvalidField(eq("objects"), anything())))
+        );
+
+        let result = modify_service_state_batch(
+            State(state.clone()),
+            Path("MyObject".to_owned()),
+            Json(ModifyServiceStateBatchRequest {
+                objects: (0..=MAX_STATE_BATCH_SIZE)
+                    .map(|i| clear_state(&i.to_string()))
+                    .collect(),
+            }),
+        )
+        .await;
+        assert_that!(
+            result,
+            err(pat!(MetaApiError::InvalidField(eq("objects"), anything())))
+        );
+
+        // Setting the state requires the service to exist
+        let mut set_state = clear_state("a");
+        set_state
+            .new_state
+            .insert("my-key".to_owned(), Bytes::from_static(b"my-value"));
+        let result = modify_service_state_batch(
+            State(state.clone()),
+            Path("MyObject".to_owned()),
+            Json(ModifyServiceStateBatchRequest {
+                objects: vec![clear_state("b"), set_state],
+            }),
+        )
+        .await;
+        assert_that!(
+            result,
+            err(pat!(MetaApiError::ServiceNotFound(eq("MyObject"))))
+        );
+
+        // Nothing was appended
+        assert_that!(
+            state.bifrost.read_all(LogId::new(0)).await.unwrap(),
+            empty()
+        );
+    }
+
+    #[test]
+    fn purge_chunks_cover_the_partition() {
+        for key_range in [
+            0..=PartitionKey::MAX,
+            0..=0,
+            10..=24,
+            PartitionKey::MAX - 3..=PartitionKey::MAX,
+            1000..=1000 + 16 * 100 - 1,
+        ] {
+            let chunks: Vec<_> = purge_chunks(&key_range).collect();
+            assert!(chunks.len() as u64 <= PURGE_CHUNKS_PER_PARTITION);
+            assert_eq!(chunks.first().map(|c| *c.start()), Some(*key_range.start()));
+            assert_eq!(chunks.last().map(|c| *c.end()), Some(*key_range.end()));
+            for pair in chunks.windows(2) {
+                assert_eq!(*pair[0].end() + 1, *pair[1].start(), "{key_range:?}");
+            }
+        }
+        assert_eq!(
+            purge_chunks(&(0..=PartitionKey::MAX)).count() as u64,
+            PURGE_CHUNKS_PER_PARTITION
+        );
     }
 }
@@ -0,0 +1,394 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::create_envelope_header;
+use super::error::*;
+use super::invocations::{deletion_command, DeleteInvocationParams, DeletionMode};
+use super::query::{
+    bool_column, execute_query, optional_string, query_context, string_column, timestamp_millis,
+};
+use crate::state::AdminServiceState;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use bytes::Bytes;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::ScalarValue;
+use okapi_operation::*;
+use restate_admin_rest_model::workflows::*;
+use restate_types::errors::{codes, InvocationError};
+use restate_types::identifiers::{InvocationId, ServiceId, WithPartitionKey};
+use restate_types::invocation::{
+    CompletePromiseRequest as CompletePromiseCommand, InvocationTarget, ResponseResult,
+    ServiceType, WorkflowHandlerType,
+};
+use restate_types::schema::service::HandlerMetadataType;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+use serde::Deserialize;
+use tracing::warn;
+
+const DEFAULT_LIST_WORKFLOW_RUNS_LIMIT: u64 = 100;
+
+const WORKFLOW_RUNS_QUERY: &str = "SELECT
+        id,
+        target_service_key,
+        status,
+        created_at,
+        completion_result,
+        completion_failure
+    FROM sys_invocation
+    WHERE target_service_name = $1 AND target_handler_name = $2";
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListWorkflowRunsParams {
+    pub limit: Option<u64>,
+}
+
+/// List workflow runs
+#[openapi(
+    summary = "List workflow runs",
+    description = "List the runs of a workflow, the most recently created first.",
+    operation_id = "list_workflow_runs",
+    tags = "workflow",
+    parameters(
+        path(
+            name = "workflow",
+            description = "Fully qualified workflow service name.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "limit",
+            description = "Maximum number of runs to return. Defaults to 100.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u64",
+        )
+    )
+)]
+pub async fn list_workflow_runs<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(workflow): Path<String>,
+    Query(ListWorkflowRunsParams { limit }): Query<ListWorkflowRunsParams>,
+) -> Result<Json<ListWorkflowRunsResponse>, MetaApiError> {
+    let workflow_handler = check_is_workflow(&state, &workflow, "list workflow runs")?;
+
+    let batches = execute_query(
+        query_context(&state)?,
+        &format!(
+            "{WORKFLOW_RUNS_QUERY} ORDER BY created_at DESC LIMIT {}",
+            limit.unwrap_or(DEFAULT_LIST_WORKFLOW_RUNS_LIMIT)
+        ),
+        [
+            ScalarValue::from(workflow),
+            ScalarValue::from(workflow_handler),
+        ],
+    )
+    .await?;
+
+    Ok(ListWorkflowRunsResponse {
+        runs: workflow_runs(&batches)?,
+    }
+    .into())
+}
+
+/// Describe a workflow run
+#[openapi(
+    summary = "Describe a workflow run",
+    description = "Get the run of a workflow together with its durable promises.",
+    operation_id = "describe_workflow_run",
+    tags = "workflow",
+    parameters(
+        path(
+            name = "workflow",
+            description = "Fully qualified workflow service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "key",
+            description = "Workflow key.",
+            schema = "std::string::String"
+        )
+    )
+)]
+pub async fn describe_workflow_run<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path((workflow, key)): Path<(String, String)>,
+) -> Result<Json<DescribeWorkflowRunResponse>, MetaApiError> {
+    let workflow_handler = check_is_workflow(&state, &workflow, "describe workflow runs")?;
+    let query_context = query_context(&state)?;
+
+    let batches = execute_query(
+        query_context,
+        &format!("{WORKFLOW_RUNS_QUERY} AND target_service_key = $3"),
+        [
+            ScalarValue::from(workflow.as_str()),
+            ScalarValue::from(workflow_handler),
+            ScalarValue::from(key.as_str()),
+        ],
+    )
+    .await?;
+    let run = workflow_runs(&batches)?.pop();
+
+    let batches = execute_query(
+        query_context,
+        "SELECT key, completed, completion_success_value_utf8, completion_failure
+        FROM sys_promise
+        WHERE service_name = $1 AND service_key = $2
+        ORDER BY key",
+        [
+            ScalarValue::from(workflow.as_str()),
+            ScalarValue::from(key.as_str()),
+        ],
+    )
+    .await?;
+    let promises = workflow_promises(&batches)?;
+
+    if run.is_none() && promises.is_empty() {
+        return Err(MetaApiError::WorkflowRunNotFound(workflow, key));
+    }
+
+    Ok(DescribeWorkflowRunResponse { run, promises }.into())
+}
+
+fn workflow_runs(batches: &[RecordBatch]) -> Result<Vec<WorkflowRunResponse>, MetaApiError> {
+    let mut runs = Vec::new();
+    for batch in batches {
+        let ids = string_column(batch, "id")?;
+        let keys = string_column(batch, "target_service_key")?;
+        let statuses = string_column(batch, "status")?;
+        let completion_results = string_column(batch, "completion_result")?;
+        let completion_failures = string_column(batch, "completion_failure")?;
+
+        for row in 0..batch.num_rows() {
+            let created_at = timestamp_millis(batch, "created_at", row)?.unwrap_or_default();
+            runs.push(WorkflowRunResponse {
+                key: keys.value(row).to_owned(),
+                invocation_id: ids.value(row).to_owned(),
+                status: statuses.value(row).to_owned(),
+                created_at: (SystemTime::UNIX_EPOCH + Duration::from_millis(created_at)).into(),
+                completion_result: optional_string(completion_results, row),
+                completion_failure: optional_string(completion_failures, row),
+            });
+        }
+    }
+    Ok(runs)
+}
+
+fn workflow_promises(
+    batches: &[RecordBatch],
+) -> Result<Vec<WorkflowPromiseResponse>, MetaApiError> {
+    let mut promises = Vec::new();
+    for batch in batches {
+        let names = string_column(batch, "key")?;
+        let completed = bool_column(batch, "completed")?;
+        let values = string_column(batch, "completion_success_value_utf8")?;
+        let failures = string_column(batch, "completion_failure")?;
+
+        for row in 0..batch.num_rows() {
+            promises.push(WorkflowPromiseResponse {
+                name: names.value(row).to_owned(),
+                completed: completed.value(row),
+                value: optional_string(values, row),
+                failure: optional_string(failures, row),
+            });
+        }
+    }
+    Ok(promises)
+}
+
+/// Complete a workflow promise
+#[openapi(
+    summary = "Complete a workflow promise",
+    description = "Resolve or reject a durable promise of a workflow. \
+    If the promise was already completed, the request has no effect.",
+    operation_id = "complete_workflow_promise",
+    tags = "workflow",
+    parameters(
+        path(
+            name = "workflow",
+            description = "Fully qualified workflow service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "key",
+            description = "Workflow key.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "promise",
+            description = "Promise name.",
+            schema = "std::string::String"
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn complete_workflow_promise<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path((workflow, key, promise)): Path<(String, String, String)>,
+    #[request_body(required = true)] Json(request): Json<CompletePromiseRequest>,
+) -> Result<StatusCode, MetaApiError> {
+    check_is_workflow(&state, &workflow, "complete promises")?;
+
+    let result = completion_result(request)?;
+
+    let service_id = ServiceId::new(workflow, key);
+    let partition_key = service_id.partition_key();
+    let cmd = Command::CompletePromise(CompletePromiseCommand {
+        service_id,
+        promise_key: promise.into(),
+        result,
+    });
+
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(create_envelope_header(partition_key), cmd)),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append promise completion command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending promise completion to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
+
+/// Converts the completion of a promise, or of an awakeable, to its result.
+pub(super) fn completion_result(
+    request: CompletePromiseRequest,
+) -> Result<ResponseResult, MetaApiError> {
+    Ok(match request {
+        CompletePromiseRequest::Value(value) => ResponseResult::Success(Bytes::from(
+            serde_json::to_vec(&value)
+                .map_err(|e| MetaApiError::InvalidField("value", e.to_string()))?,
+        )),
+        CompletePromiseRequest::Failure(PromiseFailure { code, message }) => {
+            ResponseResult::Failure(InvocationError::new(
+                code.map(Into::into).unwrap_or(codes::INTERNAL),
+                message,
+            ))
+        }
+    })
+}
+
+/// Delete a workflow run
+#[openapi(
+    summary = "Delete a workflow run",
+    description = "Delete the run of the given workflow. This has the same semantics of deleting \
+    the invocation of the workflow run, see delete_invocation.",
+    operation_id = "delete_workflow",
+    tags = "workflow",
+    parameters(
+        path(
+            name = "workflow",
+            description = "Fully qualified workflow service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "key",
+            description = "Workflow key.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "mode",
+            description = "If cancel, it will gracefully terminate the workflow run. \
+            If kill, it will terminate the workflow run with a hard stop. \
+            If purge, it will only cleanup the workflow run, its state and its promises, if the run is completed.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "DeletionMode",
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn delete_workflow<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path((workflow, key)): Path<(String, String)>,
+    Query(DeleteInvocationParams { mode }): Query<DeleteInvocationParams>,
+) -> Result<StatusCode, MetaApiError> {
+    let workflow_handler = check_is_workflow(&state, &workflow, "delete workflow runs")?;
+
+    // The id of the workflow run is deterministic
+    let invocation_id = InvocationId::generate(
+        &InvocationTarget::workflow(
+            workflow,
+            key,
+            workflow_handler,
+            WorkflowHandlerType::Workflow,
+        ),
+        None,
+    );
+    let cmd = deletion_command(invocation_id, mode.unwrap_or_default());
+
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(invocation_id.partition_key()),
+            cmd,
+        )),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append workflow termination command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending workflow termination to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
+
+/// Returns the name of the workflow run handler.
+fn check_is_workflow<V>(
+    state: &AdminServiceState<V>,
+    workflow: &str,
+    operation: &'static str,
+) -> Result<String, MetaApiError> {
+    let service = state
+        .schema_registry
+        .get_service(workflow)
+        .ok_or_else(|| MetaApiError::ServiceNotFound(workflow.to_owned()))?;
+    if service.ty != ServiceType::Workflow {
+        return Err(MetaApiError::UnsupportedOperation(operation, service.ty));
+    }
+
+    service
+        .handlers
+        .into_iter()
+        .find(|h| h.ty == Some(HandlerMetadataType::Workflow))
+        .map(|h| h.name)
+        .ok_or_else(|| {
+            MetaApiError::Internal(format!("Workflow '{workflow}' has no workflow handler"))
+        })
+}
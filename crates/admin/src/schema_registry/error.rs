@@ -16,7 +16,7 @@ use restate_core::metadata_store::ReadModifyWriteError;
 use restate_core::ShutdownError;
 use restate_types::endpoint_manifest;
 use restate_types::errors::GenericError;
-use restate_types::identifiers::DeploymentId;
+use restate_types::identifiers::{DeploymentId, SubscriptionId};
 use restate_types::invocation::ServiceType;
 use restate_types::schema::invocation_target::BadInputContentType;
 
@@ -104,6 +104,9 @@ pub enum ServiceError {
         #[source]
         error: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+    #[error("the service '{0}' is the sink of the subscriptions {1:?}, remove them first")]
+    #[code(unknown)]
+    TargetedBySubscriptions(String, Vec<SubscriptionId>),
     #[error("modifying retention time for service type {0} is unsupported")]
     #[code(unknown)]
     CannotModifyRetentionTime(ServiceType),
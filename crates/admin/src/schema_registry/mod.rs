@@ -19,19 +19,27 @@ use std::time::Duration;
 
 use http::Uri;
 use tracing::subscriber::NoSubscriber;
+use tracing::{info, warn};
 
+use restate_core::metadata_store::ReadModifyWriteError;
 use restate_core::{Metadata, MetadataWriter};
-use restate_service_protocol::discovery::{DiscoverEndpoint, DiscoveredEndpoint, ServiceDiscovery};
+use restate_service_protocol::discovery::{
+    DiscoverEndpoint, DiscoveredEndpoint, DiscoveredMetadata, ServiceDiscovery,
+};
+use restate_types::config::Configuration;
+use restate_types::endpoint_manifest;
 use restate_types::identifiers::{DeploymentId, ServiceRevision, SubscriptionId};
-use restate_types::metadata_store::keys::SCHEMA_INFORMATION_KEY;
+use restate_types::metadata_store::keys::{SCHEMA_HISTORY_KEY, SCHEMA_INFORMATION_KEY};
 use restate_types::schema::deployment::{
     DeliveryOptions, Deployment, DeploymentMetadata, DeploymentResolver,
 };
+use restate_types::schema::history::SchemaHistory;
 use restate_types::schema::service::{HandlerMetadata, ServiceMetadata, ServiceMetadataResolver};
 use restate_types::schema::subscriptions::{
     ListSubscriptionFilter, Subscription, SubscriptionResolver, SubscriptionValidator,
 };
 use restate_types::schema::Schema;
+use restate_types::{Version, Versioned};
 
 use crate::schema_registry::error::{SchemaError, SchemaRegistryError, ServiceError};
 use crate::schema_registry::updater::SchemaUpdater;
@@ -70,6 +78,45 @@ pub enum ModifyServiceChange {
     WorkflowCompletionRetention(Duration),
     InactivityTimeout(Duration),
     AbortTimeout(Duration),
+    EnforceInputSchema(bool),
+}
+
+/// Declarative description of deployments, service configuration overrides and subscriptions,
+/// applied with [`SchemaRegistry::apply_manifest`].
+#[derive(Default)]
+pub struct SchemaManifest {
+    pub deployments: Vec<DiscoverEndpoint>,
+    pub services: Vec<(String, Vec<ModifyServiceChange>)>,
+    pub subscriptions: Vec<ManifestSubscription>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ManifestSubscription {
+    pub source: Uri,
+    pub sink: Uri,
+    pub options: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestChange {
+    Added,
+    Updated,
+    Unchanged,
+}
+
+/// Result of applying a [`SchemaManifest`] to the schema.
+#[derive(Debug)]
+pub struct ManifestPlan {
+    pub deployments: Vec<(DeploymentId, ManifestChange)>,
+    pub subscriptions: Vec<(SubscriptionId, ManifestChange)>,
+    pub previous: Schema,
+    pub current: Schema,
+}
+
+impl ManifestPlan {
+    pub fn has_changes(&self) -> bool {
+        self.previous.version() != self.current.version()
+    }
 }
 
 /// Responsible for updating the registered schema information. This includes the discovery of
@@ -103,28 +150,14 @@ impl<V> SchemaRegistry<V> {
         discover_endpoint: DiscoverEndpoint,
         force: Force,
         apply_mode: ApplyMode,
+        author: Option<String>,
     ) -> Result<(DeploymentId, Vec<ServiceMetadata>), SchemaRegistryError> {
         // The number of concurrent discovery calls is bound by the number of concurrent
         // {register,update}_deployment calls. If it should become a problem that a user tries to register
         // the same endpoint too often, then we need to add a synchronization mechanism which
         // ensures that only a limited number of discover calls per endpoint are running.
         let discovered_metadata = self.service_discovery.discover(discover_endpoint).await?;
-
-        let deployment_metadata = match discovered_metadata.endpoint {
-            DiscoveredEndpoint::Http(uri, http_version) => DeploymentMetadata::new_http(
-                uri.clone(),
-                discovered_metadata.protocol_type,
-                http_version,
-                DeliveryOptions::new(discovered_metadata.headers),
-                discovered_metadata.supported_protocol_versions,
-            ),
-            DiscoveredEndpoint::Lambda(arn, assume_role_arn) => DeploymentMetadata::new_lambda(
-                arn,
-                assume_role_arn,
-                DeliveryOptions::new(discovered_metadata.headers),
-                discovered_metadata.supported_protocol_versions,
-            ),
-        };
+        let (deployment_metadata, services) = into_deployment_metadata(discovered_metadata);
 
         let (id, services) = if !apply_mode.should_apply() {
             let mut updater = SchemaUpdater::new(
@@ -134,11 +167,7 @@ impl<V> SchemaRegistry<V> {
 
             // suppress logging output in case of a dry run
             let id = tracing::subscriber::with_default(NoSubscriber::new(), || {
-                updater.add_deployment(
-                    deployment_metadata,
-                    discovered_metadata.services,
-                    force.force_enabled(),
-                )
+                updater.add_deployment(deployment_metadata, services, force.force_enabled())
             })?;
 
             let schema_information = updater.into_inner();
@@ -149,20 +178,23 @@ impl<V> SchemaRegistry<V> {
             (id, services)
         } else {
             let mut new_deployment_id = None;
+            let mut previous_schema_information = None;
             let schema_information = self
                 .metadata_writer
                 .metadata_store_client()
                 .read_modify_write(
                     SCHEMA_INFORMATION_KEY.clone(),
                     |schema_information: Option<Schema>| {
+                        let schema_information = schema_information.unwrap_or_default();
+                        previous_schema_information = Some(schema_information.clone());
                         let mut updater = SchemaUpdater::new(
-                            schema_information.unwrap_or_default(),
+                            schema_information,
                             self.experimental_feature_kafka_ingress_next,
                         );
 
                         new_deployment_id = Some(updater.add_deployment(
                             deployment_metadata.clone(),
-                            discovered_metadata.services.clone(),
+                            services.clone(),
                             force.force_enabled(),
                         )?);
                         Ok(updater.into_inner())
@@ -175,6 +207,13 @@ impl<V> SchemaRegistry<V> {
                 .get_deployment_and_services(&new_deployment_id)
                 .expect("deployment was just added");
 
+            self.record_history(
+                previous_schema_information,
+                &schema_information,
+                author,
+                format!("register deployment {new_deployment_id}"),
+            )
+            .await;
             self.metadata_writer
                 .update(Arc::new(schema_information))
                 .await?;
@@ -190,28 +229,14 @@ impl<V> SchemaRegistry<V> {
         deployment_id: DeploymentId,
         discover_endpoint: DiscoverEndpoint,
         apply_mode: ApplyMode,
+        author: Option<String>,
     ) -> Result<(Deployment, Vec<ServiceMetadata>), SchemaRegistryError> {
         // The number of concurrent discovery calls is bound by the number of concurrent
         // {register,update}_deployment calls. If it should become a problem that a user tries to register
         // the same endpoint too often, then we need to add a synchronization mechanism which
         // ensures that only a limited number of discover calls per endpoint are running.
         let discovered_metadata = self.service_discovery.discover(discover_endpoint).await?;
-
-        let deployment_metadata = match discovered_metadata.endpoint {
-            DiscoveredEndpoint::Http(uri, http_version) => DeploymentMetadata::new_http(
-                uri.clone(),
-                discovered_metadata.protocol_type,
-                http_version,
-                DeliveryOptions::new(discovered_metadata.headers),
-                discovered_metadata.supported_protocol_versions,
-            ),
-            DiscoveredEndpoint::Lambda(arn, assume_role_arn) => DeploymentMetadata::new_lambda(
-                arn,
-                assume_role_arn,
-                DeliveryOptions::new(discovered_metadata.headers),
-                discovered_metadata.supported_protocol_versions,
-            ),
-        };
+        let (deployment_metadata, services) = into_deployment_metadata(discovered_metadata);
 
         if !apply_mode.should_apply() {
             let mut updater = SchemaUpdater::new(
@@ -221,11 +246,7 @@ impl<V> SchemaRegistry<V> {
 
             // suppress logging output in case of a dry run
             tracing::subscriber::with_default(NoSubscriber::new(), || {
-                updater.update_deployment(
-                    deployment_id,
-                    deployment_metadata,
-                    discovered_metadata.services,
-                )
+                updater.update_deployment(deployment_id, deployment_metadata, services)
             })?;
 
             let schema_information = updater.into_inner();
@@ -233,21 +254,24 @@ impl<V> SchemaRegistry<V> {
                 .get_deployment_and_services(&deployment_id)
                 .expect("deployment was just added"))
         } else {
+            let mut previous_schema_information = None;
             let schema_information = self
                 .metadata_writer
                 .metadata_store_client()
                 .read_modify_write(
                     SCHEMA_INFORMATION_KEY.clone(),
                     |schema_information: Option<Schema>| {
+                        let schema_information = schema_information.unwrap_or_default();
+                        previous_schema_information = Some(schema_information.clone());
                         let mut updater = SchemaUpdater::new(
-                            schema_information.unwrap_or_default(),
+                            schema_information,
                             self.experimental_feature_kafka_ingress_next,
                         );
 
                         updater.update_deployment(
                             deployment_id,
                             deployment_metadata.clone(),
-                            discovered_metadata.services.clone(),
+                            services.clone(),
                         )?;
                         Ok(updater.into_inner())
                     },
@@ -258,6 +282,13 @@ impl<V> SchemaRegistry<V> {
                 .get_deployment_and_services(&deployment_id)
                 .expect("deployment was just updated");
 
+            self.record_history(
+                previous_schema_information,
+                &schema_information,
+                author,
+                format!("update deployment {deployment_id}"),
+            )
+            .await;
             self.metadata_writer
                 .update(Arc::new(schema_information))
                 .await?;
@@ -269,7 +300,9 @@ impl<V> SchemaRegistry<V> {
     pub async fn delete_deployment(
         &self,
         deployment_id: DeploymentId,
+        author: Option<String>,
     ) -> Result<(), SchemaRegistryError> {
+        let mut previous_schema_information = None;
         let schema_registry = self
             .metadata_writer
             .metadata_store_client()
@@ -277,6 +310,7 @@ impl<V> SchemaRegistry<V> {
                 SCHEMA_INFORMATION_KEY.clone(),
                 |schema_registry: Option<Schema>| {
                     let schema_information: Schema = schema_registry.unwrap_or_default();
+                    previous_schema_information = Some(schema_information.clone());
 
                     if schema_information.get_deployment(&deployment_id).is_some() {
                         let mut updater = SchemaUpdater::new(
@@ -293,6 +327,13 @@ impl<V> SchemaRegistry<V> {
                 },
             )
             .await?;
+        self.record_history(
+            previous_schema_information,
+            &schema_registry,
+            author,
+            format!("remove deployment {deployment_id}"),
+        )
+        .await;
         self.metadata_writer
             .update(Arc::new(schema_registry))
             .await?;
@@ -300,11 +341,51 @@ impl<V> SchemaRegistry<V> {
         Ok(())
     }
 
+    pub async fn delete_service(
+        &self,
+        service_name: String,
+        author: Option<String>,
+    ) -> Result<(), SchemaRegistryError> {
+        let mut previous_schema_information = None;
+        let schema_information = self
+            .metadata_writer
+            .metadata_store_client()
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let schema_information = schema_information.unwrap_or_default();
+                    previous_schema_information = Some(schema_information.clone());
+
+                    let mut updater = SchemaUpdater::new(
+                        schema_information,
+                        self.experimental_feature_kafka_ingress_next,
+                    );
+                    updater.remove_service(&service_name)?;
+                    Ok::<_, SchemaError>(updater.into_inner())
+                },
+            )
+            .await?;
+        self.record_history(
+            previous_schema_information,
+            &schema_information,
+            author,
+            format!("remove service {service_name}"),
+        )
+        .await;
+        self.metadata_writer
+            .update(Arc::new(schema_information))
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn modify_service(
         &self,
         service_name: String,
         changes: Vec<ModifyServiceChange>,
+        author: Option<String>,
     ) -> Result<ServiceMetadata, SchemaRegistryError> {
+        let mut previous_schema_information = None;
         let schema_information = self
             .metadata_writer
             .metadata_store_client()
@@ -312,6 +393,7 @@ impl<V> SchemaRegistry<V> {
                 SCHEMA_INFORMATION_KEY.clone(),
                 |schema_information: Option<Schema>| {
                     let schema_information = schema_information.unwrap_or_default();
+                    previous_schema_information = Some(schema_information.clone());
 
                     if schema_information
                         .resolve_latest_service(&service_name)
@@ -336,6 +418,83 @@ impl<V> SchemaRegistry<V> {
             .resolve_latest_service(&service_name)
             .expect("service was just modified");
 
+        self.record_history(
+            previous_schema_information,
+            &schema_information,
+            author,
+            format!("modify service '{service_name}'"),
+        )
+        .await;
+        self.metadata_writer
+            .update(Arc::new(schema_information))
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Restores the service-level configuration of the service, as it was at the given schema
+    /// version. Handlers and deployments are left untouched.
+    pub async fn rollback_service(
+        &self,
+        service_name: String,
+        schema_version: Version,
+        author: Option<String>,
+    ) -> Result<ServiceMetadata, SchemaRegistryError> {
+        let history = self.get_history().await?;
+        let Some(services) = history.services_at(schema_version) else {
+            return Err(SchemaError::NotFound(format!(
+                "schema version {schema_version} in the retained history, the oldest one is {}",
+                history.oldest_version()
+            ))
+            .into());
+        };
+        let Some(target) = services.get(&service_name) else {
+            return Err(SchemaError::NotFound(format!(
+                "service with name '{service_name}' at schema version {schema_version}"
+            ))
+            .into());
+        };
+
+        let mut previous_schema_information = None;
+        let schema_information = self
+            .metadata_writer
+            .metadata_store_client()
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let schema_information = schema_information.unwrap_or_default();
+                    previous_schema_information = Some(schema_information.clone());
+
+                    if schema_information
+                        .resolve_latest_service(&service_name)
+                        .is_some()
+                    {
+                        let mut updater = SchemaUpdater::new(
+                            schema_information,
+                            self.experimental_feature_kafka_ingress_next,
+                        );
+                        updater.restore_service_configuration(&service_name, target)?;
+                        Ok(updater.into_inner())
+                    } else {
+                        Err(SchemaError::NotFound(format!(
+                            "service with name '{service_name}'"
+                        )))
+                    }
+                },
+            )
+            .await?;
+
+        let response = schema_information
+            .resolve_latest_service(&service_name)
+            .expect("service was just rolled back");
+
+        self.record_history(
+            previous_schema_information,
+            &schema_information,
+            author,
+            format!("rollback service '{service_name}' configuration to {schema_version}"),
+        )
+        .await;
         self.metadata_writer
             .update(Arc::new(schema_information))
             .await?;
@@ -343,6 +502,69 @@ impl<V> SchemaRegistry<V> {
         Ok(response)
     }
 
+    pub async fn get_history(&self) -> Result<SchemaHistory, SchemaRegistryError> {
+        self.metadata_writer
+            .metadata_store_client()
+            .get(SCHEMA_HISTORY_KEY.clone())
+            .await
+            .map(Option::unwrap_or_default)
+            .map_err(|e| SchemaRegistryError::Internal(e.to_string()))
+    }
+
+    /// Appends the change to the schema history. The history is stored under its own key, so
+    /// failing to record the change doesn't fail the change itself.
+    async fn record_history(
+        &self,
+        previous: Option<Schema>,
+        current: &Schema,
+        author: Option<String>,
+        description: String,
+    ) {
+        let Some(previous) = previous else {
+            return;
+        };
+        let max_changes = Configuration::pinned().admin.schema_history_max_changes;
+
+        let mut folded_until = None;
+        let result = self
+            .metadata_writer
+            .metadata_store_client()
+            .read_modify_write(
+                SCHEMA_HISTORY_KEY.clone(),
+                |history: Option<SchemaHistory>| {
+                    let mut history = history.unwrap_or_default();
+                    let oldest_version = history.oldest_version();
+                    if history.record(
+                        &previous,
+                        current,
+                        author.clone(),
+                        description.clone(),
+                        max_changes,
+                    ) {
+                        folded_until = (history.oldest_version() != oldest_version)
+                            .then(|| history.oldest_version());
+                        Ok(history)
+                    } else {
+                        Err("no service was changed")
+                    }
+                },
+            )
+            .await;
+
+        match result {
+            Ok(_) => {
+                if let Some(oldest_version) = folded_until {
+                    info!(
+                        "Schema history exceeds {max_changes} changes, the changes up to version {oldest_version} were folded and can't be diffed or rolled back to anymore"
+                    );
+                }
+            }
+            Err(ReadModifyWriteError::FailedOperation(_)) => {}
+            // The next recorded change reconstructs this one as an untracked change
+            Err(err) => warn!("Failed to record the schema change in the history: {err}"),
+        }
+    }
+
     pub async fn delete_subscription(
         &self,
         subscription_id: SubscriptionId,
@@ -480,6 +702,142 @@ where
 
         Ok(subscription)
     }
+
+    /// Applies the manifest in a single schema update. Deployments are discovered first, then
+    /// registered together with the service configuration overrides and the subscriptions.
+    pub(crate) async fn apply_manifest(
+        &self,
+        manifest: SchemaManifest,
+        force: Force,
+        apply_mode: ApplyMode,
+        author: Option<String>,
+    ) -> Result<ManifestPlan, SchemaRegistryError> {
+        let SchemaManifest {
+            deployments: discover_endpoints,
+            services,
+            subscriptions,
+        } = manifest;
+
+        let mut deployments = Vec::with_capacity(discover_endpoints.len());
+        for discover_endpoint in discover_endpoints {
+            let discovered_metadata = self.service_discovery.discover(discover_endpoint).await?;
+            deployments.push(into_deployment_metadata(discovered_metadata));
+        }
+
+        // Plan against the current schema first, to skip the write if there's nothing to change.
+        // Suppress logging output, since this is a dry run.
+        let current_plan = tracing::subscriber::with_default(NoSubscriber::new(), || {
+            self.plan_manifest(
+                Metadata::with_current(|m| m.schema()).deref().clone(),
+                &force,
+                &deployments,
+                &services,
+                &subscriptions,
+            )
+        })?;
+        if !apply_mode.should_apply() || !current_plan.has_changes() {
+            return Ok(current_plan);
+        }
+
+        let mut applied_plan = None;
+        let schema_information = self
+            .metadata_writer
+            .metadata_store_client()
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let new_plan = self.plan_manifest(
+                        schema_information.unwrap_or_default(),
+                        &force,
+                        &deployments,
+                        &services,
+                        &subscriptions,
+                    )?;
+                    let schema_information = new_plan.current.clone();
+                    applied_plan = Some(new_plan);
+                    Ok::<_, SchemaError>(schema_information)
+                },
+            )
+            .await?;
+        let applied_plan = applied_plan.expect("manifest was just applied");
+
+        self.record_history(
+            Some(applied_plan.previous.clone()),
+            &schema_information,
+            author,
+            "apply manifest".to_owned(),
+        )
+        .await;
+        self.metadata_writer
+            .update(Arc::new(schema_information))
+            .await?;
+
+        Ok(applied_plan)
+    }
+
+    fn plan_manifest(
+        &self,
+        schema_information: Schema,
+        force: &Force,
+        deployments: &[(DeploymentMetadata, Vec<endpoint_manifest::Service>)],
+        services: &[(String, Vec<ModifyServiceChange>)],
+        subscriptions: &[ManifestSubscription],
+    ) -> Result<ManifestPlan, SchemaError> {
+        let mut updater = SchemaUpdater::new(
+            schema_information.clone(),
+            self.experimental_feature_kafka_ingress_next,
+        );
+
+        let mut deployment_changes = Vec::with_capacity(deployments.len());
+        for (deployment_metadata, discovered_services) in deployments {
+            deployment_changes.push(updater.apply_deployment(
+                deployment_metadata.clone(),
+                discovered_services.clone(),
+                force.force_enabled(),
+            )?);
+        }
+        for (service_name, changes) in services {
+            updater.apply_service_configuration(service_name.clone(), changes.clone())?;
+        }
+        let mut subscription_changes = Vec::with_capacity(subscriptions.len());
+        for subscription in subscriptions {
+            subscription_changes.push(updater.apply_subscription(
+                subscription.source.clone(),
+                subscription.sink.clone(),
+                subscription.options.clone(),
+                &self.subscription_validator,
+            )?);
+        }
+
+        Ok(ManifestPlan {
+            deployments: deployment_changes,
+            subscriptions: subscription_changes,
+            previous: schema_information,
+            current: updater.into_inner(),
+        })
+    }
+}
+
+fn into_deployment_metadata(
+    discovered_metadata: DiscoveredMetadata,
+) -> (DeploymentMetadata, Vec<endpoint_manifest::Service>) {
+    let deployment_metadata = match discovered_metadata.endpoint {
+        DiscoveredEndpoint::Http(uri, http_version) => DeploymentMetadata::new_http(
+            uri,
+            discovered_metadata.protocol_type,
+            http_version,
+            DeliveryOptions::new(discovered_metadata.headers),
+            discovered_metadata.supported_protocol_versions,
+        ),
+        DiscoveredEndpoint::Lambda(arn, assume_role_arn) => DeploymentMetadata::new_lambda(
+            arn,
+            assume_role_arn,
+            DeliveryOptions::new(discovered_metadata.headers),
+            discovered_metadata.supported_protocol_versions,
+        ),
+    };
+
+    (deployment_metadata, discovered_metadata.services)
 }
 
 /// Newtype for service names
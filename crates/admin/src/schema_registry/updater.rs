@@ -11,7 +11,7 @@
 use crate::schema_registry::error::{
     DeploymentError, SchemaError, ServiceError, SubscriptionError,
 };
-use crate::schema_registry::{ModifyServiceChange, ServiceName};
+use crate::schema_registry::{ManifestChange, ModifyServiceChange, ServiceName};
 use http::{HeaderValue, Uri};
 use restate_types::endpoint_manifest;
 use restate_types::identifiers::{DeploymentId, SubscriptionId};
@@ -23,15 +23,18 @@ use restate_types::schema::deployment::DeploymentSchemas;
 use restate_types::schema::invocation_target::{
     InputRules, InputValidationRule, InvocationTargetMetadata, OutputContentTypeRule, OutputRules,
     DEFAULT_IDEMPOTENCY_RETENTION, DEFAULT_WORKFLOW_COMPLETION_RETENTION,
+    ENFORCE_INPUT_SCHEMA_METADATA_KEY,
+};
+use restate_types::schema::service::{
+    HandlerSchemas, ServiceLocation, ServiceMetadata, ServiceSchemas,
 };
-use restate_types::schema::service::{HandlerSchemas, ServiceLocation, ServiceSchemas};
 use restate_types::schema::subscriptions::{
     EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Source, Subscription,
     SubscriptionValidator,
 };
 use restate_types::schema::Schema;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use tracing::{debug, info, warn};
 
 /// Responsible for updating the provided [`Schema`] with new
@@ -146,6 +149,7 @@ impl SchemaUpdater {
                         })
                         .collect::<Result<Vec<_>, _>>()?,
                     existing_service.location.public,
+                    existing_service.enforce_input_schema,
                 );
 
                 let removed_handlers: Vec<String> = existing_service
@@ -219,6 +223,7 @@ impl SchemaUpdater {
                             })
                             .collect::<Result<Vec<_>, _>>()?,
                         true,
+                        false,
                     ),
                     ty: service_type,
                     location: ServiceLocation {
@@ -233,6 +238,7 @@ impl SchemaUpdater {
                     },
                     inactivity_timeout: None,
                     abort_timeout: None,
+                    enforce_input_schema: false,
                     service_openapi_cache: Default::default(),
                     documentation: service.documentation,
                     metadata: service.metadata,
@@ -343,6 +349,7 @@ impl SchemaUpdater {
                         })
                         .collect::<Result<Vec<_>, _>>()?,
                     existing_service.location.public,
+                    existing_service.enforce_input_schema,
                 );
 
                 let removed_handlers: Vec<String> = existing_service
@@ -391,6 +398,7 @@ impl SchemaUpdater {
                             })
                             .collect::<Result<Vec<_>, _>>()?,
                         true,
+                        false,
                     ),
                     ty: service_type,
                     location: ServiceLocation {
@@ -405,6 +413,7 @@ impl SchemaUpdater {
                     },
                     inactivity_timeout: None,
                     abort_timeout: None,
+                    enforce_input_schema: false,
                     service_openapi_cache: Default::default(),
                     documentation: service.documentation,
                     metadata: service.metadata,
@@ -644,6 +653,36 @@ impl SchemaUpdater {
         }
     }
 
+    /// Removes the service from the schema, and from the services exposed by its deployments.
+    /// Fails if a subscription still targets the service.
+    pub fn remove_service(&mut self, name: &str) -> Result<(), SchemaError> {
+        if !self.schema_information.services.contains_key(name) {
+            return Err(SchemaError::NotFound(format!("service with name '{name}'")));
+        }
+
+        let mut subscriptions: Vec<_> = self
+            .schema_information
+            .subscriptions
+            .values()
+            .filter(|subscription| sink_service_name(subscription.sink()) == name)
+            .map(Subscription::id)
+            .collect();
+        if !subscriptions.is_empty() {
+            subscriptions.sort();
+            return Err(
+                ServiceError::TargetedBySubscriptions(name.to_owned(), subscriptions).into(),
+            );
+        }
+
+        self.schema_information.services.remove(name);
+        for deployment in self.schema_information.deployments.values_mut() {
+            deployment.services.retain(|service| service.name != name);
+        }
+        self.modified = true;
+
+        Ok(())
+    }
+
     pub fn modify_service(
         &mut self,
         name: String,
@@ -690,6 +729,13 @@ impl SchemaUpdater {
                     ModifyServiceChange::AbortTimeout(abort_timeout) => {
                         schemas.abort_timeout = Some(abort_timeout);
                     }
+                    ModifyServiceChange::EnforceInputSchema(enforce_input_schema) => {
+                        schemas.enforce_input_schema = enforce_input_schema;
+                        for h in schemas.handlers.values_mut() {
+                            h.target_meta.enforce_input_schema =
+                                handler_enforces_input_schema(&h.metadata, enforce_input_schema);
+                        }
+                    }
                 }
             }
         }
@@ -698,6 +744,161 @@ impl SchemaUpdater {
 
         Ok(())
     }
+
+    /// Restores the service-level configuration from a previous service metadata.
+    pub fn restore_service_configuration(
+        &mut self,
+        name: &str,
+        previous: &ServiceMetadata,
+    ) -> Result<(), SchemaError> {
+        let Some(schemas) = self.schema_information.services.get(name) else {
+            return Err(SchemaError::NotFound(format!("service with name '{name}'")));
+        };
+
+        let mut changes = vec![
+            ModifyServiceChange::Public(previous.public),
+            ModifyServiceChange::IdempotencyRetention(previous.idempotency_retention.into()),
+            ModifyServiceChange::EnforceInputSchema(previous.enforce_input_schema),
+        ];
+        if let Some(workflow_completion_retention) = previous.workflow_completion_retention {
+            if schemas.ty == ServiceType::Workflow {
+                changes.push(ModifyServiceChange::WorkflowCompletionRetention(
+                    workflow_completion_retention.into(),
+                ));
+            }
+        }
+        self.modify_service(name.to_owned(), changes)?;
+
+        // Timeouts can be unset, which modify_service doesn't support
+        let schemas = self
+            .schema_information
+            .services
+            .get_mut(name)
+            .expect("service exists");
+        schemas.inactivity_timeout = previous.inactivity_timeout.map(Into::into);
+        schemas.abort_timeout = previous.abort_timeout.map(Into::into);
+
+        Ok(())
+    }
+
+    /// Registers the deployment, unless exactly one deployment with the same endpoint, headers
+    /// and services is already registered. Existing deployments with the same endpoint are
+    /// overridden only if `force` is set, see [`Self::add_deployment`].
+    pub fn apply_deployment(
+        &mut self,
+        deployment_metadata: DeploymentMetadata,
+        services: Vec<endpoint_manifest::Service>,
+        force: bool,
+    ) -> Result<(DeploymentId, ManifestChange), SchemaError> {
+        let existing_deployments: Vec<_> = self
+            .schema_information
+            .find_existing_deployments_by_endpoint(&deployment_metadata.ty)
+            .map(|(id, schemas)| {
+                (
+                    *id,
+                    schemas.metadata.delivery_options.additional_headers
+                        == deployment_metadata.delivery_options.additional_headers
+                        && same_services(&schemas.services, &services),
+                )
+            })
+            .collect();
+
+        if let [(deployment_id, true)] = existing_deployments[..] {
+            return Ok((deployment_id, ManifestChange::Unchanged));
+        }
+
+        let change = if existing_deployments.is_empty() {
+            ManifestChange::Added
+        } else {
+            ManifestChange::Updated
+        };
+        let deployment_id = self.add_deployment(deployment_metadata, services, force)?;
+
+        Ok((deployment_id, change))
+    }
+
+    /// Applies only the changes that differ from the current service configuration.
+    pub fn apply_service_configuration(
+        &mut self,
+        name: String,
+        changes: Vec<ModifyServiceChange>,
+    ) -> Result<ManifestChange, SchemaError> {
+        let Some(schemas) = self.schema_information.services.get(&name) else {
+            return Err(SchemaError::NotFound(format!("service with name '{name}'")));
+        };
+
+        let changes: Vec<_> = changes
+            .into_iter()
+            .filter(|change| match change {
+                ModifyServiceChange::Public(public) => schemas.location.public != *public,
+                ModifyServiceChange::IdempotencyRetention(retention) => {
+                    schemas.idempotency_retention != *retention
+                }
+                ModifyServiceChange::WorkflowCompletionRetention(retention) => {
+                    schemas.workflow_completion_retention != Some(*retention)
+                }
+                ModifyServiceChange::InactivityTimeout(timeout) => {
+                    schemas.inactivity_timeout != Some(*timeout)
+                }
+                ModifyServiceChange::AbortTimeout(timeout) => {
+                    schemas.abort_timeout != Some(*timeout)
+                }
+                ModifyServiceChange::EnforceInputSchema(enforce_input_schema) => {
+                    schemas.enforce_input_schema != *enforce_input_schema
+                }
+            })
+            .collect();
+
+        if changes.is_empty() {
+            return Ok(ManifestChange::Unchanged);
+        }
+        self.modify_service(name, changes)?;
+
+        Ok(ManifestChange::Updated)
+    }
+
+    /// Adds the subscription, unless a subscription with the same source and sink exists. If
+    /// the existing subscription has different options, it's replaced keeping its id.
+    pub fn apply_subscription<V: SubscriptionValidator>(
+        &mut self,
+        source: Uri,
+        sink: Uri,
+        options: Option<HashMap<String, String>>,
+        validator: &V,
+    ) -> Result<(SubscriptionId, ManifestChange), SchemaError> {
+        let existing_subscription = self
+            .schema_information
+            .subscriptions
+            .values()
+            .find(|subscription| {
+                subscription.source().to_string() == source.to_string()
+                    && subscription.sink().to_string() == sink.to_string()
+            })
+            .map(|subscription| {
+                (
+                    subscription.id(),
+                    options.as_ref().is_none_or(|options| {
+                        options
+                            .iter()
+                            .all(|(key, value)| subscription.metadata().get(key) == Some(value))
+                    }),
+                )
+            });
+
+        match existing_subscription {
+            Some((subscription_id, true)) => Ok((subscription_id, ManifestChange::Unchanged)),
+            Some((subscription_id, false)) => {
+                self.remove_subscription(subscription_id);
+                self.add_subscription(Some(subscription_id), source, sink, options, validator)?;
+                Ok((subscription_id, ManifestChange::Updated))
+            }
+            None => {
+                let subscription_id =
+                    self.add_subscription(None, source, sink, options, validator)?;
+                Ok((subscription_id, ManifestChange::Added))
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -856,6 +1057,7 @@ impl DiscoveredHandlerMetadata {
     fn compute_handlers(
         handlers: Vec<DiscoveredHandlerMetadata>,
         public: bool,
+        enforce_input_schema: bool,
     ) -> HashMap<String, HandlerSchemas> {
         handlers
             .into_iter()
@@ -876,6 +1078,10 @@ impl DiscoveredHandlerMetadata {
                             target_ty: handler.ty,
                             input_rules: handler.input,
                             output_rules: handler.output,
+                            enforce_input_schema: handler_enforces_input_schema(
+                                &handler.metadata,
+                                enforce_input_schema,
+                            ),
                         },
                         documentation: handler.documentation,
                         metadata: handler.metadata,
@@ -886,6 +1092,60 @@ impl DiscoveredHandlerMetadata {
     }
 }
 
+/// The handler metadata overrides the service setting, when set.
+/// Compares the registered services with the discovered ones, by name and handler names.
+fn sink_service_name(sink: &Sink) -> &str {
+    match sink {
+        Sink::DeprecatedService { name, .. } => name,
+        Sink::Invocation {
+            event_invocation_target_template,
+        } => match event_invocation_target_template {
+            EventInvocationTargetTemplate::Service { name, .. }
+            | EventInvocationTargetTemplate::VirtualObject { name, .. }
+            | EventInvocationTargetTemplate::Workflow { name, .. } => name,
+        },
+    }
+}
+
+fn same_services(
+    registered: &[ServiceMetadata],
+    discovered: &[endpoint_manifest::Service],
+) -> bool {
+    let registered: BTreeMap<_, BTreeSet<_>> = registered
+        .iter()
+        .map(|service| {
+            (
+                service.name.clone(),
+                service.handlers.iter().map(|h| h.name.clone()).collect(),
+            )
+        })
+        .collect();
+    let discovered: BTreeMap<_, BTreeSet<_>> = discovered
+        .iter()
+        .map(|service| {
+            (
+                service.name.to_string(),
+                service
+                    .handlers
+                    .iter()
+                    .map(|h| h.name.to_string())
+                    .collect(),
+            )
+        })
+        .collect();
+    registered == discovered
+}
+
+fn handler_enforces_input_schema(
+    handler_metadata: &HashMap<String, String>,
+    service_enforces_input_schema: bool,
+) -> bool {
+    handler_metadata
+        .get(ENFORCE_INPUT_SCHEMA_METADATA_KEY)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(service_enforces_input_schema)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -971,6 +1231,76 @@ mod tests {
         schema.assert_service_handler(GREETER_SERVICE_NAME, "greet");
     }
 
+    #[test]
+    fn apply_deployment_and_service_configuration_is_idempotent() {
+        let deployment = Deployment::mock();
+        let mut updater = SchemaUpdater::default();
+        let (deployment_id, change) = updater
+            .apply_deployment(deployment.metadata.clone(), vec![greeter_service()], false)
+            .unwrap();
+        assert_eq!(change, ManifestChange::Added);
+        assert_eq!(
+            updater
+                .apply_service_configuration(
+                    GREETER_SERVICE_NAME.to_owned(),
+                    vec![ModifyServiceChange::Public(false)]
+                )
+                .unwrap(),
+            ManifestChange::Updated
+        );
+        let schema = updater.into_inner();
+        let version = schema.version();
+
+        let mut updater = SchemaUpdater::new(schema, false);
+        assert_eq!(
+            updater
+                .apply_deployment(deployment.metadata.clone(), vec![greeter_service()], false)
+                .unwrap(),
+            (deployment_id, ManifestChange::Unchanged)
+        );
+        assert_eq!(
+            updater
+                .apply_service_configuration(
+                    GREETER_SERVICE_NAME.to_owned(),
+                    vec![ModifyServiceChange::Public(false)]
+                )
+                .unwrap(),
+            ManifestChange::Unchanged
+        );
+        assert_eq!(updater.into_inner().version(), version);
+
+        let mut updater = SchemaUpdater::default();
+        // Changing the services of a registered endpoint requires force
+        let (deployment_id, _) = updater
+            .apply_deployment(deployment.metadata.clone(), vec![greeter_service()], false)
+            .unwrap();
+        assert!(let SchemaError::Override(_) = updater
+            .apply_deployment(
+                deployment.metadata.clone(),
+                vec![greeter_service(), another_greeter_service()],
+                false
+            )
+            .unwrap_err());
+        assert_eq!(
+            updater
+                .apply_deployment(
+                    deployment.metadata.clone(),
+                    vec![greeter_service(), another_greeter_service()],
+                    true,
+                )
+                .unwrap(),
+            (deployment_id, ManifestChange::Updated)
+        );
+
+        let mut updater = SchemaUpdater::default();
+        assert!(let SchemaError::NotFound(_) = updater
+            .apply_service_configuration(
+                GREETER_SERVICE_NAME.to_owned(),
+                vec![ModifyServiceChange::Public(false)]
+            )
+            .unwrap_err());
+    }
+
     #[test]
     fn register_new_deployment_add_unregistered_service() {
         let mut updater = SchemaUpdater::default();
@@ -1186,6 +1516,39 @@ mod tests {
         assert!(schemas.get_deployment(&deployment_1.id).is_none());
     }
 
+    #[test]
+    fn remove_service() {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment = Deployment::mock_with_uri("http://localhost:9080");
+        let deployment_id = updater
+            .add_deployment(
+                deployment.metadata.clone(),
+                vec![greeter_service(), another_greeter_service()],
+                false,
+            )
+            .unwrap();
+        let schemas = updater.into_inner();
+
+        let version_before_removal = schemas.version();
+        updater = SchemaUpdater::new(schemas, false);
+        updater.remove_service(GREETER_SERVICE_NAME).unwrap();
+        assert!(matches!(
+            updater.remove_service(GREETER_SERVICE_NAME),
+            Err(SchemaError::NotFound(_))
+        ));
+        let schemas = updater.into_inner();
+
+        assert!(version_before_removal < schemas.version());
+        assert!(schemas
+            .resolve_latest_service(GREETER_SERVICE_NAME)
+            .is_none());
+        schemas.assert_service_deployment(ANOTHER_GREETER_SERVICE_NAME, deployment_id);
+        let (_, services) = schemas.get_deployment_and_services(&deployment_id).unwrap();
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].name, ANOTHER_GREETER_SERVICE_NAME);
+    }
+
     mod remove_method {
         use super::*;
 
@@ -72,12 +72,19 @@ where
     ) -> anyhow::Result<()> {
         let opts = updateable_config.live_load();
 
-        let rest_state = state::AdminServiceState::new(self.schema_registry, self.bifrost);
+        let rest_state = state::AdminServiceState::new(
+            self.schema_registry,
+            self.bifrost,
+            self.query_context.clone(),
+        );
 
         let router = self
             .query_context
             .map(|query_context| {
-                let query_state = Arc::new(state::QueryServiceState { query_context });
+                let query_state = Arc::new(state::QueryServiceState {
+                    query_context,
+                    running_queries: Default::default(),
+                });
 
                 axum::Router::new().merge(storage_query::create_router(query_state))
             })
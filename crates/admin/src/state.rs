@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use crate::schema_registry::SchemaRegistry;
+use crate::storage_query::RunningQueries;
 use restate_bifrost::Bifrost;
 use restate_storage_query_datafusion::context::QueryContext;
 
@@ -16,18 +17,25 @@ use restate_storage_query_datafusion::context::QueryContext;
 pub struct AdminServiceState<V> {
     pub schema_registry: SchemaRegistry<V>,
     pub bifrost: Bifrost,
+    pub query_context: Option<QueryContext>,
 }
 
 #[derive(Clone)]
 pub struct QueryServiceState {
     pub query_context: QueryContext,
+    pub running_queries: RunningQueries,
 }
 
 impl<V> AdminServiceState<V> {
-    pub fn new(schema_registry: SchemaRegistry<V>, bifrost: Bifrost) -> Self {
+    pub fn new(
+        schema_registry: SchemaRegistry<V>,
+        bifrost: Bifrost,
+        query_context: Option<QueryContext>,
+    ) -> Self {
         Self {
             schema_registry,
             bifrost,
+            query_context,
         }
     }
 }
@@ -19,12 +19,18 @@ use okapi_operation::{okapi, Components, ToMediaTypes, ToResponses};
 use schemars::JsonSchema;
 use serde::Serialize;
 
+use super::running_queries::QueryId;
+
 /// This error is used by handlers to propagate API errors,
 /// and later converted to a response through the IntoResponse implementation
 #[derive(Debug, thiserror::Error)]
 pub enum StorageQueryError {
     #[error("datafusion failed: {0}")]
     DataFusion(#[from] DataFusionError),
+    #[error("query {0} not found, it might have already completed")]
+    QueryNotFound(QueryId),
+    #[error("invalid page: {0}")]
+    InvalidPage(String),
 }
 
 /// # Error description response
@@ -37,7 +43,11 @@ struct ErrorDescriptionResponse {
 
 impl IntoResponse for StorageQueryError {
     fn into_response(self) -> Response {
-        let status_code = StatusCode::INTERNAL_SERVER_ERROR;
+        let status_code = match &self {
+            StorageQueryError::DataFusion(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            StorageQueryError::QueryNotFound(_) => StatusCode::NOT_FOUND,
+            StorageQueryError::InvalidPage(_) => StatusCode::BAD_REQUEST,
+        };
 
         (
             status_code,
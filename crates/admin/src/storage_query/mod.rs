@@ -11,15 +11,20 @@
 mod convert;
 mod error;
 mod query;
+mod running_queries;
 
-use axum::{routing::post, Router};
+use axum::routing::{delete, post};
+use axum::Router;
 use std::sync::Arc;
 
 use crate::state::QueryServiceState;
 
+pub use running_queries::RunningQueries;
+
 pub fn create_router(state: Arc<QueryServiceState>) -> Router<()> {
     // Setup the router
     axum::Router::new()
         .route("/query", post(query::query))
+        .route("/query/:query_id", delete(query::cancel_query))
         .with_state(state)
 }
@@ -9,22 +9,29 @@
 // by the Apache License, Version 2.0.
 
 use std::io::Write;
+use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Response};
 use axum::{http, Extension, Json};
 use bytes::Bytes;
-use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::datatypes::{DataType, Schema};
 use datafusion::arrow::ipc::writer::StreamWriter;
 use datafusion::arrow::json::writer::JsonArray;
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::display::array_value_to_string;
+use datafusion::common::ScalarValue;
 use datafusion::error::DataFusionError;
 use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::prelude::{ident, lit, DataFrame};
 use futures::{ready, Stream, StreamExt, TryStreamExt};
-use http::{HeaderMap, HeaderValue};
+use http::{HeaderMap, HeaderValue, StatusCode};
 use http_body::Frame;
 use http_body_util::StreamBody;
 use okapi_operation::*;
@@ -36,8 +43,12 @@ use serde_with::serde_as;
 
 use super::convert::{ConvertRecordBatchStream, V1_CONVERTER};
 use super::error::StorageQueryError;
+use super::running_queries::{GuardedRecordBatchStream, QueryId};
 use crate::state::QueryServiceState;
 
+pub(super) const QUERY_ID_HEADER: &str = "x-restate-query-id";
+pub(super) const NEXT_PAGE_AFTER_HEADER: &str = "x-restate-query-next-page-after";
+
 #[serde_as]
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct QueryRequest {
@@ -47,12 +58,68 @@ pub struct QueryRequest {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[schemars(with = "String")]
     pub query: String,
+
+    /// # Limit
+    ///
+    /// Maximum number of rows returned by the query. Rows exceeding the limit are not returned.
+    #[serde(default)]
+    pub limit: Option<usize>,
+
+    /// # Timeout
+    ///
+    /// Maximum duration of the query, after which the query fails.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
+    #[serde(default)]
+    #[serde_as(as = "Option<restate_serde_util::DurationString>")]
+    #[schemars(with = "Option<String>")]
+    pub timeout: Option<Duration>,
+
+    /// # Memory limit
+    ///
+    /// Maximum memory in bytes the query can use. This is enforced in addition to the
+    /// memory limit shared by all the queries, configured with `admin.query-engine.memory-size`.
+    #[serde(default)]
+    pub memory_limit: Option<NonZeroUsize>,
+
+    /// # Page
+    ///
+    /// Return a single page of the query results, using keyset pagination.
+    #[serde(default)]
+    pub page: Option<QueryPage>,
+}
+
+/// # Query page
+///
+/// Keyset pagination over the results of a query. Rows are ordered by the `key` column, and
+/// the next page can be requested by setting `after` to the value of the
+/// `x-restate-query-next-page-after` response header, which is the key of the last row of the
+/// current page. The header is missing on the last page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct QueryPage {
+    /// # Key
+    ///
+    /// Name of the column used as pagination key. Its values must be unique and not null,
+    /// for example `id` when listing `sys_invocation`. Strings, integers and timestamps
+    /// columns are supported.
+    pub key: String,
+
+    /// # Size
+    ///
+    /// Maximum number of rows in the page.
+    pub size: usize,
+
+    /// # After
+    ///
+    /// Return only rows with a key greater than this value.
+    #[serde(default)]
+    pub after: Option<String>,
 }
 
 /// Query storage
 #[openapi(
     summary = "Query storage",
-    description = "Query the storage API",
+    description = "Query the storage API. The id of the query, which can be used to cancel it, is returned in the `x-restate-query-id` header. When requesting a page of the results and more rows are left, the value of `page.after` for requesting the next page is returned in the `x-restate-query-next-page-after` header.",
     operation_id = "query",
     tags = "storage",
     responses(ignore_return_type = true, from_type = "StorageQueryError")
@@ -63,7 +130,40 @@ pub async fn query(
     headers: HeaderMap,
     #[request_body(required = true)] Json(payload): Json<QueryRequest>,
 ) -> Result<impl IntoResponse, StorageQueryError> {
-    let record_batch_stream = state.query_context.execute(&payload.query).await?;
+    let running_query = state.running_queries.register();
+    let query_id = running_query.query_id();
+
+    let mut df = state.query_context.dataframe(&payload.query).await?;
+    let mut page_key = None;
+    if let Some(page) = payload.page {
+        let (paged_df, key, size) = apply_page(df, page, payload.limit)?;
+        df = paged_df;
+        page_key = Some((key, size));
+    } else if let Some(limit) = payload.limit {
+        df = df.limit(0, Some(limit))?;
+    }
+
+    let record_batch_stream = state
+        .query_context
+        .execute_dataframe(df, payload.memory_limit)
+        .await?;
+    let mut record_batch_stream: SendableRecordBatchStream = Box::pin(
+        GuardedRecordBatchStream::new(record_batch_stream, running_query, payload.timeout),
+    );
+
+    let mut next_page_after = None;
+    if let Some((key, size)) = page_key {
+        // Pages are bounded by their size, so they can be buffered to find out the key of
+        // their last row before responding.
+        let schema = record_batch_stream.schema();
+        let batches: Vec<RecordBatch> = record_batch_stream.try_collect().await?;
+        let (batches, after) = split_page(batches, &key, size)?;
+        next_page_after = after;
+        record_batch_stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(batches.into_iter().map(Ok)),
+        ));
+    }
 
     let record_batch_stream: SendableRecordBatchStream = match version {
         AdminApiVersion::V1 => Box::pin(ConvertRecordBatchStream::new(
@@ -89,12 +189,165 @@ pub async fn query(
         ),
     };
 
-    Ok(Response::builder()
+    let mut response = Response::builder()
         .header(http::header::CONTENT_TYPE, content_type)
+        .header(QUERY_ID_HEADER, query_id);
+    if let Some(after) = next_page_after {
+        let after = HeaderValue::try_from(after).map_err(|_| {
+            StorageQueryError::InvalidPage(
+                "the page key can't be represented as a header value".to_owned(),
+            )
+        })?;
+        response = response.header(NEXT_PAGE_AFTER_HEADER, after);
+    }
+
+    Ok(response
         .body(StreamBody::new(result_stream))
         .expect("content-type header is correct"))
 }
 
+/// Sorts the query results by the page key and keeps the rows of the requested page, plus one
+/// to know whether there is a next page. Returns the key column and the size of the page.
+fn apply_page(
+    mut df: DataFrame,
+    page: QueryPage,
+    limit: Option<usize>,
+) -> Result<(DataFrame, String, usize), StorageQueryError> {
+    if page.size == 0 {
+        return Err(StorageQueryError::InvalidPage(
+            "the page size must be greater than 0".to_owned(),
+        ));
+    }
+    let Ok(key_field) = df.schema().field_with_unqualified_name(&page.key) else {
+        return Err(StorageQueryError::InvalidPage(format!(
+            "the query results have no column '{}'",
+            page.key
+        )));
+    };
+    let key_type = key_field.data_type().clone();
+    if !is_supported_page_key(&key_type) {
+        return Err(StorageQueryError::InvalidPage(format!(
+            "the column '{}' of type {key_type} can't be used as page key, only strings, \
+             integers and timestamps are supported",
+            page.key
+        )));
+    }
+
+    let key = ident(&page.key);
+    if let Some(after) = page.after {
+        df = df.filter(key.clone().gt(lit(page_key_value(after, &key_type)?)))?;
+    }
+    let size = page.size.min(limit.unwrap_or(usize::MAX));
+    // Fetch one more row to know whether there is a next page
+    df = df
+        .sort(vec![key.sort(true, false)])?
+        .limit(0, Some(size.saturating_add(1)))?;
+    Ok((df, page.key, size))
+}
+
+fn is_supported_page_key(data_type: &DataType) -> bool {
+    data_type.is_integer()
+        || matches!(
+            data_type,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View | DataType::Timestamp(_, _)
+        )
+}
+
+/// Parses the `after` value of a page into a value of the key column type, so that keys are
+/// compared by their type, e.g. numerically for integers.
+fn page_key_value(after: String, key_type: &DataType) -> Result<ScalarValue, StorageQueryError> {
+    let invalid = || {
+        StorageQueryError::InvalidPage(format!(
+            "'{after}' is not a valid page key of type {key_type}"
+        ))
+    };
+    let value = match key_type {
+        // See page_key_cursor
+        DataType::Timestamp(_, _) => {
+            ScalarValue::Int64(Some(after.parse().map_err(|_| invalid())?))
+        }
+        _ => ScalarValue::Utf8(Some(after.clone())),
+    };
+    match value.cast_to(key_type) {
+        Ok(value) if !value.is_null() => Ok(value),
+        _ => Err(invalid()),
+    }
+}
+
+/// Formats the page key of the given row, to be parsed back by [`page_key_value`].
+fn page_key_cursor(column: &ArrayRef, row: usize) -> Result<String, DataFusionError> {
+    match column.data_type() {
+        // Formatted timestamps don't always parse back to the same value, hence they're
+        // written as their raw value in the unit of the column
+        DataType::Timestamp(_, _) => Ok(ScalarValue::try_from_array(column, row)?
+            .cast_to(&DataType::Int64)?
+            .to_string()),
+        _ => Ok(array_value_to_string(column, row)?),
+    }
+}
+
+/// Truncates the rows to the page size. If rows are left over, returns the key of the last row
+/// of the page, which is where the next page starts after.
+fn split_page(
+    batches: Vec<RecordBatch>,
+    key: &str,
+    size: usize,
+) -> Result<(Vec<RecordBatch>, Option<String>), DataFusionError> {
+    let total_rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+    if total_rows <= size {
+        return Ok((batches, None));
+    }
+
+    let mut remaining = size;
+    let mut page = Vec::with_capacity(batches.len());
+    for batch in batches {
+        if remaining == 0 {
+            break;
+        }
+        if batch.num_rows() == 0 {
+            continue;
+        }
+        let rows = batch.num_rows().min(remaining);
+        page.push(batch.slice(0, rows));
+        remaining -= rows;
+    }
+
+    let after = match page.last() {
+        Some(last_batch) => {
+            let column = last_batch.column_by_name(key).ok_or_else(|| {
+                DataFusionError::Execution(format!("page key column '{key}' is missing"))
+            })?;
+            Some(page_key_cursor(column, last_batch.num_rows() - 1)?)
+        }
+        None => None,
+    };
+    Ok((page, after))
+}
+
+/// Cancel query
+#[openapi(
+    summary = "Cancel query",
+    description = "Cancel a running query",
+    operation_id = "cancel_query",
+    tags = "storage",
+    parameters(path(
+        name = "query_id",
+        description = "Query identifier, as returned in the x-restate-query-id header.",
+        schema = "u64"
+    )),
+    responses(ignore_return_type = true, from_type = "StorageQueryError")
+)]
+pub async fn cancel_query(
+    State(state): State<Arc<QueryServiceState>>,
+    Path(query_id): Path<QueryId>,
+) -> Result<StatusCode, StorageQueryError> {
+    if state.running_queries.cancel(query_id) {
+        Ok(StatusCode::ACCEPTED)
+    } else {
+        Err(StorageQueryError::QueryNotFound(query_id))
+    }
+}
+
 trait RecordBatchWriter
 where
     Self: Sized,
@@ -233,3 +486,146 @@ impl<W: RecordBatchWriter + Unpin> Stream for WriteRecordBatchStream<W> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::array::{
+        BooleanArray, Int64Array, StringArray, TimestampMillisecondArray,
+    };
+    use datafusion::prelude::SessionContext;
+
+    fn batch(ids: &[&str]) -> RecordBatch {
+        RecordBatch::try_from_iter([("id", Arc::new(StringArray::from(ids.to_vec())) as ArrayRef)])
+            .unwrap()
+    }
+
+    fn ids(batches: &[RecordBatch]) -> Vec<String> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let column = batch.column_by_name("id").unwrap();
+                (0..batch.num_rows()).map(|row| array_value_to_string(column, row).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn last_page_has_no_next_page() {
+        let (page, after) = split_page(vec![batch(&["a", "b"]), batch(&["c"])], "id", 3).unwrap();
+
+        assert_eq!(ids(&page), ["a", "b", "c"]);
+        assert_eq!(after, None);
+    }
+
+    #[test]
+    fn truncates_page_across_batches() {
+        let (page, after) = split_page(
+            vec![batch(&["a", "b"]), batch(&[]), batch(&["c", "d"])],
+            "id",
+            3,
+        )
+        .unwrap();
+
+        assert_eq!(ids(&page), ["a", "b", "c"]);
+        assert_eq!(after.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn truncates_page_at_batch_boundary() {
+        let (page, after) = split_page(vec![batch(&["a", "b"]), batch(&["c"])], "id", 2).unwrap();
+
+        assert_eq!(ids(&page), ["a", "b"]);
+        assert_eq!(after.as_deref(), Some("b"));
+    }
+
+    fn numbers(numbers: impl IntoIterator<Item = i64>) -> DataFrame {
+        let batch = RecordBatch::try_from_iter([(
+            "number",
+            Arc::new(Int64Array::from_iter_values(numbers)) as ArrayRef,
+        )])
+        .unwrap();
+        SessionContext::new().read_batch(batch).unwrap()
+    }
+
+    fn page(key: &str, size: usize, after: Option<&str>) -> QueryPage {
+        QueryPage {
+            key: key.to_owned(),
+            size,
+            after: after.map(ToOwned::to_owned),
+        }
+    }
+
+    fn column_numbers(batches: &[RecordBatch]) -> Vec<i64> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column_by_name("number")
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[restate_core::test]
+    async fn pages_numeric_key_numerically() {
+        let (df, key, size) =
+            apply_page(numbers((1..=12).rev()), page("number", 3, None), None).unwrap();
+        let (first_page, after) = split_page(df.collect().await.unwrap(), &key, size).unwrap();
+        assert_eq!(column_numbers(&first_page), [1, 2, 3]);
+        let after = after.unwrap();
+        assert_eq!(after, "3");
+
+        // Compared as strings, "10", "11" and "12" would come before "3"
+        let (df, _, _) =
+            apply_page(numbers(1..=12), page("number", 10, Some(&after)), None).unwrap();
+        assert_eq!(
+            column_numbers(&df.collect().await.unwrap()),
+            [4, 5, 6, 7, 8, 9, 10, 11, 12]
+        );
+    }
+
+    #[restate_core::test]
+    async fn rejects_invalid_numeric_page_key() {
+        assert!(matches!(
+            apply_page(numbers(1..=12), page("number", 3, Some("abc")), None),
+            Err(StorageQueryError::InvalidPage(_))
+        ));
+    }
+
+    #[restate_core::test]
+    async fn rejects_unsupported_page_key_type() {
+        let batch = RecordBatch::try_from_iter([(
+            "flag",
+            Arc::new(BooleanArray::from(vec![true, false])) as ArrayRef,
+        )])
+        .unwrap();
+        let df = SessionContext::new().read_batch(batch).unwrap();
+
+        assert!(matches!(
+            apply_page(df, page("flag", 1, None), None),
+            Err(StorageQueryError::InvalidPage(_))
+        ));
+    }
+
+    #[test]
+    fn timestamp_page_key_round_trips() {
+        let column = Arc::new(
+            TimestampMillisecondArray::from(vec![1_736_000_000_123]).with_timezone("+00:00"),
+        ) as ArrayRef;
+
+        let cursor = page_key_cursor(&column, 0).unwrap();
+
+        assert_eq!(cursor, "1736000000123");
+        assert_eq!(
+            page_key_value(cursor, column.data_type()).unwrap(),
+            ScalarValue::try_from_array(&column, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream};
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use tokio::time::Sleep;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+pub type QueryId = u64;
+
+/// Keeps track of the queries currently executed by this admin server, so they can be cancelled.
+#[derive(Clone, Default)]
+pub struct RunningQueries {
+    inner: Arc<RunningQueriesInner>,
+}
+
+#[derive(Default)]
+struct RunningQueriesInner {
+    queries: Mutex<HashMap<QueryId, CancellationToken>>,
+}
+
+impl RunningQueries {
+    /// Registers a new query under a random id, so that the ids of other clients' queries can't
+    /// be guessed.
+    pub(super) fn register(&self) -> RunningQuery {
+        let cancellation_token = CancellationToken::new();
+        let mut queries = self.inner.queries.lock();
+        let query_id = loop {
+            if let Entry::Vacant(entry) = queries.entry(rand::random()) {
+                let query_id = *entry.key();
+                entry.insert(cancellation_token.clone());
+                break query_id;
+            }
+        };
+        drop(queries);
+
+        RunningQuery {
+            query_id,
+            cancellation_token,
+            running_queries: self.clone(),
+        }
+    }
+
+    /// Returns `false` if there is no running query with the given id.
+    pub(super) fn cancel(&self, query_id: QueryId) -> bool {
+        match self.inner.queries.lock().remove(&query_id) {
+            Some(cancellation_token) => {
+                cancellation_token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Registration of a running query, removed from [`RunningQueries`] when dropped.
+pub(super) struct RunningQuery {
+    query_id: QueryId,
+    cancellation_token: CancellationToken,
+    running_queries: RunningQueries,
+}
+
+impl RunningQuery {
+    pub(super) fn query_id(&self) -> QueryId {
+        self.query_id
+    }
+}
+
+impl Drop for RunningQuery {
+    fn drop(&mut self) {
+        self.running_queries
+            .inner
+            .queries
+            .lock()
+            .remove(&self.query_id);
+    }
+}
+
+/// Fails the wrapped stream when the query is cancelled or exceeds its timeout.
+///
+/// Dropping the stream, e.g. because the client disconnected, drops the underlying
+/// DataFusion stream which stops the execution of the query.
+pub(super) struct GuardedRecordBatchStream {
+    inner: SendableRecordBatchStream,
+    running_query: RunningQuery,
+    cancelled: Pin<Box<WaitForCancellationFutureOwned>>,
+    timeout: Option<(Duration, Pin<Box<Sleep>>)>,
+    done: bool,
+}
+
+impl GuardedRecordBatchStream {
+    pub(super) fn new(
+        inner: SendableRecordBatchStream,
+        running_query: RunningQuery,
+        timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            inner,
+            cancelled: Box::pin(running_query.cancellation_token.clone().cancelled_owned()),
+            running_query,
+            timeout: timeout.map(|timeout| (timeout, Box::pin(tokio::time::sleep(timeout)))),
+            done: false,
+        }
+    }
+}
+
+impl RecordBatchStream for GuardedRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Stream for GuardedRecordBatchStream {
+    type Item = Result<RecordBatch, DataFusionError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        if self.cancelled.as_mut().poll(cx).is_ready() {
+            self.done = true;
+            return Poll::Ready(Some(Err(DataFusionError::Execution(format!(
+                "query {} was cancelled",
+                self.running_query.query_id
+            )))));
+        }
+
+        if let Some((timeout, sleep)) = self.timeout.as_mut() {
+            if sleep.as_mut().poll(cx).is_ready() {
+                let timeout = *timeout;
+                self.done = true;
+                return Poll::Ready(Some(Err(DataFusionError::Execution(format!(
+                    "query {} exceeded its timeout of {timeout:?}",
+                    self.running_query.query_id
+                )))));
+            }
+        }
+
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::datatypes::Schema;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+
+    fn pending_stream() -> SendableRecordBatchStream {
+        Box::pin(RecordBatchStreamAdapter::new(
+            Arc::new(Schema::empty()),
+            futures::stream::pending(),
+        ))
+    }
+
+    fn is_running(running_queries: &RunningQueries, query_id: QueryId) -> bool {
+        running_queries.inner.queries.lock().contains_key(&query_id)
+    }
+
+    #[test]
+    fn registers_queries_until_dropped() {
+        let running_queries = RunningQueries::default();
+
+        let first = running_queries.register();
+        let second = running_queries.register();
+        assert_ne!(first.query_id(), second.query_id());
+        assert!(is_running(&running_queries, first.query_id()));
+
+        let query_id = first.query_id();
+        drop(first);
+        assert!(!is_running(&running_queries, query_id));
+        assert!(!running_queries.cancel(query_id));
+        assert!(is_running(&running_queries, second.query_id()));
+    }
+
+    #[tokio::test]
+    async fn cancel_fails_the_stream() {
+        let running_queries = RunningQueries::default();
+        let running_query = running_queries.register();
+        let query_id = running_query.query_id();
+        let mut stream = GuardedRecordBatchStream::new(pending_stream(), running_query, None);
+
+        assert!(running_queries.cancel(query_id));
+        assert!(!is_running(&running_queries, query_id));
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("cancelled"), "{err}");
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn timeout_fails_the_stream() {
+        let running_queries = RunningQueries::default();
+        let mut stream = GuardedRecordBatchStream::new(
+            pending_stream(),
+            running_queries.register(),
+            Some(Duration::from_secs(1)),
+        );
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("timeout"), "{err}");
+        assert!(stream.next().await.is_none());
+    }
+}
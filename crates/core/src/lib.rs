@@ -12,8 +12,10 @@ mod error;
 mod metadata;
 pub mod metadata_store;
 mod metric_definitions;
+pub mod metric_labels;
 pub mod network;
 pub mod partitions;
+pub mod payload_store;
 pub mod protobuf;
 pub mod task_center;
 pub mod worker_api;
@@ -0,0 +1,171 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Service and handler labels of the invocation metrics.
+//!
+//! Labelling the invocation metrics by service and handler is opt-in, see
+//! [`CommonOptions::metrics_service_labels`]. Every distinct label value creates a new time
+//! series, so the number of distinct services and handlers getting their own label values is
+//! bounded by [`CommonOptions::metrics_service_labels_limit`]. Whatever exceeds the limit is
+//! labelled as [`OVERFLOW_LABEL_VALUE`].
+//!
+//! [`CommonOptions::metrics_service_labels`]: restate_types::config::CommonOptions::metrics_service_labels
+//! [`CommonOptions::metrics_service_labels_limit`]: restate_types::config::CommonOptions::metrics_service_labels_limit
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, RwLock};
+
+use metrics::Label;
+
+use restate_types::config::Configuration;
+
+pub const SERVICE_LABEL: &str = "rpc.service";
+pub const HANDLER_LABEL: &str = "rpc.method";
+/// Label value of the services and handlers exceeding the cardinality limit.
+pub const OVERFLOW_LABEL_VALUE: &str = "_other";
+
+static ADMITTED: LazyLock<AdmittedLabels> = LazyLock::new(AdmittedLabels::default);
+
+/// Returns the service and handler labels of an invocation metric, or no labels if the service
+/// labels are disabled.
+pub fn service_handler_labels(service: &str, handler: &str) -> Vec<Label> {
+    let Some(limit) = enabled_limit() else {
+        return Vec::new();
+    };
+
+    if ADMITTED.admit(service, Some(handler), limit) {
+        vec![
+            Label::new(SERVICE_LABEL, service.to_owned()),
+            Label::new(HANDLER_LABEL, handler.to_owned()),
+        ]
+    } else if ADMITTED.admit(service, None, limit) {
+        vec![
+            Label::new(SERVICE_LABEL, service.to_owned()),
+            Label::new(HANDLER_LABEL, OVERFLOW_LABEL_VALUE),
+        ]
+    } else {
+        vec![
+            Label::new(SERVICE_LABEL, OVERFLOW_LABEL_VALUE),
+            Label::new(HANDLER_LABEL, OVERFLOW_LABEL_VALUE),
+        ]
+    }
+}
+
+/// Returns the service label of an invocation metric, or no labels if the service labels are
+/// disabled.
+pub fn service_labels(service: &str) -> Vec<Label> {
+    let Some(limit) = enabled_limit() else {
+        return Vec::new();
+    };
+
+    if ADMITTED.admit(service, None, limit) {
+        vec![Label::new(SERVICE_LABEL, service.to_owned())]
+    } else {
+        vec![Label::new(SERVICE_LABEL, OVERFLOW_LABEL_VALUE)]
+    }
+}
+
+fn enabled_limit() -> Option<usize> {
+    let config = Configuration::pinned();
+    config
+        .common
+        .metrics_service_labels
+        .then(|| config.common.metrics_service_labels_limit.get())
+}
+
+/// The services and handlers which got their own label values so far.
+///
+/// Once admitted, a label value is kept for the lifetime of the process, so that the time series
+/// of a service don't flip between its own label value and the overflow one.
+#[derive(Debug, Default)]
+struct AdmittedLabels {
+    inner: RwLock<AdmittedLabelsInner>,
+}
+
+#[derive(Debug, Default)]
+struct AdmittedLabelsInner {
+    handlers: HashMap<String, HashSet<String>>,
+    /// Number of admitted services plus admitted handlers
+    len: usize,
+}
+
+impl AdmittedLabelsInner {
+    fn contains(&self, service: &str, handler: Option<&str>) -> bool {
+        self.handlers
+            .get(service)
+            .is_some_and(|handlers| handler.is_none_or(|handler| handlers.contains(handler)))
+    }
+}
+
+impl AdmittedLabels {
+    /// Returns whether the service, or the handler of the service if given, has its own label
+    /// values. Admits it if there is room left below the limit.
+    fn admit(&self, service: &str, handler: Option<&str>, limit: usize) -> bool {
+        if self
+            .inner
+            .read()
+            .expect("lock not poisoned")
+            .contains(service, handler)
+        {
+            return true;
+        }
+
+        let mut inner = self.inner.write().expect("lock not poisoned");
+        if inner.contains(service, handler) {
+            return true;
+        }
+
+        let service_admitted = inner.handlers.contains_key(service);
+        let required = usize::from(!service_admitted) + usize::from(handler.is_some());
+        if inner.len + required > limit {
+            return false;
+        }
+
+        let handlers = inner.handlers.entry(service.to_owned()).or_default();
+        if let Some(handler) = handler {
+            handlers.insert(handler.to_owned());
+        }
+        inner.len += required;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admits_up_to_the_limit() {
+        let admitted = AdmittedLabels::default();
+
+        // service + handler
+        assert!(admitted.admit("Greeter", Some("greet"), 3));
+        assert!(admitted.admit("Greeter", Some("greet"), 3));
+        assert!(admitted.admit("Greeter", None, 3));
+        // one more handler
+        assert!(admitted.admit("Greeter", Some("farewell"), 3));
+
+        // no room left
+        assert!(!admitted.admit("Greeter", Some("hello"), 3));
+        assert!(!admitted.admit("Counter", None, 3));
+
+        // already admitted ones keep their labels
+        assert!(admitted.admit("Greeter", Some("farewell"), 3));
+    }
+
+    #[test]
+    fn admits_service_without_handlers() {
+        let admitted = AdmittedLabels::default();
+
+        assert!(admitted.admit("Greeter", None, 2));
+        assert!(admitted.admit("Greeter", Some("greet"), 2));
+        assert!(!admitted.admit("Counter", Some("add"), 2));
+    }
+}
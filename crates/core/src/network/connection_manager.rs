@@ -31,6 +31,7 @@ use restate_types::{GenerationalNodeId, NodeId, PlainNodeId, Version};
 
 use super::connection::{OwnedConnection, WeakConnection};
 use super::error::{NetworkError, ProtocolError};
+use super::fault_injection;
 use super::handshake::wait_for_welcome;
 use super::metric_definitions::{
     self, CONNECTION_DROPPED, INCOMING_CONNECTION, MESSAGE_PROCESSING_DURATION, MESSAGE_RECEIVED,
@@ -216,6 +217,11 @@ impl<T: TransportConnect> ConnectionManager<T> {
 
         self.verify_node_id(peer_node_id, &header, &nodes_config)?;
 
+        #[cfg(any(test, feature = "fault-injection"))]
+        if fault_injection::is_partitioned_from(peer_node_id.as_plain()) {
+            return Err(ProtocolError::HandshakeFailed("network partition injected").into());
+        }
+
         let (tx, output_stream) =
             mpsc::channel(self.networking_options.outbound_queue_length.into());
         let output_stream = ReceiverStream::new(output_stream);
@@ -283,6 +289,13 @@ impl<T: TransportConnect> ConnectionManager<T> {
             return self.connect_loopback();
         }
 
+        #[cfg(any(test, feature = "fault-injection"))]
+        if fault_injection::is_partitioned_from(node_id.as_plain()) {
+            return Err(NetworkError::Unavailable(format!(
+                "network partition from {node_id} injected"
+            )));
+        }
+
         let my_node_id = self.metadata.my_node_id();
         let nodes_config = self.metadata.nodes_config_snapshot();
         let cluster_name = nodes_config.cluster_name().to_owned();
@@ -499,6 +512,9 @@ where
     Span::current().record("task_id", tracing::field::display(current_task.id()));
     let mut cancellation = std::pin::pin!(current_task.cancellation_token().cancelled());
     let mut seen_versions = MetadataVersions::default();
+    #[cfg(any(test, feature = "fault-injection"))]
+    let mut incoming_faults = (connection.peer != metadata.my_node_id())
+        .then(|| fault_injection::IncomingMessageFaults::new(connection.peer.as_plain()));
 
     // Receive loop
     loop {
@@ -526,6 +542,13 @@ where
         };
 
         MESSAGE_RECEIVED.increment(1);
+        #[cfg(any(test, feature = "fault-injection"))]
+        if let Some(incoming_faults) = &mut incoming_faults {
+            if !incoming_faults.admit().await {
+                trace!(peer = %connection.peer, "Dropping message because of injected network faults");
+                continue;
+            }
+        }
         let processing_started = Instant::now();
 
         //  header is required on all messages
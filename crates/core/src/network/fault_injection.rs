@@ -0,0 +1,140 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Applies the faults configured in [`NetworkFaultInjection`] to the node-to-node fabric.
+//!
+//! Only compiled in with the `fault-injection` feature, which the server integration tests and
+//! the opt-in `fault-injection` feature of restate-server enable, so that production builds
+//! don't pay for it on every received message.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use restate_types::config::{Configuration, NetworkFaultInjection};
+use restate_types::live::BoxedLiveLoad;
+use restate_types::PlainNodeId;
+
+/// Returns true if a network partition from the given peer was injected.
+pub(super) fn is_partitioned_from(peer: PlainNodeId) -> bool {
+    Configuration::pinned()
+        .networking
+        .fault_injection
+        .partitioned_from
+        .contains(&peer)
+}
+
+/// Applies the injected faults to the messages received from a peer.
+pub(super) struct IncomingMessageFaults {
+    peer: PlainNodeId,
+    faults: BoxedLiveLoad<NetworkFaultInjection>,
+}
+
+impl IncomingMessageFaults {
+    pub(super) fn new(peer: PlainNodeId) -> Self {
+        Self {
+            peer,
+            faults: Box::new(Configuration::mapped_updateable(|config| {
+                &config.networking.fault_injection
+            })),
+        }
+    }
+
+    /// Delays the received message if configured. Returns false if the message must be dropped.
+    pub(super) async fn admit(&mut self) -> bool {
+        let delay = match admission(self.faults.live_load(), self.peer, &mut rand::thread_rng()) {
+            Admission::Admit => return true,
+            Admission::Drop => return false,
+            Admission::Delay(delay) => delay,
+        };
+        tokio::time::sleep(delay).await;
+        true
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Admission {
+    Admit,
+    Drop,
+    Delay(Duration),
+}
+
+fn admission(faults: &NetworkFaultInjection, peer: PlainNodeId, rng: &mut impl Rng) -> Admission {
+    if faults.is_empty() {
+        return Admission::Admit;
+    }
+    if faults.partitioned_from.contains(&peer)
+        || (faults.message_drop_rate > 0.0 && rng.gen_bool(faults.message_drop_rate.min(1.0)))
+    {
+        return Admission::Drop;
+    }
+    match faults.message_delay {
+        Some(delay) => Admission::Delay(delay.into()),
+        None => Admission::Admit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn admission_applies_faults() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let peer = PlainNodeId::new(1);
+
+        assert_eq!(
+            admission(&NetworkFaultInjection::default(), peer, &mut rng),
+            Admission::Admit
+        );
+
+        let partitioned = NetworkFaultInjection {
+            partitioned_from: vec![PlainNodeId::new(2)],
+            ..Default::default()
+        };
+        assert_eq!(admission(&partitioned, peer, &mut rng), Admission::Admit);
+        assert_eq!(
+            admission(&partitioned, PlainNodeId::new(2), &mut rng),
+            Admission::Drop
+        );
+
+        let delayed = NetworkFaultInjection {
+            message_delay: Some(Duration::from_millis(10).into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            admission(&delayed, peer, &mut rng),
+            Admission::Delay(Duration::from_millis(10))
+        );
+
+        let dropping = NetworkFaultInjection {
+            message_drop_rate: 1.0,
+            ..delayed
+        };
+        assert_eq!(admission(&dropping, peer, &mut rng), Admission::Drop);
+    }
+
+    #[test]
+    fn drop_rate_is_applied_per_message() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let faults = NetworkFaultInjection {
+            message_drop_rate: 0.5,
+            ..Default::default()
+        };
+
+        let dropped = (0..1000)
+            .filter(|_| admission(&faults, PlainNodeId::new(1), &mut rng) == Admission::Drop)
+            .count();
+        assert!((400..600).contains(&dropped), "dropped {dropped} messages");
+    }
+}
@@ -11,6 +11,8 @@
 mod connection;
 mod connection_manager;
 mod error;
+#[cfg(any(test, feature = "fault-injection"))]
+mod fault_injection;
 mod handshake;
 mod message_router;
 pub(crate) mod metric_definitions;
@@ -8,7 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::Duration;
+
 use assert2::let_assert;
+use rand::seq::SliceRandom;
 use tracing::trace;
 
 use restate_types::identifiers::{
@@ -18,16 +21,18 @@ use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationRe
 use restate_types::journal_v2::Signal;
 use restate_types::live::Live;
 use restate_types::net::partition_processor::{
-    AppendInvocationReplyOn, GetInvocationOutputResponseMode, InvocationOutput,
-    PartitionProcessorRpcError, PartitionProcessorRpcRequest, PartitionProcessorRpcRequestInner,
-    PartitionProcessorRpcResponse, SubmittedInvocationNotification,
+    AppendInvocationReplyOn, GetInvocationOutputResponseMode, InvocationOutput, OutputChunks,
+    OutputStreamCursor, PartitionProcessorRpcError, PartitionProcessorRpcRequest,
+    PartitionProcessorRpcRequestInner, PartitionProcessorRpcResponse, ReplicaReadOptions,
+    SubmittedInvocationNotification,
 };
 use restate_types::partition_table::{FindPartition, PartitionTable, PartitionTableError};
+use restate_types::NodeId;
 
 use crate::network::rpc_router::{ConnectionAwareRpcError, ConnectionAwareRpcRouter, RpcError};
 use crate::network::{HasConnection, Networking, Outgoing, TransportConnect};
 use crate::partitions::PartitionRouting;
-use crate::ShutdownError;
+use crate::{Metadata, ShutdownError};
 
 #[derive(Debug, thiserror::Error)]
 pub enum PartitionProcessorRpcClientError {
@@ -55,6 +60,8 @@ pub enum PartitionProcessorRpcClientError {
     Starting,
     #[error("partition processor stopping")]
     Stopping,
+    #[error("replica for partition '{0}' did not catch up with the read index in time")]
+    ReplicaLagging(PartitionId),
 }
 
 impl PartitionProcessorRpcClientError {
@@ -76,6 +83,8 @@ impl PartitionProcessorRpcClientError {
                 // and for which we know for certain that no message was proposed yet to the log.
                 true
             }
+            // Replica reads never propose messages to the log
+            PartitionProcessorRpcClientError::ReplicaLagging(_) => true,
             _ => false,
         }
     }
@@ -96,6 +105,9 @@ impl From<PartitionProcessorRpcError> for PartitionProcessorRpcClientError {
             }
             PartitionProcessorRpcError::Starting => PartitionProcessorRpcClientError::Starting,
             PartitionProcessorRpcError::Stopping => PartitionProcessorRpcClientError::Stopping,
+            PartitionProcessorRpcError::ReplicaLagging(partition_id) => {
+                PartitionProcessorRpcClientError::ReplicaLagging(partition_id)
+            }
         }
     }
 }
@@ -127,6 +139,13 @@ pub enum GetInvocationOutputResponse {
     Ready(InvocationOutput),
 }
 
+#[derive(Debug, Clone)]
+pub enum ReadOutputChunksResponse {
+    /// The invocation doesn't exist, or it completed without retaining its output chunks.
+    NotFound,
+    Chunks(OutputChunks),
+}
+
 pub struct PartitionProcessorRpcClient<C> {
     networking: Networking<C>,
     rpc_router: ConnectionAwareRpcRouter<PartitionProcessorRpcRequest>,
@@ -302,6 +321,86 @@ where
         })
     }
 
+    /// Get invocation output from a replica of the partition, preferring the local node. The
+    /// replica replies once it applied the log up to a read index obtained according to the
+    /// given options.
+    pub async fn read_invocation_output_from_replica(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_query: InvocationQuery,
+        options: ReplicaReadOptions,
+    ) -> Result<GetInvocationOutputResponse, PartitionProcessorRpcClientError> {
+        let partition_id = self
+            .partition_table
+            .pinned()
+            .find_partition_id(invocation_query.partition_key())?;
+
+        let replicas = self
+            .partition_routing
+            .get_replica_nodes_by_partition(partition_id);
+        let my_node_id = Metadata::with_current(|m| m.my_node_id_opt()).map(|id| id.as_plain());
+        let node_id = replicas
+            .iter()
+            .find(|node_id| my_node_id.is_some_and(|my_node_id| node_id.id() == my_node_id))
+            .or_else(|| replicas.choose(&mut rand::thread_rng()))
+            .copied()
+            .ok_or(PartitionProcessorRpcClientError::UnknownNode(partition_id))?;
+
+        let response = self
+            .send(
+                node_id,
+                partition_id,
+                request_id,
+                PartitionProcessorRpcRequestInner::ReadInvocationOutput(invocation_query, options),
+            )
+            .await?;
+
+        Ok(match response {
+            PartitionProcessorRpcResponse::NotFound => GetInvocationOutputResponse::NotFound,
+            PartitionProcessorRpcResponse::NotSupported => {
+                GetInvocationOutputResponse::NotSupported
+            }
+            PartitionProcessorRpcResponse::NotReady => GetInvocationOutputResponse::NotReady,
+            PartitionProcessorRpcResponse::Output(output) => {
+                GetInvocationOutputResponse::Ready(output)
+            }
+            _ => {
+                panic!("Expecting either PartitionProcessorRpcResponse::Output or PartitionProcessorRpcResponse::NotFound or PartitionProcessorRpcResponse::NotSupported or PartitionProcessorRpcResponse::NotReady")
+            }
+        })
+    }
+
+    /// Read the output chunks emitted by the invocation after the given cursor. If there are
+    /// none, the partition processor waits up to `max_wait` for new chunks before replying.
+    pub async fn read_output_chunks(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_query: InvocationQuery,
+        cursor: OutputStreamCursor,
+        max_wait: Duration,
+    ) -> Result<ReadOutputChunksResponse, PartitionProcessorRpcClientError> {
+        let response = self
+            .resolve_partition_id_and_send(
+                request_id,
+                PartitionProcessorRpcRequestInner::ReadOutputChunks(
+                    invocation_query,
+                    cursor,
+                    max_wait,
+                ),
+            )
+            .await?;
+
+        Ok(match response {
+            PartitionProcessorRpcResponse::NotFound => ReadOutputChunksResponse::NotFound,
+            PartitionProcessorRpcResponse::OutputChunks(chunks) => {
+                ReadOutputChunksResponse::Chunks(chunks)
+            }
+            _ => {
+                panic!("Expecting either PartitionProcessorRpcResponse::OutputChunks or PartitionProcessorRpcResponse::NotFound")
+            }
+        })
+    }
+
     pub async fn append_invocation_response(
         &self,
         request_id: PartitionProcessorRpcRequestId,
@@ -358,6 +457,17 @@ where
             .get_node_by_partition(partition_id)
             .ok_or(PartitionProcessorRpcClientError::UnknownNode(partition_id))?;
 
+        self.send(node_id, partition_id, request_id, inner_request)
+            .await
+    }
+
+    async fn send(
+        &self,
+        node_id: NodeId,
+        partition_id: PartitionId,
+        request_id: PartitionProcessorRpcRequestId,
+        inner_request: PartitionProcessorRpcRequestInner,
+    ) -> Result<PartitionProcessorRpcResponse, PartitionProcessorRpcClientError> {
         let rpc_result = self
             .rpc_router
             .call(
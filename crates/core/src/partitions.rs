@@ -76,6 +76,26 @@ impl PartitionRouting {
         maybe_node
     }
 
+    /// Look up the nodes which host a replica of the given partition, including the leader. Like
+    /// [`PartitionRouting::get_node_by_partition`], an empty response schedules a refresh.
+    pub fn get_replica_nodes_by_partition(&self, partition_id: PartitionId) -> Vec<NodeId> {
+        let mappings = self.partition_to_node_mappings.load();
+
+        let replicas = mappings
+            .replicas
+            .get(&partition_id)
+            .cloned()
+            .unwrap_or_default();
+        if replicas.is_empty() {
+            debug!(
+                ?partition_id,
+                "No known replica node for partition - requesting refresh"
+            );
+            self.request_refresh();
+        }
+        replicas
+    }
+
     /// Provide a hint that the partition-to-nodes view may be outdated. This is useful when a
     /// caller discovers via some other mechanism that routing information may be invalid - for
     /// example, when a request to a node previously returned by
@@ -106,6 +126,8 @@ struct PartitionToNodesRoutingTable {
     /// A mapping of partition IDs to node IDs that are believed to be authoritative for that
     /// serving requests for that partition.
     inner: HashMap<PartitionId, NodeId>,
+    /// A mapping of partition IDs to the nodes hosting a replica of that partition.
+    replicas: HashMap<PartitionId, Vec<NodeId>>,
 }
 
 /// Task to refresh the routing information, periodically or on-demand. A single
@@ -138,6 +160,7 @@ impl PartitionRoutingRefresher {
             inner: Arc::new(ArcSwap::new(Arc::new(PartitionToNodesRoutingTable {
                 version: Version::INVALID,
                 inner: HashMap::default(),
+                replicas: HashMap::default(),
             }))),
         }
     }
@@ -222,15 +245,25 @@ pub fn spawn_partition_routing_refresher(
 impl From<Pinned<PartitionTable>> for PartitionToNodesRoutingTable {
     fn from(value: Pinned<PartitionTable>) -> Self {
         let mut inner = HashMap::<PartitionId, NodeId>::default();
+        let mut replicas = HashMap::<PartitionId, Vec<NodeId>>::default();
         for (partition_id, partition) in value.partitions() {
             if let Some(leader) = partition.placement.leader() {
                 inner.insert(*partition_id, leader.into());
             }
+            replicas.insert(
+                *partition_id,
+                partition
+                    .placement
+                    .iter()
+                    .map(|node| (*node).into())
+                    .collect(),
+            );
         }
 
         Self {
             version: value.version(),
             inner,
+            replicas,
         }
     }
 }
@@ -255,6 +288,8 @@ pub mod mocks {
 
         let mut mappings = HashMap::default();
         mappings.insert(partition_id, NodeId::Generational(node_id));
+        let mut replicas = HashMap::default();
+        replicas.insert(partition_id, vec![NodeId::Generational(node_id)]);
 
         PartitionRouting {
             sender,
@@ -262,6 +297,7 @@ pub mod mocks {
                 super::PartitionToNodesRoutingTable {
                     version: Version::MIN,
                     inner: mappings,
+                    replicas,
                 },
             ))),
         }
@@ -0,0 +1,398 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Offloading of large invocation payloads to an object store.
+//!
+//! Payloads larger than the configured threshold are written to the object store and replaced,
+//! wherever they would be stored inline (log records, partition store tables), by a reference,
+//! see [`restate_types::payload_reference`]. Only the inputs of the invocations received by the
+//! ingress are offloaded. They are resolved by the invoker before being sent to the deployment,
+//! every other component sees the reference.
+//!
+//! Objects are stored under `[<prefix>/]<invocation_id>/input-<sha256>`. Every object is owned by
+//! a single invocation, which makes it possible to garbage collect them once the invocation is
+//! purged, without keeping an index of references. Naming the objects after their content makes
+//! sure duplicate requests, which share the invocation id, never overwrite the input of the
+//! original invocation. Invocations restarted as new get their own copy of the input.
+//!
+//! Since references are plain byte strings, they could be forged by callers. Payloads starting
+//! with [`PAYLOAD_REFERENCE_MAGIC`] are never accepted as inputs, and references are only
+//! resolved or copied on behalf of the invocation owning their object.
+
+use std::fmt;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+use url::Url;
+
+use restate_types::config::PayloadStoreOptions;
+use restate_types::identifiers::InvocationId;
+pub use restate_types::payload_reference::{is_payload_reference, PAYLOAD_REFERENCE_MAGIC};
+use restate_types::payload_reference::{payload_reference, payload_reference_path};
+
+const INPUT_OBJECT_NAME_PREFIX: &str = "input-";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadStoreError {
+    #[error("malformed payload reference")]
+    MalformedReference,
+    #[error("the payload starts with the reserved prefix of payload references")]
+    ReservedPrefix,
+    #[error("the payload reference does not belong to invocation {0}")]
+    ForeignReference(InvocationId),
+    #[error(transparent)]
+    ObjectStore(#[from] object_store::Error),
+}
+
+/// Provides write and read access to the offloaded payloads.
+#[derive(Clone)]
+pub struct PayloadStore {
+    object_store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+    offload_threshold: usize,
+}
+
+impl fmt::Debug for PayloadStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PayloadStore")
+            .field("object_store", &self.object_store.to_string())
+            .field("prefix", &self.prefix)
+            .field("offload_threshold", &self.offload_threshold)
+            .finish()
+    }
+}
+
+impl PayloadStore {
+    /// Creates an instance of the payload store if a destination is configured.
+    pub fn create_if_configured(
+        options: &PayloadStoreOptions,
+    ) -> anyhow::Result<Option<PayloadStore>> {
+        let Some(destination) = &options.destination else {
+            return Ok(None);
+        };
+        let mut destination = Url::parse(destination)
+            .map_err(|e| anyhow::anyhow!("Failed parsing payload store URL: {e}"))?;
+        // Prevent passing configuration options to object_store via the destination URL.
+        destination
+            .query()
+            .inspect(|params| info!("Payload store destination parameters ignored: {params}"));
+        destination.set_query(None);
+
+        let (object_store, prefix): (Arc<dyn ObjectStore>, _) = if destination.scheme() == "s3" {
+            let store = AmazonS3Builder::from_env()
+                .with_url(destination.clone())
+                .build()?;
+            (
+                Arc::new(store),
+                ObjectPath::from(destination.path().trim_start_matches('/')),
+            )
+        } else {
+            let (store, prefix) = object_store::parse_url(&destination)?;
+            (store.into(), prefix)
+        };
+        debug!("Offloading large payloads to: {destination}");
+
+        Ok(Some(PayloadStore {
+            object_store,
+            prefix,
+            offload_threshold: options.offload_threshold.get(),
+        }))
+    }
+
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn new_for_testing(offload_threshold: usize) -> Self {
+        Self {
+            object_store: Arc::new(object_store::memory::InMemory::new()),
+            prefix: ObjectPath::default(),
+            offload_threshold,
+        }
+    }
+
+    /// Offloads the input of the given invocation if it's larger than the threshold, returning
+    /// the reference to store in its place. Smaller payloads are returned unchanged. Payloads
+    /// which look like a reference are refused.
+    pub async fn offload_input(
+        &self,
+        invocation_id: &InvocationId,
+        payload: Bytes,
+    ) -> Result<Bytes, PayloadStoreError> {
+        if is_payload_reference(&payload) {
+            return Err(PayloadStoreError::ReservedPrefix);
+        }
+        if payload.len() <= self.offload_threshold {
+            return Ok(payload);
+        }
+
+        let name = format!(
+            "{INPUT_OBJECT_NAME_PREFIX}{:x}",
+            Sha256::digest(payload.as_ref())
+        );
+        let path = self.invocation_path(invocation_id).child(name);
+        self.object_store
+            .put(&path, PutPayload::from_bytes(payload))
+            .await?;
+
+        Ok(payload_reference(path.as_ref()))
+    }
+
+    /// Resolves the given input of the invocation if it's a reference, otherwise returns it
+    /// unchanged. The reference must point to an input offloaded for this invocation.
+    pub async fn resolve_input(
+        &self,
+        invocation_id: &InvocationId,
+        payload: Bytes,
+    ) -> Result<Bytes, PayloadStoreError> {
+        if !is_payload_reference(&payload) {
+            return Ok(payload);
+        }
+
+        let path = self.owned_input_path(invocation_id, &payload)?;
+        Ok(self.object_store.get(&path).await?.bytes().await?)
+    }
+
+    /// Copies the input object of the source invocation to the target reference, see
+    /// [`restate_types::payload_reference::rebase_payload_reference`].
+    pub async fn copy_input(
+        &self,
+        source_invocation_id: &InvocationId,
+        source: &[u8],
+        target_invocation_id: &InvocationId,
+        target: &[u8],
+    ) -> Result<(), PayloadStoreError> {
+        self.object_store
+            .copy(
+                &self.owned_input_path(source_invocation_id, source)?,
+                &self.owned_input_path(target_invocation_id, target)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes the payloads offloaded for the given invocation, if any.
+    pub async fn delete_invocation_payloads(
+        &self,
+        invocation_id: &InvocationId,
+    ) -> Result<(), PayloadStoreError> {
+        let prefix = self.invocation_path(invocation_id);
+        let mut objects = self.object_store.list(Some(&prefix));
+        while let Some(object) = objects.try_next().await? {
+            match self.object_store.delete(&object.location).await {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_reference(reference: &[u8]) -> Result<ObjectPath, PayloadStoreError> {
+        let path =
+            payload_reference_path(reference).ok_or(PayloadStoreError::MalformedReference)?;
+        ObjectPath::parse(path).map_err(|_| PayloadStoreError::MalformedReference)
+    }
+
+    /// Returns the path of the referenced input object, if it's owned by the given invocation.
+    fn owned_input_path(
+        &self,
+        invocation_id: &InvocationId,
+        reference: &[u8],
+    ) -> Result<ObjectPath, PayloadStoreError> {
+        let path = Self::parse_reference(reference)?;
+        let is_owned = path
+            .prefix_match(&self.invocation_path(invocation_id))
+            .is_some_and(|mut parts| {
+                parts
+                    .next()
+                    .is_some_and(|name| name.as_ref().starts_with(INPUT_OBJECT_NAME_PREFIX))
+                    && parts.next().is_none()
+            });
+        if !is_owned {
+            return Err(PayloadStoreError::ForeignReference(*invocation_id));
+        }
+        Ok(path)
+    }
+
+    fn invocation_path(&self, invocation_id: &InvocationId) -> ObjectPath {
+        self.prefix.child(invocation_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::invocation::InvocationTarget;
+    use restate_types::payload_reference::rebase_payload_reference;
+
+    #[tokio::test]
+    async fn offload_and_resolve() -> anyhow::Result<()> {
+        let payload_store = PayloadStore::new_for_testing(4);
+        let invocation_id = InvocationId::mock_generate(&InvocationTarget::mock_service());
+
+        let small = Bytes::from_static(b"1234");
+        assert_eq!(
+            payload_store
+                .offload_input(&invocation_id, small.clone())
+                .await?,
+            small
+        );
+
+        let large = Bytes::from_static(b"12345");
+        let reference = payload_store
+            .offload_input(&invocation_id, large.clone())
+            .await?;
+        assert!(is_payload_reference(&reference));
+        assert_eq!(
+            payload_store
+                .resolve_input(&invocation_id, reference.clone())
+                .await?,
+            large
+        );
+
+        payload_store
+            .delete_invocation_payloads(&invocation_id)
+            .await?;
+        assert!(matches!(
+            payload_store.resolve_input(&invocation_id, reference).await,
+            Err(PayloadStoreError::ObjectStore(
+                object_store::Error::NotFound { .. }
+            ))
+        ));
+        // Deleting again is a no-op
+        payload_store
+            .delete_invocation_payloads(&invocation_id)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn duplicates_do_not_overwrite_input() -> anyhow::Result<()> {
+        let payload_store = PayloadStore::new_for_testing(4);
+        let invocation_id = InvocationId::mock_generate(&InvocationTarget::mock_service());
+
+        let original = Bytes::from_static(b"original");
+        let original_reference = payload_store
+            .offload_input(&invocation_id, original.clone())
+            .await?;
+        let duplicate_reference = payload_store
+            .offload_input(&invocation_id, Bytes::from_static(b"duplicate"))
+            .await?;
+        assert_ne!(original_reference, duplicate_reference);
+        assert_eq!(
+            payload_store
+                .resolve_input(&invocation_id, original_reference)
+                .await?,
+            original
+        );
+
+        // Purging the invocation deletes the objects of the duplicates as well
+        payload_store
+            .delete_invocation_payloads(&invocation_id)
+            .await?;
+        assert!(payload_store
+            .resolve_input(&invocation_id, duplicate_reference)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn copy_survives_purge_of_source() -> anyhow::Result<()> {
+        let payload_store = PayloadStore::new_for_testing(4);
+        let invocation_id = InvocationId::mock_generate(&InvocationTarget::mock_service());
+        let restarted_invocation_id = invocation_id.generate_restart();
+
+        let input = Bytes::from_static(b"12345");
+        let reference = payload_store
+            .offload_input(&invocation_id, input.clone())
+            .await?;
+        let rebased_reference =
+            rebase_payload_reference(&reference, &invocation_id, &restarted_invocation_id).unwrap();
+        payload_store
+            .copy_input(
+                &invocation_id,
+                &reference,
+                &restarted_invocation_id,
+                &rebased_reference,
+            )
+            .await?;
+
+        payload_store
+            .delete_invocation_payloads(&invocation_id)
+            .await?;
+        assert!(payload_store
+            .resolve_input(&invocation_id, reference)
+            .await
+            .is_err());
+        assert_eq!(
+            payload_store
+                .resolve_input(&restarted_invocation_id, rebased_reference)
+                .await?,
+            input
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refuses_forged_references() -> anyhow::Result<()> {
+        let payload_store = PayloadStore::new_for_testing(4);
+        let victim = InvocationId::mock_generate(&InvocationTarget::mock_service());
+        let attacker = InvocationId::mock_generate(&InvocationTarget::mock_service());
+
+        let victim_reference = payload_store
+            .offload_input(&victim, Bytes::from_static(b"secret input"))
+            .await?;
+
+        // A caller can't submit a reference as input
+        assert!(matches!(
+            payload_store
+                .offload_input(&attacker, victim_reference.clone())
+                .await,
+            Err(PayloadStoreError::ReservedPrefix)
+        ));
+
+        // Nor have another invocation's input resolved or copied on its behalf
+        assert!(matches!(
+            payload_store
+                .resolve_input(&attacker, victim_reference.clone())
+                .await,
+            Err(PayloadStoreError::ForeignReference(id)) if id == attacker
+        ));
+        let attacker_reference = payload_reference(&format!("{attacker}/input-abc"));
+        assert!(matches!(
+            payload_store
+                .copy_input(&attacker, &victim_reference, &attacker, &attacker_reference)
+                .await,
+            Err(PayloadStoreError::ForeignReference(id)) if id == attacker
+        ));
+
+        // Only input objects directly below the invocation's own path are resolved
+        for path in [
+            format!("{attacker}/../{victim}/input-abc"),
+            format!("{attacker}/nested/input-abc"),
+            format!("{attacker}/output-abc"),
+        ] {
+            assert!(payload_store
+                .resolve_input(&attacker, payload_reference(&path))
+                .await
+                .is_err());
+        }
+
+        Ok(())
+    }
+}
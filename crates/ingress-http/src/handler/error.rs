@@ -13,8 +13,8 @@ use super::APPLICATION_JSON;
 use crate::RequestDispatcherError;
 use bytes::Bytes;
 use http::{header, Response, StatusCode};
-use restate_types::errors::{IdDecodeError, InvocationError};
-use restate_types::schema::invocation_target::InputValidationError;
+use restate_types::errors::{codes, IdDecodeError, InvocationError, InvocationErrorCode};
+use restate_types::schema::invocation_target::{InputValidationError, JsonSchemaViolation};
 use serde::Serialize;
 use std::string;
 
@@ -48,6 +48,8 @@ pub(crate) enum HandlerError {
     NotImplemented,
     #[error("bad header {0}: {1:?}")]
     BadHeader(header::HeaderName, #[source] header::ToStrError),
+    #[error("bad output stream mode '{0}', expected either 'chunked' or 'sse'")]
+    BadOutputStreamMode(String),
     #[error("bad delay query parameter, must be a ISO8601 duration: {0}")]
     BadDelayDuration(String),
     #[error("bad path, cannot decode key: {0:?}")]
@@ -84,6 +86,8 @@ pub(crate) enum HandlerError {
     BadInvocationId(String, IdDecodeError),
     #[error("dispatcher error: {0}")]
     DispatcherError(#[from] RequestDispatcherError),
+    #[error("the request body starts with a prefix reserved for internal use")]
+    ReservedPayloadPrefix,
 }
 
 // IMPORTANT! If you touch this, please update crates/types/src/schema/openapi.rs too
@@ -94,6 +98,10 @@ pub enum ErrorResponse {
         // InvocationError has its own json representation, we simply use that
         InvocationError,
     ),
+    InputSchemaViolations {
+        message: String,
+        violations: Vec<JsonSchemaViolation>,
+    },
     Other {
         // This will simply write the error using the Display trait
         #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
@@ -102,11 +110,8 @@ pub enum ErrorResponse {
 }
 
 impl HandlerError {
-    pub(crate) fn fill_builder<B: http_body::Body + Default + From<Bytes>>(
-        self,
-        res_builder: http::response::Builder,
-    ) -> Response<B> {
-        let status_code = match &self {
+    pub(crate) fn status_code(&self) -> StatusCode {
+        match self {
             HandlerError::NotFound
             | HandlerError::ServiceNotFound(_)
             | HandlerError::ServiceHandlerNotFound(_, _)
@@ -115,6 +120,7 @@ impl HandlerError {
             | HandlerError::PrivateService
             | HandlerError::UrlDecodingError(_)
             | HandlerError::BadDelayDuration(_)
+            | HandlerError::BadOutputStreamMode(_)
             | HandlerError::BadAwakeablesPath
             | HandlerError::UnsupportedDelay
             | HandlerError::BadHeader(_, _)
@@ -124,7 +130,8 @@ impl HandlerError {
             | HandlerError::BadWorkflowPath
             | HandlerError::InputValidation(_)
             | HandlerError::UnsupportedIdempotencyKey
-            | HandlerError::UnsupportedGetOutput => StatusCode::BAD_REQUEST,
+            | HandlerError::UnsupportedGetOutput
+            | HandlerError::ReservedPayloadPrefix => StatusCode::BAD_REQUEST,
             HandlerError::DispatcherError(_) => {
                 // TODO add more distinctions between different dispatcher errors (unavailable, etc)
                 StatusCode::INTERNAL_SERVER_ERROR
@@ -137,10 +144,33 @@ impl HandlerError {
                 StatusCode::from_u16(e.code().into()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
             }
             HandlerError::NotReady => StatusCode::from_u16(470).unwrap(),
-        };
+        }
+    }
+
+    /// The Restate error code of this error. Unlike the status code, it keeps the codes of the
+    /// invocation errors which are not valid HTTP status codes.
+    pub(crate) fn error_code(&self) -> InvocationErrorCode {
+        match self {
+            HandlerError::Invocation(e) => e.code(),
+            HandlerError::NotReady => codes::NOT_READY,
+            _ => InvocationErrorCode::from(self.status_code().as_u16()),
+        }
+    }
+
+    pub(crate) fn fill_builder<B: http_body::Body + Default + From<Bytes>>(
+        self,
+        res_builder: http::response::Builder,
+    ) -> Response<B> {
+        let status_code = self.status_code();
 
         let error_response = match self {
             HandlerError::Invocation(e) => ErrorResponse::Invocation(e),
+            HandlerError::InputValidation(InputValidationError::JsonSchemaViolations(
+                violations,
+            )) => ErrorResponse::InputSchemaViolations {
+                message: "input does not match the JSON schema of the handler".to_owned(),
+                violations,
+            },
             e => ErrorResponse::Other { message: e },
         };
 
@@ -13,9 +13,10 @@ use http::{Method, Request, Response};
 use http_body_util::Full;
 use tracing::warn;
 
+use super::output_stream::OutputStreamMode;
 use super::path_parsing::{InvocationRequestType, InvocationTargetType, TargetType};
-use super::Handler;
 use super::HandlerError;
+use super::{into_response_body, Handler, ResponseBody};
 use crate::RequestDispatcher;
 use restate_core::network::partition_processor_rpc_client::{
     AttachInvocationResponse, GetInvocationOutputResponse,
@@ -33,7 +34,7 @@ where
         self,
         req: Request<B>,
         invocation_request_type: InvocationRequestType,
-    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    ) -> Result<Response<ResponseBody>, HandlerError>
     where
         <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     {
@@ -45,13 +46,13 @@ where
                 )
                 .await
             }
-            InvocationRequestType::GetOutput(invocation_target_type) => {
-                self.handle_invocation_get_output(
+            InvocationRequestType::GetOutput(invocation_target_type) => self
+                .handle_invocation_get_output(
                     req,
                     Self::convert_to_invocation_query(invocation_target_type)?,
                 )
                 .await
-            }
+                .map(into_response_body),
         }
     }
 
@@ -84,7 +85,7 @@ where
         self,
         req: Request<B>,
         invocation_query: InvocationQuery,
-    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    ) -> Result<Response<ResponseBody>, HandlerError>
     where
         <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     {
@@ -93,6 +94,26 @@ where
             return Err(HandlerError::MethodNotAllowed);
         }
 
+        if let Some(output_stream_mode) = OutputStreamMode::from_headers(req.headers())? {
+            Self::stream_output(
+                self.dispatcher.clone(),
+                output_stream_mode,
+                invocation_query.clone(),
+                self.attach_and_reply(invocation_query),
+            )
+            .await
+        } else {
+            self.attach_and_reply(invocation_query)
+                .await
+                .map(into_response_body)
+        }
+    }
+
+    /// Attaches to the invocation, and replies with its response once completed.
+    pub(crate) async fn attach_and_reply(
+        self,
+        invocation_query: InvocationQuery,
+    ) -> Result<Response<Full<Bytes>>, HandlerError> {
         // Wait on response
         let response = match self
             .dispatcher
@@ -12,6 +12,7 @@ mod awakeables;
 mod error;
 mod health;
 mod invocation;
+mod output_stream;
 mod path_parsing;
 mod responses;
 mod service_handler;
@@ -26,22 +27,33 @@ use std::task::{Context, Poll};
 use error::HandlerError;
 use futures::future::BoxFuture;
 use futures::FutureExt;
-use http_body_util::Full;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full};
 use hyper::http::HeaderValue;
 use hyper::{Request, Response};
 use path_parsing::RequestType;
 use restate_types::live::Live;
-use restate_types::schema::invocation_target::InvocationTargetResolver;
+use restate_types::schema::invocation_target::{InputSchemaValidators, InvocationTargetResolver};
 use restate_types::schema::service::ServiceMetadataResolver;
 
 use super::*;
 
 const APPLICATION_JSON: HeaderValue = HeaderValue::from_static("application/json");
 
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Body of the ingress responses, either buffered or streamed.
+pub(crate) type ResponseBody = UnsyncBoxBody<Bytes, BoxError>;
+
+fn into_response_body(response: Response<Full<Bytes>>) -> Response<ResponseBody> {
+    response.map(|body| body.map_err(|never| match never {}).boxed_unsync())
+}
+
 #[derive(Clone)]
 pub(crate) struct Handler<Schemas, Dispatcher> {
     schemas: Live<Schemas>,
     dispatcher: Dispatcher,
+    input_schema_validators: InputSchemaValidators,
 }
 
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
@@ -49,6 +61,7 @@ impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
         Self {
             schemas,
             dispatcher,
+            input_schema_validators: InputSchemaValidators::default(),
         }
     }
 }
@@ -61,7 +74,7 @@ where
     <Body as http_body::Body>::Data: Send + 'static,
     <Body as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
 {
-    type Response = Response<Full<Bytes>>;
+    type Response = Response<ResponseBody>;
     type Error = Infallible;
     type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -75,14 +88,15 @@ where
         let mut this = self.clone();
         async move {
             match res? {
-                RequestType::Health => this.handle_health(req),
+                RequestType::Health => this.handle_health(req).map(into_response_body),
                 RequestType::OpenAPI => {
                     // TODO
                     Err(HandlerError::NotImplemented)
                 }
-                RequestType::Awakeable(awakeable_request) => {
-                    this.handle_awakeable(req, awakeable_request).await
-                }
+                RequestType::Awakeable(awakeable_request) => this
+                    .handle_awakeable(req, awakeable_request)
+                    .await
+                    .map(into_response_body),
                 RequestType::Service(service_request) => {
                     this.handle_service_request(req, service_request).await
                 }
@@ -94,7 +108,7 @@ where
                 }
             }
         }
-        .map(|r| Ok::<_, Infallible>(r.unwrap_or_else(|e| e.into_response())))
+        .map(|r| Ok::<_, Infallible>(r.unwrap_or_else(|e| into_response_body(e.into_response()))))
         .boxed()
     }
 }
@@ -0,0 +1,328 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Streaming of the output chunks emitted by invocations.
+//!
+//! Clients opt into streaming either with `Accept: text/event-stream`, or by setting the
+//! `x-restate-output-stream` header to `chunked` or `sse`. A driver task reads the chunks from
+//! the partition processor while waiting for the invocation response, and forwards both to the
+//! response body. If the invocation completes without emitting any chunk, the regular response
+//! is sent instead.
+//!
+//! Streaming calls are retained for at least `ingress.output-stream-retention` after they
+//! complete, so the last chunks can still be read. Like for idempotent calls, this makes them
+//! attachable by invocation id for that time, even without an idempotency key.
+
+use std::future::{ready, Future};
+use std::pin::pin;
+use std::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use bytestring::ByteString;
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt, TryStreamExt};
+use http::{header, HeaderMap, HeaderName, HeaderValue, Response, StatusCode};
+use http_body::Frame;
+use http_body_util::{BodyExt, Full, StreamBody};
+use tracing::{debug, Instrument};
+
+use restate_core::network::partition_processor_rpc_client::ReadOutputChunksResponse;
+use restate_core::{TaskCenter, TaskKind};
+use restate_types::invocation::InvocationQuery;
+use restate_types::net::partition_processor::OutputStreamCursor;
+
+use super::responses::X_RESTATE_ID;
+use super::{into_response_body, BoxError, Handler, HandlerError, ResponseBody};
+use crate::RequestDispatcher;
+
+pub(crate) const X_RESTATE_OUTPUT_STREAM: HeaderName =
+    HeaderName::from_static("x-restate-output-stream");
+const TEXT_EVENT_STREAM: &str = "text/event-stream";
+
+/// Interval between reads of the output chunks when the invocation doesn't exist yet, or when
+/// the read failed.
+const READ_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+/// Number of chunk batches buffered before the driver waits for the client to catch up.
+const OUTPUT_STREAM_BUFFER: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputStreamMode {
+    /// Chunks are written as they are to the response body, followed by the handler output.
+    Chunked,
+    /// Chunks and handler output are written as server-sent events.
+    ServerSentEvents,
+}
+
+impl OutputStreamMode {
+    /// Parses the output stream mode requested by the client, if any.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Result<Option<Self>, HandlerError> {
+        if let Some(value) = headers.get(X_RESTATE_OUTPUT_STREAM) {
+            let value = value
+                .to_str()
+                .map_err(|e| HandlerError::BadHeader(X_RESTATE_OUTPUT_STREAM, e))?
+                .trim();
+            return if value.eq_ignore_ascii_case("chunked") {
+                Ok(Some(OutputStreamMode::Chunked))
+            } else if value.eq_ignore_ascii_case("sse") {
+                Ok(Some(OutputStreamMode::ServerSentEvents))
+            } else {
+                Err(HandlerError::BadOutputStreamMode(value.to_owned()))
+            };
+        }
+
+        let accepts_event_stream = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|media_range| media_range.split(';').next())
+            .any(|media_type| media_type.trim().eq_ignore_ascii_case(TEXT_EVENT_STREAM));
+        Ok(accepts_event_stream.then_some(OutputStreamMode::ServerSentEvents))
+    }
+
+    fn content_type(self) -> HeaderValue {
+        match self {
+            OutputStreamMode::Chunked => HeaderValue::from_static("text/plain; charset=utf-8"),
+            OutputStreamMode::ServerSentEvents => HeaderValue::from_static(TEXT_EVENT_STREAM),
+        }
+    }
+
+    fn encode_chunks(self, chunks: Vec<ByteString>) -> Bytes {
+        match self {
+            OutputStreamMode::Chunked => {
+                let mut buf = BytesMut::new();
+                for chunk in chunks {
+                    buf.put_slice(chunk.as_bytes());
+                }
+                buf.freeze()
+            }
+            OutputStreamMode::ServerSentEvents => {
+                let mut buf = BytesMut::new();
+                for chunk in chunks {
+                    encode_event(&mut buf, "chunk", &chunk);
+                }
+                buf.freeze()
+            }
+        }
+    }
+
+    /// Encodes the invocation response, which ends the stream. In chunked mode failures can
+    /// only be reported by aborting the body, since the status code was already sent.
+    async fn encode_response(self, response: Response<Full<Bytes>>) -> Result<Bytes, BoxError> {
+        let (parts, body) = response.into_parts();
+        let body = match body.collect().await {
+            Ok(collected) => collected.to_bytes(),
+            Err(never) => match never {},
+        };
+
+        match self {
+            OutputStreamMode::Chunked if parts.status.is_success() => Ok(body),
+            OutputStreamMode::Chunked => Err(format!(
+                "invocation failed with status {}: {}",
+                parts.status,
+                String::from_utf8_lossy(&body)
+            )
+            .into()),
+            OutputStreamMode::ServerSentEvents => {
+                let mut buf = BytesMut::new();
+                encode_event(
+                    &mut buf,
+                    if parts.status.is_success() {
+                        "output"
+                    } else {
+                        "error"
+                    },
+                    &String::from_utf8_lossy(&body),
+                );
+                Ok(buf.freeze())
+            }
+        }
+    }
+}
+
+/// Writes a server-sent event, splitting the data in one field per line.
+fn encode_event(buf: &mut BytesMut, event: &str, data: &str) {
+    buf.put_slice(b"event: ");
+    buf.put_slice(event.as_bytes());
+    buf.put_u8(b'\n');
+    for line in data.split('\n') {
+        buf.put_slice(b"data: ");
+        buf.put_slice(line.strip_suffix('\r').unwrap_or(line).as_bytes());
+        buf.put_u8(b'\n');
+    }
+    buf.put_u8(b'\n');
+}
+
+enum OutputStreamEvent {
+    Chunks(Vec<ByteString>),
+    Response(Response<Full<Bytes>>),
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    /// Streams the output chunks of the invocation, followed by its response.
+    pub(crate) async fn stream_output(
+        dispatcher: Dispatcher,
+        mode: OutputStreamMode,
+        invocation_query: InvocationQuery,
+        response: impl Future<Output = Result<Response<Full<Bytes>>, HandlerError>> + Send + 'static,
+    ) -> Result<Response<ResponseBody>, HandlerError> {
+        let invocation_id = invocation_query.to_invocation_id();
+        let (tx, mut rx) = mpsc::channel(OUTPUT_STREAM_BUFFER);
+        TaskCenter::spawn_child(
+            TaskKind::Ingress,
+            "ingress-output-stream",
+            drive_output_stream(dispatcher, invocation_query, response, tx).in_current_span(),
+        )
+        .map_err(|_| HandlerError::Unavailable)?;
+
+        let first_chunks = match rx.next().await {
+            Some(OutputStreamEvent::Chunks(chunks)) => chunks,
+            // Completed without streaming anything, reply as usual
+            Some(OutputStreamEvent::Response(response)) => {
+                return Ok(into_response_body(response));
+            }
+            None => return Err(HandlerError::Unavailable),
+        };
+
+        let body = futures::stream::once(ready(OutputStreamEvent::Chunks(first_chunks)))
+            .chain(rx)
+            .then(move |event| async move {
+                match event {
+                    OutputStreamEvent::Chunks(chunks) => Ok(mode.encode_chunks(chunks)),
+                    OutputStreamEvent::Response(response) => mode.encode_response(response).await,
+                }
+            })
+            .map_ok(Frame::data);
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, mode.content_type())
+            .header(header::CACHE_CONTROL, "no-cache")
+            .header(X_RESTATE_ID, invocation_id.to_string())
+            .body(StreamBody::new(body).boxed_unsync())
+            .unwrap())
+    }
+}
+
+/// Reads the output chunks until the invocation completed, then sends the response.
+async fn drive_output_stream<Dispatcher: RequestDispatcher>(
+    dispatcher: Dispatcher,
+    invocation_query: InvocationQuery,
+    response: impl Future<Output = Result<Response<Full<Bytes>>, HandlerError>>,
+    mut tx: mpsc::Sender<OutputStreamEvent>,
+) -> anyhow::Result<()> {
+    let mut response = pin!(response);
+    let mut cursor = OutputStreamCursor::default();
+    let mut chunks_completed = false;
+
+    let response = loop {
+        tokio::select! {
+            response = &mut response => break response,
+            read = dispatcher.read_output_chunks(invocation_query.clone(), cursor), if !chunks_completed => {
+                match read {
+                    Ok(ReadOutputChunksResponse::Chunks(chunks)) => {
+                        cursor = chunks.next;
+                        chunks_completed = chunks.completed;
+                        if !chunks.chunks.is_empty()
+                            && tx.send(OutputStreamEvent::Chunks(chunks.chunks)).await.is_err()
+                        {
+                            // The client went away
+                            return Ok(());
+                        }
+                    }
+                    // The invocation might not have been created yet
+                    Ok(ReadOutputChunksResponse::NotFound) => {
+                        tokio::time::sleep(READ_RETRY_INTERVAL).await
+                    }
+                    Err(err) => {
+                        debug!("Failed reading output chunks, retrying: {err}");
+                        tokio::time::sleep(READ_RETRY_INTERVAL).await
+                    }
+                }
+            }
+        }
+    };
+
+    // Forward the chunks emitted right before the completion
+    while !chunks_completed {
+        match dispatcher
+            .read_output_chunks(invocation_query.clone(), cursor)
+            .await
+        {
+            Ok(ReadOutputChunksResponse::Chunks(chunks)) => {
+                cursor = chunks.next;
+                chunks_completed = chunks.completed;
+                if !chunks.chunks.is_empty()
+                    && tx
+                        .send(OutputStreamEvent::Chunks(chunks.chunks))
+                        .await
+                        .is_err()
+                {
+                    return Ok(());
+                }
+            }
+            // The invocation is not retained, there's nothing left to read
+            Ok(ReadOutputChunksResponse::NotFound) => break,
+            Err(err) => {
+                debug!("Failed reading the last output chunks: {err}");
+                break;
+            }
+        }
+    }
+
+    let _ = tx
+        .send(OutputStreamEvent::Response(
+            response.unwrap_or_else(|e| e.into_response()),
+        ))
+        .await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_output_stream_mode() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(OutputStreamMode::from_headers(&headers).unwrap(), None);
+
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/json, text/event-stream;q=0.9"),
+        );
+        assert_eq!(
+            OutputStreamMode::from_headers(&headers).unwrap(),
+            Some(OutputStreamMode::ServerSentEvents)
+        );
+
+        headers.insert(X_RESTATE_OUTPUT_STREAM, HeaderValue::from_static("chunked"));
+        assert_eq!(
+            OutputStreamMode::from_headers(&headers).unwrap(),
+            Some(OutputStreamMode::Chunked)
+        );
+
+        headers.insert(X_RESTATE_OUTPUT_STREAM, HeaderValue::from_static("lines"));
+        assert!(matches!(
+            OutputStreamMode::from_headers(&headers),
+            Err(HandlerError::BadOutputStreamMode(mode)) if mode == "lines"
+        ));
+    }
+
+    #[test]
+    fn encode_multi_line_event() {
+        let mut buf = BytesMut::new();
+        encode_event(&mut buf, "chunk", "Hello\r\nworld");
+        assert_eq!(&buf[..], b"event: chunk\ndata: Hello\ndata: world\n\n");
+    }
+}
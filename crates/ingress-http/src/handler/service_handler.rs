@@ -14,27 +14,34 @@ use bytes::Bytes;
 use bytestring::ByteString;
 use http::{header, HeaderMap, HeaderName, Method, Request, Response, StatusCode};
 use http_body_util::{BodyExt, Full};
-use metrics::{counter, histogram};
+use metrics::{counter, histogram, Label};
 use serde::de::IntoDeserializer;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use tracing::{info, trace, trace_span, Instrument};
 
+use restate_core::metric_labels::service_handler_labels;
+use restate_types::config::Configuration;
 use restate_types::identifiers::{InvocationId, WithInvocationId};
 use restate_types::invocation::{
-    Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
-    SpanRelation, WorkflowHandlerType,
+    Header, InvocationQuery, InvocationRequest, InvocationRequestHeader, InvocationTarget,
+    InvocationTargetType, SpanRelation, WorkflowHandlerType,
 };
+use restate_types::payload_reference::is_payload_reference;
 use restate_types::schema::invocation_target::{
     InvocationTargetMetadata, InvocationTargetResolver,
 };
 
+use super::output_stream::OutputStreamMode;
 use super::path_parsing::{InvokeType, ServiceRequestType, TargetType};
 use super::tracing::prepare_tracing_span;
 use super::HandlerError;
-use super::{Handler, APPLICATION_JSON};
+use super::{into_response_body, Handler, ResponseBody, APPLICATION_JSON};
 use crate::handler::responses::{IDEMPOTENCY_EXPIRES, X_RESTATE_ID};
-use crate::metric_definitions::{INGRESS_REQUESTS, INGRESS_REQUEST_DURATION, REQUEST_COMPLETED};
+use crate::metric_definitions::{
+    ERROR_CODE_LABEL, INGRESS_REQUESTS, INGRESS_REQUEST_DURATION, REQUEST_COMPLETED,
+    REQUEST_FAILED, STATUS_LABEL,
+};
 use crate::RequestDispatcher;
 
 pub(crate) const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
@@ -72,7 +79,7 @@ where
         self,
         req: Request<B>,
         service_request: ServiceRequestType,
-    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    ) -> Result<Response<ResponseBody>, HandlerError>
     where
         <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     {
@@ -153,6 +160,12 @@ where
                 return Err(HandlerError::MethodNotAllowed);
             }
 
+            // Check whether the client asked to stream the output
+            let output_stream_mode = match invoke_ty {
+                InvokeType::Call => OutputStreamMode::from_headers(&parts.headers)?,
+                InvokeType::Send => None,
+            };
+
             // Collect body
             let body = body
                 .collect()
@@ -160,6 +173,11 @@ where
                 .map_err(|e| HandlerError::Body(e.into()))?
                 .to_bytes();
             trace!(rpc.request = ?body);
+            // Only the ingress creates payload references, when offloading large bodies. A
+            // forged one would let the caller read the offloaded payloads of other invocations.
+            if is_payload_reference(&body) {
+                return Err(HandlerError::ReservedPayloadPrefix);
+            }
 
             // Validate content-type and body
             invocation_target_meta.input_rules.validate(
@@ -173,6 +191,12 @@ where
                     .transpose()?,
                 &body,
             )?;
+            self.input_schema_validators.validate(
+                invocation_target.service_name(),
+                invocation_target.handler_name(),
+                &invocation_target_meta,
+                &body,
+            )?;
 
             // Parse delay query parameter
             let delay = parse_delay(parts.uri.query())?;
@@ -186,6 +210,16 @@ where
             invocation_request_header.with_related_span(SpanRelation::Parent(ingress_span_context));
             invocation_request_header.completion_retention_duration =
                 invocation_target_meta.compute_retention(idempotency_key.is_some());
+            if output_stream_mode.is_some()
+                && invocation_request_header
+                    .completion_retention_duration
+                    .is_zero()
+            {
+                // Retain the output chunks until the ingress read them all. The completed
+                // invocation is retained as a whole, hence it stays attachable for that time.
+                invocation_request_header.completion_retention_duration =
+                    Configuration::pinned().ingress.output_stream_retention();
+            }
             if let Some(key) = idempotency_key {
                 invocation_request_header.idempotency_key = Some(key);
             }
@@ -196,12 +230,29 @@ where
                     if delay.is_some() {
                         return Err(HandlerError::UnsupportedDelay);
                     }
-                    Self::handle_service_call(
-                        InvocationRequest::new(invocation_request_header, body),
-                        invocation_target_meta,
-                        self.dispatcher,
-                    )
-                    .await
+                    let invocation_request =
+                        InvocationRequest::new(invocation_request_header, body);
+                    if let Some(output_stream_mode) = output_stream_mode {
+                        Self::stream_output(
+                            self.dispatcher.clone(),
+                            output_stream_mode,
+                            InvocationQuery::Invocation(invocation_id),
+                            Self::handle_service_call(
+                                invocation_request,
+                                invocation_target_meta,
+                                self.dispatcher,
+                            ),
+                        )
+                        .await
+                    } else {
+                        Self::handle_service_call(
+                            invocation_request,
+                            invocation_target_meta,
+                            self.dispatcher,
+                        )
+                        .await
+                        .map(into_response_body)
+                    }
                 }
                 InvokeType::Send => {
                     invocation_request_header.execution_time =
@@ -212,28 +263,28 @@ where
                         self.dispatcher,
                     )
                     .await
+                    .map(into_response_body)
                 }
             }
         }
         .instrument(runtime_span)
         .await;
 
-        // Note that we only record (mostly) successful requests here. We might want to
-        // change this in the _near_ future.
-        histogram!(
-            INGRESS_REQUEST_DURATION,
-            "rpc.service" => service_name.clone(),
-            "rpc.method" => handler_name.clone(),
-        )
-        .record(start_time.elapsed());
+        let mut labels = service_handler_labels(&service_name, &handler_name);
+        match &result {
+            Ok(_) => {
+                // Note that we only record the latency of successful requests here.
+                histogram!(INGRESS_REQUEST_DURATION, labels.clone()).record(start_time.elapsed());
 
-        counter!(
-            INGRESS_REQUESTS,
-            "status" => REQUEST_COMPLETED,
-            "rpc.service" => service_name,
-            "rpc.method" => handler_name,
-        )
-        .increment(1);
+                labels.push(Label::new(STATUS_LABEL, REQUEST_COMPLETED));
+                counter!(INGRESS_REQUESTS, labels).increment(1);
+            }
+            Err(err) => {
+                labels.push(Label::new(STATUS_LABEL, REQUEST_FAILED));
+                labels.push(Label::new(ERROR_CODE_LABEL, err.error_code().to_string()));
+                counter!(INGRESS_REQUESTS, labels).increment(1);
+            }
+        }
         result
     }
 
@@ -23,17 +23,19 @@ use tower::ServiceExt;
 use tracing_test::traced_test;
 
 use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
+    AttachInvocationResponse, GetInvocationOutputResponse, ReadOutputChunksResponse,
 };
 use restate_core::TestCoreEnv;
 use restate_test_util::{assert, assert_eq};
+use restate_types::config::Configuration;
 use restate_types::identifiers::{IdempotencyId, InvocationId, ServiceId, WithInvocationId};
 use restate_types::invocation::{
     InvocationQuery, InvocationTarget, InvocationTargetType, VirtualObjectHandlerType,
     WorkflowHandlerType,
 };
 use restate_types::net::partition_processor::{
-    IngressResponseResult, InvocationOutput, SubmittedInvocationNotification,
+    IngressResponseResult, InvocationOutput, OutputChunks, OutputStreamCursor,
+    SubmittedInvocationNotification,
 };
 use restate_types::schema::invocation_target::{
     InputContentType, InputRules, InputValidationRule, InvocationTargetMetadata,
@@ -44,7 +46,7 @@ use super::health::HealthResponse;
 use super::mocks::*;
 use super::service_handler::*;
 use super::ConnectInfo;
-use super::Handler;
+use super::{Handler, ResponseBody};
 use crate::handler::responses::X_RESTATE_ID;
 use crate::MockRequestDispatcher;
 
@@ -939,6 +941,27 @@ async fn private_service() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn forged_payload_reference() {
+    let invocation_id = InvocationId::mock_random();
+    let forged_reference =
+        restate_types::payload_reference::payload_reference(&format!("{invocation_id}/input-abc"));
+
+    let response = handle(
+        hyper::Request::post("http://localhost/greeter.Greeter/greet")
+            .header("content-type", "application/octet-stream")
+            .body(Full::new(forged_reference))
+            .unwrap(),
+        // The request must not be dispatched
+        MockRequestDispatcher::default(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("reserved for internal use"));
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn invalid_input() {
@@ -968,6 +991,45 @@ async fn invalid_input() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn input_not_matching_json_schema() {
+    let response = handle_with_schemas_and_dispatcher(
+        hyper::Request::post("http://localhost/greeter.Greeter/greet")
+            .header("content-type", "application/json")
+            .body(Full::new(Bytes::from_static(br#"{"person": 1}"#)))
+            .unwrap(),
+        MockSchemas::default().with_service_and_target(
+            "greeter.Greeter",
+            "greet",
+            InvocationTargetMetadata {
+                input_rules: InputRules {
+                    input_validation_rules: vec![InputValidationRule::JsonValue {
+                        content_type: InputContentType::MimeTypeAndSubtype(
+                            "application".into(),
+                            "json".into(),
+                        ),
+                        schema: Some(serde_json::json!({
+                            "type": "object",
+                            "properties": {"person": {"type": "string"}},
+                            "required": ["person"]
+                        })),
+                    }],
+                },
+                enforce_input_schema: true,
+                ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
+            },
+        ),
+        MockRequestDispatcher::default(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let response_value: serde_json::Value = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(response_value["violations"][0]["instancePath"], "/person");
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn set_custom_content_type_on_response() {
@@ -1067,6 +1129,104 @@ async fn set_custom_content_type_on_empty_response() {
     );
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn call_service_streaming_output_with_sse() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet")
+        .method(Method::POST)
+        .header("accept", "text/event-stream")
+        .body(Empty::<Bytes>::default())
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_call()
+        .return_once(|invocation_request| {
+            // Streaming calls are retained to read the last chunks, even without idempotency key
+            assert!(invocation_request.header.idempotency_key.is_none());
+            assert_eq!(
+                invocation_request.header.completion_retention_duration,
+                Configuration::pinned().ingress.output_stream_retention()
+            );
+
+            ready(Ok(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_request.invocation_id()),
+                completion_expiry_time: None,
+                response: IngressResponseResult::Success(
+                    invocation_request.header.target,
+                    Bytes::from_static(b"{\"greeting\":\"Igal\"}"),
+                ),
+            }))
+            .boxed()
+        });
+    mock_dispatcher
+        .expect_read_output_chunks()
+        .returning(|_, cursor| {
+            assert_eq!(cursor, OutputStreamCursor::default());
+            ready(Ok(ReadOutputChunksResponse::Chunks(OutputChunks {
+                chunks: vec![ByteString::from("Hello"), ByteString::from("multi\nline")],
+                next: OutputStreamCursor {
+                    entry_index: 3,
+                    chunk_index: 2,
+                },
+                completed: true,
+            })))
+            .boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let (parts, response_body) = response.into_parts();
+    assert!(parts.headers.contains_key(X_RESTATE_ID));
+    assert_eq!(
+        parts.headers.get("content-type").unwrap(),
+        "text/event-stream"
+    );
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    assert_eq!(
+        response_bytes,
+        Bytes::from_static(
+            b"event: chunk\ndata: Hello\n\n\
+            event: chunk\ndata: multi\ndata: line\n\n\
+            event: output\ndata: {\"greeting\":\"Igal\"}\n\n"
+        )
+    );
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn call_service_streaming_output_without_chunks() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet")
+        .method(Method::POST)
+        .header("x-restate-output-stream", "chunked")
+        .body(Empty::<Bytes>::default())
+        .unwrap();
+
+    let mut mock_dispatcher = expect_invocation_and_reply_with_non_empty();
+    mock_dispatcher
+        .expect_read_output_chunks()
+        .returning(|_, cursor| {
+            ready(Ok(ReadOutputChunksResponse::Chunks(OutputChunks {
+                chunks: vec![],
+                next: cursor,
+                completed: true,
+            })))
+            .boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    // Nothing was streamed, the response is the regular one
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    assert_eq!(response_bytes, Bytes::from_static(b"123"));
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn health() {
@@ -1128,7 +1288,7 @@ pub async fn handle_with_schemas_and_dispatcher<B: http_body::Body + Send + 'sta
     mut req: Request<B>,
     schemas: MockSchemas,
     dispatcher: MockRequestDispatcher,
-) -> Response<Full<Bytes>>
+) -> Response<ResponseBody>
 where
     <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     <B as http_body::Body>::Data: Send + Sync + 'static,
@@ -1147,10 +1307,23 @@ where
 pub async fn handle<B: http_body::Body + Send + 'static>(
     req: Request<B>,
     mock_request_dispatcher: MockRequestDispatcher,
-) -> Response<Full<Bytes>>
+) -> Response<ResponseBody>
 where
     <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     <B as http_body::Body>::Data: Send + Sync + 'static,
 {
     handle_with_schemas_and_dispatcher(req, mock_schemas(), mock_request_dispatcher).await
 }
+
+#[test]
+fn error_code_keeps_the_invocation_error_code() {
+    use restate_types::errors::{codes, InvocationError, InvocationErrorCode};
+
+    let custom = super::HandlerError::Invocation(InvocationError::new(1000u16, "custom"));
+    assert_eq!(custom.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(custom.error_code(), InvocationErrorCode::new(1000));
+
+    let not_found = super::HandlerError::ServiceNotFound("greeter.Greeter".to_owned());
+    assert_eq!(not_found.error_code(), codes::NOT_FOUND);
+    assert_eq!(super::HandlerError::NotReady.error_code(), codes::NOT_READY);
+}
@@ -13,16 +13,15 @@ use http::{Method, Request, Response};
 use http_body_util::Full;
 use tracing::{info, warn};
 
-use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
-};
+use restate_core::network::partition_processor_rpc_client::GetInvocationOutputResponse;
 use restate_types::identifiers::ServiceId;
 use restate_types::invocation::InvocationQuery;
 use restate_types::schema::invocation_target::InvocationTargetResolver;
 
+use super::output_stream::OutputStreamMode;
 use super::path_parsing::WorkflowRequestType;
-use super::Handler;
 use super::HandlerError;
+use super::{into_response_body, Handler, ResponseBody};
 use crate::RequestDispatcher;
 
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
@@ -34,7 +33,7 @@ where
         self,
         req: Request<B>,
         workflow_request_type: WorkflowRequestType,
-    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    ) -> Result<Response<ResponseBody>, HandlerError>
     where
         <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     {
@@ -43,10 +42,10 @@ where
                 self.handle_workflow_attach(req, ServiceId::new(name, key))
                     .await
             }
-            WorkflowRequestType::GetOutput(name, key) => {
-                self.handle_workflow_get_output(req, ServiceId::new(name, key))
-                    .await
-            }
+            WorkflowRequestType::GetOutput(name, key) => self
+                .handle_workflow_get_output(req, ServiceId::new(name, key))
+                .await
+                .map(into_response_body),
         }
     }
 
@@ -54,7 +53,7 @@ where
         self,
         req: Request<B>,
         workflow_id: ServiceId,
-    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    ) -> Result<Response<ResponseBody>, HandlerError>
     where
         <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     {
@@ -68,30 +67,20 @@ where
             "Processing workflow attach request"
         );
 
-        // Wait on response
-        let response = match self
-            .dispatcher
-            .attach_invocation(InvocationQuery::Workflow(workflow_id.clone()))
-            .await?
-        {
-            AttachInvocationResponse::NotFound => {
-                return Err(HandlerError::NotFound);
-            }
-            AttachInvocationResponse::NotSupported => {
-                return Err(HandlerError::NotImplemented);
-            }
-            AttachInvocationResponse::Ready(response) => response,
-        };
-
-        Self::reply_with_invocation_response(response, move |invocation_target| {
-            self.schemas
-                .pinned()
-                .resolve_latest_invocation_target(
-                    invocation_target.service_name(),
-                    invocation_target.handler_name(),
-                )
-                .ok_or(HandlerError::NotFound)
-        })
+        let invocation_query = InvocationQuery::Workflow(workflow_id);
+        if let Some(output_stream_mode) = OutputStreamMode::from_headers(req.headers())? {
+            Self::stream_output(
+                self.dispatcher.clone(),
+                output_stream_mode,
+                invocation_query.clone(),
+                self.attach_and_reply(invocation_query),
+            )
+            .await
+        } else {
+            self.attach_and_reply(invocation_query)
+                .await
+                .map(into_response_body)
+        }
     }
 
     pub(crate) async fn handle_workflow_get_output<B: http_body::Body>(
@@ -21,12 +21,14 @@ use std::future::Future;
 use std::net::{IpAddr, SocketAddr};
 
 use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
+    AttachInvocationResponse, GetInvocationOutputResponse, ReadOutputChunksResponse,
 };
 use restate_types::identifiers::InvocationId;
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use restate_types::journal_v2::Signal;
-use restate_types::net::partition_processor::{InvocationOutput, SubmittedInvocationNotification};
+use restate_types::net::partition_processor::{
+    InvocationOutput, OutputStreamCursor, SubmittedInvocationNotification,
+};
 
 /// Client connection information for a given RPC request
 #[derive(Clone, Copy, Debug)]
@@ -79,6 +81,14 @@ pub trait RequestDispatcher {
         invocation_query: InvocationQuery,
     ) -> impl Future<Output = Result<GetInvocationOutputResponse, RequestDispatcherError>> + Send;
 
+    /// Read the output chunks streamed by the invocation after the cursor, waiting for new ones
+    /// when there are none yet.
+    fn read_output_chunks(
+        &self,
+        invocation_query: InvocationQuery,
+        cursor: OutputStreamCursor,
+    ) -> impl Future<Output = Result<ReadOutputChunksResponse, RequestDispatcherError>> + Send;
+
     /// Send invocation response (for awakeables).
     /// **NOTE:** This works only for targeting invocations using Journal Table V1/Service Protocol <= V3.
     fn send_invocation_response(
@@ -162,6 +172,7 @@ mod mocks {
                 workflow_completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                enforce_input_schema: invocation_target_metadata.enforce_input_schema,
             });
             self.1
                 .add(service_name, [(handler_name, invocation_target_metadata)]);
@@ -261,6 +272,15 @@ mod mocks {
             MockRequestDispatcher::get_invocation_output(self, invocation_query)
         }
 
+        fn read_output_chunks(
+            &self,
+            invocation_query: InvocationQuery,
+            cursor: OutputStreamCursor,
+        ) -> impl Future<Output = Result<ReadOutputChunksResponse, RequestDispatcherError>> + Send
+        {
+            MockRequestDispatcher::read_output_chunks(self, invocation_query, cursor)
+        }
+
         fn send_invocation_response(
             &self,
             invocation_response: InvocationResponse,
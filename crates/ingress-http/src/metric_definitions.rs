@@ -16,15 +16,19 @@ pub const INGRESS_REQUESTS: &str = "restate.ingress.requests.total";
 // values of label `status` in INGRESS_REQUEST
 pub const REQUEST_ADMITTED: &str = "admitted";
 pub const REQUEST_COMPLETED: &str = "completed";
+pub const REQUEST_FAILED: &str = "failed";
 pub const REQUEST_DENIED_THROTTLE: &str = "throttled";
 
+pub const STATUS_LABEL: &str = "status";
+pub const ERROR_CODE_LABEL: &str = "error_code";
+
 pub const INGRESS_REQUEST_DURATION: &str = "restate.ingress.request_duration.seconds";
 
 pub(crate) fn describe_metrics() {
     describe_counter!(
         INGRESS_REQUESTS,
         Unit::Count,
-        "Number of ingress requests in different states, see label state to classify"
+        "Number of ingress requests in different states, see label status to classify. Failed requests are labelled by Restate error code"
     );
     describe_histogram!(
         INGRESS_REQUEST_DURATION,
@@ -11,23 +11,28 @@
 use crate::{RequestDispatcher, RequestDispatcherError};
 use anyhow::anyhow;
 use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
+    AttachInvocationResponse, GetInvocationOutputResponse, ReadOutputChunksResponse,
 };
 use restate_core::network::partition_processor_rpc_client::{
     PartitionProcessorRpcClient, PartitionProcessorRpcClientError,
 };
 use restate_core::network::TransportConnect;
+use restate_core::payload_store::PayloadStore;
+use restate_types::config::{Configuration, OutputReadMode};
 use restate_types::identifiers::{InvocationId, PartitionProcessorRpcRequestId, WithInvocationId};
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use restate_types::journal_v2::Signal;
-use restate_types::net::partition_processor::{InvocationOutput, SubmittedInvocationNotification};
+use restate_types::net::partition_processor::{
+    InvocationOutput, OutputStreamCursor, SubmittedInvocationNotification,
+};
 use restate_types::retries::RetryPolicy;
 use std::future::Future;
 use std::time::Duration;
-use tracing::{debug_span, trace, Instrument};
+use tracing::{debug, debug_span, trace, Instrument};
 
 pub struct RpcRequestDispatcher<C> {
     partition_processor_rpc_client: PartitionProcessorRpcClient<C>,
+    payload_store: Option<PayloadStore>,
     retry_policy: RetryPolicy,
 }
 
@@ -35,15 +40,20 @@ impl<T> Clone for RpcRequestDispatcher<T> {
     fn clone(&self) -> Self {
         RpcRequestDispatcher {
             partition_processor_rpc_client: self.partition_processor_rpc_client.clone(),
+            payload_store: self.payload_store.clone(),
             retry_policy: self.retry_policy.clone(),
         }
     }
 }
 
 impl<C> RpcRequestDispatcher<C> {
-    pub fn new(partition_processor_rpc_client: PartitionProcessorRpcClient<C>) -> Self {
+    pub fn new(
+        partition_processor_rpc_client: PartitionProcessorRpcClient<C>,
+        payload_store: Option<PayloadStore>,
+    ) -> Self {
         Self {
             partition_processor_rpc_client,
+            payload_store,
             // TODO figure out how to tune this?
             retry_policy: RetryPolicy::fixed_delay(Duration::from_millis(50), None),
         }
@@ -75,6 +85,49 @@ impl<C> RpcRequestDispatcher<C> {
             .await
             .map_err(|e| anyhow!("Error when trying to route the request internally: {e}"))?)
     }
+
+    /// Reads the invocation output from a partition replica, if configured. Returns `None` if the
+    /// read should go to the leader instead.
+    async fn read_invocation_output_from_replica(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_query: &InvocationQuery,
+    ) -> Option<GetInvocationOutputResponse> {
+        let options = {
+            let config = Configuration::pinned();
+            if config.ingress.output_read_mode != OutputReadMode::Replica {
+                return None;
+            }
+            config.ingress.replica_read_options()
+        };
+
+        match self
+            .partition_processor_rpc_client
+            .read_invocation_output_from_replica(request_id, invocation_query.clone(), options)
+            .await
+        {
+            Ok(response) => Some(response),
+            Err(err) => {
+                debug!("Reading invocation output from replica failed, falling back to the leader: {err}");
+                None
+            }
+        }
+    }
+
+    /// Offloads the request body to the payload store, if configured and the body is large enough.
+    async fn offload_input(
+        &self,
+        mut invocation_request: InvocationRequest,
+    ) -> Result<InvocationRequest, RequestDispatcherError> {
+        if let Some(payload_store) = &self.payload_store {
+            let invocation_id = invocation_request.invocation_id();
+            invocation_request.body = payload_store
+                .offload_input(&invocation_id, invocation_request.body)
+                .await
+                .map_err(|e| anyhow!("Error when trying to offload the request body: {e}"))?;
+        }
+        Ok(invocation_request)
+    }
 }
 
 impl<C> RequestDispatcher for RpcRequestDispatcher<C>
@@ -87,6 +140,7 @@ where
     ) -> Result<SubmittedInvocationNotification, RequestDispatcherError> {
         let request_id = PartitionProcessorRpcRequestId::default();
         let is_idempotent = invocation_request.is_idempotent();
+        let invocation_request = self.offload_input(invocation_request).await?;
         self.execute_rpc(is_idempotent, || {
             self.partition_processor_rpc_client
                 .append_invocation_and_wait_submit_notification(
@@ -104,6 +158,7 @@ where
     ) -> Result<InvocationOutput, RequestDispatcherError> {
         let request_id = PartitionProcessorRpcRequestId::default();
         let is_idempotent = invocation_request.is_idempotent();
+        let invocation_request = self.offload_input(invocation_request).await?;
         self.execute_rpc(is_idempotent, || {
             self.partition_processor_rpc_client
                 .append_invocation_and_wait_output(request_id, invocation_request.clone())
@@ -117,10 +172,22 @@ where
         invocation_query: InvocationQuery,
     ) -> Result<AttachInvocationResponse, RequestDispatcherError> {
         let request_id = PartitionProcessorRpcRequestId::default();
-        self.execute_rpc(true, || {
-            self.partition_processor_rpc_client
-                .attach_invocation(request_id, invocation_query.clone())
-        })
+        async {
+            // Only completed invocations can be served by replicas, attaching to running ones
+            // requires the leader.
+            if let Some(GetInvocationOutputResponse::Ready(output)) = self
+                .read_invocation_output_from_replica(request_id, &invocation_query)
+                .await
+            {
+                return Ok(AttachInvocationResponse::Ready(output));
+            }
+
+            self.execute_rpc(true, || {
+                self.partition_processor_rpc_client
+                    .attach_invocation(request_id, invocation_query.clone())
+            })
+            .await
+        }
         .instrument(debug_span!("attach to invocation", %request_id, invocation_id = %invocation_query.to_invocation_id()))
         .await
     }
@@ -130,11 +197,40 @@ where
         invocation_query: InvocationQuery,
     ) -> Result<GetInvocationOutputResponse, RequestDispatcherError> {
         let request_id = PartitionProcessorRpcRequestId::default();
+        async {
+            if let Some(response) = self
+                .read_invocation_output_from_replica(request_id, &invocation_query)
+                .await
+            {
+                return Ok(response);
+            }
+
+            self.execute_rpc(true, || {
+                self.partition_processor_rpc_client
+                    .get_invocation_output(request_id, invocation_query.clone())
+            })
+            .await
+        }
+        .instrument(debug_span!("get invocation output", %request_id, invocation_id = %invocation_query.to_invocation_id()))
+        .await
+    }
+
+    async fn read_output_chunks(
+        &self,
+        invocation_query: InvocationQuery,
+        cursor: OutputStreamCursor,
+    ) -> Result<ReadOutputChunksResponse, RequestDispatcherError> {
+        let request_id = PartitionProcessorRpcRequestId::default();
+        let max_wait = Configuration::pinned().ingress.output_stream_poll_timeout();
         self.execute_rpc(true, || {
-            self.partition_processor_rpc_client
-                .get_invocation_output(request_id, invocation_query.clone())
+            self.partition_processor_rpc_client.read_output_chunks(
+                request_id,
+                invocation_query.clone(),
+                cursor,
+                max_wait,
+            )
         })
-        .instrument(debug_span!("get invocation output", %request_id, invocation_id = %invocation_query.to_invocation_id()))
+        .instrument(debug_span!("read output chunks", %request_id, invocation_id = %invocation_query.to_invocation_id()))
         .await
     }
 
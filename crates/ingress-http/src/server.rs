@@ -10,10 +10,9 @@
 
 use super::*;
 
-use crate::handler::Handler;
+use crate::handler::{Handler, ResponseBody};
 use codederror::CodedError;
 use http::{Request, Response};
-use http_body_util::Full;
 use hyper::body::Incoming;
 use hyper_util::rt::TokioIo;
 use hyper_util::server::conn::auto;
@@ -185,7 +184,7 @@ where
         F: Send,
         T: tower::Service<
                 Request<Incoming>,
-                Response = Response<Full<Bytes>>,
+                Response = Response<ResponseBody>,
                 Error = Infallible,
                 Future = F,
             > + Clone
@@ -28,13 +28,18 @@ use tracing::{debug, info, info_span, warn, Instrument};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::dispatcher::{DispatchKafkaEvent, KafkaIngressDispatcher, KafkaIngressEvent};
-use crate::metric_definitions::KAFKA_INGRESS_REQUESTS;
+use crate::metric_definitions::{KAFKA_INGRESS_INVALID_RECORDS, KAFKA_INGRESS_REQUESTS};
 use restate_core::{task_center, TaskCenter, TaskHandle, TaskKind};
 use restate_types::invocation::{Header, SpanRelation};
+use restate_types::live::Live;
 use restate_types::message::MessageIndex;
+use restate_types::schema::invocation_target::{
+    InputSchemaValidators, InputValidationError, InvocationTargetResolver,
+};
 use restate_types::schema::subscriptions::{
     EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Subscription,
 };
+use restate_types::schema::Schema;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -94,16 +99,21 @@ impl KafkaDeduplicationId {
 pub struct MessageSender {
     subscription: Subscription,
     dispatcher: KafkaIngressDispatcher,
+    schema: Live<Schema>,
+    input_schema_validators: InputSchemaValidators,
     experimental_feature_kafka_ingress_next: bool,
 
     subscription_id: String,
     ingress_request_counter: metrics::Counter,
+    invalid_records_counter: metrics::Counter,
 }
 
 impl MessageSender {
     pub fn new(
         subscription: Subscription,
         dispatcher: KafkaIngressDispatcher,
+        schema: Live<Schema>,
+        input_schema_validators: InputSchemaValidators,
         experimental_feature_kafka_ingress_next: bool,
     ) -> Self {
         Self {
@@ -112,8 +122,14 @@ impl MessageSender {
                 KAFKA_INGRESS_REQUESTS,
                 "subscription" => subscription.id().to_string()
             ),
+            invalid_records_counter: counter!(
+                KAFKA_INGRESS_INVALID_RECORDS,
+                "subscription" => subscription.id().to_string()
+            ),
             subscription,
             dispatcher,
+            schema,
+            input_schema_validators,
             experimental_feature_kafka_ingress_next,
         }
     }
@@ -143,6 +159,21 @@ impl MessageSender {
         } else {
             Bytes::default()
         };
+
+        // Records not matching the input schema would fail the same way on every retry,
+        // so we skip them instead of stopping the consumer.
+        if let Err(err) = self.validate_payload(&payload) {
+            warn!(
+                parent: &ingress_span,
+                "Skipping record at offset {} of topic {} partition {}: {err}",
+                msg.offset(),
+                msg.topic(),
+                msg.partition()
+            );
+            self.invalid_records_counter.increment(1);
+            return Ok(());
+        }
+
         let headers = Self::generate_events_attributes(&msg, &self.subscription_id);
 
         let (deduplication_id, deduplication_index) =
@@ -174,6 +205,34 @@ impl MessageSender {
         Ok(())
     }
 
+    /// Validates the payload against the input JSON schema of the sink handler, if it enforces it.
+    fn validate_payload(&self, payload: &[u8]) -> Result<(), InputValidationError> {
+        let (service_name, handler_name) = match self.subscription.sink() {
+            Sink::DeprecatedService { name, handler, .. }
+            | Sink::Invocation {
+                event_invocation_target_template:
+                    EventInvocationTargetTemplate::Service { name, handler }
+                    | EventInvocationTargetTemplate::VirtualObject { name, handler, .. }
+                    | EventInvocationTargetTemplate::Workflow { name, handler, .. },
+            } => (name, handler),
+        };
+        let Some(invocation_target_metadata) = self
+            .schema
+            .pinned()
+            .resolve_latest_invocation_target(service_name, handler_name)
+        else {
+            // Nothing to validate against, the invocation fails as usual
+            return Ok(());
+        };
+
+        self.input_schema_validators.validate(
+            service_name,
+            handler_name,
+            &invocation_target_metadata,
+            payload,
+        )
+    }
+
     fn generate_events_attributes(msg: &impl Message, subscription_id: &str) -> Vec<Header> {
         let mut headers = Vec::with_capacity(6);
         headers.push(Header::new("kafka.offset", msg.offset().to_string()));
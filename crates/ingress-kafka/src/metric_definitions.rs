@@ -11,6 +11,7 @@
 use metrics::{describe_counter, Unit};
 
 pub const KAFKA_INGRESS_REQUESTS: &str = "restate.kafka_ingress.requests.total";
+pub const KAFKA_INGRESS_INVALID_RECORDS: &str = "restate.kafka_ingress.invalid_records.total";
 
 pub(crate) fn describe_metrics() {
     describe_counter!(
@@ -18,4 +19,9 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of Kafka ingress requests"
     );
+    describe_counter!(
+        KAFKA_INGRESS_INVALID_RECORDS,
+        Unit::Count,
+        "Number of Kafka records skipped because they don't match the input JSON schema of the handler"
+    );
 }
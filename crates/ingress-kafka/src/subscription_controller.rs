@@ -20,9 +20,11 @@ use restate_bifrost::Bifrost;
 use restate_core::cancellation_watcher;
 use restate_types::config::IngressOptions;
 use restate_types::identifiers::SubscriptionId;
-use restate_types::live::LiveLoad;
+use restate_types::live::{Live, LiveLoad};
 use restate_types::retries::RetryPolicy;
+use restate_types::schema::invocation_target::InputSchemaValidators;
 use restate_types::schema::subscriptions::{Source, Subscription};
+use restate_types::schema::Schema;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::warn;
@@ -44,18 +46,22 @@ pub enum Error {
 // In future versions, we should either pull this out in a separate process, or generify it and move it to the worker, or an ad-hoc module
 pub struct Service {
     dispatcher: KafkaIngressDispatcher,
+    schema: Live<Schema>,
+    input_schema_validators: InputSchemaValidators,
 
     commands_tx: SubscriptionCommandSender,
     commands_rx: SubscriptionCommandReceiver,
 }
 
 impl Service {
-    pub fn new(bifrost: Bifrost) -> Service {
+    pub fn new(bifrost: Bifrost, schema: Live<Schema>) -> Service {
         metric_definitions::describe_metrics();
         let (commands_tx, commands_rx) = mpsc::channel(10);
 
         Service {
             dispatcher: KafkaIngressDispatcher::new(bifrost),
+            schema,
+            input_schema_validators: InputSchemaValidators::default(),
             commands_tx,
             commands_rx,
         }
@@ -147,6 +153,8 @@ impl Service {
             MessageSender::new(
                 subscription,
                 self.dispatcher.clone(),
+                self.schema.clone(),
+                self.input_schema_validators.clone(),
                 options.experimental_feature_kafka_ingress_next(),
             ),
         );
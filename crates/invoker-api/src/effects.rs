@@ -10,7 +10,7 @@
 
 use restate_types::deployment::PinnedDeployment;
 use restate_types::errors::InvocationError;
-use restate_types::identifiers::InvocationId;
+use restate_types::identifiers::{DeploymentId, InvocationId};
 use restate_types::journal::enriched::EnrichedRawEntry;
 use restate_types::journal::EntryIndex;
 use restate_types::journal_v2;
@@ -45,6 +45,16 @@ pub enum EffectKind {
     SuspendedV2 {
         waiting_for_notifications: HashSet<journal_v2::NotificationId>,
     },
+    /// This is sent when an attempt to execute the invocation failed with a transient error,
+    /// and the invoker is going to retry it.
+    TransientError {
+        /// Number of the failed attempt, starting from 1.
+        attempt: u32,
+        deployment_id: Option<DeploymentId>,
+        error: InvocationError,
+        related_entry_index: Option<EntryIndex>,
+        related_entry_name: Option<String>,
+    },
     /// This is sent always after [`Self::JournalEntry`] with `OutputStreamEntry`(s).
     End,
     /// This is sent when the invoker exhausted all its attempts to make progress on the specific invocation.
@@ -21,6 +21,8 @@ use std::future::Future;
 /// Metadata associated with a journal
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct JournalMetadata {
+    /// Number of entries of the journal stream. Events are not part of the stream, because
+    /// they're not sent to the deployment.
     pub length: EntryIndex,
     pub span_context: ServiceInvocationSpanContext,
     pub pinned_deployment: Option<PinnedDeployment>,
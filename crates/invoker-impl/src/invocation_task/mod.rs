@@ -14,28 +14,35 @@ mod service_protocol_runner_v4;
 use super::Notification;
 
 use crate::invocation_task::service_protocol_runner::ServiceProtocolRunner;
-use crate::metric_definitions::INVOKER_TASK_DURATION;
+use crate::metric_definitions::{invocation_task_labels, INVOKER_TASK_DURATION};
 use bytes::Bytes;
-use futures::{future, stream, FutureExt};
+use futures::{future, stream, FutureExt, StreamExt};
 use http::response::Parts as ResponseParts;
 use http::{HeaderName, HeaderValue, Response};
 use http_body::{Body, Frame};
 use metrics::histogram;
+use restate_core::payload_store::{is_payload_reference, PayloadStore, PayloadStoreError};
+use restate_invoker_api::journal_reader::JournalEntry;
 use restate_invoker_api::{
     EagerState, EntryEnricher, InvocationErrorReport, InvokeInputJournal, JournalReader,
     StateReader,
 };
 use restate_service_client::{Request, ResponseBody, ServiceClient, ServiceClientError};
+use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_service_protocol::message::{EncodingError, MessageType};
+use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
 use restate_types::deployment::PinnedDeployment;
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::{DeploymentId, InvocationId, PartitionLeaderEpoch};
 use restate_types::invocation::InvocationTarget;
+use restate_types::journal;
 use restate_types::journal::enriched::EnrichedRawEntry;
-use restate_types::journal::raw::RawEntryCodecError;
+use restate_types::journal::raw::{RawEntryCodec, RawEntryCodecError};
 use restate_types::journal::{EntryIndex, EntryType};
 use restate_types::journal_v2;
+use restate_types::journal_v2::command::InputCommand;
 use restate_types::journal_v2::raw::RawNotification;
+use restate_types::journal_v2::EntryMetadata;
 use restate_types::journal_v2::{CommandIndex, NotificationId};
 use restate_types::live::Live;
 use restate_types::schema::deployment::DeploymentResolver;
@@ -138,6 +145,9 @@ pub(crate) enum InvocationTaskError {
     #[error("malformed ProposeRunCompletion, missing result field")]
     #[code(restate_errors::RT0012)]
     MalformedProposeRunCompletion,
+    #[error("malformed output chunk, the content is not valid UTF-8")]
+    #[code(restate_errors::RT0012)]
+    MalformedOutputChunk,
 
     #[error("error when trying to read the journal: {0}")]
     #[code(restate_errors::RT0006)]
@@ -145,6 +155,9 @@ pub(crate) enum InvocationTaskError {
     #[error("error when trying to read the service instance state: {0}")]
     #[code(restate_errors::RT0006)]
     StateReader(anyhow::Error),
+    #[error("error when trying to read the offloaded input: {0}")]
+    #[code(restate_errors::RT0006)]
+    PayloadStore(#[from] PayloadStoreError),
 
     #[error(transparent)]
     #[code(restate_errors::RT0010)]
@@ -213,6 +226,7 @@ impl InvocationTaskError {
             self,
             InvocationTaskError::JournalReader(_)
                 | InvocationTaskError::StateReader(_)
+                | InvocationTaskError::PayloadStore(_)
                 | InvocationTaskError::NoDeploymentForService
                 | InvocationTaskError::BadNegotiatedServiceProtocolVersion(_)
                 | InvocationTaskError::UnknownDeployment(_)
@@ -306,6 +320,9 @@ pub(super) enum InvocationTaskOutputInner {
     NewNotificationProposal {
         notification: RawNotification,
     },
+    NewEvent {
+        event: journal_v2::Event,
+    },
     Closed,
     Suspended(HashSet<EntryIndex>),
     SuspendedV2(HashSet<NotificationId>),
@@ -344,6 +361,7 @@ pub(super) struct InvocationTask<SR, JR, EE, DMR> {
     journal_reader: JR,
     entry_enricher: EE,
     schemas: Live<DMR>,
+    payload_store: Option<PayloadStore>,
     invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
     invoker_rx: mpsc::UnboundedReceiver<Notification>,
 }
@@ -405,6 +423,7 @@ where
         journal_reader: JR,
         entry_enricher: EE,
         deployment_metadata_resolver: Live<Schemas>,
+        payload_store: Option<PayloadStore>,
         invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
         invoker_rx: mpsc::UnboundedReceiver<Notification>,
     ) -> Self {
@@ -420,6 +439,7 @@ where
             journal_reader,
             entry_enricher,
             schemas: deployment_metadata_resolver,
+            payload_store,
             invoker_tx,
             invoker_rx,
             message_size_limit,
@@ -458,7 +478,11 @@ where
         };
 
         self.send_invoker_tx(inner);
-        histogram!(INVOKER_TASK_DURATION).record(start.elapsed());
+        histogram!(
+            INVOKER_TASK_DURATION,
+            invocation_task_labels(&self.invocation_target, [])
+        )
+        .record(start.elapsed());
     }
 
     async fn select_protocol_version_and_run(
@@ -497,9 +521,18 @@ where
         };
 
         // We execute those concurrently
-        let ((journal_metadata, journal_stream), state_iter) =
+        let ((journal_metadata, mut journal_stream), state_iter) =
             shortcircuit!(tokio::try_join!(read_journal_future, read_state_future));
 
+        // The input entry is always the first one, resolve it in case it was offloaded
+        let mut input_entry = journal_stream.next().await;
+        if let (Some(payload_store), Some(entry)) = (&self.payload_store, input_entry.take()) {
+            input_entry = Some(shortcircuit!(
+                resolve_offloaded_input(payload_store, &self.invocation_id, entry).await
+            ));
+        }
+        let journal_stream = stream::iter(input_entry).chain(journal_stream);
+
         // Resolve the deployment metadata
         let schemas = self.schemas.live_load();
         let (deployment, chosen_service_protocol_version, deployment_changed) =
@@ -600,6 +633,50 @@ impl<SR, JR, EE, DMR> InvocationTask<SR, JR, EE, DMR> {
     }
 }
 
+/// Replaces the payload of the given input entry with the offloaded one, if it's a reference. Only
+/// the inputs offloaded for the invocation itself are resolved, so that a forged reference can't
+/// read the inputs of other invocations.
+async fn resolve_offloaded_input(
+    payload_store: &PayloadStore,
+    invocation_id: &InvocationId,
+    entry: JournalEntry,
+) -> Result<JournalEntry, InvocationTaskError> {
+    match entry {
+        JournalEntry::JournalV1(entry) if entry.ty() == EntryType::Input => {
+            let journal::Entry::Input(input_entry) =
+                entry.deserialize_entry_ref::<ProtobufRawEntryCodec>()?
+            else {
+                return Ok(JournalEntry::JournalV1(entry));
+            };
+            if !is_payload_reference(&input_entry.value) {
+                return Ok(JournalEntry::JournalV1(entry));
+            }
+            let value = payload_store
+                .resolve_input(invocation_id, input_entry.value)
+                .await?;
+            Ok(JournalEntry::JournalV1(
+                ProtobufRawEntryCodec::serialize_as_input_entry(input_entry.headers, value)
+                    .erase_enrichment(),
+            ))
+        }
+        JournalEntry::JournalV2(entry)
+            if entry.ty() == journal_v2::EntryType::Command(journal_v2::CommandType::Input) =>
+        {
+            let mut input_command = entry.decode::<ServiceProtocolV4Codec, InputCommand>()?;
+            if !is_payload_reference(&input_command.payload) {
+                return Ok(JournalEntry::JournalV2(entry));
+            }
+            input_command.payload = payload_store
+                .resolve_input(invocation_id, input_command.payload)
+                .await?;
+            Ok(JournalEntry::JournalV2(
+                journal_v2::Entry::Command(input_command.into()).encode::<ServiceProtocolV4Codec>(),
+            ))
+        }
+        entry => Ok(entry),
+    }
+}
+
 fn service_protocol_version_to_header_value(
     service_protocol_version: ServiceProtocolVersion,
 ) -> HeaderValue {
@@ -31,7 +31,7 @@ use restate_service_client::{Endpoint, Method, Parts, Request};
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
 use restate_service_protocol_v4::message_codec::{
-    proto, Decoder, Encoder, Message, MessageHeader, MessageType,
+    proto, Decoder, Encoder, Message, MessageHeader, MessageType, OUTPUT_CHUNK_MESSAGE_TYPE,
 };
 use restate_types::errors::{codes, InvocationError};
 use restate_types::identifiers::InvocationId;
@@ -45,7 +45,8 @@ use restate_types::journal_v2::command::{
 };
 use restate_types::journal_v2::raw::{RawCommand, RawEntry, RawEntryInner, RawNotification};
 use restate_types::journal_v2::{
-    CommandIndex, CommandType, Entry, EntryType, NotificationId, RunCompletion, RunResult, SignalId,
+    CommandIndex, CommandType, Entry, EntryType, Event, NotificationId, RunCompletion, RunResult,
+    SignalId,
 };
 use restate_types::schema::deployment::{
     Deployment, DeploymentMetadata, DeploymentType, ProtocolType,
@@ -943,9 +944,18 @@ where
                     MessageType::GetLazyStateCompletionNotification,
                 ))
             }
-            Message::Custom(_, _) => {
-                unimplemented!()
+            Message::Custom(OUTPUT_CHUNK_MESSAGE_TYPE, chunk) => {
+                let chunk = crate::shortcircuit!(ByteString::try_from(chunk)
+                    .map_err(|_| InvocationTaskError::MalformedOutputChunk));
+                self.invocation_task
+                    .send_invoker_tx(InvocationTaskOutputInner::NewEvent {
+                        event: Event::output_chunk(chunk),
+                    });
+                TerminalLoopState::Continue(())
             }
+            Message::Custom(ty, _) => TerminalLoopState::Failed(
+                InvocationTaskError::UnexpectedMessageV4(MessageType::Custom(ty)),
+            ),
         }
     }
 
@@ -21,8 +21,9 @@ use input_command::{InputCommand, InvokeCommand};
 use invocation_state_machine::InvocationStateMachine;
 use invocation_task::InvocationTask;
 use invocation_task::{InvocationTaskOutput, InvocationTaskOutputInner};
-use metrics::counter;
+use metrics::{counter, histogram};
 use restate_core::cancellation_watcher;
+use restate_core::payload_store::PayloadStore;
 use restate_errors::warn_it;
 use restate_invoker_api::{
     Effect, EffectKind, EntryEnricher, InvocationErrorReport, InvocationStatusReport,
@@ -53,8 +54,9 @@ use tracing::{debug, trace};
 
 use crate::invocation_task::InvocationTaskError;
 use crate::metric_definitions::{
-    INVOKER_ENQUEUE, INVOKER_INVOCATION_TASK, TASK_OP_COMPLETED, TASK_OP_FAILED, TASK_OP_STARTED,
-    TASK_OP_SUSPENDED,
+    invocation_task_labels, ERROR_CODE_LABEL, INVOKER_ENQUEUE, INVOKER_INVOCATION_ATTEMPTS,
+    INVOKER_INVOCATION_TASK, STATUS_LABEL, TASK_OP_COMPLETED, TASK_OP_FAILED, TASK_OP_STARTED,
+    TASK_OP_SUSPENDED, TRANSIENT_LABEL,
 };
 pub use input_command::ChannelStatusReader;
 pub use input_command::InvokerHandle;
@@ -99,6 +101,7 @@ struct DefaultInvocationTaskRunner<EE, Schemas> {
     client: ServiceClient,
     entry_enricher: EE,
     schemas: Live<Schemas>,
+    payload_store: Option<PayloadStore>,
 }
 
 impl<SR, EE, Schemas> InvocationTaskRunner<SR> for DefaultInvocationTaskRunner<EE, Schemas>
@@ -144,6 +147,7 @@ where
                 storage_reader,
                 self.entry_enricher.clone(),
                 self.schemas.clone(),
+                self.payload_store.clone(),
                 invoker_tx,
                 invoker_rx,
             )
@@ -176,6 +180,7 @@ impl<SR, EE, Schemas> Service<SR, EE, Schemas> {
         deployment_metadata_resolver: Live<Schemas>,
         client: ServiceClient,
         entry_enricher: EE,
+        payload_store: Option<PayloadStore>,
     ) -> Service<SR, EE, Schemas>
     where
         SR: JournalReader<JournalStream = JS> + StateReader + Clone + Send + Sync + 'static,
@@ -200,6 +205,7 @@ impl<SR, EE, Schemas> Service<SR, EE, Schemas> {
                     client,
                     entry_enricher,
                     schemas: deployment_metadata_resolver,
+                    payload_store,
                 },
                 invocation_tasks: Default::default(),
                 retry_timers: Default::default(),
@@ -215,6 +221,7 @@ impl<SR, EE, Schemas> Service<SR, EE, Schemas> {
         invoker_options: &InvokerOptions,
         entry_enricher: EE,
         schemas: Live<Schemas>,
+        payload_store: Option<PayloadStore>,
     ) -> Result<Service<SR, EE, Schemas>, BuildError>
     where
         SR: JournalReader<JournalStream = JS> + StateReader + Clone + Send + Sync + 'static,
@@ -231,6 +238,7 @@ impl<SR, EE, Schemas> Service<SR, EE, Schemas> {
             schemas,
             client,
             entry_enricher,
+            payload_store,
         ))
     }
 }
@@ -429,6 +437,13 @@ where
                             notification
                         ).await
                     },
+                    InvocationTaskOutputInner::NewEvent { event } => {
+                        self.handle_new_event(
+                            partition,
+                            invocation_id,
+                            event
+                        ).await
+                    },
                     InvocationTaskOutputInner::Closed => {
                         self.handle_invocation_task_closed(partition, invocation_id).await
                     },
@@ -562,6 +577,53 @@ where
         });
     }
 
+    #[instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            restate.invocation.id = %invocation_id,
+            restate.invoker.partition_leader_epoch = ?partition,
+            restate.journal.event.ty = %event.ty,
+        )
+    )]
+    async fn handle_new_event(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+        event: journal_v2::Event,
+    ) {
+        if let Some((output_tx, ism)) = self
+            .invocation_state_machine_manager
+            .resolve_invocation(partition, &invocation_id)
+        {
+            trace!(
+                restate.invocation.target = %ism.invocation_target,
+                "Received a new event. Invocation state: {:?}",
+                ism.invocation_state_debug()
+            );
+            if let Some(pinned_deployment) = ism.pinned_deployment_to_notify() {
+                let _ = output_tx
+                    .send(Effect {
+                        invocation_id,
+                        kind: EffectKind::PinnedDeployment(pinned_deployment),
+                    })
+                    .await;
+            }
+            let _ = output_tx
+                .send(Effect {
+                    invocation_id,
+                    kind: EffectKind::JournalEntryV2 {
+                        command_index_to_ack: None,
+                        entry: RawEntry::new(RawEntryHeader::new(), event),
+                    },
+                })
+                .await;
+        } else {
+            // If no state machine, this might be an event for an aborted invocation.
+            trace!("No state machine found for given event");
+        }
+    }
+
     #[instrument(
         level = "trace",
         skip_all,
@@ -888,11 +950,16 @@ where
             .invocation_state_machine_manager
             .remove_invocation(partition, &invocation_id)
         {
-            counter!(INVOKER_INVOCATION_TASK, "status" => TASK_OP_COMPLETED).increment(1);
+            counter!(
+                INVOKER_INVOCATION_TASK,
+                invocation_task_labels(&ism.invocation_target, [(STATUS_LABEL, TASK_OP_COMPLETED)])
+            )
+            .increment(1);
             trace!(
                 restate.invocation.target = %ism.invocation_target,
                 "Invocation task closed correctly");
             self.quota.unreserve_slot();
+            self.record_invocation_attempts(&partition, &invocation_id, &ism.invocation_target);
             self.status_store.on_end(&partition, &invocation_id);
             let _ = sender
                 .send(Effect {
@@ -924,11 +991,16 @@ where
             .invocation_state_machine_manager
             .remove_invocation(partition, &invocation_id)
         {
-            counter!(INVOKER_INVOCATION_TASK, "status" => TASK_OP_SUSPENDED).increment(1);
+            counter!(
+                INVOKER_INVOCATION_TASK,
+                invocation_task_labels(&ism.invocation_target, [(STATUS_LABEL, TASK_OP_SUSPENDED)])
+            )
+            .increment(1);
             trace!(
                 restate.invocation.target = %ism.invocation_target,
                 "Suspending invocation");
             self.quota.unreserve_slot();
+            self.record_invocation_attempts(&partition, &invocation_id, &ism.invocation_target);
             self.status_store.on_end(&partition, &invocation_id);
             let _ = sender
                 .send(Effect {
@@ -962,12 +1034,17 @@ where
             .invocation_state_machine_manager
             .remove_invocation(partition, &invocation_id)
         {
-            counter!(INVOKER_INVOCATION_TASK, "status" => TASK_OP_SUSPENDED).increment(1);
+            counter!(
+                INVOKER_INVOCATION_TASK,
+                invocation_task_labels(&ism.invocation_target, [(STATUS_LABEL, TASK_OP_SUSPENDED)])
+            )
+            .increment(1);
             trace!(
                 restate.invocation.target = %ism.invocation_target,
                 "Suspending invocation"
             );
             self.quota.unreserve_slot();
+            self.record_invocation_attempts(&partition, &invocation_id, &ism.invocation_target);
             self.status_store.on_end(&partition, &invocation_id);
             let _ = sender
                 .send(Effect {
@@ -1085,6 +1162,23 @@ where
 
     // --- Helpers
 
+    /// Records how many times the invocation task was started until it completed, suspended or
+    /// failed, which must be called before its status is dropped.
+    fn record_invocation_attempts(
+        &self,
+        partition: &PartitionLeaderEpoch,
+        invocation_id: &InvocationId,
+        invocation_target: &InvocationTarget,
+    ) {
+        if let Some(report) = self.status_store.get(partition, invocation_id) {
+            histogram!(
+                INVOKER_INVOCATION_ATTEMPTS,
+                invocation_task_labels(invocation_target, [])
+            )
+            .record(report.start_count as f64);
+        }
+    }
+
     async fn handle_error_event(
         &mut self,
         partition: PartitionLeaderEpoch,
@@ -1092,14 +1186,24 @@ where
         error: InvocationTaskError,
         mut ism: InvocationStateMachine,
     ) {
+        let error_code = codederror::CodedError::code(&error)
+            .map(codederror::Code::code)
+            .unwrap_or("unknown");
         match ism.handle_task_error(
             error.next_retry_interval_override(),
             error.should_bump_start_message_retry_count_since_last_stored_entry(),
         ) {
             Some(next_retry_timer_duration) if error.is_transient() => {
-                counter!(INVOKER_INVOCATION_TASK,
-                    "status" => TASK_OP_FAILED,
-                    "transient" => "true"
+                counter!(
+                    INVOKER_INVOCATION_TASK,
+                    invocation_task_labels(
+                        &ism.invocation_target,
+                        [
+                            (STATUS_LABEL, TASK_OP_FAILED),
+                            (TRANSIENT_LABEL, "true"),
+                            (ERROR_CODE_LABEL, error_code),
+                        ]
+                    )
                 )
                 .increment(1);
                 warn_it!(
@@ -1110,11 +1214,34 @@ where
                     humantime::format_duration(next_retry_timer_duration));
                 trace!("Invocation state: {:?}.", ism.invocation_state_debug());
                 let next_retry_at = SystemTime::now() + next_retry_timer_duration;
+                let error_report = error.into_invocation_error_report();
+
+                // Let the partition processor record the failed attempt
+                let (attempt, deployment_id) = self
+                    .status_store
+                    .get(&partition, &invocation_id)
+                    .map(|report| (report.start_count as u32, report.last_attempt_deployment_id))
+                    .unwrap_or_default();
+                let _ = self
+                    .invocation_state_machine_manager
+                    .resolve_partition_sender(partition)
+                    .expect("Partition should be registered")
+                    .send(Effect {
+                        invocation_id,
+                        kind: EffectKind::TransientError {
+                            attempt,
+                            deployment_id,
+                            error: error_report.err.clone(),
+                            related_entry_index: error_report.related_entry_index,
+                            related_entry_name: error_report.related_entry_name.clone(),
+                        },
+                    })
+                    .await;
 
                 self.status_store.on_failure(
                     partition,
                     invocation_id,
-                    error.into_invocation_error_report(),
+                    error_report,
                     Some(next_retry_at),
                 );
                 self.invocation_state_machine_manager.register_invocation(
@@ -1126,9 +1253,16 @@ where
                     .sleep_until(next_retry_at, (partition, invocation_id));
             }
             _ => {
-                counter!(INVOKER_INVOCATION_TASK,
-                    "status" => TASK_OP_FAILED,
-                    "transient" => "false"
+                counter!(
+                    INVOKER_INVOCATION_TASK,
+                    invocation_task_labels(
+                        &ism.invocation_target,
+                        [
+                            (STATUS_LABEL, TASK_OP_FAILED),
+                            (TRANSIENT_LABEL, "false"),
+                            (ERROR_CODE_LABEL, error_code),
+                        ]
+                    )
                 )
                 .increment(1);
                 warn_it!(
@@ -1137,6 +1271,7 @@ where
                     restate.invocation.target = %ism.invocation_target,
                     "Error when executing the invocation, not going to retry.");
                 self.quota.unreserve_slot();
+                self.record_invocation_attempts(&partition, &invocation_id, &ism.invocation_target);
                 self.status_store.on_end(&partition, &invocation_id);
 
                 let _ = self
@@ -1184,7 +1319,11 @@ where
             "Invocation task started state. Invocation state: {:?}",
             ism.invocation_state_debug()
         );
-        counter!(INVOKER_INVOCATION_TASK, "status" => TASK_OP_STARTED).increment(1);
+        counter!(
+            INVOKER_INVOCATION_TASK,
+            invocation_task_labels(&ism.invocation_target, [(STATUS_LABEL, TASK_OP_STARTED)])
+        )
+        .increment(1);
         self.invocation_state_machine_manager
             .register_invocation(partition, invocation_id, ism);
     }
@@ -1443,6 +1582,7 @@ mod tests {
             )
             .unwrap(),
             entry_enricher::test_util::MockEntryEnricher,
+            None,
         );
 
         let mut handle = service.handle();
@@ -10,10 +10,14 @@
 
 /// Optional to have but adds description/help message to the metrics emitted to
 /// the metrics' sink.
-use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+use metrics::{describe_counter, describe_gauge, describe_histogram, Label, Unit};
+
+use restate_core::metric_labels::service_handler_labels;
+use restate_types::invocation::InvocationTarget;
 
 pub const INVOKER_ENQUEUE: &str = "restate.invoker.enqueue.total";
 pub const INVOKER_INVOCATION_TASK: &str = "restate.invoker.invocation_task.total";
+pub const INVOKER_INVOCATION_ATTEMPTS: &str = "restate.invoker.invocation_attempts";
 pub const INVOKER_AVAILABLE_SLOTS: &str = "restate.invoker.available_slots";
 pub const INVOKER_TASK_DURATION: &str = "restate.invoker.task_duration.seconds";
 
@@ -22,6 +26,10 @@ pub const TASK_OP_SUSPENDED: &str = "suspended";
 pub const TASK_OP_FAILED: &str = "failed";
 pub const TASK_OP_COMPLETED: &str = "completed";
 
+pub const STATUS_LABEL: &str = "status";
+pub const TRANSIENT_LABEL: &str = "transient";
+pub const ERROR_CODE_LABEL: &str = "error_code";
+
 pub(crate) fn describe_metrics() {
     describe_counter!(
         INVOKER_ENQUEUE,
@@ -32,7 +40,13 @@ pub(crate) fn describe_metrics() {
     describe_counter!(
         INVOKER_INVOCATION_TASK,
         Unit::Count,
-        "Invocation task operation"
+        "Invocation task operation, see label status to classify. Failures are labelled by error code"
+    );
+
+    describe_histogram!(
+        INVOKER_INVOCATION_ATTEMPTS,
+        Unit::Count,
+        "Number of attempts of an invocation task until it completed, suspended or failed"
     );
 
     describe_gauge!(
@@ -47,3 +61,21 @@ pub(crate) fn describe_metrics() {
         "Time taken to complete an invoker task"
     )
 }
+
+/// Labels of the metrics of an invocation task, including the service and handler labels if
+/// enabled.
+pub(crate) fn invocation_task_labels(
+    invocation_target: &InvocationTarget,
+    labels: impl IntoIterator<Item = (&'static str, &'static str)>,
+) -> Vec<Label> {
+    let mut result = service_handler_labels(
+        invocation_target.service_name(),
+        invocation_target.handler_name(),
+    );
+    result.extend(
+        labels
+            .into_iter()
+            .map(|(key, value)| Label::new(key, value)),
+    );
+    result
+}
@@ -38,6 +38,16 @@ impl InvocationStatusStore {
             })
     }
 
+    pub(super) fn get(
+        &self,
+        partition: &PartitionLeaderEpoch,
+        invocation_id: &InvocationId,
+    ) -> Option<&InvocationStatusReportInner> {
+        self.0
+            .get(partition)
+            .and_then(|inner| inner.get(invocation_id))
+    }
+
     // -- Methods used by the invoker to notify the status
 
     pub(super) fn on_start(
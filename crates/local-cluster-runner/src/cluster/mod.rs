@@ -21,8 +21,11 @@ use tracing::info;
 use typed_builder::TypedBuilder;
 
 use restate_types::errors::GenericError;
+use restate_types::PlainNodeId;
 
-use crate::node::{HealthCheck, HealthError, Node, NodeStartError, StartedNode};
+use crate::node::{
+    FaultInjectionError, HealthCheck, HealthError, Node, NodeStartError, StartedNode,
+};
 
 #[derive(Debug, Serialize, Deserialize, TypedBuilder)]
 pub struct Cluster {
@@ -178,6 +181,57 @@ impl StartedCluster {
         Ok(())
     }
 
+    /// Partition the network between the nodes at the given indexes, so that no node on one side
+    /// can communicate with any node on the other side. Nodes on the same side are unaffected.
+    pub async fn partition(
+        &mut self,
+        side_a: &[usize],
+        side_b: &[usize],
+    ) -> Result<(), FaultInjectionError> {
+        let ids_a = self.node_ids(side_a).await?;
+        let ids_b = self.node_ids(side_b).await?;
+
+        for (sides, other_side_ids) in [(side_a, &ids_b), (side_b, &ids_a)] {
+            for &idx in sides {
+                self.nodes[idx]
+                    .update_config(|config| {
+                        let partitioned_from =
+                            &mut config.networking.fault_injection.partitioned_from;
+                        for node_id in other_side_ids {
+                            if !partitioned_from.contains(node_id) {
+                                partitioned_from.push(*node_id);
+                            }
+                        }
+                    })
+                    .await?;
+            }
+        }
+        info!("Partitioned nodes {ids_a:?} from nodes {ids_b:?}");
+        Ok(())
+    }
+
+    /// Remove every network partition previously injected with [`Self::partition`].
+    pub async fn heal_partitions(&mut self) -> Result<(), FaultInjectionError> {
+        for node in &mut self.nodes {
+            if node
+                .config()
+                .networking
+                .fault_injection
+                .partitioned_from
+                .is_empty()
+            {
+                continue;
+            }
+            node.update_config(|config| config.networking.fault_injection.partitioned_from.clear())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn node_ids(&self, indexes: &[usize]) -> Result<Vec<PlainNodeId>, FaultInjectionError> {
+        future::try_join_all(indexes.iter().map(|idx| self.nodes[*idx].node_id())).await
+    }
+
     pub async fn push_node(&mut self, node: Node) -> Result<(), NodeStartError> {
         self.nodes.push(
             node.start_clustered(self.base_dir.as_path(), self.cluster_name.clone())
@@ -21,7 +21,7 @@ use restate_types::logs::metadata::ProviderConfiguration;
 use restate_types::partition_table::PartitionReplication;
 use restate_types::retries::RetryPolicy;
 use restate_types::{
-    config::{Configuration, MetadataStoreClient},
+    config::{Configuration, MetadataStoreClient, NetworkFaultInjection},
     errors::GenericError,
     metadata_store::keys::NODES_CONFIG_KEY,
     net::{AdvertisedAddress, BindAddress},
@@ -97,6 +97,11 @@ pub struct Node {
     inherit_env: bool,
     #[builder(default)]
     env: Vec<(String, String)>,
+    /// Maximum size of any file written by the node. Writes beyond it fail as if the disk was
+    /// full, which makes it possible to test the behaviour of a node running out of disk space.
+    #[builder(default)]
+    #[serde(default)]
+    file_size_limit: Option<u64>,
     #[builder(default)]
     #[serde(skip)]
     searcher: Searcher,
@@ -120,6 +125,18 @@ pub enum NodeStartError {
     SpawnError(io::Error),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum FaultInjectionError {
+    #[error("Node {0} is not running")]
+    NotRunning(String),
+    #[error("Failed to determine the id of node {0}")]
+    UnknownNodeId(String),
+    #[error("Failed to signal node {0}: {1}")]
+    Signal(String, io::Error),
+    #[error("Failed to update the config of node {0}: {1}")]
+    UpdateConfig(String, io::Error),
+}
+
 impl Node {
     pub fn node_name(&self) -> &str {
         self.base_config.node_name()
@@ -287,6 +304,7 @@ impl Node {
             args,
             inherit_env,
             env,
+            file_size_limit,
             searcher,
         } = self;
 
@@ -333,7 +351,7 @@ impl Node {
         } else {
             &mut cmd
         }
-        .env("RESTATE_CONFIG", node_config_file)
+        .env("RESTATE_CONFIG", &node_config_file)
         .env("DO_NOT_TRACK", "true") // avoid sending telemetry as part of tests
         .envs(env)
         .stdin(Stdio::null())
@@ -343,6 +361,22 @@ impl Node {
         .process_group(0) // avoid terminal control C being propagated
         .args(&args);
 
+        if let Some(file_size_limit) = file_size_limit {
+            // SAFETY: only async-signal-safe syscalls are performed between fork and exec
+            unsafe {
+                cmd.pre_exec(move || {
+                    // Exceeding the limit makes writes fail with EFBIG instead of killing the
+                    // process, like a full disk would do
+                    nix::sys::signal::signal(
+                        nix::sys::signal::SIGXFSZ,
+                        nix::sys::signal::SigHandler::SigIgn,
+                    )
+                    .map_err(|errno| io::Error::from_raw_os_error(errno as i32))?;
+                    rlimit::setrlimit(rlimit::Resource::FSIZE, file_size_limit, file_size_limit)
+                });
+            }
+        }
+
         let mut child = cmd.spawn().map_err(NodeStartError::SpawnError)?;
         let pid = child.id().expect("child to have a pid");
         info!(
@@ -420,6 +454,7 @@ impl Node {
 
         Ok(StartedNode {
             log_file: node_log_filename,
+            config_file: node_config_file,
             status: StartedNodeStatus::Running {
                 child_handle,
                 searcher,
@@ -510,6 +545,7 @@ impl TryInto<OsString> for BinarySource {
 
 pub struct StartedNode {
     log_file: PathBuf,
+    config_file: PathBuf,
     status: StartedNodeStatus,
     config: Configuration,
 }
@@ -630,6 +666,86 @@ impl StartedNode {
         }
     }
 
+    /// Send a SIGSTOP to the current process, pausing it until [`Self::resume`] is called.
+    pub fn pause(&self) -> Result<(), FaultInjectionError> {
+        self.signal(nix::sys::signal::SIGSTOP)
+    }
+
+    /// Send a SIGCONT to the current process, resuming it after [`Self::pause`].
+    pub fn resume(&self) -> Result<(), FaultInjectionError> {
+        self.signal(nix::sys::signal::SIGCONT)
+    }
+
+    fn signal(&self, signal: nix::sys::signal::Signal) -> Result<(), FaultInjectionError> {
+        let Some(pid) = self.pid() else {
+            return Err(FaultInjectionError::NotRunning(self.node_name().to_owned()));
+        };
+        info!(
+            "Sending {signal} to node {} (pid {})",
+            self.node_name(),
+            pid
+        );
+        nix::sys::signal::kill(
+            nix::unistd::Pid::from_raw(pid.try_into().expect("pid_t = i32")),
+            signal,
+        )
+        .map_err(|errno| {
+            FaultInjectionError::Signal(
+                self.node_name().to_owned(),
+                io::Error::from_raw_os_error(errno as i32),
+            )
+        })
+    }
+
+    /// Update the config of the running node. The node picks up the changes asynchronously by
+    /// watching its config file, hence only options which can be changed at runtime are affected.
+    pub async fn update_config(
+        &mut self,
+        update: impl FnOnce(&mut Configuration),
+    ) -> Result<(), FaultInjectionError> {
+        update(&mut self.config);
+        let config_dump = self.config.dump().map_err(|err| {
+            FaultInjectionError::UpdateConfig(self.node_name().to_owned(), io::Error::other(err))
+        })?;
+        tokio::fs::write(&self.config_file, config_dump)
+            .await
+            .map_err(|err| FaultInjectionError::UpdateConfig(self.node_name().to_owned(), err))
+    }
+
+    /// Set the faults injected in the messages this node receives from the other nodes,
+    /// replacing the previous ones. Faults are only applied by a restate-server built with its
+    /// `fault-injection` feature, as the one of the server integration tests.
+    pub async fn set_network_faults(
+        &mut self,
+        faults: NetworkFaultInjection,
+    ) -> Result<(), FaultInjectionError> {
+        self.update_config(|config| config.networking.fault_injection = faults)
+            .await
+    }
+
+    /// Get the id of the node, either the configured one or the one it was assigned when
+    /// joining the cluster.
+    pub async fn node_id(&self) -> Result<PlainNodeId, FaultInjectionError> {
+        if let Some(node_id) = self.config.common.force_node_id {
+            return Ok(node_id);
+        }
+
+        let unknown_node_id = || FaultInjectionError::UnknownNodeId(self.node_name().to_owned());
+        let metadata_client = self
+            .metadata_client()
+            .await
+            .map_err(|_| unknown_node_id())?;
+        let nodes_config = metadata_client
+            .get::<NodesConfiguration>(NODES_CONFIG_KEY.clone())
+            .await
+            .map_err(|_| unknown_node_id())?
+            .ok_or_else(unknown_node_id)?;
+        nodes_config
+            .find_node_by_name(self.node_name())
+            .map(|node| node.current_generation.as_plain())
+            .ok_or_else(unknown_node_id)
+    }
+
     /// Get the pid of the subprocess. Returns none after it has exited.
     pub fn pid(&self) -> Option<u32> {
         match self.status {
@@ -21,7 +21,7 @@ use tracing::{debug, error, info, trace, warn};
 
 use crate::cluster_marker::ClusterValidationError;
 use crate::init::NodeInit;
-use crate::network_server::NetworkServer;
+use crate::network_server::{HealthProbe, NetworkServer};
 use crate::roles::{AdminRole, BaseRole, IngressRole, WorkerRole};
 use codederror::CodedError;
 use restate_bifrost::BifrostService;
@@ -32,6 +32,7 @@ use restate_core::network::{
     GrpcConnector, MessageRouterBuilder, NetworkServerBuilder, Networking,
 };
 use restate_core::partitions::{spawn_partition_routing_refresher, PartitionRoutingRefresher};
+use restate_core::payload_store::PayloadStore;
 use restate_core::{cancellation_watcher, Metadata, TaskKind};
 use restate_core::{spawn_metadata_manager, MetadataBuilder, MetadataManager, TaskCenter};
 #[cfg(feature = "replicated-loglet")]
@@ -112,6 +113,10 @@ pub enum BuildError {
     #[error("building metadata store failed: {0}")]
     #[code(unknown)]
     MetadataStore(#[from] anyhow::Error),
+
+    #[error("building payload store failed: {0}")]
+    #[code(unknown)]
+    PayloadStore(anyhow::Error),
 }
 
 pub struct Node {
@@ -268,6 +273,8 @@ impl Node {
                 metadata.updateable_partition_table(),
                 partition_routing_refresher.partition_routing(),
                 &mut router_builder,
+                PayloadStore::create_if_configured(&config.common.payload_store)
+                    .map_err(BuildError::PayloadStore)?,
             ))
         } else {
             None
@@ -342,6 +349,15 @@ impl Node {
             let common_options = config.common.clone();
             let connection_manager = self.networking.connection_manager().clone();
             let metadata_store_client = self.metadata_store_client.clone();
+            let health_probe = HealthProbe::new(
+                self.health.clone(),
+                config.common.roles,
+                self.worker_role
+                    .as_ref()
+                    .map(|role| role.partition_processor_manager_handle()),
+                self.partition_routing_refresher.partition_routing(),
+                self.metadata_store_client.clone(),
+            );
             async move {
                 NetworkServer::run(
                     health,
@@ -349,6 +365,7 @@ impl Node {
                     self.server_builder,
                     common_options,
                     metadata_store_client,
+                    health_probe,
                 )
                 .await?;
                 Ok(())
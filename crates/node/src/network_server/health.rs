@@ -0,0 +1,498 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Liveness and readiness of the node, as reported by `/health/live` and `/health/ready`.
+//!
+//! The node is ready once it is alive and all the roles considered for readiness are serving,
+//! see [`HealthOptions`] for the criteria.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::Json;
+use enumset::EnumSet;
+use http::StatusCode;
+use serde::Serialize;
+
+use restate_core::metadata_store::MetadataStoreClient;
+use restate_core::partitions::PartitionRouting;
+use restate_core::worker_api::ProcessorsManagerHandle;
+use restate_types::cluster::cluster_state::{PartitionProcessorStatus, ReplayStatus, RunMode};
+use restate_types::config::{Configuration, HealthOptions};
+use restate_types::health::Health;
+use restate_types::identifiers::PartitionId;
+use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
+use restate_types::nodes_config::Role;
+use restate_types::protobuf::common::{
+    AdminStatus, IngressStatus, LogServerStatus, MetadataServerStatus, NodeRpcStatus, NodeStatus,
+    WorkerStatus,
+};
+
+use crate::network_server::state::NodeCtrlHandlerState;
+
+/// Upper bound for querying the partition processors, so that a busy partition processor
+/// manager doesn't hold up probes.
+const PARTITION_PROCESSORS_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+/// Upper bound for reading from the metadata store, which doesn't complete while the metadata
+/// store cluster has no quorum.
+const METADATA_STORE_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What the health endpoints need to know about the node.
+#[derive(Clone)]
+pub struct HealthProbe {
+    health: Health,
+    roles: EnumSet<Role>,
+    processor_manager_handle: Option<ProcessorsManagerHandle>,
+    partition_routing: PartitionRouting,
+    metadata_store_client: MetadataStoreClient,
+}
+
+impl HealthProbe {
+    pub fn new(
+        health: Health,
+        roles: EnumSet<Role>,
+        processor_manager_handle: Option<ProcessorsManagerHandle>,
+        partition_routing: PartitionRouting,
+        metadata_store_client: MetadataStoreClient,
+    ) -> Self {
+        Self {
+            health,
+            roles,
+            processor_manager_handle,
+            partition_routing,
+            metadata_store_client,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LivenessReport {
+    live: bool,
+    node_status: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    ready: bool,
+    node_status: String,
+    node_rpc_status: String,
+    roles: BTreeMap<String, RoleReadiness>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoleReadiness {
+    ready: bool,
+    /// Whether the role counts towards the readiness of the node
+    required: bool,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partitions: Option<PartitionsDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    routing: Option<RoutingDetails>,
+}
+
+impl RoleReadiness {
+    fn new(ready: bool, status: impl std::fmt::Debug) -> Self {
+        Self {
+            ready,
+            required: true,
+            status: format!("{status:?}"),
+            reason: None,
+            partitions: None,
+            routing: None,
+        }
+    }
+
+    fn not_ready(mut self, reason: impl Into<String>) -> Self {
+        self.ready = false;
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+/// The partition processors running on a worker.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PartitionsDetails {
+    running: usize,
+    active: usize,
+    leaders: usize,
+    /// Partition processors meant to lead, but not actively leading yet
+    pending_leaders: usize,
+}
+
+impl PartitionsDetails {
+    fn from_state<'a>(state: impl IntoIterator<Item = &'a PartitionProcessorStatus>) -> Self {
+        let mut details = Self::default();
+        for status in state {
+            let is_active = status.replay_status == ReplayStatus::Active;
+            details.running += 1;
+            details.active += usize::from(is_active);
+            if status.planned_mode == RunMode::Leader {
+                if status.effective_mode == RunMode::Leader && is_active {
+                    details.leaders += 1;
+                } else {
+                    details.pending_leaders += 1;
+                }
+            }
+        }
+        details
+    }
+}
+
+/// The partitions the ingress knows the leader of. Whether the leaders are reachable is not
+/// checked.
+#[derive(Debug, Serialize)]
+pub struct RoutingDetails {
+    partitions: usize,
+    partitions_with_unknown_leader: usize,
+}
+
+// -- Direct HTTP Handlers --
+pub async fn live(State(state): State<NodeCtrlHandlerState>) -> (StatusCode, Json<LivenessReport>) {
+    let node_status = state.health_probe.health.current_node_status();
+    let live = !matches!(node_status, NodeStatus::ShuttingDown);
+
+    (
+        status_code(live),
+        Json(LivenessReport {
+            live,
+            node_status: format!("{node_status:?}"),
+        }),
+    )
+}
+
+pub async fn ready(
+    State(state): State<NodeCtrlHandlerState>,
+) -> (StatusCode, Json<ReadinessReport>) {
+    let options = Configuration::pinned().common.health.clone();
+    let report = readiness_report(&state, &options).await;
+    (status_code(report.ready), Json(report))
+}
+
+async fn readiness_report(
+    state: &NodeCtrlHandlerState,
+    options: &HealthOptions,
+) -> ReadinessReport {
+    let probe = &state.health_probe;
+    let node_status = probe.health.current_node_status();
+    let node_rpc_status = probe.health.current_node_rpc_status();
+    let mut ready = node_status == NodeStatus::Alive && node_rpc_status == NodeRpcStatus::Ready;
+
+    let required_roles = options.readiness_roles.unwrap_or(probe.roles);
+    let mut roles = BTreeMap::new();
+    for role in probe.roles {
+        let mut readiness = role_readiness(state, options, role).await;
+        readiness.required = required_roles.contains(role);
+        if readiness.required {
+            ready &= readiness.ready;
+        }
+        roles.insert(role.to_string(), readiness);
+    }
+
+    ReadinessReport {
+        ready,
+        node_status: format!("{node_status:?}"),
+        node_rpc_status: format!("{node_rpc_status:?}"),
+        roles,
+    }
+}
+
+async fn role_readiness(
+    state: &NodeCtrlHandlerState,
+    options: &HealthOptions,
+    role: Role,
+) -> RoleReadiness {
+    let probe = &state.health_probe;
+    match role {
+        Role::Worker => worker_readiness(probe, options).await,
+        Role::Admin => {
+            let status = probe.health.current_admin_status();
+            RoleReadiness::new(status == AdminStatus::Ready, status)
+        }
+        Role::MetadataServer => metadata_server_readiness(probe).await,
+        Role::LogServer => {
+            let status = probe.health.current_log_server_status();
+            let readiness = RoleReadiness::new(status == LogServerStatus::Ready, status);
+            if status == LogServerStatus::Failsafe {
+                readiness.not_ready("the log-server is in failsafe mode and rejects stores")
+            } else {
+                readiness
+            }
+        }
+        Role::HttpIngress => ingress_readiness(state, options),
+    }
+}
+
+async fn worker_readiness(probe: &HealthProbe, options: &HealthOptions) -> RoleReadiness {
+    let status = probe.health.current_worker_status();
+    let mut readiness = RoleReadiness::new(status == WorkerStatus::Ready, status);
+
+    let Some(handle) = &probe.processor_manager_handle else {
+        return readiness;
+    };
+
+    match tokio::time::timeout(PARTITION_PROCESSORS_QUERY_TIMEOUT, handle.get_state()).await {
+        Ok(Ok(processors)) => {
+            let details = PartitionsDetails::from_state(processors.values());
+            if options.readiness_require_partition_leaders && details.pending_leaders > 0 {
+                readiness = readiness.not_ready(format!(
+                    "{} partition processors are not leading yet",
+                    details.pending_leaders
+                ));
+            }
+            readiness.partitions = Some(details);
+            readiness
+        }
+        Ok(Err(_)) => readiness.not_ready("the partition processor manager is shut down"),
+        Err(_) => readiness.not_ready("timed out querying the partition processors"),
+    }
+}
+
+/// Being a member of the metadata store cluster is not enough to serve requests, the cluster also
+/// needs a quorum. Reads from the metadata store are linearizable, so they only succeed while
+/// there is one.
+async fn metadata_server_readiness(probe: &HealthProbe) -> RoleReadiness {
+    let status = probe.health.current_metadata_store_status();
+    let readiness = RoleReadiness::new(status == MetadataServerStatus::Member, status);
+    match status {
+        MetadataServerStatus::Member => {}
+        MetadataServerStatus::Standby => {
+            return readiness.not_ready("not a member of the metadata store cluster");
+        }
+        MetadataServerStatus::AwaitingProvisioning => {
+            return readiness.not_ready("the cluster has not been provisioned yet");
+        }
+        _ => return readiness,
+    }
+
+    match tokio::time::timeout(
+        METADATA_STORE_QUERY_TIMEOUT,
+        probe
+            .metadata_store_client
+            .get_version(NODES_CONFIG_KEY.clone()),
+    )
+    .await
+    {
+        Ok(Ok(_)) => readiness,
+        Ok(Err(err)) => readiness.not_ready(format!(
+            "failed reading from the metadata store cluster, it may have no quorum: {err}"
+        )),
+        Err(_) => readiness
+            .not_ready("timed out reading from the metadata store cluster, it may have no quorum"),
+    }
+}
+
+fn ingress_readiness(state: &NodeCtrlHandlerState, options: &HealthOptions) -> RoleReadiness {
+    let probe = &state.health_probe;
+    let status = probe.health.current_ingress_status();
+    let mut readiness = RoleReadiness::new(status == IngressStatus::Ready, status);
+
+    let Some(metadata) = state.task_center.metadata() else {
+        return readiness.not_ready("the node has not joined the cluster yet");
+    };
+    let partition_ids: Vec<PartitionId> = metadata
+        .partition_table_ref()
+        .partition_ids()
+        .copied()
+        .collect();
+    let partitions_with_unknown_leader = partition_ids
+        .iter()
+        .filter(|partition_id| {
+            probe
+                .partition_routing
+                .get_node_by_partition(**partition_id)
+                .is_none()
+        })
+        .count();
+
+    if options.readiness_require_known_partition_leaders && partitions_with_unknown_leader > 0 {
+        readiness = readiness.not_ready(format!(
+            "the leaders of {partitions_with_unknown_leader} partitions are unknown"
+        ));
+    }
+    readiness.routing = Some(RoutingDetails {
+        partitions: partition_ids.len(),
+        partitions_with_unknown_leader,
+    });
+    readiness
+}
+
+fn status_code(healthy: bool) -> StatusCode {
+    if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_core::partitions::mocks::fixed_single_node;
+    use restate_core::TaskCenter;
+    use restate_types::GenerationalNodeId;
+
+    fn processor(
+        planned_mode: RunMode,
+        effective_mode: RunMode,
+        replay_status: ReplayStatus,
+    ) -> PartitionProcessorStatus {
+        PartitionProcessorStatus {
+            planned_mode,
+            effective_mode,
+            replay_status,
+            ..PartitionProcessorStatus::default()
+        }
+    }
+
+    #[test]
+    fn counts_pending_leaders() {
+        let state = [
+            processor(RunMode::Leader, RunMode::Leader, ReplayStatus::Active),
+            processor(RunMode::Leader, RunMode::Leader, ReplayStatus::CatchingUp),
+            processor(RunMode::Leader, RunMode::Follower, ReplayStatus::Active),
+            processor(RunMode::Follower, RunMode::Follower, ReplayStatus::Active),
+            processor(RunMode::Follower, RunMode::Follower, ReplayStatus::Starting),
+        ];
+
+        assert_eq!(
+            PartitionsDetails::from_state(&state),
+            PartitionsDetails {
+                running: 5,
+                active: 3,
+                leaders: 1,
+                pending_leaders: 2,
+            }
+        );
+    }
+
+    fn handler_state(roles: EnumSet<Role>) -> NodeCtrlHandlerState {
+        let health = Health::default();
+        health.node_status().update(NodeStatus::Alive);
+        health.node_rpc_status().update(NodeRpcStatus::Ready);
+        NodeCtrlHandlerState {
+            prometheus_handle: None,
+            task_center: TaskCenter::current(),
+            health_probe: HealthProbe::new(
+                health,
+                roles,
+                None,
+                fixed_single_node(GenerationalNodeId::new(1, 1), PartitionId::MIN),
+                MetadataStoreClient::new_in_memory(),
+            ),
+        }
+    }
+
+    #[restate_core::test]
+    async fn live_until_shutting_down() {
+        let state = handler_state(Role::Admin.into());
+
+        let (status, Json(report)) = live(State(state.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(report.live);
+
+        state
+            .health_probe
+            .health
+            .node_status()
+            .update(NodeStatus::ShuttingDown);
+        let (status, Json(report)) = live(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!report.live);
+    }
+
+    #[restate_core::test]
+    async fn ready_once_all_roles_are_ready() {
+        let state = handler_state(Role::Admin | Role::LogServer);
+        let health = &state.health_probe.health;
+        health.admin_status().update(AdminStatus::Ready);
+        health.log_server_status().update(LogServerStatus::Failsafe);
+
+        let report = readiness_report(&state, &HealthOptions::default()).await;
+        assert!(!report.ready);
+        assert!(report.roles["admin"].ready);
+        let log_server = &report.roles["log-server"];
+        assert!(!log_server.ready);
+        assert!(log_server.required);
+        assert!(log_server.reason.is_some());
+
+        health.log_server_status().update(LogServerStatus::Ready);
+        let report = readiness_report(&state, &HealthOptions::default()).await;
+        assert!(report.ready);
+    }
+
+    #[restate_core::test]
+    async fn optional_roles_dont_affect_readiness() {
+        let state = handler_state(Role::Admin | Role::LogServer);
+        let health = &state.health_probe.health;
+        health.admin_status().update(AdminStatus::Ready);
+        health.log_server_status().update(LogServerStatus::Failsafe);
+
+        let options = HealthOptions {
+            readiness_roles: Some(Role::Admin.into()),
+            ..HealthOptions::default()
+        };
+        let report = readiness_report(&state, &options).await;
+        assert!(report.ready);
+        assert!(report.roles["admin"].required);
+        let log_server = &report.roles["log-server"];
+        assert!(!log_server.ready);
+        assert!(!log_server.required);
+
+        // a required role which isn't ready makes the node not ready
+        health.admin_status().update(AdminStatus::StartingUp);
+        let report = readiness_report(&state, &options).await;
+        assert!(!report.ready);
+    }
+
+    #[restate_core::test]
+    async fn not_ready_before_the_node_is_alive() {
+        let state = handler_state(Role::Admin.into());
+        state
+            .health_probe
+            .health
+            .admin_status()
+            .update(AdminStatus::Ready);
+        state
+            .health_probe
+            .health
+            .node_rpc_status()
+            .update(NodeRpcStatus::StartingUp);
+
+        let report = readiness_report(&state, &HealthOptions::default()).await;
+        assert!(!report.ready);
+        assert!(report.roles["admin"].ready);
+    }
+
+    #[restate_core::test]
+    async fn metadata_server_ready_with_quorum() {
+        let state = handler_state(Role::MetadataServer.into());
+        let health = &state.health_probe.health;
+
+        health
+            .metadata_server_status()
+            .update(MetadataServerStatus::Standby);
+        let report = readiness_report(&state, &HealthOptions::default()).await;
+        assert!(!report.ready);
+        assert!(!report.roles["metadata-server"].ready);
+
+        // the in-memory metadata store always serves reads
+        health
+            .metadata_server_status()
+            .update(MetadataServerStatus::Member);
+        let report = readiness_report(&state, &HealthOptions::default()).await;
+        assert!(report.ready);
+        assert!(report.roles["metadata-server"].ready);
+    }
+}
@@ -14,7 +14,7 @@ use axum::extract::State;
 use metrics_exporter_prometheus::formatting;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use metrics_tracing_context::TracingContextLayer;
-use metrics_util::layers::Layer;
+use metrics_util::layers::{FanoutBuilder, Layer};
 use metrics_util::MetricKindMask;
 use rocksdb::statistics::{Histogram, Ticker};
 
@@ -22,6 +22,7 @@ use restate_core::task_center::TaskCenterMonitoring;
 use restate_rocksdb::{CfName, RocksDbManager};
 use restate_types::config::CommonOptions;
 
+use crate::network_server::otlp_metrics::{build_otlp_recorder, OtlpMetrics};
 use crate::network_server::prometheus_helpers::{
     format_rocksdb_histogram_for_prometheus, format_rocksdb_property_for_prometheus,
     format_rocksdb_stat_ticker_for_prometheus, MetricUnit,
@@ -34,7 +35,7 @@ use crate::network_server::state::NodeCtrlHandlerState;
 /// as much as possible (e.g. `restate.invocation.id`)
 static ALLOWED_LABELS: &[&str] = &["rpc.method", "rpc.service", "command", "service", "db"];
 
-const ROCKSDB_TICKERS: &[Ticker] = &[
+pub(crate) const ROCKSDB_TICKERS: &[Ticker] = &[
     Ticker::BlockCacheBytesRead,
     Ticker::BlockCacheBytesWrite,
     Ticker::BlockCacheHit,
@@ -63,7 +64,7 @@ const ROCKSDB_TICKERS: &[Ticker] = &[
     Ticker::WriteWithWal,
 ];
 
-const ROCKSDB_HISTOGRAMS: &[(Histogram, &str, MetricUnit)] = &[
+pub(crate) const ROCKSDB_HISTOGRAMS: &[(Histogram, &str, MetricUnit)] = &[
     (Histogram::DbGet, "rocksdb.db.get", MetricUnit::Micros),
     (
         Histogram::DbMultiget,
@@ -141,7 +142,7 @@ const ROCKSDB_HISTOGRAMS: &[(Histogram, &str, MetricUnit)] = &[
 ];
 
 // Per database properties
-const ROCKSDB_DB_PROPERTIES: &[(&str, MetricUnit)] = &[
+pub(crate) const ROCKSDB_DB_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.block-cache-capacity", MetricUnit::Bytes),
     ("rocksdb.block-cache-usage", MetricUnit::Bytes),
     ("rocksdb.block-cache-pinned-usage", MetricUnit::Bytes),
@@ -149,7 +150,7 @@ const ROCKSDB_DB_PROPERTIES: &[(&str, MetricUnit)] = &[
 ];
 
 // Per column-family properties
-const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
+pub(crate) const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.num-immutable-mem-table", MetricUnit::Count),
     ("rocksdb.mem-table-flush-pending", MetricUnit::Count),
     ("rocksdb.is-write-stopped", MetricUnit::Count),
@@ -184,21 +185,43 @@ const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.num-files-at-level6", MetricUnit::Count),
 ];
 
-pub(crate) fn install_global_prometheus_recorder(opts: &CommonOptions) -> PrometheusHandle {
-    let builder = PrometheusBuilder::default()
-        // Remove a metric from registry if it was not updated for that duration
-        .idle_timeout(
-            MetricKindMask::HISTOGRAM,
-            opts.histogram_inactivity_timeout.map(Into::into),
-        );
-    let recorder = builder.build_recorder();
-    let prometheus_handle = recorder.handle();
-    let recorder = TracingContextLayer::only_allow(ALLOWED_LABELS).layer(recorder);
+/// Installs the global metrics recorder, feeding the Prometheus endpoint unless disabled and the
+/// OTLP metrics exporter if an endpoint is configured.
+pub(crate) fn install_global_metrics_recorder(
+    opts: &CommonOptions,
+) -> anyhow::Result<(Option<PrometheusHandle>, Option<OtlpMetrics>)> {
+    let mut fanout = FanoutBuilder::default();
+    let mut prometheus_handle = None;
+    let mut otlp_metrics = None;
+
+    if !opts.disable_prometheus {
+        let builder = PrometheusBuilder::default()
+            // Remove a metric from registry if it was not updated for that duration
+            .idle_timeout(
+                MetricKindMask::HISTOGRAM,
+                opts.histogram_inactivity_timeout.map(Into::into),
+            );
+        let recorder = builder.build_recorder();
+        prometheus_handle = Some(recorder.handle());
+        fanout = fanout.add_recorder(recorder);
+    }
+
+    if let Some(endpoint) = &opts.metrics_endpoint {
+        let (recorder, metrics) = build_otlp_recorder(opts, endpoint)?;
+        otlp_metrics = Some(metrics);
+        fanout = fanout.add_recorder(recorder);
+    }
+
+    if prometheus_handle.is_none() && otlp_metrics.is_none() {
+        return Ok((None, None));
+    }
+
+    let recorder = TracingContextLayer::only_allow(ALLOWED_LABELS).layer(fanout.build());
 
     // We do not expect this to fail except due to atomic CAS failure
     // which should never happen in practice.
     metrics::set_global_recorder(recorder).expect("no global metrics recorder should be installed");
-    prometheus_handle
+    Ok((prometheus_handle, otlp_metrics))
 }
 
 // -- Direct HTTP Handlers --
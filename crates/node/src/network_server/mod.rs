@@ -9,10 +9,13 @@
 // by the Apache License, Version 2.0.
 
 mod grpc_svc_handler;
+mod health;
 mod metrics;
+mod otlp_metrics;
 mod pprof;
 mod prometheus_helpers;
 mod service;
 mod state;
 
+pub use health::HealthProbe;
 pub use service::NetworkServer;
@@ -0,0 +1,656 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use opentelemetry::metrics::{Meter, MeterProvider, ObservableCounter, ObservableGauge};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{MetricExporter, WithExportConfig, WithTonicConfig};
+use opentelemetry_sdk::metrics::{
+    new_view, Aggregation, Instrument, InstrumentKind, PeriodicReader, SdkMeterProvider, Stream,
+};
+use tonic::codegen::http::HeaderMap;
+use tonic::metadata::MetadataMap;
+use tonic::transport::ClientTlsConfig;
+
+use restate_rocksdb::{CfName, RocksDbManager};
+use restate_types::config::CommonOptions;
+
+use crate::network_server::metrics::{
+    ROCKSDB_CF_PROPERTIES, ROCKSDB_DB_PROPERTIES, ROCKSDB_HISTOGRAMS, ROCKSDB_TICKERS,
+};
+use crate::network_server::prometheus_helpers::MetricUnit;
+
+static PREFIX: &str = "restate";
+
+/// Pushes the metrics to an OTLP endpoint. Keep it alive for as long as metrics should be
+/// exported, and [`Self::shutdown`] it to flush the last metrics.
+pub(crate) struct OtlpMetrics {
+    provider: SdkMeterProvider,
+    // RocksDB statistics are observed through callbacks of these instruments
+    _rocksdb_counters: Vec<ObservableCounter<u64>>,
+    _rocksdb_gauges: Vec<ObservableGauge<f64>>,
+}
+
+impl OtlpMetrics {
+    pub(crate) fn shutdown(&self) -> anyhow::Result<()> {
+        self.provider.shutdown()?;
+        Ok(())
+    }
+}
+
+/// Creates the OTLP metrics pipeline for [`CommonOptions::metrics_endpoint`], returning the
+/// recorder to feed it.
+pub(crate) fn build_otlp_recorder(
+    opts: &CommonOptions,
+    endpoint: &str,
+) -> anyhow::Result<(OtlpRecorder, OtlpMetrics)> {
+    let header_map = HeaderMap::from_iter(HashMap::from(opts.metrics_headers.clone()));
+    let exporter = MetricExporter::builder()
+        .with_tonic()
+        .with_tls_config(ClientTlsConfig::new().with_native_roots())
+        .with_endpoint(endpoint)
+        .with_metadata(MetadataMap::from_headers(header_map))
+        .build()?;
+
+    let reader = PeriodicReader::builder(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_interval(opts.metrics_export_interval.into())
+        .build();
+
+    let resource = opentelemetry_sdk::Resource::new(vec![
+        KeyValue::new(
+            opentelemetry_semantic_conventions::resource::SERVICE_NAME,
+            "restate-server",
+        ),
+        KeyValue::new(
+            opentelemetry_semantic_conventions::resource::SERVICE_NAMESPACE,
+            "Restate",
+        ),
+        KeyValue::new(
+            opentelemetry_semantic_conventions::resource::SERVICE_INSTANCE_ID,
+            format!("{}/{}", opts.cluster_name(), opts.node_name()),
+        ),
+        KeyValue::new(
+            opentelemetry_semantic_conventions::resource::SERVICE_VERSION,
+            env!("CARGO_PKG_VERSION"),
+        ),
+    ]);
+
+    // Exponential histograms adapt to the scale of the recorded values, which vary from
+    // microseconds to bytes across our histograms.
+    let exponential_histograms = new_view(
+        Instrument::new().kind(InstrumentKind::Histogram),
+        Stream::new().aggregation(Aggregation::Base2ExponentialHistogram {
+            max_size: 160,
+            max_scale: 20,
+            record_min_max: true,
+        }),
+    )?;
+
+    let provider = SdkMeterProvider::builder()
+        .with_resource(resource)
+        .with_reader(reader)
+        .with_view(exponential_histograms)
+        .build();
+    let meter = provider.meter(PREFIX);
+
+    let (rocksdb_counters, rocksdb_gauges) = register_rocksdb_instruments(&meter);
+
+    Ok((
+        OtlpRecorder {
+            meter,
+            instruments: Mutex::default(),
+        },
+        OtlpMetrics {
+            provider,
+            _rocksdb_counters: rocksdb_counters,
+            _rocksdb_gauges: rocksdb_gauges,
+        },
+    ))
+}
+
+/// A [`Recorder`] forwarding the metrics to OpenTelemetry instruments.
+pub(crate) struct OtlpRecorder {
+    meter: Meter,
+    instruments: Mutex<Instruments>,
+}
+
+/// The handles are shared by all the registrations of the same key, as gauges and absolute
+/// counters are tracked by the handle.
+#[derive(Default)]
+struct Instruments {
+    descriptions: HashMap<String, (Option<Unit>, SharedString)>,
+    counters: HashMap<Key, Arc<OtlpCounter>>,
+    gauges: HashMap<Key, Arc<OtlpGauge>>,
+    histograms: HashMap<Key, Arc<OtlpHistogram>>,
+}
+
+impl OtlpRecorder {
+    fn describe(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.instruments
+            .lock()
+            .unwrap()
+            .descriptions
+            .insert(key.as_str().to_owned(), (unit, description));
+    }
+}
+
+impl Recorder for OtlpRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description)
+    }
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        let mut instruments = self.instruments.lock().unwrap();
+        if let Some(counter) = instruments.counters.get(key) {
+            return Counter::from_arc(Arc::clone(counter));
+        }
+
+        let (unit, description) = describe_instrument(&instruments.descriptions, key);
+        let counter = Arc::new(OtlpCounter {
+            counter: self
+                .meter
+                .u64_counter(key.name().to_owned())
+                .with_unit(unit)
+                .with_description(description)
+                .build(),
+            attributes: attributes(key),
+            last_absolute: AtomicU64::new(0),
+        });
+        instruments
+            .counters
+            .insert(key.clone(), Arc::clone(&counter));
+        Counter::from_arc(counter)
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        let mut instruments = self.instruments.lock().unwrap();
+        if let Some(gauge) = instruments.gauges.get(key) {
+            return Gauge::from_arc(Arc::clone(gauge));
+        }
+
+        let (unit, description) = describe_instrument(&instruments.descriptions, key);
+        let gauge = Arc::new(OtlpGauge {
+            gauge: self
+                .meter
+                .f64_gauge(key.name().to_owned())
+                .with_unit(unit)
+                .with_description(description)
+                .build(),
+            attributes: attributes(key),
+            value: Mutex::new(0.0),
+        });
+        instruments.gauges.insert(key.clone(), Arc::clone(&gauge));
+        Gauge::from_arc(gauge)
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        let mut instruments = self.instruments.lock().unwrap();
+        if let Some(histogram) = instruments.histograms.get(key) {
+            return Histogram::from_arc(Arc::clone(histogram));
+        }
+
+        let (unit, description) = describe_instrument(&instruments.descriptions, key);
+        let histogram = Arc::new(OtlpHistogram {
+            histogram: self
+                .meter
+                .f64_histogram(key.name().to_owned())
+                .with_unit(unit)
+                .with_description(description)
+                .build(),
+            attributes: attributes(key),
+        });
+        instruments
+            .histograms
+            .insert(key.clone(), Arc::clone(&histogram));
+        Histogram::from_arc(histogram)
+    }
+}
+
+fn describe_instrument(
+    descriptions: &HashMap<String, (Option<Unit>, SharedString)>,
+    key: &Key,
+) -> (String, String) {
+    match descriptions.get(key.name()) {
+        Some((unit, description)) => (
+            unit.map(otlp_unit).unwrap_or_default().to_owned(),
+            description.to_string(),
+        ),
+        None => (String::new(), String::new()),
+    }
+}
+
+fn attributes(key: &Key) -> Vec<KeyValue> {
+    key.labels()
+        .map(|label| KeyValue::new(label.key().to_owned(), label.value().to_owned()))
+        .collect()
+}
+
+/// Units as per the [UCUM](https://ucum.org/ucum) codes used by OpenTelemetry.
+fn otlp_unit(unit: Unit) -> &'static str {
+    match unit {
+        Unit::Count => "1",
+        Unit::Percent => "%",
+        Unit::Seconds => "s",
+        Unit::Milliseconds => "ms",
+        Unit::Microseconds => "us",
+        Unit::Nanoseconds => "ns",
+        Unit::Tebibytes => "TiBy",
+        Unit::Gibibytes => "GiBy",
+        Unit::Mebibytes => "MiBy",
+        Unit::Kibibytes => "KiBy",
+        Unit::Bytes => "By",
+        Unit::TerabitsPerSecond => "Tbit/s",
+        Unit::GigabitsPerSecond => "Gbit/s",
+        Unit::MegabitsPerSecond => "Mbit/s",
+        Unit::KilobitsPerSecond => "kbit/s",
+        Unit::BitsPerSecond => "bit/s",
+        Unit::CountPerSecond => "1/s",
+    }
+}
+
+struct OtlpCounter {
+    counter: opentelemetry::metrics::Counter<u64>,
+    attributes: Vec<KeyValue>,
+    last_absolute: AtomicU64,
+}
+
+impl CounterFn for OtlpCounter {
+    fn increment(&self, value: u64) {
+        self.counter.add(value, &self.attributes);
+    }
+
+    fn absolute(&self, value: u64) {
+        // OpenTelemetry counters can only be incremented
+        let previous = self.last_absolute.fetch_max(value, Ordering::Relaxed);
+        if value > previous {
+            self.counter.add(value - previous, &self.attributes);
+        }
+    }
+}
+
+struct OtlpGauge {
+    gauge: opentelemetry::metrics::Gauge<f64>,
+    attributes: Vec<KeyValue>,
+    value: Mutex<f64>,
+}
+
+impl OtlpGauge {
+    fn update(&self, f: impl FnOnce(f64) -> f64) {
+        // The value is recorded while holding the lock, so that concurrent updates can't record
+        // their values out of order and leave the gauge at a stale value.
+        let mut value = self.value.lock().unwrap();
+        *value = f(*value);
+        self.gauge.record(*value, &self.attributes);
+    }
+}
+
+impl GaugeFn for OtlpGauge {
+    fn increment(&self, value: f64) {
+        self.update(|current| current + value)
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|current| current - value)
+    }
+
+    fn set(&self, value: f64) {
+        self.update(|_| value)
+    }
+}
+
+struct OtlpHistogram {
+    histogram: opentelemetry::metrics::Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl HistogramFn for OtlpHistogram {
+    fn record(&self, value: f64) {
+        self.histogram.record(value, &self.attributes);
+    }
+}
+
+/// Registers the RocksDB statistics rendered by the Prometheus endpoint as observable
+/// instruments, read on every export.
+fn register_rocksdb_instruments(
+    meter: &Meter,
+) -> (Vec<ObservableCounter<u64>>, Vec<ObservableGauge<f64>>) {
+    let mut counters = Vec::new();
+    let mut gauges = Vec::new();
+
+    for ticker in ROCKSDB_TICKERS {
+        let ticker = *ticker;
+        counters.push(
+            meter
+                .u64_observable_counter(format!("{PREFIX}.{}", ticker.name()))
+                .with_callback(move |observer| {
+                    for db in RocksDbManager::get().get_all_dbs() {
+                        observer.observe(db.get_ticker_count(ticker), &[db_attribute(&db.name)]);
+                    }
+                })
+                .build(),
+        );
+    }
+
+    for (histogram, name, unit) in ROCKSDB_HISTOGRAMS {
+        let (histogram, unit) = (*histogram, *unit);
+        gauges.push(
+            meter
+                .f64_observable_gauge(format!("{PREFIX}.{name}"))
+                .with_unit(unit.otlp_unit())
+                .with_callback(move |observer| {
+                    for db in RocksDbManager::get().get_all_dbs() {
+                        let data = db.get_histogram_data(histogram);
+                        for (quantile, value) in [
+                            ("0.5", data.median()),
+                            ("0.95", data.p95()),
+                            ("0.99", data.p99()),
+                            ("1.0", data.max()),
+                        ] {
+                            observer.observe(
+                                unit.normalize_value(value),
+                                &[db_attribute(&db.name), KeyValue::new("quantile", quantile)],
+                            );
+                        }
+                    }
+                })
+                .build(),
+        );
+        counters.push(
+            meter
+                .u64_observable_counter(format!("{PREFIX}.{name}.count"))
+                .with_callback(move |observer| {
+                    for db in RocksDbManager::get().get_all_dbs() {
+                        observer.observe(
+                            db.get_histogram_data(histogram).count(),
+                            &[db_attribute(&db.name)],
+                        );
+                    }
+                })
+                .build(),
+        );
+    }
+
+    for (property, unit) in ROCKSDB_DB_PROPERTIES {
+        let property = *property;
+        gauges.push(
+            meter
+                .f64_observable_gauge(format!("{PREFIX}.{property}"))
+                .with_unit(unit.otlp_unit())
+                .with_callback(move |observer| {
+                    let default_cf = CfName::new("default");
+                    for db in RocksDbManager::get().get_all_dbs() {
+                        let value = db
+                            .inner()
+                            .get_property_int_cf(&default_cf, property)
+                            .unwrap_or_default()
+                            .unwrap_or_default();
+                        observer.observe(value as f64, &[db_attribute(&db.name)]);
+                    }
+                })
+                .build(),
+        );
+    }
+
+    for (property, unit) in ROCKSDB_CF_PROPERTIES {
+        let property = *property;
+        gauges.push(
+            meter
+                .f64_observable_gauge(format!("{PREFIX}.{property}"))
+                .with_unit(unit.otlp_unit())
+                .with_callback(move |observer| {
+                    for db in RocksDbManager::get().get_all_dbs() {
+                        for cf in &db.cfs() {
+                            let value = db
+                                .inner()
+                                .get_property_int_cf(cf, property)
+                                .unwrap_or_default()
+                                .unwrap_or_default();
+                            observer.observe(
+                                value as f64,
+                                &[db_attribute(&db.name), KeyValue::new("cf", cf.to_string())],
+                            );
+                        }
+                    }
+                })
+                .build(),
+        );
+    }
+
+    for (name, value) in [
+        (
+            "rocksdb.memory.write_buffer_manager_capacity",
+            RocksDbManager::get_total_write_buffer_capacity as fn(&RocksDbManager) -> u64,
+        ),
+        (
+            "rocksdb.memory.write_buffer_manager_usage",
+            RocksDbManager::get_total_write_buffer_usage,
+        ),
+    ] {
+        gauges.push(
+            meter
+                .f64_observable_gauge(format!("{PREFIX}.{name}"))
+                .with_unit(MetricUnit::Bytes.otlp_unit())
+                .with_callback(move |observer| {
+                    observer.observe(value(RocksDbManager::get()) as f64, &[]);
+                })
+                .build(),
+        );
+    }
+
+    (counters, gauges)
+}
+
+fn db_attribute(db_name: &impl ToString) -> KeyValue {
+    KeyValue::new("db", db_name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Weak;
+
+    use metrics::{Label, Level};
+    use opentelemetry_sdk::metrics::data::{self, ResourceMetrics};
+    use opentelemetry_sdk::metrics::reader::MetricReader;
+    use opentelemetry_sdk::metrics::{ManualReader, MetricResult, Pipeline, Temporality};
+    use opentelemetry_sdk::Resource;
+
+    /// Shares a [`ManualReader`] with the meter provider, to collect the recorded metrics.
+    #[derive(Clone, Debug)]
+    struct SharedReader(Arc<ManualReader>);
+
+    impl MetricReader for SharedReader {
+        fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+            self.0.register_pipeline(pipeline)
+        }
+
+        fn collect(&self, rm: &mut ResourceMetrics) -> MetricResult<()> {
+            self.0.collect(rm)
+        }
+
+        fn force_flush(&self) -> MetricResult<()> {
+            self.0.force_flush()
+        }
+
+        fn shutdown(&self) -> MetricResult<()> {
+            self.0.shutdown()
+        }
+
+        fn temporality(&self, kind: InstrumentKind) -> Temporality {
+            self.0.temporality(kind)
+        }
+    }
+
+    /// The provider must be kept alive, dropping it shuts down the reader.
+    fn test_recorder() -> (OtlpRecorder, SharedReader, SdkMeterProvider) {
+        let reader = SharedReader(Arc::new(ManualReader::builder().build()));
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader.clone())
+            .build();
+        let recorder = OtlpRecorder {
+            meter: provider.meter(PREFIX),
+            instruments: Mutex::default(),
+        };
+        (recorder, reader, provider)
+    }
+
+    fn metadata() -> Metadata<'static> {
+        Metadata::new(module_path!(), Level::INFO, Some(module_path!()))
+    }
+
+    fn collect(reader: &SharedReader) -> Vec<data::Metric> {
+        let mut rm = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: vec![],
+        };
+        reader.collect(&mut rm).unwrap();
+        rm.scope_metrics
+            .into_iter()
+            .flat_map(|scope_metrics| scope_metrics.metrics)
+            .collect()
+    }
+
+    fn find<'a>(metrics: &'a [data::Metric], name: &str) -> &'a data::Metric {
+        metrics
+            .iter()
+            .find(|metric| metric.name == name)
+            .unwrap_or_else(|| panic!("metric {name} was not collected"))
+    }
+
+    fn sum_value(metric: &data::Metric) -> u64 {
+        let sum = metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .expect("counter is collected as a sum");
+        assert_eq!(sum.data_points.len(), 1);
+        sum.data_points[0].value
+    }
+
+    fn gauge_value(metric: &data::Metric) -> f64 {
+        let gauge = metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Gauge<f64>>()
+            .expect("gauge is collected as a gauge");
+        assert_eq!(gauge.data_points.len(), 1);
+        gauge.data_points[0].value
+    }
+
+    #[test]
+    fn absolute_counter_adds_the_increase() {
+        let (recorder, reader, _provider) = test_recorder();
+        let counter = recorder.register_counter(&Key::from_name("test.counter"), &metadata());
+
+        counter.absolute(5);
+        assert_eq!(sum_value(find(&collect(&reader), "test.counter")), 5);
+
+        counter.absolute(8);
+        assert_eq!(sum_value(find(&collect(&reader), "test.counter")), 8);
+
+        // a lower absolute value is not a decrease of the counter
+        counter.absolute(3);
+        assert_eq!(sum_value(find(&collect(&reader), "test.counter")), 8);
+
+        counter.increment(2);
+        assert_eq!(sum_value(find(&collect(&reader), "test.counter")), 10);
+    }
+
+    #[test]
+    fn gauge_tracks_increments_and_decrements() {
+        let (recorder, reader, _provider) = test_recorder();
+        let key = Key::from_parts("test.gauge", vec![Label::new("partition", "1")]);
+        let gauge = recorder.register_gauge(&key, &metadata());
+
+        gauge.increment(3.0);
+        gauge.decrement(1.0);
+        assert_eq!(gauge_value(find(&collect(&reader), "test.gauge")), 2.0);
+
+        // another registration of the same key shares the value
+        let same_gauge = recorder.register_gauge(&key, &metadata());
+        same_gauge.increment(1.5);
+        assert_eq!(gauge_value(find(&collect(&reader), "test.gauge")), 3.5);
+
+        gauge.set(7.0);
+        gauge.decrement(2.0);
+        let metrics = collect(&reader);
+        let metric = find(&metrics, "test.gauge");
+        assert_eq!(gauge_value(metric), 5.0);
+        let attributes = &metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Gauge<f64>>()
+            .unwrap()
+            .data_points[0]
+            .attributes;
+        assert_eq!(attributes, &vec![KeyValue::new("partition", "1")]);
+    }
+
+    #[test]
+    fn described_units_are_mapped() {
+        let (recorder, reader, _provider) = test_recorder();
+        recorder.describe_histogram(
+            KeyName::from("test.duration"),
+            Some(Unit::Seconds),
+            SharedString::from("How long it took"),
+        );
+        recorder.describe_gauge(
+            KeyName::from("test.size"),
+            Some(Unit::Bytes),
+            SharedString::from("How big it is"),
+        );
+        recorder
+            .register_histogram(&Key::from_name("test.duration"), &metadata())
+            .record(0.5);
+        recorder
+            .register_gauge(&Key::from_name("test.size"), &metadata())
+            .set(1024.0);
+        recorder
+            .register_counter(&Key::from_name("test.undescribed"), &metadata())
+            .increment(1);
+
+        let metrics = collect(&reader);
+        let duration = find(&metrics, "test.duration");
+        assert_eq!(duration.unit, "s");
+        assert_eq!(duration.description, "How long it took");
+        assert_eq!(find(&metrics, "test.size").unit, "By");
+        assert_eq!(find(&metrics, "test.undescribed").unit, "");
+    }
+
+    #[test]
+    fn maps_units_to_ucum() {
+        assert_eq!(otlp_unit(Unit::Count), "1");
+        assert_eq!(otlp_unit(Unit::Percent), "%");
+        assert_eq!(otlp_unit(Unit::Seconds), "s");
+        assert_eq!(otlp_unit(Unit::Milliseconds), "ms");
+        assert_eq!(otlp_unit(Unit::Microseconds), "us");
+        assert_eq!(otlp_unit(Unit::Nanoseconds), "ns");
+        assert_eq!(otlp_unit(Unit::Bytes), "By");
+        assert_eq!(otlp_unit(Unit::Kibibytes), "KiBy");
+        assert_eq!(otlp_unit(Unit::MegabitsPerSecond), "Mbit/s");
+        assert_eq!(otlp_unit(Unit::CountPerSecond), "1/s");
+    }
+}
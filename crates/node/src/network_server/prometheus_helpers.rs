@@ -24,7 +24,7 @@ pub enum MetricUnit {
 }
 
 impl MetricUnit {
-    fn normalize_value(&self, value: f64) -> f64 {
+    pub(crate) fn normalize_value(&self, value: f64) -> f64 {
         match self {
             // Prometheus recommends base units, so we convert micros to seconds
             // (fractions) when convenient.
@@ -41,6 +41,16 @@ impl MetricUnit {
             MetricUnit::Count => "count",
         }
     }
+
+    /// The unit of the normalized value, as per the [UCUM](https://ucum.org/ucum) codes used by
+    /// OpenTelemetry.
+    pub(crate) fn otlp_unit(&self) -> &'static str {
+        match self {
+            MetricUnit::Micros => "s",
+            MetricUnit::Bytes => "By",
+            MetricUnit::Count => "1",
+        }
+    }
 }
 
 pub fn format_rocksdb_stat_ticker_for_prometheus(
@@ -13,21 +13,23 @@ use std::pin::pin;
 use axum::routing::{get, on, MethodFilter};
 use tokio::time::MissedTickBehavior;
 use tonic::codec::CompressionEncoding;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 use restate_core::metadata_store::MetadataStoreClient;
 use restate_core::network::protobuf::core_node_svc::core_node_svc_server::CoreNodeSvcServer;
 use restate_core::network::tonic_service_filter::{TonicServiceFilter, WaitForReady};
 use restate_core::network::{ConnectionManager, NetworkServerBuilder, TransportConnect};
 use restate_core::protobuf::node_ctl_svc::node_ctl_svc_server::NodeCtlSvcServer;
+use restate_core::task_center::TaskCenterMonitoring;
 use restate_core::{cancellation_watcher, TaskCenter, TaskKind};
 use restate_types::config::CommonOptions;
 use restate_types::health::Health;
 use restate_types::protobuf::common::NodeStatus;
 
 use super::grpc_svc_handler::{CoreNodeSvcHandler, NodeCtlSvcHandler};
+use super::health::{self, HealthProbe};
 use super::pprof;
-use crate::network_server::metrics::{install_global_prometheus_recorder, render_metrics};
+use crate::network_server::metrics::{install_global_metrics_recorder, render_metrics};
 use crate::network_server::state::NodeCtrlHandlerStateBuilder;
 
 pub struct NetworkServer {}
@@ -39,14 +41,16 @@ impl NetworkServer {
         mut server_builder: NetworkServerBuilder,
         options: CommonOptions,
         metadata_store_client: MetadataStoreClient,
+        health_probe: HealthProbe,
     ) -> Result<(), anyhow::Error> {
         // Configure Metric Exporter
         let mut state_builder = NodeCtrlHandlerStateBuilder::default();
         state_builder.task_center(TaskCenter::current());
+        state_builder.health_probe(health_probe);
 
-        if !options.disable_prometheus {
-            let prometheus_handle = install_global_prometheus_recorder(&options);
+        let (prometheus_handle, otlp_metrics) = install_global_metrics_recorder(&options)?;
 
+        if let Some(prometheus_handle) = prometheus_handle {
             TaskCenter::spawn_child(TaskKind::SystemService, "prometheus-metrics-upkeep", {
                 let prometheus_handle = prometheus_handle.clone();
                 async move {
@@ -76,6 +80,41 @@ impl NetworkServer {
             state_builder.prometheus_handle(Some(prometheus_handle));
         }
 
+        if let Some(otlp_metrics) = otlp_metrics {
+            let export_interval: std::time::Duration = options.metrics_export_interval.into();
+            TaskCenter::spawn_child(
+                TaskKind::SystemService,
+                "otlp-metrics-exporter",
+                async move {
+                    debug!("OTLP metrics exporter started");
+
+                    // The tokio runtime metrics are only submitted on demand
+                    let mut submit_interval = tokio::time::interval(export_interval);
+                    submit_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                    let mut cancel = pin!(cancellation_watcher());
+
+                    loop {
+                        tokio::select! {
+                            _ = &mut cancel => {
+                                break;
+                            }
+                            _ = submit_interval.tick() => {
+                                TaskCenter::current().submit_metrics();
+                            }
+                        }
+                    }
+
+                    // Flushes the last metrics, which blocks until they are exported
+                    let result = tokio::task::spawn_blocking(move || otlp_metrics.shutdown()).await;
+                    if let Err(err) = result.map_err(anyhow::Error::from).and_then(|r| r) {
+                        warn!("Failed to shut down the OTLP metrics exporter: {err}");
+                    }
+                    debug!("OTLP metrics exporter stopped");
+                    Ok(())
+                },
+            )?;
+        }
+
         let shared_state = state_builder.build().expect("should be infallible");
 
         let post_or_put = MethodFilter::POST.or(MethodFilter::PUT);
@@ -83,6 +122,8 @@ impl NetworkServer {
         // -- HTTP service (for prometheus et al.)
         let axum_router = axum::Router::new()
             .route("/metrics", get(render_metrics))
+            .route("/health/live", get(health::live))
+            .route("/health/ready", get(health::ready))
             .route("/debug/pprof/heap", get(pprof::heap))
             .route(
                 "/debug/pprof/heap/activate",
@@ -11,9 +11,12 @@
 use metrics_exporter_prometheus::PrometheusHandle;
 use restate_core::task_center;
 
+use crate::network_server::health::HealthProbe;
+
 #[derive(Clone, derive_builder::Builder)]
 pub struct NodeCtrlHandlerState {
     #[builder(default)]
     pub prometheus_handle: Option<PrometheusHandle>,
     pub task_center: task_center::Handle,
+    pub health_probe: HealthProbe,
 }
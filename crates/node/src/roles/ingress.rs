@@ -12,6 +12,7 @@ use restate_core::network::partition_processor_rpc_client::PartitionProcessorRpc
 use restate_core::network::rpc_router::ConnectionAwareRpcRouter;
 use restate_core::network::{MessageRouterBuilder, Networking, TransportConnect};
 use restate_core::partitions::PartitionRouting;
+use restate_core::payload_store::PayloadStore;
 use restate_ingress_http::rpc_request_dispatcher::RpcRequestDispatcher;
 use restate_ingress_http::HyperServerIngress;
 use restate_types::config::IngressOptions;
@@ -36,15 +37,19 @@ impl<T: TransportConnect> IngressRole<T> {
         partition_table: Live<PartitionTable>,
         partition_routing: PartitionRouting,
         router_builder: &mut MessageRouterBuilder,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         let rpc_router = ConnectionAwareRpcRouter::new(router_builder);
 
-        let dispatcher = RpcRequestDispatcher::new(PartitionProcessorRpcClient::new(
-            networking,
-            rpc_router,
-            partition_table,
-            partition_routing,
-        ));
+        let dispatcher = RpcRequestDispatcher::new(
+            PartitionProcessorRpcClient::new(
+                networking,
+                rpc_router,
+                partition_table,
+                partition_routing,
+            ),
+            payload_store,
+        );
         let ingress_http = HyperServerIngress::from_options(
             ingress_options.live_load(),
             dispatcher,
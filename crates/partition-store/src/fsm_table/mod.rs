@@ -11,6 +11,7 @@
 use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
 use restate_storage_api::Result;
 use restate_types::identifiers::PartitionId;
+use restate_types::invocation::JournalLimits;
 use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
 
@@ -32,11 +33,17 @@ impl PartitionStoreProtobufValue for SequenceNumber {
     type ProtobufType = crate::protobuf_types::v1::SequenceNumber;
 }
 
+impl PartitionStoreProtobufValue for JournalLimits {
+    type ProtobufType = crate::protobuf_types::v1::JournalLimits;
+}
+
 mod fsm_variable {
     pub(crate) const INBOX_SEQ_NUMBER: u64 = 0;
     pub(crate) const OUTBOX_SEQ_NUMBER: u64 = 1;
 
     pub(crate) const APPLIED_LSN: u64 = 2;
+
+    pub(crate) const JOURNAL_LIMITS: u64 = 3;
 }
 
 fn get<T: PartitionStoreProtobufValue, S: StorageAccess>(
@@ -80,6 +87,11 @@ impl ReadOnlyFsmTable for PartitionStore {
         get::<SequenceNumber, _>(self, self.partition_id(), fsm_variable::APPLIED_LSN)
             .map(|opt| opt.map(|seq_number| Lsn::from(u64::from(seq_number))))
     }
+
+    async fn get_journal_limits(&mut self) -> Result<JournalLimits> {
+        get::<JournalLimits, _>(self, self.partition_id(), fsm_variable::JOURNAL_LIMITS)
+            .map(Option::unwrap_or_default)
+    }
 }
 
 impl<'a> ReadOnlyFsmTable for PartitionStoreTransaction<'a> {
@@ -97,6 +109,11 @@ impl<'a> ReadOnlyFsmTable for PartitionStoreTransaction<'a> {
         get::<SequenceNumber, _>(self, self.partition_id(), fsm_variable::APPLIED_LSN)
             .map(|opt| opt.map(|seq_number| Lsn::from(u64::from(seq_number))))
     }
+
+    async fn get_journal_limits(&mut self) -> Result<JournalLimits> {
+        get::<JournalLimits, _>(self, self.partition_id(), fsm_variable::JOURNAL_LIMITS)
+            .map(Option::unwrap_or_default)
+    }
 }
 
 impl<'a> FsmTable for PartitionStoreTransaction<'a> {
@@ -126,4 +143,13 @@ impl<'a> FsmTable for PartitionStoreTransaction<'a> {
             &SequenceNumber::from(seq_number),
         )
     }
+
+    async fn put_journal_limits(&mut self, journal_limits: JournalLimits) {
+        put(
+            self,
+            self.partition_id(),
+            fsm_variable::JOURNAL_LIMITS,
+            &journal_limits,
+        )
+    }
 }
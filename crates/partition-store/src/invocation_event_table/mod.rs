@@ -0,0 +1,221 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::keys::{define_table_key, KeyKind, TableKey};
+use crate::owned_iter::OwnedIterator;
+use crate::protobuf_types::PartitionStoreProtobufValue;
+use crate::scan::TableScan;
+use crate::{PartitionStore, TableKind, TableScanIterationDecision};
+use crate::{PartitionStoreTransaction, StorageAccess};
+use futures::Stream;
+use futures_util::stream;
+use restate_rocksdb::RocksDbPerfGuard;
+use restate_storage_api::invocation_event_table::{
+    InvocationEvent, InvocationEventTable, OwnedInvocationEventRow, ReadOnlyInvocationEventTable,
+};
+use restate_storage_api::Result;
+use restate_types::identifiers::{InvocationId, InvocationUuid, PartitionKey, WithPartitionKey};
+use std::ops::RangeInclusive;
+
+define_table_key!(
+    TableKind::InvocationEvent,
+    KeyKind::InvocationEvent,
+    InvocationEventKey(
+        partition_key: PartitionKey,
+        invocation_uuid: InvocationUuid,
+        event_index: u32
+    )
+);
+
+impl PartitionStoreProtobufValue for InvocationEvent {
+    type ProtobufType = crate::protobuf_types::v1::InvocationEvent;
+}
+
+fn invocation_events_prefix(invocation_id: &InvocationId) -> InvocationEventKey {
+    InvocationEventKey::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid())
+}
+
+fn create_key(invocation_id: &InvocationId, event_index: u32) -> InvocationEventKey {
+    invocation_events_prefix(invocation_id).event_index(event_index)
+}
+
+/// Returns the indexes of the stored events of the given invocation, in ascending order.
+fn event_indexes<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+) -> Result<Vec<u32>> {
+    storage
+        .for_each_key_value_in_place(
+            TableScan::SinglePartitionKeyPrefix(
+                invocation_id.partition_key(),
+                invocation_events_prefix(invocation_id),
+            ),
+            |mut k, _| TableScanIterationDecision::Emit(decode_event_index(&mut k)),
+        )
+        .into_iter()
+        .collect()
+}
+
+/// Returns the indexes of the oldest and of the latest stored events of the given invocation.
+/// Only the two ends of the event log are read, so that the cost doesn't depend on the number
+/// of retained events.
+fn event_index_bounds<S: StorageAccess>(
+    storage: &S,
+    invocation_id: &InvocationId,
+) -> Result<Option<(u32, u32)>> {
+    let mut iterator = storage.iterator_from(TableScan::SinglePartitionKeyPrefix(
+        invocation_id.partition_key(),
+        invocation_events_prefix(invocation_id),
+    ));
+    let Some((mut oldest_key, _)) = iterator.item() else {
+        return Ok(None);
+    };
+    let oldest = decode_event_index(&mut oldest_key)?;
+
+    iterator.seek_for_prev(create_key(invocation_id, u32::MAX).serialize());
+    let latest = match iterator.item() {
+        Some((mut latest_key, _)) => decode_event_index(&mut latest_key)?,
+        None => oldest,
+    };
+
+    Ok(Some((oldest, latest)))
+}
+
+fn decode_event_index(key: &mut &[u8]) -> Result<u32> {
+    InvocationEventKey::deserialize_from(key).and_then(|key| key.event_index_ok_or().copied())
+}
+
+fn get_invocation_events<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+) -> Result<Vec<InvocationEvent>> {
+    let _x = RocksDbPerfGuard::new("get-invocation-events");
+    storage
+        .for_each_key_value_in_place(
+            TableScan::SinglePartitionKeyPrefix(
+                invocation_id.partition_key(),
+                invocation_events_prefix(invocation_id),
+            ),
+            |_, mut v| TableScanIterationDecision::Emit(InvocationEvent::decode(&mut v)),
+        )
+        .into_iter()
+        .collect()
+}
+
+fn all_invocation_events<S: StorageAccess>(
+    storage: &S,
+    range: RangeInclusive<PartitionKey>,
+) -> impl Stream<Item = Result<OwnedInvocationEventRow>> + Send + '_ {
+    let iter = storage.iterator_from(TableScan::FullScanPartitionKeyRange::<InvocationEventKey>(
+        range,
+    ));
+    stream::iter(OwnedIterator::new(iter).map(|(mut k, mut v)| {
+        let key = InvocationEventKey::deserialize_from(&mut k)?;
+        let event = InvocationEvent::decode(&mut v)?;
+
+        let (partition_key, invocation_uuid, event_index) = key.into_inner_ok_or()?;
+
+        Ok(OwnedInvocationEventRow {
+            invocation_id: InvocationId::from_parts(partition_key, invocation_uuid),
+            event_index,
+            event,
+        })
+    }))
+}
+
+fn append_invocation_event<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    event: &InvocationEvent,
+    retained_events: usize,
+) -> Result<()> {
+    let bounds = event_index_bounds(storage, invocation_id)?;
+    let next_event_index = bounds.map(|(_, latest)| latest + 1).unwrap_or_default();
+
+    storage.put_kv(create_key(invocation_id, next_event_index), event);
+
+    // Trim the oldest events exceeding the retention. Unless the retention was lowered, this is
+    // at most one event.
+    if let Some((oldest, _)) = bounds {
+        let retained_events = u32::try_from(retained_events).unwrap_or(u32::MAX);
+        let first_retained = next_event_index
+            .saturating_add(1)
+            .saturating_sub(retained_events);
+        for event_index in oldest..first_retained {
+            storage.delete_key(&create_key(invocation_id, event_index));
+        }
+    }
+
+    Ok(())
+}
+
+fn delete_invocation_events<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+) -> Result<()> {
+    for event_index in event_indexes(storage, invocation_id)? {
+        storage.delete_key(&create_key(invocation_id, event_index));
+    }
+
+    Ok(())
+}
+
+impl ReadOnlyInvocationEventTable for PartitionStore {
+    async fn get_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> Result<Vec<InvocationEvent>> {
+        self.assert_partition_key(invocation_id);
+        get_invocation_events(self, invocation_id)
+    }
+
+    fn all_invocation_events(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<OwnedInvocationEventRow>> + Send {
+        all_invocation_events(self, range)
+    }
+}
+
+impl<'a> ReadOnlyInvocationEventTable for PartitionStoreTransaction<'a> {
+    async fn get_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> Result<Vec<InvocationEvent>> {
+        self.assert_partition_key(invocation_id);
+        get_invocation_events(self, invocation_id)
+    }
+
+    fn all_invocation_events(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<OwnedInvocationEventRow>> + Send {
+        all_invocation_events(self, range)
+    }
+}
+
+impl<'a> InvocationEventTable for PartitionStoreTransaction<'a> {
+    async fn append_invocation_event(
+        &mut self,
+        invocation_id: &InvocationId,
+        event: &InvocationEvent,
+        retained_events: usize,
+    ) -> Result<()> {
+        self.assert_partition_key(invocation_id);
+        append_invocation_event(self, invocation_id, event, retained_events)
+    }
+
+    async fn delete_invocation_events(&mut self, invocation_id: &InvocationId) -> Result<()> {
+        self.assert_partition_key(invocation_id);
+        delete_invocation_events(self, invocation_id)
+    }
+}
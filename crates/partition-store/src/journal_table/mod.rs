@@ -25,7 +25,7 @@ use restate_types::identifiers::{
     EntryIndex, InvocationId, InvocationUuid, JournalEntryId, PartitionKey, WithPartitionKey,
 };
 use std::io::Cursor;
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 
 define_table_key!(
     Journal,
@@ -127,9 +127,17 @@ fn delete_journal<S: StorageAccess>(
     invocation_id: &InvocationId,
     journal_length: EntryIndex,
 ) {
-    let mut key = write_journal_entry_key(invocation_id, 0);
+    delete_journal_range(storage, invocation_id, 0..journal_length)
+}
+
+fn delete_journal_range<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    range: Range<EntryIndex>,
+) {
+    let mut key = write_journal_entry_key(invocation_id, range.start);
     let k = &mut key;
-    for journal_index in 0..journal_length {
+    for journal_index in range {
         k.journal_index = Some(journal_index);
         storage.delete_key(k);
     }
@@ -207,6 +215,17 @@ impl<'a> JournalTable for PartitionStoreTransaction<'a> {
         let _x = RocksDbPerfGuard::new("delete-journal");
         delete_journal(self, invocation_id, journal_length)
     }
+
+    async fn truncate_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        from_index: EntryIndex,
+        journal_length: EntryIndex,
+    ) {
+        self.assert_partition_key(invocation_id);
+        let _x = RocksDbPerfGuard::new("truncate-journal");
+        delete_journal_range(self, invocation_id, from_index..journal_length)
+    }
 }
 
 #[cfg(test)]
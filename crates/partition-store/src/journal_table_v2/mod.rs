@@ -23,7 +23,8 @@ use restate_rocksdb::RocksDbPerfGuard;
 use restate_storage_api::journal_table_v2::{JournalTable, ReadOnlyJournalTable};
 use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::{
-    EntryIndex, InvocationId, InvocationUuid, JournalEntryId, PartitionKey, WithPartitionKey,
+    EntryIndex, InvocationId, InvocationUuid, JournalEntryId, PartitionId, PartitionKey,
+    WithPartitionKey,
 };
 use restate_types::journal_v2::raw::{RawCommand, RawEntry, RawEntryInner};
 use restate_types::journal_v2::{CompletionId, EntryMetadata, NotificationId};
@@ -159,6 +160,37 @@ fn get_journal<S: StorageAccess>(
     )
 }
 
+fn get_journal_range<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    invocation_id: &InvocationId,
+    from: EntryIndex,
+    length: EntryIndex,
+) -> Vec<Result<(EntryIndex, RawEntry)>> {
+    if from >= length {
+        return vec![];
+    }
+    let _x = RocksDbPerfGuard::new("get-journal-range");
+    storage.for_each_key_value_in_place(
+        TableScan::KeyRangeInclusiveInSinglePartition(
+            partition_id,
+            write_journal_entry_key(invocation_id, from),
+            write_journal_entry_key(invocation_id, length - 1),
+        ),
+        |k, mut v| {
+            let key = JournalKey::deserialize_from(&mut Cursor::new(k)).map(|journal_key| {
+                journal_key
+                    .journal_index
+                    .expect("The journal index must be part of the journal key.")
+            });
+            let entry =
+                StoredEntry::decode(&mut v).map_err(|error| StorageError::Generic(error.into()));
+
+            TableScanIterationDecision::Emit(key.and_then(|key| entry.map(|entry| (key, entry.0))))
+        },
+    )
+}
+
 fn all_journals<S: StorageAccess>(
     storage: &S,
     range: RangeInclusive<PartitionKey>,
@@ -244,6 +276,71 @@ fn delete_journal<S: StorageAccess>(
     Ok(())
 }
 
+fn truncate_journal<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    from_index: EntryIndex,
+    journal_length: EntryIndex,
+) -> Result<()> {
+    let mut key = write_journal_entry_key(invocation_id, from_index);
+    let k = &mut key;
+    for journal_index in from_index..journal_length {
+        k.journal_index = Some(journal_index);
+        storage.delete_key(k);
+    }
+
+    // Delete the indexes pointing to the removed entries
+    let notification_id_to_notification_index =
+        JournalNotificationIdToNotificationIndexKey::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid());
+    let removed_notification_ids: Vec<_> = get_notifications_index(storage, *invocation_id)?
+        .into_iter()
+        .filter(|(_, journal_index)| *journal_index >= from_index)
+        .map(|(notification_id, _)| notification_id)
+        .collect();
+    for notification_id in removed_notification_ids {
+        storage.delete_key(
+            &notification_id_to_notification_index
+                .clone()
+                .notification_id(notification_id),
+        );
+    }
+
+    let completion_id_to_command_index = JournalCompletionIdToCommandIndexKey::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid());
+    let removed_completion_ids =
+        OwnedIterator::new(storage.iterator_from(TableScan::SinglePartitionKeyPrefix(
+            invocation_id.partition_key(),
+            completion_id_to_command_index.clone(),
+        )))
+        .map(|(mut key, mut value)| {
+            let journal_key = JournalCompletionIdToCommandIndexKey::deserialize_from(&mut key)?;
+            let index = JournalEntryIndex::decode(&mut value)
+                .map_err(|err| StorageError::Conversion(err.into()))?;
+            let (_, _, completion_id) = journal_key.into_inner_ok_or()?;
+            Ok((completion_id, index.0))
+        })
+        .filter_map(|result| match result {
+            Ok((completion_id, journal_index)) if journal_index >= from_index => {
+                Some(Ok(completion_id))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for completion_id in removed_completion_ids {
+        storage.delete_key(
+            &completion_id_to_command_index
+                .clone()
+                .completion_id(completion_id),
+        );
+    }
+
+    Ok(())
+}
+
 fn get_notifications_index<S: StorageAccess>(
     storage: &mut S,
     invocation_id: InvocationId,
@@ -323,6 +420,23 @@ impl ReadOnlyJournalTable for PartitionStore {
         stream::iter(get_journal(self, &invocation_id, journal_length))
     }
 
+    fn get_journal_range(
+        &mut self,
+        invocation_id: InvocationId,
+        from: EntryIndex,
+        length: EntryIndex,
+    ) -> impl Stream<Item = Result<(EntryIndex, RawEntry)>> + Send {
+        self.assert_partition_key(&invocation_id);
+        let partition_id = self.partition_id();
+        stream::iter(get_journal_range(
+            self,
+            partition_id,
+            &invocation_id,
+            from,
+            length,
+        ))
+    }
+
     fn all_journals(
         &self,
         range: RangeInclusive<PartitionKey>,
@@ -366,6 +480,23 @@ impl<'a> ReadOnlyJournalTable for PartitionStoreTransaction<'a> {
         stream::iter(get_journal(self, &invocation_id, journal_length))
     }
 
+    fn get_journal_range(
+        &mut self,
+        invocation_id: InvocationId,
+        from: EntryIndex,
+        length: EntryIndex,
+    ) -> impl Stream<Item = Result<(EntryIndex, RawEntry)>> + Send {
+        self.assert_partition_key(&invocation_id);
+        let partition_id = self.partition_id();
+        stream::iter(get_journal_range(
+            self,
+            partition_id,
+            &invocation_id,
+            from,
+            length,
+        ))
+    }
+
     fn all_journals(
         &self,
         range: RangeInclusive<PartitionKey>,
@@ -410,6 +541,17 @@ impl<'a> JournalTable for PartitionStoreTransaction<'a> {
         let _x = RocksDbPerfGuard::new("delete-journal");
         delete_journal(self, &invocation_id, journal_length)
     }
+
+    async fn truncate_journal(
+        &mut self,
+        invocation_id: InvocationId,
+        from_index: EntryIndex,
+        journal_length: EntryIndex,
+    ) -> Result<()> {
+        self.assert_partition_key(&invocation_id);
+        let _x = RocksDbPerfGuard::new("truncate-journal");
+        truncate_journal(self, &invocation_id, from_index, journal_length)
+    }
 }
 
 #[cfg(test)]
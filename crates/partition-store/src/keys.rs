@@ -41,6 +41,7 @@ pub enum KeyKind {
     State,
     Timers,
     Promise,
+    InvocationEvent,
 }
 
 impl KeyKind {
@@ -84,6 +85,7 @@ impl KeyKind {
             KeyKind::State => b"st",
             KeyKind::Timers => b"ti",
             KeyKind::Promise => b"pr",
+            KeyKind::InvocationEvent => b"ie",
         }
     }
 
@@ -112,6 +114,7 @@ impl KeyKind {
             b"st" => Some(KeyKind::State),
             b"ti" => Some(KeyKind::Timers),
             b"pr" => Some(KeyKind::Promise),
+            b"ie" => Some(KeyKind::InvocationEvent),
             _ => None,
         }
     }
@@ -12,10 +12,12 @@ pub mod deduplication_table;
 pub mod fsm_table;
 pub mod idempotency_table;
 pub mod inbox_table;
+pub mod invocation_event_table;
 pub mod invocation_status_table;
 pub mod journal_table;
 pub mod journal_table_v2;
 pub mod keys;
+pub mod migration;
 pub mod outbox_table;
 mod owned_iter;
 mod partition_store;
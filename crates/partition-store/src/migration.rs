@@ -0,0 +1,376 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Migration of the partition stores contents to a cluster with a different partition layout.
+//!
+//! The contents of each source partition are exported as a partition snapshot. On import, every
+//! record is routed to the target partition owning its partition key, see [`Repartitioner`].
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use bytes::{Buf, BytesMut};
+
+use restate_rocksdb::RocksError;
+use restate_storage_api::timer_table::Timer;
+use restate_storage_api::StorageError;
+use restate_types::identifiers::{PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::message::MessageIndex;
+
+use crate::inbox_table::InboxKey;
+use crate::keys::{KeyKind, TableKey};
+use crate::protobuf_types::PartitionStoreProtobufValue;
+use crate::snapshots::{LocalPartitionSnapshot, PartitionSnapshotMetadata};
+use crate::timer_table::TimersKey;
+
+/// Directory of an export holding the partition snapshots, one sub-directory per snapshot.
+pub const PARTITIONS_DIR: &str = "partitions";
+/// File of a partition snapshot directory describing the snapshot.
+pub const SNAPSHOT_METADATA_FILE: &str = "metadata.json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error(transparent)]
+    Rocks(#[from] RocksError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("target partition {0} already contains data")]
+    TargetNotEmpty(PartitionId),
+    #[error("the outbox of partition {0} is not empty, the source cluster must be fully drained")]
+    OutboxNotEmpty(PartitionId),
+    #[error("no target partition owns the partition key {0}")]
+    Unroutable(PartitionKey),
+    #[error("failed accessing the exported snapshot {0}: {1}")]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("invalid snapshot metadata {0}: {1}")]
+    Metadata(PathBuf, #[source] serde_json::Error),
+    #[error("no snapshot covers the partition keys {0:?}")]
+    MissingKeys(RangeInclusive<PartitionKey>),
+    #[error("the snapshots of the partitions {0} and {1} overlap")]
+    OverlappingSnapshots(PartitionId, PartitionId),
+}
+
+/// Checks that the key ranges of the source partitions cover all the partition keys exactly
+/// once, i.e. that no source partition is missing or exported twice.
+pub fn check_key_space_coverage(
+    mut key_ranges: Vec<(PartitionId, RangeInclusive<PartitionKey>)>,
+) -> Result<(), MigrationError> {
+    key_ranges.sort_by_key(|(_, key_range)| *key_range.start());
+
+    // The next partition key to cover, `None` once the whole key space is covered
+    let mut next = Some(0);
+    let mut previous = None;
+    for (partition_id, key_range) in key_ranges {
+        let Some(next_key) = next else {
+            return Err(MigrationError::OverlappingSnapshots(
+                previous.unwrap_or(partition_id),
+                partition_id,
+            ));
+        };
+        if *key_range.start() > next_key {
+            return Err(MigrationError::MissingKeys(
+                next_key..=key_range.start() - 1,
+            ));
+        }
+        if *key_range.start() < next_key {
+            return Err(MigrationError::OverlappingSnapshots(
+                previous.unwrap_or(partition_id),
+                partition_id,
+            ));
+        }
+        next = key_range.end().checked_add(1);
+        previous = Some(partition_id);
+    }
+
+    match next {
+        Some(next_key) => Err(MigrationError::MissingKeys(next_key..=PartitionKey::MAX)),
+        None => Ok(()),
+    }
+}
+
+/// Routes the records of the source partitions to the target partitions.
+///
+/// Records keyed by partition key are routed as is. Timers are keyed by partition id, so their
+/// key is rewritten with the id of the target partition. The records which only make sense in
+/// the source cluster, the partition state machine and the deduplication information, are
+/// skipped. The outbox is expected to be empty.
+#[derive(Debug)]
+pub struct Repartitioner {
+    targets: Vec<(PartitionId, RangeInclusive<PartitionKey>)>,
+    max_inbox_seq_numbers: HashMap<PartitionId, MessageIndex>,
+}
+
+impl Repartitioner {
+    pub fn new(targets: Vec<(PartitionId, RangeInclusive<PartitionKey>)>) -> Self {
+        Self {
+            targets,
+            max_inbox_seq_numbers: HashMap::new(),
+        }
+    }
+
+    /// Returns the target partition of the record and its key there, or `None` if the record
+    /// must not be migrated.
+    pub fn route(
+        &mut self,
+        source_partition: PartitionId,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<Option<(PartitionId, BytesMut)>, MigrationError> {
+        let key_kind = KeyKind::deserialize(&mut &key[..])?;
+        match key_kind {
+            KeyKind::Fsm | KeyKind::Deduplication => Ok(None),
+            KeyKind::Outbox => Err(MigrationError::OutboxNotEmpty(source_partition)),
+            KeyKind::Timers => {
+                let timer = Timer::decode(&mut &value[..])?;
+                let target = self.target(timer.partition_key())?;
+                let key = TimersKey::deserialize_from(&mut &key[..])?
+                    .partition_id(target.into())
+                    .serialize();
+                Ok(Some((target, key)))
+            }
+            KeyKind::Inbox => {
+                let inbox_key = InboxKey::deserialize_from(&mut &key[..])?;
+                let target = self.target(
+                    inbox_key
+                        .partition_key
+                        .ok_or(StorageError::DataIntegrityError)?,
+                )?;
+                let sequence_number = inbox_key
+                    .sequence_number
+                    .ok_or(StorageError::DataIntegrityError)?;
+                let max = self.max_inbox_seq_numbers.entry(target).or_default();
+                *max = (*max).max(sequence_number);
+                Ok(Some((target, BytesMut::from(key))))
+            }
+            _ => {
+                let mut partition_key = &key[KeyKind::SERIALIZED_LENGTH..];
+                if partition_key.remaining() < size_of::<PartitionKey>() {
+                    return Err(StorageError::DataIntegrityError.into());
+                }
+                let target = self.target(partition_key.get_u64())?;
+                Ok(Some((target, BytesMut::from(key))))
+            }
+        }
+    }
+
+    /// The inbox sequence number each target partition must continue from, so that new inbox
+    /// entries are enqueued after the migrated ones.
+    pub fn next_inbox_seq_numbers(&self) -> impl Iterator<Item = (PartitionId, MessageIndex)> + '_ {
+        self.max_inbox_seq_numbers
+            .iter()
+            .map(|(partition_id, max)| (*partition_id, max + 1))
+    }
+
+    fn target(&self, partition_key: PartitionKey) -> Result<PartitionId, MigrationError> {
+        self.targets
+            .iter()
+            .find(|(_, key_range)| key_range.contains(&partition_key))
+            .map(|(partition_id, _)| *partition_id)
+            .ok_or(MigrationError::Unroutable(partition_key))
+    }
+}
+
+/// Writes the metadata of an exported snapshot next to its files, so that it can be read back by
+/// [`read_exported_snapshots`].
+pub fn write_snapshot_metadata(
+    snapshot: &LocalPartitionSnapshot,
+    metadata: &PartitionSnapshotMetadata,
+) -> Result<(), MigrationError> {
+    let path = snapshot.base_dir.join(SNAPSHOT_METADATA_FILE);
+    let json = serde_json::to_vec_pretty(metadata)
+        .map_err(|err| MigrationError::Metadata(path.clone(), err))?;
+    std::fs::write(&path, json).map_err(|err| MigrationError::Io(path, err))
+}
+
+/// Reads the partition snapshots of an export directory.
+pub fn read_exported_snapshots(
+    export_dir: &Path,
+) -> Result<Vec<(PartitionSnapshotMetadata, LocalPartitionSnapshot)>, MigrationError> {
+    let partitions_dir = export_dir.join(PARTITIONS_DIR);
+    let entries = std::fs::read_dir(&partitions_dir)
+        .map_err(|err| MigrationError::Io(partitions_dir.clone(), err))?;
+
+    let mut snapshots = Vec::new();
+    for entry in entries {
+        let base_dir = entry
+            .map_err(|err| MigrationError::Io(partitions_dir.clone(), err))?
+            .path();
+        let path = base_dir.join(SNAPSHOT_METADATA_FILE);
+        if !path.is_file() {
+            continue;
+        }
+        let json = std::fs::read(&path).map_err(|err| MigrationError::Io(path.clone(), err))?;
+        let mut metadata: PartitionSnapshotMetadata =
+            serde_json::from_slice(&json).map_err(|err| MigrationError::Metadata(path, err))?;
+
+        // the export may have been moved, the files are wherever the metadata is
+        let directory = base_dir.to_string_lossy().to_string();
+        for file in &mut metadata.files {
+            file.directory.clone_from(&directory);
+        }
+
+        let snapshot = LocalPartitionSnapshot {
+            base_dir,
+            min_applied_lsn: metadata.min_applied_lsn,
+            db_comparator_name: metadata.db_comparator_name.clone(),
+            files: metadata.files.clone(),
+            key_range: metadata.key_range.clone(),
+        };
+        snapshots.push((metadata, snapshot));
+    }
+    snapshots.sort_by_key(|(metadata, _)| metadata.partition_id);
+
+    Ok(snapshots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_storage_api::timer_table::TimerKeyKind;
+    use restate_types::identifiers::{InvocationId, InvocationUuid};
+    use restate_types::storage::StorageCodec;
+
+    use crate::fsm_table::PartitionStateMachineKey;
+    use crate::protobuf_types::ProtobufStorageWrapper;
+    use crate::state_table::StateKey;
+
+    fn repartitioner() -> Repartitioner {
+        Repartitioner::new(vec![
+            (PartitionId::from(0), 0..=99),
+            (PartitionId::from(1), 100..=PartitionKey::MAX),
+        ])
+    }
+
+    #[test]
+    fn checks_key_space_coverage() {
+        let (p0, p1, p2) = (
+            PartitionId::from(0),
+            PartitionId::from(1),
+            PartitionId::from(2),
+        );
+
+        assert!(check_key_space_coverage(vec![(p0, 0..=PartitionKey::MAX)]).is_ok());
+        assert!(
+            check_key_space_coverage(vec![(p1, 100..=PartitionKey::MAX), (p0, 0..=99)]).is_ok()
+        );
+
+        assert!(matches!(
+            check_key_space_coverage(vec![]),
+            Err(MigrationError::MissingKeys(keys)) if keys == (0..=PartitionKey::MAX)
+        ));
+        assert!(matches!(
+            check_key_space_coverage(vec![(p0, 0..=99), (p2, 200..=PartitionKey::MAX)]),
+            Err(MigrationError::MissingKeys(keys)) if keys == (100..=199)
+        ));
+        assert!(matches!(
+            check_key_space_coverage(vec![(p0, 0..=99), (p1, 100..=199)]),
+            Err(MigrationError::MissingKeys(keys)) if keys == (200..=PartitionKey::MAX)
+        ));
+        assert!(matches!(
+            check_key_space_coverage(vec![(p0, 0..=99), (p1, 50..=PartitionKey::MAX)]),
+            Err(MigrationError::OverlappingSnapshots(a, b)) if a == p0 && b == p1
+        ));
+        assert!(matches!(
+            check_key_space_coverage(vec![
+                (p0, 0..=PartitionKey::MAX),
+                (p1, PartitionKey::MAX..=PartitionKey::MAX)
+            ]),
+            Err(MigrationError::OverlappingSnapshots(a, b)) if a == p0 && b == p1
+        ));
+    }
+
+    #[test]
+    fn routes_on_partition_key() {
+        let mut repartitioner = repartitioner();
+        let key = StateKey::default()
+            .partition_key(150)
+            .service_name("Counter".into())
+            .service_key("a".into())
+            .state_key("count".into())
+            .serialize();
+
+        let (target, routed_key) = repartitioner
+            .route(PartitionId::from(0), &key, b"1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(target, PartitionId::from(1));
+        assert_eq!(routed_key, key);
+    }
+
+    #[test]
+    fn skips_partition_state() {
+        let mut repartitioner = repartitioner();
+        let key = PartitionStateMachineKey::default()
+            .partition_id(PartitionId::from(3).into())
+            .state_id(0)
+            .serialize();
+
+        assert!(repartitioner
+            .route(PartitionId::from(3), &key, &[])
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn tracks_inbox_seq_numbers() {
+        let mut repartitioner = repartitioner();
+        for sequence_number in [3, 7] {
+            let key = InboxKey::default()
+                .partition_key(10)
+                .service_name("Counter".into())
+                .service_key("a".into())
+                .sequence_number(sequence_number)
+                .serialize();
+            repartitioner
+                .route(PartitionId::from(5), &key, &[])
+                .unwrap();
+        }
+
+        assert_eq!(
+            repartitioner.next_inbox_seq_numbers().collect::<Vec<_>>(),
+            vec![(PartitionId::from(0), 8)]
+        );
+    }
+
+    #[test]
+    fn rewrites_timer_partition_id() {
+        let mut repartitioner = repartitioner();
+        let invocation_id = InvocationId::from_parts(120, InvocationUuid::mock_random());
+        let timer = Timer::CompleteJournalEntry(invocation_id, 1);
+        let key = TimersKey::default()
+            .partition_id(PartitionId::from(7).into())
+            .timestamp(42)
+            .kind(TimerKeyKind::CompleteJournalEntry {
+                invocation_uuid: invocation_id.invocation_uuid(),
+                journal_index: 1,
+            })
+            .serialize();
+        let mut value = BytesMut::new();
+        StorageCodec::encode(
+            &ProtobufStorageWrapper::<<Timer as PartitionStoreProtobufValue>::ProtobufType>(
+                timer.into(),
+            ),
+            &mut value,
+        )
+        .unwrap();
+
+        let (target, routed_key) = repartitioner
+            .route(PartitionId::from(7), &key, &value)
+            .unwrap()
+            .unwrap();
+        assert_eq!(target, PartitionId::from(1));
+
+        let routed_key = TimersKey::deserialize_from(&mut &routed_key[..]).unwrap();
+        assert_eq!(routed_key.partition_id, Some(PartitionId::from(1).into()));
+        assert_eq!(routed_key.timestamp, Some(42));
+    }
+}
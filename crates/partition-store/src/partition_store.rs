@@ -130,6 +130,7 @@ pub enum TableKind {
     Inbox,
     Journal,
     Promise,
+    InvocationEvent,
 }
 
 impl TableKind {
@@ -152,6 +153,7 @@ impl TableKind {
                 KeyKind::JournalV2NotificationIdToNotificationIndex,
             ],
             Self::Promise => &[KeyKind::Promise],
+            Self::InvocationEvent => &[KeyKind::InvocationEvent],
         }
     }
 
@@ -229,6 +231,7 @@ impl Clone for PartitionStore {
 
 pub(crate) fn cf_options(
     memory_budget: usize,
+    payload_compression_threshold: Option<usize>,
 ) -> impl Fn(rocksdb::Options) -> rocksdb::Options + Send + Sync + 'static {
     move |mut cf_options| {
         set_memory_related_opts(&mut cf_options, memory_budget);
@@ -251,6 +254,15 @@ pub(crate) fn cf_options(
             DBCompressionType::Zstd,
         ]);
 
+        if let Some(threshold) = payload_compression_threshold {
+            // Large values (mostly journal entries) are stored in zstd-compressed blob files
+            // so they are not rewritten on every compaction of the LSM tree.
+            cf_options.set_enable_blob_files(true);
+            cf_options.set_min_blob_size(threshold as u64);
+            cf_options.set_blob_compression_type(DBCompressionType::Zstd);
+            cf_options.set_enable_blob_gc(true);
+        }
+
         cf_options
     }
 }
@@ -8,16 +8,17 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::RangeInclusive;
 use std::path::Path;
 use std::sync::Arc;
 
-use rocksdb::ExportImportFilesMetaData;
+use rocksdb::{ExportImportFilesMetaData, IteratorMode, ReadOptions, WriteBatch, WriteOptions};
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
 use crate::cf_options;
+use crate::migration::{check_key_space_coverage, MigrationError, Repartitioner};
 use crate::snapshots::LocalPartitionSnapshot;
 use crate::PartitionStore;
 use crate::DB;
@@ -25,12 +26,16 @@ use restate_core::worker_api::SnapshotError;
 use restate_rocksdb::{
     CfName, CfPrefixPattern, DbName, DbSpecBuilder, RocksDb, RocksDbManager, RocksError,
 };
+use restate_storage_api::fsm_table::FsmTable;
+use restate_storage_api::Transaction;
 use restate_types::config::{RocksDbOptions, StorageOptions};
 use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
 use restate_types::live::{BoxedLiveLoad, LiveLoad};
 
 const DB_NAME: &str = "db";
 const PARTITION_CF_PREFIX: &str = "data-";
+/// Number of records written per batch when importing re-partitioned snapshots.
+const MIGRATION_WRITE_BATCH_SIZE: usize = 10_000;
 
 /// Controls how a partition store is opened
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -65,7 +70,10 @@ impl PartitionStoreManager {
         let db_spec = DbSpecBuilder::new(DbName::new(DB_NAME), options.data_dir(), db_options())
             .add_cf_pattern(
                 CfPrefixPattern::new(PARTITION_CF_PREFIX),
-                cf_options(per_partition_memory_budget),
+                cf_options(
+                    per_partition_memory_budget,
+                    options.payload_compression_threshold(),
+                ),
             )
             .ensure_column_families(partition_ids_to_cfs(initial_partition_set))
             // This is added as an experiment. We might make this configurable to let users decide
@@ -206,6 +214,157 @@ impl PartitionStoreManager {
         Ok(partition_store)
     }
 
+    /// Imports the snapshots of the partitions of another cluster, routing their records to the
+    /// target partitions owning their partition keys. The snapshots must cover all the partition
+    /// keys. The target partition stores are created if missing, and must not contain any data.
+    /// If the import fails, the data it wrote to the target partitions is dropped, so that it can
+    /// be retried. See [`Repartitioner`] for which records are migrated.
+    pub async fn import_repartitioned_snapshots(
+        &self,
+        snapshots: Vec<(PartitionId, LocalPartitionSnapshot)>,
+        target_partitions: &[(PartitionId, RangeInclusive<PartitionKey>)],
+        opts: &RocksDbOptions,
+    ) -> Result<Vec<PartitionStore>, MigrationError> {
+        check_key_space_coverage(
+            snapshots
+                .iter()
+                .map(|(partition_id, snapshot)| (*partition_id, snapshot.key_range.clone()))
+                .collect(),
+        )?;
+
+        let mut target_stores = Vec::with_capacity(target_partitions.len());
+        for (partition_id, key_range) in target_partitions {
+            let partition_store = self
+                .open_partition_store(
+                    *partition_id,
+                    key_range.clone(),
+                    OpenMode::CreateIfMissing,
+                    opts,
+                )
+                .await?;
+            if !self.is_cf_empty(&cf_for_partition(*partition_id))? {
+                return Err(MigrationError::TargetNotEmpty(*partition_id));
+            }
+            target_stores.push(partition_store);
+        }
+
+        let source_partitions: Vec<_> = snapshots.iter().map(|(id, _)| *id).collect();
+        let result = self
+            .copy_repartitioned_snapshots(snapshots, target_partitions, &mut target_stores, opts)
+            .await;
+        if let Err(err) = result {
+            warn!(%err, "Failed importing the migrated partitions, dropping the imported data");
+            drop(target_stores);
+            for source_partition in source_partitions {
+                let source_cf = migration_source_cf(source_partition);
+                if self.rocksdb.inner().cf_handle(&source_cf).is_some() {
+                    if let Err(err) = self.raw_db.drop_cf(&source_cf) {
+                        warn!(%err, "Failed dropping the column family {source_cf}");
+                    }
+                }
+            }
+            self.drop_migration_targets(target_partitions).await?;
+            return Err(err);
+        }
+
+        Ok(target_stores)
+    }
+
+    /// Drops the target partition stores of a migration, if they exist. Used to clean up after an
+    /// import which was interrupted before completing, e.g. because the process was killed.
+    pub async fn drop_migration_targets(
+        &self,
+        target_partitions: &[(PartitionId, RangeInclusive<PartitionKey>)],
+    ) -> Result<(), RocksError> {
+        let mut guard = self.lookup.lock().await;
+        for (partition_id, _) in target_partitions {
+            guard.live.remove(partition_id);
+            let cf_name = cf_for_partition(*partition_id);
+            if self.rocksdb.inner().cf_handle(&cf_name).is_some() {
+                info!(%partition_id, "Dropping the partition store of a migration target");
+                self.raw_db.drop_cf(&cf_name)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn copy_repartitioned_snapshots(
+        &self,
+        snapshots: Vec<(PartitionId, LocalPartitionSnapshot)>,
+        target_partitions: &[(PartitionId, RangeInclusive<PartitionKey>)],
+        target_stores: &mut [PartitionStore],
+        opts: &RocksDbOptions,
+    ) -> Result<(), MigrationError> {
+        let mut repartitioner = Repartitioner::new(target_partitions.to_vec());
+        for (source_partition, snapshot) in snapshots {
+            let source_cf = migration_source_cf(source_partition);
+            if self.rocksdb.inner().cf_handle(&source_cf).is_some() {
+                self.raw_db.drop_cf(&source_cf).map_err(RocksError::from)?;
+            }
+
+            let mut import_metadata = ExportImportFilesMetaData::default();
+            import_metadata.set_db_comparator_name(snapshot.db_comparator_name.as_str());
+            import_metadata.set_files(&snapshot.files);
+
+            info!(
+                %source_partition,
+                path = ?snapshot.base_dir,
+                "Importing partition store snapshot of migrated partition"
+            );
+            self.rocksdb
+                .import_cf(source_cf.clone(), opts, import_metadata)
+                .await?;
+
+            let raw_db = self.raw_db.clone();
+            let copy_source_cf = source_cf.clone();
+            let (returned_repartitioner, copied) = tokio::task::spawn_blocking(move || {
+                let copied = copy_repartitioned_records(
+                    &raw_db,
+                    &copy_source_cf,
+                    source_partition,
+                    &mut repartitioner,
+                );
+                (repartitioner, copied)
+            })
+            .await
+            .expect("copying the migrated records does not panic");
+            repartitioner = returned_repartitioner;
+
+            self.raw_db.drop_cf(&source_cf).map_err(RocksError::from)?;
+            let copied = copied?;
+            debug!(%source_partition, "Migrated {copied} records");
+        }
+
+        let next_inbox_seq_numbers: HashMap<_, _> =
+            repartitioner.next_inbox_seq_numbers().collect();
+        for partition_store in target_stores.iter_mut() {
+            if let Some(seq_number) = next_inbox_seq_numbers.get(&partition_store.partition_id()) {
+                let mut txn = partition_store.transaction();
+                txn.put_inbox_seq_number(*seq_number).await;
+                txn.commit().await?;
+            }
+            // the records were written without WAL
+            partition_store.flush_memtables(true).await?;
+        }
+
+        Ok(())
+    }
+
+    fn is_cf_empty(&self, cf_name: &CfName) -> Result<bool, RocksError> {
+        let cf = self
+            .raw_db
+            .cf_handle(cf_name)
+            .ok_or_else(|| RocksError::UnknownColumnFamily(cf_name.clone()))?;
+        let mut opts = ReadOptions::default();
+        opts.set_total_order_seek(true);
+        Ok(self
+            .raw_db
+            .iterator_cf_opt(&cf, opts, IteratorMode::Start)
+            .next()
+            .transpose()?
+            .is_none())
+    }
+
     pub async fn export_partition_snapshot(
         &self,
         partition_id: PartitionId,
@@ -239,6 +398,56 @@ impl PartitionStoreManager {
     }
 }
 
+/// Copies the records of the source column family to the target partitions' column families.
+/// Returns the number of copied records.
+fn copy_repartitioned_records(
+    raw_db: &DB,
+    source_cf: &CfName,
+    source_partition: PartitionId,
+    repartitioner: &mut Repartitioner,
+) -> Result<u64, MigrationError> {
+    let source = raw_db
+        .cf_handle(source_cf)
+        .ok_or_else(|| RocksError::UnknownColumnFamily(source_cf.clone()))?;
+    let mut read_opts = ReadOptions::default();
+    read_opts.set_total_order_seek(true);
+    let mut write_opts = WriteOptions::default();
+    // We disable WAL since the target partitions are flushed once the import completes.
+    write_opts.disable_wal(true);
+
+    let mut copied = 0;
+    let mut batch = WriteBatch::default();
+    for record in raw_db.iterator_cf_opt(&source, read_opts, IteratorMode::Start) {
+        let (key, value) = record.map_err(RocksError::from)?;
+        let Some((target, key)) = repartitioner.route(source_partition, &key, &value)? else {
+            continue;
+        };
+        let target_cf = cf_for_partition(target);
+        let target = raw_db
+            .cf_handle(&target_cf)
+            .ok_or(RocksError::UnknownColumnFamily(target_cf))?;
+        batch.put_cf(&target, key, value);
+        copied += 1;
+
+        if batch.len() >= MIGRATION_WRITE_BATCH_SIZE {
+            raw_db
+                .write_opt(std::mem::take(&mut batch), &write_opts)
+                .map_err(RocksError::from)?;
+        }
+    }
+    raw_db
+        .write_opt(batch, &write_opts)
+        .map_err(RocksError::from)?;
+
+    Ok(copied)
+}
+
+/// Temporary column family the snapshot of a source partition is imported into, it must not
+/// clash with the target partitions.
+fn migration_source_cf(source_partition: PartitionId) -> CfName {
+    CfName::from(format!("{PARTITION_CF_PREFIX}migration-{source_partition}"))
+}
+
 fn cf_for_partition(partition_id: PartitionId) -> CfName {
     CfName::from(format!("{PARTITION_CF_PREFIX}{partition_id}"))
 }
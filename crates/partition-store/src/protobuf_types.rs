@@ -146,17 +146,18 @@ pub mod v1 {
             Ingress, PartitionProcessor, ResponseSink,
         };
         use crate::protobuf_types::v1::{
-            enriched_entry_header, entry, entry_result, inbox_entry, invocation_resolution_result,
-            invocation_status, invocation_status_v2, invocation_target, journal_entry,
-            outbox_message, promise, response_result, source, span_relation,
-            submit_notification_sink, timer, virtual_object_status, BackgroundCallResolutionResult,
-            DedupSequenceNumber, Duration, EnrichedEntryHeader, Entry, EntryResult,
-            EpochSequenceNumber, Header, IdempotencyId, IdempotencyMetadata, InboxEntry,
-            InvocationId, InvocationResolutionResult, InvocationStatus, InvocationStatusV2,
-            InvocationTarget, InvocationV2Lite, JournalEntry, JournalEntryId, JournalEntryIndex,
-            JournalMeta, KvPair, OutboxMessage, Promise, ResponseResult, SequenceNumber, ServiceId,
-            ServiceInvocation, ServiceInvocationResponseSink, Source, SpanContext, SpanRelation,
-            StateMutation, SubmitNotificationSink, Timer, VirtualObjectStatus,
+            enriched_entry_header, entry, entry_result, inbox_entry, invocation_event,
+            invocation_resolution_result, invocation_status, invocation_status_v2,
+            invocation_target, journal_entry, outbox_message, promise, response_result, source,
+            span_relation, submit_notification_sink, timer, virtual_object_status,
+            BackgroundCallResolutionResult, DedupSequenceNumber, Duration, EnrichedEntryHeader,
+            Entry, EntryResult, EpochSequenceNumber, Header, IdempotencyId, IdempotencyMetadata,
+            InboxEntry, InvocationEvent, InvocationId, InvocationResolutionResult,
+            InvocationStatus, InvocationStatusV2, InvocationTarget, InvocationV2Lite, JournalEntry,
+            JournalEntryId, JournalEntryIndex, JournalLimits, JournalMeta, KvPair, OutboxMessage,
+            Promise, ResponseResult, SequenceNumber, ServiceId, ServiceInvocation,
+            ServiceInvocationResponseSink, Source, SpanContext, SpanRelation, StateMutation,
+            SubmitNotificationSink, Timer, VirtualObjectStatus,
         };
         use crate::protobuf_types::ConversionError;
         use restate_storage_api::StorageError;
@@ -406,6 +407,8 @@ pub mod v1 {
                     idempotency_key,
                     inbox_sequence_number,
                     journal_length,
+                    journal_size,
+                    output_chunks,
                     deployment_id,
                     service_protocol_version,
                     waiting_for_completions,
@@ -494,6 +497,8 @@ pub mod v1 {
                                 invocation_target,
                                 journal_metadata: restate_storage_api::invocation_status_table::JournalMetadata {
                                     length: journal_length,
+                                    size: journal_size,
+                                    output_chunks,
                                     span_context: expect_or_fail!(span_context)?.try_into()?,
                                 },
                                 pinned_deployment: derive_pinned_deployment(
@@ -516,6 +521,8 @@ pub mod v1 {
                                 invocation_target,
                                 journal_metadata: restate_storage_api::invocation_status_table::JournalMetadata {
                                     length: journal_length,
+                                    size: journal_size,
+                                    output_chunks,
                                     span_context: expect_or_fail!(span_context)?.try_into()?,
                                 },
                                 pinned_deployment: derive_pinned_deployment(
@@ -554,6 +561,8 @@ pub mod v1 {
                                 invocation_target,
                                 journal_metadata: restate_storage_api::invocation_status_table::JournalMetadata {
                                     length: journal_length,
+                                    size: journal_size,
+                                    output_chunks,
                                     span_context: expect_or_fail!(span_context)?.try_into()?,
                                 },
                                 pinned_deployment: derive_pinned_deployment(
@@ -580,6 +589,7 @@ pub mod v1 {
                                 completion_retention_duration: completion_retention_duration
                                     .unwrap_or_default()
                                     .try_into()?,
+                                journal_length,
                             },
                         ))
                     }
@@ -641,6 +651,8 @@ pub mod v1 {
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
                         inbox_sequence_number: None,
                         journal_length: 0,
+                        journal_size: None,
+                        output_chunks: 0,
                         deployment_id: None,
                         service_protocol_version: None,
                         waiting_for_completions: vec![],
@@ -696,6 +708,8 @@ pub mod v1 {
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
                         inbox_sequence_number: Some(inbox_sequence_number),
                         journal_length: 0,
+                        journal_size: None,
+                        output_chunks: 0,
                         deployment_id: None,
                         service_protocol_version: None,
                         waiting_for_completions: vec![],
@@ -760,6 +774,8 @@ pub mod v1 {
                             idempotency_key: idempotency_key.map(|key| key.to_string()),
                             inbox_sequence_number: None,
                             journal_length: journal_metadata.length,
+                            journal_size: journal_metadata.size,
+                            output_chunks: journal_metadata.output_chunks,
                             deployment_id,
                             service_protocol_version,
                             waiting_for_completions: vec![],
@@ -844,6 +860,8 @@ pub mod v1 {
                             idempotency_key: idempotency_key.map(|key| key.to_string()),
                             inbox_sequence_number: None,
                             journal_length: journal_metadata.length,
+                            journal_size: journal_metadata.size,
+                            output_chunks: journal_metadata.output_chunks,
                             deployment_id,
                             service_protocol_version,
                             waiting_for_completions,
@@ -909,6 +927,8 @@ pub mod v1 {
                             idempotency_key: idempotency_key.map(|key| key.to_string()),
                             inbox_sequence_number: None,
                             journal_length: journal_metadata.length,
+                            journal_size: journal_metadata.size,
+                            output_chunks: journal_metadata.output_chunks,
                             deployment_id,
                             service_protocol_version,
                             waiting_for_completions: vec![],
@@ -926,6 +946,7 @@ pub mod v1 {
                             timestamps,
                             response_result,
                             completion_retention_duration,
+                            journal_length,
                         },
                     ) => InvocationStatusV2 {
                         status: invocation_status_v2::Status::Completed.into(),
@@ -954,7 +975,9 @@ pub mod v1 {
                         completion_retention_duration: Some(completion_retention_duration.into()),
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
                         inbox_sequence_number: None,
-                        journal_length: 0,
+                        journal_length,
+                        journal_size: None,
+                        output_chunks: 0,
                         deployment_id: None,
                         service_protocol_version: None,
                         waiting_for_completions: vec![],
@@ -1540,6 +1563,7 @@ pub mod v1 {
                         // The value Duration::MAX here disables the new cleaner task business logic.
                         // Look at crates/worker/src/partition/cleaner.rs for more details.
                         completion_retention_duration: std::time::Duration::MAX,
+                        journal_length: 0,
                     },
                 )
             }
@@ -1555,8 +1579,9 @@ pub mod v1 {
                     idempotency_key,
                     timestamps,
                     response_result,
-                    // We don't store this in the old invocation status table
+                    // We don't store these in the old invocation status table
                     completion_retention_duration: _,
+                    journal_length: _,
                     // The old invocation status table doesn't support span context on Completed
                     span_context: _,
                 } = value;
@@ -1586,6 +1611,8 @@ pub mod v1 {
                 Ok(
                     restate_storage_api::invocation_status_table::JournalMetadata {
                         length,
+                        size: value.size,
+                        output_chunks: value.output_chunks,
                         span_context,
                     },
                 )
@@ -1597,10 +1624,14 @@ pub mod v1 {
                 let restate_storage_api::invocation_status_table::JournalMetadata {
                     span_context,
                     length,
+                    size,
+                    output_chunks,
                 } = value;
 
                 JournalMeta {
                     length,
+                    size,
+                    output_chunks,
                     span_context: Some(SpanContext::from(span_context)),
                 }
             }
@@ -1640,6 +1671,13 @@ pub mod v1 {
                         )?,
                     ),
                     source::Source::Internal(_) => restate_types::invocation::Source::Internal,
+                    source::Source::Restart(restart) => restate_types::invocation::Source::Restart(
+                        restate_types::identifiers::InvocationId::try_from(
+                            restart
+                                .invocation_id
+                                .ok_or(ConversionError::missing_field("invocation_id"))?,
+                        )?,
+                    ),
                 };
 
                 Ok(source)
@@ -1667,6 +1705,11 @@ pub mod v1 {
                         invocation_target: Some(InvocationTarget::from(invocation_target)),
                     }),
                     restate_types::invocation::Source::Internal => source::Source::Internal(()),
+                    restate_types::invocation::Source::Restart(invocation_id) => {
+                        source::Source::Restart(source::Restart {
+                            invocation_id: Some(InvocationId::from(invocation_id)),
+                        })
+                    }
                 };
 
                 Source {
@@ -3748,6 +3791,24 @@ pub mod v1 {
             }
         }
 
+        impl From<restate_types::invocation::JournalLimits> for JournalLimits {
+            fn from(value: restate_types::invocation::JournalLimits) -> Self {
+                JournalLimits {
+                    max_entries: value.max_entries,
+                    max_size: value.max_size,
+                }
+            }
+        }
+
+        impl From<JournalLimits> for restate_types::invocation::JournalLimits {
+            fn from(value: JournalLimits) -> Self {
+                Self {
+                    max_entries: value.max_entries,
+                    max_size: value.max_size,
+                }
+            }
+        }
+
         impl From<crate::journal_table_v2::JournalEntryIndex> for JournalEntryIndex {
             fn from(value: crate::journal_table_v2::JournalEntryIndex) -> Self {
                 Self {
@@ -3761,5 +3822,113 @@ pub mod v1 {
                 Self::from(value.entry_index)
             }
         }
+
+        impl TryFrom<InvocationEvent> for restate_storage_api::invocation_event_table::InvocationEvent {
+            type Error = ConversionError;
+
+            fn try_from(value: InvocationEvent) -> Result<Self, ConversionError> {
+                use restate_storage_api::invocation_event_table::InvocationEventKind;
+
+                let kind = match value.kind.ok_or(ConversionError::missing_field("kind"))? {
+                    invocation_event::Kind::Created(_) => InvocationEventKind::Created,
+                    invocation_event::Kind::Scheduled(scheduled) => {
+                        InvocationEventKind::Scheduled {
+                            execution_time: MillisSinceEpoch::new(scheduled.execution_time),
+                        }
+                    }
+                    invocation_event::Kind::Inboxed(_) => InvocationEventKind::Inboxed,
+                    invocation_event::Kind::Started(_) => InvocationEventKind::Started,
+                    invocation_event::Kind::PinnedDeployment(pinned_deployment) => {
+                        InvocationEventKind::PinnedDeployment {
+                            deployment_id: pinned_deployment
+                                .deployment_id
+                                .parse()
+                                .map_err(ConversionError::invalid_data)?,
+                        }
+                    }
+                    invocation_event::Kind::TransientError(transient_error) => {
+                        InvocationEventKind::TransientError {
+                            attempt: transient_error.attempt,
+                            deployment_id: transient_error
+                                .deployment_id
+                                .map(|deployment_id| deployment_id.parse())
+                                .transpose()
+                                .map_err(ConversionError::invalid_data)?,
+                            error_code: transient_error.error_code.into(),
+                            error_message: transient_error.error_message.into(),
+                            related_entry_index: transient_error.related_entry_index,
+                            related_entry_name: transient_error
+                                .related_entry_name
+                                .map(ByteString::from),
+                        }
+                    }
+                    invocation_event::Kind::Suspended(_) => InvocationEventKind::Suspended,
+                    invocation_event::Kind::Resumed(_) => InvocationEventKind::Resumed,
+                    invocation_event::Kind::Completed(_) => InvocationEventKind::Completed,
+                    invocation_event::Kind::Failed(failed) => InvocationEventKind::Failed {
+                        error_code: failed.error_code.into(),
+                        error_message: failed.error_message.into(),
+                    },
+                };
+
+                Ok(Self::new(MillisSinceEpoch::new(value.timestamp), kind))
+            }
+        }
+
+        impl From<restate_storage_api::invocation_event_table::InvocationEvent> for InvocationEvent {
+            fn from(value: restate_storage_api::invocation_event_table::InvocationEvent) -> Self {
+                use restate_storage_api::invocation_event_table::InvocationEventKind;
+
+                // SAFETY: We're only storing the timestamp, we don't use it in the PP business logic.
+                let timestamp = unsafe { value.timestamp() }.as_u64();
+                let kind = match value.kind {
+                    InvocationEventKind::Created => invocation_event::Kind::Created(()),
+                    InvocationEventKind::Scheduled { execution_time } => {
+                        invocation_event::Kind::Scheduled(invocation_event::Scheduled {
+                            execution_time: execution_time.as_u64(),
+                        })
+                    }
+                    InvocationEventKind::Inboxed => invocation_event::Kind::Inboxed(()),
+                    InvocationEventKind::Started => invocation_event::Kind::Started(()),
+                    InvocationEventKind::PinnedDeployment { deployment_id } => {
+                        invocation_event::Kind::PinnedDeployment(
+                            invocation_event::PinnedDeployment {
+                                deployment_id: deployment_id.to_string(),
+                            },
+                        )
+                    }
+                    InvocationEventKind::TransientError {
+                        attempt,
+                        deployment_id,
+                        error_code,
+                        error_message,
+                        related_entry_index,
+                        related_entry_name,
+                    } => invocation_event::Kind::TransientError(invocation_event::TransientError {
+                        attempt,
+                        deployment_id: deployment_id.map(|deployment_id| deployment_id.to_string()),
+                        error_code: error_code.into(),
+                        error_message: error_message.to_string(),
+                        related_entry_index,
+                        related_entry_name: related_entry_name.map(|name| name.to_string()),
+                    }),
+                    InvocationEventKind::Suspended => invocation_event::Kind::Suspended(()),
+                    InvocationEventKind::Resumed => invocation_event::Kind::Resumed(()),
+                    InvocationEventKind::Completed => invocation_event::Kind::Completed(()),
+                    InvocationEventKind::Failed {
+                        error_code,
+                        error_message,
+                    } => invocation_event::Kind::Failed(invocation_event::Failed {
+                        error_code: error_code.into(),
+                        error_message: error_message.to_string(),
+                    }),
+                };
+
+                InvocationEvent {
+                    timestamp,
+                    kind: Some(kind),
+                }
+            }
+        }
     }
 }
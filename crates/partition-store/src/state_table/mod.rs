@@ -154,8 +154,11 @@ impl ReadOnlyStateTable for PartitionStore {
         stream::iter(get_all_user_states_for_service(self, service_id))
     }
 
-    fn get_all_user_states(&self) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
-        get_all_user_states(self, self.partition_key_range().clone())
+    fn get_all_user_states(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
+        get_all_user_states(self, range)
     }
 }
 
@@ -177,8 +180,11 @@ impl<'a> ReadOnlyStateTable for PartitionStoreTransaction<'a> {
         stream::iter(get_all_user_states_for_service(self, service_id))
     }
 
-    fn get_all_user_states(&self) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
-        get_all_user_states(self, self.partition_key_range().clone())
+    fn get_all_user_states(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
+        get_all_user_states(self, range)
     }
 }
 
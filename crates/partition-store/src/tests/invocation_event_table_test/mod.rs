@@ -0,0 +1,131 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::storage_test_environment;
+
+use restate_storage_api::invocation_event_table::{
+    InvocationEvent, InvocationEventKind, InvocationEventTable, ReadOnlyInvocationEventTable,
+};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{DeploymentId, InvocationId, InvocationUuid};
+use restate_types::time::MillisSinceEpoch;
+
+const INVOCATION_ID_1: InvocationId =
+    InvocationId::from_parts(10, InvocationUuid::from_u128(12345678900001));
+const INVOCATION_ID_2: InvocationId =
+    InvocationId::from_parts(11, InvocationUuid::from_u128(12345678900021));
+
+fn event(timestamp: u64, kind: InvocationEventKind) -> InvocationEvent {
+    InvocationEvent::new(MillisSinceEpoch::new(timestamp), kind)
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_invocation_event_table() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let deployment_id = DeploymentId::new();
+    let events = vec![
+        event(1, InvocationEventKind::Created),
+        event(2, InvocationEventKind::Started),
+        event(3, InvocationEventKind::PinnedDeployment { deployment_id }),
+        event(
+            4,
+            InvocationEventKind::TransientError {
+                attempt: 1,
+                deployment_id: Some(deployment_id),
+                error_code: 500u16.into(),
+                error_message: "boom".into(),
+                related_entry_index: Some(1),
+                related_entry_name: None,
+            },
+        ),
+        event(5, InvocationEventKind::Suspended),
+    ];
+
+    // Fill in some data, the last event of the same transaction must see the previous ones
+    let mut txn = rocksdb.transaction();
+    for e in &events {
+        txn.append_invocation_event(&INVOCATION_ID_1, e, 4)
+            .await
+            .unwrap();
+    }
+    txn.append_invocation_event(&INVOCATION_ID_2, &event(6, InvocationEventKind::Created), 4)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    // Only the last 4 events are retained
+    assert_eq!(
+        rocksdb
+            .get_invocation_events(&INVOCATION_ID_1)
+            .await
+            .unwrap(),
+        events[1..].to_vec()
+    );
+
+    // Appending in a new transaction continues the sequence
+    let mut txn = rocksdb.transaction();
+    txn.append_invocation_event(&INVOCATION_ID_1, &event(7, InvocationEventKind::Resumed), 4)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut expected = events[2..].to_vec();
+    expected.push(event(7, InvocationEventKind::Resumed));
+    assert_eq!(
+        rocksdb
+            .get_invocation_events(&INVOCATION_ID_1)
+            .await
+            .unwrap(),
+        expected
+    );
+
+    // Lowering the retention trims all the events exceeding it
+    let mut txn = rocksdb.transaction();
+    txn.append_invocation_event(
+        &INVOCATION_ID_1,
+        &event(8, InvocationEventKind::Completed),
+        2,
+    )
+    .await
+    .unwrap();
+    txn.commit().await.unwrap();
+
+    assert_eq!(
+        rocksdb
+            .get_invocation_events(&INVOCATION_ID_1)
+            .await
+            .unwrap(),
+        vec![
+            event(7, InvocationEventKind::Resumed),
+            event(8, InvocationEventKind::Completed)
+        ]
+    );
+
+    // Delete
+    let mut txn = rocksdb.transaction();
+    txn.delete_invocation_events(&INVOCATION_ID_1)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    assert!(rocksdb
+        .get_invocation_events(&INVOCATION_ID_1)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        rocksdb
+            .get_invocation_events(&INVOCATION_ID_2)
+            .await
+            .unwrap(),
+        vec![event(6, InvocationEventKind::Created)]
+    );
+}
@@ -116,6 +116,18 @@ async fn point_lookups<T: JournalTable>(txn: &mut T) {
     assert!(result.is_none());
 }
 
+async fn truncate_journal<T: JournalTable>(txn: &mut T) {
+    txn.truncate_journal(&MOCK_INVOCATION_ID_1, 3, 5).await;
+
+    let mut journal = pin!(txn.get_journal(&MOCK_INVOCATION_ID_1, 5));
+    let mut count = 0;
+    while (journal.next().await).is_some() {
+        count += 1;
+    }
+
+    assert_eq!(count, 3);
+}
+
 async fn delete_journal<T: JournalTable>(txn: &mut T) {
     txn.delete_journal(&MOCK_INVOCATION_ID_1, 5).await;
 }
@@ -141,6 +153,7 @@ async fn journal_tests() {
     get_entire_journal(&mut txn).await;
     get_subset_of_a_journal(&mut txn).await;
     point_lookups(&mut txn).await;
+    truncate_journal(&mut txn).await;
     delete_journal(&mut txn).await;
 
     txn.commit().await.expect("should not fail");
@@ -161,6 +161,29 @@ async fn get_subset_of_a_journal<T: JournalTable>(txn: &mut T) {
     assert_eq!(count, 2);
 }
 
+async fn get_range_of_a_journal<T: JournalTable>(txn: &mut T) {
+    let journal: Vec<_> = txn
+        .get_journal_range(MOCK_INVOCATION_ID_1, 3, 7)
+        .map(|result| result.unwrap().0)
+        .collect()
+        .await;
+    assert_eq!(journal, vec![3, 4, 5, 6]);
+
+    let journal: Vec<_> = txn
+        .get_journal_range(MOCK_INVOCATION_ID_1, 8, 100)
+        .map(|result| result.unwrap().0)
+        .collect()
+        .await;
+    assert_eq!(journal, vec![8, 9]);
+
+    assert_eq!(
+        txn.get_journal_range(MOCK_INVOCATION_ID_1, 5, 5)
+            .count()
+            .await,
+        0
+    );
+}
+
 async fn sleep_point_lookups<T: JournalTable>(txn: &mut T) {
     let result = txn
         .get_journal_entry(MOCK_INVOCATION_ID_1, 2)
@@ -221,6 +244,7 @@ async fn test_sleep_journal() {
     populate_sleep_journal(&mut txn).await;
     get_entire_sleep_journal(&mut txn).await;
     get_subset_of_a_journal(&mut txn).await;
+    get_range_of_a_journal(&mut txn).await;
     check_sleep_completion_index(&mut txn).await;
     check_sleep_notification_index(&mut txn).await;
 
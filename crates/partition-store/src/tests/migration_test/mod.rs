@@ -0,0 +1,197 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::RangeInclusive;
+
+use bytes::Bytes;
+use tempfile::tempdir;
+
+use super::mock_random_service_invocation;
+use crate::migration::MigrationError;
+use crate::{OpenMode, PartitionStoreManager};
+use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
+use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
+use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
+use restate_storage_api::Transaction;
+use restate_types::config::WorkerOptions;
+use restate_types::identifiers::{PartitionId, PartitionKey, ServiceId, SnapshotId};
+use restate_types::live::Live;
+use restate_types::logs::Lsn;
+
+const SPLIT_KEY: PartitionKey = PartitionKey::MAX / 2;
+
+pub(crate) async fn run_tests(manager: PartitionStoreManager) {
+    let worker_options = Live::from_value(WorkerOptions::default());
+    let rocksdb_opts = &worker_options.pinned().storage.rocksdb;
+
+    let low = ServiceId::with_partition_key(10, "Counter", "low");
+    let high = ServiceId::with_partition_key(SPLIT_KEY + 10, "Counter", "high");
+
+    // a single source partition spanning all keys
+    let source_partition = PartitionId::from(10);
+    let mut source = manager
+        .open_partition_store(
+            source_partition,
+            RangeInclusive::new(0, PartitionKey::MAX),
+            OpenMode::CreateIfMissing,
+            rocksdb_opts,
+        )
+        .await
+        .unwrap();
+    let mut txn = source.transaction();
+    txn.put_user_state(&low, b"count", b"1").await;
+    txn.put_user_state(&high, b"count", b"2").await;
+    txn.put_applied_lsn(Lsn::new(100)).await;
+    txn.commit().await.unwrap();
+
+    let snapshots_dir = tempdir().unwrap();
+    let snapshot = manager
+        .export_partition_snapshot(source_partition, SnapshotId::new(), snapshots_dir.path())
+        .await
+        .unwrap();
+
+    // split in two target partitions
+    let targets = [
+        (PartitionId::from(11), RangeInclusive::new(0, SPLIT_KEY)),
+        (
+            PartitionId::from(12),
+            RangeInclusive::new(SPLIT_KEY + 1, PartitionKey::MAX),
+        ),
+    ];
+    let mut target_stores = manager
+        .import_repartitioned_snapshots(vec![(source_partition, snapshot)], &targets, rocksdb_opts)
+        .await
+        .unwrap();
+
+    let (low_store, high_store) = target_stores.split_at_mut(1);
+    let (low_store, high_store) = (&mut low_store[0], &mut high_store[0]);
+    assert_eq!(
+        low_store.get_user_state(&low, b"count").await.unwrap(),
+        Some(Bytes::from_static(b"1"))
+    );
+    assert_eq!(
+        low_store.get_user_state(&high, b"count").await.unwrap(),
+        None
+    );
+    assert_eq!(
+        high_store.get_user_state(&high, b"count").await.unwrap(),
+        Some(Bytes::from_static(b"2"))
+    );
+    assert_eq!(
+        high_store.get_user_state(&low, b"count").await.unwrap(),
+        None
+    );
+
+    // the partition state of the source is not migrated
+    assert_eq!(low_store.get_applied_lsn().await.unwrap(), None);
+
+    // importing again into the same targets is refused
+    let snapshot = manager
+        .export_partition_snapshot(source_partition, SnapshotId::new(), snapshots_dir.path())
+        .await
+        .unwrap();
+    assert!(manager
+        .import_repartitioned_snapshots(vec![(source_partition, snapshot)], &targets, rocksdb_opts)
+        .await
+        .is_err());
+
+    import_fails_on_missing_keys(&manager).await;
+    failed_import_drops_the_targets(&manager).await;
+}
+
+async fn import_fails_on_missing_keys(manager: &PartitionStoreManager) {
+    let worker_options = Live::from_value(WorkerOptions::default());
+    let rocksdb_opts = &worker_options.pinned().storage.rocksdb;
+
+    // the source partition owning the upper half of the keys was not exported
+    let source_partition = PartitionId::from(20);
+    manager
+        .open_partition_store(
+            source_partition,
+            RangeInclusive::new(0, SPLIT_KEY),
+            OpenMode::CreateIfMissing,
+            rocksdb_opts,
+        )
+        .await
+        .unwrap();
+    let snapshots_dir = tempdir().unwrap();
+    let snapshot = manager
+        .export_partition_snapshot(source_partition, SnapshotId::new(), snapshots_dir.path())
+        .await
+        .unwrap();
+
+    let target_partition = PartitionId::from(21);
+    let result = manager
+        .import_repartitioned_snapshots(
+            vec![(source_partition, snapshot)],
+            &[(target_partition, RangeInclusive::new(0, PartitionKey::MAX))],
+            rocksdb_opts,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(MigrationError::MissingKeys(range))
+            if range == RangeInclusive::new(SPLIT_KEY + 1, PartitionKey::MAX)
+    ));
+    assert!(!manager.has_partition_store(target_partition).await);
+}
+
+async fn failed_import_drops_the_targets(manager: &PartitionStoreManager) {
+    let worker_options = Live::from_value(WorkerOptions::default());
+    let rocksdb_opts = &worker_options.pinned().storage.rocksdb;
+
+    // a source partition which was not drained cannot be migrated
+    let source_partition = PartitionId::from(30);
+    let mut source = manager
+        .open_partition_store(
+            source_partition,
+            RangeInclusive::new(0, PartitionKey::MAX),
+            OpenMode::CreateIfMissing,
+            rocksdb_opts,
+        )
+        .await
+        .unwrap();
+    let mut txn = source.transaction();
+    txn.put_user_state(
+        &ServiceId::with_partition_key(10, "Counter", "low"),
+        b"count",
+        b"1",
+    )
+    .await;
+    txn.put_outbox_message(
+        0,
+        &OutboxMessage::ServiceInvocation(mock_random_service_invocation()),
+    )
+    .await;
+    txn.commit().await.unwrap();
+
+    let snapshots_dir = tempdir().unwrap();
+    let snapshot = manager
+        .export_partition_snapshot(source_partition, SnapshotId::new(), snapshots_dir.path())
+        .await
+        .unwrap();
+
+    let targets = [(
+        PartitionId::from(31),
+        RangeInclusive::new(0, PartitionKey::MAX),
+    )];
+    let result = manager
+        .import_repartitioned_snapshots(vec![(source_partition, snapshot)], &targets, rocksdb_opts)
+        .await;
+    assert!(matches!(
+        result,
+        Err(MigrationError::OutboxNotEmpty(partition_id)) if partition_id == source_partition
+    ));
+    assert!(!manager.has_partition_store(PartitionId::from(31)).await);
+    assert!(manager
+        .get_partition_store(PartitionId::from(31))
+        .await
+        .is_none());
+}
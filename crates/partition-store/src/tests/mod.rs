@@ -29,9 +29,11 @@ use restate_types::state_mut::ExternalStateMutation;
 
 mod idempotency_table_test;
 mod inbox_table_test;
+mod invocation_event_table_test;
 mod invocation_status_table_test;
 mod journal_table_test;
 mod journal_table_v2_test;
+mod migration_test;
 mod outbox_table_test;
 mod promise_table_test;
 mod snapshots_test;
@@ -83,6 +85,7 @@ async fn test_read_write() {
     virtual_object_status_table_test::run_tests(store.clone()).await;
     timer_table_test::run_tests(store.clone()).await;
     snapshots_test::run_tests(manager.clone(), store.clone()).await;
+    migration_test::run_tests(manager.clone()).await;
 }
 
 pub(crate) fn mock_service_invocation(service_id: ServiceId) -> ServiceInvocation {
@@ -28,6 +28,10 @@ pub mod proto {
 
 const CUSTOM_MESSAGE_MASK: u16 = 0xFC00;
 
+/// Custom message carrying a chunk of output streamed by the handler, as UTF-8 text. Chunks are
+/// journaled as [`restate_types::journal_v2::EventType::OutputChunk`] events.
+pub const OUTPUT_CHUNK_MESSAGE_TYPE: u16 = 0xFC01;
+
 type MessageTypeId = u16;
 
 #[derive(Debug, thiserror::Error)]
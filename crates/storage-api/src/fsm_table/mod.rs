@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use crate::Result;
+use restate_types::invocation::JournalLimits;
 use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
 use std::future::Future;
@@ -19,6 +20,9 @@ pub trait ReadOnlyFsmTable {
     fn get_outbox_seq_number(&mut self) -> impl Future<Output = Result<MessageIndex>> + Send + '_;
 
     fn get_applied_lsn(&mut self) -> impl Future<Output = Result<Option<Lsn>>> + Send + '_;
+
+    /// Journal limits last announced by a leader, see [`JournalLimits`].
+    fn get_journal_limits(&mut self) -> impl Future<Output = Result<JournalLimits>> + Send + '_;
 }
 
 pub trait FsmTable: ReadOnlyFsmTable {
@@ -31,4 +35,9 @@ pub trait FsmTable: ReadOnlyFsmTable {
         &mut self,
         seq_number: MessageIndex,
     ) -> impl Future<Output = ()> + Send;
+
+    fn put_journal_limits(
+        &mut self,
+        journal_limits: JournalLimits,
+    ) -> impl Future<Output = ()> + Send;
 }
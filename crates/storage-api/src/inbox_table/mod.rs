@@ -8,7 +8,6 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::promise_table::ReadOnlyPromiseTable;
 use crate::Result;
 use futures_util::Stream;
 use restate_types::identifiers::{InvocationId, PartitionKey, ServiceId, WithPartitionKey};
@@ -95,7 +94,7 @@ pub trait ReadOnlyInboxTable {
     ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send;
 }
 
-pub trait InboxTable: ReadOnlyPromiseTable {
+pub trait InboxTable: ReadOnlyInboxTable {
     fn put_inbox_entry(
         &mut self,
         sequence_number: MessageIndex,
@@ -0,0 +1,121 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::Result;
+use bytestring::ByteString;
+use futures_util::Stream;
+use restate_types::errors::InvocationErrorCode;
+use restate_types::identifiers::{DeploymentId, EntryIndex, InvocationId, PartitionKey};
+use restate_types::time::MillisSinceEpoch;
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+/// Event in the lifecycle of an invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationEvent {
+    timestamp: MillisSinceEpoch,
+    pub kind: InvocationEventKind,
+}
+
+impl InvocationEvent {
+    pub fn new(timestamp: MillisSinceEpoch, kind: InvocationEventKind) -> Self {
+        Self { timestamp, kind }
+    }
+
+    /// Create a new event using the system time.
+    ///
+    /// # Safety
+    /// The value of this time is not consistent across replicas of a partition, because it's not agreed.
+    /// You **MUST NOT** use it within the Partition processor business logic, but only for observability purposes.
+    pub fn now(kind: InvocationEventKind) -> Self {
+        Self::new(MillisSinceEpoch::now(), kind)
+    }
+
+    /// Time at which the event was recorded.
+    ///
+    /// # Safety
+    /// The value of this time is not consistent across replicas of a partition, because it's not agreed.
+    /// You **MUST NOT** use it within the Partition processor business logic, but only for observability purposes.
+    pub unsafe fn timestamp(&self) -> MillisSinceEpoch {
+        self.timestamp
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationEventKind {
+    /// The invocation was accepted by the partition processor.
+    Created,
+    /// The invocation was scheduled for a later execution.
+    Scheduled {
+        execution_time: MillisSinceEpoch,
+    },
+    /// The invocation was enqueued in the inbox of its virtual object.
+    Inboxed,
+    /// The invocation was sent to the invoker for the first time.
+    Started,
+    /// The invocation was pinned to the given deployment.
+    PinnedDeployment {
+        deployment_id: DeploymentId,
+    },
+    /// An attempt to execute the invocation failed with a retryable error.
+    TransientError {
+        attempt: u32,
+        deployment_id: Option<DeploymentId>,
+        error_code: InvocationErrorCode,
+        error_message: ByteString,
+        related_entry_index: Option<EntryIndex>,
+        related_entry_name: Option<ByteString>,
+    },
+    Suspended,
+    Resumed,
+    /// The invocation completed, either successfully or with a terminal error returned by the handler.
+    Completed,
+    /// The invocation failed without completing, e.g. because it was killed or retries were exhausted.
+    Failed {
+        error_code: InvocationErrorCode,
+        error_message: ByteString,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedInvocationEventRow {
+    pub invocation_id: InvocationId,
+    pub event_index: u32,
+    pub event: InvocationEvent,
+}
+
+pub trait ReadOnlyInvocationEventTable {
+    /// Returns the retained events of the given invocation, oldest first.
+    fn get_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> impl Future<Output = Result<Vec<InvocationEvent>>> + Send;
+
+    fn all_invocation_events(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<OwnedInvocationEventRow>> + Send;
+}
+
+pub trait InvocationEventTable: ReadOnlyInvocationEventTable {
+    /// Appends the event to the event log of the given invocation. If the log grows beyond
+    /// `retained_events`, the oldest events are removed.
+    fn append_invocation_event(
+        &mut self,
+        invocation_id: &InvocationId,
+        event: &InvocationEvent,
+        retained_events: usize,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn delete_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
@@ -364,6 +364,11 @@ pub enum InvocationStatusDiscriminants {
 #[derive(Debug, Clone, PartialEq)]
 pub struct JournalMetadata {
     pub length: EntryIndex,
+    /// Total size in bytes of the serialized journal entries. Unknown for the invocations started
+    /// before the journal sizes were tracked.
+    pub size: Option<u64>,
+    /// Number of output chunks streamed by the handler, stored as events in the journal.
+    pub output_chunks: u32,
     pub span_context: ServiceInvocationSpanContext,
 }
 
@@ -372,6 +377,8 @@ impl JournalMetadata {
         Self {
             span_context,
             length,
+            size: Some(0),
+            output_chunks: 0,
         }
     }
 
@@ -532,6 +539,9 @@ pub struct CompletedInvocation {
     pub timestamps: StatusTimestamps,
     pub response_result: ResponseResult,
     pub completion_retention_duration: Duration,
+    /// Length of the journal retained with the completed status, to replay the output chunks
+    /// streamed by the invocation on attach. Zero if the journal was dropped on completion.
+    pub journal_length: EntryIndex,
 }
 
 impl CompletedInvocation {
@@ -552,6 +562,7 @@ impl CompletedInvocation {
             response_result,
             completion_retention_duration: in_flight_invocation_metadata
                 .completion_retention_duration,
+            journal_length: 0,
         }
     }
 
@@ -650,6 +661,7 @@ mod test_util {
                 timestamps,
                 response_result: ResponseResult::Success(Bytes::from_static(b"123")),
                 completion_retention_duration: Duration::from_secs(60 * 60),
+                journal_length: 0,
             }
         }
 
@@ -667,6 +679,7 @@ mod test_util {
                 timestamps: StatusTimestamps::now(),
                 response_result: ResponseResult::Success(Bytes::from_static(b"123")),
                 completion_retention_duration: Duration::from_secs(60 * 60),
+                journal_length: 0,
             }
         }
     }
@@ -37,6 +37,15 @@ impl JournalEntry {
             JournalEntry::Completion(_) => false,
         }
     }
+
+    /// Size in bytes of the serialized entry. Completions are not counted, as they are merged
+    /// into the entry they complete.
+    pub fn serialized_size(&self) -> u64 {
+        match self {
+            JournalEntry::Entry(entry) => entry.serialized_entry().len() as u64,
+            JournalEntry::Completion(_) => 0,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -77,4 +86,12 @@ pub trait JournalTable: ReadOnlyJournalTable {
         invocation_id: &InvocationId,
         journal_length: EntryIndex,
     ) -> impl Future<Output = ()> + Send;
+
+    /// Deletes the journal entries in the range `from_index..journal_length`.
+    fn truncate_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        from_index: EntryIndex,
+        journal_length: EntryIndex,
+    ) -> impl Future<Output = ()> + Send;
 }
@@ -30,6 +30,14 @@ pub trait ReadOnlyJournalTable {
         length: EntryIndex,
     ) -> impl Stream<Item = Result<(EntryIndex, RawEntry)>> + Send;
 
+    /// Reads the journal entries in the index range `from..length`.
+    fn get_journal_range(
+        &mut self,
+        invocation_id: InvocationId,
+        from: EntryIndex,
+        length: EntryIndex,
+    ) -> impl Stream<Item = Result<(EntryIndex, RawEntry)>> + Send;
+
     fn all_journals(
         &self,
         range: RangeInclusive<PartitionKey>,
@@ -62,4 +70,13 @@ pub trait JournalTable: ReadOnlyJournalTable {
         invocation_id: InvocationId,
         length: EntryIndex,
     ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Deletes the journal entries in the range `from_index..length`, together with the
+    /// notification and completion id indexes pointing to them.
+    fn truncate_journal(
+        &mut self,
+        invocation_id: InvocationId,
+        from_index: EntryIndex,
+        length: EntryIndex,
+    ) -> impl Future<Output = Result<()>> + Send;
 }
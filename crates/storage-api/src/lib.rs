@@ -29,6 +29,7 @@ pub mod deduplication_table;
 pub mod fsm_table;
 pub mod idempotency_table;
 pub mod inbox_table;
+pub mod invocation_event_table;
 pub mod invocation_status_table;
 pub mod journal_table;
 pub mod journal_table_v2;
@@ -59,6 +60,7 @@ pub trait Transaction:
     + timer_table::TimerTable
     + idempotency_table::IdempotencyTable
     + promise_table::PromiseTable
+    + invocation_event_table::InvocationEventTable
     + Send
 {
     fn commit(self) -> impl Future<Output = Result<()>> + Send;
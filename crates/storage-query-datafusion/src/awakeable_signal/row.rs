@@ -0,0 +1,51 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashSet;
+
+use crate::awakeable_signal::schema::SysAwakeableSignalBuilder;
+use crate::table_util::format_using;
+use restate_types::identifiers::{ExternalSignalIdentifier, InvocationId, WithPartitionKey};
+use restate_types::journal_v2::NotificationId;
+
+/// Signal indexes below this one are reserved for the built-in signals, see
+/// [`restate_types::journal_v2::BuiltInSignal`]. The SDKs allocate the awakeables from here on.
+const FIRST_AWAKEABLE_SIGNAL_INDEX: u32 = 17;
+
+/// Appends a row for each awakeable the suspended invocation is waiting on.
+#[inline]
+pub(crate) fn append_awakeable_signal_rows(
+    builder: &mut SysAwakeableSignalBuilder,
+    output: &mut String,
+    invocation_id: InvocationId,
+    waiting_for_notifications: HashSet<NotificationId>,
+) {
+    for notification_id in waiting_for_notifications {
+        let NotificationId::SignalIndex(signal_index) = notification_id else {
+            continue;
+        };
+        if signal_index < FIRST_AWAKEABLE_SIGNAL_INDEX {
+            continue;
+        }
+
+        let mut row = builder.row();
+        row.partition_key(invocation_id.partition_key());
+        if row.is_id_defined() {
+            row.id(format_using(
+                output,
+                &ExternalSignalIdentifier::new(invocation_id, signal_index),
+            ));
+        }
+        if row.is_invocation_id_defined() {
+            row.invocation_id(format_using(output, &invocation_id));
+        }
+        row.signal_index(signal_index);
+    }
+}
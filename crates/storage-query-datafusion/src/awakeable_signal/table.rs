@@ -0,0 +1,96 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use futures::{Stream, StreamExt};
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::invocation_status_table::{
+    InvocationStatus, ReadOnlyInvocationStatusTable,
+};
+use restate_types::identifiers::{InvocationId, PartitionKey};
+use restate_types::journal_v2::NotificationId;
+
+use crate::awakeable_signal::row::append_awakeable_signal_rows;
+use crate::awakeable_signal::schema::SysAwakeableSignalBuilder;
+use crate::context::{QueryContext, SelectPartitions};
+use crate::partition_filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_awakeable_signal";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    local_partition_store_manager: Option<PartitionStoreManager>,
+) -> datafusion::common::Result<()> {
+    let local_scanner = local_partition_store_manager.map(|partition_store_manager| {
+        Arc::new(LocalPartitionsScanner::new(
+            partition_store_manager,
+            AwakeableSignalScanner,
+        )) as Arc<dyn ScanPartition>
+    });
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysAwakeableSignalBuilder::schema(),
+        ctx.create_distributed_scanner(NAME, local_scanner),
+        FirstMatchingPartitionKeyExtractor::default().with_invocation_id("invocation_id"),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+/// Journal v2 awakeables are signals, which are journaled only once they are completed. The
+/// pending ones are known only by the invocations suspended waiting on them.
+#[derive(Debug, Clone)]
+struct AwakeableSignalScanner;
+
+impl ScanLocalPartition for AwakeableSignalScanner {
+    type Builder = SysAwakeableSignalBuilder;
+    type Item = (InvocationId, HashSet<NotificationId>);
+
+    fn scan_partition_store(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = restate_storage_api::Result<Self::Item>> + Send {
+        partition_store
+            .all_invocation_statuses(range)
+            .filter_map(|result| {
+                std::future::ready(match result {
+                    Ok((
+                        invocation_id,
+                        InvocationStatus::Suspended {
+                            waiting_for_notifications,
+                            ..
+                        },
+                    )) => Some(Ok((invocation_id, waiting_for_notifications))),
+                    Ok(_) => None,
+                    Err(err) => Some(Err(err)),
+                })
+            })
+    }
+
+    fn append_row(
+        row_builder: &mut Self::Builder,
+        string_buffer: &mut String,
+        (invocation_id, waiting_for_notifications): Self::Item,
+    ) {
+        append_awakeable_signal_rows(
+            row_builder,
+            string_buffer,
+            invocation_id,
+            waiting_for_notifications,
+        )
+    }
+}
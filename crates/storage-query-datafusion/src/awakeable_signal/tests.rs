@@ -0,0 +1,98 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashSet;
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{LargeStringArray, UInt32Array};
+use datafusion::arrow::compute::concat_batches;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::invocation_status_table::{
+    InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable,
+};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{ExternalSignalIdentifier, InvocationId};
+use restate_types::journal_v2::NotificationId;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_pending_awakeables_of_suspended_invocations() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let suspended_invocation_id = InvocationId::mock_random();
+    tx.put_invocation_status(
+        &suspended_invocation_id,
+        &InvocationStatus::Suspended {
+            metadata: InFlightInvocationMetadata::mock(),
+            waiting_for_notifications: HashSet::from([
+                NotificationId::CompletionId(1),
+                // Cancel signal
+                NotificationId::SignalIndex(1),
+                NotificationId::SignalIndex(17),
+                NotificationId::SignalIndex(18),
+            ]),
+        },
+    )
+    .await;
+    tx.put_invocation_status(
+        &InvocationId::mock_random(),
+        &InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+    )
+    .await;
+    tx.commit().await.unwrap();
+
+    // The view is a union, hence the rows can be spread over multiple batches
+    let batches = engine
+        .execute(
+            "SELECT id, invocation_id, journal_index, signal_index FROM sys_awakeable ORDER BY signal_index",
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let records = concat_batches(&batches[0].schema(), &batches).unwrap();
+
+    assert_eq!(records.num_rows(), 2);
+    assert_eq!(
+        records
+            .column_by_name("journal_index")
+            .unwrap()
+            .null_count(),
+        2
+    );
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "id" => LargeStringArray: eq(ExternalSignalIdentifier::new(suspended_invocation_id, 17).to_string()),
+                    "invocation_id" => LargeStringArray: eq(suspended_invocation_id.to_string()),
+                    "signal_index" => UInt32Array: eq(17),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "id" => LargeStringArray: eq(ExternalSignalIdentifier::new(suspended_invocation_id, 18).to_string()),
+                    "invocation_id" => LargeStringArray: eq(suspended_invocation_id.to_string()),
+                    "signal_index" => UInt32Array: eq(18),
+                }
+            )
+        )
+    );
+}
@@ -10,18 +10,22 @@
 
 use std::cmp::max;
 use std::fmt::Debug;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use codederror::CodedError;
 use datafusion::catalog::TableProvider;
+use datafusion::catalog_common::MemorySchemaProvider;
+use datafusion::datasource::ViewTable;
 use datafusion::error::DataFusionError;
 use datafusion::execution::context::SQLOptions;
-use datafusion::execution::runtime_env::RuntimeEnvBuilder;
+use datafusion::execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder};
 use datafusion::execution::SessionStateBuilder;
+use datafusion::logical_expr::LogicalPlan;
 use datafusion::physical_optimizer::optimizer::PhysicalOptimizer;
-use datafusion::physical_plan::SendableRecordBatchStream;
-use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion::physical_plan::{execute_stream, SendableRecordBatchStream};
+use datafusion::prelude::{DataFrame, SessionConfig, SessionContext};
 use datafusion::sql::TableReference;
 use restate_core::Metadata;
 use restate_invoker_api::StatusHandle;
@@ -35,6 +39,7 @@ use restate_types::schema::deployment::DeploymentResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
 use tracing::warn;
 
+use crate::query_memory_pool::QueryMemoryPool;
 use crate::remote_query_scanner_manager::RemoteScannerManager;
 use crate::table_providers::ScanPartition;
 use crate::{analyzer, physical_optimizer};
@@ -87,6 +92,24 @@ const SYS_INVOCATION_VIEW: &str = "CREATE VIEW sys_invocation as SELECT
         FROM sys_invocation_status ss
         LEFT JOIN sys_invocation_state sis ON ss.id = sis.id";
 
+// Journal v1 awakeables are journal entries, while journal v2 awakeables are signals the
+// suspended invocations are waiting on.
+const SYS_AWAKEABLE_VIEW: &str = "CREATE VIEW sys_awakeable as SELECT
+            awakeable_id AS id,
+            id AS invocation_id,
+            index AS journal_index,
+            name,
+            arrow_cast(NULL, 'UInt32') AS signal_index
+        FROM sys_journal
+        WHERE entry_type = 'Awakeable' AND completed = false
+        UNION ALL SELECT
+            id,
+            invocation_id,
+            arrow_cast(NULL, 'UInt32') AS journal_index,
+            arrow_cast(NULL, 'LargeUtf8') AS name,
+            signal_index
+        FROM sys_awakeable_signal";
+
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum BuildError {
     #[error(transparent)]
@@ -167,6 +190,16 @@ impl QueryContext {
         crate::promise::register_self(
             &ctx,
             partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::invocation_event::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::awakeable_signal::register_self(
+            &ctx,
+            partition_selector,
             local_partition_store_manager,
         )?;
 
@@ -175,6 +208,11 @@ impl QueryContext {
             .sql(SYS_INVOCATION_VIEW)
             .await
             .map(|_| ctx)?;
+        let ctx = ctx
+            .datafusion_context
+            .sql(SYS_AWAKEABLE_VIEW)
+            .await
+            .map(|_| ctx)?;
 
         Ok(ctx)
     }
@@ -317,13 +355,101 @@ impl QueryContext {
         &self,
         sql: &str,
     ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        let plan = self.plan(sql).await?;
+        self.execute_plan(plan).await
+    }
+
+    /// Parses and plans the given statement without executing it. The returned plan might
+    /// contain parameter placeholders (`$1`, `$2`, ...) which need to be replaced before
+    /// calling [`QueryContext::execute_plan`].
+    pub async fn plan(&self, sql: &str) -> datafusion::common::Result<LogicalPlan> {
         let state = self.datafusion_context.state();
         let statement = state.sql_to_statement(sql, "postgres")?;
         let plan = state.statement_to_plan(statement).await?;
         self.sql_options.verify_plan(&plan)?;
+        Ok(plan)
+    }
+
+    /// Registers a view named `schema.name` in the default catalog, creating the schema if it
+    /// doesn't exist yet. The view is defined by the given `SELECT` statement, which is subject to
+    /// the same restrictions as any other query.
+    pub async fn register_view(
+        &self,
+        schema: &str,
+        name: &str,
+        select: &str,
+    ) -> datafusion::common::Result<()> {
+        let catalog_name = self
+            .datafusion_context
+            .state()
+            .config_options()
+            .catalog
+            .default_catalog
+            .clone();
+        let catalog = self
+            .datafusion_context
+            .catalog(&catalog_name)
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!("Default catalog {catalog_name} is missing"))
+            })?;
+        if catalog.schema(schema).is_none() {
+            catalog.register_schema(schema, Arc::new(MemorySchemaProvider::new()))?;
+        }
+
+        let plan = self.plan(select).await?;
+        let view = ViewTable::try_new(plan, Some(select.to_owned()))?;
+        self.datafusion_context
+            .register_table(TableReference::partial(schema, name), Arc::new(view))
+            .map(|_| ())
+    }
+
+    pub async fn execute_plan(
+        &self,
+        plan: LogicalPlan,
+    ) -> datafusion::common::Result<SendableRecordBatchStream> {
         let df = self.datafusion_context.execute_logical_plan(plan).await?;
         df.execute_stream().await
     }
+
+    /// Parses and plans the given statement into a [`DataFrame`], which can be further
+    /// refined (e.g. filtered, sorted or limited) before executing it with
+    /// [`QueryContext::execute_dataframe`].
+    pub async fn dataframe(&self, sql: &str) -> datafusion::common::Result<DataFrame> {
+        let plan = self.plan(sql).await?;
+        self.datafusion_context.execute_logical_plan(plan).await
+    }
+
+    /// Executes the given [`DataFrame`]. If a `memory_limit` is provided, the query fails as soon
+    /// as it tries to reserve more than `memory_limit` bytes, even if the memory pool shared by
+    /// all queries still has capacity left.
+    pub async fn execute_dataframe(
+        &self,
+        df: DataFrame,
+        memory_limit: Option<NonZeroUsize>,
+    ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        let Some(memory_limit) = memory_limit else {
+            return df.execute_stream().await;
+        };
+
+        let task_ctx = df.task_ctx();
+        let physical_plan = df.create_physical_plan().await?;
+
+        let shared_runtime = task_ctx.runtime_env();
+        let runtime = RuntimeEnv {
+            memory_pool: Arc::new(QueryMemoryPool::new(
+                Arc::clone(&shared_runtime.memory_pool),
+                memory_limit.get(),
+            )),
+            disk_manager: Arc::clone(&shared_runtime.disk_manager),
+            cache_manager: Arc::clone(&shared_runtime.cache_manager),
+            object_store_registry: Arc::clone(&shared_runtime.object_store_registry),
+        };
+
+        execute_stream(
+            physical_plan,
+            Arc::new(task_ctx.with_runtime(Arc::new(runtime))),
+        )
+    }
 }
 
 impl AsRef<SessionContext> for QueryContext {
@@ -0,0 +1,82 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::schema::SysInvocationEventBuilder;
+
+use crate::table_util::format_using;
+use restate_storage_api::invocation_event_table::{InvocationEventKind, OwnedInvocationEventRow};
+use restate_types::identifiers::WithPartitionKey;
+
+#[inline]
+pub(crate) fn append_invocation_event_row(
+    builder: &mut SysInvocationEventBuilder,
+    output: &mut String,
+    row_value: OwnedInvocationEventRow,
+) {
+    let mut row = builder.row();
+    row.partition_key(row_value.invocation_id.partition_key());
+    if row.is_id_defined() {
+        row.id(format_using(output, &row_value.invocation_id));
+    }
+    row.index(row_value.event_index);
+    // SAFETY: this timestamp is used only for observability purposes.
+    row.recorded_at(unsafe { row_value.event.timestamp() }.as_u64() as i64);
+
+    match row_value.event.kind {
+        InvocationEventKind::Created => row.event_type("created"),
+        InvocationEventKind::Scheduled { execution_time } => {
+            row.event_type("scheduled");
+            row.execution_time(execution_time.as_u64() as i64);
+        }
+        InvocationEventKind::Inboxed => row.event_type("inboxed"),
+        InvocationEventKind::Started => row.event_type("started"),
+        InvocationEventKind::PinnedDeployment { deployment_id } => {
+            row.event_type("pinned_deployment");
+            if row.is_deployment_id_defined() {
+                row.deployment_id(format_using(output, &deployment_id));
+            }
+        }
+        InvocationEventKind::TransientError {
+            attempt,
+            deployment_id,
+            error_code,
+            error_message,
+            related_entry_index,
+            related_entry_name,
+        } => {
+            row.event_type("transient_error");
+            row.attempt(attempt);
+            if let Some(deployment_id) = deployment_id {
+                if row.is_deployment_id_defined() {
+                    row.deployment_id(format_using(output, &deployment_id));
+                }
+            }
+            row.error_code(u32::from(error_code));
+            row.error_message(&error_message);
+            if let Some(related_entry_index) = related_entry_index {
+                row.related_entry_index(related_entry_index);
+            }
+            if let Some(related_entry_name) = related_entry_name {
+                row.related_entry_name(&related_entry_name);
+            }
+        }
+        InvocationEventKind::Suspended => row.event_type("suspended"),
+        InvocationEventKind::Resumed => row.event_type("resumed"),
+        InvocationEventKind::Completed => row.event_type("completed"),
+        InvocationEventKind::Failed {
+            error_code,
+            error_message,
+        } => {
+            row.event_type("failed");
+            row.error_code(u32::from(error_code));
+            row.error_message(&error_message);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_invocation_event(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier).
+    id: DataType::LargeUtf8,
+
+    /// The index of this event in the event log of the invocation. Only the most recent events of
+    /// each invocation are retained, and the event log is removed together with the invocation.
+    index: DataType::UInt32,
+
+    /// The event type. Either `created` or `scheduled` or `inboxed` or `started` or
+    /// `pinned_deployment` or `transient_error` or `suspended` or `resumed` or `completed` or
+    /// `failed`.
+    event_type: DataType::LargeUtf8,
+
+    /// Timestamp indicating when the event was recorded.
+    recorded_at: TimestampMillisecond,
+
+    /// If `event_type = 'scheduled'`, the time at which the invocation will be executed.
+    execution_time: TimestampMillisecond,
+
+    /// If `event_type = 'transient_error'`, the number of the failed attempt.
+    attempt: DataType::UInt32,
+
+    /// If `event_type = 'pinned_deployment'` or `event_type = 'transient_error'`, the
+    /// [Deployment ID](/operate/versioning#deployments) used by the attempt.
+    deployment_id: DataType::LargeUtf8,
+
+    /// If `event_type = 'transient_error'` or `event_type = 'failed'`, the error code.
+    error_code: DataType::UInt32,
+
+    /// If `event_type = 'transient_error'` or `event_type = 'failed'`, the error message.
+    error_message: DataType::LargeUtf8,
+
+    /// If `event_type = 'transient_error'`, the index of the journal entry that caused the
+    /// error, if any.
+    related_entry_index: DataType::UInt32,
+
+    /// If `event_type = 'transient_error'`, the name of the journal entry that caused the error,
+    /// if any.
+    related_entry_name: DataType::LargeUtf8,
+));
@@ -147,6 +147,12 @@ fn fill_invoked_by(row: &mut SysInvocationStatusRowBuilder, output: &mut String,
         Source::Internal => {
             row.invoked_by("restate");
         }
+        Source::Restart(invocation_id) => {
+            row.invoked_by("restart");
+            if row.is_invoked_by_id_defined() {
+                row.invoked_by_id(format_using(output, &invocation_id));
+            }
+        }
         Source::Subscription(sub_id) => {
             row.invoked_by("subscription");
             row.invoked_by_subscription_id(format_using(output, &sub_id))
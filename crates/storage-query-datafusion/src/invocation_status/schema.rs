@@ -54,9 +54,11 @@ define_table!(sys_invocation_status(
     /// * `ingress` if the invocation was created externally.
     /// * `service` if the invocation was created by another Restate service.
     /// * `subscription` if the invocation was created by a subscription (e.g. Kafka).
+    /// * `restart` if the invocation was created by restarting another invocation.
     invoked_by: DataType::LargeUtf8,
 
-    /// The caller [Invocation ID](/operate/invocation#invocation-identifier) if `invoked_by = 'service'`.
+    /// The caller [Invocation ID](/operate/invocation#invocation-identifier) if `invoked_by = 'service'`,
+    /// or the restarted invocation ID if `invoked_by = 'restart'`.
     invoked_by_id: DataType::LargeUtf8,
 
     /// The subscription id if `invoked_by = 'subscription'`.
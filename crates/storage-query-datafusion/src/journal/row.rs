@@ -11,18 +11,24 @@
 use crate::journal::schema::SysJournalBuilder;
 
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_service_protocol::message::{Encoder as ProtocolMessageEncoder, ProtocolMessage};
 use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
+use restate_service_protocol_v4::message_codec::Encoder as V4ProtocolMessageEncoder;
 use restate_storage_api::journal_table::JournalEntry;
-use restate_types::identifiers::{JournalEntryId, WithInvocationId, WithPartitionKey};
+use restate_types::identifiers::{
+    AwakeableIdentifier, JournalEntryId, WithInvocationId, WithPartitionKey,
+};
 use restate_types::journal::enriched::EnrichedEntryHeader;
 use restate_types::journal::{CompletePromiseEntry, GetPromiseEntry, PeekPromiseEntry};
 
 use crate::log_data_corruption_error;
 use crate::table_util::format_using;
+use restate_types::journal::raw::PlainRawEntry;
 use restate_types::journal::Entry;
 use restate_types::journal_v2;
-use restate_types::journal_v2::raw::RawEntry;
+use restate_types::journal_v2::raw::{RawEntry, RawEntryInner};
 use restate_types::journal_v2::EntryMetadata;
+use restate_types::service_protocol::ServiceProtocolVersion;
 
 #[inline]
 pub(crate) fn append_journal_row(
@@ -66,6 +72,14 @@ pub(crate) fn append_journal_row(
                 row.raw(entry.serialized_entry());
             }
 
+            if row.is_protocol_message_defined() {
+                // Service protocol versions <= 3 share the same message framing
+                row.protocol_message(
+                    ProtocolMessageEncoder::new(ServiceProtocolVersion::V3)
+                        .encode(ProtocolMessage::from(PlainRawEntry::from(entry.clone()))),
+                );
+            }
+
             match &entry.header() {
                 EnrichedEntryHeader::Call {
                     enrichment_result: Some(enrichment_result),
@@ -109,6 +123,17 @@ pub(crate) fn append_journal_row(
                         };
                     }
                 }
+                EnrichedEntryHeader::Awakeable { .. } => {
+                    if row.is_awakeable_id_defined() {
+                        row.awakeable_id(format_using(
+                            output,
+                            &AwakeableIdentifier::new(
+                                journal_entry_id.invocation_id(),
+                                journal_entry_id.journal_index(),
+                            ),
+                        ));
+                    }
+                }
                 EnrichedEntryHeader::Sleep { .. } => {
                     if row.is_sleep_wakeup_at_defined() {
                         match entry.deserialize_entry_ref::<ProtobufRawEntryCodec>() {
@@ -177,4 +202,19 @@ pub(crate) fn append_journal_row_v2(
     }
 
     row.appended_at(raw_entry.header().append_time.as_u64() as i64);
+
+    if row.is_protocol_message_defined() {
+        let encoder = V4ProtocolMessageEncoder::new(ServiceProtocolVersion::V4);
+        match raw_entry.inner {
+            RawEntryInner::Command(cmd) => row.protocol_message(
+                encoder.encode_raw(cmd.command_type().into(), cmd.serialized_content()),
+            ),
+            RawEntryInner::Notification(notif) => row.protocol_message(
+                encoder.encode_raw(notif.ty().into(), notif.serialized_content()),
+            ),
+            RawEntryInner::Event(_) => {
+                // Events are never sent to the deployment
+            }
+        }
+    }
 }
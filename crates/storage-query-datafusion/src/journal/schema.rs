@@ -49,8 +49,12 @@ define_table!(sys_journal(
     /// If this entry is a promise related entry (GetPromise, PeekPromise, CompletePromise), indicates the promise name.
     promise_name: DataType::LargeUtf8,
 
+    /// If this entry is an awakeable, indicates the awakeable ID.
+    awakeable_id: DataType::LargeUtf8,
+
     /// Raw binary representation of the entry. Check the [service protocol](https://github.com/restatedev/service-protocol)
-    /// for more details to decode it.
+    /// for more details to decode it. When the input of the invocation was offloaded to the payload
+    /// store, the input entry contains a reference to the offloaded payload instead.
     raw: DataType::LargeBinary,
 
     /// The journal version.
@@ -61,4 +65,9 @@ define_table!(sys_journal(
 
     /// When the entry was appended to the journal
     appended_at: TimestampMillisecond,
+
+    /// The entry framed as a [service protocol](https://github.com/restatedev/service-protocol) message,
+    /// header included, as it is sent to the service deployment when replaying the journal. This is
+    /// empty for entries that are not sent to the deployment.
+    protocol_message: DataType::LargeBinary,
 ));
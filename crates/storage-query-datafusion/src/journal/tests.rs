@@ -11,21 +11,24 @@
 use crate::mocks::*;
 use crate::row;
 use bytes::Bytes;
-use datafusion::arrow::array::{Int64Array, LargeStringArray, UInt32Array};
+use datafusion::arrow::array::{Int64Array, LargeBinaryArray, LargeStringArray, UInt32Array};
+use datafusion::arrow::compute::concat_batches;
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::StreamExt;
 use googletest::all;
 use googletest::prelude::{assert_that, eq};
 use prost::Message;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_service_protocol::message::{Encoder, ProtocolMessage};
 use restate_storage_api::journal_table::{JournalEntry, JournalTable};
 use restate_storage_api::Transaction;
-use restate_types::identifiers::InvocationId;
+use restate_types::identifiers::{AwakeableIdentifier, InvocationId};
 use restate_types::invocation::InvocationTarget;
 use restate_types::journal::enriched::{
     CallEnrichmentResult, EnrichedEntryHeader, EnrichedRawEntry,
 };
-use restate_types::journal::{Entry, EntryType, InputEntry};
+use restate_types::journal::raw::PlainRawEntry;
+use restate_types::journal::{AwakeableEntry, Entry, EntryResult, EntryType, InputEntry};
 use restate_types::service_protocol;
 
 #[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
@@ -125,6 +128,47 @@ async fn get_entries() {
     );
 }
 
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_entry_protocol_message() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let invocation_id = InvocationId::mock_random();
+    let run_entry = EnrichedRawEntry::new(
+        EnrichedEntryHeader::Run {},
+        service_protocol::RunEntryMessage {
+            name: "my-side-effect".to_string(),
+            result: None,
+        }
+        .encode_to_vec()
+        .into(),
+    );
+    tx.put_journal_entry(&invocation_id, 1, &JournalEntry::Entry(run_entry.clone()))
+        .await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT protocol_message FROM sys_journal")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    let expected_message = Encoder::new(service_protocol::ServiceProtocolVersion::V3)
+        .encode(ProtocolMessage::from(PlainRawEntry::from(run_entry)));
+    assert_that!(
+        records,
+        row!(
+            0,
+            {
+                "protocol_message" => LargeBinaryArray: eq(expected_message.to_vec())
+            }
+        )
+    );
+}
+
 #[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
 async fn select_count_star() {
     let mut engine = MockQueryEngine::create().await;
@@ -194,3 +238,55 @@ async fn select_count_star() {
         ),)
     );
 }
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_pending_awakeables() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let invocation_id = InvocationId::mock_random();
+    tx.put_journal_entry(
+        &invocation_id,
+        1,
+        &JournalEntry::Entry(ProtobufRawEntryCodec::serialize_enriched(Entry::Awakeable(
+            AwakeableEntry { result: None },
+        ))),
+    )
+    .await;
+    tx.put_journal_entry(
+        &invocation_id,
+        2,
+        &JournalEntry::Entry(ProtobufRawEntryCodec::serialize_enriched(Entry::Awakeable(
+            AwakeableEntry {
+                result: Some(EntryResult::Success(Bytes::from_static(b"done"))),
+            },
+        ))),
+    )
+    .await;
+    tx.commit().await.unwrap();
+
+    // The view is a union, hence the rows can be spread over multiple batches
+    let batches = engine
+        .execute("SELECT id, invocation_id, journal_index FROM sys_awakeable")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let records = concat_batches(&batches[0].schema(), &batches).unwrap();
+
+    assert_eq!(records.num_rows(), 1);
+    assert_that!(
+        records,
+        row!(
+            0,
+            {
+                "id" => LargeStringArray: eq(AwakeableIdentifier::new(invocation_id, 1).to_string()),
+                "invocation_id" => LargeStringArray: eq(invocation_id.to_string()),
+                "journal_index" => UInt32Array: eq(1),
+            }
+        )
+    );
+}
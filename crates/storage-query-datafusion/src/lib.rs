@@ -13,9 +13,11 @@ pub mod context;
 
 pub mod remote_query_scanner_server;
 
+mod awakeable_signal;
 mod deployment;
 mod idempotency;
 mod inbox;
+mod invocation_event;
 mod invocation_state;
 mod invocation_status;
 mod journal;
@@ -23,6 +25,7 @@ mod keyed_service_status;
 mod partition_store_scanner;
 mod physical_optimizer;
 mod promise;
+mod query_memory_pool;
 mod service;
 mod state;
 #[cfg(feature = "table_docs")]
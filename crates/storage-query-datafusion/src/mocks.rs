@@ -213,6 +213,10 @@ impl MockQueryEngine {
     ) -> datafusion::common::Result<SendableRecordBatchStream> {
         self.2.execute(sql).await
     }
+
+    pub fn query_context(&self) -> &QueryContext {
+        &self.2
+    }
 }
 
 // --- Matchers for rows
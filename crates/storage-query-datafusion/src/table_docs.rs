@@ -9,7 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use crate::{
-    deployment, idempotency, inbox, invocation_state, invocation_status, journal,
+    deployment, idempotency, inbox, invocation_event, invocation_state, invocation_status, journal,
     keyed_service_status, promise, service, state,
 };
 use std::borrow::Cow;
@@ -24,6 +24,7 @@ pub const ALL_TABLE_DOCS: &[StaticTableDocs] = &[
     inbox::schema::TABLE_DOCS,
     idempotency::schema::TABLE_DOCS,
     promise::schema::TABLE_DOCS,
+    invocation_event::schema::TABLE_DOCS,
     service::schema::TABLE_DOCS,
     deployment::schema::TABLE_DOCS,
 ];
@@ -138,3 +139,46 @@ pub fn sys_invocation_table_docs() -> OwnedTableDocs {
         columns,
     }
 }
+
+/// Journal v2 awakeables are listed only once the invocation waiting on them is suspended.
+pub fn sys_awakeable_table_docs() -> OwnedTableDocs {
+    // We need to compile this manually, due to the fact that it's a view.
+    let column = |name: &str| {
+        *journal::schema::TABLE_DOCS
+            .columns
+            .iter()
+            .find(|column| column.name == name)
+            .unwrap_or_else(|| panic!("{name} should exist"))
+    };
+
+    let columns = vec![
+        TableColumn {
+            name: "id",
+            ..column("awakeable_id")
+        },
+        TableColumn {
+            name: "invocation_id",
+            ..column("id")
+        },
+        TableColumn {
+            name: "journal_index",
+            column_type: "UInt32",
+            description: "The index of the awakeable entry in the journal. Empty for the awakeables of invocations using the journal v2.",
+        },
+        TableColumn {
+            name: "name",
+            column_type: "Utf8",
+            description: "The name of the awakeable entry, if any. Empty for the awakeables of invocations using the journal v2.",
+        },
+        TableColumn {
+            name: "signal_index",
+            column_type: "UInt32",
+            description: "The index of the signal completing the awakeable. Empty for the awakeables of invocations using the journal v1.",
+        },
+    ];
+
+    OwnedTableDocs {
+        name: Cow::Borrowed("sys_awakeable"),
+        columns,
+    }
+}
@@ -10,7 +10,7 @@
 
 use std::time::{Duration, SystemTime};
 
-use datafusion::arrow::array::{LargeStringArray, UInt64Array};
+use datafusion::arrow::array::{Int64Array, LargeStringArray, UInt64Array};
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::StreamExt;
 use googletest::all;
@@ -113,3 +113,36 @@ async fn query_sys_invocation() {
         ))
     );
 }
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn register_view() {
+    let engine = MockQueryEngine::create().await;
+
+    engine
+        .query_context()
+        .register_view("my_schema", "my_view", "SELECT 1 AS one")
+        .await
+        .unwrap();
+
+    let records = engine
+        .execute("SELECT one FROM my_schema.my_view")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(records, all!(row!(0, { "one" => Int64Array: eq(1) })));
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn register_view_rejects_ddl() {
+    let engine = MockQueryEngine::create().await;
+
+    assert!(engine
+        .query_context()
+        .register_view("my_schema", "my_view", "CREATE SCHEMA other_schema")
+        .await
+        .is_err());
+}
@@ -8,32 +8,77 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use pgwire::api::portal::Portal;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::{ParamValues, ScalarValue};
+use datafusion::logical_expr::LogicalPlan;
+use futures::stream::{BoxStream, Peekable};
+use futures::{Sink, SinkExt, StreamExt};
+use pgwire::api::portal::{Format, Portal};
 use pgwire::api::query::ExtendedQueryHandler;
-use pgwire::api::results::{DescribePortalResponse, DescribeStatementResponse, Response};
-use pgwire::api::stmt::{NoopQueryParser, StoredStatement};
-use pgwire::api::ClientInfo;
-use pgwire::error::{PgWireError, PgWireResult};
-use std::sync::Arc;
+use pgwire::api::results::{
+    DescribePortalResponse, DescribeStatementResponse, FieldInfo, QueryResponse, Response, Tag,
+};
+use pgwire::api::stmt::{QueryParser, StoredStatement};
+use pgwire::api::store::PortalStore;
+use pgwire::api::{ClientInfo, ClientPortalStore, Type, DEFAULT_NAME};
+use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
+use pgwire::messages::data::DataRow;
+use pgwire::messages::extendedquery::{
+    Close, CloseComplete, Execute, PortalSuspended, Sync as PgSync, TARGET_TYPE_BYTE_PORTAL,
+    TARGET_TYPE_BYTE_STATEMENT,
+};
+use pgwire::messages::response::ReadyForQuery;
+use pgwire::messages::PgWireBackendMessage;
+use tokio::sync::Mutex;
+
+use restate_storage_query_datafusion::context::QueryContext;
 
-#[derive(Debug, Clone)]
-pub struct NoopExtendedQueryHandler {
-    query_parser: Arc<NoopQueryParser>,
+use crate::pgwire_server::{
+    arrow_to_pg_rows, into_field_info, into_pg_type, DfSessionService, RowStream,
+};
+
+/// Parses statements received through the extended query protocol into DataFusion logical
+/// plans. Parameter placeholders (`$1`, `$2`, ...) are kept in the plan and replaced on `Bind`.
+pub(crate) struct DfQueryParser {
+    query_context: QueryContext,
 }
 
-impl NoopExtendedQueryHandler {
-    pub(crate) fn new() -> Self {
-        NoopExtendedQueryHandler {
-            query_parser: Arc::new(Default::default()),
-        }
+impl DfQueryParser {
+    pub(crate) fn new(query_context: QueryContext) -> Self {
+        Self { query_context }
+    }
+}
+
+#[async_trait]
+impl QueryParser for DfQueryParser {
+    type Statement = LogicalPlan;
+
+    async fn parse_sql<C>(
+        &self,
+        _client: &C,
+        sql: &str,
+        _types: &[Type],
+    ) -> PgWireResult<Self::Statement>
+    where
+        C: ClientInfo + Unpin + Send + Sync,
+    {
+        self.query_context
+            .plan(sql)
+            .await
+            .map_err(|e| PgWireError::ApiError(Box::new(e)))
     }
 }
 
 #[async_trait]
-impl ExtendedQueryHandler for NoopExtendedQueryHandler {
-    type Statement = String;
-    type QueryParser = NoopQueryParser;
+impl ExtendedQueryHandler for DfSessionService {
+    type Statement = LogicalPlan;
+    type QueryParser = DfQueryParser;
 
     fn query_parser(&self) -> Arc<Self::QueryParser> {
         self.query_parser.clone()
@@ -42,40 +87,463 @@ impl ExtendedQueryHandler for NoopExtendedQueryHandler {
     async fn do_describe_statement<C>(
         &self,
         _client: &mut C,
-        _statement: &StoredStatement<Self::Statement>,
+        statement: &StoredStatement<Self::Statement>,
     ) -> PgWireResult<DescribeStatementResponse>
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        Err(PgWireError::ApiError(
-            "Extended Query is not implemented on this server.".into(),
-        ))
+        let inferred_types = ordered_parameter_types(&statement.statement)?;
+        let parameter_types = (0..inferred_types.len())
+            .map(|idx| parameter_pg_type(statement, &inferred_types, idx))
+            .collect();
+        // The format of the result columns is only known once the statement is bound
+        let fields = into_field_info(
+            statement.statement.schema().as_arrow(),
+            Some(&Format::UnifiedText),
+        )?;
+
+        Ok(DescribeStatementResponse::new(parameter_types, fields))
     }
 
     async fn do_describe_portal<C>(
         &self,
         _client: &mut C,
-        _portal: &Portal<Self::Statement>,
+        portal: &Portal<Self::Statement>,
     ) -> PgWireResult<DescribePortalResponse>
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        Err(PgWireError::ApiError(
-            "Extended Query is not implemented on this server.".into(),
-        ))
+        let fields = into_field_info(
+            portal.statement.statement.schema().as_arrow(),
+            Some(&portal.result_column_format),
+        )?;
+
+        Ok(DescribePortalResponse::new(fields))
     }
 
     async fn do_query<'a, 'b: 'a, C>(
         &'b self,
         _client: &mut C,
-        _portal: &'a Portal<Self::Statement>,
+        portal: &'a Portal<Self::Statement>,
         _max_rows: usize,
     ) -> PgWireResult<Response<'a>>
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        Err(PgWireError::ApiError(
-            "Extended Query is not implemented on this server.".into(),
-        ))
+        let (fields, rows) = self.execute_portal(portal).await?;
+        Ok(Response::Query(QueryResponse::new(fields, rows)))
+    }
+
+    /// Overrides the default implementation to support portal suspension: when the client asks
+    /// for at most `max_rows` rows, the remaining rows are kept until the client executes the
+    /// portal again.
+    async fn on_execute<C>(&self, client: &mut C, message: Execute) -> PgWireResult<()>
+    where
+        C: ClientInfo + ClientPortalStore + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::PortalStore: PortalStore<Statement = Self::Statement>,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let portal_name = message.name.as_deref().unwrap_or(DEFAULT_NAME);
+        let Some(portal) = client.portal_store().get_portal(portal_name) else {
+            return Err(PgWireError::PortalNotFound(portal_name.to_owned()));
+        };
+        let max_rows = match usize::try_from(message.max_rows) {
+            Ok(max_rows) if max_rows > 0 => max_rows,
+            _ => usize::MAX,
+        };
+
+        let suspended = self.suspended_portals.take(portal_name, &portal).await;
+        let mut cursor = match suspended {
+            Some(cursor) => cursor,
+            None => {
+                let (_, rows) = self.execute_portal(&portal).await?;
+                PortalCursor::new(&portal, rows)
+            }
+        };
+
+        let mut sent = 0;
+        while sent < max_rows {
+            let Some(row) = cursor.next().await else {
+                break;
+            };
+            client.feed(PgWireBackendMessage::DataRow(row?)).await?;
+            sent += 1;
+        }
+
+        if cursor.is_exhausted().await {
+            let tag = Tag::new("SELECT").with_rows(cursor.fetched);
+            client
+                .feed(PgWireBackendMessage::CommandComplete(tag.into()))
+                .await?;
+        } else {
+            client
+                .feed(PgWireBackendMessage::PortalSuspended(PortalSuspended::new()))
+                .await?;
+            self.suspended_portals
+                .suspend(portal_name.to_owned(), cursor)
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Overrides the default implementation to also drop the rows left in the closed portal.
+    async fn on_close<C>(&self, client: &mut C, message: Close) -> PgWireResult<()>
+    where
+        C: ClientInfo + ClientPortalStore + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::PortalStore: PortalStore<Statement = Self::Statement>,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let name = message.name.as_deref().unwrap_or(DEFAULT_NAME);
+        match message.target_type {
+            TARGET_TYPE_BYTE_STATEMENT => {
+                client.portal_store().rm_statement(name);
+            }
+            TARGET_TYPE_BYTE_PORTAL => {
+                client.portal_store().rm_portal(name);
+                self.suspended_portals.close(name).await;
+            }
+            _ => {}
+        }
+        client
+            .send(PgWireBackendMessage::CloseComplete(CloseComplete::new()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Overrides the default implementation to drop the rows left in the suspended portals.
+    /// Transaction blocks aren't supported, hence `Sync` always ends the implicit transaction,
+    /// which closes the portals as in PostgreSQL.
+    async fn on_sync<C>(&self, client: &mut C, _message: PgSync) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        self.suspended_portals.close_all().await;
+        client
+            .send(PgWireBackendMessage::ReadyForQuery(ReadyForQuery::new(
+                client.transaction_status(),
+            )))
+            .await?;
+        client.flush().await?;
+
+        Ok(())
+    }
+}
+
+impl DfSessionService {
+    async fn execute_portal(
+        &self,
+        portal: &Portal<LogicalPlan>,
+    ) -> PgWireResult<(Arc<Vec<FieldInfo>>, RowStream)> {
+        let inferred_types = ordered_parameter_types(&portal.statement.statement)?;
+        let parameters = deserialize_parameters(portal, &inferred_types)?;
+        let plan = portal
+            .statement
+            .statement
+            .clone()
+            .replace_params_with_values(&parameters)
+            .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+
+        let ctx = self.session_context.lock().await;
+        let df = ctx
+            .execute_plan(plan)
+            .await
+            .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+
+        arrow_to_pg_rows(df, Some(&portal.result_column_format))
+    }
+}
+
+/// The suspended portals of a connection, by portal name. Dropping a cursor releases the query
+/// it reads the rows from.
+pub(crate) struct SuspendedPortals<P = Portal<LogicalPlan>, T = DataRow> {
+    cursors: Mutex<HashMap<String, PortalCursor<P, T>>>,
+}
+
+impl<P, T> Default for SuspendedPortals<P, T> {
+    fn default() -> Self {
+        Self {
+            cursors: Mutex::default(),
+        }
+    }
+}
+
+impl<P, T> SuspendedPortals<P, T> {
+    /// Takes the cursor of the given portal, if it was suspended.
+    async fn take(&self, name: &str, portal: &Arc<P>) -> Option<PortalCursor<P, T>> {
+        self.cursors
+            .lock()
+            .await
+            .remove(name)
+            // the client bound a new portal with the same name in the meantime
+            .filter(|cursor| cursor.is_for(portal))
+    }
+
+    async fn suspend(&self, name: String, cursor: PortalCursor<P, T>) {
+        self.cursors.lock().await.insert(name, cursor);
+    }
+
+    async fn close(&self, name: &str) {
+        self.cursors.lock().await.remove(name);
+    }
+
+    async fn close_all(&self) {
+        self.cursors.lock().await.clear();
+    }
+}
+
+/// The rows of a suspended portal which were not sent to the client yet.
+pub(crate) struct PortalCursor<P = Portal<LogicalPlan>, T = DataRow> {
+    /// The bound portal the rows belong to, kept to recognize when the client binds a new
+    /// portal with the same name.
+    portal: Arc<P>,
+    rows: Pin<Box<Peekable<BoxStream<'static, PgWireResult<T>>>>>,
+    /// Number of rows fetched so far
+    fetched: usize,
+}
+
+impl<P, T> PortalCursor<P, T> {
+    fn new(portal: &Arc<P>, rows: BoxStream<'static, PgWireResult<T>>) -> Self {
+        Self {
+            portal: Arc::clone(portal),
+            rows: Box::pin(rows.peekable()),
+            fetched: 0,
+        }
+    }
+
+    fn is_for(&self, portal: &Arc<P>) -> bool {
+        Arc::ptr_eq(&self.portal, portal)
+    }
+
+    async fn next(&mut self) -> Option<PgWireResult<T>> {
+        let row = self.rows.next().await;
+        self.fetched += usize::from(row.is_some());
+        row
+    }
+
+    async fn is_exhausted(&mut self) -> bool {
+        self.rows.as_mut().peek().await.is_none()
+    }
+}
+
+/// DataFusion returns the parameter types keyed by placeholder id (`$1`, `$2`, ...),
+/// this returns them in positional order.
+fn ordered_parameter_types(plan: &LogicalPlan) -> PgWireResult<Vec<Option<DataType>>> {
+    let mut parameter_types = plan
+        .get_parameter_types()
+        .map_err(|e| PgWireError::ApiError(Box::new(e)))?
+        .into_iter()
+        .map(|(id, data_type)| {
+            id.strip_prefix('$')
+                .and_then(|idx| idx.parse::<usize>().ok())
+                .map(|idx| (idx, data_type))
+                .ok_or_else(|| {
+                    user_error(format!(
+                        "Unsupported parameter placeholder {id}, expected $1, $2, ..."
+                    ))
+                })
+        })
+        .collect::<PgWireResult<Vec<_>>>()?;
+    parameter_types.sort_by_key(|(idx, _)| *idx);
+
+    Ok(parameter_types
+        .into_iter()
+        .map(|(_, data_type)| data_type)
+        .collect())
+}
+
+/// Types specified by the client on `Parse` take precedence over the ones inferred by DataFusion.
+fn parameter_pg_type(
+    statement: &StoredStatement<LogicalPlan>,
+    inferred_types: &[Option<DataType>],
+    idx: usize,
+) -> Type {
+    match statement.parameter_types.get(idx) {
+        Some(pg_type) if *pg_type != Type::UNKNOWN => pg_type.clone(),
+        _ => inferred_types
+            .get(idx)
+            .and_then(Option::as_ref)
+            .and_then(|data_type| into_pg_type(data_type).ok())
+            .unwrap_or(Type::UNKNOWN),
+    }
+}
+
+fn deserialize_parameters(
+    portal: &Portal<LogicalPlan>,
+    inferred_types: &[Option<DataType>],
+) -> PgWireResult<ParamValues> {
+    let mut values = Vec::with_capacity(portal.parameter_len());
+
+    for idx in 0..portal.parameter_len() {
+        let pg_type = parameter_pg_type(&portal.statement, inferred_types, idx);
+        let value = match pg_type {
+            Type::BOOL => ScalarValue::Boolean(portal.parameter::<bool>(idx, &pg_type)?),
+            Type::CHAR => ScalarValue::Int8(portal.parameter::<i8>(idx, &pg_type)?),
+            Type::INT2 => ScalarValue::Int16(portal.parameter::<i16>(idx, &pg_type)?),
+            Type::INT4 => ScalarValue::Int32(portal.parameter::<i32>(idx, &pg_type)?),
+            Type::INT8 => ScalarValue::Int64(portal.parameter::<i64>(idx, &pg_type)?),
+            Type::FLOAT4 => ScalarValue::Float32(portal.parameter::<f32>(idx, &pg_type)?),
+            Type::FLOAT8 => ScalarValue::Float64(portal.parameter::<f64>(idx, &pg_type)?),
+            Type::BYTEA => ScalarValue::Binary(portal.parameter::<Vec<u8>>(idx, &pg_type)?),
+            // Everything else (text, timestamps, unknown) is read in its textual
+            // representation and cast to the type DataFusion expects below.
+            _ => ScalarValue::Utf8(portal.parameter::<String>(idx, &pg_type)?),
+        };
+
+        let value = match inferred_types.get(idx).and_then(Option::as_ref) {
+            Some(data_type) if *data_type != value.data_type() => value
+                .cast_to(data_type)
+                .map_err(|e| PgWireError::ApiError(Box::new(e)))?,
+            _ => value,
+        };
+        values.push(value);
+    }
+
+    Ok(ParamValues::List(values))
+}
+
+fn user_error(message: String) -> PgWireError {
+    PgWireError::UserError(Box::new(ErrorInfo::new(
+        "ERROR".to_owned(),
+        "XX000".to_owned(),
+        message,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::prelude::SessionContext;
+    use futures::stream;
+
+    fn cursor(rows: usize) -> PortalCursor<(), usize> {
+        PortalCursor::new(&Arc::new(()), stream::iter((0..rows).map(Ok)).boxed())
+    }
+
+    async fn fetch(cursor: &mut PortalCursor<(), usize>, max_rows: usize) -> Vec<usize> {
+        let mut rows = Vec::new();
+        while rows.len() < max_rows {
+            match cursor.next().await {
+                Some(row) => rows.push(row.unwrap()),
+                None => break,
+            }
+        }
+        rows
+    }
+
+    #[tokio::test]
+    async fn suspends_after_max_rows() {
+        let mut cursor = cursor(5);
+
+        assert_eq!(fetch(&mut cursor, 2).await, vec![0, 1]);
+        assert!(!cursor.is_exhausted().await);
+        assert_eq!(fetch(&mut cursor, 2).await, vec![2, 3]);
+        assert!(!cursor.is_exhausted().await);
+        assert_eq!(fetch(&mut cursor, 2).await, vec![4]);
+        assert!(cursor.is_exhausted().await);
+        assert_eq!(cursor.fetched, 5);
+    }
+
+    #[tokio::test]
+    async fn exhausted_when_last_row_is_fetched() {
+        let mut cursor = cursor(2);
+
+        assert_eq!(fetch(&mut cursor, 2).await, vec![0, 1]);
+        assert!(cursor.is_exhausted().await);
+        assert_eq!(cursor.fetched, 2);
+    }
+
+    #[test]
+    fn identifies_portal() {
+        let portal = Arc::new(());
+        let other_portal = Arc::new(());
+        let cursor = PortalCursor::<(), usize>::new(&portal, stream::empty().boxed());
+
+        assert!(cursor.is_for(&portal));
+        assert!(!cursor.is_for(&other_portal));
+    }
+
+    #[tokio::test]
+    async fn drops_suspended_portal_on_close() {
+        let portals = SuspendedPortals::<(), usize>::default();
+        let portal = Arc::new(());
+        // Stands for the query the rows are read from
+        let query = Arc::new(());
+        let query_ref = Arc::clone(&query);
+        let rows = stream::iter(0..5)
+            .map(move |row| {
+                let _ = &query_ref;
+                Ok(row)
+            })
+            .boxed();
+        portals
+            .suspend("p1".to_owned(), PortalCursor::new(&portal, rows))
+            .await;
+        assert_eq!(Arc::strong_count(&query), 2);
+
+        portals.close("p1").await;
+
+        assert_eq!(Arc::strong_count(&query), 1);
+        assert!(portals.take("p1", &portal).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn drops_all_suspended_portals_on_sync() {
+        let portals = SuspendedPortals::<(), usize>::default();
+        let portal = Arc::new(());
+        for name in ["p1", "p2"] {
+            let rows = stream::iter((0..5).map(Ok)).boxed();
+            portals
+                .suspend(name.to_owned(), PortalCursor::new(&portal, rows))
+                .await;
+        }
+
+        portals.close_all().await;
+
+        assert!(portals.take("p1", &portal).await.is_none());
+        assert!(portals.take("p2", &portal).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn takes_suspended_portal_only_for_the_same_portal() {
+        let portals = SuspendedPortals::<(), usize>::default();
+        let portal = Arc::new(());
+        portals
+            .suspend(
+                "p1".to_owned(),
+                PortalCursor::new(&portal, stream::empty().boxed()),
+            )
+            .await;
+
+        assert!(portals.take("p1", &Arc::new(())).await.is_none());
+        portals
+            .suspend(
+                "p1".to_owned(),
+                PortalCursor::new(&portal, stream::empty().boxed()),
+            )
+            .await;
+        assert!(portals.take("p1", &portal).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn orders_parameter_types_by_placeholder() {
+        let plan = SessionContext::new()
+            .state()
+            .create_logical_plan(
+                "SELECT column1 FROM (VALUES (1, 'a')) WHERE column2 = $2 AND column1 = $1",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ordered_parameter_types(&plan).unwrap(),
+            vec![Some(DataType::Int64), Some(DataType::Utf8)]
+        );
     }
 }
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 mod extended_query;
+mod pg_catalog;
 mod pgwire_server;
 pub mod service;
 
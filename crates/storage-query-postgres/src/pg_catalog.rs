@@ -0,0 +1,73 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Minimal `pg_catalog` emulation, enough for drivers and BI tools to introspect the
+//! available tables. The `information_schema` is already provided by DataFusion.
+
+use restate_storage_query_datafusion::context::QueryContext;
+
+const PG_CATALOG_SCHEMA: &str = "pg_catalog";
+
+const PG_NAMESPACE_VIEW: &str = "SELECT
+            column1 AS oid,
+            column2 AS nspname
+        FROM (VALUES
+            (11, 'pg_catalog'),
+            (2200, 'public'),
+            (13000, 'information_schema')
+        )";
+
+const PG_DATABASE_VIEW: &str = "SELECT
+            column1 AS oid,
+            column2 AS datname
+        FROM (VALUES (1, 'restate'))";
+
+// Only the types returned by the pgwire server, see pgwire_server::into_pg_type
+const PG_TYPE_VIEW: &str = "SELECT
+            column1 AS oid,
+            column2 AS typname,
+            11 AS typnamespace
+        FROM (VALUES
+            (16, 'bool'),
+            (17, 'bytea'),
+            (18, 'char'),
+            (20, 'int8'),
+            (21, 'int2'),
+            (23, 'int4'),
+            (25, 'text'),
+            (700, 'float4'),
+            (701, 'float8'),
+            (705, 'unknown'),
+            (1043, 'varchar'),
+            (1083, 'time'),
+            (1114, 'timestamp')
+        )";
+
+const PG_CLASS_VIEW: &str = "SELECT
+            table_name AS relname,
+            CASE
+                WHEN table_schema = 'pg_catalog' THEN 11
+                WHEN table_schema = 'information_schema' THEN 13000
+                ELSE 2200
+            END AS relnamespace,
+            CASE WHEN table_type = 'VIEW' THEN 'v' ELSE 'r' END AS relkind
+        FROM information_schema.tables";
+
+pub(crate) async fn register(ctx: &QueryContext) -> datafusion::common::Result<()> {
+    for (name, select) in [
+        ("pg_namespace", PG_NAMESPACE_VIEW),
+        ("pg_database", PG_DATABASE_VIEW),
+        ("pg_type", PG_TYPE_VIEW),
+        ("pg_class", PG_CLASS_VIEW),
+    ] {
+        ctx.register_view(PG_CATALOG_SCHEMA, name, select).await?;
+    }
+    Ok(())
+}
@@ -26,15 +26,17 @@ use datafusion::arrow::datatypes::Int64Type;
 use datafusion::arrow::datatypes::Int8Type;
 use datafusion::arrow::datatypes::UInt32Type;
 use datafusion::arrow::datatypes::UInt64Type;
-use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::arrow::datatypes::{DataType, Schema, TimeUnit};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::stream::BoxStream;
 use futures::{stream, StreamExt};
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 
 use pgwire::api::auth::noop::NoopStartupHandler;
 use pgwire::api::copy::NoopCopyHandler;
+use pgwire::api::portal::Format;
 use pgwire::api::query::SimpleQueryHandler;
 use pgwire::api::results::{DataRowEncoder, FieldFormat, FieldInfo, QueryResponse, Response};
 use pgwire::api::{ClientInfo, NoopErrorHandler, PgWireServerHandlers, Type};
@@ -42,13 +44,12 @@ use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
 use pgwire::messages::data::DataRow;
 use pgwire::tokio::process_socket;
 
-use crate::extended_query::NoopExtendedQueryHandler;
+use crate::extended_query::{DfQueryParser, SuspendedPortals};
 use restate_core::{TaskCenter, TaskKind};
 use restate_storage_query_datafusion::context::QueryContext;
 
 pub(crate) struct HandlerFactory {
     processor: Arc<DfSessionService>,
-    placeholder: Arc<NoopExtendedQueryHandler>,
     authenticator: Arc<NoAuthHandler>,
     copy_handler: Arc<NoopCopyHandler>,
 }
@@ -56,7 +57,7 @@ pub(crate) struct HandlerFactory {
 impl PgWireServerHandlers for HandlerFactory {
     type StartupHandler = NoAuthHandler;
     type SimpleQueryHandler = DfSessionService;
-    type ExtendedQueryHandler = NoopExtendedQueryHandler;
+    type ExtendedQueryHandler = DfSessionService;
     type CopyHandler = NoopCopyHandler;
     type ErrorHandler = NoopErrorHandler;
 
@@ -65,7 +66,7 @@ impl PgWireServerHandlers for HandlerFactory {
     }
 
     fn extended_query_handler(&self) -> Arc<Self::ExtendedQueryHandler> {
-        self.placeholder.clone()
+        self.processor.clone()
     }
 
     fn startup_handler(&self) -> Arc<Self::StartupHandler> {
@@ -88,14 +89,11 @@ impl NoopStartupHandler for NoAuthHandler {}
 impl HandlerFactory {
     pub fn new(ctx: QueryContext) -> Self {
         let processor = Arc::new(DfSessionService::new(ctx));
-        // We have not implemented extended query in this server, use placeholder instead
-        let placeholder = Arc::new(NoopExtendedQueryHandler::new());
         let authenticator = Arc::new(NoAuthHandler {});
         let copy_handler = Arc::new(NoopCopyHandler);
 
         Self {
             processor,
-            placeholder,
             authenticator,
             copy_handler,
         }
@@ -119,13 +117,18 @@ pub fn spawn_connection(
 }
 
 pub struct DfSessionService {
-    session_context: Mutex<QueryContext>,
+    pub(crate) session_context: Mutex<QueryContext>,
+    pub(crate) query_parser: Arc<DfQueryParser>,
+    /// Portals of the connection which were executed with a row limit and have rows left
+    pub(crate) suspended_portals: SuspendedPortals,
 }
 
 impl DfSessionService {
     pub fn new(ctx: QueryContext) -> DfSessionService {
         DfSessionService {
+            query_parser: Arc::new(DfQueryParser::new(ctx.clone())),
             session_context: Mutex::new(ctx),
+            suspended_portals: SuspendedPortals::default(),
         }
     }
 }
@@ -146,12 +149,12 @@ impl SimpleQueryHandler for DfSessionService {
             .await
             .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
 
-        let resp = arrow_to_pg_encoder(df).await?;
+        let resp = arrow_to_pg_encoder(df, None).await?;
         Ok(vec![Response::Query(resp)])
     }
 }
 
-fn into_pg_type(df_type: &DataType) -> PgWireResult<Type> {
+pub(crate) fn into_pg_type(df_type: &DataType) -> PgWireResult<Type> {
     Ok(match df_type {
         DataType::Null => Type::UNKNOWN,
         DataType::Boolean => Type::BOOL,
@@ -181,26 +184,47 @@ fn into_pg_type(df_type: &DataType) -> PgWireResult<Type> {
     })
 }
 
-async fn arrow_to_pg_encoder<'a>(
+/// Describes the columns of the given schema. When `format` is `None`, the columns are
+/// described for the simple query protocol, otherwise the format requested by the client
+/// through the extended query protocol is used.
+pub(crate) fn into_field_info(
+    schema: &Schema,
+    format: Option<&Format>,
+) -> PgWireResult<Vec<FieldInfo>> {
+    schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(idx, f)| {
+            let pg_type = into_pg_type(f.data_type())?;
+            let format = match format {
+                Some(format) => format.format_for(idx),
+                None if matches!(f.data_type(), DataType::Binary) => FieldFormat::Binary,
+                None => FieldFormat::Text,
+            };
+
+            Ok(FieldInfo::new(f.name().into(), None, None, pg_type, format))
+        })
+        .collect()
+}
+
+pub(crate) type RowStream = BoxStream<'static, PgWireResult<DataRow>>;
+
+pub(crate) async fn arrow_to_pg_encoder<'a>(
     recordbatch_stream: SendableRecordBatchStream,
+    format: Option<&Format>,
 ) -> PgWireResult<QueryResponse<'a>> {
+    let (fields, pg_row_stream) = arrow_to_pg_rows(recordbatch_stream, format)?;
+    Ok(QueryResponse::new(fields, pg_row_stream))
+}
+
+/// Describes the columns of the given record batches and encodes them as rows.
+pub(crate) fn arrow_to_pg_rows(
+    recordbatch_stream: SendableRecordBatchStream,
+    format: Option<&Format>,
+) -> PgWireResult<(Arc<Vec<FieldInfo>>, RowStream)> {
     let schema = recordbatch_stream.schema();
-    let fields = Arc::new(
-        schema
-            .fields()
-            .iter()
-            .map(|f| {
-                let pg_type = into_pg_type(f.data_type())?;
-                let format = if matches!(f.data_type(), DataType::Binary) {
-                    FieldFormat::Binary
-                } else {
-                    FieldFormat::Text
-                };
-
-                Ok(FieldInfo::new(f.name().into(), None, None, pg_type, format))
-            })
-            .collect::<PgWireResult<Vec<FieldInfo>>>()?,
-    );
+    let fields = Arc::new(into_field_info(&schema, format)?);
 
     let fields_ref = fields.clone();
     let pg_row_stream = recordbatch_stream
@@ -221,9 +245,10 @@ async fn arrow_to_pg_encoder<'a>(
 
             stream::iter(results)
         })
-        .flatten();
+        .flatten()
+        .boxed();
 
-    Ok(QueryResponse::new(fields, pg_row_stream))
+    Ok((fields, pg_row_stream))
 }
 
 fn encode_row(
@@ -75,6 +75,12 @@ pub struct AdminOptions {
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub default_partition_replication: PartitionReplication,
 
+    /// # Schema history size
+    ///
+    /// Number of schema changes retained in the history. Older changes are folded into the
+    /// baseline the history starts from, so they can't be diffed or rolled back to anymore.
+    pub schema_history_max_changes: NonZeroUsize,
+
     #[cfg(any(test, feature = "test-util"))]
     pub disable_cluster_controller: bool,
 }
@@ -113,6 +119,7 @@ impl Default for AdminOptions {
             log_trim_interval: Some(Duration::from_secs(60 * 60).into()),
             log_trim_threshold: 1000,
             default_partition_replication: PartitionReplication::default(),
+            schema_history_max_changes: NonZeroUsize::new(256).expect("is non zero"),
             #[cfg(any(test, feature = "test-util"))]
             disable_cluster_controller: false,
             log_tail_update_interval: Duration::from_secs(5 * 60).into(),
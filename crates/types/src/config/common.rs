@@ -20,7 +20,7 @@ use serde_with::serde_as;
 
 use restate_serde_util::{NonZeroByteCount, SerdeableHeaderHashMap};
 
-use super::{AwsOptions, HttpOptions, PerfStatsLevel, RocksDbOptions};
+use super::{AwsOptions, HttpOptions, PayloadStoreOptions, PerfStatsLevel, RocksDbOptions};
 use crate::locality::NodeLocation;
 use crate::net::{AdvertisedAddress, BindAddress};
 use crate::nodes_config::Role;
@@ -179,6 +179,47 @@ pub struct CommonOptions {
     /// Disable prometheus metric recording and reporting. Default is `false`.
     pub disable_prometheus: bool,
 
+    /// # Metrics Endpoint
+    ///
+    /// Specify the endpoint to push metrics to. Metrics will be exported using
+    /// [OTLP gRPC](https://opentelemetry.io/docs/specs/otlp/#otlpgrpc), in addition to the
+    /// Prometheus endpoint.
+    ///
+    /// If unset, metrics are not pushed.
+    pub metrics_endpoint: Option<String>,
+
+    /// # Metrics export interval
+    ///
+    /// The interval at which metrics are pushed to the [`Self::metrics_endpoint`].
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub metrics_export_interval: humantime::Duration,
+
+    /// # Additional metrics headers
+    ///
+    /// Specify additional headers you want the system to send to the metrics endpoint (e.g.
+    /// authentication headers).
+    pub metrics_headers: SerdeableHeaderHashMap,
+
+    /// # Service metric labels
+    ///
+    /// Label the invocation metrics of the invoker and the partition processors by service and
+    /// handler. Every service and handler creates new time series, see
+    /// [`Self::metrics_service_labels_limit`]. Default is `false`.
+    pub metrics_service_labels: bool,
+
+    /// # Service metric labels limit
+    ///
+    /// Maximum number of distinct services and handlers getting their own label values when
+    /// [`Self::metrics_service_labels`] is enabled. Services and handlers beyond the limit are
+    /// labelled as `_other`.
+    pub metrics_service_labels_limit: NonZeroUsize,
+
+    #[serde(flatten)]
+    pub health: HealthOptions,
+
     /// Storage high priority thread pool
     ///
     /// This configures the restate-managed storage thread pool for performing
@@ -275,6 +316,11 @@ pub struct CommonOptions {
     /// Restate uses Scarf to collect anonymous usage data to help us understand how the software is being used.
     /// You can set this flag to true to disable this collection. It can also be set with the environment variable DO_NOT_TRACK=true.
     pub disable_telemetry: bool,
+
+    /// # Payload store
+    ///
+    /// Offloading of large invocation payloads to an object store.
+    pub payload_store: PayloadStoreOptions,
 }
 
 impl CommonOptions {
@@ -397,6 +443,12 @@ impl Default for CommonOptions {
             bootstrap_num_partitions: NonZeroU16::new(24).expect("is not zero"),
             histogram_inactivity_timeout: None,
             disable_prometheus: false,
+            metrics_endpoint: None,
+            metrics_export_interval: Duration::from_secs(30).into(),
+            metrics_headers: SerdeableHeaderHashMap::default(),
+            metrics_service_labels: false,
+            metrics_service_labels_limit: NonZeroUsize::new(1000).expect("is not zero"),
+            health: HealthOptions::default(),
             service_client: Default::default(),
             shutdown_timeout: Duration::from_secs(60).into(),
             tracing: TracingOptions::default(),
@@ -424,6 +476,7 @@ impl Default for CommonOptions {
             ),
             initialization_timeout: Duration::from_secs(5 * 60).into(),
             disable_telemetry: false,
+            payload_store: PayloadStoreOptions::default(),
         }
     }
 }
@@ -731,6 +784,48 @@ impl Default for TracingOptions {
     }
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(
+        title = "Health",
+        description = "Criteria of the readiness reported by the node's `/health/ready` endpoint"
+    )
+)]
+pub struct HealthOptions {
+    /// # Readiness roles
+    ///
+    /// The roles which must be serving for the node to be reported as ready. If unset, all the
+    /// roles of the node are considered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub readiness_roles: Option<EnumSet<Role>>,
+
+    /// # Require partition leaders
+    ///
+    /// Report the worker role as ready only once all the partition processors which are meant to
+    /// lead their partition on this node are active leaders. Default is `true`.
+    pub readiness_require_partition_leaders: bool,
+
+    /// # Require known partition leaders
+    ///
+    /// Report the ingress role as ready only once the leader of every partition is known, so that
+    /// requests can be routed to it. Whether the leaders are reachable is not checked. Default is
+    /// `true`.
+    pub readiness_require_known_partition_leaders: bool,
+}
+
+impl Default for HealthOptions {
+    fn default() -> Self {
+        Self {
+            readiness_roles: None,
+            readiness_require_partition_leaders: true,
+            readiness_require_known_partition_leaders: true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::nodes_config::Role;
@@ -10,13 +10,18 @@
 
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use tokio::sync::Semaphore;
 
+use crate::net::partition_processor::ReplicaReadOptions;
+
 use super::KafkaClusterOptions;
 
 /// # Ingress options
+#[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "schemars", schemars(rename = "IngressOptions"))]
@@ -37,6 +42,58 @@ pub struct IngressOptions {
 
     kafka_clusters: Vec<KafkaClusterOptions>,
 
+    /// # Output read mode
+    ///
+    /// Where to read invocation outputs from, when serving `output` and `attach` requests.
+    pub output_read_mode: OutputReadMode,
+
+    /// # Replica read max staleness
+    ///
+    /// When reading outputs from replicas, maximum age of the read index a replica can reuse
+    /// instead of looking up the log tail again. Trades freshness of the outputs for fewer log
+    /// tail lookups, which is useful with clients polling outputs. With zero, reads are
+    /// linearizable.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    replica_read_max_staleness: humantime::Duration,
+
+    /// # Replica read timeout
+    ///
+    /// When reading outputs from replicas, maximum time a replica waits to apply the log up to
+    /// the read index. Reads which time out fall back to the partition leader.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    replica_read_timeout: humantime::Duration,
+
+    /// # Output stream retention
+    ///
+    /// How long to retain a streaming call after it completed, when the invocation isn't already
+    /// retained for longer, e.g. because of its idempotency key. Chunks are read until the
+    /// completion, so this must cover the time the ingress needs to read the last chunks.
+    ///
+    /// The whole completed invocation is retained, as for idempotent calls: during this time the
+    /// streaming call, even without an idempotency key, can be attached to and its output read
+    /// by invocation id, and it is listed in the invocation status table.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    output_stream_retention: humantime::Duration,
+
+    /// # Output stream poll timeout
+    ///
+    /// When streaming the output of an invocation, maximum time the partition processor waits
+    /// for new output chunks before replying to the ingress, which then polls again.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    output_stream_poll_timeout: humantime::Duration,
+
     /// # Experimental feature to run the ingress independent of the worker role
     ///
     /// This feature is experimental and should be used with caution. It allows to run the ingress
@@ -83,6 +140,39 @@ impl IngressOptions {
     pub fn experimental_feature_kafka_ingress_next(&self) -> bool {
         self.experimental_feature_kafka_ingress_next
     }
+
+    pub fn replica_read_options(&self) -> ReplicaReadOptions {
+        ReplicaReadOptions {
+            max_staleness: self.replica_read_max_staleness.into(),
+            timeout: self.replica_read_timeout.into(),
+        }
+    }
+
+    pub fn output_stream_retention(&self) -> Duration {
+        self.output_stream_retention.into()
+    }
+
+    pub fn output_stream_poll_timeout(&self) -> Duration {
+        self.output_stream_poll_timeout.into()
+    }
+}
+
+/// # Output read mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputReadMode {
+    /// # Leader
+    ///
+    /// Read outputs from the partition leader. Reads fail while the leadership changes.
+    #[default]
+    Leader,
+    /// # Replica
+    ///
+    /// Read outputs from any replica of the partition, preferring the local node, once it
+    /// applied the log up to a read index obtained from the log tail. Reads which can't be
+    /// served by the replica fall back to the leader.
+    Replica,
 }
 
 impl Default for IngressOptions {
@@ -92,6 +182,11 @@ impl Default for IngressOptions {
             // max is limited by Tower's LoadShedLayer.
             concurrent_api_requests_limit: None,
             kafka_clusters: Default::default(),
+            output_read_mode: OutputReadMode::default(),
+            replica_read_max_staleness: Duration::ZERO.into(),
+            replica_read_timeout: Duration::from_secs(2).into(),
+            output_stream_retention: Duration::from_secs(60).into(),
+            output_stream_poll_timeout: Duration::from_secs(10).into(),
             experimental_feature_enable_separate_ingress_role: false,
             experimental_feature_kafka_ingress_next: false,
         }
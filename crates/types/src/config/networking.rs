@@ -12,6 +12,7 @@ use std::num::NonZeroUsize;
 use std::time::Duration;
 
 use crate::retries::RetryPolicy;
+use crate::PlainNodeId;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -63,6 +64,13 @@ pub struct NetworkingOptions {
     /// The number of messages that can be queued on the outbound stream of a single
     /// connection.
     pub outbound_queue_length: NonZeroUsize,
+
+    /// # Fault injection
+    ///
+    /// Faults to inject in the node-to-node networking. Intended for testing only, changes are
+    /// applied without restarting the node. Faults are only applied by binaries built with the
+    /// `fault-injection` feature of `restate-server`, which is off by default.
+    pub fault_injection: NetworkFaultInjection,
 }
 
 impl Default for NetworkingOptions {
@@ -80,6 +88,46 @@ impl Default for NetworkingOptions {
             http2_keep_alive_interval: Duration::from_secs(5).into(),
             http2_keep_alive_timeout: Duration::from_secs(5).into(),
             http2_adaptive_window: true,
+            fault_injection: NetworkFaultInjection::default(),
         }
     }
 }
+
+/// # Network fault injection
+///
+/// Faults applied to the messages this node receives from its peers.
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "NetworkFaultInjection", default)
+)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct NetworkFaultInjection {
+    /// # Partitioned from
+    ///
+    /// Nodes this node cannot communicate with. Connections to and from these nodes are refused
+    /// and their messages are dropped.
+    pub partitioned_from: Vec<PlainNodeId>,
+
+    /// # Message delay
+    ///
+    /// Latency added to every message received from other nodes.
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub message_delay: Option<humantime::Duration>,
+
+    /// # Message drop rate
+    ///
+    /// Probability, between 0 and 1, of dropping a message received from other nodes.
+    pub message_drop_rate: f64,
+}
+
+impl NetworkFaultInjection {
+    pub fn is_empty(&self) -> bool {
+        self.partitioned_from.is_empty()
+            && self.message_delay.is_none()
+            && self.message_drop_rate <= 0.0
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroUsize;
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use restate_serde_util::NonZeroByteCount;
+
+/// # Payload store options
+///
+/// Configures the offloading of large invocation payloads to an object store.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "PayloadStoreOptions", default)
+)]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct PayloadStoreOptions {
+    /// # Destination
+    ///
+    /// Object store where large payloads are offloaded to. This property supports URLs with either
+    /// `s3://` or `file://` protocol scheme. Payloads are stored under the URL path, prefixed by
+    /// the id of the invocation they belong to.
+    ///
+    /// Example: `s3://payloads-bucket/restate/cluster`
+    ///
+    /// The destination must be the same on all the nodes of the cluster, as every node must be
+    /// able to resolve the payloads offloaded by the others.
+    ///
+    /// Default: `None` - payloads are always stored inline
+    pub destination: Option<String>,
+
+    /// # Offload threshold
+    ///
+    /// Payloads larger than this threshold are offloaded to the destination object store. Only a
+    /// reference to the offloaded payload is then stored in the log and in the partition store.
+    /// Only the inputs of the invocations received by the HTTP ingress are offloaded, outputs, state,
+    /// and the inputs of service-to-service calls and Kafka subscriptions are always stored inline.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub offload_threshold: NonZeroUsize,
+}
+
+impl Default for PayloadStoreOptions {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            // 1 MiB
+            offload_threshold: NonZeroUsize::new(1024 * 1024).expect("Non zero number"),
+        }
+    }
+}
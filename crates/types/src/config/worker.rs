@@ -8,7 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::num::{NonZeroU16, NonZeroU64, NonZeroUsize};
+use std::num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -18,6 +18,7 @@ use tracing::warn;
 
 use super::{CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
 use crate::identifiers::PartitionId;
+use crate::invocation::JournalLimits;
 use crate::retries::RetryPolicy;
 use restate_serde_util::NonZeroByteCount;
 
@@ -54,6 +55,39 @@ pub struct WorkerOptions {
     #[cfg_attr(feature = "schemars", schemars(skip))]
     experimental_feature_invocation_status_killed: bool,
 
+    /// # Maximum journal entries
+    ///
+    /// Maximum number of journal entries a single invocation can record. Once exceeded, the
+    /// invocation is failed with error code 572. If unset, the number of entries is unbounded.
+    ///
+    /// The limit configured on the leader of a partition applies to all its replicas, and changes
+    /// take effect once a leader with the new value is elected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_journal_entries: Option<NonZeroU32>,
+
+    /// # Maximum journal size
+    ///
+    /// Maximum total size of the entries a single invocation can record in its journal. Once
+    /// exceeded, the invocation is failed with error code 572. If unset, the journal size is
+    /// unbounded. Invocations started before upgrading to a version tracking the journal sizes
+    /// are not subject to this limit.
+    ///
+    /// The limit configured on the leader of a partition applies to all its replicas, and changes
+    /// take effect once a leader with the new value is elected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    max_journal_size: Option<NonZeroUsize>,
+
+    /// # Retained invocation events
+    ///
+    /// Number of lifecycle events retained for each invocation, and exposed through the
+    /// `sys_invocation_event` table. Once exceeded, the oldest events are removed.
+    ///
+    /// The events are recorded by the partition processors, hence this must be set to the same
+    /// value on all the nodes of the cluster.
+    invocation_events_retention: NonZeroUsize,
+
     pub storage: StorageOptions,
 
     pub invoker: InvokerOptions,
@@ -95,6 +129,17 @@ impl WorkerOptions {
     pub fn experimental_feature_invocation_status_killed(&self) -> bool {
         self.experimental_feature_invocation_status_killed
     }
+
+    pub fn journal_limits(&self) -> JournalLimits {
+        JournalLimits {
+            max_entries: self.max_journal_entries.map(Into::into),
+            max_size: self.max_journal_size.map(|size| usize::from(size) as u64),
+        }
+    }
+
+    pub fn invocation_events_retention(&self) -> usize {
+        self.invocation_events_retention.into()
+    }
 }
 
 impl Default for WorkerOptions {
@@ -105,6 +150,9 @@ impl Default for WorkerOptions {
             cleanup_interval: Duration::from_secs(60 * 60).into(),
             experimental_feature_disable_idempotency_table: false,
             experimental_feature_invocation_status_killed: false,
+            max_journal_entries: None,
+            max_journal_size: None,
+            invocation_events_retention: NonZeroUsize::new(100).expect("Non zero number"),
             storage: StorageOptions::default(),
             invoker: Default::default(),
             max_command_batch_size: NonZeroUsize::new(4).expect("Non zero number"),
@@ -235,6 +283,9 @@ impl Default for InvokerOptions {
     }
 }
 
+/// Smallest value size moved to blob files, see [`StorageOptions::payload_compression_threshold`].
+const MIN_PAYLOAD_COMPRESSION_THRESHOLD: usize = 4 * 1024;
+
 /// # Storage options
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
@@ -270,6 +321,21 @@ pub struct StorageOptions {
     /// partitions. The divisor is defined in `num-partitions-to-share-memory-budget`
     rocksdb_memory_ratio: f32,
 
+    /// # Payload compression threshold
+    ///
+    /// Values larger than this threshold, typically large journal entry payloads, are moved out
+    /// of the partition store's LSM tree into blob files compressed with zstd. This reduces write
+    /// amplification and disk usage for invocations with large journals. If unset, all values are
+    /// stored inline and compressed according to the regular per-level compression settings.
+    ///
+    /// Blob files are meant for large values only, hence thresholds below 4 KiB are raised to
+    /// 4 KiB, so that small values like state entries, timers and journal metadata always stay
+    /// in the LSM tree.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    payload_compression_threshold: Option<NonZeroUsize>,
+
     /// # Persist lsn interval
     ///
     /// Controls the interval at which worker tries to persist the last applied lsn. Lsn persisting
@@ -332,6 +398,11 @@ impl StorageOptions {
             .get()
     }
 
+    pub fn payload_compression_threshold(&self) -> Option<usize> {
+        self.payload_compression_threshold
+            .map(|threshold| usize::from(threshold).max(MIN_PAYLOAD_COMPRESSION_THRESHOLD))
+    }
+
     pub fn data_dir(&self) -> PathBuf {
         super::data_dir("db")
     }
@@ -354,6 +425,7 @@ impl Default for StorageOptions {
             // set by apply_common in runtime
             rocksdb_memory_budget: None,
             rocksdb_memory_ratio: 0.49,
+            payload_compression_threshold: None,
             // persist the lsn every hour
             persist_lsn_interval: Some(Duration::from_secs(60 * 60).into()),
             persist_lsn_threshold: 1000,
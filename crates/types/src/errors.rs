@@ -150,6 +150,7 @@ pub mod codes {
     pub const PROTOCOL_VIOLATION: InvocationErrorCode = InvocationErrorCode(571);
     pub const CONFLICT: InvocationErrorCode = InvocationErrorCode(409);
     pub const NOT_READY: InvocationErrorCode = InvocationErrorCode(470);
+    pub const JOURNAL_TOO_LARGE: InvocationErrorCode = InvocationErrorCode(572);
 }
 
 /// This struct represents errors arisen when processing a service invocation.
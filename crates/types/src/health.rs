@@ -75,6 +75,14 @@ impl Health {
         *self.metadata_store_status.borrow()
     }
 
+    pub fn current_ingress_status(&self) -> IngressStatus {
+        *self.ingress_status.borrow()
+    }
+
+    pub fn current_node_rpc_status(&self) -> NodeRpcStatus {
+        *self.node_rpc_status.borrow()
+    }
+
     pub fn node_status(&self) -> HealthStatus<NodeStatus> {
         HealthStatus(self.node_status.clone())
     }
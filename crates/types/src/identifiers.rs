@@ -443,6 +443,15 @@ impl InvocationId {
         )
     }
 
+    /// Generates a new random invocation id, sharing the partition key with this one.
+    /// Used to restart an invocation as a new invocation, see [`crate::invocation::RestartMode`].
+    pub fn generate_restart(&self) -> Self {
+        InvocationId::from_parts(
+            self.partition_key,
+            InvocationUuid::from_u128(Ulid::new().into()),
+        )
+    }
+
     pub const fn from_parts(partition_key: PartitionKey, invocation_uuid: InvocationUuid) -> Self {
         Self {
             partition_key,
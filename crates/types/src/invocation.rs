@@ -12,8 +12,8 @@
 
 use crate::errors::InvocationError;
 use crate::identifiers::{
-    EntryIndex, IdempotencyId, InvocationId, PartitionKey, PartitionProcessorRpcRequestId,
-    ServiceId, SubscriptionId, WithInvocationId, WithPartitionKey,
+    DeploymentId, EntryIndex, IdempotencyId, InvocationId, PartitionKey,
+    PartitionProcessorRpcRequestId, ServiceId, SubscriptionId, WithInvocationId, WithPartitionKey,
 };
 use crate::time::MillisSinceEpoch;
 use bytes::Bytes;
@@ -22,7 +22,7 @@ use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceState};
 use serde_with::{serde_as, FromInto};
 use std::fmt;
 use std::hash::Hash;
-use std::ops::Deref;
+use std::ops::{Deref, RangeInclusive};
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -533,6 +533,8 @@ pub enum Source {
     Service(InvocationId, InvocationTarget),
     /// Internal calls for the non-deterministic built-in services
     Internal,
+    /// Restart of the given invocation, see [`RestartMode::AsNew`]
+    Restart(InvocationId),
 }
 
 impl Source {
@@ -809,6 +811,64 @@ pub struct PurgeInvocationRequest {
     pub invocation_id: InvocationId,
 }
 
+/// Message to purge the stored data of a service from a key range of a partition: the state,
+/// the inbox state mutations, the promises, and the completed invocations. In-flight
+/// invocations and the data they still use are left untouched.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PurgeServiceRequest {
+    pub service_name: ByteString,
+    /// Keys to purge, within the partition this request is addressed to.
+    pub partition_key_range: RangeInclusive<PartitionKey>,
+}
+
+/// Message to re-pin an in-flight invocation to another deployment, supporting the same service
+/// protocol version the invocation was pinned with.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MigrateInvocationRequest {
+    pub invocation_id: InvocationId,
+    /// The invocation is migrated only if it's still pinned to this deployment.
+    pub from_deployment: DeploymentId,
+    pub to_deployment: DeploymentId,
+}
+
+/// Limits enforced on the journal of every invocation. The leader of a partition announces its
+/// configured limits, and the replicas apply them from the log, so that all of them fail the same
+/// invocations.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JournalLimits {
+    /// Maximum number of entries in a journal.
+    pub max_entries: Option<u32>,
+    /// Maximum total size in bytes of the entries in a journal.
+    pub max_size: Option<u64>,
+}
+
+/// Message to restart an invocation.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RestartInvocationRequest {
+    pub invocation_id: InvocationId,
+    pub mode: RestartMode,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum RestartMode {
+    /// Start a new invocation with the same target, input and headers of the restarted one.
+    /// The new invocation must be in the same partition of the restarted invocation.
+    AsNew { new_invocation_id: InvocationId },
+    /// Truncate the journal of the invocation, removing every entry starting from `from_index`,
+    /// and invoke it again. The input entry cannot be removed, hence `from_index` must be `>= 1`.
+    /// Notifications which don't complete a removed command are kept, and appended again after
+    /// the truncated journal.
+    InPlace { from_index: EntryIndex },
+}
+
+/// Message to complete a workflow promise from outside of the workflow, e.g. through the admin API.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CompletePromiseRequest {
+    pub service_id: ServiceId,
+    pub promise_key: ByteString,
+    pub result: ResponseResult,
+}
+
 // A hack to allow spancontext to be serialized.
 // Details in https://github.com/open-telemetry/opentelemetry-rust/issues/576#issuecomment-1253396100
 #[derive(serde::Serialize, serde::Deserialize)]
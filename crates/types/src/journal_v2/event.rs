@@ -19,6 +19,8 @@ use strum::EnumString;
 )]
 pub enum EventType {
     Lifecycle,
+    /// Chunk of output streamed by the handler before completing, see [`Event::output_chunk`].
+    OutputChunk,
     #[strum(default)]
     Other(String),
 }
@@ -29,6 +31,25 @@ pub struct Event {
     pub metadata: HashMap<String, ByteString>,
 }
 
+impl Event {
+    const OUTPUT_CHUNK_CONTENT_KEY: &'static str = "content";
+
+    pub fn output_chunk(content: impl Into<ByteString>) -> Self {
+        Self {
+            ty: EventType::OutputChunk,
+            metadata: HashMap::from([(Self::OUTPUT_CHUNK_CONTENT_KEY.to_owned(), content.into())]),
+        }
+    }
+
+    /// Content of the output chunk, if this is an [`EventType::OutputChunk`] event.
+    pub fn output_chunk_content(&self) -> Option<&ByteString> {
+        if self.ty != EventType::OutputChunk {
+            return None;
+        }
+        self.metadata.get(Self::OUTPUT_CHUNK_CONTENT_KEY)
+    }
+}
+
 impl EntryMetadata for Event {
     fn ty(&self) -> EntryType {
         EntryType::Event
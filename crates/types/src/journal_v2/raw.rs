@@ -83,6 +83,15 @@ impl RawEntry {
     pub fn header(&self) -> &RawEntryHeader {
         &self.header
     }
+
+    /// Size in bytes of the serialized content of this entry. Events are not counted.
+    pub fn serialized_content_len(&self) -> usize {
+        match &self.inner {
+            RawEntryInner::Command(cmd) => cmd.serialized_content.len(),
+            RawEntryInner::Notification(notif) => notif.serialized_content.len(),
+            RawEntryInner::Event(_) => 0,
+        }
+    }
 }
 
 impl RawEntry {
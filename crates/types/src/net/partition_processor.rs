@@ -8,9 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::Duration;
+
 use crate::errors::InvocationError;
 use crate::identifiers::{
-    InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, WithPartitionKey,
+    EntryIndex, InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
+    WithPartitionKey,
 };
 use crate::invocation::{InvocationQuery, InvocationRequest, InvocationResponse, InvocationTarget};
 use crate::journal_v2::Signal;
@@ -18,6 +21,7 @@ use crate::net::define_rpc;
 use crate::net::TargetName;
 use crate::time::MillisSinceEpoch;
 use bytes::Bytes;
+use bytestring::ByteString;
 use serde::{Deserialize, Serialize};
 
 define_rpc! {
@@ -53,12 +57,37 @@ pub enum GetInvocationOutputResponseMode {
     ReplyIfNotReady,
 }
 
+/// Bounds of reads served by partition replicas.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ReplicaReadOptions {
+    /// Maximum age of the read index the replica can reuse. With zero, the replica obtains a
+    /// fresh read index for every read, making it linearizable.
+    pub max_staleness: Duration,
+    /// Maximum time the replica waits to apply the log up to the read index.
+    pub timeout: Duration,
+}
+
+/// Position in the output stream of an invocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputStreamCursor {
+    /// Next journal entry to read.
+    pub entry_index: EntryIndex,
+    /// Number of chunks read so far.
+    pub chunk_index: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PartitionProcessorRpcRequestInner {
     AppendInvocation(InvocationRequest, AppendInvocationReplyOn),
     GetInvocationOutput(InvocationQuery, GetInvocationOutputResponseMode),
+    /// Like [`GetInvocationOutputResponseMode::ReplyIfNotReady`], but can be served by any replica
+    /// of the partition once it applied the log up to the read index.
+    ReadInvocationOutput(InvocationQuery, ReplicaReadOptions),
     AppendInvocationResponse(InvocationResponse),
     AppendSignal(InvocationId, Signal),
+    /// Reads the output chunks emitted after the cursor. If there are none yet, waits up to the
+    /// given duration for new ones before replying.
+    ReadOutputChunks(InvocationQuery, OutputStreamCursor, Duration),
 }
 
 impl WithPartitionKey for PartitionProcessorRpcRequestInner {
@@ -66,8 +95,10 @@ impl WithPartitionKey for PartitionProcessorRpcRequestInner {
         match self {
             PartitionProcessorRpcRequestInner::AppendInvocation(si, _) => si.partition_key(),
             PartitionProcessorRpcRequestInner::GetInvocationOutput(iq, _) => iq.partition_key(),
+            PartitionProcessorRpcRequestInner::ReadInvocationOutput(iq, _) => iq.partition_key(),
             PartitionProcessorRpcRequestInner::AppendInvocationResponse(ir) => ir.partition_key(),
             PartitionProcessorRpcRequestInner::AppendSignal(si, _) => si.partition_key(),
+            PartitionProcessorRpcRequestInner::ReadOutputChunks(iq, _, _) => iq.partition_key(),
         }
     }
 }
@@ -86,6 +117,8 @@ pub enum PartitionProcessorRpcError {
     Starting,
     #[error("partition processor stopping")]
     Stopping,
+    #[error("replica for partition '{0}' did not catch up with the read index in time")]
+    ReplicaLagging(PartitionId),
 }
 
 impl PartitionProcessorRpcError {
@@ -97,6 +130,7 @@ impl PartitionProcessorRpcError {
             PartitionProcessorRpcError::Internal(_) => false,
             PartitionProcessorRpcError::Starting => false,
             PartitionProcessorRpcError::Stopping => false,
+            PartitionProcessorRpcError::ReplicaLagging(_) => false,
         }
     }
 }
@@ -109,6 +143,7 @@ pub enum PartitionProcessorRpcResponse {
     NotSupported,
     Submitted(SubmittedInvocationNotification),
     Output(InvocationOutput),
+    OutputChunks(OutputChunks),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -127,6 +162,15 @@ pub struct InvocationOutput {
     pub response: IngressResponseResult,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OutputChunks {
+    pub chunks: Vec<ByteString>,
+    /// Cursor to read the following chunks.
+    pub next: OutputStreamCursor,
+    /// If true, the invocation completed and won't emit more chunks.
+    pub completed: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum IngressResponseResult {
     Success(InvocationTarget, Bytes),
@@ -0,0 +1,105 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! References to the payloads offloaded to the payload store.
+//!
+//! References are plain byte strings starting with [`PAYLOAD_REFERENCE_MAGIC`], followed by the
+//! object path `[<prefix>/]<invocation_id>/<name>`. They live here rather than next to the payload
+//! store, because they are recognized and rewritten by components without access to the store.
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::identifiers::InvocationId;
+
+/// Prefix identifying a payload reference. It starts with a NUL byte and is long enough to make
+/// collisions with user payloads, which are typically JSON or protobuf, practically impossible.
+pub const PAYLOAD_REFERENCE_MAGIC: &[u8] = b"\0restate.payload-ref.v1\0";
+
+/// Returns true if the given payload is a reference to an offloaded payload.
+pub fn is_payload_reference(payload: &[u8]) -> bool {
+    payload.starts_with(PAYLOAD_REFERENCE_MAGIC)
+}
+
+/// Creates the reference to the object stored at the given path.
+pub fn payload_reference(path: &str) -> Bytes {
+    let mut reference = BytesMut::with_capacity(PAYLOAD_REFERENCE_MAGIC.len() + path.len());
+    reference.put_slice(PAYLOAD_REFERENCE_MAGIC);
+    reference.put_slice(path.as_bytes());
+    reference.freeze()
+}
+
+/// Returns the object path of the given reference, or `None` if it's not a valid reference.
+pub fn payload_reference_path(reference: &[u8]) -> Option<&str> {
+    reference
+        .strip_prefix(PAYLOAD_REFERENCE_MAGIC)
+        .and_then(|path| std::str::from_utf8(path).ok())
+}
+
+/// Returns the reference to the same object name, owned by the new invocation instead, or `None`
+/// if the reference is not owned by the given invocation. The object itself must be copied to the
+/// returned reference before it can be resolved.
+pub fn rebase_payload_reference(
+    reference: &[u8],
+    owner: &InvocationId,
+    new_owner: &InvocationId,
+) -> Option<Bytes> {
+    let path = payload_reference_path(reference)?;
+    let (owner_path, name) = path.rsplit_once('/')?;
+    let rebased = match owner_path.rsplit_once('/') {
+        Some((prefix, id)) if id == owner.to_string() => format!("{prefix}/{new_owner}/{name}"),
+        None if owner_path == owner.to_string() => format!("{new_owner}/{name}"),
+        _ => return None,
+    };
+    Some(payload_reference(&rebased))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::invocation::InvocationTarget;
+
+    #[test]
+    fn rebase_keeps_prefix_and_name() {
+        let source = InvocationId::mock_generate(&InvocationTarget::mock_service());
+        let target = InvocationId::mock_generate(&InvocationTarget::mock_service());
+
+        let reference = payload_reference(&format!("some/prefix/{source}/input-abc"));
+        let rebased = rebase_payload_reference(&reference, &source, &target).unwrap();
+        assert_eq!(
+            payload_reference_path(&rebased),
+            Some(format!("some/prefix/{target}/input-abc").as_str())
+        );
+
+        let reference = payload_reference(&format!("{source}/input-abc"));
+        let rebased = rebase_payload_reference(&reference, &source, &target).unwrap();
+        assert_eq!(
+            payload_reference_path(&rebased),
+            Some(format!("{target}/input-abc").as_str())
+        );
+
+        assert_eq!(
+            rebase_payload_reference(b"not a reference", &source, &target),
+            None
+        );
+    }
+
+    #[test]
+    fn rebase_refuses_references_of_other_invocations() {
+        let source = InvocationId::mock_generate(&InvocationTarget::mock_service());
+        let other = InvocationId::mock_generate(&InvocationTarget::mock_service());
+        let target = InvocationId::mock_generate(&InvocationTarget::mock_service());
+
+        let reference = payload_reference(&format!("some/prefix/{other}/input-abc"));
+        assert_eq!(rebase_payload_reference(&reference, &source, &target), None);
+        let reference = payload_reference(&format!("{other}/input-abc"));
+        assert_eq!(rebase_payload_reference(&reference, &source, &target), None);
+    }
+}
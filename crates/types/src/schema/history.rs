@@ -0,0 +1,432 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! History of the changes applied to the [`Schema`].
+//!
+//! Each change records the services it touched, together with their metadata after the change.
+//! Changes are ordered by the schema version they produced, so changes recorded out of order by
+//! concurrent writers still apply in the right order. A change whose recording was lost, e.g.
+//! because the writer crashed after updating the schema, is reconstructed as an untracked change
+//! when the next change is recorded.
+//!
+//! The history retains a configurable number of changes: older changes are folded into a
+//! baseline, so the services can still be reconstructed at any retained version.
+
+use std::collections::{HashMap, VecDeque};
+use std::num::NonZeroUsize;
+
+use serde::{Deserialize, Serialize};
+
+use super::service::{ServiceMetadata, ServiceMetadataResolver};
+use super::Schema;
+use crate::time::MillisSinceEpoch;
+use crate::{flexbuffers_storage_encode_decode, Version, Versioned};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaChange {
+    /// Version of the schema after the change.
+    pub schema_version: Version,
+    pub timestamp: MillisSinceEpoch,
+    pub author: Option<String>,
+    pub description: String,
+    /// Metadata of the services touched by the change, after the change. `None` if the service
+    /// was removed.
+    pub services: HashMap<String, Option<ServiceMetadata>>,
+    /// True if the change was not recorded by its writer, and was reconstructed from the schema
+    /// observed by the following change.
+    #[serde(default)]
+    pub untracked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaHistory {
+    version: Version,
+    /// Schema version the baseline corresponds to.
+    baseline_version: Version,
+    /// Services at the baseline version.
+    baseline: HashMap<String, ServiceMetadata>,
+    /// Retained changes, in ascending schema version order.
+    changes: VecDeque<SchemaChange>,
+}
+
+impl Default for SchemaHistory {
+    fn default() -> Self {
+        Self {
+            version: Version::INVALID,
+            baseline_version: Version::INVALID,
+            baseline: HashMap::default(),
+            changes: VecDeque::default(),
+        }
+    }
+}
+
+impl SchemaHistory {
+    /// Records the change from the previous to the current schema, retaining at most
+    /// `max_changes` changes. Returns false if the history was not modified, because the change
+    /// was already recorded or didn't touch any service.
+    pub fn record(
+        &mut self,
+        previous: &Schema,
+        current: &Schema,
+        author: Option<String>,
+        description: String,
+        max_changes: NonZeroUsize,
+    ) -> bool {
+        let has_baseline = self.baseline_version != Version::INVALID || !self.changes.is_empty();
+        if has_baseline && current.version <= self.baseline_version {
+            // Already folded into the baseline
+            return false;
+        }
+        let position = self.position(current.version);
+        if self
+            .changes
+            .get(position)
+            .is_some_and(|change| change.schema_version == current.version && !change.untracked)
+        {
+            return false;
+        }
+
+        let previous_services = services_by_name(previous);
+        let services = changed_services(&previous_services, &services_by_name(current));
+        let mut modified = false;
+        if !has_baseline {
+            if services.is_empty() {
+                return false;
+            }
+            self.baseline_version = previous.version;
+            self.baseline = previous_services;
+        } else {
+            modified = self.record_untracked(previous.version, previous_services);
+        }
+
+        let change = SchemaChange {
+            schema_version: current.version,
+            timestamp: MillisSinceEpoch::now(),
+            author,
+            description,
+            services,
+            untracked: false,
+        };
+        let position = self.position(current.version);
+        match self.changes.get_mut(position) {
+            // Replaces the untracked change reconstructed before this one was recorded
+            Some(existing) if existing.schema_version == current.version => {
+                if change.services.is_empty() {
+                    self.changes.remove(position);
+                } else {
+                    *existing = change;
+                }
+                modified = true;
+            }
+            _ if !change.services.is_empty() => {
+                self.changes.insert(position, change);
+                modified = true;
+            }
+            _ => {}
+        }
+        if !modified {
+            return false;
+        }
+
+        while self.changes.len() > max_changes.get() {
+            let folded = self.changes.pop_front().expect("changes are not empty");
+            self.baseline_version = folded.schema_version;
+            apply_change(&mut self.baseline, folded.services);
+        }
+
+        self.version = self.version.next();
+        true
+    }
+
+    /// Records the changes leading to the given schema version as untracked, if they differ from
+    /// the services reconstructed from the history. Returns true if a change was recorded.
+    fn record_untracked(
+        &mut self,
+        schema_version: Version,
+        services: HashMap<String, ServiceMetadata>,
+    ) -> bool {
+        let position = self.position(schema_version);
+        if schema_version <= self.baseline_version
+            || self
+                .changes
+                .get(position)
+                .is_some_and(|change| change.schema_version == schema_version)
+        {
+            return false;
+        }
+        let known_services = self
+            .services_at(schema_version)
+            .expect("version is not older than the baseline");
+        let services = changed_services(&known_services, &services);
+        if services.is_empty() {
+            return false;
+        }
+
+        self.changes.insert(
+            position,
+            SchemaChange {
+                schema_version,
+                timestamp: MillisSinceEpoch::now(),
+                author: None,
+                description: "untracked change".to_owned(),
+                services,
+                untracked: true,
+            },
+        );
+        true
+    }
+
+    /// Position of the change with the given schema version, or where it should be inserted.
+    fn position(&self, schema_version: Version) -> usize {
+        self.changes
+            .partition_point(|change| change.schema_version < schema_version)
+    }
+
+    pub fn changes(&self) -> impl DoubleEndedIterator<Item = &SchemaChange> {
+        self.changes.iter()
+    }
+
+    /// Oldest schema version the services can be reconstructed at.
+    pub fn oldest_version(&self) -> Version {
+        self.baseline_version
+    }
+
+    /// Reconstructs the services at the given schema version. Returns `None` if the version is
+    /// older than the retained history.
+    pub fn services_at(&self, schema_version: Version) -> Option<HashMap<String, ServiceMetadata>> {
+        if schema_version < self.baseline_version {
+            return None;
+        }
+
+        let mut services = self.baseline.clone();
+        for change in self
+            .changes
+            .iter()
+            .take_while(|change| change.schema_version <= schema_version)
+        {
+            apply_change(&mut services, change.services.clone());
+        }
+        Some(services)
+    }
+}
+
+impl Versioned for SchemaHistory {
+    fn version(&self) -> Version {
+        self.version
+    }
+}
+
+flexbuffers_storage_encode_decode!(SchemaHistory);
+
+fn services_by_name(schema: &Schema) -> HashMap<String, ServiceMetadata> {
+    schema
+        .list_services()
+        .into_iter()
+        .map(|service| (service.name.clone(), service))
+        .collect()
+}
+
+fn changed_services(
+    previous: &HashMap<String, ServiceMetadata>,
+    current: &HashMap<String, ServiceMetadata>,
+) -> HashMap<String, Option<ServiceMetadata>> {
+    let mut services = HashMap::new();
+    for (name, previous_service) in previous {
+        match current.get(name) {
+            None => {
+                services.insert(name.clone(), None);
+            }
+            Some(current_service) if !same_metadata(previous_service, current_service) => {
+                services.insert(name.clone(), Some(current_service.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, current_service) in current {
+        if !previous.contains_key(name) {
+            services.insert(name.clone(), Some(current_service.clone()));
+        }
+    }
+    services
+}
+
+fn apply_change(
+    services: &mut HashMap<String, ServiceMetadata>,
+    changed_services: HashMap<String, Option<ServiceMetadata>>,
+) {
+    for (name, service) in changed_services {
+        match service {
+            Some(service) => {
+                services.insert(name, service);
+            }
+            None => {
+                services.remove(&name);
+            }
+        }
+    }
+}
+
+fn same_metadata(a: &ServiceMetadata, b: &ServiceMetadata) -> bool {
+    // Handlers are listed in no particular order
+    let mut a = serde_json::to_value(a).expect("service metadata can be serialized");
+    let mut b = serde_json::to_value(b).expect("service metadata can be serialized");
+    for value in [&mut a, &mut b] {
+        if let Some(serde_json::Value::Array(handlers)) = value.get_mut("handlers") {
+            handlers.sort_by(|h1, h2| h1["name"].as_str().cmp(&h2["name"].as_str()));
+        }
+    }
+    a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::LazyLock;
+    use std::time::Duration;
+
+    use crate::identifiers::DeploymentId;
+    use crate::invocation::ServiceType;
+    use crate::schema::service::{ServiceLocation, ServiceSchemas};
+
+    static DEPLOYMENT_ID: LazyLock<DeploymentId> = LazyLock::new(DeploymentId::new);
+    const MAX_CHANGES: NonZeroUsize = NonZeroUsize::MAX;
+
+    fn schema_with_public(version: Version, services: &[(&str, bool)]) -> Schema {
+        let mut schema = Schema {
+            version,
+            ..Schema::default()
+        };
+        for (name, public) in services {
+            schema.services.insert(
+                name.to_string(),
+                ServiceSchemas {
+                    revision: 1,
+                    handlers: HashMap::default(),
+                    ty: ServiceType::Service,
+                    location: ServiceLocation {
+                        latest_deployment: *DEPLOYMENT_ID,
+                        public: *public,
+                    },
+                    idempotency_retention: Duration::from_secs(60 * 60 * 24),
+                    workflow_completion_retention: None,
+                    inactivity_timeout: None,
+                    abort_timeout: None,
+                    enforce_input_schema: false,
+                    documentation: None,
+                    metadata: HashMap::default(),
+                    service_openapi_cache: Default::default(),
+                },
+            );
+        }
+        schema
+    }
+
+    #[test]
+    fn reconstruct_services_at_version() {
+        let v1 = schema_with_public(Version::MIN, &[("Greeter", true)]);
+        let v2 = schema_with_public(Version::from(2), &[("Greeter", false)]);
+        let v3 = schema_with_public(Version::from(3), &[("Greeter", false), ("Counter", true)]);
+        let v4 = schema_with_public(Version::from(4), &[("Counter", true)]);
+
+        let mut history = SchemaHistory::default();
+        assert!(history.record(&Schema::default(), &v1, None, "v1".to_owned(), MAX_CHANGES));
+        assert!(history.record(
+            &v1,
+            &v2,
+            Some("admin".to_owned()),
+            "v2".to_owned(),
+            MAX_CHANGES
+        ));
+        assert!(history.record(&v2, &v3, None, "v3".to_owned(), MAX_CHANGES));
+        assert!(history.record(&v3, &v4, None, "v4".to_owned(), MAX_CHANGES));
+        // Nothing changed
+        assert!(!history.record(&v4, &v4, None, "v4".to_owned(), MAX_CHANGES));
+
+        let changes: Vec<_> = history.changes().collect();
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes[1].author.as_deref(), Some("admin"));
+        assert!(matches!(changes[3].services.get("Greeter"), Some(None)));
+        assert!(!changes[3].services.contains_key("Counter"));
+
+        let at_v2 = history.services_at(Version::from(2)).unwrap();
+        assert!(!at_v2["Greeter"].public);
+        assert!(!at_v2.contains_key("Counter"));
+
+        let at_v4 = history.services_at(Version::from(4)).unwrap();
+        assert!(!at_v4.contains_key("Greeter"));
+        assert!(at_v4["Counter"].public);
+    }
+
+    #[test]
+    fn fold_dropped_changes_into_baseline() {
+        let max_changes = NonZeroUsize::new(4).unwrap();
+        let mut history = SchemaHistory::default();
+        let mut previous = Schema::default();
+        for i in 1..=6 {
+            let current = schema_with_public(Version::from(i), &[("Greeter", i % 2 == 0)]);
+            assert!(history.record(&previous, &current, None, format!("v{i}"), max_changes));
+            previous = current;
+        }
+
+        assert_eq!(history.changes().count(), 4);
+        assert_eq!(history.oldest_version(), Version::from(2));
+        assert!(history.services_at(Version::MIN).is_none());
+        assert!(history.services_at(Version::from(2)).unwrap()["Greeter"].public);
+        assert!(!history.services_at(Version::from(3)).unwrap()["Greeter"].public);
+    }
+
+    #[test]
+    fn record_changes_out_of_order() {
+        let v1 = schema_with_public(Version::MIN, &[("Greeter", true)]);
+        let v2 = schema_with_public(Version::from(2), &[("Greeter", false)]);
+        let v3 = schema_with_public(Version::from(3), &[("Greeter", false), ("Counter", true)]);
+
+        let mut history = SchemaHistory::default();
+        assert!(history.record(&Schema::default(), &v1, None, "v1".to_owned(), MAX_CHANGES));
+        // The writer of v2 records its change after the writer of v3
+        assert!(history.record(&v2, &v3, None, "v3".to_owned(), MAX_CHANGES));
+        assert!(history.changes().nth(1).unwrap().untracked);
+        assert!(history.record(
+            &v1,
+            &v2,
+            Some("admin".to_owned()),
+            "v2".to_owned(),
+            MAX_CHANGES
+        ));
+        assert!(!history.record(&v1, &v2, None, "v2".to_owned(), MAX_CHANGES));
+
+        let changes: Vec<_> = history.changes().collect();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[1].schema_version, Version::from(2));
+        assert_eq!(changes[1].author.as_deref(), Some("admin"));
+        assert!(!changes[1].untracked);
+        assert_eq!(changes[2].schema_version, Version::from(3));
+        assert!(!history.services_at(Version::from(2)).unwrap()["Greeter"].public);
+    }
+
+    #[test]
+    fn reconstruct_lost_changes() {
+        let v1 = schema_with_public(Version::MIN, &[("Greeter", true)]);
+        let v2 = schema_with_public(Version::from(2), &[("Greeter", false)]);
+        let v3 = schema_with_public(Version::from(3), &[("Greeter", false), ("Counter", true)]);
+
+        let mut history = SchemaHistory::default();
+        assert!(history.record(&Schema::default(), &v1, None, "v1".to_owned(), MAX_CHANGES));
+        // The change to v2 was never recorded
+        assert!(history.record(&v2, &v3, None, "v3".to_owned(), MAX_CHANGES));
+
+        let untracked = history.changes().nth(1).unwrap();
+        assert!(untracked.untracked);
+        assert_eq!(untracked.schema_version, Version::from(2));
+        assert!(!history.services_at(Version::from(2)).unwrap()["Greeter"].public);
+        assert!(history.services_at(Version::from(3)).unwrap()["Counter"].public);
+    }
+}
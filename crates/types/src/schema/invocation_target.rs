@@ -14,13 +14,18 @@ use crate::invocation::InvocationTargetType;
 use bytes::Bytes;
 use bytestring::ByteString;
 use itertools::Itertools;
+use moka::sync::{Cache, CacheBuilder};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use std::{cmp, fmt};
+use tracing::warn;
 
 pub const DEFAULT_IDEMPOTENCY_RETENTION: Duration = Duration::from_secs(60 * 60 * 24);
 pub const DEFAULT_WORKFLOW_COMPLETION_RETENTION: Duration = Duration::from_secs(60 * 60 * 24);
+/// Handler metadata key overriding the service setting to enforce the input JSON schema.
+pub const ENFORCE_INPUT_SCHEMA_METADATA_KEY: &str = "restate.enforce-input-schema";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvocationTargetMetadata {
@@ -32,6 +37,9 @@ pub struct InvocationTargetMetadata {
     pub target_ty: InvocationTargetType,
     pub input_rules: InputRules,
     pub output_rules: OutputRules,
+    /// If true, the input must match the JSON schema of the target, see [`InputSchemaValidators`].
+    #[serde(default)]
+    pub enforce_input_schema: bool,
 }
 
 impl InvocationTargetMetadata {
@@ -87,6 +95,29 @@ pub enum InputValidationError {
     BadConfiguration,
     #[error("Content-type '{0}' does not match '{1}'")]
     ContentTypeNotMatching(String, InputContentType),
+    #[error("Input is not valid JSON: {0}")]
+    MalformedJson(String),
+    #[error("Input does not match the JSON schema: {}", .0.iter().join("; "))]
+    JsonSchemaViolations(Vec<JsonSchemaViolation>),
+}
+
+/// A violation of the input JSON schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonSchemaViolation {
+    /// JSON pointer to the part of the input violating the schema.
+    pub instance_path: String,
+    pub message: String,
+}
+
+impl fmt::Display for JsonSchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.instance_path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.instance_path, self.message)
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -114,15 +145,90 @@ impl InputRules {
     }
 
     pub fn json_schema(&self) -> Option<serde_json::Value> {
+        self.json_schema_ref().cloned()
+    }
+
+    pub fn json_schema_ref(&self) -> Option<&serde_json::Value> {
         for rule in &self.input_validation_rules {
             if let InputValidationRule::JsonValue { schema, .. } = rule {
-                return schema.clone();
+                return schema.as_ref();
             }
         }
         None
     }
 }
 
+/// Compiled input JSON schemas, keyed by the schema itself so that each schema is compiled once,
+/// and a changed schema is compiled again regardless of how the service was re-registered.
+/// Cloning shares the cache.
+#[derive(Clone)]
+pub struct InputSchemaValidators {
+    cache: Cache<String, Option<Arc<jsonschema::Validator>>>,
+}
+
+impl Default for InputSchemaValidators {
+    fn default() -> Self {
+        Self {
+            cache: CacheBuilder::new(1024)
+                .name("InputSchemaValidators")
+                .build(),
+        }
+    }
+}
+
+impl InputSchemaValidators {
+    /// Validates the input against the JSON schema of the target, if the target enforces it.
+    /// This complements [`InputRules::validate`], which checks only the content type.
+    pub fn validate(
+        &self,
+        service_name: &str,
+        handler_name: &str,
+        invocation_target_metadata: &InvocationTargetMetadata,
+        buf: &[u8],
+    ) -> Result<(), InputValidationError> {
+        // Empty inputs are accepted or rejected by the input rules already
+        if !invocation_target_metadata.enforce_input_schema || buf.is_empty() {
+            return Ok(());
+        }
+        let Some(schema) = invocation_target_metadata.input_rules.json_schema_ref() else {
+            return Ok(());
+        };
+
+        let Some(validator) = self.cache.get_with(schema.to_string(), || {
+            match jsonschema::validator_for(schema) {
+                Ok(validator) => Some(Arc::new(validator)),
+                Err(err) => {
+                    // The schema registry rejects invalid schemas, so this should not happen
+                    warn!(
+                        rpc.service = service_name,
+                        rpc.method = handler_name,
+                        "Cannot compile the input JSON schema, skipping validation: {err}"
+                    );
+                    None
+                }
+            }
+        }) else {
+            return Ok(());
+        };
+
+        let input: serde_json::Value = serde_json::from_slice(buf)
+            .map_err(|e| InputValidationError::MalformedJson(e.to_string()))?;
+        let violations: Vec<_> = validator
+            .iter_errors(&input)
+            .map(|err| JsonSchemaViolation {
+                instance_path: err.instance_path.to_string(),
+                message: err.to_string(),
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(InputValidationError::JsonSchemaViolations(violations))
+        }
+    }
+}
+
 impl Default for InputRules {
     fn default() -> Self {
         Self {
@@ -188,8 +294,8 @@ pub enum InputValidationRule {
     JsonValue {
         // Can use wildcards
         content_type: InputContentType,
-        // Right now we don't use this schema for anything except printing,
-        // so no need to use a more specialized type (we validate the schema is valid inside the schema registry updater)
+        // Used for printing, and to validate the input when the target enforces it, see InputSchemaValidators.
+        // The compiled schema is cached there (we validate the schema is valid inside the schema registry updater)
         schema: Option<serde_json::Value>,
     },
 }
@@ -240,7 +346,7 @@ impl InputValidationRule {
                     return Err(InputValidationError::EmptyValue);
                 }
 
-                // TODO add additional json validation.
+                // The JSON schema is enforced separately, see InputSchemaValidators
             }
         }
         Ok(())
@@ -497,6 +603,7 @@ pub mod test_util {
                 target_ty: invocation_target_type,
                 input_rules: Default::default(),
                 output_rules: Default::default(),
+                enforce_input_schema: false,
             }
         }
     }
@@ -671,6 +778,69 @@ mod tests {
         assert_eq!(input_rules.infer_content_type(true), None);
     }
 
+    #[test]
+    fn enforce_input_json_schema() {
+        let mut target = InvocationTargetMetadata {
+            input_rules: InputRules {
+                input_validation_rules: vec![InputValidationRule::JsonValue {
+                    content_type: InputContentType::Any,
+                    schema: Some(serde_json::json!({
+                        "type": "object",
+                        "properties": { "name": { "type": "string" } },
+                        "required": ["name"]
+                    })),
+                }],
+            },
+            output_rules: Default::default(),
+            public: true,
+            completion_retention: None,
+            idempotency_retention: DEFAULT_IDEMPOTENCY_RETENTION,
+            target_ty: InvocationTargetType::Service,
+            enforce_input_schema: false,
+        };
+        let validators = InputSchemaValidators::default();
+
+        // Not enforced
+        assert!(validators
+            .validate("greeter", "greet", &target, b"{}")
+            .is_ok());
+
+        target.enforce_input_schema = true;
+        assert!(validators
+            .validate("greeter", "greet", &target, br#"{"name": "Till"}"#)
+            .is_ok());
+        assert!(matches!(
+            validators.validate("greeter", "greet", &target, b"{\"name\": 1"),
+            Err(InputValidationError::MalformedJson(_))
+        ));
+        let Err(InputValidationError::JsonSchemaViolations(violations)) =
+            validators.validate("greeter", "greet", &target, br#"{"name": 1}"#)
+        else {
+            panic!("expected schema violations");
+        };
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].instance_path, "/name");
+
+        // A changed schema is enforced right away, even if the service is otherwise the same
+        target.input_rules = InputRules {
+            input_validation_rules: vec![InputValidationRule::JsonValue {
+                content_type: InputContentType::Any,
+                schema: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": { "name": { "type": "integer" } },
+                    "required": ["name"]
+                })),
+            }],
+        };
+        assert!(validators
+            .validate("greeter", "greet", &target, br#"{"name": 1}"#)
+            .is_ok());
+        assert!(matches!(
+            validators.validate("greeter", "greet", &target, br#"{"name": "Till"}"#),
+            Err(InputValidationError::JsonSchemaViolations(_))
+        ));
+    }
+
     #[test]
     fn infer_content_type_set_content_type_if_empty() {
         let ct = http::HeaderValue::from_static("application/restate");
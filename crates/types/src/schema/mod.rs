@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 pub mod deployment;
+pub mod history;
 pub mod invocation_target;
 pub mod openapi;
 pub mod service;
@@ -686,6 +686,24 @@ fn error_response_json_schema() -> Value {
             "description": {
                 "type": "string",
                 "title": "Verbose error description"
+            },
+            "violations": {
+                "type": "array",
+                "title": "Input JSON schema violations",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "instancePath": {
+                            "type": "string",
+                            "title": "JSON pointer to the invalid value"
+                        },
+                        "message": {
+                            "type": "string",
+                            "title": "Violation message"
+                        }
+                    },
+                    "required": ["instancePath", "message"]
+                }
             }
         },
         "required": ["message"],
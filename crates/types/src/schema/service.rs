@@ -122,6 +122,13 @@ pub struct ServiceMetadata {
     )]
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub abort_timeout: Option<humantime::Duration>,
+
+    /// # Enforce input schema
+    ///
+    /// If true, the ingress rejects requests whose body doesn't match the input JSON schema of
+    /// the handler. Handlers can override this with the `restate.enforce-input-schema` metadata.
+    #[serde(default)]
+    pub enforce_input_schema: bool,
 }
 
 // This type is used only for exposing the handler metadata, and not internally. See [ServiceAndHandlerType].
@@ -239,6 +246,8 @@ pub struct ServiceSchemas {
     pub workflow_completion_retention: Option<Duration>,
     pub inactivity_timeout: Option<Duration>,
     pub abort_timeout: Option<Duration>,
+    #[serde(default)]
+    pub enforce_input_schema: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub documentation: Option<String>,
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
@@ -268,6 +277,7 @@ impl ServiceSchemas {
             workflow_completion_retention: self.workflow_completion_retention.map(Into::into),
             inactivity_timeout: self.inactivity_timeout.map(Into::into),
             abort_timeout: self.abort_timeout.map(Into::into),
+            enforce_input_schema: self.enforce_input_schema,
         }
     }
 
@@ -393,6 +403,7 @@ pub mod test_util {
                 workflow_completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                enforce_input_schema: false,
             }
         }
 
@@ -425,6 +436,7 @@ pub mod test_util {
                 workflow_completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                enforce_input_schema: false,
             }
         }
     }
@@ -11,6 +11,7 @@
 use std::ops::RangeInclusive;
 
 use restate_types::identifiers::{LeaderEpoch, PartitionKey};
+use restate_types::invocation::JournalLimits;
 use restate_types::GenerationalNodeId;
 
 /// Announcing a new leader. This message can be written by any component to make the specified
@@ -25,6 +26,9 @@ pub struct AnnounceLeader {
     // Fallback if this is not set to use Envelope's header's destination partition-key as a
     // single key filter.
     pub partition_key_range: Option<RangeInclusive<PartitionKey>>,
+    // Option for backwards compatibility. The journal limits configured on the leader, applied by
+    // all the replicas from this message on. If not set, the previous limits stay in place.
+    pub journal_limits: Option<JournalLimits>,
 }
 
 #[cfg(test)]
@@ -32,6 +36,7 @@ mod tests {
     use crate::control::AnnounceLeader;
     use bytes::BytesMut;
     use restate_types::identifiers::LeaderEpoch;
+    use restate_types::invocation::JournalLimits;
     use restate_types::storage::StorageCodec;
     use restate_types::{flexbuffers_storage_encode_decode, GenerationalNodeId};
 
@@ -53,6 +58,7 @@ mod tests {
             node_id: Some(node_id),
             leader_epoch,
             partition_key_range: Some(1..=100),
+            journal_limits: Some(JournalLimits::default()),
         };
 
         let old_announce_leader = OldAnnounceLeader {
@@ -70,6 +76,7 @@ mod tests {
             expected_announce_leader.node_id
         );
         assert_eq!(new_announce_leader.partition_key_range, None);
+        assert_eq!(new_announce_leader.journal_limits, None);
         assert_eq!(
             new_announce_leader.leader_epoch,
             expected_announce_leader.leader_epoch
@@ -16,8 +16,9 @@ use restate_core::{Metadata, ShutdownError};
 use restate_storage_api::deduplication_table::DedupInformation;
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey, WithPartitionKey};
 use restate_types::invocation::{
-    AttachInvocationRequest, GetInvocationOutputResponse, InvocationResponse,
-    InvocationTermination, NotifySignalRequest, PurgeInvocationRequest, ServiceInvocation,
+    AttachInvocationRequest, CompletePromiseRequest, GetInvocationOutputResponse,
+    InvocationResponse, InvocationTermination, MigrateInvocationRequest, NotifySignalRequest,
+    PurgeInvocationRequest, PurgeServiceRequest, RestartInvocationRequest, ServiceInvocation,
 };
 use restate_types::message::MessageIndex;
 use restate_types::state_mut::ExternalStateMutation;
@@ -142,6 +143,14 @@ pub enum Command {
     TerminateInvocation(InvocationTermination),
     /// Purge a completed invocation
     PurgeInvocation(PurgeInvocationRequest),
+    /// Purge the stored data of a service from the partition
+    PurgeService(PurgeServiceRequest),
+    /// Manual completion of a workflow promise
+    CompletePromise(CompletePromiseRequest),
+    /// Re-pin an in-flight invocation to another deployment
+    MigrateInvocation(MigrateInvocationRequest),
+    /// Restart an invocation, either as a new invocation or in place truncating its journal
+    RestartInvocation(RestartInvocationRequest),
     /// Start an invocation on this partition
     Invoke(ServiceInvocation),
     /// Truncate the message outbox up to, and including, the specified index.
@@ -202,6 +211,14 @@ impl HasRecordKeys for Envelope {
                 Keys::Single(terminate.invocation_id.partition_key())
             }
             Command::PurgeInvocation(purge) => Keys::Single(purge.invocation_id.partition_key()),
+            Command::PurgeService(purge) => Keys::RangeInclusive(purge.partition_key_range.clone()),
+            Command::CompletePromise(complete) => Keys::Single(complete.service_id.partition_key()),
+            Command::MigrateInvocation(migrate) => {
+                Keys::Single(migrate.invocation_id.partition_key())
+            }
+            Command::RestartInvocation(restart) => {
+                Keys::Single(restart.invocation_id.partition_key())
+            }
             Command::Invoke(invoke) => Keys::Single(invoke.partition_key()),
             // todo: Remove this, or pass the partition key range but filter based on partition-id
             // on read if needed.
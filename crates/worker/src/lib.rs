@@ -124,7 +124,7 @@ impl Worker {
         let config = updateable_config.pinned();
 
         // ingress_kafka
-        let ingress_kafka = IngressKafkaService::new(bifrost.clone());
+        let ingress_kafka = IngressKafkaService::new(bifrost.clone(), metadata.updateable_schema());
         let subscription_controller_handle = SubscriptionControllerHandle::new(
             config.ingress.clone(),
             ingress_kafka.create_command_sender(),
@@ -12,6 +12,8 @@
 /// the metrics' sink.
 use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
 
+use restate_storage_api::invocation_event_table::InvocationEventKind;
+
 pub const PARTITION_APPLY_COMMAND: &str = "restate.partition.apply_command.seconds";
 pub const PARTITION_ACTUATOR_HANDLED: &str = "restate.partition.actuator_handled.total";
 pub const PARTITION_STORAGE_TX_CREATED: &str = "restate.partition.storage_tx_created.total";
@@ -33,8 +35,13 @@ pub const PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION: &str =
     "restate.partition.handle_action_batch_duration.seconds";
 pub const PARTITION_HANDLE_INVOKER_EFFECT_COMMAND: &str =
     "restate.partition.handle_invoker_effect.seconds";
+pub const PARTITION_INVOCATION_JOURNAL_ENTRIES: &str = "restate.partition.journal_entries";
+pub const PARTITION_INVOCATION_JOURNAL_SIZE: &str = "restate.partition.journal_size.bytes";
+pub const PARTITION_INVOCATIONS: &str = "restate.partition.invocations.total";
+pub const PARTITION_INVOCATION_DURATION: &str = "restate.partition.invocation_duration.seconds";
 
 pub const PARTITION_LABEL: &str = "partition";
+pub const STATUS_LABEL: &str = "status";
 
 pub(crate) fn describe_metrics() {
     describe_histogram!(
@@ -82,6 +89,26 @@ pub(crate) fn describe_metrics() {
         Unit::Seconds,
         "Time spent handling an invoker effect command"
     );
+    describe_histogram!(
+        PARTITION_INVOCATION_JOURNAL_ENTRIES,
+        Unit::Count,
+        "Number of journal entries of completed invocations"
+    );
+    describe_histogram!(
+        PARTITION_INVOCATION_JOURNAL_SIZE,
+        Unit::Bytes,
+        "Total size of the journal entries of completed invocations"
+    );
+    describe_histogram!(
+        PARTITION_INVOCATION_DURATION,
+        Unit::Seconds,
+        "Time from the creation to the completion of invocations, including retries and suspensions"
+    );
+    describe_counter!(
+        PARTITION_INVOCATIONS,
+        Unit::Count,
+        "Number of invocations reaching a status, see label status to classify"
+    );
 
     describe_gauge!(
         NUM_ACTIVE_PARTITIONS,
@@ -125,3 +152,20 @@ pub(crate) fn describe_metrics() {
         "Number of seconds since the last record was applied"
     );
 }
+
+/// Value of the label `status` in [`PARTITION_INVOCATIONS`] for the invocation event, if the event
+/// is a status change.
+pub(crate) fn invocation_event_status(event_kind: &InvocationEventKind) -> Option<&'static str> {
+    match event_kind {
+        InvocationEventKind::Created => Some("created"),
+        InvocationEventKind::Scheduled { .. } => Some("scheduled"),
+        InvocationEventKind::Inboxed => Some("inboxed"),
+        InvocationEventKind::Started => Some("started"),
+        InvocationEventKind::Suspended => Some("suspended"),
+        InvocationEventKind::Resumed => Some("resumed"),
+        InvocationEventKind::Completed => Some("completed"),
+        InvocationEventKind::Failed { .. } => Some("failed"),
+        InvocationEventKind::PinnedDeployment { .. }
+        | InvocationEventKind::TransientError { .. } => None,
+    }
+}
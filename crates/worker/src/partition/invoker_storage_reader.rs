@@ -17,8 +17,10 @@ use restate_storage_api::invocation_status_table::{
 use restate_storage_api::state_table::ReadOnlyStateTable;
 use restate_storage_api::{journal_table as journal_table_v1, journal_table_v2};
 use restate_types::identifiers::InvocationId;
-use restate_types::identifiers::ServiceId;
+use restate_types::identifiers::{EntryIndex, ServiceId};
+use restate_types::journal_v2::raw::RawEntryInner;
 use restate_types::service_protocol::ServiceProtocolVersion;
+use std::future;
 use std::vec::IntoIter;
 
 #[derive(Debug, thiserror::Error)]
@@ -56,7 +58,7 @@ where
         let invocation_status = self.0.get_invocation_status(invocation_id).await?;
 
         if let InvocationStatus::Invoked(invoked_status) = invocation_status {
-            let journal_metadata = JournalMetadata::new(
+            let mut journal_metadata = JournalMetadata::new(
                 invoked_status.journal_metadata.length,
                 invoked_status.journal_metadata.span_context,
                 invoked_status.pinned_deployment.clone(),
@@ -77,10 +79,11 @@ where
                 .map(|entry| {
                     entry
                         .map_err(InvokerStorageReaderError::Storage)
-                        .map(|(_, entry)| {
-                            restate_invoker_api::journal_reader::JournalEntry::JournalV2(entry)
-                        })
+                        .map(|(_, entry)| entry)
                 })
+                // Events are not sent to the deployment
+                .try_filter(|entry| future::ready(!matches!(entry.inner, RawEntryInner::Event(_))))
+                .map_ok(restate_invoker_api::journal_reader::JournalEntry::JournalV2)
                 // TODO: Update invoker to maintain transaction while reading the journal stream: See https://github.com/restatedev/restate/issues/275
                 // collecting the stream because we cannot keep the transaction open
                 .try_collect::<Vec<_>>()
@@ -111,6 +114,7 @@ where
                 .await?
             };
 
+            journal_metadata.length = journal_stream.len() as EntryIndex;
             Ok((journal_metadata, stream::iter(journal_stream)))
         } else {
             Err(InvokerStorageReaderError::NotInvoked)
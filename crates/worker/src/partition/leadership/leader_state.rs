@@ -21,7 +21,8 @@ use futures::{stream, FutureExt, StreamExt};
 use metrics::{counter, Counter};
 use restate_bifrost::CommitToken;
 use restate_core::network::Reciprocal;
-use restate_core::{TaskCenter, TaskHandle, TaskId};
+use restate_core::payload_store::PayloadStore;
+use restate_core::{TaskCenter, TaskHandle, TaskId, TaskKind};
 use restate_partition_store::PartitionStore;
 use restate_types::identifiers::{
     InvocationId, LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
@@ -42,7 +43,7 @@ use std::pin::Pin;
 use std::task::{ready, Context, Poll};
 use std::time::{Duration, SystemTime};
 use tokio_stream::wrappers::ReceiverStream;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 const BATCH_READY_UP_TO: usize = 10;
 
@@ -71,6 +72,7 @@ pub struct LeaderState {
     shuffle_stream: ReceiverStream<shuffle::OutboxTruncation>,
     pub pending_cleanup_timers_to_schedule: VecDeque<(InvocationId, Duration)>,
     cleaner_task_id: TaskId,
+    payload_store: Option<PayloadStore>,
 }
 
 impl LeaderState {
@@ -86,6 +88,7 @@ impl LeaderState {
         self_proposer: SelfProposer,
         invoker_rx: InvokerStream,
         shuffle_rx: tokio::sync::mpsc::Receiver<shuffle::OutboxTruncation>,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         LeaderState {
             partition_id,
@@ -102,6 +105,7 @@ impl LeaderState {
             invoker_stream: invoker_rx,
             shuffle_stream: ReceiverStream::new(shuffle_rx),
             pending_cleanup_timers_to_schedule: Default::default(),
+            payload_store,
         }
     }
 
@@ -451,6 +455,46 @@ impl LeaderState {
                     .await
                     .map_err(Error::Invoker)?;
             }
+            Action::DeleteOffloadedPayloads { invocation_id } => {
+                if let Some(payload_store) = self.payload_store.clone() {
+                    // ignore shutdown errors
+                    let _ = TaskCenter::spawn_child(
+                        TaskKind::Disposable,
+                        "delete-offloaded-payloads",
+                        async move {
+                            if let Err(err) = payload_store
+                                .delete_invocation_payloads(&invocation_id)
+                                .await
+                            {
+                                debug!(%invocation_id, "Failed to delete offloaded payloads: {err}");
+                            }
+                            Ok(())
+                        },
+                    );
+                }
+            }
+            Action::CopyOffloadedPayload {
+                source_invocation_id,
+                source,
+                target_invocation_id,
+                target,
+            } => {
+                if let Some(payload_store) = &self.payload_store {
+                    // Copied before handling the following actions, so that the copy is in place
+                    // before the invocation owning it is invoked, or its source purged.
+                    if let Err(err) = payload_store
+                        .copy_input(
+                            &source_invocation_id,
+                            &source,
+                            &target_invocation_id,
+                            &target,
+                        )
+                        .await
+                    {
+                        warn!("Failed to copy offloaded payload: {err}");
+                    }
+                }
+            }
         }
 
         Ok(())
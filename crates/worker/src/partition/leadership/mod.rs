@@ -24,6 +24,7 @@ use tracing::{debug, instrument, warn};
 
 use restate_bifrost::Bifrost;
 use restate_core::network::Reciprocal;
+use restate_core::payload_store::PayloadStore;
 use restate_core::{my_node_id, ShutdownError, TaskCenter, TaskKind};
 use restate_errors::NotRunningError;
 use restate_invoker_api::InvokeInputJournal;
@@ -35,6 +36,7 @@ use restate_storage_api::invocation_status_table::{
 use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
 use restate_storage_api::timer_table::{TimerKey, TimerTable};
 use restate_timer::TokioClock;
+use restate_types::config::Configuration;
 use restate_types::errors::{GenericError, KILLED_INVOCATION_ERROR};
 use restate_types::identifiers::{InvocationId, PartitionKey, PartitionProcessorRpcRequestId};
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionLeaderEpoch};
@@ -160,6 +162,7 @@ pub(crate) struct LeadershipState<I> {
     channel_size: usize,
     invoker_tx: I,
     bifrost: Bifrost,
+    payload_store: Option<PayloadStore>,
 }
 
 impl<I> LeadershipState<I>
@@ -175,6 +178,7 @@ where
         invoker_tx: I,
         bifrost: Bifrost,
         last_seen_leader_epoch: Option<LeaderEpoch>,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         Self {
             state: State::Follower,
@@ -185,6 +189,7 @@ where
             invoker_tx,
             bifrost,
             last_seen_leader_epoch,
+            payload_store,
         }
     }
 
@@ -224,6 +229,7 @@ where
                     .partition_key_range
                     .clone(),
             ),
+            journal_limits: Some(Configuration::pinned().worker.journal_limits()),
         });
 
         let mut self_proposer = SelfProposer::new(
@@ -377,6 +383,7 @@ where
                 self_proposer.take().expect("must be present"),
                 invoker_rx,
                 shuffle_rx,
+                self.payload_store.clone(),
             ));
 
             Ok(())
@@ -619,6 +626,7 @@ mod tests {
     use restate_rocksdb::RocksDbManager;
     use restate_types::config::{CommonOptions, RocksDbOptions, StorageOptions};
     use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey};
+    use restate_types::invocation::JournalLimits;
     use restate_types::live::Constant;
     use restate_types::logs::{KeyFilter, Lsn, SequenceNumber};
     use restate_types::GenerationalNodeId;
@@ -660,6 +668,7 @@ mod tests {
             invoker_tx,
             bifrost.clone(),
             None,
+            None,
         );
 
         assert!(matches!(state.state, State::Follower));
@@ -685,6 +694,7 @@ mod tests {
                 node_id: Some(NODE_ID),
                 leader_epoch,
                 partition_key_range: Some(PARTITION_KEY_RANGE),
+                journal_limits: Some(JournalLimits::default()),
             }
         );
 
@@ -24,6 +24,7 @@ use tracing::{debug, error, info, instrument, trace, warn, Span};
 
 use restate_bifrost::Bifrost;
 use restate_core::network::{HasConnection, Incoming, Outgoing};
+use restate_core::payload_store::PayloadStore;
 use restate_core::{cancellation_watcher, ShutdownError, TaskCenter, TaskKind};
 use restate_partition_store::{PartitionStore, PartitionStoreTransaction};
 use restate_storage_api::deduplication_table::{
@@ -44,7 +45,8 @@ use restate_types::cluster::cluster_state::{PartitionProcessorStatus, ReplayStat
 use restate_types::config::WorkerOptions;
 use restate_types::errors::KILLED_INVOCATION_ERROR;
 use restate_types::identifiers::{
-    LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, WithPartitionKey,
+    InvocationId, LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
+    WithPartitionKey,
 };
 use restate_types::invocation;
 use restate_types::invocation::{
@@ -70,11 +72,15 @@ use crate::metric_definitions::{
 };
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
 use crate::partition::leadership::{LeadershipState, PartitionProcessorMetadata};
+use crate::partition::output_streams::OutputStreamReads;
+use crate::partition::replica_reads::ReplicaReads;
 use crate::partition::state_machine::{ActionCollector, ExperimentalFeature, StateMachine};
 
 mod cleaner;
 pub mod invoker_storage_reader;
 mod leadership;
+mod output_streams;
+mod replica_reads;
 pub mod shuffle;
 pub mod snapshots;
 mod state_machine;
@@ -94,9 +100,11 @@ pub(super) struct PartitionProcessorBuilder<InvokerInputSender> {
     num_timers_in_memory_limit: Option<usize>,
     disable_idempotency_table: bool,
     invocation_status_killed: bool,
+    invocation_events_retention: usize,
     cleanup_interval: Duration,
     channel_size: usize,
     max_command_batch_size: usize,
+    payload_store: Option<PayloadStore>,
 
     status: PartitionProcessorStatus,
     invoker_tx: InvokerInputSender,
@@ -120,6 +128,7 @@ where
         rpc_rx: mpsc::Receiver<Incoming<PartitionProcessorRpcRequest>>,
         status_watch_tx: watch::Sender<PartitionProcessorStatus>,
         invoker_tx: InvokerInputSender,
+        payload_store: Option<PayloadStore>,
     ) -> Self {
         Self {
             partition_id,
@@ -128,9 +137,11 @@ where
             num_timers_in_memory_limit: options.num_timers_in_memory_limit(),
             disable_idempotency_table: options.experimental_feature_disable_idempotency_table(),
             invocation_status_killed: options.experimental_feature_invocation_status_killed(),
+            invocation_events_retention: options.invocation_events_retention(),
             cleanup_interval: options.cleanup_interval(),
             channel_size: options.internal_queue_length(),
             max_command_batch_size: options.max_command_batch_size(),
+            payload_store,
             invoker_tx,
             control_rx,
             rpc_rx,
@@ -150,8 +161,10 @@ where
             cleanup_interval,
             disable_idempotency_table,
             invocation_status_killed,
+            invocation_events_retention,
             channel_size,
             max_command_batch_size,
+            payload_store,
             invoker_tx,
             control_rx,
             rpc_rx,
@@ -165,6 +178,7 @@ where
             partition_key_range.clone(),
             disable_idempotency_table,
             invocation_status_killed,
+            invocation_events_retention,
         )
         .await?;
 
@@ -187,6 +201,7 @@ where
             invoker_tx,
             bifrost.clone(),
             last_seen_leader_epoch,
+            payload_store,
         );
 
         Ok(PartitionProcessor {
@@ -196,6 +211,8 @@ where
             state_machine,
             max_command_batch_size,
             partition_store,
+            replica_reads: ReplicaReads::new(partition_id, bifrost.clone()),
+            output_stream_reads: OutputStreamReads::default(),
             bifrost,
             control_rx,
             rpc_rx,
@@ -209,8 +226,10 @@ where
         partition_key_range: RangeInclusive<PartitionKey>,
         disable_idempotency_table: bool,
         invocation_status_killed: bool,
+        invocation_events_retention: usize,
     ) -> Result<StateMachine, StorageError> {
         let inbox_seq_number = partition_store.get_inbox_seq_number().await?;
+        let journal_limits = partition_store.get_journal_limits().await?;
         let outbox_seq_number = partition_store.get_outbox_seq_number().await?;
         let outbox_head_seq_number = partition_store.get_outbox_head_seq_number().await?;
 
@@ -228,6 +247,8 @@ where
             outbox_head_seq_number,
             partition_key_range,
             experimental_features,
+            journal_limits,
+            invocation_events_retention,
         );
 
         Ok(state_machine)
@@ -247,6 +268,8 @@ pub struct PartitionProcessor<InvokerSender> {
 
     max_command_batch_size: usize,
     partition_store: PartitionStore,
+    replica_reads: ReplicaReads,
+    output_stream_reads: OutputStreamReads,
 }
 
 #[derive(Debug, derive_more::Display, thiserror::Error)]
@@ -302,6 +325,9 @@ where
         self.control_rx.close();
         while self.control_rx.recv().await.is_some() {}
 
+        self.replica_reads.abort();
+        self.output_stream_reads.abort();
+
         // Drain rpc_rx
         self.rpc_rx.close();
         while let Some(msg) = self.rpc_rx.recv().await {
@@ -419,6 +445,8 @@ where
         info!("PartitionProcessor starting event loop.");
 
         loop {
+            let replica_reads_deadline = self.replica_reads.next_deadline();
+            let output_stream_reads_deadline = self.output_stream_reads.next_deadline();
             tokio::select! {
                 Some(command) = self.control_rx.recv() => {
                     if let Err(err) = self.on_command(command).await {
@@ -428,6 +456,15 @@ where
                 Some(rpc) = self.rpc_rx.recv() => {
                     self.on_rpc(rpc, &mut partition_store).await;
                 }
+                _ = self.replica_reads.next_read_index() => {
+                    self.serve_replica_reads(&mut partition_store).await;
+                }
+                _ = Self::sleep_until(replica_reads_deadline) => {
+                    self.replica_reads.expire(Instant::now());
+                }
+                _ = Self::sleep_until(output_stream_reads_deadline) => {
+                    self.output_stream_reads.expire(Instant::now());
+                }
                 _ = status_update_timer.tick() => {
                     self.status_watch_tx.send_modify(|old| {
                         old.clone_from(&self.status);
@@ -449,6 +486,7 @@ where
                         let command_start = Instant::now();
 
                         trace!(%lsn, "Processing bifrost record for '{}': {:?}", envelope.command.name(), envelope.header);
+                        self.output_stream_reads.on_command_applied(&envelope.command);
 
                         let leadership_change = self.apply_record(
                             lsn,
@@ -493,6 +531,8 @@ where
 
                     // Commit our changes and notify actuators about actions if we are the leader
                     transaction.commit().await?;
+                    self.serve_replica_reads(&mut partition_store).await;
+                    self.serve_output_stream_reads(&mut partition_store).await;
                     let actions_start = Instant::now();
                     self.leadership_state.handle_actions(action_collector.drain(..)).await?;
                     record_actions_latency.record(actions_start.elapsed());
@@ -650,6 +690,11 @@ where
                     ),
                 );
             }
+            PartitionProcessorRpcRequestInner::ReadInvocationOutput(invocation_query, options) => {
+                self.replica_reads
+                    .register(request_id, invocation_query, options, response_tx);
+                self.serve_replica_reads(partition_store).await;
+            }
             PartitionProcessorRpcRequestInner::AppendInvocationResponse(invocation_response) => {
                 self.leadership_state
                     .self_propose_and_respond_asynchronously(
@@ -671,17 +716,123 @@ where
                     )
                     .await;
             }
+            PartitionProcessorRpcRequestInner::ReadOutputChunks(
+                invocation_query,
+                cursor,
+                max_wait,
+            ) => {
+                let result = async {
+                    let invocation_id = self
+                        .resolve_invocation_query(invocation_query, partition_store)
+                        .await?;
+                    let invocation_status = partition_store
+                        .get_invocation_status(&invocation_id)
+                        .await?;
+                    let chunks = output_streams::read_output_chunks(
+                        partition_store,
+                        invocation_id,
+                        invocation_status,
+                        cursor,
+                    )
+                    .await?;
+                    Ok::<_, StorageError>((invocation_id, chunks))
+                }
+                .await;
+
+                match result {
+                    Ok((invocation_id, Some(chunks)))
+                        if chunks.chunks.is_empty() && !chunks.completed && !max_wait.is_zero() =>
+                    {
+                        self.output_stream_reads.register(
+                            request_id,
+                            invocation_id,
+                            cursor,
+                            max_wait,
+                            response_tx,
+                        );
+                    }
+                    Ok((_, Some(chunks))) => respond_to_rpc(
+                        response_tx
+                            .prepare(Ok(PartitionProcessorRpcResponse::OutputChunks(chunks))),
+                    ),
+                    Ok((_, None)) => respond_to_rpc(
+                        response_tx.prepare(Ok(PartitionProcessorRpcResponse::NotFound)),
+                    ),
+                    Err(err) => respond_to_rpc(
+                        response_tx
+                            .prepare(Err(PartitionProcessorRpcError::Internal(err.to_string()))),
+                    ),
+                }
+            }
         };
     }
 
-    async fn handle_rpc_get_invocation_output(
+    /// Serves the output stream reads of the invocations touched by the applied records.
+    async fn serve_output_stream_reads(&mut self, partition_store: &mut PartitionStore) {
+        for read in self.output_stream_reads.take_touched() {
+            let result = async {
+                let invocation_status = partition_store
+                    .get_invocation_status(&read.invocation_id)
+                    .await?;
+                output_streams::read_output_chunks(
+                    partition_store,
+                    read.invocation_id,
+                    invocation_status,
+                    read.cursor,
+                )
+                .await
+            }
+            .await;
+
+            match result {
+                Ok(Some(chunks)) if chunks.chunks.is_empty() && !chunks.completed => {
+                    self.output_stream_reads.requeue(read);
+                }
+                Ok(Some(chunks)) => respond_to_rpc(
+                    read.response_tx
+                        .prepare(Ok(PartitionProcessorRpcResponse::OutputChunks(chunks))),
+                ),
+                Ok(None) => respond_to_rpc(
+                    read.response_tx
+                        .prepare(Ok(PartitionProcessorRpcResponse::NotFound)),
+                ),
+                Err(err) => respond_to_rpc(
+                    read.response_tx
+                        .prepare(Err(PartitionProcessorRpcError::Internal(err.to_string()))),
+                ),
+            }
+        }
+    }
+
+    /// Serves the replica reads whose read index was applied.
+    async fn serve_replica_reads(&mut self, partition_store: &mut PartitionStore) {
+        let last_applied_lsn = self.status.last_applied_log_lsn.unwrap_or(Lsn::INVALID);
+        for read in self.replica_reads.take_ready(last_applied_lsn) {
+            let response = self
+                .handle_rpc_get_invocation_output(
+                    read.request_id,
+                    read.invocation_query,
+                    partition_store,
+                )
+                .await
+                .map_err(|err| PartitionProcessorRpcError::Internal(err.to_string()));
+            respond_to_rpc(read.response_tx.prepare(response));
+        }
+    }
+
+    async fn sleep_until(deadline: Option<Instant>) {
+        match deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+            None => futures::future::pending().await,
+        }
+    }
+
+    async fn resolve_invocation_query(
         &self,
-        request_id: PartitionProcessorRpcRequestId,
         invocation_query: InvocationQuery,
         partition_store: &mut PartitionStore,
-    ) -> Result<PartitionProcessorRpcResponse, StorageError> {
-        // We can handle this immediately by querying the partition store, no need to go through proposals
-        let invocation_id = match invocation_query {
+    ) -> Result<InvocationId, StorageError> {
+        Ok(match invocation_query {
             InvocationQuery::Invocation(iid) => iid,
             ref q @ InvocationQuery::Workflow(ref sid) => {
                 // TODO We need this query for backward compatibility, remove when we remove the idempotency table
@@ -703,7 +854,19 @@ where
                     }
                 }
             }
-        };
+        })
+    }
+
+    async fn handle_rpc_get_invocation_output(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_query: InvocationQuery,
+        partition_store: &mut PartitionStore,
+    ) -> Result<PartitionProcessorRpcResponse, StorageError> {
+        // We can handle this immediately by querying the partition store, no need to go through proposals
+        let invocation_id = self
+            .resolve_invocation_query(invocation_query, partition_store)
+            .await?;
 
         let invocation_status = partition_store
             .get_invocation_status(&invocation_id)
@@ -796,18 +959,22 @@ where
             // todo: check whether it's worth passing the arc further down
             let envelope = Arc::unwrap_or_clone(envelope);
 
-            if let Command::AnnounceLeader(announce_leader) = envelope.command {
+            let announce_leader = match &envelope.command {
+                Command::AnnounceLeader(announce_leader) => Some(announce_leader.clone()),
+                _ => None,
+            };
+            // Announcements are applied too, as they carry the journal limits of the leader
+            self.state_machine
+                .apply(
+                    envelope.command,
+                    transaction,
+                    action_collector,
+                    self.leadership_state.is_leader(),
+                )
+                .await?;
+            if let Some(announce_leader) = announce_leader {
                 // leadership change detected, let's finish our transaction here
                 return Ok(Some((envelope.header, announce_leader)));
-            } else {
-                self.state_machine
-                    .apply(
-                        envelope.command,
-                        transaction,
-                        action_collector,
-                        self.leadership_state.is_leader(),
-                    )
-                    .await?;
             }
         } else {
             self.status.num_skipped_records += 1;
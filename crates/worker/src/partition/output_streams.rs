@@ -0,0 +1,358 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Reads of the output chunks streamed by invocations.
+//!
+//! Handlers stream output chunks by proposing [`EventType::OutputChunk`] events, which are stored
+//! in the journal like any other event and counted in the journal metadata. Once the invocation
+//! completes, the journal is retained together with the completed status if it contains output
+//! chunks, so they can still be replayed on attach. Reads finding no new chunks wait for the partition processor to apply a record
+//! touching the invocation, up to the requested duration, which lets the ingress long-poll the
+//! output stream.
+//!
+//! [`EventType::OutputChunk`]: restate_types::journal_v2::EventType::OutputChunk
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::time::{Duration, Instant};
+
+use bytestring::ByteString;
+use futures::{future, TryStreamExt};
+
+use restate_core::network::Reciprocal;
+use restate_storage_api::invocation_status_table::InvocationStatus;
+use restate_storage_api::journal_table_v2::ReadOnlyJournalTable;
+use restate_storage_api::Result as StorageResult;
+use restate_types::identifiers::{EntryIndex, InvocationId, PartitionProcessorRpcRequestId};
+use restate_types::journal_v2::raw::RawEntryInner;
+use restate_types::net::partition_processor::{
+    OutputChunks, OutputStreamCursor, PartitionProcessorRpcError, PartitionProcessorRpcResponse,
+};
+use restate_types::service_protocol::ServiceProtocolVersion;
+use restate_wal_protocol::Command;
+
+use super::respond_to_rpc;
+
+/// Reads the output chunks stored in the journal within the given range of entries.
+pub(crate) async fn read_journal_output_chunks<S: ReadOnlyJournalTable>(
+    storage: &mut S,
+    invocation_id: InvocationId,
+    from: EntryIndex,
+    journal_length: EntryIndex,
+) -> StorageResult<Vec<ByteString>> {
+    storage
+        .get_journal_range(invocation_id, from, journal_length)
+        .try_filter_map(|(_, entry)| {
+            future::ready(Ok(match &entry.inner {
+                RawEntryInner::Event(event) => event.output_chunk_content().cloned(),
+                _ => None,
+            }))
+        })
+        .try_collect()
+        .await
+}
+
+/// Reads the output chunks emitted after the cursor by the invocation with the given status.
+/// Returns `None` if the invocation doesn't exist anymore.
+pub(super) async fn read_output_chunks<S: ReadOnlyJournalTable>(
+    storage: &mut S,
+    invocation_id: InvocationId,
+    invocation_status: InvocationStatus,
+    cursor: OutputStreamCursor,
+) -> StorageResult<Option<OutputChunks>> {
+    let (journal_length, completed, has_new_chunks) = match invocation_status {
+        InvocationStatus::Free => return Ok(None),
+        InvocationStatus::Completed(completed) => (completed.journal_length, true, true),
+        InvocationStatus::Invoked(metadata)
+        | InvocationStatus::Suspended { metadata, .. }
+        | InvocationStatus::Killed(metadata)
+            if metadata
+                .pinned_deployment
+                .as_ref()
+                .is_some_and(|pd| pd.service_protocol_version >= ServiceProtocolVersion::V4) =>
+        {
+            (
+                metadata.journal_metadata.length,
+                false,
+                metadata.journal_metadata.output_chunks > cursor.chunk_index,
+            )
+        }
+        // Not started yet, or using a service protocol version without output streaming
+        _ => (0, false, false),
+    };
+
+    // The chunks count of the in-flight invocations spares reading the journal on every wake-up
+    // of the long-polling reads
+    let chunks = if has_new_chunks {
+        read_journal_output_chunks(storage, invocation_id, cursor.entry_index, journal_length)
+            .await?
+    } else {
+        vec![]
+    };
+    Ok(Some(OutputChunks {
+        next: OutputStreamCursor {
+            entry_index: journal_length.max(cursor.entry_index),
+            chunk_index: cursor.chunk_index + chunks.len() as u32,
+        },
+        chunks,
+        completed,
+    }))
+}
+
+pub(super) struct PendingChunksRead {
+    pub request_id: PartitionProcessorRpcRequestId,
+    pub invocation_id: InvocationId,
+    pub cursor: OutputStreamCursor,
+    pub response_tx: Reciprocal<Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>>,
+    deadline: Instant,
+}
+
+/// Reads waiting for new output chunks, indexed by invocation.
+#[derive(Default)]
+pub(super) struct OutputStreamReads {
+    waiting: HashMap<InvocationId, Vec<PendingChunksRead>>,
+    /// Invocations touched by the records applied since the last [`Self::take_touched`].
+    touched: HashSet<InvocationId>,
+    all_touched: bool,
+}
+
+impl OutputStreamReads {
+    pub fn register(
+        &mut self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_id: InvocationId,
+        cursor: OutputStreamCursor,
+        max_wait: Duration,
+        response_tx: Reciprocal<Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>>,
+    ) {
+        self.requeue(PendingChunksRead {
+            request_id,
+            invocation_id,
+            cursor,
+            response_tx,
+            deadline: Instant::now() + max_wait,
+        });
+    }
+
+    /// Puts back a read which is still waiting for new chunks.
+    pub fn requeue(&mut self, read: PendingChunksRead) {
+        self.waiting
+            .entry(read.invocation_id)
+            .or_default()
+            .push(read);
+    }
+
+    /// Records the invocations whose output stream might change by applying the command.
+    pub fn on_command_applied(&mut self, command: &Command) {
+        if self.waiting.is_empty() {
+            return;
+        }
+        let invocation_id = match command {
+            Command::InvokerEffect(effect) => effect.invocation_id,
+            Command::TerminateInvocation(termination) => termination.invocation_id,
+            Command::PurgeInvocation(purge) => purge.invocation_id,
+            Command::RestartInvocation(restart) => restart.invocation_id,
+            Command::Timer(timer) => timer.invocation_id(),
+            Command::PurgeService(_) => {
+                self.all_touched = true;
+                return;
+            }
+            _ => return,
+        };
+        if self.waiting.contains_key(&invocation_id) {
+            self.touched.insert(invocation_id);
+        }
+    }
+
+    /// Takes the waiting reads of the touched invocations, to check whether new chunks are
+    /// available for them.
+    pub fn take_touched(&mut self) -> Vec<PendingChunksRead> {
+        let touched = mem::take(&mut self.touched);
+        if mem::take(&mut self.all_touched) {
+            return self.waiting.drain().flat_map(|(_, reads)| reads).collect();
+        }
+        touched
+            .into_iter()
+            .filter_map(|invocation_id| self.waiting.remove(&invocation_id))
+            .flatten()
+            .collect()
+    }
+
+    /// Earliest deadline of the waiting reads.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.waiting
+            .values()
+            .flatten()
+            .map(|read| read.deadline)
+            .min()
+    }
+
+    /// Replies to the waiting reads whose deadline expired that there are no new chunks.
+    pub fn expire(&mut self, now: Instant) {
+        let mut expired = vec![];
+        self.waiting.retain(|_, reads| {
+            let (invocation_expired, waiting): (Vec<_>, Vec<_>) = mem::take(reads)
+                .into_iter()
+                .partition(|read| read.deadline <= now);
+            expired.extend(invocation_expired);
+            *reads = waiting;
+            !reads.is_empty()
+        });
+        for read in expired {
+            respond_to_rpc(read.response_tx.prepare(Ok(
+                PartitionProcessorRpcResponse::OutputChunks(OutputChunks {
+                    chunks: vec![],
+                    next: read.cursor,
+                    completed: false,
+                }),
+            )));
+        }
+    }
+
+    /// Fails all the reads, because the partition processor is stopping.
+    pub fn abort(&mut self) {
+        for read in self.waiting.drain().flat_map(|(_, reads)| reads) {
+            respond_to_rpc(
+                read.response_tx
+                    .prepare(Err(PartitionProcessorRpcError::Stopping)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use test_log::test;
+    use tokio::sync::mpsc;
+
+    use restate_core::network::{Incoming, OwnedConnection};
+    use restate_types::identifiers::{PartitionId, PartitionKey};
+    use restate_types::invocation::{InvocationQuery, PurgeInvocationRequest, PurgeServiceRequest};
+    use restate_types::net::codec::MessageBodyExt;
+    use restate_types::net::partition_processor::{
+        PartitionProcessorRpcRequest, PartitionProcessorRpcRequestInner,
+    };
+    use restate_types::net::CURRENT_PROTOCOL_VERSION;
+    use restate_types::GenerationalNodeId;
+
+    const PEER: GenerationalNodeId = GenerationalNodeId::new(2, 1);
+    const MAX_WAIT: Duration = Duration::from_secs(10);
+
+    fn register_read(
+        reads: &mut OutputStreamReads,
+        connection: &Arc<OwnedConnection>,
+        invocation_id: InvocationId,
+    ) -> PartitionProcessorRpcRequestId {
+        let request_id = PartitionProcessorRpcRequestId::new();
+        let (response_tx, _) = Incoming::for_testing(
+            connection.downgrade(),
+            PartitionProcessorRpcRequest {
+                request_id,
+                partition_id: PartitionId::MIN,
+                inner: PartitionProcessorRpcRequestInner::ReadOutputChunks(
+                    InvocationQuery::Invocation(invocation_id),
+                    OutputStreamCursor::default(),
+                    MAX_WAIT,
+                ),
+            },
+            None,
+        )
+        .split();
+        reads.register(
+            request_id,
+            invocation_id,
+            OutputStreamCursor::default(),
+            MAX_WAIT,
+            response_tx,
+        );
+        request_id
+    }
+
+    fn purge(invocation_id: InvocationId) -> Command {
+        Command::PurgeInvocation(PurgeInvocationRequest { invocation_id })
+    }
+
+    #[test(restate_core::test)]
+    async fn wakes_only_touched_invocations() {
+        let mut reads = OutputStreamReads::default();
+        let (net_tx, _net_rx) = mpsc::channel(10);
+        let connection = OwnedConnection::new_fake(PEER, CURRENT_PROTOCOL_VERSION, net_tx);
+        let touched_id = InvocationId::mock_random();
+        let untouched_id = InvocationId::mock_random();
+
+        let request_id = register_read(&mut reads, &connection, touched_id);
+        register_read(&mut reads, &connection, untouched_id);
+
+        // Records of invocations without waiting reads are ignored
+        reads.on_command_applied(&purge(InvocationId::mock_random()));
+        assert!(reads.take_touched().is_empty());
+
+        reads.on_command_applied(&purge(touched_id));
+        let touched = reads.take_touched();
+        assert_eq!(touched.len(), 1);
+        assert_eq!(touched[0].request_id, request_id);
+        assert_eq!(touched[0].invocation_id, touched_id);
+
+        // The touched invocations are reset after being taken
+        assert!(reads.take_touched().is_empty());
+        assert!(reads.next_deadline().is_some());
+    }
+
+    #[test(restate_core::test)]
+    async fn purge_service_wakes_all_invocations() {
+        let mut reads = OutputStreamReads::default();
+        let (net_tx, _net_rx) = mpsc::channel(10);
+        let connection = OwnedConnection::new_fake(PEER, CURRENT_PROTOCOL_VERSION, net_tx);
+
+        register_read(&mut reads, &connection, InvocationId::mock_random());
+        register_read(&mut reads, &connection, InvocationId::mock_random());
+
+        reads.on_command_applied(&Command::PurgeService(PurgeServiceRequest {
+            service_name: "Greeter".into(),
+            partition_key_range: PartitionKey::MIN..=PartitionKey::MAX,
+        }));
+        assert_eq!(reads.take_touched().len(), 2);
+        assert!(reads.next_deadline().is_none());
+    }
+
+    #[test(restate_core::test)]
+    async fn expired_reads_respond_without_chunks() -> anyhow::Result<()> {
+        let mut reads = OutputStreamReads::default();
+        let (net_tx, mut net_rx) = mpsc::channel(10);
+        let connection = OwnedConnection::new_fake(PEER, CURRENT_PROTOCOL_VERSION, net_tx);
+
+        register_read(&mut reads, &connection, InvocationId::mock_random());
+        reads.expire(Instant::now());
+        assert!(reads.next_deadline().is_some());
+
+        reads.expire(Instant::now() + MAX_WAIT);
+        assert!(reads.next_deadline().is_none());
+
+        let response = net_rx.recv().await.expect("response to be sent");
+        let response: Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError> = response
+            .body
+            .expect("response body")
+            .try_decode(connection.protocol_version())?;
+        assert!(matches!(
+            response,
+            Ok(PartitionProcessorRpcResponse::OutputChunks(OutputChunks {
+                chunks,
+                completed: false,
+                ..
+            })) if chunks.is_empty()
+        ));
+
+        Ok(())
+    }
+}
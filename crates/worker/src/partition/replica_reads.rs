@@ -0,0 +1,361 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Reads served by any replica of a partition, leader or follower.
+//!
+//! A read is served once the partition processor applied the log up to the read index, which is
+//! the last lsn of the log when the read was received, as reported by the log tail. Since the
+//! state of the partition is a function of the log, this makes reads linearizable regardless of
+//! which replica serves them. Reads can opt into reusing a read index obtained within a bounded
+//! time, trading freshness for fewer log tail lookups.
+
+use std::future::Future;
+use std::mem;
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+
+use restate_bifrost::Bifrost;
+use restate_core::network::Reciprocal;
+use restate_types::identifiers::{PartitionId, PartitionProcessorRpcRequestId};
+use restate_types::invocation::InvocationQuery;
+use restate_types::logs::{LogId, Lsn, SequenceNumber};
+use restate_types::net::partition_processor::{
+    PartitionProcessorRpcError, PartitionProcessorRpcResponse, ReplicaReadOptions,
+};
+
+use super::respond_to_rpc;
+
+type ReadIndexLookup = BoxFuture<
+    'static,
+    (
+        PendingRead,
+        Instant,
+        Result<Result<Lsn, restate_bifrost::Error>, tokio::time::error::Elapsed>,
+    ),
+>;
+
+pub(super) struct PendingRead {
+    pub request_id: PartitionProcessorRpcRequestId,
+    pub invocation_query: InvocationQuery,
+    pub response_tx: Reciprocal<Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>>,
+    read_index: Lsn,
+    deadline: Instant,
+}
+
+pub(super) struct ReplicaReads {
+    partition_id: PartitionId,
+    bifrost: Bifrost,
+    /// Last read index obtained from the log tail, and when its lookup started.
+    last_read_index: Option<(Lsn, Instant)>,
+    lookups: FuturesUnordered<ReadIndexLookup>,
+    /// Reads waiting for the partition processor to apply the log up to their read index.
+    waiting: Vec<PendingRead>,
+}
+
+impl ReplicaReads {
+    pub fn new(partition_id: PartitionId, bifrost: Bifrost) -> Self {
+        Self {
+            partition_id,
+            bifrost,
+            last_read_index: None,
+            lookups: FuturesUnordered::new(),
+            waiting: Vec::new(),
+        }
+    }
+
+    /// Registers a read, looking up a fresh read index unless the last one is within the
+    /// staleness bound of the read.
+    pub fn register(
+        &mut self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_query: InvocationQuery,
+        options: ReplicaReadOptions,
+        response_tx: Reciprocal<Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>>,
+    ) {
+        let now = Instant::now();
+        let mut read = PendingRead {
+            request_id,
+            invocation_query,
+            response_tx,
+            read_index: Lsn::INVALID,
+            deadline: now + options.timeout,
+        };
+
+        match self.last_read_index {
+            Some((read_index, looked_up_at))
+                if now.duration_since(looked_up_at) <= options.max_staleness =>
+            {
+                read.read_index = read_index;
+                self.waiting.push(read);
+            }
+            _ => {
+                let bifrost = self.bifrost.clone();
+                let log_id = LogId::from(self.partition_id);
+                self.push_lookup(read, now, options.timeout, async move {
+                    let tail = bifrost.find_tail(log_id).await?;
+                    Ok(tail.offset().prev())
+                });
+            }
+        }
+    }
+
+    /// Looks up the read index of the given read, giving up after the timeout of the read, since
+    /// the log tail might not be found while the log is being reconfigured.
+    fn push_lookup(
+        &mut self,
+        read: PendingRead,
+        looked_up_at: Instant,
+        timeout: Duration,
+        read_index: impl Future<Output = Result<Lsn, restate_bifrost::Error>> + Send + 'static,
+    ) {
+        self.lookups.push(
+            async move {
+                let read_index = tokio::time::timeout(timeout, read_index).await;
+                (read, looked_up_at, read_index)
+            }
+            .boxed(),
+        );
+    }
+
+    /// Waits for the next read index lookup to complete, registering the read as waiting for it.
+    /// Pending forever if there are no lookups in flight.
+    pub async fn next_read_index(&mut self) {
+        let Some((mut read, looked_up_at, read_index)) = self.lookups.next().await else {
+            return futures::future::pending().await;
+        };
+
+        match read_index {
+            Ok(Ok(read_index)) => {
+                if self
+                    .last_read_index
+                    .is_none_or(|(_, last_looked_up_at)| last_looked_up_at < looked_up_at)
+                {
+                    self.last_read_index = Some((read_index, looked_up_at));
+                }
+                read.read_index = read_index;
+                self.waiting.push(read);
+            }
+            Ok(Err(err)) => respond_to_rpc(read.response_tx.prepare(Err(
+                PartitionProcessorRpcError::Internal(format!(
+                    "failed looking up the read index: {err}"
+                )),
+            ))),
+            // Retryable, another replica or the leader might serve the read in time
+            Err(_) => respond_to_rpc(read.response_tx.prepare(Err(
+                PartitionProcessorRpcError::ReplicaLagging(self.partition_id),
+            ))),
+        }
+    }
+
+    /// Takes the reads which can be served by a replica which applied the log up to the given lsn.
+    pub fn take_ready(&mut self, last_applied_lsn: Lsn) -> Vec<PendingRead> {
+        let (ready, waiting) = mem::take(&mut self.waiting)
+            .into_iter()
+            .partition(|read| read.read_index <= last_applied_lsn);
+        self.waiting = waiting;
+        ready
+    }
+
+    /// Earliest deadline of the waiting reads.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.waiting.iter().map(|read| read.deadline).min()
+    }
+
+    /// Fails the waiting reads whose deadline expired.
+    pub fn expire(&mut self, now: Instant) {
+        let (expired, waiting) = mem::take(&mut self.waiting)
+            .into_iter()
+            .partition(|read| read.deadline <= now);
+        self.waiting = waiting;
+        for read in expired {
+            respond_to_rpc(read.response_tx.prepare(Err(
+                PartitionProcessorRpcError::ReplicaLagging(self.partition_id),
+            )));
+        }
+    }
+
+    /// Fails all the reads, because the partition processor is stopping.
+    pub fn abort(&mut self) {
+        for read in self.waiting.drain(..) {
+            respond_to_rpc(
+                read.response_tx
+                    .prepare(Err(PartitionProcessorRpcError::Stopping)),
+            );
+        }
+        self.lookups.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use test_log::test;
+    use tokio::sync::mpsc;
+
+    use restate_core::network::partition_processor_rpc_client::PartitionProcessorRpcClientError;
+    use restate_core::network::{Incoming, OwnedConnection};
+    use restate_core::TestCoreEnvBuilder;
+    use restate_types::identifiers::InvocationId;
+    use restate_types::net::codec::MessageBodyExt;
+    use restate_types::net::partition_processor::{
+        PartitionProcessorRpcRequest, PartitionProcessorRpcRequestInner,
+    };
+    use restate_types::net::CURRENT_PROTOCOL_VERSION;
+    use restate_types::partition_table::PartitionTable;
+    use restate_types::{GenerationalNodeId, Version};
+
+    const PEER: GenerationalNodeId = GenerationalNodeId::new(2, 1);
+
+    async fn replica_reads() -> ReplicaReads {
+        let env = TestCoreEnvBuilder::with_incoming_only_connector()
+            .set_partition_table(PartitionTable::with_equally_sized_partitions(
+                Version::MIN,
+                1,
+            ))
+            .build()
+            .await;
+        let bifrost = Bifrost::init_in_memory(env.metadata_writer).await;
+        ReplicaReads::new(PartitionId::MIN, bifrost)
+    }
+
+    fn options(max_staleness: Duration) -> ReplicaReadOptions {
+        ReplicaReadOptions {
+            max_staleness,
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    fn read_request(
+        connection: &Arc<OwnedConnection>,
+        options: ReplicaReadOptions,
+    ) -> (
+        PartitionProcessorRpcRequestId,
+        InvocationQuery,
+        Reciprocal<Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>>,
+    ) {
+        let request_id = PartitionProcessorRpcRequestId::new();
+        let invocation_query = InvocationQuery::Invocation(InvocationId::mock_random());
+        let (response_tx, _) = Incoming::for_testing(
+            connection.downgrade(),
+            PartitionProcessorRpcRequest {
+                request_id,
+                partition_id: PartitionId::MIN,
+                inner: PartitionProcessorRpcRequestInner::ReadInvocationOutput(
+                    invocation_query.clone(),
+                    options,
+                ),
+            },
+            None,
+        )
+        .split();
+        (request_id, invocation_query, response_tx)
+    }
+
+    #[test(restate_core::test)]
+    async fn looks_up_and_reuses_read_index() -> anyhow::Result<()> {
+        let mut reads = replica_reads().await;
+        let (net_tx, _net_rx) = mpsc::channel(10);
+        let connection = OwnedConnection::new_fake(PEER, CURRENT_PROTOCOL_VERSION, net_tx);
+        let expected_read_index = reads
+            .bifrost
+            .find_tail(LogId::from(PartitionId::MIN))
+            .await?
+            .offset()
+            .prev();
+
+        // The first read looks up the read index
+        let (request_id, invocation_query, response_tx) =
+            read_request(&connection, options(Duration::from_secs(60)));
+        reads.register(
+            request_id,
+            invocation_query,
+            options(Duration::from_secs(60)),
+            response_tx,
+        );
+        assert!(reads.take_ready(Lsn::MAX).is_empty());
+        reads.next_read_index().await;
+
+        let ready = reads.take_ready(expected_read_index);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].request_id, request_id);
+
+        // Reads within the staleness bound reuse it
+        let (request_id, invocation_query, response_tx) =
+            read_request(&connection, options(Duration::from_secs(60)));
+        reads.register(
+            request_id,
+            invocation_query,
+            options(Duration::from_secs(60)),
+            response_tx,
+        );
+        assert!(reads.lookups.is_empty());
+        assert_eq!(reads.take_ready(expected_read_index).len(), 1);
+
+        // Reads which can't accept a stale read index look it up again
+        let (request_id, invocation_query, response_tx) =
+            read_request(&connection, options(Duration::ZERO));
+        reads.register(
+            request_id,
+            invocation_query,
+            options(Duration::ZERO),
+            response_tx,
+        );
+        assert_eq!(reads.lookups.len(), 1);
+        reads.next_read_index().await;
+        assert_eq!(reads.take_ready(expected_read_index).len(), 1);
+
+        Ok(())
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn read_index_lookup_times_out() -> anyhow::Result<()> {
+        let mut reads = replica_reads().await;
+        let (net_tx, mut net_rx) = mpsc::channel(10);
+        let connection = OwnedConnection::new_fake(PEER, CURRENT_PROTOCOL_VERSION, net_tx);
+
+        let (request_id, invocation_query, response_tx) =
+            read_request(&connection, options(Duration::ZERO));
+        let read = PendingRead {
+            request_id,
+            invocation_query,
+            response_tx,
+            read_index: Lsn::INVALID,
+            deadline: Instant::now() + Duration::from_secs(1),
+        };
+        reads.push_lookup(
+            read,
+            Instant::now(),
+            Duration::from_secs(1),
+            futures::future::pending(),
+        );
+        reads.next_read_index().await;
+        assert!(reads.take_ready(Lsn::MAX).is_empty());
+
+        let response = net_rx.recv().await.expect("response to be sent");
+        let response: Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError> = response
+            .body
+            .expect("response body")
+            .try_decode(connection.protocol_version())?;
+        let err = PartitionProcessorRpcClientError::from(response.unwrap_err());
+        assert!(matches!(
+            err,
+            PartitionProcessorRpcClientError::ReplicaLagging(PartitionId::MIN)
+        ));
+        assert!(err.is_safe_to_retry());
+
+        Ok(())
+    }
+}
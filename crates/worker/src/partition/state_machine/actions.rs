@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use bytes::Bytes;
 use restate_invoker_api::InvokeInputJournal;
 use restate_storage_api::outbox_table::OutboxMessage;
 use restate_storage_api::timer_table::TimerKey;
@@ -73,6 +74,18 @@ pub enum Action {
         invocation_id: InvocationId,
         retention: Duration,
     },
+    /// Delete the payloads of the invocation offloaded to the payload store, if any.
+    DeleteOffloadedPayloads {
+        invocation_id: InvocationId,
+    },
+    /// Copy the offloaded input of `source_invocation_id` referenced by `source` to the `target`
+    /// reference of `target_invocation_id`.
+    CopyOffloadedPayload {
+        source_invocation_id: InvocationId,
+        source: Bytes,
+        target_invocation_id: InvocationId,
+        target: Bytes,
+    },
 }
 
 impl Action {
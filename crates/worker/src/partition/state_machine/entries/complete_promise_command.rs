@@ -11,12 +11,13 @@
 use crate::debug_if_leader;
 use crate::partition::state_machine::entries::ApplyJournalCommandEffect;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
+use bytestring::ByteString;
 use restate_storage_api::fsm_table::FsmTable;
 use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
 use restate_storage_api::promise_table::{Promise, PromiseState, PromiseTable};
 use restate_storage_api::state_table::ReadOnlyStateTable;
 use restate_types::errors::ALREADY_COMPLETED_INVOCATION_ERROR;
-use restate_types::identifiers::WithInvocationId;
+use restate_types::identifiers::{ServiceId, WithInvocationId};
 use restate_types::invocation::{InvocationResponse, ResponseResult};
 use restate_types::journal_v2::{
     CompletePromiseCommand, CompletePromiseCompletion, CompletePromiseResult, CompletePromiseValue,
@@ -41,80 +42,7 @@ where
         let complete_result = if let Some(service_id) =
             invocation_metadata.invocation_target.as_keyed_service_id()
         {
-            // Load state and write completion
-            let promise_metadata = ctx
-                .storage
-                .get_promise(&service_id, &self.entry.key)
-                .await?;
-
-            match promise_metadata {
-                None => {
-                    debug_if_leader!(
-                        ctx.is_leader,
-                        rpc.service = %service_id.service_name,
-                        "Complete promise {} without listeners",
-                        self.entry.key
-                    );
-
-                    ctx.storage
-                        .put_promise(
-                            &service_id,
-                            &self.entry.key,
-                            &Promise {
-                                state: PromiseState::Completed(self.entry.value.clone().into()),
-                            },
-                        )
-                        .await;
-                    CompletePromiseResult::Void
-                }
-                Some(Promise {
-                    state: PromiseState::NotCompleted(listeners),
-                }) => {
-                    // Send response to listeners
-                    // TODO there's no point here to send an outbox message,
-                    //  because we have the guarantee the the listener has the same partition key, so we could just process the command now.
-                    //  Because we still miss the API for doing that, for now we use the outbox.
-                    for listener in listeners {
-                        ctx.handle_outgoing_message(OutboxMessage::ServiceResponse(
-                            InvocationResponse {
-                                id: listener.invocation_id(),
-                                entry_index: listener.journal_index(),
-                                result: match self.entry.value.clone() {
-                                    CompletePromiseValue::Success(s) => ResponseResult::Success(s),
-                                    CompletePromiseValue::Failure(f) => {
-                                        ResponseResult::Failure(f.into())
-                                    }
-                                },
-                            },
-                        ))
-                        .await?;
-                    }
-
-                    debug_if_leader!(
-                        ctx.is_leader,
-                        rpc.service = %service_id.service_name,
-                        "Complete promise {} with listeners waiting on it",
-                        self.entry.key
-                    );
-
-                    ctx.storage
-                        .put_promise(
-                            &service_id,
-                            &self.entry.key,
-                            &Promise {
-                                state: PromiseState::Completed(self.entry.value.clone().into()),
-                            },
-                        )
-                        .await;
-                    CompletePromiseResult::Void
-                }
-                Some(Promise {
-                    state: PromiseState::Completed(_),
-                }) => {
-                    // Conflict!
-                    CompletePromiseResult::Failure(ALREADY_COMPLETED_INVOCATION_ERROR.into())
-                }
-            }
+            complete_promise(ctx, &service_id, &self.entry.key, self.entry.value.clone()).await?
         } else {
             warn!(
                 "Trying to process entry {} for a target that has no promises",
@@ -131,3 +59,74 @@ where
         Ok(())
     }
 }
+
+/// Completes the promise `key` of the given workflow, notifying the listeners waiting on it.
+/// Returns a failure if the promise was already completed.
+pub(in crate::partition::state_machine) async fn complete_promise<S>(
+    ctx: &mut StateMachineApplyContext<'_, S>,
+    service_id: &ServiceId,
+    key: &ByteString,
+    value: CompletePromiseValue,
+) -> Result<CompletePromiseResult, Error>
+where
+    S: PromiseTable + FsmTable + OutboxTable,
+{
+    // Load state and write completion
+    let promise_metadata = ctx.storage.get_promise(service_id, key).await?;
+
+    match promise_metadata {
+        None => {
+            debug_if_leader!(
+                ctx.is_leader,
+                rpc.service = %service_id.service_name,
+                "Complete promise {} without listeners",
+                key
+            );
+        }
+        Some(Promise {
+            state: PromiseState::NotCompleted(listeners),
+        }) => {
+            // Send response to listeners
+            // TODO there's no point here to send an outbox message,
+            //  because we have the guarantee the the listener has the same partition key, so we could just process the command now.
+            //  Because we still miss the API for doing that, for now we use the outbox.
+            for listener in listeners {
+                ctx.handle_outgoing_message(OutboxMessage::ServiceResponse(InvocationResponse {
+                    id: listener.invocation_id(),
+                    entry_index: listener.journal_index(),
+                    result: match value.clone() {
+                        CompletePromiseValue::Success(s) => ResponseResult::Success(s),
+                        CompletePromiseValue::Failure(f) => ResponseResult::Failure(f.into()),
+                    },
+                }))
+                .await?;
+            }
+
+            debug_if_leader!(
+                ctx.is_leader,
+                rpc.service = %service_id.service_name,
+                "Complete promise {} with listeners waiting on it",
+                key
+            );
+        }
+        Some(Promise {
+            state: PromiseState::Completed(_),
+        }) => {
+            // Conflict!
+            return Ok(CompletePromiseResult::Failure(
+                ALREADY_COMPLETED_INVOCATION_ERROR.into(),
+            ));
+        }
+    }
+
+    ctx.storage
+        .put_promise(
+            service_id,
+            key,
+            &Promise {
+                state: PromiseState::Completed(value.into()),
+            },
+        )
+        .await;
+    Ok(CompletePromiseResult::Void)
+}
@@ -25,7 +25,13 @@ impl<'e, 'ctx: 'e, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContex
     for ApplyEventCommand<'e>
 {
     async fn apply(self, _ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
-        // TODO is there anything to do here?!
+        if self.entry.output_chunk_content().is_some() {
+            // Counted so that the completion and the output stream reads can tell whether the
+            // journal contains chunks without scanning it
+            if let Some(journal_metadata) = self.invocation_status.get_journal_metadata_mut() {
+                journal_metadata.output_chunks += 1;
+            }
+        }
         Ok(())
     }
 }
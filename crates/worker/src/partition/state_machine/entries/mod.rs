@@ -48,6 +48,7 @@ use crate::partition::state_machine::lifecycle::VerifyOrMigrateJournalTableToV2C
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
 use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
 use restate_storage_api::fsm_table::FsmTable;
+use restate_storage_api::invocation_event_table::InvocationEventTable;
 use restate_storage_api::invocation_status_table::{InvocationStatus, InvocationStatusTable};
 use restate_storage_api::journal_table as journal_table_v1;
 use restate_storage_api::journal_table_v2::JournalTable;
@@ -63,6 +64,8 @@ use restate_types::journal_v2::{
 use std::collections::VecDeque;
 use tracing::info;
 
+pub(super) use complete_promise_command::complete_promise;
+
 pub(super) struct OnJournalEntryCommand {
     pub(super) invocation_id: InvocationId,
     pub(super) invocation_status: InvocationStatus,
@@ -101,6 +104,7 @@ where
     S: JournalTable
         + journal_table_v1::JournalTable
         + InvocationStatusTable
+        + InvocationEventTable
         + TimerTable
         + FsmTable
         + OutboxTable
@@ -359,8 +363,11 @@ where
             )
             .await?;
 
-            // Update journal length
+            // Update journal length and size
             journal_meta.length += 1;
+            if let Some(size) = &mut journal_meta.size {
+                *size += entry.serialized_content_len() as u64;
+            }
         }
 
         // Update timestamps
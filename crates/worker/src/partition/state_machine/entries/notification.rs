@@ -10,6 +10,7 @@
 
 use crate::partition::state_machine::lifecycle::ResumeInvocationCommand;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
+use restate_storage_api::invocation_event_table::InvocationEventTable;
 use restate_storage_api::invocation_status_table::InvocationStatus;
 use restate_storage_api::journal_table_v2::ReadOnlyJournalTable;
 use restate_types::identifiers::InvocationId;
@@ -25,7 +26,7 @@ pub(super) struct ApplyNotificationCommand<'e> {
 impl<'e, 'ctx: 'e, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
     for ApplyNotificationCommand<'e>
 where
-    S: ReadOnlyJournalTable,
+    S: ReadOnlyJournalTable + InvocationEventTable,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
         if cfg!(debug_assertions) {
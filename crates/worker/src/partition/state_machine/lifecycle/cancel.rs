@@ -12,6 +12,7 @@ use crate::partition::state_machine::entries::OnJournalEntryCommand;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
 use restate_storage_api::fsm_table::FsmTable;
 use restate_storage_api::inbox_table::InboxTable;
+use restate_storage_api::invocation_event_table::InvocationEventTable;
 use restate_storage_api::invocation_status_table::{InvocationStatus, InvocationStatusTable};
 use restate_storage_api::journal_table;
 use restate_storage_api::journal_table_v2::JournalTable;
@@ -34,6 +35,7 @@ impl<'ctx, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>
 where
     S: JournalTable
         + InvocationStatusTable
+        + InvocationEventTable
         + InboxTable
         + FsmTable
         + StateTable
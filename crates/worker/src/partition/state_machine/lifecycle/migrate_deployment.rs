@@ -0,0 +1,91 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::debug_if_leader;
+use crate::partition::state_machine::{Action, CommandHandler, Error, StateMachineApplyContext};
+use restate_invoker_api::InvokeInputJournal;
+use restate_storage_api::invocation_status_table::{InvocationStatus, InvocationStatusTable};
+use restate_types::invocation::MigrateInvocationRequest;
+
+pub struct OnMigrateInvocationCommand {
+    pub invocation_status: InvocationStatus,
+    pub request: MigrateInvocationRequest,
+}
+
+impl<'ctx, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
+    for OnMigrateInvocationCommand
+where
+    S: InvocationStatusTable,
+{
+    async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
+        let MigrateInvocationRequest {
+            invocation_id,
+            from_deployment,
+            to_deployment,
+        } = self.request;
+        let mut invocation_status = self.invocation_status;
+        let is_invoked = matches!(invocation_status, InvocationStatus::Invoked(_));
+
+        let metadata = match &mut invocation_status {
+            InvocationStatus::Invoked(metadata) | InvocationStatus::Suspended { metadata, .. } => {
+                metadata
+            }
+            _ => {
+                debug_if_leader!(
+                    ctx.is_leader,
+                    "Ignoring migration of invocation {} because it's not in-flight anymore",
+                    invocation_id
+                );
+                return Ok(());
+            }
+        };
+        let Some(pinned_deployment) = metadata
+            .pinned_deployment
+            .as_mut()
+            .filter(|pinned_deployment| pinned_deployment.deployment_id == from_deployment)
+        else {
+            debug_if_leader!(
+                ctx.is_leader,
+                "Ignoring migration of invocation {} because it's not pinned to deployment {} anymore",
+                invocation_id,
+                from_deployment
+            );
+            return Ok(());
+        };
+
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.deployment.id = %to_deployment,
+            "Migrate invocation {} from deployment {}",
+            invocation_id,
+            from_deployment
+        );
+        // The service protocol version is retained, the new deployment was checked to support it.
+        pinned_deployment.deployment_id = to_deployment;
+        metadata.timestamps.update();
+        let invocation_target = metadata.invocation_target.clone();
+
+        if is_invoked {
+            // Restart the current attempt, so the invoker picks up the new deployment
+            ctx.send_abort_invocation_to_invoker(invocation_id, false);
+            ctx.action_collector.push(Action::Invoke {
+                invocation_id,
+                invocation_target,
+                invoke_input_journal: InvokeInputJournal::NoCachedJournal,
+            });
+        }
+
+        ctx.storage
+            .put_invocation_status(&invocation_id, &invocation_status)
+            .await;
+
+        Ok(())
+    }
+}
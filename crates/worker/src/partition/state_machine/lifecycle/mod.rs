@@ -9,13 +9,19 @@
 // by the Apache License, Version 2.0.
 
 mod cancel;
+mod migrate_deployment;
 mod migrate_journal_table;
 mod pinned_deployment;
+mod purge_service;
+mod restart;
 mod resume;
 mod suspend;
 
 pub(super) use cancel::OnCancelCommand;
+pub(super) use migrate_deployment::OnMigrateInvocationCommand;
 pub(super) use migrate_journal_table::VerifyOrMigrateJournalTableToV2Command;
 pub(super) use pinned_deployment::OnPinnedDeploymentCommand;
+pub(super) use purge_service::OnPurgeServiceCommand;
+pub(super) use restart::OnRestartInvocationCommand;
 pub(super) use resume::ResumeInvocationCommand;
 pub(super) use suspend::OnSuspendCommand;
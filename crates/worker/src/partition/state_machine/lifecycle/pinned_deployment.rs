@@ -12,6 +12,7 @@ use super::VerifyOrMigrateJournalTableToV2Command;
 
 use crate::debug_if_leader;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
+use restate_storage_api::invocation_event_table::{InvocationEventKind, InvocationEventTable};
 use restate_storage_api::invocation_status_table::{InvocationStatus, InvocationStatusTable};
 use restate_storage_api::{journal_table as journal_table_v1, journal_table_v2};
 use restate_types::deployment::PinnedDeployment;
@@ -27,7 +28,10 @@ pub struct OnPinnedDeploymentCommand {
 impl<'ctx, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
     for OnPinnedDeploymentCommand
 where
-    S: journal_table_v1::JournalTable + journal_table_v2::JournalTable + InvocationStatusTable,
+    S: journal_table_v1::JournalTable
+        + journal_table_v2::JournalTable
+        + InvocationStatusTable
+        + InvocationEventTable,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
         let mut in_flight_invocation_metadata = self
@@ -51,6 +55,14 @@ where
             restate.deployment.service_protocol_version = %self.pinned_deployment.service_protocol_version.as_repr(),
             "Store chosen deployment to storage"
         );
+        ctx.record_invocation_event(
+            self.invocation_id,
+            &in_flight_invocation_metadata.invocation_target,
+            InvocationEventKind::PinnedDeployment {
+                deployment_id: self.pinned_deployment.deployment_id,
+            },
+        )
+        .await?;
         in_flight_invocation_metadata.set_pinned_deployment(self.pinned_deployment);
         // We recreate the InvocationStatus in Invoked state as the invoker can notify the
         // chosen deployment_id only when the invocation is in-flight.
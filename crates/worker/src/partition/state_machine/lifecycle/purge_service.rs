@@ -0,0 +1,181 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashSet;
+
+use futures::TryStreamExt;
+
+use crate::debug_if_leader;
+use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
+use restate_storage_api::idempotency_table::{IdempotencyTable, ReadOnlyIdempotencyTable};
+use restate_storage_api::inbox_table::{InboxEntry, InboxTable, ReadOnlyInboxTable};
+use restate_storage_api::invocation_event_table::InvocationEventTable;
+use restate_storage_api::invocation_status_table::{
+    InvocationStatus, InvocationStatusTable, ReadOnlyInvocationStatusTable,
+};
+use restate_storage_api::journal_table::JournalTable;
+use restate_storage_api::journal_table_v2;
+use restate_storage_api::promise_table::{PromiseTable, ReadOnlyPromiseTable};
+use restate_storage_api::service_status_table::{
+    ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus, VirtualObjectStatusTable,
+};
+use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
+use restate_types::invocation::PurgeServiceRequest;
+
+/// Removes the data of a service within the requested key range of this partition. Everything
+/// still used by in-flight invocations is retained: the invocations themselves, their inbox
+/// entries, and the state and promises of the locked virtual objects/workflows.
+///
+/// The admin splits the purge of a partition in several requests, each sweeping a chunk of its
+/// key range, to bound the work done by a single command.
+pub struct OnPurgeServiceCommand {
+    pub request: PurgeServiceRequest,
+}
+
+impl<'ctx, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
+    for OnPurgeServiceCommand
+where
+    S: InvocationStatusTable
+        + InvocationEventTable
+        + IdempotencyTable
+        + VirtualObjectStatusTable
+        + StateTable
+        + PromiseTable
+        + InboxTable
+        + JournalTable
+        + journal_table_v2::JournalTable,
+{
+    async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
+        let PurgeServiceRequest {
+            service_name,
+            partition_key_range: requested_range,
+        } = self.request;
+        // Sweep only the requested keys owned by this partition
+        let partition_key_range = *requested_range.start().max(ctx.partition_key_range.start())
+            ..=*requested_range.end().min(ctx.partition_key_range.end());
+        if partition_key_range.is_empty() {
+            return Ok(());
+        }
+
+        let locked_service_ids: HashSet<_> = ctx
+            .storage
+            .all_virtual_object_statuses(partition_key_range.clone())
+            .try_filter_map(|(service_id, status)| {
+                let locked = service_id.service_name == service_name
+                    && matches!(status, VirtualObjectStatus::Locked(_));
+                futures::future::ok(locked.then_some(service_id))
+            })
+            .try_collect()
+            .await?;
+
+        let completed_invocations: Vec<_> = ctx
+            .storage
+            .all_invocation_statuses(partition_key_range.clone())
+            .try_filter_map(|(invocation_id, status)| {
+                let completed = matches!(
+                    &status,
+                    InvocationStatus::Completed(completed)
+                        if completed.invocation_target.service_name() == &service_name
+                );
+                futures::future::ok(completed.then_some(invocation_id))
+            })
+            .try_collect()
+            .await?;
+        for invocation_id in &completed_invocations {
+            // Cleans up the idempotency key too, and the workflow state/promises
+            ctx.on_purge_invocation(*invocation_id).await?;
+        }
+
+        // Idempotency keys whose invocation is already gone
+        let idempotency_ids: Vec<_> = ctx
+            .storage
+            .all_idempotency_metadata(partition_key_range.clone())
+            .try_filter(|(idempotency_id, _)| {
+                futures::future::ready(idempotency_id.service_name == service_name)
+            })
+            .try_collect()
+            .await?;
+        let mut purged_idempotency_ids = 0;
+        for (idempotency_id, metadata) in idempotency_ids {
+            if matches!(
+                ctx.storage
+                    .get_invocation_status(&metadata.invocation_id)
+                    .await?,
+                InvocationStatus::Free
+            ) {
+                ctx.do_delete_idempotency_id(idempotency_id).await?;
+                purged_idempotency_ids += 1;
+            }
+        }
+
+        let state_service_ids: HashSet<_> = ctx
+            .storage
+            .get_all_user_states(partition_key_range.clone())
+            .try_filter_map(|(service_id, _, _)| {
+                let purge = service_id.service_name == service_name
+                    && !locked_service_ids.contains(&service_id);
+                futures::future::ok(purge.then_some(service_id))
+            })
+            .try_collect()
+            .await?;
+        for service_id in &state_service_ids {
+            ctx.storage.delete_all_user_state(service_id).await?;
+        }
+
+        let promise_service_ids: HashSet<_> = ctx
+            .storage
+            .all_promises(partition_key_range.clone())
+            .try_filter_map(|promise| {
+                let purge = promise.service_id.service_name == service_name
+                    && !locked_service_ids.contains(&promise.service_id);
+                futures::future::ok(purge.then_some(promise.service_id))
+            })
+            .try_collect()
+            .await?;
+        for service_id in &promise_service_ids {
+            ctx.storage.delete_all_promises(service_id).await;
+        }
+
+        // Inboxed invocations are in-flight, only the pending state mutations are removed
+        let state_mutations: Vec<_> = ctx
+            .storage
+            .all_inboxes(partition_key_range.clone())
+            .try_filter_map(|entry| {
+                let purge = matches!(
+                    &entry.inbox_entry,
+                    InboxEntry::StateMutation(mutation) if mutation.service_id.service_name == service_name
+                );
+                futures::future::ok(
+                    purge.then(|| (entry.service_id().clone(), entry.inbox_sequence_number)),
+                )
+            })
+            .try_collect()
+            .await?;
+        for (service_id, sequence_number) in &state_mutations {
+            ctx.do_delete_inbox_entry(service_id.clone(), *sequence_number)
+                .await?;
+        }
+
+        debug_if_leader!(
+            ctx.is_leader,
+            rpc.service = %service_name,
+            "Purged service in the keys {:?}: {} completed invocations, {} idempotency keys, state of {} keys, promises of {} keys, {} inboxed state mutations. Retained the data of {} locked keys",
+            partition_key_range,
+            completed_invocations.len(),
+            purged_idempotency_ids,
+            state_service_ids.len(),
+            promise_service_ids.len(),
+            state_mutations.len(),
+            locked_service_ids.len()
+        );
+
+        Ok(())
+    }
+}
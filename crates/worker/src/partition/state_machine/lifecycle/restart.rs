@@ -0,0 +1,421 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::debug_if_leader;
+use crate::partition::state_machine::lifecycle::ResumeInvocationCommand;
+use crate::partition::state_machine::{
+    should_use_journal_table_v2, Action, CommandHandler, Error, StateMachineApplyContext,
+};
+use bytes::Bytes;
+use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
+use restate_storage_api::fsm_table::FsmTable;
+use restate_storage_api::invocation_event_table::InvocationEventTable;
+use restate_storage_api::invocation_status_table::{InvocationStatus, InvocationStatusTable};
+use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
+use restate_storage_api::timer_table::{Timer, TimerKey, TimerTable};
+use restate_storage_api::{journal_table as journal_table_v1, journal_table_v2};
+use restate_types::identifiers::{EntryIndex, InvocationId, WithPartitionKey};
+use restate_types::invocation::{
+    Header, InvocationTargetType, RestartMode, ServiceInvocation, ServiceInvocationSpanContext,
+    Source, WorkflowHandlerType,
+};
+use restate_types::journal as journal_v1;
+use restate_types::journal::enriched::EnrichedEntryHeader;
+use restate_types::journal_v2::command::{CommandMetadata, InputCommand};
+use restate_types::journal_v2::raw::{RawEntry, RawEntryInner};
+use restate_types::journal_v2::{Command, CommandType, EntryType, NotificationId};
+use restate_types::payload_reference::rebase_payload_reference;
+use std::collections::HashSet;
+use tracing::warn;
+
+pub struct OnRestartInvocationCommand {
+    pub invocation_id: InvocationId,
+    pub invocation_status: InvocationStatus,
+    pub mode: RestartMode,
+}
+
+impl<'ctx, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
+    for OnRestartInvocationCommand
+where
+    S: journal_table_v1::JournalTable
+        + journal_table_v2::JournalTable
+        + InvocationStatusTable
+        + InvocationEventTable
+        + TimerTable
+        + OutboxTable
+        + FsmTable,
+{
+    async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
+        let OnRestartInvocationCommand {
+            invocation_id,
+            mut invocation_status,
+            mode,
+        } = self;
+        let uses_journal_table_v2 = should_use_journal_table_v2(&invocation_status);
+        let is_invoked = matches!(invocation_status, InvocationStatus::Invoked(_));
+
+        let Some(metadata) = invocation_status.get_invocation_metadata_mut() else {
+            debug_if_leader!(
+                ctx.is_leader,
+                "Ignoring restart of invocation {} because it's not in-flight",
+                invocation_id
+            );
+            return Ok(());
+        };
+
+        match mode {
+            RestartMode::AsNew { new_invocation_id } => {
+                if new_invocation_id.partition_key() != invocation_id.partition_key() {
+                    warn!(
+                        "Ignoring restart of invocation {} because the new invocation id {} belongs to another partition",
+                        invocation_id, new_invocation_id
+                    );
+                    return Ok(());
+                }
+                if metadata.invocation_target.invocation_target_ty()
+                    == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
+                {
+                    // The id of the workflow run is deterministic, there can't be a new one
+                    warn!(
+                        "Ignoring restart of invocation {} because workflow runs can only be restarted in place",
+                        invocation_id
+                    );
+                    return Ok(());
+                }
+
+                let (headers, mut argument) = read_input(ctx, invocation_id).await?;
+                // The offloaded input is owned by the restarted invocation, which might be
+                // purged before the new one, hence the new invocation gets its own copy. A
+                // reference to the input of another invocation is not rebased, the invoker
+                // refuses to resolve it.
+                if let Some(target) =
+                    rebase_payload_reference(&argument, &invocation_id, &new_invocation_id)
+                {
+                    ctx.action_collector.push(Action::CopyOffloadedPayload {
+                        source_invocation_id: invocation_id,
+                        source: argument,
+                        target_invocation_id: new_invocation_id,
+                        target: target.clone(),
+                    });
+                    argument = target;
+                }
+                debug_if_leader!(
+                    ctx.is_leader,
+                    "Restart invocation {} as new invocation {}",
+                    invocation_id,
+                    new_invocation_id
+                );
+
+                let service_invocation = ServiceInvocation {
+                    invocation_id: new_invocation_id,
+                    invocation_target: metadata.invocation_target.clone(),
+                    argument,
+                    source: Source::Restart(invocation_id),
+                    span_context: ServiceInvocationSpanContext::start(
+                        &new_invocation_id,
+                        metadata.journal_metadata.span_context.as_linked(),
+                    ),
+                    headers,
+                    execution_time: None,
+                    completion_retention_duration: Some(metadata.completion_retention_duration),
+                    idempotency_key: None,
+                    response_sink: None,
+                    submit_notification_sink: None,
+                };
+                ctx.handle_outgoing_message(OutboxMessage::ServiceInvocation(service_invocation))
+                    .await?;
+            }
+            RestartMode::InPlace { from_index } => {
+                let journal_length = metadata.journal_metadata.length;
+                if from_index == 0 || from_index > journal_length {
+                    warn!(
+                        "Ignoring restart of invocation {} because the journal index {} is out of range 1..={}",
+                        invocation_id, from_index, journal_length
+                    );
+                    return Ok(());
+                }
+
+                // Completions of the removed entries can't be told apart from the ones of the
+                // re-executed entries, which reuse the same entry indexes and completion ids.
+                // Hence we restart only when no completion is pending in the removed entries,
+                // except for sleeps, whose timers are removed together with them.
+                let truncated = if uses_journal_table_v2 {
+                    scan_truncated_journal_v2(ctx, invocation_id, from_index, journal_length)
+                        .await?
+                } else {
+                    scan_truncated_journal_v1(ctx, invocation_id, from_index, journal_length)
+                        .await?
+                };
+                if truncated.pending_completions > 0 {
+                    warn!(
+                        "Ignoring restart of invocation {} because {} entries from index {} are still awaiting a completion. Wait for them to complete, or restart from a later index",
+                        invocation_id, truncated.pending_completions, from_index
+                    );
+                    return Ok(());
+                }
+
+                debug_if_leader!(
+                    ctx.is_leader,
+                    restate.journal.length = journal_length,
+                    "Restart invocation {} truncating the journal from index {}, keeping {} notifications",
+                    invocation_id,
+                    from_index,
+                    truncated.kept_notifications.len()
+                );
+
+                for timer_key in truncated.sleep_timers {
+                    ctx.do_delete_timer(timer_key).await?;
+                }
+                let mut new_journal_length = from_index;
+                if uses_journal_table_v2 {
+                    journal_table_v2::JournalTable::truncate_journal(
+                        ctx.storage,
+                        invocation_id,
+                        from_index,
+                        journal_length,
+                    )
+                    .await?;
+                    // Notifications are appended when they arrive, hence the removed entries
+                    // contain the late completions of the kept commands, and signals.
+                    for notification in &truncated.kept_notifications {
+                        journal_table_v2::JournalTable::put_journal_entry(
+                            ctx.storage,
+                            invocation_id,
+                            new_journal_length,
+                            notification,
+                            &[],
+                        )
+                        .await?;
+                        new_journal_length += 1;
+                    }
+                } else {
+                    journal_table_v1::JournalTable::truncate_journal(
+                        ctx.storage,
+                        &invocation_id,
+                        from_index,
+                        journal_length,
+                    )
+                    .await;
+                }
+                metadata.journal_metadata.length = new_journal_length;
+                metadata.journal_metadata.size = metadata
+                    .journal_metadata
+                    .size
+                    .map(|size| size.saturating_sub(truncated.size));
+                metadata.journal_metadata.output_chunks = metadata
+                    .journal_metadata
+                    .output_chunks
+                    .saturating_sub(truncated.output_chunks);
+
+                if is_invoked {
+                    // Stop the current attempt, the invocation is restarted with the truncated journal
+                    ctx.send_abort_invocation_to_invoker(invocation_id, false);
+                }
+                ResumeInvocationCommand {
+                    invocation_id,
+                    invocation_status: &mut invocation_status,
+                }
+                .apply(ctx)
+                .await?;
+
+                ctx.storage
+                    .put_invocation_status(&invocation_id, &invocation_status)
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads headers and argument of the input entry, from either journal table.
+async fn read_input<S>(
+    ctx: &mut StateMachineApplyContext<'_, S>,
+    invocation_id: InvocationId,
+) -> Result<(Vec<Header>, Bytes), Error>
+where
+    S: journal_table_v1::JournalTable + journal_table_v2::JournalTable,
+{
+    const INPUT_INDEX: EntryIndex = 0;
+
+    if let Some(entry) = journal_table_v2::ReadOnlyJournalTable::get_journal_entry(
+        ctx.storage,
+        invocation_id,
+        INPUT_INDEX,
+    )
+    .await?
+    {
+        let input = entry.decode::<ServiceProtocolV4Codec, InputCommand>()?;
+        return Ok((input.headers, input.payload));
+    }
+
+    match journal_table_v1::ReadOnlyJournalTable::get_journal_entry(
+        ctx.storage,
+        &invocation_id,
+        INPUT_INDEX,
+    )
+    .await?
+    {
+        Some(journal_table_v1::JournalEntry::Entry(entry)) => {
+            match entry.deserialize_entry_ref::<ProtobufRawEntryCodec>()? {
+                journal_v1::Entry::Input(journal_v1::InputEntry { headers, value }) => {
+                    Ok((headers, value))
+                }
+                _ => Err(Error::BadEntryVariant(EntryType::Command(
+                    CommandType::Input,
+                ))),
+            }
+        }
+        _ => Err(Error::BadEntryVariant(EntryType::Command(
+            CommandType::Input,
+        ))),
+    }
+}
+
+/// The entries removed by an in-place restart.
+#[derive(Default)]
+struct TruncatedJournal {
+    /// Serialized size of the removed entries
+    size: u64,
+    /// Number of removed output chunks
+    output_chunks: u32,
+    /// Timers of the removed sleeps, which would otherwise complete the re-executed entries
+    sleep_timers: Vec<TimerKey>,
+    /// Number of removed entries still awaiting a completion, sleeps excluded
+    pending_completions: usize,
+    /// Notifications in the removed range which don't complete a removed command, to be appended
+    /// again after the truncation. Only used by the journal table v2, where completions are
+    /// separate entries.
+    kept_notifications: Vec<RawEntry>,
+}
+
+async fn scan_truncated_journal_v2<S>(
+    ctx: &mut StateMachineApplyContext<'_, S>,
+    invocation_id: InvocationId,
+    from_index: EntryIndex,
+    journal_length: EntryIndex,
+) -> Result<TruncatedJournal, Error>
+where
+    S: journal_table_v2::JournalTable,
+{
+    let notifications =
+        journal_table_v2::ReadOnlyJournalTable::get_notifications_index(ctx.storage, invocation_id)
+            .await?;
+
+    let mut truncated = TruncatedJournal::default();
+    let mut removed_completion_ids = HashSet::new();
+    let mut notifications_in_range = vec![];
+    for journal_index in from_index..journal_length {
+        let Some(entry) = journal_table_v2::ReadOnlyJournalTable::get_journal_entry(
+            ctx.storage,
+            invocation_id,
+            journal_index,
+        )
+        .await?
+        else {
+            continue;
+        };
+        if let RawEntryInner::Notification(notification) = &entry.inner {
+            notifications_in_range.push((notification.id(), entry));
+            continue;
+        }
+        truncated.size += entry.serialized_content_len() as u64;
+        if let RawEntryInner::Event(event) = &entry.inner {
+            if event.output_chunk_content().is_some() {
+                truncated.output_chunks += 1;
+            }
+            continue;
+        }
+        if !matches!(entry.ty(), EntryType::Command(_)) {
+            continue;
+        }
+        let command = entry.decode::<ServiceProtocolV4Codec, Command>()?;
+        let completion_ids = command.related_completion_ids();
+        match command {
+            Command::Sleep(sleep) => {
+                if !notifications.contains_key(&NotificationId::CompletionId(sleep.completion_id)) {
+                    let (timer_key, _) = Timer::complete_journal_entry(
+                        sleep.wake_up_time.as_u64(),
+                        invocation_id,
+                        sleep.completion_id,
+                    );
+                    truncated.sleep_timers.push(timer_key);
+                }
+            }
+            // Completed by the attempt which gets aborted
+            Command::Run(_) => {}
+            _ => {
+                if completion_ids.iter().any(|completion_id| {
+                    !notifications.contains_key(&NotificationId::CompletionId(*completion_id))
+                }) {
+                    truncated.pending_completions += 1;
+                }
+            }
+        }
+        removed_completion_ids.extend(completion_ids);
+    }
+
+    // Completions always follow their command, so all the removed commands are known by now
+    for (notification_id, entry) in notifications_in_range {
+        match notification_id {
+            NotificationId::CompletionId(completion_id)
+                if removed_completion_ids.contains(&completion_id) =>
+            {
+                truncated.size += entry.serialized_content_len() as u64;
+            }
+            _ => truncated.kept_notifications.push(entry),
+        }
+    }
+    Ok(truncated)
+}
+
+async fn scan_truncated_journal_v1<S>(
+    ctx: &mut StateMachineApplyContext<'_, S>,
+    invocation_id: InvocationId,
+    from_index: EntryIndex,
+    journal_length: EntryIndex,
+) -> Result<TruncatedJournal, Error>
+where
+    S: journal_table_v1::JournalTable,
+{
+    let mut truncated = TruncatedJournal::default();
+    for journal_index in from_index..journal_length {
+        let Some(journal_table_v1::JournalEntry::Entry(entry)) =
+            journal_table_v1::ReadOnlyJournalTable::get_journal_entry(
+                ctx.storage,
+                &invocation_id,
+                journal_index,
+            )
+            .await?
+        else {
+            continue;
+        };
+        truncated.size += entry.serialized_entry().len() as u64;
+        match entry.header() {
+            EnrichedEntryHeader::Sleep {
+                is_completed: false,
+            } => {
+                if let journal_v1::Entry::Sleep(journal_v1::SleepEntry { wake_up_time, .. }) =
+                    entry.deserialize_entry_ref::<ProtobufRawEntryCodec>()?
+                {
+                    let (timer_key, _) =
+                        Timer::complete_journal_entry(wake_up_time, invocation_id, journal_index);
+                    truncated.sleep_timers.push(timer_key);
+                }
+            }
+            header if header.is_completed() == Some(false) => {
+                truncated.pending_completions += 1;
+            }
+            _ => {}
+        }
+    }
+    Ok(truncated)
+}
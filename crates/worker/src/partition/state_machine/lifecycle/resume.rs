@@ -11,6 +11,7 @@
 use crate::debug_if_leader;
 use crate::partition::state_machine::{Action, CommandHandler, Error, StateMachineApplyContext};
 use restate_invoker_api::InvokeInputJournal;
+use restate_storage_api::invocation_event_table::{InvocationEventKind, InvocationEventTable};
 use restate_storage_api::invocation_status_table::InvocationStatus;
 use restate_types::identifiers::InvocationId;
 
@@ -21,8 +22,11 @@ pub struct ResumeInvocationCommand<'e> {
 
 impl<'e, 'ctx: 'e, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
     for ResumeInvocationCommand<'e>
+where
+    S: InvocationEventTable,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
+        let is_suspended = matches!(self.invocation_status, InvocationStatus::Suspended { .. });
         let metadata = match self.invocation_status {
             InvocationStatus::Suspended { metadata, .. } | InvocationStatus::Invoked(metadata) => {
                 metadata
@@ -47,6 +51,14 @@ impl<'e, 'ctx: 'e, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContex
         metadata.timestamps.update();
         *self.invocation_status = InvocationStatus::Invoked(metadata.clone());
 
+        if is_suspended {
+            ctx.record_invocation_event(
+                self.invocation_id,
+                &invocation_target,
+                InvocationEventKind::Resumed,
+            )
+            .await?;
+        }
         ctx.action_collector.push(Action::Invoke {
             invocation_id: self.invocation_id,
             invocation_target,
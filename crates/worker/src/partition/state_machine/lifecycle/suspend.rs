@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
+use restate_storage_api::invocation_event_table::{InvocationEventKind, InvocationEventTable};
 use restate_storage_api::invocation_status_table::{InvocationStatus, InvocationStatusTable};
 use restate_storage_api::journal_table_v2::ReadOnlyJournalTable;
 use restate_types::identifiers::InvocationId;
@@ -25,7 +26,7 @@ pub struct OnSuspendCommand {
 impl<'ctx, 's: 'ctx, S> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S>>
     for OnSuspendCommand
 where
-    S: ReadOnlyJournalTable + InvocationStatusTable,
+    S: ReadOnlyJournalTable + InvocationStatusTable + InvocationEventTable,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S>) -> Result<(), Error> {
         debug_assert!(
@@ -72,6 +73,12 @@ where
             );
 
             in_flight_invocation_metadata.timestamps.update();
+            ctx.record_invocation_event(
+                self.invocation_id,
+                &in_flight_invocation_metadata.invocation_target,
+                InvocationEventKind::Suspended,
+            )
+            .await?;
             invocation_status = InvocationStatus::Suspended {
                 metadata: in_flight_invocation_metadata,
                 waiting_for_notifications: self.waiting_for_notifications,
@@ -13,7 +13,11 @@ mod entries;
 mod lifecycle;
 mod utils;
 
-use crate::metric_definitions::PARTITION_APPLY_COMMAND;
+use crate::metric_definitions::{
+    invocation_event_status, PARTITION_APPLY_COMMAND, PARTITION_INVOCATIONS,
+    PARTITION_INVOCATION_DURATION, PARTITION_INVOCATION_JOURNAL_ENTRIES,
+    PARTITION_INVOCATION_JOURNAL_SIZE, STATUS_LABEL,
+};
 use crate::partition::state_machine::lifecycle::OnCancelCommand;
 use crate::partition::types::{InvokerEffect, InvokerEffectKind, OutboxMessageExt};
 use ::tracing::{debug, trace, warn, Instrument, Span};
@@ -23,7 +27,8 @@ use bytes::Bytes;
 use bytestring::ByteString;
 use enumset::EnumSet;
 use futures::{StreamExt, TryStreamExt};
-use metrics::{histogram, Histogram};
+use metrics::{counter, histogram, Histogram, Label};
+use restate_core::metric_labels::{service_handler_labels, service_labels};
 use restate_invoker_api::InvokeInputJournal;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
@@ -31,9 +36,12 @@ use restate_storage_api::fsm_table::FsmTable;
 use restate_storage_api::idempotency_table::IdempotencyMetadata;
 use restate_storage_api::idempotency_table::{IdempotencyTable, ReadOnlyIdempotencyTable};
 use restate_storage_api::inbox_table::{InboxEntry, InboxTable};
+use restate_storage_api::invocation_event_table::{
+    InvocationEvent, InvocationEventKind, InvocationEventTable,
+};
 use restate_storage_api::invocation_status_table::{
     CompletedInvocation, InFlightInvocationMetadata, InboxedInvocation, InvocationStatusTable,
-    PreFlightInvocationMetadata, ReadOnlyInvocationStatusTable,
+    JournalMetadata, PreFlightInvocationMetadata, ReadOnlyInvocationStatusTable,
 };
 use restate_storage_api::invocation_status_table::{InvocationStatus, ScheduledInvocation};
 use restate_storage_api::journal_table::ReadOnlyJournalTable;
@@ -50,7 +58,7 @@ use restate_storage_api::timer_table::{Timer, TimerTable};
 use restate_storage_api::Result as StorageResult;
 use restate_tracing_instrumentation as instrumentation;
 use restate_types::errors::{
-    GenericError, InvocationErrorCode, ALREADY_COMPLETED_INVOCATION_ERROR,
+    codes, GenericError, InvocationError, InvocationErrorCode, ALREADY_COMPLETED_INVOCATION_ERROR,
     ATTACH_NOT_SUPPORTED_INVOCATION_ERROR, CANCELED_INVOCATION_ERROR, KILLED_INVOCATION_ERROR,
     NOT_FOUND_INVOCATION_ERROR, NOT_READY_INVOCATION_ERROR,
     WORKFLOW_ALREADY_INVOKED_INVOCATION_ERROR,
@@ -63,12 +71,13 @@ use restate_types::identifiers::{
     IdempotencyId, JournalEntryId, WithInvocationId, WithPartitionKey,
 };
 use restate_types::invocation::{
-    AttachInvocationRequest, InvocationQuery, InvocationResponse, InvocationTarget,
-    InvocationTargetType, InvocationTermination, NotifySignalRequest, ResponseResult,
-    ServiceInvocation, ServiceInvocationResponseSink, ServiceInvocationSpanContext, Source,
-    SubmitNotificationSink, TerminationFlavor, VirtualObjectHandlerType, WorkflowHandlerType,
+    AttachInvocationRequest, CompletePromiseRequest, InvocationQuery, InvocationResponse,
+    InvocationTarget, InvocationTargetType, InvocationTermination, NotifySignalRequest,
+    ResponseResult, RestartInvocationRequest, ServiceInvocation, ServiceInvocationResponseSink,
+    ServiceInvocationSpanContext, Source, SubmitNotificationSink, TerminationFlavor,
+    VirtualObjectHandlerType, WorkflowHandlerType,
 };
-use restate_types::invocation::{InvocationInput, SpanRelation};
+use restate_types::invocation::{InvocationInput, JournalLimits, SpanRelation};
 use restate_types::journal::enriched::EnrichedRawEntry;
 use restate_types::journal::enriched::{
     AwakeableEnrichmentResult, CallEnrichmentResult, EnrichedEntryHeader,
@@ -83,8 +92,9 @@ use restate_types::journal_v2::command::{OutputCommand, OutputResult};
 use restate_types::journal_v2::raw::RawNotification;
 use restate_types::journal_v2::{
     AttachInvocationCompletion, AttachInvocationResult, CallCompletion, CallResult, CommandType,
-    CompletionId, EntryMetadata, GetInvocationOutputCompletion, GetInvocationOutputResult,
-    GetPromiseCompletion, GetPromiseResult, NotificationId, Signal, SignalResult, SleepCompletion,
+    CompletePromiseResult, CompletePromiseValue, CompletionId, EntryMetadata,
+    GetInvocationOutputCompletion, GetInvocationOutputResult, GetPromiseCompletion,
+    GetPromiseResult, NotificationId, Signal, SignalResult, SleepCompletion,
 };
 use restate_types::message::MessageIndex;
 use restate_types::net::partition_processor::IngressResponseResult;
@@ -115,6 +125,34 @@ pub enum ExperimentalFeature {
     InvocationStatusKilled,
 }
 
+/// Returns the error to fail the invocation with, if appending an entry of the given size to the
+/// journal would exceed the limits.
+fn check_journal_limits(
+    journal_limits: &JournalLimits,
+    journal_metadata: &JournalMetadata,
+    entry_size: u64,
+) -> Option<InvocationError> {
+    if let Some(max_entries) = journal_limits.max_entries {
+        if journal_metadata.length >= max_entries {
+            return Some(InvocationError::new(
+                codes::JOURNAL_TOO_LARGE,
+                format!("The journal exceeds the limit of {max_entries} entries"),
+            ));
+        }
+    }
+    // The size of the journals of invocations started before the sizes were tracked is
+    // unknown, hence they're not subject to the size limit.
+    if let (Some(max_size), Some(size)) = (journal_limits.max_size, journal_metadata.size) {
+        if size + entry_size > max_size {
+            return Some(InvocationError::new(
+                codes::JOURNAL_TOO_LARGE,
+                format!("The journal exceeds the size limit of {max_size} bytes"),
+            ));
+        }
+    }
+    None
+}
+
 pub struct StateMachine {
     // initialized from persistent storage
     inbox_seq_number: MessageIndex,
@@ -127,6 +165,10 @@ pub struct StateMachine {
 
     /// Enabled experimental features.
     experimental_features: EnumSet<ExperimentalFeature>,
+    /// Journal limits last announced by a leader, see [`JournalLimits`].
+    journal_limits: JournalLimits,
+    /// Number of lifecycle events retained for each invocation, see [`InvocationEventTable`].
+    invocation_events_retention: usize,
 }
 
 impl Debug for StateMachine {
@@ -200,6 +242,8 @@ impl StateMachine {
         outbox_head_seq_number: Option<MessageIndex>,
         partition_key_range: RangeInclusive<PartitionKey>,
         experimental_features: EnumSet<ExperimentalFeature>,
+        journal_limits: JournalLimits,
+        invocation_events_retention: usize,
     ) -> Self {
         let invoker_apply_latency =
             histogram!(crate::metric_definitions::PARTITION_HANDLE_INVOKER_EFFECT_COMMAND);
@@ -210,6 +254,8 @@ impl StateMachine {
             partition_key_range,
             invoker_apply_latency,
             experimental_features,
+            journal_limits,
+            invocation_events_retention,
         }
     }
 }
@@ -223,6 +269,8 @@ pub(crate) struct StateMachineApplyContext<'a, S> {
     partition_key_range: RangeInclusive<PartitionKey>,
     invoker_apply_latency: &'a Histogram,
     experimental_features: &'a EnumSet<ExperimentalFeature>,
+    journal_limits: &'a mut JournalLimits,
+    invocation_events_retention: usize,
     is_leader: bool,
 }
 
@@ -252,6 +300,8 @@ impl StateMachine {
                 partition_key_range: self.partition_key_range.clone(),
                 invoker_apply_latency: &self.invoker_apply_latency,
                 experimental_features: &self.experimental_features,
+                journal_limits: &mut self.journal_limits,
+                invocation_events_retention: self.invocation_events_retention,
                 is_leader,
             }
             .on_apply(command)
@@ -281,6 +331,33 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         Ok(status)
     }
 
+    async fn record_invocation_event(
+        &mut self,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        event_kind: InvocationEventKind,
+    ) -> Result<(), Error>
+    where
+        S: InvocationEventTable,
+    {
+        if self.is_leader {
+            if let Some(status) = invocation_event_status(&event_kind) {
+                let mut labels = service_labels(invocation_target.service_name());
+                labels.push(Label::new(STATUS_LABEL, status));
+                counter!(PARTITION_INVOCATIONS, labels).increment(1);
+            }
+        }
+
+        self.storage
+            .append_invocation_event(
+                &invocation_id,
+                &InvocationEvent::now(event_kind),
+                self.invocation_events_retention,
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn register_timer(
         &mut self,
         timer_value: TimerKeyValue,
@@ -386,6 +463,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             + PromiseTable
             + JournalTable
             + InvocationStatusTable
+            + InvocationEventTable
             + OutboxTable
             + FsmTable
             + TimerTable
@@ -444,9 +522,54 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                 self.on_purge_invocation(purge_invocation_request.invocation_id)
                     .await
             }
+            Command::PurgeService(purge_service_request) => {
+                lifecycle::OnPurgeServiceCommand {
+                    request: purge_service_request,
+                }
+                .apply(self)
+                .await
+            }
             Command::PatchState(mutation) => self.handle_external_state_mutation(mutation).await,
-            Command::AnnounceLeader(_) => {
-                // no-op :-)
+            Command::MigrateInvocation(migrate_invocation_request) => {
+                lifecycle::OnMigrateInvocationCommand {
+                    invocation_status: self
+                        .get_invocation_status(&migrate_invocation_request.invocation_id)
+                        .await?,
+                    request: migrate_invocation_request,
+                }
+                .apply(self)
+                .await
+            }
+            Command::RestartInvocation(RestartInvocationRequest {
+                invocation_id,
+                mode,
+            }) => {
+                lifecycle::OnRestartInvocationCommand {
+                    invocation_id,
+                    invocation_status: self.get_invocation_status(&invocation_id).await?,
+                    mode,
+                }
+                .apply(self)
+                .await
+            }
+            Command::CompletePromise(complete_promise_request) => {
+                self.handle_external_promise_completion(complete_promise_request)
+                    .await
+            }
+            Command::AnnounceLeader(announce_leader) => {
+                // The limits configured on the leader apply to all the replicas, so that they fail
+                // the same invocations regardless of their own configuration.
+                if let Some(journal_limits) = announce_leader.journal_limits {
+                    if *self.journal_limits != journal_limits {
+                        debug_if_leader!(
+                            self.is_leader,
+                            "Applying the journal limits announced by the leader: {:?}",
+                            journal_limits
+                        );
+                        *self.journal_limits = journal_limits;
+                        self.storage.put_journal_limits(journal_limits).await;
+                    }
+                }
                 Ok(())
             }
             Command::ScheduleTimer(timer) => {
@@ -489,6 +612,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     where
         S: IdempotencyTable
             + InvocationStatusTable
+            + InvocationEventTable
             + OutboxTable
             + FsmTable
             + VirtualObjectStatusTable
@@ -519,6 +643,12 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             // Invocation was deduplicated, nothing else to do here
             return Ok(());
         };
+        self.record_invocation_event(
+            invocation_id,
+            &service_invocation.invocation_target,
+            InvocationEventKind::Created,
+        )
+        .await?;
 
         // Prepare PreFlightInvocationMetadata structure
         let submit_notification_sink = service_invocation.submit_notification_sink.take();
@@ -578,6 +708,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     where
         S: IdempotencyTable
             + InvocationStatusTable
+            + InvocationEventTable
             + VirtualObjectStatusTable
             + OutboxTable
             + FsmTable,
@@ -762,10 +893,11 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         metadata: PreFlightInvocationMetadata,
     ) -> Result<Option<PreFlightInvocationMetadata>, Error>
     where
-        S: TimerTable + InvocationStatusTable,
+        S: TimerTable + InvocationStatusTable + InvocationEventTable,
     {
         if let Some(execution_time) = metadata.execution_time {
             let span_context = metadata.span_context.clone();
+            let invocation_target = metadata.invocation_target.clone();
             debug_if_leader!(self.is_leader, "Store scheduled invocation");
 
             self.register_timer(
@@ -782,6 +914,12 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                     ),
                 )
                 .await;
+            self.record_invocation_event(
+                invocation_id,
+                &invocation_target,
+                InvocationEventKind::Scheduled { execution_time },
+            )
+            .await?;
             // The span will be created later on invocation
             return Ok(None);
         }
@@ -796,7 +934,11 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         metadata: PreFlightInvocationMetadata,
     ) -> Result<Option<PreFlightInvocationMetadata>, Error>
     where
-        S: VirtualObjectStatusTable + InvocationStatusTable + InboxTable + FsmTable,
+        S: VirtualObjectStatusTable
+            + InvocationStatusTable
+            + InvocationEventTable
+            + InboxTable
+            + FsmTable,
     {
         if metadata.invocation_target.invocation_target_ty()
             == InvocationTargetType::VirtualObject(VirtualObjectHandlerType::Exclusive)
@@ -821,6 +963,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                     restate.outbox.seq = inbox_seq_number,
                     "Store inboxed invocation"
                 );
+                let invocation_target = metadata.invocation_target.clone();
                 self.storage
                     .put_invocation_status(
                         &invocation_id,
@@ -832,6 +975,12 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                         ),
                     )
                     .await;
+                self.record_invocation_event(
+                    invocation_id,
+                    &invocation_target,
+                    InvocationEventKind::Inboxed,
+                )
+                .await?;
 
                 return Ok(None);
             } else {
@@ -860,7 +1009,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         invocation_input: InvocationInput,
     ) -> Result<(), Error>
     where
-        S: JournalTable + InvocationStatusTable,
+        S: JournalTable + InvocationStatusTable + InvocationEventTable,
     {
         let invoke_input_journal = self
             .init_journal(
@@ -900,6 +1049,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             invocation_input.headers,
             invocation_input.argument,
         ));
+        in_flight_invocation_metadata.journal_metadata.size = Some(input_entry.serialized_size());
         self.storage
             .put_journal_entry(&invocation_id, 0, &input_entry)
             .await;
@@ -932,13 +1082,14 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         invoke_input_journal: InvokeInputJournal,
     ) -> Result<(), Error>
     where
-        S: InvocationStatusTable,
+        S: InvocationStatusTable + InvocationEventTable,
     {
         debug_if_leader!(self.is_leader, "Invoke");
 
+        let invocation_target = in_flight_invocation_metadata.invocation_target.clone();
         self.action_collector.push(Action::Invoke {
             invocation_id,
-            invocation_target: in_flight_invocation_metadata.invocation_target.clone(),
+            invocation_target: invocation_target.clone(),
             invoke_input_journal,
         });
         self.storage
@@ -947,6 +1098,12 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                 &InvocationStatus::Invoked(in_flight_invocation_metadata),
             )
             .await;
+        self.record_invocation_event(
+            invocation_id,
+            &invocation_target,
+            InvocationEventKind::Started,
+        )
+        .await?;
 
         Ok(())
     }
@@ -992,6 +1149,37 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         Ok(())
     }
 
+    async fn handle_external_promise_completion(
+        &mut self,
+        CompletePromiseRequest {
+            service_id,
+            promise_key,
+            result,
+        }: CompletePromiseRequest,
+    ) -> Result<(), Error>
+    where
+        S: PromiseTable + FsmTable + OutboxTable,
+    {
+        let value = match result {
+            ResponseResult::Success(value) => CompletePromiseValue::Success(value),
+            ResponseResult::Failure(err) => CompletePromiseValue::Failure(err.into()),
+        };
+
+        if let CompletePromiseResult::Failure(failure) =
+            entries::complete_promise(self, &service_id, &promise_key, value).await?
+        {
+            debug_if_leader!(
+                self.is_leader,
+                rpc.service = %service_id.service_name,
+                "Ignoring external completion of promise {}: {}",
+                promise_key,
+                failure.message
+            );
+        }
+
+        Ok(())
+    }
+
     async fn on_terminate_invocation(
         &mut self,
         InvocationTermination {
@@ -1002,6 +1190,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     where
         S: VirtualObjectStatusTable
             + InvocationStatusTable
+            + InvocationEventTable
             + InboxTable
             + FsmTable
             + StateTable
@@ -1021,6 +1210,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     where
         S: VirtualObjectStatusTable
             + InvocationStatusTable
+            + InvocationEventTable
             + InboxTable
             + FsmTable
             + StateTable
@@ -1080,6 +1270,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     where
         S: VirtualObjectStatusTable
             + InvocationStatusTable
+            + InvocationEventTable
             + InboxTable
             + FsmTable
             + StateTable
@@ -1150,7 +1341,14 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                     )
                     .await?
                 {
+                    let invocation_target = metadata.invocation_target.clone();
                     self.do_resume_service( invocation_id, metadata).await?;
+                    self.record_invocation_event(
+                        invocation_id,
+                        &invocation_target,
+                        InvocationEventKind::Resumed,
+                    )
+                    .await?;
                 }
             }
             InvocationStatus::Inboxed(inboxed) => {
@@ -1201,7 +1399,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         inboxed_invocation: InboxedInvocation,
     ) -> Result<(), Error>
     where
-        S: InvocationStatusTable + InboxTable + OutboxTable + FsmTable,
+        S: InvocationStatusTable + InvocationEventTable + InboxTable + OutboxTable + FsmTable,
     {
         let error = match termination_flavor {
             TerminationFlavor::Kill => KILLED_INVOCATION_ERROR,
@@ -1237,7 +1435,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             inbox_sequence_number,
         )
         .await?;
-        self.do_free_invocation(invocation_id).await;
+        self.do_free_invocation(invocation_id).await?;
 
         self.notify_invocation_result(
             invocation_id,
@@ -1257,7 +1455,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         scheduled_invocation: ScheduledInvocation,
     ) -> Result<(), Error>
     where
-        S: InvocationStatusTable + TimerTable + OutboxTable + FsmTable,
+        S: InvocationStatusTable + InvocationEventTable + TimerTable + OutboxTable + FsmTable,
     {
         let error = match termination_flavor {
             TerminationFlavor::Kill => KILLED_INVOCATION_ERROR,
@@ -1295,7 +1493,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         } else {
             warn!("Scheduled invocations must always have an execution time.");
         }
-        self.do_free_invocation(invocation_id).await;
+        self.do_free_invocation(invocation_id).await?;
 
         self.notify_invocation_result(
             invocation_id,
@@ -1317,6 +1515,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         S: InboxTable
             + VirtualObjectStatusTable
             + InvocationStatusTable
+            + InvocationEventTable
             + VirtualObjectStatusTable
             + StateTable
             + JournalTable
@@ -1362,6 +1561,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         S: InboxTable
             + VirtualObjectStatusTable
             + InvocationStatusTable
+            + InvocationEventTable
             + VirtualObjectStatusTable
             + StateTable
             + JournalTable
@@ -1566,18 +1766,25 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     async fn on_purge_invocation(&mut self, invocation_id: InvocationId) -> Result<(), Error>
     where
         S: InvocationStatusTable
+            + InvocationEventTable
             + IdempotencyTable
             + VirtualObjectStatusTable
             + StateTable
-            + PromiseTable,
+            + PromiseTable
+            + JournalTable
+            + journal_table_v2::JournalTable,
     {
         match self.get_invocation_status(&invocation_id).await? {
             InvocationStatus::Completed(CompletedInvocation {
                 invocation_target,
                 idempotency_key,
+                journal_length,
                 ..
             }) => {
-                self.do_free_invocation(invocation_id).await;
+                self.do_free_invocation(invocation_id).await?;
+                if journal_length > 0 {
+                    self.do_drop_journal(invocation_id, journal_length).await?;
+                }
 
                 // Also cleanup the associated idempotency key if any
                 if let Some(idempotency_key) = idempotency_key {
@@ -1621,6 +1828,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     where
         S: IdempotencyTable
             + InvocationStatusTable
+            + InvocationEventTable
             + OutboxTable
             + FsmTable
             + VirtualObjectStatusTable
@@ -1681,7 +1889,12 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
 
     async fn on_neo_invoke_timer(&mut self, invocation_id: InvocationId) -> Result<(), Error>
     where
-        S: VirtualObjectStatusTable + InvocationStatusTable + InboxTable + FsmTable + JournalTable,
+        S: VirtualObjectStatusTable
+            + InvocationStatusTable
+            + InvocationEventTable
+            + InboxTable
+            + FsmTable
+            + JournalTable,
     {
         debug_if_leader!(
             self.is_leader,
@@ -1731,6 +1944,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     async fn try_invoker_effect(&mut self, invoker_effect: InvokerEffect) -> Result<(), Error>
     where
         S: InvocationStatusTable
+            + InvocationEventTable
             + JournalTable
             + StateTable
             + PromiseTable
@@ -1761,6 +1975,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     ) -> Result<(), Error>
     where
         S: InvocationStatusTable
+            + InvocationEventTable
             + JournalTable
             + StateTable
             + PromiseTable
@@ -1787,6 +2002,55 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             );
             return Ok(());
         }
+        let invocation_target = invocation_status
+            .invocation_target()
+            .expect("Must be present if status is killed or invoked")
+            .clone();
+
+        let appended_entry_size = match &kind {
+            InvokerEffectKind::JournalEntry { entry, .. } => {
+                Some(entry.serialized_entry().len() as u64)
+            }
+            InvokerEffectKind::JournalEntryV2 { entry, .. } => {
+                Some(entry.serialized_content_len() as u64)
+            }
+            _ => None,
+        };
+        if let Some(error) = appended_entry_size.and_then(|entry_size| {
+            check_journal_limits(
+                self.journal_limits,
+                invocation_status
+                    .get_journal_metadata()
+                    .expect("Must be present if status is killed or invoked"),
+                entry_size,
+            )
+        }) {
+            debug_if_leader!(
+                self.is_leader,
+                restate.invocation.id = %invocation_id,
+                "Failing invocation because the journal limits were exceeded: {}",
+                error
+            );
+            self.do_send_abort_invocation_to_invoker(invocation_id, false);
+            self.record_invocation_event(
+                invocation_id,
+                &invocation_target,
+                InvocationEventKind::Failed {
+                    error_code: error.code(),
+                    error_message: error.message().into(),
+                },
+            )
+            .await?;
+            self.end_invocation(
+                invocation_id,
+                invocation_status
+                    .into_invocation_metadata()
+                    .expect("Must be present if status is killed or invoked"),
+                Some(ResponseResult::Failure(error)),
+            )
+            .await?;
+            return Ok(());
+        }
 
         match kind {
             InvokerEffectKind::PinnedDeployment(pinned_deployment) => {
@@ -1865,7 +2129,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                         invocation_metadata,
                         waiting_for_completed_entries,
                     )
-                    .await;
+                    .await?;
                 }
             }
             InvokerEffectKind::SuspendedV2 {
@@ -1879,7 +2143,41 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                 .apply(self)
                 .await?;
             }
+            InvokerEffectKind::TransientError {
+                attempt,
+                deployment_id,
+                error,
+                related_entry_index,
+                related_entry_name,
+            } => {
+                self.record_invocation_event(
+                    invocation_id,
+                    &invocation_target,
+                    InvocationEventKind::TransientError {
+                        attempt,
+                        deployment_id,
+                        error_code: error.code(),
+                        error_message: error.message().into(),
+                        related_entry_index,
+                        related_entry_name: related_entry_name.map(Into::into),
+                    },
+                )
+                .await?;
+            }
             InvokerEffectKind::End => {
+                self.record_invocation_event(
+                    invocation_id,
+                    &invocation_target,
+                    if is_status_killed {
+                        InvocationEventKind::Failed {
+                            error_code: KILLED_INVOCATION_ERROR.code(),
+                            error_message: KILLED_INVOCATION_ERROR.message().into(),
+                        }
+                    } else {
+                        InvocationEventKind::Completed
+                    },
+                )
+                .await?;
                 self.end_invocation(
                     invocation_id,
                     invocation_status
@@ -1895,6 +2193,15 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                 .await?;
             }
             InvokerEffectKind::Failed(e) => {
+                self.record_invocation_event(
+                    invocation_id,
+                    &invocation_target,
+                    InvocationEventKind::Failed {
+                        error_code: e.code(),
+                        error_message: e.message().into(),
+                    },
+                )
+                .await?;
                 self.end_invocation(
                     invocation_id,
                     invocation_status
@@ -1924,6 +2231,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             + OutboxTable
             + FsmTable
             + InvocationStatusTable
+            + InvocationEventTable
             + StateTable
             + journal_table_v2::JournalTable,
     {
@@ -1931,6 +2239,27 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         let journal_length = invocation_metadata.journal_metadata.length;
         let completion_retention_time = invocation_metadata.completion_retention_duration;
 
+        if self.is_leader {
+            let labels = service_handler_labels(
+                invocation_target.service_name(),
+                invocation_target.handler_name(),
+            );
+            // Only for observability, the creation time is not agreed across the replicas
+            let creation_time = unsafe { invocation_metadata.timestamps.creation_time() };
+            histogram!(PARTITION_INVOCATION_DURATION, labels.clone())
+                .record(creation_time.elapsed());
+            histogram!(PARTITION_INVOCATION_JOURNAL_ENTRIES, labels.clone()).record(journal_length);
+            // Unknown for invocations started before the journal sizes were tracked
+            if let Some(journal_size) = invocation_metadata.journal_metadata.size {
+                histogram!(PARTITION_INVOCATION_JOURNAL_SIZE, labels).record(journal_size as f64);
+            }
+        }
+
+        // Retained invocations which streamed output chunks keep their journal, so the chunks can
+        // be replayed on attach. The journal is dropped when the completed status is purged.
+        let retain_journal = !completion_retention_time.is_zero()
+            && invocation_metadata.journal_metadata.output_chunks > 0;
+
         // If there are any response sinks, or we need to store back the completed status,
         //  we need to find the latest output entry
         if !invocation_metadata.response_sinks.is_empty() || !completion_retention_time.is_zero() {
@@ -1980,10 +2309,14 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
 
             // Store the completed status, if needed
             if !completion_retention_time.is_zero() {
-                let completed_invocation = CompletedInvocation::from_in_flight_invocation_metadata(
-                    invocation_metadata,
-                    response_result,
-                );
+                let mut completed_invocation =
+                    CompletedInvocation::from_in_flight_invocation_metadata(
+                        invocation_metadata,
+                        response_result,
+                    );
+                if retain_journal {
+                    completed_invocation.journal_length = journal_length;
+                }
                 self.do_store_completed_invocation(invocation_id, completed_invocation)
                     .await;
             }
@@ -2001,9 +2334,11 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
 
         // If no retention, immediately cleanup the invocation status
         if completion_retention_time.is_zero() {
-            self.do_free_invocation(invocation_id).await;
+            self.do_free_invocation(invocation_id).await?;
+        }
+        if !retain_journal {
+            self.do_drop_journal(invocation_id, journal_length).await?;
         }
-        self.do_drop_journal(invocation_id, journal_length).await?;
 
         // Consume inbox and move on
         self.consume_inbox(&invocation_target).await?;
@@ -2066,6 +2401,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         S: InboxTable
             + VirtualObjectStatusTable
             + InvocationStatusTable
+            + InvocationEventTable
             + VirtualObjectStatusTable
             + StateTable
             + JournalTable,
@@ -2152,7 +2488,8 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             + FsmTable
             + TimerTable
             + JournalTable
-            + InvocationStatusTable,
+            + InvocationStatusTable
+            + InvocationEventTable,
     {
         debug_assert_eq!(
             entry_index, invocation_metadata.journal_metadata.length,
@@ -2992,6 +3329,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         S: JournalTable
             + journal_table_v2::JournalTable
             + InvocationStatusTable
+            + InvocationEventTable
             + TimerTable
             + FsmTable
             + OutboxTable
@@ -3124,6 +3462,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         S: JournalTable
             + journal_table_v2::JournalTable
             + InvocationStatusTable
+            + InvocationEventTable
             + TimerTable
             + FsmTable
             + OutboxTable,
@@ -3149,7 +3488,14 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                 )
                 .await?
                 {
-            self.do_resume_service( invocation_id, metadata).await?;
+                    let invocation_target = metadata.invocation_target.clone();
+                    self.do_resume_service( invocation_id, metadata).await?;
+                    self.record_invocation_event(
+                        invocation_id,
+                        &invocation_target,
+                        InvocationEventKind::Resumed,
+                    )
+                    .await?;
                 }
             }
             _ => {
@@ -3312,6 +3658,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
     where
         S: ReadOnlyIdempotencyTable
             + InvocationStatusTable
+            + InvocationEventTable
             + ReadOnlyVirtualObjectStatusTable
             + OutboxTable
             + FsmTable,
@@ -3481,7 +3828,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         mut metadata: InFlightInvocationMetadata,
     ) -> Result<(), Error>
     where
-        S: InvocationStatusTable,
+        S: InvocationStatusTable + InvocationEventTable,
     {
         debug_if_leader!(
             self.is_leader,
@@ -3518,8 +3865,9 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         invocation_id: InvocationId,
         mut metadata: InFlightInvocationMetadata,
         waiting_for_completed_entries: HashSet<EntryIndex>,
-    ) where
-        S: InvocationStatusTable,
+    ) -> Result<(), Error>
+    where
+        S: InvocationStatusTable + InvocationEventTable,
     {
         debug_if_leader!(
             self.is_leader,
@@ -3529,6 +3877,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         );
 
         metadata.timestamps.update();
+        let invocation_target = metadata.invocation_target.clone();
         self.storage
             .put_invocation_status(
                 &invocation_id,
@@ -3541,6 +3890,12 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
                 },
             )
             .await;
+        self.record_invocation_event(
+            invocation_id,
+            &invocation_target,
+            InvocationEventKind::Suspended,
+        )
+        .await
     }
 
     async fn do_store_completed_invocation(
@@ -3548,7 +3903,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         invocation_id: InvocationId,
         completed_invocation: CompletedInvocation,
     ) where
-        S: InvocationStatusTable,
+        S: InvocationStatusTable + InvocationEventTable,
     {
         debug_if_leader!(
             self.is_leader,
@@ -3564,9 +3919,9 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             .await;
     }
 
-    async fn do_free_invocation(&mut self, invocation_id: InvocationId)
+    async fn do_free_invocation(&mut self, invocation_id: InvocationId) -> Result<(), Error>
     where
-        S: InvocationStatusTable,
+        S: InvocationStatusTable + InvocationEventTable,
     {
         debug_if_leader!(
             self.is_leader,
@@ -3577,6 +3932,10 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         self.storage
             .put_invocation_status(&invocation_id, &InvocationStatus::Free)
             .await;
+        self.storage
+            .delete_invocation_events(&invocation_id)
+            .await?;
+        Ok(())
     }
 
     async fn do_delete_inbox_entry(
@@ -3815,7 +4174,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         entry_index: EntryIndex,
         journal_entry: &JournalEntry,
     ) where
-        S: JournalTable + InvocationStatusTable,
+        S: JournalTable + InvocationStatusTable + InvocationEventTable,
     {
         debug_if_leader!(
             self.is_leader,
@@ -3839,6 +4198,9 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
             "journal should not have gaps"
         );
         journal_meta.length = entry_index + 1;
+        if let Some(size) = &mut journal_meta.size {
+            *size += journal_entry.serialized_size();
+        }
 
         // Update timestamps
         if let Some(timestamps) = previous_invocation_status.get_timestamps_mut() {
@@ -3869,6 +4231,8 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         JournalTable::delete_journal(self.storage, &invocation_id, journal_length).await;
         journal_table_v2::JournalTable::delete_journal(self.storage, invocation_id, journal_length)
             .await?;
+        self.action_collector
+            .push(Action::DeleteOffloadedPayloads { invocation_id });
         Ok(())
     }
 
@@ -3992,7 +4356,7 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         additional_response_sink: ServiceInvocationResponseSink,
     ) -> Result<(), Error>
     where
-        S: InvocationStatusTable,
+        S: InvocationStatusTable + InvocationEventTable,
     {
         debug_if_leader!(
             self.is_leader,
@@ -275,6 +275,7 @@ async fn complete_already_completed_invocation(
             timestamps: StatusTimestamps::now(),
             response_result: ResponseResult::Success(response_bytes.clone()),
             completion_retention_duration: Default::default(),
+            journal_length: 0,
         }),
     )
     .await;
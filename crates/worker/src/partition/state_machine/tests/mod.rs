@@ -27,13 +27,14 @@ use ::tracing::info;
 use bytes::Bytes;
 use bytestring::ByteString;
 use futures::{StreamExt, TryStreamExt};
-use googletest::{all, assert_that, pat, property};
+use googletest::{all, assert_that, elements_are, pat, property};
 use restate_core::TaskCenter;
 use restate_invoker_api::{EffectKind, InvokeInputJournal};
 use restate_partition_store::{OpenMode, PartitionStore, PartitionStoreManager};
 use restate_rocksdb::RocksDbManager;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_storage_api::inbox_table::ReadOnlyInboxTable;
+use restate_storage_api::invocation_event_table::ReadOnlyInvocationEventTable;
 use restate_storage_api::invocation_status_table::{
     InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable,
     ReadOnlyInvocationStatusTable,
@@ -44,25 +45,31 @@ use restate_storage_api::service_status_table::{
     ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus, VirtualObjectStatusTable,
 };
 use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
+use restate_storage_api::timer_table::TimerTable;
 use restate_storage_api::Transaction;
 use restate_test_util::matchers::*;
 use restate_types::config::{CommonOptions, WorkerOptions};
+use restate_types::deployment::PinnedDeployment;
 use restate_types::errors::{codes, InvocationError, KILLED_INVOCATION_ERROR};
 use restate_types::identifiers::{
-    AwakeableIdentifier, InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
-    ServiceId,
+    AwakeableIdentifier, DeploymentId, InvocationId, LeaderEpoch, PartitionId, PartitionKey,
+    PartitionProcessorRpcRequestId, ServiceId,
 };
 use restate_types::invocation::{
-    Header, InvocationResponse, InvocationTarget, InvocationTermination, ResponseResult,
-    ServiceInvocation, ServiceInvocationResponseSink, Source, VirtualObjectHandlerType,
+    Header, InvocationResponse, InvocationTarget, InvocationTermination, MigrateInvocationRequest,
+    PurgeInvocationRequest, PurgeServiceRequest, ResponseResult, RestartInvocationRequest,
+    RestartMode, ServiceInvocation, ServiceInvocationResponseSink, Source,
+    VirtualObjectHandlerType,
 };
 use restate_types::journal::enriched::EnrichedRawEntry;
 use restate_types::journal::{
-    CompleteAwakeableEntry, Completion, CompletionResult, EntryResult, InvokeRequest,
+    CompleteAwakeableEntry, Completion, CompletionResult, EntryResult, InvokeRequest, SleepEntry,
 };
 use restate_types::journal::{Entry, EntryType};
 use restate_types::live::{Constant, Live};
+use restate_types::net::partition_processor::{OutputChunks, OutputStreamCursor};
 use restate_types::state_mut::ExternalStateMutation;
+use restate_wal_protocol::control::AnnounceLeader;
 use std::collections::{HashMap, HashSet};
 use test_log::test;
 use tracing_subscriber::fmt::format::FmtSpan;
@@ -93,6 +100,8 @@ impl TestEnv {
             None, /* outbox_head_seq_number */
             PartitionKey::MIN..=PartitionKey::MAX,
             experimental_features,
+            JournalLimits::default(),
+            100, /* invocation_events_retention */
         ))
         .await
     }
@@ -599,6 +608,84 @@ async fn clear_all_user_states() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(restate_core::test)]
+async fn purge_service_retains_locked_keys() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let unlocked_service_id = ServiceId::new("MySvc", "unlocked");
+    let locked_service_id = ServiceId::new("MySvc", "locked");
+    let other_service_id = ServiceId::new("OtherSvc", "unlocked");
+
+    let mut txn = test_env.storage.transaction();
+    for service_id in [&unlocked_service_id, &locked_service_id, &other_service_id] {
+        txn.put_user_state(service_id, b"my-key", b"my-val").await;
+    }
+    txn.commit().await.unwrap();
+
+    // The in-flight invocation locks the key
+    fixtures::mock_start_invocation_with_service_id(&mut test_env, locked_service_id.clone()).await;
+
+    test_env
+        .apply(Command::PurgeService(PurgeServiceRequest {
+            service_name: ByteString::from_static("MySvc"),
+            partition_key_range: PartitionKey::MIN..=PartitionKey::MAX,
+        }))
+        .await;
+
+    for (service_id, expected_states) in [
+        (&unlocked_service_id, 0),
+        (&locked_service_id, 1),
+        (&other_service_id, 1),
+    ] {
+        assert_eq!(
+            test_env
+                .storage
+                .get_all_user_states_for_service(service_id)
+                .count()
+                .await,
+            expected_states,
+            "states of {service_id:?}"
+        );
+    }
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn purge_service_sweeps_only_the_requested_keys() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let purged_service_id = ServiceId::with_partition_key(10, "MySvc", "purged");
+    let retained_service_id = ServiceId::with_partition_key(20, "MySvc", "retained");
+
+    let mut txn = test_env.storage.transaction();
+    for service_id in [&purged_service_id, &retained_service_id] {
+        txn.put_user_state(service_id, b"my-key", b"my-val").await;
+    }
+    txn.commit().await.unwrap();
+
+    test_env
+        .apply(Command::PurgeService(PurgeServiceRequest {
+            service_name: ByteString::from_static("MySvc"),
+            partition_key_range: 0..=15,
+        }))
+        .await;
+
+    for (service_id, expected_states) in [(&purged_service_id, 0), (&retained_service_id, 1)] {
+        assert_eq!(
+            test_env
+                .storage
+                .get_all_user_states_for_service(service_id)
+                .count()
+                .await,
+            expected_states,
+            "states of {service_id:?}"
+        );
+    }
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
 #[test(restate_core::test)]
 async fn get_state_keys() -> TestResult {
     let mut test_env = TestEnv::create().await;
@@ -941,6 +1028,803 @@ async fn send_ingress_response_to_multiple_targets() -> TestResult {
     Ok(())
 }
 
+#[test(restate_core::test)]
+async fn migrate_invoked_invocation_to_other_deployment() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let old_deployment = DeploymentId::new();
+    let new_deployment = DeploymentId::new();
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::PinnedDeployment(PinnedDeployment {
+                deployment_id: old_deployment,
+                service_protocol_version: ServiceProtocolVersion::V3,
+            }),
+        }))
+        .await;
+
+    // Migration from a deployment the invocation is not pinned to is ignored
+    let actions = test_env
+        .apply(Command::MigrateInvocation(MigrateInvocationRequest {
+            invocation_id,
+            from_deployment: new_deployment,
+            to_deployment: old_deployment,
+        }))
+        .await;
+    assert_that!(actions, empty());
+
+    let actions = test_env
+        .apply(Command::MigrateInvocation(MigrateInvocationRequest {
+            invocation_id,
+            from_deployment: old_deployment,
+            to_deployment: new_deployment,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            contains(pat!(Action::AbortInvocation {
+                invocation_id: eq(invocation_id),
+                acknowledge: eq(false)
+            })),
+            contains(matchers::actions::invoke_for_id(invocation_id))
+        )
+    );
+
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+            .get_invocation_metadata()
+            .and_then(|metadata| metadata.pinned_deployment.clone()),
+        some(eq(PinnedDeployment {
+            deployment_id: new_deployment,
+            service_protocol_version: ServiceProtocolVersion::V3,
+        }))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_invocation_in_place() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let _ = test_env
+        .apply_multiple([
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                kind: InvokerEffectKind::JournalEntry {
+                    entry_index: 1,
+                    entry: ProtobufRawEntryCodec::serialize_enriched(Entry::clear_all_state()),
+                },
+            }),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                kind: InvokerEffectKind::JournalEntry {
+                    entry_index: 2,
+                    entry: ProtobufRawEntryCodec::serialize_enriched(Entry::Sleep(SleepEntry {
+                        wake_up_time: 1337,
+                        result: None,
+                    })),
+                },
+            }),
+        ])
+        .await;
+    assert_that!(
+        test_env
+            .storage
+            .next_timers_greater_than(None, usize::MAX)
+            .try_collect::<Vec<_>>()
+            .await?,
+        len(eq(1))
+    );
+
+    let actions = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            mode: RestartMode::InPlace { from_index: 1 },
+        }))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            contains(pat!(Action::AbortInvocation {
+                invocation_id: eq(invocation_id),
+                acknowledge: eq(false)
+            })),
+            contains(matchers::actions::invoke_for_id(invocation_id))
+        )
+    );
+
+    // Journal is truncated and the timer of the removed sleep is gone
+    let invocation_status = test_env
+        .storage
+        .get_invocation_status(&invocation_id)
+        .await?;
+    assert_that!(invocation_status, pat!(InvocationStatus::Invoked(_)));
+    assert_eq!(invocation_status.get_journal_metadata().unwrap().length, 1);
+    assert_that!(
+        test_env
+            .storage
+            .get_journal_entry(&invocation_id, 1)
+            .await?,
+        none()
+    );
+    assert_that!(
+        test_env
+            .storage
+            .next_timers_greater_than(None, usize::MAX)
+            .try_collect::<Vec<_>>()
+            .await?,
+        empty()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_invocation_in_place_ignored_with_pending_completions() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 1,
+                entry: ProtobufRawEntryCodec::serialize_enriched(Entry::awakeable(None)),
+            },
+        }))
+        .await;
+
+    // A late completion of the awakeable would complete the re-executed entry at index 1
+    let actions = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            mode: RestartMode::InPlace { from_index: 1 },
+        }))
+        .await;
+    assert_that!(actions, not(contains(pat!(Action::AbortInvocation { .. }))));
+
+    let invocation_status = test_env
+        .storage
+        .get_invocation_status(&invocation_id)
+        .await?;
+    assert_eq!(invocation_status.get_journal_metadata().unwrap().length, 2);
+    assert_that!(
+        test_env
+            .storage
+            .get_journal_entry(&invocation_id, 1)
+            .await?,
+        some(anything())
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_invocation_in_place_keeps_notifications_of_kept_commands() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    fixtures::mock_pinned_deployment_v4(&mut test_env, invocation_id).await;
+
+    let run_completion = |completion_id| journal_v2::RunCompletion {
+        completion_id,
+        result: journal_v2::RunResult::Success(Bytes::from_static(b"123")),
+    };
+    let _ = test_env
+        .apply_multiple([
+            fixtures::invoker_entry_effect(
+                invocation_id,
+                journal_v2::RunCommand {
+                    completion_id: 1,
+                    name: Default::default(),
+                },
+            ),
+            fixtures::invoker_entry_effect(
+                invocation_id,
+                journal_v2::RunCommand {
+                    completion_id: 2,
+                    name: Default::default(),
+                },
+            ),
+            // The completion of the kept run is stored after the removed one
+            fixtures::invoker_entry_effect(invocation_id, run_completion(1)),
+            fixtures::invoker_entry_effect(invocation_id, run_completion(2)),
+        ])
+        .await;
+
+    let actions = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            mode: RestartMode::InPlace { from_index: 2 },
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::AbortInvocation {
+            invocation_id: eq(invocation_id),
+            acknowledge: eq(false)
+        }))
+    );
+
+    // The completion of the kept run is appended again, the one of the removed run is dropped
+    let invocation_status = test_env
+        .storage
+        .get_invocation_status(&invocation_id)
+        .await?;
+    assert_eq!(invocation_status.get_journal_metadata().unwrap().length, 3);
+    let notification =
+        restate_storage_api::journal_table_v2::ReadOnlyJournalTable::get_journal_entry(
+            &mut test_env.storage,
+            invocation_id,
+            2,
+        )
+        .await?
+        .unwrap()
+        .inner
+        .try_as_notification()
+        .unwrap();
+    assert_eq!(
+        notification.decode::<ServiceProtocolV4Codec, journal_v2::RunCompletion>()?,
+        run_completion(1)
+    );
+    assert_that!(
+        restate_storage_api::journal_table_v2::ReadOnlyJournalTable::get_journal_entry(
+            &mut test_env.storage,
+            invocation_id,
+            3
+        )
+        .await?,
+        none()
+    );
+    assert_eq!(
+        restate_storage_api::journal_table_v2::ReadOnlyJournalTable::get_notifications_index(
+            &mut test_env.storage,
+            invocation_id
+        )
+        .await?,
+        HashMap::from([(journal_v2::NotificationId::CompletionId(1), 2)])
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_invocation_as_new() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let new_invocation_id = invocation_id.generate_restart();
+
+    let _ = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            mode: RestartMode::AsNew { new_invocation_id },
+        }))
+        .await;
+
+    // The new invocation is sent through the outbox, the old one keeps running
+    assert_that!(
+        test_env.storage.get_outbox_message(0).await?,
+        some(pat!(
+            restate_storage_api::outbox_table::OutboxMessage::ServiceInvocation(pat!(
+                ServiceInvocation {
+                    invocation_id: eq(new_invocation_id),
+                    source: eq(Source::Restart(invocation_id))
+                }
+            ))
+        ))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Invoked(_))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_invocation_as_new_copies_offloaded_input() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+    let new_invocation_id = invocation_id.generate_restart();
+    let input_reference =
+        restate_types::payload_reference::payload_reference(&format!("{invocation_id}/input-abc"));
+    let new_input_reference = restate_types::payload_reference::payload_reference(&format!(
+        "{new_invocation_id}/input-abc"
+    ));
+
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            argument: input_reference.clone(),
+            ..ServiceInvocation::initialize(
+                invocation_id,
+                invocation_target,
+                Source::Ingress(PartitionProcessorRpcRequestId::new()),
+            )
+        }))
+        .await;
+    let actions = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            mode: RestartMode::AsNew { new_invocation_id },
+        }))
+        .await;
+
+    // The new invocation references its own copy of the input
+    assert_that!(
+        actions,
+        contains(pat!(Action::CopyOffloadedPayload {
+            source_invocation_id: eq(invocation_id),
+            source: eq(input_reference),
+            target_invocation_id: eq(new_invocation_id),
+            target: eq(new_input_reference.clone())
+        }))
+    );
+    assert_that!(
+        test_env.storage.get_outbox_message(0).await?,
+        some(pat!(
+            restate_storage_api::outbox_table::OutboxMessage::ServiceInvocation(pat!(
+                ServiceInvocation {
+                    invocation_id: eq(new_invocation_id),
+                    argument: eq(new_input_reference)
+                }
+            ))
+        ))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_invocation_as_new_does_not_copy_foreign_input() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+    let new_invocation_id = invocation_id.generate_restart();
+    // A reference forged by the caller, pointing to the input of another invocation
+    let forged_reference = restate_types::payload_reference::payload_reference(&format!(
+        "{}/input-abc",
+        InvocationId::mock_random()
+    ));
+
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            argument: forged_reference.clone(),
+            ..ServiceInvocation::initialize(
+                invocation_id,
+                invocation_target,
+                Source::Ingress(PartitionProcessorRpcRequestId::new()),
+            )
+        }))
+        .await;
+    let actions = test_env
+        .apply(Command::RestartInvocation(RestartInvocationRequest {
+            invocation_id,
+            mode: RestartMode::AsNew { new_invocation_id },
+        }))
+        .await;
+
+    assert!(!actions
+        .iter()
+        .any(|action| matches!(action, Action::CopyOffloadedPayload { .. })));
+    assert_that!(
+        test_env.storage.get_outbox_message(0).await?,
+        some(pat!(
+            restate_storage_api::outbox_table::OutboxMessage::ServiceInvocation(pat!(
+                ServiceInvocation {
+                    invocation_id: eq(new_invocation_id),
+                    argument: eq(forged_reference)
+                }
+            ))
+        ))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn retain_journal_with_output_chunks_until_purge() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            completion_retention_duration: Some(std::time::Duration::from_secs(60)),
+            ..ServiceInvocation::initialize(
+                invocation_id,
+                invocation_target,
+                Source::Ingress(PartitionProcessorRpcRequestId::new()),
+            )
+        }))
+        .await;
+    fixtures::mock_pinned_deployment_v4(&mut test_env, invocation_id).await;
+    let _ = test_env
+        .apply(fixtures::invoker_entry_effect(
+            invocation_id,
+            journal_v2::Entry::Event(journal_v2::Event::output_chunk("hello")),
+        ))
+        .await;
+
+    // The chunk is counted in the journal metadata, reads past it don't find new chunks
+    let invocation_status = test_env
+        .storage
+        .get_invocation_status(&invocation_id)
+        .await?;
+    assert_that!(
+        invocation_status.get_journal_metadata(),
+        some(pat!(JournalMetadata {
+            output_chunks: eq(1)
+        }))
+    );
+    let chunks = crate::partition::output_streams::read_output_chunks(
+        &mut test_env.storage,
+        invocation_id,
+        invocation_status,
+        OutputStreamCursor {
+            entry_index: 2,
+            chunk_index: 1,
+        },
+    )
+    .await?;
+    assert_that!(
+        chunks,
+        some(pat!(OutputChunks {
+            chunks: empty(),
+            completed: eq(false)
+        }))
+    );
+
+    let _ = test_env
+        .apply_multiple([
+            fixtures::invoker_entry_effect(
+                invocation_id,
+                OutputCommand {
+                    result: OutputResult::Success(Bytes::from_static(b"123")),
+                    name: Default::default(),
+                },
+            ),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                kind: InvokerEffectKind::End,
+            }),
+        ])
+        .await;
+
+    // The completed status references the retained journal, which still has the chunks
+    let invocation_status = test_env
+        .storage
+        .get_invocation_status(&invocation_id)
+        .await?;
+    assert_that!(
+        invocation_status,
+        pat!(InvocationStatus::Completed(pat!(CompletedInvocation {
+            journal_length: eq(3)
+        })))
+    );
+    let chunks = crate::partition::output_streams::read_output_chunks(
+        &mut test_env.storage,
+        invocation_id,
+        invocation_status,
+        OutputStreamCursor::default(),
+    )
+    .await?;
+    assert_that!(
+        chunks,
+        some(pat!(OutputChunks {
+            chunks: elements_are![eq(ByteString::from_static("hello"))],
+            next: eq(OutputStreamCursor {
+                entry_index: 3,
+                chunk_index: 1
+            }),
+            completed: eq(true)
+        }))
+    );
+
+    // Purging the invocation drops the journal too
+    let _ = test_env
+        .apply(Command::PurgeInvocation(PurgeInvocationRequest {
+            invocation_id,
+        }))
+        .await;
+    assert_that!(
+        restate_storage_api::journal_table_v2::ReadOnlyJournalTable::get_journal_entry(
+            &mut test_env.storage,
+            invocation_id,
+            1
+        )
+        .await?,
+        none()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+/// The ingress retains the streaming calls to read their last chunks, which makes them
+/// attachable after the completion, even without idempotency key.
+#[test(restate_core::test)]
+async fn retained_streaming_call_without_idempotency_key_is_attachable() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+    let request_id = PartitionProcessorRpcRequestId::new();
+    let attach_request_id = PartitionProcessorRpcRequestId::new();
+    let response_bytes = Bytes::from_static(b"123");
+
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            response_sink: Some(ServiceInvocationResponseSink::Ingress { request_id }),
+            // As set by the ingress with the ingress.output-stream-retention
+            completion_retention_duration: Some(std::time::Duration::from_secs(60)),
+            ..ServiceInvocation::initialize(
+                invocation_id,
+                invocation_target.clone(),
+                Source::Ingress(request_id),
+            )
+        }))
+        .await;
+    fixtures::mock_pinned_deployment_v4(&mut test_env, invocation_id).await;
+    let _ = test_env
+        .apply_multiple([
+            fixtures::invoker_entry_effect(
+                invocation_id,
+                journal_v2::Entry::Event(journal_v2::Event::output_chunk("hello")),
+            ),
+            fixtures::invoker_entry_effect(
+                invocation_id,
+                OutputCommand {
+                    result: OutputResult::Success(response_bytes.clone()),
+                    name: Default::default(),
+                },
+            ),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                kind: InvokerEffectKind::End,
+            }),
+        ])
+        .await;
+
+    let actions = test_env
+        .apply(Command::AttachInvocation(AttachInvocationRequest {
+            invocation_query: InvocationQuery::Invocation(invocation_id),
+            block_on_inflight: true,
+            response_sink: ServiceInvocationResponseSink::Ingress {
+                request_id: attach_request_id,
+            },
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::IngressResponse {
+            request_id: eq(attach_request_id),
+            invocation_id: some(eq(invocation_id)),
+            response: eq(IngressResponseResult::Success(
+                invocation_target,
+                response_bytes
+            ))
+        }))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn record_invocation_lifecycle_events() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let deployment_id = DeploymentId::new();
+
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::TransientError {
+                attempt: 1,
+                deployment_id: Some(deployment_id),
+                error: InvocationError::internal("my-error"),
+                related_entry_index: Some(1),
+                related_entry_name: None,
+            },
+        }))
+        .await;
+
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_events(&invocation_id)
+            .await?
+            .into_iter()
+            .map(|event| event.kind)
+            .collect::<Vec<_>>(),
+        elements_are![
+            eq(InvocationEventKind::Created),
+            eq(InvocationEventKind::Started),
+            pat!(InvocationEventKind::TransientError {
+                attempt: eq(1),
+                deployment_id: some(eq(deployment_id)),
+                error_code: eq(codes::INTERNAL),
+                error_message: eq(ByteString::from_static("my-error")),
+                related_entry_index: some(eq(1)),
+                related_entry_name: none(),
+            })
+        ]
+    );
+
+    // Once the invocation is freed, its event log is gone too
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::End,
+        }))
+        .await;
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_events(&invocation_id)
+            .await?,
+        empty()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn fail_invocation_exceeding_journal_limits() -> TestResult {
+    let mut test_env = TestEnv::create_with_state_machine(StateMachine::new(
+        0,
+        0,
+        None,
+        PartitionKey::MIN..=PartitionKey::MAX,
+        EnumSet::empty(),
+        JournalLimits {
+            max_entries: Some(2),
+            max_size: None,
+        },
+        100,
+    ))
+    .await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    // The input entry and this one fit in the limit
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 1,
+                entry: ProtobufRawEntryCodec::serialize_enriched(Entry::clear_all_state()),
+            },
+        }))
+        .await;
+    let journal_metadata = test_env
+        .storage
+        .get_invocation_status(&invocation_id)
+        .await?
+        .get_journal_metadata()
+        .cloned();
+    assert_that!(
+        journal_metadata,
+        some(pat!(JournalMetadata {
+            length: eq(2),
+            size: some(gt(0))
+        }))
+    );
+
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 2,
+                entry: ProtobufRawEntryCodec::serialize_enriched(Entry::clear_all_state()),
+            },
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::AbortInvocation {
+            invocation_id: eq(invocation_id),
+            acknowledge: eq(false)
+        }))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Free)
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_journal_entry(&invocation_id, 2)
+            .await?,
+        none()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn apply_journal_limits_announced_by_leader() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let journal_limits = JournalLimits {
+        max_entries: Some(1),
+        max_size: None,
+    };
+
+    let _ = test_env
+        .apply(Command::AnnounceLeader(AnnounceLeader {
+            node_id: None,
+            leader_epoch: LeaderEpoch::from(1),
+            partition_key_range: Some(PartitionKey::MIN..=PartitionKey::MAX),
+            journal_limits: Some(journal_limits),
+        }))
+        .await;
+    assert_eq!(
+        restate_storage_api::fsm_table::ReadOnlyFsmTable::get_journal_limits(&mut test_env.storage)
+            .await?,
+        journal_limits
+    );
+
+    // Announcements without limits keep the previous ones
+    let _ = test_env
+        .apply(Command::AnnounceLeader(AnnounceLeader {
+            node_id: None,
+            leader_epoch: LeaderEpoch::from(2),
+            partition_key_range: Some(PartitionKey::MIN..=PartitionKey::MAX),
+            journal_limits: None,
+        }))
+        .await;
+
+    // The input entry already reaches the announced limit
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 1,
+                entry: ProtobufRawEntryCodec::serialize_enriched(Entry::clear_all_state()),
+            },
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::AbortInvocation {
+            invocation_id: eq(invocation_id),
+            acknowledge: eq(false)
+        }))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Free)
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
 #[test(restate_core::test)]
 async fn truncate_outbox_from_empty() -> Result<(), Error> {
     // An outbox message with index 0 has been successfully processed, and must now be truncated
@@ -975,6 +1859,8 @@ async fn truncate_outbox_with_gap() -> Result<(), Error> {
         Some(outbox_head_index),
         PartitionKey::MIN..=PartitionKey::MAX,
         EnumSet::empty(),
+        JournalLimits::default(),
+        100,
     ))
     .await;
 
@@ -11,10 +11,15 @@
 use super::*;
 
 use restate_storage_api::invocation_status_table::CompletedInvocation;
+use restate_storage_api::promise_table::{
+    Promise, PromiseResult, PromiseState, ReadOnlyPromiseTable,
+};
 use restate_storage_api::service_status_table::ReadOnlyVirtualObjectStatusTable;
 use restate_types::errors::WORKFLOW_ALREADY_INVOKED_INVOCATION_ERROR;
+use restate_types::identifiers::JournalEntryId;
 use restate_types::invocation::{
-    AttachInvocationRequest, InvocationQuery, InvocationTarget, PurgeInvocationRequest,
+    AttachInvocationRequest, CompletePromiseRequest, InvocationQuery, InvocationTarget,
+    PurgeInvocationRequest,
 };
 use rstest::*;
 use std::time::Duration;
@@ -373,3 +378,70 @@ async fn purge_completed_workflow(#[case] experimental_features: EnumSet<Experim
     );
     test_env.shutdown().await;
 }
+
+#[restate_core::test]
+async fn complete_promise_externally() {
+    let mut test_env = TestEnv::create().await;
+
+    let service_id = InvocationTarget::mock_workflow()
+        .as_keyed_service_id()
+        .unwrap();
+    let promise_key = ByteString::from_static("my-promise");
+    let listener_id = InvocationId::mock_random();
+    let listener = JournalEntryId::from_parts(listener_id, 3);
+
+    let mut txn = test_env.storage().transaction();
+    txn.put_promise(
+        &service_id,
+        &promise_key,
+        &Promise {
+            state: PromiseState::NotCompleted(vec![listener]),
+        },
+    )
+    .await;
+    txn.commit().await.unwrap();
+
+    let actions = test_env
+        .apply(Command::CompletePromise(CompletePromiseRequest {
+            service_id: service_id.clone(),
+            promise_key: promise_key.clone(),
+            result: ResponseResult::Success(Bytes::from_static(b"done")),
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::NewOutboxMessage {
+            message: pat!(
+                restate_storage_api::outbox_table::OutboxMessage::ServiceResponse(pat!(
+                    restate_types::invocation::InvocationResponse {
+                        id: eq(listener_id),
+                        entry_index: eq(3),
+                        result: eq(ResponseResult::Success(Bytes::from_static(b"done")))
+                    }
+                ))
+            )
+        }))
+    );
+
+    // Completing the promise again doesn't override the first value
+    let actions = test_env
+        .apply(Command::CompletePromise(CompletePromiseRequest {
+            service_id: service_id.clone(),
+            promise_key: promise_key.clone(),
+            result: ResponseResult::Success(Bytes::from_static(b"again")),
+        }))
+        .await;
+    assert_that!(actions, empty());
+    assert_that!(
+        test_env
+            .storage()
+            .get_promise(&service_id, &promise_key)
+            .await
+            .unwrap(),
+        some(eq(Promise {
+            state: PromiseState::Completed(PromiseResult::Success(Bytes::from_static(b"done")))
+        }))
+    );
+
+    test_env.shutdown().await;
+}
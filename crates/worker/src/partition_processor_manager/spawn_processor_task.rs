@@ -15,6 +15,7 @@ use tokio::sync::{mpsc, watch};
 use tracing::{debug, info, instrument, warn};
 
 use restate_bifrost::Bifrost;
+use restate_core::payload_store::PayloadStore;
 use restate_core::{Metadata, RuntimeTaskHandle, TaskCenter, TaskKind};
 use restate_invoker_impl::Service as InvokerService;
 use restate_partition_store::snapshots::LocalPartitionSnapshot;
@@ -94,6 +95,7 @@ impl SpawnPartitionProcessorTask {
 
         let config = configuration.pinned();
         let schema = Metadata::with_current(|m| m.updateable_schema());
+        let payload_store = PayloadStore::create_if_configured(&config.common.payload_store)?;
         let invoker: InvokerService<
             InvokerStorageReader<PartitionStore>,
             EntryEnricher<Schema, ProtobufRawEntryCodec>,
@@ -103,6 +105,7 @@ impl SpawnPartitionProcessorTask {
             &config.worker.invoker,
             EntryEnricher::new(schema.clone()),
             schema,
+            payload_store.clone(),
         )?;
 
         let status_reader = invoker.status_reader();
@@ -123,6 +126,7 @@ impl SpawnPartitionProcessorTask {
             rpc_rx,
             watch_tx,
             invoker.handle(),
+            payload_store,
         );
 
         let invoker_name = Box::leak(Box::new(format!("invoker-{partition_id}")));
@@ -10,3 +10,4 @@
 
 pub mod handler;
 pub mod listener;
+pub mod script;
@@ -11,6 +11,7 @@
 use std::convert::Infallible;
 use std::error::Error;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use bytes::Bytes;
 use http_body_util::{Either, Full};
@@ -22,6 +23,7 @@ use tokio::net::TcpListener;
 use tracing::error;
 
 use crate::handler::serve;
+use crate::script::{self, Script};
 
 pub async fn run_listener(address: SocketAddr) -> Result<(), Box<dyn Error + Send + Sync>> {
     let listener = TcpListener::bind(address).await?;
@@ -55,3 +57,52 @@ pub async fn run_listener(address: SocketAddr) -> Result<(), Box<dyn Error + Sen
         }
     }
 }
+
+/// Serves the services defined by the given script.
+pub async fn run_scripted_listener(
+    address: SocketAddr,
+    script: Script,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let listener = TcpListener::bind(address).await?;
+    let manifest = Bytes::from(serde_json::to_vec(&script.manifest())?);
+    let script = Arc::new(script);
+
+    loop {
+        let (tcp, _) = listener.accept().await?;
+        let io = TokioIo::new(tcp);
+        let script = Arc::clone(&script);
+        let manifest = manifest.clone();
+        tokio::task::spawn(async move {
+            if let Err(err) = http2::Builder::new(TokioExecutor::new())
+                .timer(TokioTimer::new())
+                .serve_connection(
+                    io,
+                    service_fn(|req| {
+                        let script = Arc::clone(&script);
+                        let manifest = manifest.clone();
+                        async move {
+                            if req.uri().path() == "/discover" {
+                                return Ok(Response::builder()
+                                    .header(
+                                        "content-type",
+                                        "application/vnd.restate.endpointmanifest.v1+json",
+                                    )
+                                    .body(Either::Left(Full::new(manifest)))
+                                    .unwrap());
+                            }
+
+                            let (head, body) = script::serve(script, req).await?.into_parts();
+                            Result::<_, Infallible>::Ok(Response::from_parts(
+                                head,
+                                Either::Right(body),
+                            ))
+                        }
+                    }),
+                )
+                .await
+            {
+                error!("Error serving connection: {:?}", err);
+            }
+        });
+    }
+}
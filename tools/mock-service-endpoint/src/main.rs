@@ -13,7 +13,8 @@ use std::net::SocketAddr;
 use tracing::info;
 use tracing_subscriber::filter::LevelFilter;
 
-use mock_service_endpoint::listener::run_listener;
+use mock_service_endpoint::listener::{run_listener, run_scripted_listener};
+use mock_service_endpoint::script::Script;
 
 #[tokio::main]
 pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -30,6 +31,12 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     let addr: SocketAddr = ([127, 0, 0, 1], 9080).into();
 
+    // An optional script path replaces the default Counter service with the scripted services
+    let script = std::env::args().nth(1).map(Script::from_file).transpose()?;
+
     info!("Listening on http://{}", addr);
-    run_listener(addr).await
+    match script {
+        Some(script) => run_scripted_listener(addr, script).await,
+        None => run_listener(addr).await,
+    }
 }
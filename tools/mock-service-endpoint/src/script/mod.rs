@@ -0,0 +1,195 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Declarative scripts defining the services exposed by the mock service endpoint.
+//!
+//! A script lists the services and, for each handler, the sequence of steps it executes. Handlers
+//! are executed with service protocol v4, replaying the journal like an SDK would do. Every step
+//! updates the handler's current value, which starts as the invocation input and is used as the
+//! default value of `setState`, `call`, `run` and `output`. Example:
+//!
+//! ```json
+//! {
+//!   "protocolMode": "BIDI_STREAM",
+//!   "services": [{
+//!     "name": "Counter",
+//!     "ty": "VIRTUAL_OBJECT",
+//!     "handlers": [{
+//!       "name": "set",
+//!       "steps": [
+//!         { "type": "setState", "key": "counter" },
+//!         { "type": "sleep", "millis": 100 },
+//!         { "type": "getState", "key": "counter" }
+//!       ]
+//!     }]
+//!   }]
+//! }
+//! ```
+
+mod runner;
+
+pub use runner::serve;
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProtocolMode {
+    #[default]
+    BidiStream,
+    RequestResponse,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ServiceType {
+    #[default]
+    Service,
+    VirtualObject,
+    Workflow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HandlerType {
+    Exclusive,
+    Shared,
+    Workflow,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Script {
+    #[serde(default)]
+    pub protocol_mode: ProtocolMode,
+    pub services: Vec<ServiceScript>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceScript {
+    pub name: String,
+    #[serde(default)]
+    pub ty: ServiceType,
+    pub handlers: Vec<HandlerScript>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HandlerScript {
+    pub name: String,
+    #[serde(default)]
+    pub ty: Option<HandlerType>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+/// A step of a handler. Values are JSON, serialized to bytes when sent to Restate.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Step {
+    /// Reads a state key, the current value becomes its value or empty if not set.
+    GetState { key: String },
+    /// Sets a state key to the given value, or the current value.
+    SetState {
+        key: String,
+        #[serde(default)]
+        value: Option<serde_json::Value>,
+    },
+    /// Calls another handler with the given parameter, or the current value, and waits for its
+    /// result. A failed call fails the handler with the same failure.
+    Call {
+        service: String,
+        handler: String,
+        #[serde(default)]
+        key: Option<String>,
+        #[serde(default)]
+        parameter: Option<serde_json::Value>,
+    },
+    /// Sleeps for the given duration.
+    Sleep { millis: u64 },
+    /// Runs a side effect which results in the given value, or the current value.
+    Run {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        value: Option<serde_json::Value>,
+    },
+    /// Creates an awakeable, logging its id, and waits for its completion.
+    Awakeable,
+    /// Fails the attempt with the given code. Terminal failures complete the invocation,
+    /// otherwise the invocation is retried.
+    Fail {
+        code: u16,
+        #[serde(default)]
+        message: String,
+        #[serde(default)]
+        terminal: bool,
+    },
+    /// Streams an output chunk with the given content, which clients can read before the
+    /// invocation completes.
+    OutputChunk { content: String },
+    /// Stops responding, keeping the stream open.
+    Hang,
+    /// Completes the invocation with the given value, or the current value. Handlers without an
+    /// explicit output step complete with the current value after the last step.
+    Output {
+        #[serde(default)]
+        value: Option<serde_json::Value>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScriptError {
+    #[error("failed reading the script: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed parsing the script: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+impl Script {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ScriptError> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn handler(&self, service_name: &str, handler_name: &str) -> Option<&HandlerScript> {
+        self.services
+            .iter()
+            .find(|service| service.name == service_name)?
+            .handlers
+            .iter()
+            .find(|handler| handler.name == handler_name)
+    }
+
+    /// Generates the discovery manifest of the services defined by this script.
+    pub fn manifest(&self) -> serde_json::Value {
+        json!({
+            "protocolMode": self.protocol_mode,
+            "minProtocolVersion": 4,
+            "maxProtocolVersion": 4,
+            "services": self.services.iter().map(|service| json!({
+                "name": service.name,
+                "ty": service.ty,
+                "handlers": service.handlers.iter().map(|handler| {
+                    let mut manifest = json!({
+                        "name": handler.name,
+                        "input": {"required": false, "contentType": "application/json"},
+                        "output": {"setContentTypeIfEmpty": false, "contentType": "application/json"},
+                    });
+                    if let Some(ty) = handler.ty {
+                        manifest["ty"] = json!(ty);
+                    }
+                    manifest
+                }).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
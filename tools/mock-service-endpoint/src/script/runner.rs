@@ -0,0 +1,811 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_stream::stream;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::{Stream, StreamExt};
+use http_body_util::{BodyStream, Either, Empty, StreamBody};
+use hyper::body::{Frame, Incoming};
+use hyper::{Request, Response};
+use prost::Message as _;
+use tracing::{debug, error, info};
+
+use restate_service_protocol_v4::message_codec::proto::{
+    self, get_eager_state_command_message, notification_template, output_command_message,
+    propose_run_completion_message,
+};
+use restate_service_protocol_v4::message_codec::{
+    Decoder, Encoder, EncodingError, Message, MessageHeader, MessageType, OUTPUT_CHUNK_MESSAGE_TYPE,
+};
+use restate_types::errors::codes;
+use restate_types::identifiers::{ExternalSignalIdentifier, InvocationId};
+use restate_types::service_protocol::ServiceProtocolVersion;
+
+use super::{HandlerScript, Script, Step};
+
+/// First signal index usable for awakeables, lower indexes are reserved for built-in signals.
+const FIRST_AWAKEABLE_SIGNAL_INDEX: u32 = 17;
+
+#[derive(Debug, thiserror::Error)]
+enum RunnerError {
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+    #[error(transparent)]
+    Hyper(#[from] hyper::Error),
+    #[error(transparent)]
+    Decode(#[from] prost::DecodeError),
+    #[error("Stream ended before finished replay")]
+    UnexpectedEOF,
+    #[error("Unexpected message {0:?}")]
+    UnexpectedMessage(MessageType),
+    #[error("Invalid invocation id in start message")]
+    InvalidInvocationId,
+    #[error("Journal mismatch at command {index}: expected {expected:?}, replayed {actual:?}")]
+    JournalMismatch {
+        index: usize,
+        expected: MessageType,
+        actual: MessageType,
+    },
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    /// The attempt was suspended, the suspension message was already sent.
+    #[error("Suspended")]
+    Suspended,
+}
+
+type IncomingMessages =
+    Pin<Box<dyn Stream<Item = Result<(MessageHeader, Message), RunnerError>> + Send>>;
+
+pub async fn serve(
+    script: Arc<Script>,
+    req: Request<Incoming>,
+) -> Result<
+    Response<
+        Either<Empty<Bytes>, StreamBody<impl Stream<Item = Result<Frame<Bytes>, Infallible>>>>,
+    >,
+    Infallible,
+> {
+    let (req_head, req_body) = req.into_parts();
+    let handler = match req_head
+        .uri
+        .path()
+        .strip_prefix("/invoke/")
+        .and_then(|path| path.split_once('/'))
+    {
+        Some((service_name, handler_name)) => script.handler(service_name, handler_name).cloned(),
+        None => None,
+    };
+    let Some(handler) = handler else {
+        return Ok(Response::builder()
+            .status(404)
+            .body(Either::Left(Empty::new()))
+            .unwrap());
+    };
+
+    let req_body = BodyStream::new(req_body);
+    let mut decoder = Decoder::new(ServiceProtocolVersion::V4, usize::MAX, None);
+    let incoming: IncomingMessages = Box::pin(stream! {
+        for await frame in req_body {
+            match frame {
+                Ok(frame) => {
+                    if let Ok(data) = frame.into_data() {
+                        decoder.push(data);
+                        loop {
+                            match decoder.consume_next() {
+                                Ok(Some(message)) => yield Ok(message),
+                                Ok(None) => break,
+                                Err(err) => yield Err(RunnerError::Encoding(err)),
+                            }
+                        }
+                    }
+                }
+                Err(err) => yield Err(RunnerError::Hyper(err)),
+            }
+        }
+    });
+
+    let (tx, rx) = mpsc::unbounded();
+    tokio::spawn(async move {
+        let mut runner = Runner::new(tx, incoming);
+        match runner.run(&handler).await {
+            Ok(()) | Err(RunnerError::Suspended) => {}
+            Err(err) => {
+                error!("Error running handler {}: {err:?}", handler.name);
+                runner.send(Message::Error(error(err)));
+            }
+        }
+    });
+
+    Ok(Response::builder()
+        .status(200)
+        .header("content-type", "application/vnd.restate.invocation.v4")
+        .body(Either::Right(StreamBody::new(
+            rx.map(|message| Ok(Frame::data(message))),
+        )))
+        .unwrap())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NotificationId {
+    Completion(u32),
+    Signal(u32),
+}
+
+/// Executes a handler script, replaying the commands received with the start message.
+struct Runner {
+    encoder: Encoder,
+    tx: mpsc::UnboundedSender<Bytes>,
+    incoming: IncomingMessages,
+
+    invocation_id: Option<InvocationId>,
+    replayed_commands: Vec<Message>,
+    next_command_index: usize,
+    completions: HashMap<u32, Option<notification_template::Result>>,
+    signals: HashMap<u32, Option<notification_template::Result>>,
+    next_completion_id: u32,
+    next_signal_index: u32,
+
+    state: HashMap<Bytes, Bytes>,
+    partial_state: bool,
+    value: Bytes,
+}
+
+impl Runner {
+    fn new(tx: mpsc::UnboundedSender<Bytes>, incoming: IncomingMessages) -> Self {
+        Self {
+            encoder: Encoder::new(ServiceProtocolVersion::V4),
+            tx,
+            incoming,
+            invocation_id: None,
+            replayed_commands: Vec::new(),
+            next_command_index: 0,
+            completions: HashMap::new(),
+            signals: HashMap::new(),
+            next_completion_id: 1,
+            next_signal_index: FIRST_AWAKEABLE_SIGNAL_INDEX,
+            state: HashMap::new(),
+            partial_state: false,
+            value: Bytes::new(),
+        }
+    }
+
+    async fn run(&mut self, handler: &HandlerScript) -> Result<(), RunnerError> {
+        self.replay().await?;
+
+        for step in &handler.steps {
+            debug!("Executing step {step:?}");
+            match step {
+                Step::GetState { key } => {
+                    if !self.get_state(key).await? {
+                        return Ok(());
+                    }
+                }
+                Step::SetState { key, value } => {
+                    let value = self.value_or_current(value.as_ref())?;
+                    self.command(
+                        MessageType::SetStateCommand,
+                        proto::SetStateCommandMessage {
+                            key: Bytes::copy_from_slice(key.as_bytes()),
+                            value: Some(proto::Value {
+                                content: value.clone(),
+                            }),
+                            name: String::new(),
+                        },
+                    )?;
+                    self.state
+                        .insert(Bytes::copy_from_slice(key.as_bytes()), value);
+                }
+                Step::Call {
+                    service,
+                    handler,
+                    key,
+                    parameter,
+                } => {
+                    let parameter = self.value_or_current(parameter.as_ref())?;
+                    let invocation_id_notification_idx = self.next_completion_id();
+                    let result_completion_id = self.next_completion_id();
+                    self.command(
+                        MessageType::CallCommand,
+                        proto::CallCommandMessage {
+                            service_name: service.clone(),
+                            handler_name: handler.clone(),
+                            parameter,
+                            key: key.clone().unwrap_or_default(),
+                            invocation_id_notification_idx,
+                            result_completion_id,
+                            ..Default::default()
+                        },
+                    )?;
+                    if !self
+                        .await_value(NotificationId::Completion(result_completion_id))
+                        .await?
+                    {
+                        return Ok(());
+                    }
+                }
+                Step::Sleep { millis } => {
+                    let wake_up_time = (SystemTime::now() + Duration::from_millis(*millis))
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .expect("time went backwards")
+                        .as_millis() as u64;
+                    let result_completion_id = self.next_completion_id();
+                    self.command(
+                        MessageType::SleepCommand,
+                        proto::SleepCommandMessage {
+                            wake_up_time,
+                            result_completion_id,
+                            name: String::new(),
+                        },
+                    )?;
+                    let notification_id = NotificationId::Completion(result_completion_id);
+                    if let Some(notification_template::Result::Failure(failure)) =
+                        self.await_notification(notification_id).await?
+                    {
+                        self.output(output_command_message::Result::Failure(failure))?;
+                        return Ok(());
+                    }
+                }
+                Step::Run { name, value } => {
+                    let result_completion_id = self.next_completion_id();
+                    self.command(
+                        MessageType::RunCommand,
+                        proto::RunCommandMessage {
+                            result_completion_id,
+                            name: name.clone().unwrap_or_default(),
+                        },
+                    )?;
+                    if !self.completions.contains_key(&result_completion_id) {
+                        let value = self.value_or_current(value.as_ref())?;
+                        self.send(Message::ProposeRunCompletion(
+                            proto::ProposeRunCompletionMessage {
+                                result_completion_id,
+                                result: Some(propose_run_completion_message::Result::Value(value)),
+                            },
+                        ));
+                    }
+                    if !self
+                        .await_value(NotificationId::Completion(result_completion_id))
+                        .await?
+                    {
+                        return Ok(());
+                    }
+                }
+                Step::Awakeable => {
+                    let signal_index = self.next_signal_index;
+                    self.next_signal_index += 1;
+                    if let Some(invocation_id) = self.invocation_id {
+                        info!(
+                            "Created awakeable {}",
+                            ExternalSignalIdentifier::new(invocation_id, signal_index)
+                        );
+                    }
+                    if !self
+                        .await_value(NotificationId::Signal(signal_index))
+                        .await?
+                    {
+                        return Ok(());
+                    }
+                }
+                Step::Fail {
+                    code,
+                    message,
+                    terminal,
+                } => {
+                    if *terminal {
+                        self.output(output_command_message::Result::Failure(proto::Failure {
+                            code: u32::from(*code),
+                            message: message.clone(),
+                        }))?;
+                    } else {
+                        self.send(Message::Error(proto::ErrorMessage {
+                            code: u32::from(*code),
+                            message: message.clone(),
+                            ..Default::default()
+                        }));
+                    }
+                    return Ok(());
+                }
+                Step::OutputChunk { content } => {
+                    self.send(Message::Custom(
+                        OUTPUT_CHUNK_MESSAGE_TYPE,
+                        Bytes::copy_from_slice(content.as_bytes()),
+                    ));
+                }
+                Step::Hang => futures::future::pending::<()>().await,
+                Step::Output { value } => {
+                    let value = self.value_or_current(value.as_ref())?;
+                    self.output_value(value)?;
+                    return Ok(());
+                }
+            }
+        }
+
+        let value = self.value.clone();
+        self.output_value(value)
+    }
+
+    /// Reads the start message and the known entries.
+    async fn replay(&mut self) -> Result<(), RunnerError> {
+        let start = match self.next_message().await? {
+            Some((_, Message::Start(start))) => start,
+            Some((header, _)) => {
+                return Err(RunnerError::UnexpectedMessage(header.message_type()));
+            }
+            None => return Err(RunnerError::UnexpectedEOF),
+        };
+        self.invocation_id = Some(
+            InvocationId::from_slice(&start.id).map_err(|_| RunnerError::InvalidInvocationId)?,
+        );
+        self.partial_state = start.partial_state;
+        self.state = start
+            .state_map
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect();
+
+        for index in 0..start.known_entries {
+            let Some((header, message)) = self.next_message().await? else {
+                return Err(RunnerError::UnexpectedEOF);
+            };
+            match message {
+                Message::InputCommand(input) if index == 0 => {
+                    let input = proto::InputCommandMessage::decode(input)?;
+                    self.value = input.value.map(|value| value.content).unwrap_or_default();
+                }
+                message if is_command(header.message_type()) && index > 0 => {
+                    self.replayed_commands.push(message)
+                }
+                message => {
+                    if !self.record_notification(&message)? {
+                        return Err(RunnerError::UnexpectedMessage(header.message_type()));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the given state key, see [`Self::await_value`] for the returned value.
+    async fn get_state(&mut self, key: &str) -> Result<bool, RunnerError> {
+        let key = Bytes::copy_from_slice(key.as_bytes());
+
+        if let Some(Message::GetEagerStateCommand(replayed)) =
+            self.replayed_commands.get(self.next_command_index)
+        {
+            let replayed = proto::GetEagerStateCommandMessage::decode(replayed.clone())?;
+            self.next_command_index += 1;
+            self.value = match replayed.result {
+                Some(get_eager_state_command_message::Result::Value(value)) => value.content,
+                _ => Bytes::new(),
+            };
+            return Ok(true);
+        }
+
+        let known_value = self.state.get(&key).cloned();
+        let replayed_lazy = matches!(
+            self.replayed_commands.get(self.next_command_index),
+            Some(Message::GetLazyStateCommand(_))
+        );
+        if !replayed_lazy && (known_value.is_some() || !self.partial_state) {
+            self.command(
+                MessageType::GetEagerStateCommand,
+                proto::GetEagerStateCommandMessage {
+                    key,
+                    result: Some(match &known_value {
+                        Some(value) => {
+                            get_eager_state_command_message::Result::Value(proto::Value {
+                                content: value.clone(),
+                            })
+                        }
+                        None => get_eager_state_command_message::Result::Void(proto::Void {}),
+                    }),
+                    name: String::new(),
+                },
+            )?;
+            self.value = known_value.unwrap_or_default();
+            return Ok(true);
+        }
+
+        let result_completion_id = self.next_completion_id();
+        self.command(
+            MessageType::GetLazyStateCommand,
+            proto::GetLazyStateCommandMessage {
+                key,
+                result_completion_id,
+                name: String::new(),
+            },
+        )?;
+        self.await_value(NotificationId::Completion(result_completion_id))
+            .await
+    }
+
+    /// Waits for a notification and sets its value as current value. Returns false if the
+    /// notification is a failure, in which case the invocation is completed with it.
+    async fn await_value(&mut self, notification_id: NotificationId) -> Result<bool, RunnerError> {
+        match self.await_notification(notification_id).await? {
+            Some(notification_template::Result::Value(value)) => {
+                self.value = value.content;
+                Ok(true)
+            }
+            Some(notification_template::Result::Failure(failure)) => {
+                self.output(output_command_message::Result::Failure(failure))?;
+                Ok(false)
+            }
+            _ => {
+                self.value = Bytes::new();
+                Ok(true)
+            }
+        }
+    }
+
+    /// Waits for the given notification, suspending if the input stream is closed before it is
+    /// received.
+    async fn await_notification(
+        &mut self,
+        notification_id: NotificationId,
+    ) -> Result<Option<notification_template::Result>, RunnerError> {
+        loop {
+            let result = match notification_id {
+                NotificationId::Completion(id) => self.completions.remove(&id),
+                NotificationId::Signal(idx) => self.signals.remove(&idx),
+            };
+            if let Some(result) = result {
+                return Ok(result);
+            }
+
+            match self.next_message().await? {
+                Some((header, message)) => {
+                    if !self.record_notification(&message)? {
+                        return Err(RunnerError::UnexpectedMessage(header.message_type()));
+                    }
+                }
+                None => {
+                    let mut suspension = proto::SuspensionMessage::default();
+                    match notification_id {
+                        NotificationId::Completion(id) => suspension.waiting_completions.push(id),
+                        NotificationId::Signal(idx) => suspension.waiting_signals.push(idx),
+                    }
+                    self.send(Message::Suspension(suspension));
+                    return Err(RunnerError::Suspended);
+                }
+            }
+        }
+    }
+
+    /// Records the given message if it's a notification, returning false otherwise.
+    fn record_notification(&mut self, message: &Message) -> Result<bool, RunnerError> {
+        let content = match message {
+            Message::GetLazyStateCompletionNotification(content)
+            | Message::GetLazyStateKeysCompletionNotification(content)
+            | Message::GetPromiseCompletionNotification(content)
+            | Message::PeekPromiseCompletionNotification(content)
+            | Message::CompletePromiseCompletionNotification(content)
+            | Message::SleepCompletionNotification(content)
+            | Message::CallInvocationIdCompletionNotification(content)
+            | Message::CallCompletionNotification(content)
+            | Message::RunCompletionNotification(content)
+            | Message::AttachInvocationCompletionNotification(content)
+            | Message::GetInvocationOutputCompletionNotification(content)
+            | Message::SignalNotification(content) => content.clone(),
+            _ => return Ok(false),
+        };
+
+        let notification = proto::NotificationTemplate::decode(content)?;
+        match notification.id {
+            Some(notification_template::Id::CompletionId(id)) => {
+                self.completions.insert(id, notification.result);
+            }
+            Some(notification_template::Id::SignalId(idx)) => {
+                self.signals.insert(idx, notification.result);
+            }
+            Some(notification_template::Id::SignalName(name)) => {
+                debug!("Ignoring named signal {name}");
+            }
+            None => {}
+        }
+        Ok(true)
+    }
+
+    /// Sends the given command, unless it was already replayed.
+    fn command(
+        &mut self,
+        ty: MessageType,
+        command: impl prost::Message,
+    ) -> Result<(), RunnerError> {
+        let index = self.next_command_index;
+        self.next_command_index += 1;
+
+        if let Some(replayed) = self.replayed_commands.get(index) {
+            if replayed.ty() != ty {
+                return Err(RunnerError::JournalMismatch {
+                    // Command index including the input command
+                    index: index + 1,
+                    expected: ty,
+                    actual: replayed.ty(),
+                });
+            }
+            return Ok(());
+        }
+
+        let _ = self
+            .tx
+            .unbounded_send(self.encoder.encode_raw(ty, command.encode_to_vec().into()));
+        Ok(())
+    }
+
+    fn output_value(&mut self, value: Bytes) -> Result<(), RunnerError> {
+        self.output(output_command_message::Result::Value(proto::Value {
+            content: value,
+        }))
+    }
+
+    fn output(&mut self, result: output_command_message::Result) -> Result<(), RunnerError> {
+        self.command(
+            MessageType::OutputCommand,
+            proto::OutputCommandMessage {
+                result: Some(result),
+                name: String::new(),
+            },
+        )?;
+        self.send(Message::End(proto::EndMessage {}));
+        Ok(())
+    }
+
+    fn send(&self, message: Message) {
+        let _ = self.tx.unbounded_send(self.encoder.encode(message));
+    }
+
+    async fn next_message(&mut self) -> Result<Option<(MessageHeader, Message)>, RunnerError> {
+        self.incoming.next().await.transpose()
+    }
+
+    fn next_completion_id(&mut self) -> u32 {
+        let id = self.next_completion_id;
+        self.next_completion_id += 1;
+        id
+    }
+
+    fn value_or_current(&self, value: Option<&serde_json::Value>) -> Result<Bytes, RunnerError> {
+        Ok(match value {
+            Some(value) => serde_json::to_vec(value)?.into(),
+            None => self.value.clone(),
+        })
+    }
+}
+
+fn is_command(ty: MessageType) -> bool {
+    (0x0400..0x8000).contains(&u16::from(ty))
+}
+
+fn error(err: RunnerError) -> proto::ErrorMessage {
+    let code = match err {
+        RunnerError::JournalMismatch { .. } => codes::JOURNAL_MISMATCH,
+        RunnerError::Hyper(_) | RunnerError::Serde(_) => codes::INTERNAL,
+        _ => codes::PROTOCOL_VIOLATION,
+    };
+    proto::ErrorMessage {
+        code: code.into(),
+        message: err.to_string(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::stream;
+    use restate_types::identifiers::InvocationUuid;
+
+    fn handler(steps: serde_json::Value) -> HandlerScript {
+        serde_json::from_value(serde_json::json!({ "name": "handler", "steps": steps })).unwrap()
+    }
+
+    fn start(known_entries: u32) -> Message {
+        let invocation_id = InvocationId::from_parts(1, InvocationUuid::from(1u128));
+        Message::new_start_message(
+            Bytes::copy_from_slice(&invocation_id.to_bytes()),
+            invocation_id.to_string(),
+            None,
+            known_entries,
+            false,
+            [],
+            0,
+            Duration::ZERO,
+        )
+    }
+
+    fn input(value: &'static str) -> Message {
+        Message::InputCommand(
+            proto::InputCommandMessage {
+                value: Some(proto::Value {
+                    content: Bytes::from_static(value.as_bytes()),
+                }),
+                ..Default::default()
+            }
+            .encode_to_vec()
+            .into(),
+        )
+    }
+
+    fn sleep_completion(completion_id: u32) -> Message {
+        Message::SleepCompletionNotification(
+            proto::NotificationTemplate {
+                id: Some(notification_template::Id::CompletionId(completion_id)),
+                result: Some(notification_template::Result::Void(proto::Void {})),
+            }
+            .encode_to_vec()
+            .into(),
+        )
+    }
+
+    /// Runs the handler with the given incoming messages, returning the messages it sent.
+    async fn run(
+        handler: &HandlerScript,
+        incoming: Vec<Message>,
+    ) -> (Result<(), RunnerError>, Vec<Message>) {
+        let incoming: IncomingMessages =
+            Box::pin(stream::iter(incoming.into_iter().map(|message| {
+                Ok((MessageHeader::new(message.ty(), 0), message))
+            })));
+        let (tx, rx) = mpsc::unbounded();
+        let result = Runner::new(tx, incoming).run(handler).await;
+
+        let mut decoder = Decoder::new(ServiceProtocolVersion::V4, usize::MAX, None);
+        for chunk in rx.collect::<Vec<_>>().await {
+            decoder.push(chunk);
+        }
+        let mut sent = vec![];
+        while let Some((_, message)) = decoder.consume_next().unwrap() {
+            sent.push(message);
+        }
+        (result, sent)
+    }
+
+    fn output_value(message: &Message) -> Bytes {
+        let Message::OutputCommand(content) = message else {
+            panic!("expected an output command, got {message:?}");
+        };
+        match proto::OutputCommandMessage::decode(content.clone())
+            .unwrap()
+            .result
+        {
+            Some(output_command_message::Result::Value(value)) => value.content,
+            result => panic!("expected an output value, got {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn completes_with_current_value() {
+        let handler = handler(serde_json::json!([
+            { "type": "setState", "key": "counter", "value": 1 },
+            { "type": "getState", "key": "counter" }
+        ]));
+
+        let (result, sent) = run(&handler, vec![start(1), input("\"input\"")]).await;
+
+        assert!(result.is_ok());
+        let types: Vec<_> = sent.iter().map(Message::ty).collect();
+        assert_eq!(
+            types,
+            vec![
+                MessageType::SetStateCommand,
+                MessageType::GetEagerStateCommand,
+                MessageType::OutputCommand,
+                MessageType::End
+            ]
+        );
+        assert_eq!(output_value(&sent[2]), Bytes::from_static(b"1"));
+    }
+
+    #[tokio::test]
+    async fn suspends_awaiting_sleep() {
+        let handler = handler(serde_json::json!([{ "type": "sleep", "millis": 100 }]));
+
+        let (result, sent) = run(&handler, vec![start(1), input("\"input\"")]).await;
+
+        assert!(matches!(result, Err(RunnerError::Suspended)));
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].ty(), MessageType::SleepCommand);
+        let Message::Suspension(suspension) = &sent[1] else {
+            panic!("expected a suspension, got {:?}", sent[1]);
+        };
+        assert_eq!(suspension.waiting_completions, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn replays_known_commands() {
+        let handler = handler(serde_json::json!([{ "type": "sleep", "millis": 100 }]));
+        let replayed_sleep = Message::SleepCommand(
+            proto::SleepCommandMessage {
+                wake_up_time: 0,
+                result_completion_id: 1,
+                name: String::new(),
+            }
+            .encode_to_vec()
+            .into(),
+        );
+
+        let (result, sent) = run(
+            &handler,
+            vec![
+                start(3),
+                input("\"input\""),
+                replayed_sleep,
+                sleep_completion(1),
+            ],
+        )
+        .await;
+
+        // The sleep is not sent again, the handler completes with the input
+        assert!(result.is_ok());
+        let types: Vec<_> = sent.iter().map(Message::ty).collect();
+        assert_eq!(types, vec![MessageType::OutputCommand, MessageType::End]);
+        assert_eq!(output_value(&sent[0]), Bytes::from_static(b"\"input\""));
+    }
+
+    #[tokio::test]
+    async fn detects_journal_mismatch() {
+        let handler = handler(serde_json::json!([{ "type": "sleep", "millis": 100 }]));
+        let replayed_set_state = Message::SetStateCommand(
+            proto::SetStateCommandMessage {
+                key: Bytes::from_static(b"counter"),
+                value: None,
+                name: String::new(),
+            }
+            .encode_to_vec()
+            .into(),
+        );
+
+        let (result, _) = run(
+            &handler,
+            vec![start(2), input("\"input\""), replayed_set_state],
+        )
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err,
+            RunnerError::JournalMismatch {
+                index: 1,
+                expected: MessageType::SleepCommand,
+                actual: MessageType::SetStateCommand,
+            }
+        ));
+        assert_eq!(error(err).code, u32::from(codes::JOURNAL_MISMATCH));
+    }
+
+    #[test]
+    fn manifest_uses_script_representation() {
+        let script: Script = serde_json::from_value(serde_json::json!({
+            "protocolMode": "REQUEST_RESPONSE",
+            "services": [{
+                "name": "Counter",
+                "ty": "VIRTUAL_OBJECT",
+                "handlers": [{ "name": "get", "ty": "SHARED" }, { "name": "set" }]
+            }]
+        }))
+        .unwrap();
+
+        let manifest = script.manifest();
+        assert_eq!(manifest["protocolMode"], "REQUEST_RESPONSE");
+        assert_eq!(manifest["services"][0]["ty"], "VIRTUAL_OBJECT");
+        assert_eq!(manifest["services"][0]["handlers"][0]["ty"], "SHARED");
+        assert!(manifest["services"][0]["handlers"][1].get("ty").is_none());
+    }
+}
@@ -20,6 +20,7 @@ use crate::commands::cluster::overview::ClusterStatusOpts;
 use crate::commands::cluster::Cluster;
 use crate::commands::log::Logs;
 use crate::commands::metadata::Metadata;
+use crate::commands::migration::Migration;
 use crate::commands::node::Nodes;
 use crate::commands::partition::Partitions;
 use crate::commands::replicated_loglet::ReplicatedLoglet;
@@ -73,6 +74,10 @@ pub enum Command {
     /// Partition processor snapshots
     #[clap(subcommand)]
     Snapshots(Snapshot),
+    /// Migrate the data of a cluster to another cluster, possibly with a different number of
+    /// partitions. Operates directly on the storage of stopped nodes.
+    #[clap(subcommand)]
+    Migration(Migration),
     /// Commands that operate on replicated loglets
     #[clap(subcommand)]
     ReplicatedLoglet(ReplicatedLoglet),
@@ -15,7 +15,8 @@ use std::str::FromStr;
 
 use restate_core::metadata_store::MetadataStoreClient;
 use restate_metadata_store::local::create_client;
-use restate_types::config::MetadataStoreClientOptions;
+use restate_types::config::{Configuration, MetadataStoreClientOptions};
+use restate_types::live::Live;
 use restate_types::net::AdvertisedAddress;
 use restate_types::{flexbuffers_storage_encode_decode, Version, Versioned};
 
@@ -108,6 +109,33 @@ impl Versioned for GenericMetadataValue {
     }
 }
 
+impl MetadataCommonOpts {
+    pub fn config_file(&self) -> Option<&PathBuf> {
+        self.config_file.as_ref()
+    }
+}
+
+/// Creates a metadata store client for the configured access mode. For access mode = "direct",
+/// the local metadata store is started, which requires running in the scope of a task center.
+pub async fn connect_metadata_store(
+    opts: &MetadataCommonOpts,
+    config: &Configuration,
+) -> anyhow::Result<MetadataStoreClient> {
+    match opts.access_mode {
+        MetadataAccessMode::Remote => create_metadata_store_client(opts).await,
+        MetadataAccessMode::Direct => {
+            crate::environment::metadata_store::start_metadata_store(
+                config.common.metadata_store_client.clone(),
+                &config.metadata_store,
+                Live::from_value(config.metadata_store.clone())
+                    .map(|c| &c.rocksdb)
+                    .boxed(),
+            )
+            .await
+        }
+    }
+}
+
 pub async fn create_metadata_store_client(
     opts: &MetadataCommonOpts,
 ) -> anyhow::Result<MetadataStoreClient> {
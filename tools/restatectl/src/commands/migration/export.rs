@@ -0,0 +1,155 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use bytes::BytesMut;
+use clap::Parser;
+use cling::{Collect, Run};
+use tracing::debug;
+
+use restate_cli_util::{c_println, c_success, c_warn};
+use restate_partition_store::migration::{write_snapshot_metadata, PARTITIONS_DIR};
+use restate_partition_store::snapshots::{PartitionSnapshotMetadata, SnapshotFormatVersion};
+use restate_partition_store::{OpenMode, PartitionStoreManager};
+use restate_rocksdb::RocksDbManager;
+use restate_types::config::Configuration;
+use restate_types::identifiers::SnapshotId;
+use restate_types::metadata_store::keys::{PARTITION_TABLE_KEY, SCHEMA_INFORMATION_KEY};
+use restate_types::partition_table::PartitionTable;
+use restate_types::schema::Schema;
+use restate_types::storage::StorageCodec;
+
+use super::{PARTITION_TABLE_FILE, SCHEMA_FILE};
+use crate::commands::metadata::{connect_metadata_store, MetadataCommonOpts};
+use crate::environment::task_center::run_in_task_center;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "export")]
+pub struct ExportOpts {
+    #[clap(flatten)]
+    metadata: MetadataCommonOpts,
+
+    /// The directory to export to. The exports of all the nodes running partition processors
+    /// of the source cluster can share the same directory.
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+async fn export(opts: &ExportOpts) -> anyhow::Result<()> {
+    run_in_task_center(opts.metadata.config_file(), |config| async move {
+        let rocksdb_manager = RocksDbManager::init(Configuration::mapped_updateable(|c| &c.common));
+        debug!("RocksDB Initialized");
+
+        let result = export_inner(opts, &config).await;
+
+        rocksdb_manager.shutdown().await;
+        result
+    })
+    .await
+}
+
+async fn export_inner(opts: &ExportOpts, config: &Configuration) -> anyhow::Result<()> {
+    let metadata_store_client = connect_metadata_store(&opts.metadata, config).await?;
+    let schema: Schema = metadata_store_client
+        .get(SCHEMA_INFORMATION_KEY.clone())
+        .await?
+        .unwrap_or_default();
+    let partition_table: PartitionTable = metadata_store_client
+        .get(PARTITION_TABLE_KEY.clone())
+        .await?
+        .context("the cluster has no partition table, is it provisioned?")?;
+
+    std::fs::create_dir_all(&opts.output)
+        .with_context(|| format!("failed to create {}", opts.output.display()))?;
+    let mut schema_buf = BytesMut::new();
+    StorageCodec::encode(&schema, &mut schema_buf)?;
+    std::fs::write(opts.output.join(SCHEMA_FILE), schema_buf)
+        .context("failed to write the schema")?;
+    // the import checks that the snapshots of all these partitions were exported
+    let mut partition_table_buf = BytesMut::new();
+    StorageCodec::encode(&partition_table, &mut partition_table_buf)?;
+    std::fs::write(opts.output.join(PARTITION_TABLE_FILE), partition_table_buf)
+        .context("failed to write the partition table")?;
+    c_println!(
+        "Exported schema version {} with {} services",
+        schema.version,
+        schema.services.len()
+    );
+
+    let updateable_config = Configuration::updateable();
+    let partition_store_manager = PartitionStoreManager::create(
+        updateable_config.clone().map(|c| &c.worker.storage),
+        updateable_config
+            .clone()
+            .map(|c| &c.worker.storage.rocksdb)
+            .boxed(),
+        &[],
+    )
+    .await?;
+
+    let partitions_dir = opts.output.join(PARTITIONS_DIR);
+    let mut exported = 0;
+    for (partition_id, partition) in partition_table.partitions() {
+        if !partition_store_manager
+            .has_partition_store(*partition_id)
+            .await
+        {
+            continue;
+        }
+        partition_store_manager
+            .open_partition_store(
+                *partition_id,
+                partition.key_range.clone(),
+                OpenMode::OpenExisting,
+                &config.worker.storage.rocksdb,
+            )
+            .await?;
+
+        let snapshot_id = SnapshotId::new();
+        let snapshot = partition_store_manager
+            .export_partition_snapshot(*partition_id, snapshot_id, &partitions_dir)
+            .await
+            .with_context(|| format!("failed to export partition {partition_id}"))?;
+        let metadata = PartitionSnapshotMetadata {
+            version: SnapshotFormatVersion::V1,
+            cluster_name: config.common.cluster_name().to_owned(),
+            partition_id: *partition_id,
+            node_name: config.common.node_name().to_owned(),
+            created_at: humantime::Timestamp::from(SystemTime::now()),
+            snapshot_id,
+            key_range: snapshot.key_range.clone(),
+            min_applied_lsn: snapshot.min_applied_lsn,
+            db_comparator_name: snapshot.db_comparator_name.clone(),
+            files: snapshot.files.clone(),
+        };
+        write_snapshot_metadata(&snapshot, &metadata)?;
+
+        c_println!(
+            "Exported partition {} at applied LSN {}",
+            partition_id,
+            snapshot.min_applied_lsn
+        );
+        exported += 1;
+    }
+
+    if exported == 0 {
+        c_warn!("This node has no partition stores, only the schema was exported");
+    } else {
+        c_success!(
+            "Exported {} partitions to {}",
+            exported,
+            opts.output.display()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use clap::Parser;
+use cling::{Collect, Run};
+use tracing::debug;
+
+use restate_cli_util::{c_println, c_success, c_warn};
+use restate_core::metadata_store::{MetadataStoreClient, ReadModifyWriteError};
+use restate_partition_store::migration::read_exported_snapshots;
+use restate_partition_store::snapshots::LocalPartitionSnapshot;
+use restate_partition_store::PartitionStoreManager;
+use restate_rocksdb::RocksDbManager;
+use restate_types::config::Configuration;
+use restate_types::identifiers::PartitionId;
+use restate_types::logs::Lsn;
+use restate_types::metadata_store::keys::{PARTITION_TABLE_KEY, SCHEMA_INFORMATION_KEY};
+use restate_types::partition_table::PartitionTable;
+use restate_types::schema::Schema;
+use restate_types::storage::StorageCodec;
+use restate_types::Version;
+
+use super::{PARTITION_TABLE_FILE, SCHEMA_FILE};
+use crate::commands::metadata::{connect_metadata_store, MetadataCommonOpts};
+use crate::environment::task_center::run_in_task_center;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "import")]
+pub struct ImportOpts {
+    #[clap(flatten)]
+    metadata: MetadataCommonOpts,
+
+    /// The directory written by `restatectl migration export`
+    #[arg(short, long)]
+    input: PathBuf,
+
+    /// Only import the schema, e.g. when running the import on a node without partition processors
+    #[arg(long)]
+    schema_only: bool,
+
+    /// Drop the partition stores of the target partitions on this node before importing. A
+    /// failed import cleans up after itself, use this to retry an import which was interrupted,
+    /// e.g. because the process was killed. Any data of the target partitions is lost.
+    #[arg(long)]
+    force_clean: bool,
+}
+
+async fn import(opts: &ImportOpts) -> anyhow::Result<()> {
+    run_in_task_center(opts.metadata.config_file(), |config| async move {
+        let rocksdb_manager = RocksDbManager::init(Configuration::mapped_updateable(|c| &c.common));
+        debug!("RocksDB Initialized");
+
+        let result = import_inner(opts, &config).await;
+
+        rocksdb_manager.shutdown().await;
+        result
+    })
+    .await
+}
+
+async fn import_inner(opts: &ImportOpts, config: &Configuration) -> anyhow::Result<()> {
+    let metadata_store_client = connect_metadata_store(&opts.metadata, config).await?;
+    import_schema(opts, &metadata_store_client).await?;
+    if opts.schema_only {
+        return Ok(());
+    }
+
+    let partition_table: PartitionTable = metadata_store_client
+        .get(PARTITION_TABLE_KEY.clone())
+        .await?
+        .context("the target cluster has no partition table, is it provisioned?")?;
+    let target_partitions: Vec<_> = partition_table
+        .partitions()
+        .map(|(partition_id, partition)| (*partition_id, partition.key_range.clone()))
+        .collect();
+
+    // The export directory may contain the same partition exported by several nodes, the most
+    // recent one wins.
+    let mut snapshots = BTreeMap::new();
+    for (metadata, snapshot) in read_exported_snapshots(&opts.input)? {
+        match snapshots.get(&metadata.partition_id) {
+            Some((_, existing)) if metadata.min_applied_lsn <= existing => {}
+            _ => {
+                snapshots.insert(metadata.partition_id, (snapshot, metadata.min_applied_lsn));
+            }
+        }
+    }
+    if snapshots.is_empty() {
+        bail!("no partition snapshots found in {}", opts.input.display());
+    }
+    check_exported_partitions(opts, &snapshots)?;
+    c_println!(
+        "Importing {} source partitions into {} target partitions",
+        snapshots.len(),
+        target_partitions.len()
+    );
+
+    let updateable_config = Configuration::updateable();
+    let partition_store_manager = PartitionStoreManager::create(
+        updateable_config.clone().map(|c| &c.worker.storage),
+        updateable_config
+            .clone()
+            .map(|c| &c.worker.storage.rocksdb)
+            .boxed(),
+        &[],
+    )
+    .await?;
+
+    if opts.force_clean {
+        c_warn!("Dropping the existing partition stores of the target partitions");
+        partition_store_manager
+            .drop_migration_targets(&target_partitions)
+            .await?;
+    }
+
+    partition_store_manager
+        .import_repartitioned_snapshots(
+            snapshots
+                .into_iter()
+                .map(|(partition_id, (snapshot, _))| (partition_id, snapshot))
+                .collect(),
+            &target_partitions,
+            &config.worker.storage.rocksdb,
+        )
+        .await?;
+
+    c_success!(
+        "Imported the partition stores from {}",
+        opts.input.display()
+    );
+    Ok(())
+}
+
+/// Checks that the export contains a snapshot of every partition of the source cluster, and no
+/// other ones.
+fn check_exported_partitions(
+    opts: &ImportOpts,
+    snapshots: &BTreeMap<PartitionId, (LocalPartitionSnapshot, Lsn)>,
+) -> anyhow::Result<()> {
+    let partition_table_path = opts.input.join(PARTITION_TABLE_FILE);
+    let partition_table_bytes = std::fs::read(&partition_table_path)
+        .with_context(|| format!("failed to read {}", partition_table_path.display()))?;
+    let source_partition_table: PartitionTable =
+        StorageCodec::decode(&mut partition_table_bytes.as_slice()).with_context(|| {
+            format!("invalid partition table {}", partition_table_path.display())
+        })?;
+
+    for (partition_id, partition) in source_partition_table.partitions() {
+        match snapshots.get(partition_id) {
+            None => bail!(
+                "the export has no snapshot of partition {partition_id}, export it from a node \
+                running its partition processor"
+            ),
+            Some((snapshot, _)) if snapshot.key_range != partition.key_range => bail!(
+                "the snapshot of partition {partition_id} covers the keys {:?}, the source \
+                partition table assigns it {:?}",
+                snapshot.key_range,
+                partition.key_range
+            ),
+            Some(_) => {}
+        }
+    }
+    if let Some(partition_id) = snapshots
+        .keys()
+        .find(|partition_id| source_partition_table.get_partition(partition_id).is_none())
+    {
+        bail!(
+            "the export has a snapshot of partition {partition_id}, which is not in the source \
+            partition table"
+        );
+    }
+    Ok(())
+}
+
+/// Stores the exported schema in the target cluster, unless it already has the same one.
+async fn import_schema(
+    opts: &ImportOpts,
+    metadata_store_client: &MetadataStoreClient,
+) -> anyhow::Result<()> {
+    let schema_path = opts.input.join(SCHEMA_FILE);
+    let schema_bytes = std::fs::read(&schema_path)
+        .with_context(|| format!("failed to read {}", schema_path.display()))?;
+    let exported_schema: Schema = StorageCodec::decode(&mut schema_bytes.as_slice())
+        .with_context(|| format!("invalid schema {}", schema_path.display()))?;
+
+    let result = metadata_store_client
+        .read_modify_write(SCHEMA_INFORMATION_KEY.clone(), |current: Option<Schema>| {
+            let current_version = match current {
+                Some(current)
+                    if !current.services.is_empty() || !current.deployments.is_empty() =>
+                {
+                    return Err(current);
+                }
+                Some(current) => current.version,
+                None => Version::INVALID,
+            };
+            let mut schema = exported_schema.clone();
+            schema.version = current_version.next();
+            Ok(schema)
+        })
+        .await;
+
+    match result {
+        Ok(schema) => {
+            c_println!(
+                "Imported schema with {} services as version {}",
+                schema.services.len(),
+                schema.version
+            );
+            Ok(())
+        }
+        // Another node of the target cluster may already have imported it
+        Err(ReadModifyWriteError::FailedOperation(current))
+            if current.deployments.len() == exported_schema.deployments.len()
+                && current
+                    .deployments
+                    .keys()
+                    .all(|id| exported_schema.deployments.contains_key(id)) =>
+        {
+            c_warn!("The target cluster already has the exported schema, skipping it");
+            Ok(())
+        }
+        Err(ReadModifyWriteError::FailedOperation(_)) => bail!(
+            "the target cluster already has registered services or deployments, the schema \
+            can only be imported into an empty cluster"
+        ),
+        Err(ReadModifyWriteError::ReadWrite(err)) => Err(err.into()),
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod export;
+mod import;
+
+use cling::prelude::*;
+
+/// File of an export holding the schema of the source cluster.
+const SCHEMA_FILE: &str = "schema.bin";
+/// File of an export holding the partition table of the source cluster.
+const PARTITION_TABLE_FILE: &str = "partition_table.bin";
+
+#[derive(Run, Subcommand, Clone)]
+pub enum Migration {
+    /// Export the partition stores and the schema of a stopped node
+    Export(export::ExportOpts),
+    /// Import an export into a stopped node of the target cluster, re-partitioning its data
+    Import(import::ImportOpts),
+}
@@ -13,6 +13,7 @@ mod display_util;
 pub mod dump;
 pub mod log;
 pub mod metadata;
+pub mod migration;
 pub mod node;
 pub mod partition;
 pub mod replicated_loglet;
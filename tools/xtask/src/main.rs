@@ -148,8 +148,9 @@ fn render_table_docs(mut write: impl Write) -> io::Result<()> {
         render_table_doc(table_doc, &mut write)?;
     }
 
-    // sys_invocation is a view which was not registered at table_docs::TABLE_DOCS
+    // sys_invocation and sys_awakeable are views which were not registered at table_docs::TABLE_DOCS
     render_table_doc(&table_docs::sys_invocation_table_docs(), &mut write)?;
+    render_table_doc(&table_docs::sys_awakeable_table_docs(), &mut write)?;
 
     Ok(())
 }